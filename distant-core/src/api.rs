@@ -8,8 +8,9 @@ use distant_net::server::{ConnectionCtx, Reply, ServerCtx, ServerHandler};
 use log::*;
 
 use crate::protocol::{
-    self, ChangeKind, DirEntry, Environment, Error, Metadata, Permissions, ProcessId, PtySize,
-    SearchId, SearchQuery, SetPermissionsOptions, SystemInfo, Version,
+    self, ChangeKind, DirEntry, Environment, Error, FileBlock, FileHash, HashAlgorithm, Metadata,
+    Permissions, ProcSpawnOptions, ProcessId, ProcessInfo, PtySize, SearchId, SearchQuery,
+    SetPermissionsOptions, Signal, SystemInfo, TcpListenerId, TcpStreamId, UploadId, Version,
 };
 
 mod reply;
@@ -96,6 +97,44 @@ pub trait DistantApi {
         unsupported("read_file_text")
     }
 
+    /// Reads a range of bytes from a file.
+    ///
+    /// * `path` - the path to the file
+    /// * `offset` - the offset (in bytes) within the file where reading will begin
+    /// * `len` - the maximum number of bytes to read, or none to read until the end of the file
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn read_file_range(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<Vec<u8>> {
+        unsupported("read_file_range")
+    }
+
+    /// Streams bytes from a file. Once the stream has started, the contents of the file are
+    /// expected to be sent through the reply of `ctx` as a series of blobs, followed by a
+    /// [`FileReadDone`](protocol::Response::FileReadDone) marker or an error.
+    ///
+    /// * `path` - the path to the file
+    /// * `offset` - the offset (in bytes) within the file where reading will begin
+    /// * `len` - the maximum number of bytes to read, or none to read until the end of the file
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn read_file_stream(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<()> {
+        unsupported("read_file_stream")
+    }
+
     /// Writes bytes to a file, overwriting the file if it exists.
     ///
     /// * `path` - the path to the file
@@ -160,6 +199,90 @@ pub trait DistantApi {
         unsupported("append_file_text")
     }
 
+    /// Writes blocks of data within a file, creating it if it is missing, and then resizes the
+    /// file to the given length.
+    ///
+    /// * `path` - the path to the file
+    /// * `len` - the length of the file once patched
+    /// * `blocks` - the blocks of data to write at their offsets within the file
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn patch_file(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        len: u64,
+        blocks: Vec<FileBlock>,
+    ) -> io::Result<()> {
+        unsupported("patch_file")
+    }
+
+    /// Opens a session to upload a file in chunks, returning the id of the session and the
+    /// offset where the upload should continue.
+    ///
+    /// * `path` - the path where the file will be placed once the upload is committed
+    /// * `resume` - if true, will resume a partial upload of the same path instead of starting
+    ///   over
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn upload_open(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        resume: bool,
+    ) -> io::Result<(UploadId, u64)> {
+        unsupported("upload_open")
+    }
+
+    /// Writes a chunk of data to an upload session, returning the offset of data confirmed as
+    /// written.
+    ///
+    /// * `id` - the id of the upload session
+    /// * `offset` - the offset within the file where the chunk will be written
+    /// * `data` - the data to write
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn upload_write(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: UploadId,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> io::Result<u64> {
+        unsupported("upload_write")
+    }
+
+    /// Commits an upload session, moving the uploaded file into place.
+    ///
+    /// * `id` - the id of the upload session
+    /// * `checksum` - if provided, the hex-encoded SHA-256 digest the contents must match
+    /// * `modified` - if provided, the last modification time (in seconds) to give the file
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn upload_commit(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: UploadId,
+        checksum: Option<String>,
+        modified: Option<u64>,
+    ) -> io::Result<()> {
+        unsupported("upload_commit")
+    }
+
+    /// Aborts an upload session, discarding any partially-uploaded data.
+    ///
+    /// * `id` - the id of the upload session
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn upload_abort(&self, ctx: DistantCtx<Self::LocalData>, id: UploadId) -> io::Result<()> {
+        unsupported("upload_abort")
+    }
+
     /// Reads entries from a directory.
     ///
     /// * `path` - the path to the directory
@@ -246,6 +369,112 @@ pub trait DistantApi {
         unsupported("rename")
     }
 
+    /// Creates a symbolic link.
+    ///
+    /// * `src` - the path that the symbolic link will point to
+    /// * `dst` - the path where the symbolic link will be created
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn symlink(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        src: PathBuf,
+        dst: PathBuf,
+    ) -> io::Result<()> {
+        unsupported("symlink")
+    }
+
+    /// Creates a hard link.
+    ///
+    /// * `src` - the path to the existing file
+    /// * `dst` - the path where the hard link will be created
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn hard_link(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        src: PathBuf,
+        dst: PathBuf,
+    ) -> io::Result<()> {
+        unsupported("hard_link")
+    }
+
+    /// Reads the target of a symbolic link without canonicalizing it.
+    ///
+    /// * `path` - the path to the symbolic link
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn read_link(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+    ) -> io::Result<PathBuf> {
+        unsupported("read_link")
+    }
+
+    /// Computes the digest of some file.
+    ///
+    /// * `path` - the path to the file
+    /// * `algorithm` - the algorithm used to compute the digest
+    /// * `offset` - the offset (in bytes) within the file where hashing will begin
+    /// * `len` - the maximum number of bytes to hash, or none to hash until the end of the file
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn hash(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<String> {
+        unsupported("hash")
+    }
+
+    /// Computes the digest of every file within some directory and its subdirectories.
+    ///
+    /// * `path` - the path to the directory
+    /// * `algorithm` - the algorithm used to compute the digests
+    /// * `offset` - the offset (in bytes) within each file where hashing will begin
+    /// * `len` - the maximum number of bytes to hash within each file, or none to hash until the
+    ///   end of each file
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn hash_dir(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<Vec<FileHash>> {
+        unsupported("hash_dir")
+    }
+
+    /// Computes the digest of each fixed-size block of some file, returning the length of the
+    /// file alongside the digests.
+    ///
+    /// * `path` - the path to the file
+    /// * `algorithm` - the algorithm used to compute the digests
+    /// * `block_size` - the size of each block, where the last block may be smaller
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn block_checksums(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        block_size: u64,
+    ) -> io::Result<(u64, Vec<String>)> {
+        unsupported("block_checksums")
+    }
+
     /// Watches a file or directory for changes.
     ///
     /// * `path` - the path to the file or directory
@@ -350,12 +579,33 @@ pub trait DistantApi {
         unsupported("cancel_search")
     }
 
+    /// Replaces matches of a query within the contents of files, sending a
+    /// [`Replaced`](protocol::Response::Replaced) response for each file as it is processed and
+    /// returning the total number of files changed and matches replaced.
+    ///
+    /// * `query` - the query whose matches are replaced, which must target file contents
+    /// * `replacement` - the template used in place of each match, supporting capture groups
+    /// * `dry_run` - if true, will report diffs of the changes instead of modifying files
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn replace(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        query: SearchQuery,
+        replacement: String,
+        dry_run: bool,
+    ) -> io::Result<(u64, u64)> {
+        unsupported("replace")
+    }
+
     /// Spawns a new process, returning its id.
     ///
     /// * `cmd` - the full command to run as a new process (including arguments)
     /// * `environment` - the environment variables to associate with the process
     /// * `current_dir` - the alternative current directory to use with the process
     /// * `pty` - if provided, will run the process within a PTY of the given size
+    /// * `options` - additional options such as resource limits to apply to the process
     ///
     /// *Override this, otherwise it will return "unsupported" as an error.*
     #[allow(unused_variables)]
@@ -366,6 +616,7 @@ pub trait DistantApi {
         environment: Environment,
         current_dir: Option<PathBuf>,
         pty: Option<PtySize>,
+        options: ProcSpawnOptions,
     ) -> io::Result<ProcessId> {
         unsupported("proc_spawn")
     }
@@ -380,6 +631,22 @@ pub trait DistantApi {
         unsupported("proc_kill")
     }
 
+    /// Sends a signal to the process with the specified id.
+    ///
+    /// * `id` - the unique id of the process
+    /// * `signal` - the signal to send
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn proc_signal(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: ProcessId,
+        signal: Signal,
+    ) -> io::Result<()> {
+        unsupported("proc_signal")
+    }
+
     /// Sends data to the stdin of the process with the specified id.
     ///
     /// * `id` - the unique id of the process
@@ -412,6 +679,98 @@ pub trait DistantApi {
         unsupported("proc_resize_pty")
     }
 
+    /// Lists processes spawned on the remote machine that are still being tracked.
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn proc_list(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Vec<ProcessInfo>> {
+        unsupported("proc_list")
+    }
+
+    /// Attaches to the process with the specified id, sending its stdout, stderr, and completion
+    /// as responses to this request going forward.
+    ///
+    /// * `id` - the unique id of the process
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn proc_attach(&self, ctx: DistantCtx<Self::LocalData>, id: ProcessId) -> io::Result<()> {
+        unsupported("proc_attach")
+    }
+
+    /// Opens a tcp stream from the remote machine, sending back any data received on it.
+    ///
+    /// * `host` - the host (name or ip address) to connect to
+    /// * `port` - the port to connect to
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn tcp_connect(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        host: String,
+        port: u16,
+    ) -> io::Result<TcpStreamId> {
+        unsupported("tcp_connect")
+    }
+
+    /// Listens for tcp connections on the remote machine, opening a tcp stream for each
+    /// connection accepted. Returns the id of the listener and the port it is bound to.
+    ///
+    /// * `host` - the host (name or ip address) to bind to
+    /// * `port` - the port to bind to, where 0 means any available port
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn tcp_listen(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        host: String,
+        port: u16,
+    ) -> io::Result<(TcpListenerId, u16)> {
+        unsupported("tcp_listen")
+    }
+
+    /// Sends data on the tcp stream with the specified id.
+    ///
+    /// * `id` - the unique id of the tcp stream
+    /// * `data` - the data to send
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn tcp_write(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: TcpStreamId,
+        data: Vec<u8>,
+    ) -> io::Result<()> {
+        unsupported("tcp_write")
+    }
+
+    /// Closes the tcp stream with the specified id.
+    ///
+    /// * `id` - the unique id of the tcp stream
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn tcp_close(&self, ctx: DistantCtx<Self::LocalData>, id: TcpStreamId) -> io::Result<()> {
+        unsupported("tcp_close")
+    }
+
+    /// Stops the tcp listener with the specified id from accepting any more connections.
+    ///
+    /// * `id` - the unique id of the tcp listener
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn tcp_unlisten(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: TcpListenerId,
+    ) -> io::Result<()> {
+        unsupported("tcp_unlisten")
+    }
+
     /// Retrieves information about the system.
     ///
     /// *Override this, otherwise it will return "unsupported" as an error.*
@@ -419,6 +778,14 @@ pub trait DistantApi {
     async fn system_info(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<SystemInfo> {
         unsupported("system_info")
     }
+
+    /// Retrieves the environment variables of the server.
+    ///
+    /// *Override this, otherwise it will return "unsupported" as an error.*
+    #[allow(unused_variables)]
+    async fn environment(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Environment> {
+        unsupported("environment")
+    }
 }
 
 #[async_trait]
@@ -585,6 +952,16 @@ where
             .await
             .map(|data| protocol::Response::Text { data })
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::FileReadRange { path, offset, len } => api
+            .read_file_range(ctx, path, offset, len)
+            .await
+            .map(|data| protocol::Response::Blob { data })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::FileReadStream { path, offset, len } => api
+            .read_file_stream(ctx, path, offset, len)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
         protocol::Request::FileWrite { path, data } => api
             .write_file(ctx, path, data)
             .await
@@ -605,6 +982,35 @@ where
             .await
             .map(|_| protocol::Response::Ok)
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::FilePatch { path, len, blocks } => api
+            .patch_file(ctx, path, len, blocks)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::UploadOpen { path, resume } => api
+            .upload_open(ctx, path, resume)
+            .await
+            .map(|(id, offset)| protocol::Response::UploadOpened { id, offset })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::UploadWrite { id, offset, data } => api
+            .upload_write(ctx, id, offset, data)
+            .await
+            .map(|offset| protocol::Response::UploadWritten { id, offset })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::UploadCommit {
+            id,
+            checksum,
+            modified,
+        } => api
+            .upload_commit(ctx, id, checksum, modified)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::UploadAbort { id } => api
+            .upload_abort(ctx, id)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
         protocol::Request::DirRead {
             path,
             depth,
@@ -639,6 +1045,49 @@ where
             .await
             .map(|_| protocol::Response::Ok)
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::Symlink { src, dst } => api
+            .symlink(ctx, src, dst)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::HardLink { src, dst } => api
+            .hard_link(ctx, src, dst)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::ReadLink { path } => api
+            .read_link(ctx, path)
+            .await
+            .map(|path| protocol::Response::LinkTarget { path })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::Hash {
+            path,
+            algorithm,
+            offset,
+            len,
+            recursive,
+        } => {
+            if recursive {
+                api.hash_dir(ctx, path, algorithm, offset, len)
+                    .await
+                    .map(|entries| protocol::Response::Hashes { entries })
+                    .unwrap_or_else(protocol::Response::from)
+            } else {
+                api.hash(ctx, path, algorithm, offset, len)
+                    .await
+                    .map(|digest| protocol::Response::Hash { digest })
+                    .unwrap_or_else(protocol::Response::from)
+            }
+        }
+        protocol::Request::FileBlockChecksums {
+            path,
+            algorithm,
+            block_size,
+        } => api
+            .block_checksums(ctx, path, algorithm, block_size)
+            .await
+            .map(|(len, checksums)| protocol::Response::BlockChecksums { len, checksums })
+            .unwrap_or_else(protocol::Response::from),
         protocol::Request::Watch {
             path,
             recursive,
@@ -687,13 +1136,26 @@ where
             .await
             .map(|_| protocol::Response::Ok)
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::Replace {
+            query,
+            replacement,
+            dry_run,
+        } => api
+            .replace(ctx, query, replacement, dry_run)
+            .await
+            .map(|(files, replacements)| protocol::Response::ReplaceDone {
+                files,
+                replacements,
+            })
+            .unwrap_or_else(protocol::Response::from),
         protocol::Request::ProcSpawn {
             cmd,
             environment,
             current_dir,
             pty,
+            options,
         } => api
-            .proc_spawn(ctx, cmd.into(), environment, current_dir, pty)
+            .proc_spawn(ctx, cmd.into(), environment, current_dir, pty, options)
             .await
             .map(|id| protocol::Response::ProcSpawned { id })
             .unwrap_or_else(protocol::Response::from),
@@ -702,6 +1164,11 @@ where
             .await
             .map(|_| protocol::Response::Ok)
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::ProcSignal { id, signal } => api
+            .proc_signal(ctx, id, signal)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
         protocol::Request::ProcStdin { id, data } => api
             .proc_stdin(ctx, id, data)
             .await
@@ -712,10 +1179,50 @@ where
             .await
             .map(|_| protocol::Response::Ok)
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::ProcList {} => api
+            .proc_list(ctx)
+            .await
+            .map(|entries| protocol::Response::ProcEntries { entries })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::ProcAttach { id } => api
+            .proc_attach(ctx, id)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::TcpConnect { host, port } => api
+            .tcp_connect(ctx, host, port)
+            .await
+            .map(|id| protocol::Response::TcpConnected { id })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::TcpListen { host, port } => api
+            .tcp_listen(ctx, host, port)
+            .await
+            .map(|(id, port)| protocol::Response::TcpListening { id, port })
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::TcpWrite { id, data } => api
+            .tcp_write(ctx, id, data)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::TcpClose { id } => api
+            .tcp_close(ctx, id)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
+        protocol::Request::TcpUnlisten { id } => api
+            .tcp_unlisten(ctx, id)
+            .await
+            .map(|_| protocol::Response::Ok)
+            .unwrap_or_else(protocol::Response::from),
         protocol::Request::SystemInfo {} => api
             .system_info(ctx)
             .await
             .map(protocol::Response::SystemInfo)
             .unwrap_or_else(protocol::Response::from),
+        protocol::Request::Environment {} => api
+            .environment(ctx)
+            .await
+            .map(|vars| protocol::Response::Environment { vars })
+            .unwrap_or_else(protocol::Response::from),
     }
 }
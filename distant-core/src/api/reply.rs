@@ -30,4 +30,8 @@ impl Reply for DistantSingleReply {
     fn clone_reply(&self) -> Box<dyn Reply<Data = Self::Data>> {
         Box::new(Self(self.0.clone_reply()))
     }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
 }
@@ -6,7 +6,12 @@ use crate::protocol;
 mod ext;
 mod lsp;
 mod process;
+mod reader;
+mod replacer;
 mod searcher;
+mod sync;
+mod tcp;
+mod transfer;
 mod watcher;
 
 /// Represents a [`Client`] that communicates using the distant protocol
@@ -20,5 +25,10 @@ pub type DistantChannel =
 pub use ext::*;
 pub use lsp::*;
 pub use process::*;
+pub use reader::*;
+pub use replacer::*;
 pub use searcher::*;
+pub use sync::*;
+pub use tcp::*;
+pub use transfer::*;
 pub use watcher::*;
@@ -7,12 +7,14 @@ use distant_net::client::Channel;
 use distant_net::common::Request;
 
 use crate::client::{
-    RemoteCommand, RemoteLspCommand, RemoteLspProcess, RemoteOutput, RemoteProcess, Searcher,
-    Watcher,
+    sync, transfer, FileReader, RemoteCommand, RemoteLspCommand, RemoteLspProcess, RemoteOutput,
+    RemoteProcess, RemoteTcpListener, RemoteTcpStream, Replacer, Searcher, SyncChange, SyncOptions,
+    TransferOptions, Watcher,
 };
 use crate::protocol::{
-    self, ChangeKindSet, DirEntry, Environment, Error as Failure, Metadata, Permissions, PtySize,
-    SearchId, SearchQuery, SetPermissionsOptions, SystemInfo, Version,
+    self, ChangeKindSet, DirEntry, Environment, Error as Failure, FileBlock, FileHash,
+    HashAlgorithm, Metadata, Permissions, ProcessId, ProcessInfo, PtySize, SearchId, SearchQuery,
+    SetPermissionsOptions, SystemInfo, TcpListenerId, TcpStreamId, UploadId, Version,
 };
 
 pub type AsyncReturn<'a, T, E = io::Error> =
@@ -44,6 +46,15 @@ pub trait DistantChannelExt {
     /// Creates a remote directory, optionally creating all parent components if specified
     fn create_dir(&mut self, path: impl Into<PathBuf>, all: bool) -> AsyncReturn<'_, ()>;
 
+    /// Downloads the remote file or directory `src` to `dst` on the local machine, preserving
+    /// permissions and modification times
+    fn download(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+        options: TransferOptions,
+    ) -> AsyncReturn<'_, ()>;
+
     fn exists(&mut self, path: impl Into<PathBuf>) -> AsyncReturn<'_, bool>;
 
     /// Retrieves metadata about a path on a remote machine
@@ -68,6 +79,15 @@ pub trait DistantChannelExt {
     /// Cancel an active search query
     fn cancel_search(&mut self, id: SearchId) -> AsyncReturn<'_, ()>;
 
+    /// Replaces matches of a query within the contents of files with `replacement`, only
+    /// reporting diffs of the changes if `dry_run` is true
+    fn replace(
+        &mut self,
+        query: impl Into<SearchQuery>,
+        replacement: impl Into<String>,
+        dry_run: bool,
+    ) -> AsyncReturn<'_, Replacer>;
+
     /// Reads entries from a directory, returning a tuple of directory entries and failures
     fn read_dir(
         &mut self,
@@ -84,6 +104,33 @@ pub trait DistantChannelExt {
     /// Returns a remote file as a string
     fn read_file_text(&mut self, path: impl Into<PathBuf>) -> AsyncReturn<'_, String>;
 
+    /// Reads up to `len` bytes of a remote file starting at `offset`, reading until the end of
+    /// the file if `len` is none
+    fn read_file_range(
+        &mut self,
+        path: impl Into<PathBuf>,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, Vec<u8>>;
+
+    /// Streams up to `len` bytes of a remote file starting at `offset`, returning a reader that
+    /// receives the contents in chunks rather than all at once
+    fn read_file_stream(
+        &mut self,
+        path: impl Into<PathBuf>,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, FileReader>;
+
+    /// Writes `blocks` of data within a remote file, creating it if missing, and then resizes the
+    /// file to `len` bytes
+    fn patch_file(
+        &mut self,
+        path: impl Into<PathBuf>,
+        len: u64,
+        blocks: Vec<FileBlock>,
+    ) -> AsyncReturn<'_, ()>;
+
     /// Removes a remote file or directory, supporting removal of non-empty directories if
     /// force is true
     fn remove(&mut self, path: impl Into<PathBuf>, force: bool) -> AsyncReturn<'_, ()>;
@@ -91,6 +138,48 @@ pub trait DistantChannelExt {
     /// Renames a remote file or directory from src to dst
     fn rename(&mut self, src: impl Into<PathBuf>, dst: impl Into<PathBuf>) -> AsyncReturn<'_, ()>;
 
+    /// Creates a symbolic link at `dst` on a remote machine that points to `src`
+    fn symlink(&mut self, src: impl Into<PathBuf>, dst: impl Into<PathBuf>) -> AsyncReturn<'_, ()>;
+
+    /// Creates a hard link at `dst` on a remote machine to the existing file at `src`
+    fn hard_link(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+    ) -> AsyncReturn<'_, ()>;
+
+    /// Reads the target of a symbolic link on a remote machine without canonicalizing it
+    fn read_link(&mut self, path: impl Into<PathBuf>) -> AsyncReturn<'_, PathBuf>;
+
+    /// Computes the digest of up to `len` bytes of a remote file starting at `offset`, hashing
+    /// until the end of the file if `len` is none
+    fn hash(
+        &mut self,
+        path: impl Into<PathBuf>,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, String>;
+
+    /// Computes the digest of every file within a remote directory and its subdirectories,
+    /// applying `offset` and `len` to each file
+    fn hash_dir(
+        &mut self,
+        path: impl Into<PathBuf>,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, Vec<FileHash>>;
+
+    /// Computes the digest of each `block_size` chunk of a remote file, returning the length of
+    /// the file alongside the digests
+    fn block_checksums(
+        &mut self,
+        path: impl Into<PathBuf>,
+        algorithm: HashAlgorithm,
+        block_size: u64,
+    ) -> AsyncReturn<'_, (u64, Vec<String>)>;
+
     /// Watches a remote file or directory
     fn watch(
         &mut self,
@@ -103,6 +192,46 @@ pub trait DistantChannelExt {
     /// Unwatches a remote file or directory
     fn unwatch(&mut self, path: impl Into<PathBuf>) -> AsyncReturn<'_, ()>;
 
+    /// Uploads the local file or directory `src` to `dst` on the remote machine, preserving
+    /// permissions and, if the server supports upload sessions, modification times
+    fn upload(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+        options: TransferOptions,
+    ) -> AsyncReturn<'_, ()>;
+
+    /// Opens a session to upload a file in chunks to `path`, returning the id of the session
+    /// and the offset where the upload should continue, resuming a partial upload of the same
+    /// path if `resume` is true
+    fn upload_open(
+        &mut self,
+        path: impl Into<PathBuf>,
+        resume: bool,
+    ) -> AsyncReturn<'_, (UploadId, u64)>;
+
+    /// Writes a chunk of data at `offset` to an upload session, returning the offset of data
+    /// confirmed as written
+    fn upload_write(
+        &mut self,
+        id: UploadId,
+        offset: u64,
+        data: impl Into<Vec<u8>>,
+    ) -> AsyncReturn<'_, u64>;
+
+    /// Commits an upload session, moving the uploaded file into place once the contents match
+    /// the hex-encoded SHA-256 `checksum` (if provided) and setting its last modification time
+    /// to `modified` seconds since the unix epoch (if provided)
+    fn upload_commit(
+        &mut self,
+        id: UploadId,
+        checksum: Option<String>,
+        modified: Option<u64>,
+    ) -> AsyncReturn<'_, ()>;
+
+    /// Aborts an upload session, discarding any partially-uploaded data
+    fn upload_abort(&mut self, id: UploadId) -> AsyncReturn<'_, ()>;
+
     /// Spawns a process on the remote machine
     fn spawn(
         &mut self,
@@ -121,6 +250,12 @@ pub trait DistantChannelExt {
         pty: Option<PtySize>,
     ) -> AsyncReturn<'_, RemoteLspProcess>;
 
+    /// Lists processes that are still tracked on the remote machine
+    fn list_processes(&mut self) -> AsyncReturn<'_, Vec<ProcessInfo>>;
+
+    /// Attaches to a process on the remote machine that was spawned by another connection
+    fn attach_process(&mut self, id: ProcessId) -> AsyncReturn<'_, RemoteProcess>;
+
     /// Spawns a process on the remote machine and wait for it to complete
     fn output(
         &mut self,
@@ -130,9 +265,44 @@ pub trait DistantChannelExt {
         pty: Option<PtySize>,
     ) -> AsyncReturn<'_, RemoteOutput>;
 
+    /// Synchronizes the contents of the local directory `src` to the remote directory `dst`,
+    /// transferring only the files and blocks that changed
+    fn sync_dir(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+        options: SyncOptions,
+    ) -> AsyncReturn<'_, Vec<SyncChange>>;
+
     /// Retrieves information about the remote system
     fn system_info(&mut self) -> AsyncReturn<'_, SystemInfo>;
 
+    /// Retrieves the environment variables of the remote server
+    fn environment(&mut self) -> AsyncReturn<'_, Environment>;
+
+    /// Opens a tcp stream from the remote machine to `host` and `port`
+    fn tcp_connect(
+        &mut self,
+        host: impl Into<String>,
+        port: u16,
+    ) -> AsyncReturn<'_, RemoteTcpStream>;
+
+    /// Listens for tcp streams on the remote machine at `host` and `port`
+    fn tcp_listen(
+        &mut self,
+        host: impl Into<String>,
+        port: u16,
+    ) -> AsyncReturn<'_, RemoteTcpListener>;
+
+    /// Sends data out through a tcp stream on the remote machine
+    fn tcp_write(&mut self, id: TcpStreamId, data: impl Into<Vec<u8>>) -> AsyncReturn<'_, ()>;
+
+    /// Closes a tcp stream on the remote machine
+    fn tcp_close(&mut self, id: TcpStreamId) -> AsyncReturn<'_, ()>;
+
+    /// Stops a tcp listener on the remote machine
+    fn tcp_unlisten(&mut self, id: TcpListenerId) -> AsyncReturn<'_, ()>;
+
     /// Retrieves server version information
     fn version(&mut self) -> AsyncReturn<'_, Version>;
 
@@ -220,6 +390,17 @@ impl DistantChannelExt
         )
     }
 
+    fn download(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+        options: TransferOptions,
+    ) -> AsyncReturn<'_, ()> {
+        let src = src.into();
+        let dst = dst.into();
+        Box::pin(async move { transfer::download(self.clone(), src, dst, options).await })
+    }
+
     fn exists(&mut self, path: impl Into<PathBuf>) -> AsyncReturn<'_, bool> {
         make_body!(
             self,
@@ -283,6 +464,17 @@ impl DistantChannelExt
         )
     }
 
+    fn replace(
+        &mut self,
+        query: impl Into<SearchQuery>,
+        replacement: impl Into<String>,
+        dry_run: bool,
+    ) -> AsyncReturn<'_, Replacer> {
+        let query = query.into();
+        let replacement = replacement.into();
+        Box::pin(async move { Replacer::replace(self.clone(), query, replacement, dry_run).await })
+    }
+
     fn read_dir(
         &mut self,
         path: impl Into<PathBuf>,
@@ -332,6 +524,50 @@ impl DistantChannelExt
         )
     }
 
+    fn read_file_range(
+        &mut self,
+        path: impl Into<PathBuf>,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, Vec<u8>> {
+        make_body!(
+            self,
+            protocol::Request::FileReadRange {
+                path: path.into(),
+                offset,
+                len
+            },
+            |data| match data {
+                protocol::Response::Blob { data } => Ok(data),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
+    fn read_file_stream(
+        &mut self,
+        path: impl Into<PathBuf>,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, FileReader> {
+        let path = path.into();
+        Box::pin(async move { FileReader::read(self.clone(), path, offset, len).await })
+    }
+
+    fn patch_file(
+        &mut self,
+        path: impl Into<PathBuf>,
+        len: u64,
+        blocks: Vec<FileBlock>,
+    ) -> AsyncReturn<'_, ()> {
+        make_body!(
+            self,
+            protocol::Request::FilePatch { path: path.into(), len, blocks },
+            @ok
+        )
+    }
+
     fn remove(&mut self, path: impl Into<PathBuf>, force: bool) -> AsyncReturn<'_, ()> {
         make_body!(
             self,
@@ -348,6 +584,107 @@ impl DistantChannelExt
         )
     }
 
+    fn symlink(&mut self, src: impl Into<PathBuf>, dst: impl Into<PathBuf>) -> AsyncReturn<'_, ()> {
+        make_body!(
+            self,
+            protocol::Request::Symlink { src: src.into(), dst: dst.into() },
+            @ok
+        )
+    }
+
+    fn hard_link(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+    ) -> AsyncReturn<'_, ()> {
+        make_body!(
+            self,
+            protocol::Request::HardLink { src: src.into(), dst: dst.into() },
+            @ok
+        )
+    }
+
+    fn read_link(&mut self, path: impl Into<PathBuf>) -> AsyncReturn<'_, PathBuf> {
+        make_body!(
+            self,
+            protocol::Request::ReadLink { path: path.into() },
+            |data| match data {
+                protocol::Response::LinkTarget { path } => Ok(path),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
+    fn hash(
+        &mut self,
+        path: impl Into<PathBuf>,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, String> {
+        make_body!(
+            self,
+            protocol::Request::Hash {
+                path: path.into(),
+                algorithm,
+                offset,
+                len,
+                recursive: false,
+            },
+            |data| match data {
+                protocol::Response::Hash { digest } => Ok(digest),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
+    fn hash_dir(
+        &mut self,
+        path: impl Into<PathBuf>,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> AsyncReturn<'_, Vec<FileHash>> {
+        make_body!(
+            self,
+            protocol::Request::Hash {
+                path: path.into(),
+                algorithm,
+                offset,
+                len,
+                recursive: true,
+            },
+            |data| match data {
+                protocol::Response::Hashes { entries } => Ok(entries),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
+    fn block_checksums(
+        &mut self,
+        path: impl Into<PathBuf>,
+        algorithm: HashAlgorithm,
+        block_size: u64,
+    ) -> AsyncReturn<'_, (u64, Vec<String>)> {
+        make_body!(
+            self,
+            protocol::Request::FileBlockChecksums {
+                path: path.into(),
+                algorithm,
+                block_size,
+            },
+            |data| match data {
+                protocol::Response::BlockChecksums { len, checksums } => Ok((len, checksums)),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
     fn watch(
         &mut self,
         path: impl Into<PathBuf>,
@@ -381,6 +718,82 @@ impl DistantChannelExt
         Box::pin(async move { inner_unwatch(self, path).await })
     }
 
+    fn upload(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+        options: TransferOptions,
+    ) -> AsyncReturn<'_, ()> {
+        let src = src.into();
+        let dst = dst.into();
+        Box::pin(async move { transfer::upload(self.clone(), src, dst, options).await })
+    }
+
+    fn upload_open(
+        &mut self,
+        path: impl Into<PathBuf>,
+        resume: bool,
+    ) -> AsyncReturn<'_, (UploadId, u64)> {
+        make_body!(
+            self,
+            protocol::Request::UploadOpen {
+                path: path.into(),
+                resume
+            },
+            |data| match data {
+                protocol::Response::UploadOpened { id, offset } => Ok((id, offset)),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
+    fn upload_write(
+        &mut self,
+        id: UploadId,
+        offset: u64,
+        data: impl Into<Vec<u8>>,
+    ) -> AsyncReturn<'_, u64> {
+        make_body!(
+            self,
+            protocol::Request::UploadWrite {
+                id,
+                offset,
+                data: data.into()
+            },
+            |data| match data {
+                protocol::Response::UploadWritten { offset, .. } => Ok(offset),
+                protocol::Response::Error(x) => Err(io::Error::from(x)),
+                _ => Err(mismatched_response()),
+            }
+        )
+    }
+
+    fn upload_commit(
+        &mut self,
+        id: UploadId,
+        checksum: Option<String>,
+        modified: Option<u64>,
+    ) -> AsyncReturn<'_, ()> {
+        make_body!(
+            self,
+            protocol::Request::UploadCommit {
+                id,
+                checksum,
+                modified
+            },
+            @ok
+        )
+    }
+
+    fn upload_abort(&mut self, id: UploadId) -> AsyncReturn<'_, ()> {
+        make_body!(
+            self,
+            protocol::Request::UploadAbort { id },
+            @ok
+        )
+    }
+
     fn spawn(
         &mut self,
         cmd: impl Into<String>,
@@ -417,6 +830,18 @@ impl DistantChannelExt
         })
     }
 
+    fn list_processes(&mut self) -> AsyncReturn<'_, Vec<ProcessInfo>> {
+        make_body!(self, protocol::Request::ProcList {}, |data| match data {
+            protocol::Response::ProcEntries { entries } => Ok(entries),
+            protocol::Response::Error(x) => Err(io::Error::from(x)),
+            _ => Err(mismatched_response()),
+        })
+    }
+
+    fn attach_process(&mut self, id: ProcessId) -> AsyncReturn<'_, RemoteProcess> {
+        Box::pin(async move { RemoteProcess::attach(self.clone(), id).await })
+    }
+
     fn output(
         &mut self,
         cmd: impl Into<String>,
@@ -430,13 +855,22 @@ impl DistantChannelExt
                 .environment(environment)
                 .current_dir(current_dir)
                 .pty(pty)
-                .spawn(self.clone(), cmd)
-                .await?
-                .output()
+                .output(self.clone(), cmd)
                 .await
         })
     }
 
+    fn sync_dir(
+        &mut self,
+        src: impl Into<PathBuf>,
+        dst: impl Into<PathBuf>,
+        options: SyncOptions,
+    ) -> AsyncReturn<'_, Vec<SyncChange>> {
+        let src = src.into();
+        let dst = dst.into();
+        Box::pin(async move { sync::sync_dir(self.clone(), src, dst, options).await })
+    }
+
     fn system_info(&mut self) -> AsyncReturn<'_, SystemInfo> {
         make_body!(self, protocol::Request::SystemInfo {}, |data| match data {
             protocol::Response::SystemInfo(x) => Ok(x),
@@ -445,6 +879,51 @@ impl DistantChannelExt
         })
     }
 
+    fn environment(&mut self) -> AsyncReturn<'_, Environment> {
+        make_body!(self, protocol::Request::Environment {}, |data| match data {
+            protocol::Response::Environment { vars } => Ok(vars),
+            protocol::Response::Error(x) => Err(io::Error::from(x)),
+            _ => Err(mismatched_response()),
+        })
+    }
+
+    fn tcp_connect(
+        &mut self,
+        host: impl Into<String>,
+        port: u16,
+    ) -> AsyncReturn<'_, RemoteTcpStream> {
+        let host = host.into();
+        Box::pin(async move { RemoteTcpStream::connect(self.clone(), host, port).await })
+    }
+
+    fn tcp_listen(
+        &mut self,
+        host: impl Into<String>,
+        port: u16,
+    ) -> AsyncReturn<'_, RemoteTcpListener> {
+        let host = host.into();
+        Box::pin(async move { RemoteTcpListener::bind(self.clone(), host, port).await })
+    }
+
+    fn tcp_write(&mut self, id: TcpStreamId, data: impl Into<Vec<u8>>) -> AsyncReturn<'_, ()> {
+        make_body!(
+            self,
+            protocol::Request::TcpWrite {
+                id,
+                data: data.into()
+            },
+            @ok
+        )
+    }
+
+    fn tcp_close(&mut self, id: TcpStreamId) -> AsyncReturn<'_, ()> {
+        make_body!(self, protocol::Request::TcpClose { id }, @ok)
+    }
+
+    fn tcp_unlisten(&mut self, id: TcpListenerId) -> AsyncReturn<'_, ()> {
+        make_body!(self, protocol::Request::TcpUnlisten { id }, @ok)
+    }
+
     fn version(&mut self) -> AsyncReturn<'_, Version> {
         make_body!(self, protocol::Request::Version {}, |data| match data {
             protocol::Response::Version(x) => Ok(x),
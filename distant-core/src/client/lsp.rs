@@ -712,6 +712,7 @@ mod tests {
                         "field1": "a",
                         "field2": "b",
                     })),
+                    seq: None,
                 },
             ))
             .await
@@ -745,6 +746,7 @@ mod tests {
                 protocol::Response::ProcStdout {
                     id: proc.id(),
                     data: msg_a.to_vec(),
+                    seq: None,
                 },
             ))
             .await
@@ -763,6 +765,7 @@ mod tests {
                 protocol::Response::ProcStdout {
                     id: proc.id(),
                     data: msg_b.to_vec(),
+                    seq: None,
                 },
             ))
             .await
@@ -797,6 +800,7 @@ mod tests {
                 protocol::Response::ProcStdout {
                     id: proc.id(),
                     data: format!("{}{}", String::from_utf8(msg).unwrap(), extra).into_bytes(),
+                    seq: None,
                 },
             ))
             .await
@@ -845,6 +849,7 @@ mod tests {
                         String::from_utf8(msg_2).unwrap()
                     )
                     .into_bytes(),
+                    seq: None,
                 },
             ))
             .await
@@ -885,6 +890,7 @@ mod tests {
                         "field1": "distant://some/path",
                         "field2": "file://other/path",
                     })),
+                    seq: None,
                 },
             ))
             .await
@@ -915,6 +921,7 @@ mod tests {
                         "field1": "a",
                         "field2": "b",
                     })),
+                    seq: None,
                 },
             ))
             .await
@@ -948,6 +955,7 @@ mod tests {
                 protocol::Response::ProcStderr {
                     id: proc.id(),
                     data: msg_a.to_vec(),
+                    seq: None,
                 },
             ))
             .await
@@ -966,6 +974,7 @@ mod tests {
                 protocol::Response::ProcStderr {
                     id: proc.id(),
                     data: msg_b.to_vec(),
+                    seq: None,
                 },
             ))
             .await
@@ -1000,6 +1009,7 @@ mod tests {
                 protocol::Response::ProcStderr {
                     id: proc.id(),
                     data: format!("{}{}", String::from_utf8(msg).unwrap(), extra).into_bytes(),
+                    seq: None,
                 },
             ))
             .await
@@ -1048,6 +1058,7 @@ mod tests {
                         String::from_utf8(msg_2).unwrap()
                     )
                     .into_bytes(),
+                    seq: None,
                 },
             ))
             .await
@@ -1088,6 +1099,7 @@ mod tests {
                         "field1": "distant://some/path",
                         "field2": "file://other/path",
                     })),
+                    seq: None,
                 },
             ))
             .await
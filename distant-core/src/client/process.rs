@@ -11,36 +11,65 @@ use tokio::task::JoinHandle;
 
 use crate::client::DistantChannel;
 use crate::constants::CLIENT_PIPE_CAPACITY;
-use crate::protocol::{self, Cmd, Environment, ProcessId, PtySize};
+use crate::protocol::{
+    self, Cmd, Environment, ProcSpawnOptions, ProcessId, ProcessLimit, ProcessUsage, PtySize,
+    Signal,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RemoteOutput {
     pub success: bool,
     pub code: Option<i32>,
+    pub limit: Option<ProcessLimit>,
+    pub timed_out: bool,
+    pub signal: Option<i32>,
+    pub core_dumped: bool,
+    pub usage: Option<ProcessUsage>,
     pub stdout: Vec<u8>,
     pub stderr: Vec<u8>,
+
+    /// Stdout and stderr interleaved in the order that the server read them, which only
+    /// approximates the order that the remote process wrote them as they come from separate pipes
+    pub combined: Vec<u8>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct RemoteStatus {
     pub success: bool,
     pub code: Option<i32>,
+    pub limit: Option<ProcessLimit>,
+    pub timed_out: bool,
+    pub signal: Option<i32>,
+    pub core_dumped: bool,
+    pub usage: Option<ProcessUsage>,
 }
 
 impl From<(bool, Option<i32>)> for RemoteStatus {
     fn from((success, code): (bool, Option<i32>)) -> Self {
-        Self { success, code }
+        Self {
+            success,
+            code,
+            limit: None,
+            timed_out: false,
+            signal: None,
+            core_dumped: false,
+            usage: None,
+        }
     }
 }
 
 type StatusResult = io::Result<RemoteStatus>;
 
+/// Chunk of stdout or stderr paired with its position amongst all output of the process
+type OutputChunk = (u64, Vec<u8>);
+
 /// A [`RemoteProcess`] builder providing support to configure
 /// before spawning the process on a remote machine
 pub struct RemoteCommand {
     pty: Option<PtySize>,
     environment: Environment,
     current_dir: Option<PathBuf>,
+    options: ProcSpawnOptions,
 }
 
 impl Default for RemoteCommand {
@@ -56,6 +85,7 @@ impl RemoteCommand {
             pty: None,
             environment: Environment::new(),
             current_dir: None,
+            options: ProcSpawnOptions::default(),
         }
     }
 
@@ -77,6 +107,12 @@ impl RemoteCommand {
         self
     }
 
+    /// Configures the process with additional options such as resource limits
+    pub fn options(&mut self, options: ProcSpawnOptions) -> &mut Self {
+        self.options = options;
+        self
+    }
+
     /// Spawns the specified process on the remote machine using the given `channel` and `cmd`
     pub async fn spawn(
         &mut self,
@@ -93,6 +129,7 @@ impl RemoteCommand {
                     pty: self.pty,
                     environment: self.environment.clone(),
                     current_dir: self.current_dir.clone(),
+                    options: self.options,
                 },
             )))
             .await?;
@@ -123,11 +160,124 @@ impl RemoteCommand {
             None => return Err(io::Error::from(io::ErrorKind::ConnectionAborted)),
         };
 
+        Ok(RemoteProcess::start(
+            id,
+            origin_id,
+            channel,
+            mailbox,
+            Vec::new(),
+        ))
+    }
+
+    /// Spawns the specified process on the remote machine using the given `channel` and `cmd`,
+    /// waiting for it to complete and collecting all of its output
+    pub async fn output(
+        &mut self,
+        channel: DistantChannel,
+        cmd: impl Into<String>,
+    ) -> io::Result<RemoteOutput> {
+        self.spawn(channel, cmd).await?.output().await
+    }
+}
+
+/// Represents a process on a remote machine
+#[derive(Debug)]
+pub struct RemoteProcess {
+    /// Id of the process
+    id: ProcessId,
+
+    /// Id used to map back to mailbox
+    origin_id: String,
+
+    // Sender to abort req task
+    abort_req_task_tx: mpsc::Sender<()>,
+
+    // Sender to abort res task
+    abort_res_task_tx: mpsc::Sender<()>,
+
+    /// Sender for stdin
+    pub stdin: Option<RemoteStdin>,
+
+    /// Receiver for stdout
+    pub stdout: Option<RemoteStdout>,
+
+    /// Receiver for stderr
+    pub stderr: Option<RemoteStderr>,
+
+    /// Sender for resize events
+    resizer: RemoteProcessResizer,
+
+    /// Sender for signal events
+    signaler: RemoteProcessSignaler,
+
+    /// Sender for kill events
+    killer: RemoteProcessKiller,
+
+    /// Task that waits for the process to complete
+    wait_task: JoinHandle<()>,
+
+    /// Handles the success and exit code for a completed process
+    status: Arc<RwLock<Option<StatusResult>>>,
+}
+
+impl RemoteProcess {
+    /// Attaches to a process that is already running on the remote machine, receiving any
+    /// output that it produced while detached followed by its future output and completion
+    pub async fn attach(mut channel: DistantChannel, id: ProcessId) -> io::Result<Self> {
+        // Submit our attach request and get back a mailbox for responses
+        let mut mailbox = channel
+            .mail(Request::new(protocol::Msg::Single(
+                protocol::Request::ProcAttach { id },
+            )))
+            .await?;
+
+        // Wait to get the confirmation of the attach as either ok or error, queuing up any
+        // output of the process that arrives before it
+        let mut queue = Vec::new();
+        let mut origin_id = None;
+        while let Some(res) = mailbox.next().await {
+            for data in res.payload.into_vec() {
+                match data {
+                    protocol::Response::ProcStdout { .. }
+                    | protocol::Response::ProcStderr { .. }
+                    | protocol::Response::ProcDone { .. } => queue.push(data),
+                    protocol::Response::Ok => origin_id = Some(res.origin_id.clone()),
+                    protocol::Response::Error(x) => return Err(io::Error::from(x)),
+                    x => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Got response type of {}", x.as_ref()),
+                        ))
+                    }
+                }
+            }
+
+            if origin_id.is_some() {
+                break;
+            }
+        }
+
+        match origin_id {
+            Some(origin_id) => Ok(Self::start(id, origin_id, channel, mailbox, queue)),
+            None => Err(io::Error::from(io::ErrorKind::ConnectionAborted)),
+        }
+    }
+
+    /// Spawns the tasks that manage a process whose responses arrive in `mailbox`, handling
+    /// everything in `queue` before any new responses
+    fn start(
+        id: ProcessId,
+        origin_id: String,
+        channel: DistantChannel,
+        mailbox: Mailbox<Response<protocol::Msg<protocol::Response>>>,
+        queue: Vec<protocol::Response>,
+    ) -> Self {
         // Create channels for our stdin/stdout/stderr
         let (stdin_tx, stdin_rx) = mpsc::channel(CLIENT_PIPE_CAPACITY);
         let (stdout_tx, stdout_rx) = mpsc::channel(CLIENT_PIPE_CAPACITY);
         let (stderr_tx, stderr_rx) = mpsc::channel(CLIENT_PIPE_CAPACITY);
         let (resize_tx, resize_rx) = mpsc::channel(1);
+        let (signal_tx, signal_rx) = mpsc::channel(1);
 
         // Used to terminate request task, either explicitly by the process or internally
         // by the response task when it terminates
@@ -142,7 +292,7 @@ impl RemoteCommand {
                 _ = abort_res_task_rx.recv() => {
                     panic!("killed");
                 }
-                res = process_incoming_responses(id, mailbox, stdout_tx, stderr_tx, kill_tx_2) => {
+                res = process_incoming_responses(id, mailbox, queue, stdout_tx, stderr_tx, kill_tx_2) => {
                     res
                 }
             }
@@ -155,7 +305,7 @@ impl RemoteCommand {
                 _ = abort_req_task_rx.recv() => {
                     panic!("killed");
                 }
-                res = process_outgoing_requests( id, channel, stdin_rx, resize_rx, kill_rx) => {
+                res = process_outgoing_requests(id, channel, stdin_rx, resize_rx, signal_rx, kill_rx) => {
                     res
                 }
             }
@@ -165,13 +315,13 @@ impl RemoteCommand {
         let status_2 = Arc::clone(&status);
         let wait_task = tokio::spawn(async move {
             let res = match tokio::try_join!(req_task, res_task) {
-                Ok((_, res)) => res.map(RemoteStatus::from),
+                Ok((_, res)) => res,
                 Err(x) => Err(io::Error::new(io::ErrorKind::Interrupted, x)),
             };
             status_2.write().await.replace(res);
         });
 
-        Ok(RemoteProcess {
+        RemoteProcess {
             id,
             origin_id,
             abort_req_task_tx,
@@ -180,51 +330,13 @@ impl RemoteCommand {
             stdout: Some(RemoteStdout(stdout_rx)),
             stderr: Some(RemoteStderr(stderr_rx)),
             resizer: RemoteProcessResizer(resize_tx),
+            signaler: RemoteProcessSignaler(signal_tx),
             killer: RemoteProcessKiller(kill_tx),
             wait_task,
             status,
-        })
+        }
     }
-}
-
-/// Represents a process on a remote machine
-#[derive(Debug)]
-pub struct RemoteProcess {
-    /// Id of the process
-    id: ProcessId,
 
-    /// Id used to map back to mailbox
-    origin_id: String,
-
-    // Sender to abort req task
-    abort_req_task_tx: mpsc::Sender<()>,
-
-    // Sender to abort res task
-    abort_res_task_tx: mpsc::Sender<()>,
-
-    /// Sender for stdin
-    pub stdin: Option<RemoteStdin>,
-
-    /// Receiver for stdout
-    pub stdout: Option<RemoteStdout>,
-
-    /// Receiver for stderr
-    pub stderr: Option<RemoteStderr>,
-
-    /// Sender for resize events
-    resizer: RemoteProcessResizer,
-
-    /// Sender for kill events
-    killer: RemoteProcessKiller,
-
-    /// Task that waits for the process to complete
-    wait_task: JoinHandle<()>,
-
-    /// Handles the success and exit code for a completed process
-    status: Arc<RwLock<Option<StatusResult>>>,
-}
-
-impl RemoteProcess {
     /// Returns the id of the running process
     pub fn id(&self) -> ProcessId {
         self.id
@@ -245,6 +357,11 @@ impl RemoteProcess {
             Err(_) => RemoteStatus {
                 success: false,
                 code: None,
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
             },
         })
     }
@@ -270,25 +387,39 @@ impl RemoteProcess {
 
         let status = self.wait().await?;
 
+        let mut chunks = Vec::new();
+
         let mut stdout = Vec::new();
         if let Some(mut reader) = maybe_stdout {
-            while let Ok(data) = reader.read().await {
+            while let Some((seq, data)) = reader.0.recv().await {
                 stdout.extend(&data);
+                chunks.push((seq, data));
             }
         }
 
         let mut stderr = Vec::new();
         if let Some(mut reader) = maybe_stderr {
-            while let Ok(data) = reader.read().await {
+            while let Some((seq, data)) = reader.0.recv().await {
                 stderr.extend(&data);
+                chunks.push((seq, data));
             }
         }
 
+        // NOTE: Sort is stable, so chunks sharing a position keep stdout ahead of stderr
+        chunks.sort_by_key(|(seq, _)| *seq);
+        let combined = chunks.into_iter().flat_map(|(_, data)| data).collect();
+
         Ok(RemoteOutput {
             success: status.success,
             code: status.code,
+            limit: status.limit,
+            timed_out: status.timed_out,
+            signal: status.signal,
+            core_dumped: status.core_dumped,
+            usage: status.usage,
             stdout,
             stderr,
+            combined,
         })
     }
 
@@ -302,6 +433,16 @@ impl RemoteProcess {
         self.resizer.clone()
     }
 
+    /// Submits a request to send `signal` to the running process
+    pub async fn signal(&self, signal: Signal) -> io::Result<()> {
+        self.signaler.signal(signal).await
+    }
+
+    /// Clones a copy of the remote process signaler
+    pub fn clone_signaler(&self) -> RemoteProcessSignaler {
+        self.signaler.clone()
+    }
+
     /// Submits a kill request for the running process
     pub async fn kill(&mut self) -> io::Result<()> {
         self.killer.kill().await
@@ -336,6 +477,21 @@ impl RemoteProcessResizer {
     }
 }
 
+/// A handle to the channel to send signals to a remote process
+#[derive(Clone, Debug)]
+pub struct RemoteProcessSignaler(mpsc::Sender<Signal>);
+
+impl RemoteProcessSignaler {
+    /// Submits a request to send `signal` to the running process
+    pub async fn signal(&self, signal: Signal) -> io::Result<()> {
+        self.0
+            .send(signal)
+            .await
+            .map_err(|_| errors::dead_channel())?;
+        Ok(())
+    }
+}
+
 /// A handle to the channel to kill a remote process
 #[derive(Clone, Debug)]
 pub struct RemoteProcessKiller(mpsc::Sender<()>);
@@ -395,14 +551,14 @@ impl RemoteStdin {
 
 /// A handle to a remote process' standard output (stdout)
 #[derive(Debug)]
-pub struct RemoteStdout(mpsc::Receiver<Vec<u8>>);
+pub struct RemoteStdout(mpsc::Receiver<OutputChunk>);
 
 impl RemoteStdout {
     /// Tries to receive latest stdout for a remote process, yielding `None`
     /// if no stdout is available, and `BrokenPipe` if stdout has been closed
     pub fn try_read(&mut self) -> io::Result<Option<Vec<u8>>> {
         match self.0.try_recv() {
-            Ok(data) => Ok(Some(data)),
+            Ok((_, data)) => Ok(Some(data)),
             Err(TryRecvError::Empty) => Ok(None),
             Err(TryRecvError::Disconnected) => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
         }
@@ -424,6 +580,7 @@ impl RemoteStdout {
         self.0
             .recv()
             .await
+            .map(|(_, data)| data)
             .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))
     }
 
@@ -437,14 +594,14 @@ impl RemoteStdout {
 
 /// A handle to a remote process' stderr
 #[derive(Debug)]
-pub struct RemoteStderr(mpsc::Receiver<Vec<u8>>);
+pub struct RemoteStderr(mpsc::Receiver<OutputChunk>);
 
 impl RemoteStderr {
     /// Tries to receive latest stderr for a remote process, yielding `None`
     /// if no stderr is available, and `BrokenPipe` if stderr has been closed
     pub fn try_read(&mut self) -> io::Result<Option<Vec<u8>>> {
         match self.0.try_recv() {
-            Ok(data) => Ok(Some(data)),
+            Ok((_, data)) => Ok(Some(data)),
             Err(TryRecvError::Empty) => Ok(None),
             Err(TryRecvError::Disconnected) => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
         }
@@ -466,6 +623,7 @@ impl RemoteStderr {
         self.0
             .recv()
             .await
+            .map(|(_, data)| data)
             .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))
     }
 
@@ -484,6 +642,7 @@ async fn process_outgoing_requests(
     mut channel: DistantChannel,
     mut stdin_rx: mpsc::Receiver<Vec<u8>>,
     mut resize_rx: mpsc::Receiver<PtySize>,
+    mut signal_rx: mpsc::Receiver<Signal>,
     mut kill_rx: mpsc::Receiver<()>,
 ) -> io::Result<()> {
     let result = loop {
@@ -508,6 +667,16 @@ async fn process_outgoing_requests(
                     None => break Err(errors::dead_channel()),
                 }
             }
+            signal = signal_rx.recv() => {
+                match signal {
+                    Some(signal) => channel.fire(
+                        Request::new(
+                            protocol::Msg::Single(protocol::Request::ProcSignal { id, signal })
+                        )
+                    ).await?,
+                    None => break Err(errors::dead_channel()),
+                }
+            }
             msg = kill_rx.recv() => {
                 if msg.is_some() {
                     channel.fire(Request::new(
@@ -529,18 +698,46 @@ async fn process_outgoing_requests(
 async fn process_incoming_responses(
     proc_id: ProcessId,
     mut mailbox: Mailbox<Response<protocol::Msg<protocol::Response>>>,
-    stdout_tx: mpsc::Sender<Vec<u8>>,
-    stderr_tx: mpsc::Sender<Vec<u8>>,
+    queue: Vec<protocol::Response>,
+    stdout_tx: mpsc::Sender<OutputChunk>,
+    stderr_tx: mpsc::Sender<OutputChunk>,
     kill_tx: mpsc::Sender<()>,
-) -> io::Result<(bool, Option<i32>)> {
-    while let Some(res) = mailbox.next().await {
-        let payload = res.payload.into_vec();
+) -> io::Result<RemoteStatus> {
+    // Process anything that was queued up before the mailbox
+    let mut queue = Some(queue);
+
+    // Servers that do not number output fall back to the order in which it arrives
+    let mut arrival = 0;
+
+    loop {
+        let payload = match queue.take() {
+            Some(queue) => queue,
+            None => match mailbox.next().await {
+                Some(res) => res.payload.into_vec(),
+                None => break,
+            },
+        };
 
         // Check if any of the payload data is the termination
         let exit_status = payload.iter().find_map(|data| match data {
-            protocol::Response::ProcDone { id, success, code } if *id == proc_id => {
-                Some((*success, *code))
-            }
+            protocol::Response::ProcDone {
+                id,
+                success,
+                code,
+                limit,
+                timed_out,
+                signal,
+                core_dumped,
+                usage,
+            } if *id == proc_id => Some(RemoteStatus {
+                success: *success,
+                code: *code,
+                limit: *limit,
+                timed_out: *timed_out,
+                signal: *signal,
+                core_dumped: *core_dumped,
+                usage: *usage,
+            }),
             _ => None,
         });
 
@@ -548,22 +745,24 @@ async fn process_incoming_responses(
         // TODO: What should we do about unexpected data? For now, just ignore
         for data in payload {
             match data {
-                protocol::Response::ProcStdout { id, data } if id == proc_id => {
-                    let _ = stdout_tx.send(data).await;
+                protocol::Response::ProcStdout { id, data, seq } if id == proc_id => {
+                    let _ = stdout_tx.send((seq.unwrap_or(arrival), data)).await;
+                    arrival += 1;
                 }
-                protocol::Response::ProcStderr { id, data } if id == proc_id => {
-                    let _ = stderr_tx.send(data).await;
+                protocol::Response::ProcStderr { id, data, seq } if id == proc_id => {
+                    let _ = stderr_tx.send((seq.unwrap_or(arrival), data)).await;
+                    arrival += 1;
                 }
                 _ => {}
             }
         }
 
         // If we got a termination, then exit accordingly
-        if let Some((success, code)) = exit_status {
+        if let Some(status) = exit_status {
             // Flag that the other task should conclude
             let _ = kill_tx.try_send(());
 
-            return Ok((success, code));
+            return Ok(status);
         }
     }
 
@@ -762,6 +961,54 @@ mod tests {
         );
     }
 
+    #[test(tokio::test)]
+    async fn signal_should_send_proc_signal_request() {
+        let (mut transport, session) = make_session();
+
+        // Create a task for process spawning as we need to handle the request and a response
+        // in a separate async block
+        let spawn_task = tokio::spawn(async move {
+            RemoteCommand::new()
+                .spawn(session.clone_channel(), String::from("cmd arg"))
+                .await
+        });
+
+        // Wait until we get the request from the session
+        let req: Request<protocol::Msg<protocol::Request>> =
+            transport.read_frame_as().await.unwrap().unwrap();
+
+        // Send back a response through the session
+        let id = 12345;
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Msg::Single(protocol::Response::ProcSpawned { id }),
+            ))
+            .await
+            .unwrap();
+
+        // Receive the process and then signal it
+        let proc = spawn_task.await.unwrap().unwrap();
+        assert!(
+            proc.signal(Signal::Int).await.is_ok(),
+            "Failed to send signal request"
+        );
+
+        // Verify the signal request was sent
+        let req: Request<protocol::Msg<protocol::Request>> =
+            transport.read_frame_as().await.unwrap().unwrap();
+        match req.payload {
+            protocol::Msg::Single(protocol::Request::ProcSignal {
+                id: proc_id,
+                signal,
+            }) => {
+                assert_eq!(proc_id, id);
+                assert_eq!(signal, Signal::Int);
+            }
+            x => panic!("Unexpected request: {:?}", x),
+        }
+    }
+
     #[test(tokio::test)]
     async fn stdin_should_be_forwarded_from_receiver_field() {
         let (mut transport, session) = make_session();
@@ -844,6 +1091,7 @@ mod tests {
                 protocol::Msg::Single(protocol::Response::ProcStdout {
                     id,
                     data: b"some out".to_vec(),
+                    seq: None,
                 }),
             ))
             .await
@@ -888,6 +1136,7 @@ mod tests {
                 protocol::Msg::Single(protocol::Response::ProcStderr {
                     id,
                     data: b"some err".to_vec(),
+                    seq: None,
                 }),
             ))
             .await
@@ -1014,6 +1263,11 @@ mod tests {
                     id,
                     success: true,
                     code: Some(123),
+                    limit: None,
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
                 }),
             ))
             .await
@@ -1027,7 +1281,12 @@ mod tests {
             proc.status().await,
             Some(RemoteStatus {
                 success: true,
-                code: Some(123)
+                code: Some(123),
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
             })
         );
     }
@@ -1149,6 +1408,11 @@ mod tests {
                     id,
                     success: false,
                     code: Some(123),
+                    limit: None,
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
                 }),
             ))
             .await
@@ -1159,7 +1423,86 @@ mod tests {
             proc_wait_task.await.unwrap().unwrap(),
             RemoteStatus {
                 success: false,
-                code: Some(123)
+                code: Some(123),
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
+            }
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn spawn_should_send_options_and_wait_should_return_limit_hit_by_process() {
+        let (mut transport, session) = make_session();
+
+        let options = ProcSpawnOptions {
+            max_cpu_time: Some(1),
+            ..Default::default()
+        };
+
+        // Create a task for process spawning as we need to handle the request and a response
+        // in a separate async block
+        let spawn_task = tokio::spawn(async move {
+            RemoteCommand::new()
+                .options(options)
+                .spawn(session.clone_channel(), String::from("cmd arg"))
+                .await
+        });
+
+        // Wait until we get the request from the session and verify the options were included
+        let req: Request<protocol::Msg<protocol::Request>> =
+            transport.read_frame_as().await.unwrap().unwrap();
+        match &req.payload {
+            protocol::Msg::Single(protocol::Request::ProcSpawn { options: x, .. }) => {
+                assert_eq!(*x, options)
+            }
+            x => panic!("Unexpected request: {:?}", x),
+        }
+
+        // Send back a response through the session
+        let id = 12345;
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                protocol::Msg::Single(protocol::Response::ProcSpawned { id }),
+            ))
+            .await
+            .unwrap();
+
+        // Receive the process and then spawn a task for it to complete
+        let proc = spawn_task.await.unwrap().unwrap();
+        let proc_wait_task = tokio::spawn(proc.wait());
+
+        // Send a process completion response reporting that the cpu time limit was hit
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Msg::Single(protocol::Response::ProcDone {
+                    id,
+                    success: false,
+                    code: None,
+                    limit: Some(ProcessLimit::CpuTime),
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
+                }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            proc_wait_task.await.unwrap().unwrap(),
+            RemoteStatus {
+                success: false,
+                code: None,
+                limit: Some(ProcessLimit::CpuTime),
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
             }
         );
     }
@@ -1201,6 +1544,7 @@ mod tests {
                 protocol::Msg::Single(protocol::Response::ProcStdout {
                     id,
                     data: b"some out".to_vec(),
+                    seq: None,
                 }),
             ))
             .await
@@ -1213,6 +1557,7 @@ mod tests {
                 protocol::Msg::Single(protocol::Response::ProcStderr {
                     id,
                     data: b"some err".to_vec(),
+                    seq: None,
                 }),
             ))
             .await
@@ -1226,6 +1571,11 @@ mod tests {
                     id,
                     success: false,
                     code: Some(123),
+                    limit: None,
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
                 }),
             ))
             .await
@@ -1237,8 +1587,200 @@ mod tests {
             RemoteOutput {
                 success: false,
                 code: Some(123),
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
                 stdout: b"some out".to_vec(),
                 stderr: b"some err".to_vec(),
+                combined: b"some outsome err".to_vec(),
+            }
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn output_should_order_combined_output_by_sequence() {
+        let (mut transport, session) = make_session();
+
+        // Create a task for process spawning as we need to handle the request and a response
+        // in a separate async block
+        let output_task = tokio::spawn(async move {
+            RemoteCommand::new()
+                .output(session.clone_channel(), String::from("cmd arg"))
+                .await
+        });
+
+        // Wait until we get the request from the session
+        let req: Request<protocol::Msg<protocol::Request>> =
+            transport.read_frame_as().await.unwrap().unwrap();
+
+        // Send back a response through the session
+        let id = 12345;
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                protocol::Msg::Single(protocol::Response::ProcSpawned { id }),
+            ))
+            .await
+            .unwrap();
+
+        // Send output out of order, relying on the sequence to restore the order it was read
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                protocol::Msg::Batch(vec![
+                    protocol::Response::ProcStdout {
+                        id,
+                        data: b"b".to_vec(),
+                        seq: Some(1),
+                    },
+                    protocol::Response::ProcStderr {
+                        id,
+                        data: b"a".to_vec(),
+                        seq: Some(0),
+                    },
+                    protocol::Response::ProcStdout {
+                        id,
+                        data: b"d".to_vec(),
+                        seq: Some(3),
+                    },
+                    protocol::Response::ProcStderr {
+                        id,
+                        data: b"c".to_vec(),
+                        seq: Some(2),
+                    },
+                    protocol::Response::ProcDone {
+                        id,
+                        success: true,
+                        code: Some(0),
+                        limit: None,
+                        timed_out: false,
+                        signal: None,
+                        core_dumped: false,
+                        usage: None,
+                    },
+                ]),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            output_task.await.unwrap().unwrap(),
+            RemoteOutput {
+                success: true,
+                code: Some(0),
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
+                stdout: b"bd".to_vec(),
+                stderr: b"ac".to_vec(),
+                combined: b"abcd".to_vec(),
+            }
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn attach_should_return_error_if_attach_fails() {
+        let (mut transport, session) = make_session();
+
+        let attach_task =
+            tokio::spawn(
+                async move { RemoteProcess::attach(session.clone_channel(), 12345).await },
+            );
+
+        // Wait until we get the request from the session
+        let req: Request<protocol::Msg<protocol::Request>> =
+            transport.read_frame_as().await.unwrap().unwrap();
+        assert_eq!(
+            req.payload,
+            protocol::Msg::Single(protocol::Request::ProcAttach { id: 12345 })
+        );
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Msg::Single(protocol::Response::Error(Error {
+                    kind: ErrorKind::NotFound,
+                    description: String::from("some error"),
+                })),
+            ))
+            .await
+            .unwrap();
+
+        match attach_task.await.unwrap() {
+            Err(x) if x.kind() == io::ErrorKind::NotFound => {}
+            x => panic!("Unexpected result: {:?}", x),
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn attach_should_forward_output_received_before_confirmation() {
+        let (mut transport, session) = make_session();
+
+        let attach_task =
+            tokio::spawn(
+                async move { RemoteProcess::attach(session.clone_channel(), 12345).await },
+            );
+
+        // Wait until we get the request from the session
+        let req: Request<protocol::Msg<protocol::Request>> =
+            transport.read_frame_as().await.unwrap().unwrap();
+
+        // Send back buffered output and completion of the process ahead of the confirmation
+        let id = 12345;
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                protocol::Msg::Single(protocol::Response::ProcStdout {
+                    id,
+                    data: b"some out".to_vec(),
+                    seq: None,
+                }),
+            ))
+            .await
+            .unwrap();
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                protocol::Msg::Single(protocol::Response::ProcDone {
+                    id,
+                    success: true,
+                    code: Some(0),
+                    limit: None,
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
+                }),
+            ))
+            .await
+            .unwrap();
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Msg::Single(protocol::Response::Ok),
+            ))
+            .await
+            .unwrap();
+
+        // Verify that the queued output and completion make it to the process
+        let output = attach_task.await.unwrap().unwrap().output().await.unwrap();
+        assert_eq!(
+            output,
+            RemoteOutput {
+                success: true,
+                code: Some(0),
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
+                stdout: b"some out".to_vec(),
+                stderr: Vec::new(),
+                combined: b"some out".to_vec(),
             }
         );
     }
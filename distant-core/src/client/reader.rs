@@ -0,0 +1,383 @@
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{fmt, io};
+
+use distant_net::common::Request;
+use log::*;
+use tokio::io::{AsyncRead, ReadBuf};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::client::DistantChannel;
+use crate::constants::CLIENT_READER_CAPACITY;
+use crate::protocol;
+
+/// Represents a streamed read of a file on a remote machine, exposing the contents of the file
+/// through [`AsyncRead`]
+pub struct FileReader {
+    path: PathBuf,
+    task: JoinHandle<()>,
+    rx: mpsc::Receiver<io::Result<Vec<u8>>>,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+impl fmt::Debug for FileReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileReader")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl FileReader {
+    /// Starts streaming the contents of a remote file, beginning at `offset` and reading up to
+    /// `len` bytes (or until the end of the file if `len` is none)
+    pub async fn read(
+        mut channel: DistantChannel,
+        path: impl Into<PathBuf>,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<Self> {
+        let path = path.into();
+        trace!("Streaming {path:?} {{offset: {offset}, len: {len:?}}}");
+
+        // Submit our run request and get back a mailbox for responses
+        let mut mailbox = channel
+            .mail(Request::new(protocol::Msg::Single(
+                protocol::Request::FileReadStream {
+                    path: path.clone(),
+                    offset,
+                    len,
+                },
+            )))
+            .await?;
+
+        let (tx, rx) = mpsc::channel(CLIENT_READER_CAPACITY);
+
+        // Wait to get the confirmation of the stream as either ok or error
+        let mut queue: Vec<Vec<u8>> = Vec::new();
+        let mut confirmed = false;
+        let mut done = false;
+        while let Some(res) = mailbox.next().await {
+            for data in res.payload.into_vec() {
+                match data {
+                    // If we get data before the confirmation, queue it up
+                    protocol::Response::Blob { data } => queue.push(data),
+
+                    // If the read finished before we processed the confirmation, we still
+                    // want to forward everything that we have queued
+                    protocol::Response::FileReadDone => done = true,
+
+                    // Once we get the confirmation, mark as ready to go
+                    protocol::Response::Ok => confirmed = true,
+
+                    // If we get an explicit error, convert and return it
+                    protocol::Response::Error(x) => return Err(io::Error::from(x)),
+
+                    // Otherwise, we got something unexpected, and report as such
+                    x => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("Unexpected response: {x:?}"),
+                        ))
+                    }
+                }
+            }
+
+            // Exit if we got the confirmation
+            // NOTE: Doing this later because we want to make sure the entire payload is processed
+            //       first before exiting the loop
+            if confirmed {
+                break;
+            }
+        }
+
+        if !confirmed {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "File read stream missing started confirmation",
+            ));
+        }
+
+        // Spawn a task that forwards along any data, including what was queued up prior to the
+        // confirmation, until the read has finished
+        let task = tokio::spawn({
+            let path = path.clone();
+            async move {
+                for data in queue {
+                    if tx.send(Ok(data)).await.is_err() {
+                        return;
+                    }
+                }
+
+                while !done {
+                    let res = match mailbox.next().await {
+                        Some(res) => res,
+                        None => {
+                            let _ = tx
+                                .send(Err(io::Error::new(
+                                    io::ErrorKind::UnexpectedEof,
+                                    "File read stream closed before finishing",
+                                )))
+                                .await;
+                            return;
+                        }
+                    };
+
+                    for data in res.payload.into_vec() {
+                        match data {
+                            protocol::Response::Blob { data } => {
+                                // If we can't forward the data anymore, the reader has been
+                                // dropped and we want to quit
+                                if tx.send(Ok(data)).await.is_err() {
+                                    return;
+                                }
+                            }
+
+                            protocol::Response::FileReadDone => {
+                                trace!("Finished streaming {path:?}");
+                                done = true;
+                                break;
+                            }
+
+                            protocol::Response::Error(x) => {
+                                let _ = tx.send(Err(io::Error::from(x))).await;
+                                return;
+                            }
+
+                            _ => continue,
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            path,
+            task,
+            rx,
+            buf: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    /// Returns a reference to the path of the file being read
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Returns true if the reader is still receiving data from the remote file
+    pub fn is_active(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Returns the next chunk of data read from the file, or none once the entire requested
+    /// range has been read
+    ///
+    /// Any data buffered from a partial [`AsyncRead`] will be returned first.
+    pub async fn next(&mut self) -> Option<io::Result<Vec<u8>>> {
+        if self.pos < self.buf.len() {
+            let data = self.buf.split_off(self.pos);
+            self.buf.clear();
+            self.pos = 0;
+            return Some(Ok(data));
+        }
+
+        self.rx.recv().await
+    }
+}
+
+impl AsyncRead for FileReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            // Drain anything left over from the last chunk before waiting on more
+            if self.pos < self.buf.len() {
+                let n = std::cmp::min(buf.remaining(), self.buf.len() - self.pos);
+                let pos = self.pos;
+                buf.put_slice(&self.buf[pos..pos + n]);
+                self.pos += n;
+                return Poll::Ready(Ok(()));
+            }
+
+            match self.rx.poll_recv(cx) {
+                Poll::Ready(Some(Ok(data))) => {
+                    self.buf = data;
+                    self.pos = 0;
+                }
+                Poll::Ready(Some(Err(x))) => return Poll::Ready(Err(x)),
+
+                // Channel closing without an error means the read has finished
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distant_net::common::{FramedTransport, InmemoryTransport, Response};
+    use distant_net::Client;
+    use test_log::test;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::DistantClient;
+
+    fn make_session() -> (FramedTransport<InmemoryTransport>, DistantClient) {
+        let (t1, t2) = FramedTransport::pair(100);
+        (t1, Client::spawn_inmemory(t2, Default::default()))
+    }
+
+    #[test(tokio::test)]
+    async fn read_should_fail_if_server_responds_with_error() {
+        let (mut transport, session) = make_session();
+
+        let read_task = tokio::spawn(async move {
+            FileReader::read(session.clone_channel(), "/some/path", 0, None).await
+        });
+
+        let req: Request<protocol::Request> = transport.read_frame_as().await.unwrap().unwrap();
+        assert_eq!(
+            req.payload,
+            protocol::Request::FileReadStream {
+                path: PathBuf::from("/some/path"),
+                offset: 0,
+                len: None,
+            }
+        );
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Response::Error(protocol::Error {
+                    kind: protocol::ErrorKind::NotFound,
+                    description: String::from("missing"),
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let err = read_task.await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test(tokio::test)]
+    async fn reader_should_support_reading_all_chunks_until_done() {
+        let (mut transport, session) = make_session();
+
+        let read_task = tokio::spawn(async move {
+            FileReader::read(session.clone_channel(), "/some/path", 3, Some(9)).await
+        });
+
+        let req: Request<protocol::Request> = transport.read_frame_as().await.unwrap().unwrap();
+
+        transport
+            .write_frame_for(&Response::new(req.id.clone(), protocol::Response::Ok))
+            .await
+            .unwrap();
+
+        let mut reader = read_task.await.unwrap().unwrap();
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                vec![
+                    protocol::Response::Blob {
+                        data: b"some".to_vec(),
+                    },
+                    protocol::Response::Blob {
+                        data: b" data".to_vec(),
+                    },
+                ],
+            ))
+            .await
+            .unwrap();
+        transport
+            .write_frame_for(&Response::new(req.id, protocol::Response::FileReadDone))
+            .await
+            .unwrap();
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"some data");
+    }
+
+    #[test(tokio::test)]
+    async fn reader_should_forward_data_received_before_confirmation() {
+        let (mut transport, session) = make_session();
+
+        let read_task = tokio::spawn(async move {
+            FileReader::read(session.clone_channel(), "/some/path", 0, None).await
+        });
+
+        let req: Request<protocol::Request> = transport.read_frame_as().await.unwrap().unwrap();
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                vec![
+                    protocol::Response::Blob {
+                        data: b"early".to_vec(),
+                    },
+                    protocol::Response::Ok,
+                    protocol::Response::FileReadDone,
+                ],
+            ))
+            .await
+            .unwrap();
+
+        let mut reader = read_task.await.unwrap().unwrap();
+
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).await.unwrap();
+        assert_eq!(data, b"early");
+    }
+
+    #[test(tokio::test)]
+    async fn reader_should_fail_if_error_received_mid_stream() {
+        let (mut transport, session) = make_session();
+
+        let read_task = tokio::spawn(async move {
+            FileReader::read(session.clone_channel(), "/some/path", 0, None).await
+        });
+
+        let req: Request<protocol::Request> = transport.read_frame_as().await.unwrap().unwrap();
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                vec![
+                    protocol::Response::Ok,
+                    protocol::Response::Blob {
+                        data: b"partial".to_vec(),
+                    },
+                ],
+            ))
+            .await
+            .unwrap();
+
+        let mut reader = read_task.await.unwrap().unwrap();
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Response::Error(protocol::Error {
+                    kind: protocol::ErrorKind::Interrupted,
+                    description: String::from("interrupted"),
+                }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(reader.next().await.unwrap().unwrap(), b"partial");
+        let err = reader.next().await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+}
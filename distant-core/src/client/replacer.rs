@@ -0,0 +1,296 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::{fmt, io};
+
+use distant_net::client::Mailbox;
+use distant_net::common::{Request, Response};
+use log::*;
+
+use crate::client::DistantChannel;
+use crate::protocol::{self, SearchQuery};
+
+/// Represents the matches replaced within a single remote file (or that would be replaced during
+/// a dry run)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplacedFile {
+    /// Path to the file
+    pub path: PathBuf,
+
+    /// Number of matches replaced within the file
+    pub replacements: u64,
+
+    /// Unified diff of the changes to the file, only provided during a dry run
+    pub diff: Option<String>,
+}
+
+/// Represents the totals reported once a replace has finished
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReplaceSummary {
+    /// Total number of files changed (or that would change during a dry run)
+    pub files: u64,
+
+    /// Total number of matches replaced across all files
+    pub replacements: u64,
+}
+
+/// Represents a replace of matches within the contents of files on a remote machine, reporting
+/// each file that the server changed
+pub struct Replacer {
+    query: SearchQuery,
+    mailbox: Mailbox<Response<protocol::Msg<protocol::Response>>>,
+    queue: VecDeque<ReplacedFile>,
+    received: u64,
+    summary: Option<ReplaceSummary>,
+}
+
+impl fmt::Debug for Replacer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Replacer")
+            .field("query", &self.query)
+            .field("summary", &self.summary)
+            .finish()
+    }
+}
+
+impl Replacer {
+    /// Starts replacing matches of `query` with `replacement`, only reporting the diff of each
+    /// file that would change if `dry_run` is true
+    pub async fn replace(
+        mut channel: DistantChannel,
+        query: SearchQuery,
+        replacement: impl Into<String>,
+        dry_run: bool,
+    ) -> io::Result<Self> {
+        let replacement = replacement.into();
+        trace!("Replacing {query:?} with {replacement:?} (dry run = {dry_run})");
+
+        // Submit our run request and get back a mailbox for responses
+        let mailbox = channel
+            .mail(Request::new(protocol::Msg::Single(
+                protocol::Request::Replace {
+                    query: query.clone(),
+                    replacement,
+                    dry_run,
+                },
+            )))
+            .await?;
+
+        Ok(Self {
+            query,
+            mailbox,
+            queue: VecDeque::new(),
+            received: 0,
+            summary: None,
+        })
+    }
+
+    /// Returns the query used by the replace
+    pub fn query(&self) -> &SearchQuery {
+        &self.query
+    }
+
+    /// Returns the totals of the replace once it has finished
+    pub fn summary(&self) -> Option<ReplaceSummary> {
+        self.summary
+    }
+
+    /// Waits for the next file whose matches were replaced, returning none once the replace has
+    /// finished
+    pub async fn next(&mut self) -> io::Result<Option<ReplacedFile>> {
+        loop {
+            if let Some(file) = self.queue.pop_front() {
+                return Ok(Some(file));
+            }
+
+            // NOTE: The totals are sent as soon as the replace finishes, followed by the report
+            //       of each file that was changed, so we wait until all of them have arrived
+            if matches!(self.summary, Some(summary) if self.received >= summary.files) {
+                return Ok(None);
+            }
+
+            let res = self.mailbox.next().await.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "Replace ended without finishing",
+                )
+            })?;
+
+            for data in res.payload.into_vec() {
+                match data {
+                    protocol::Response::Replaced {
+                        path,
+                        replacements,
+                        diff,
+                    } => {
+                        self.received += 1;
+                        self.queue.push_back(ReplacedFile {
+                            path,
+                            replacements,
+                            diff,
+                        })
+                    }
+                    protocol::Response::ReplaceDone {
+                        files,
+                        replacements,
+                    } => {
+                        self.summary = Some(ReplaceSummary {
+                            files,
+                            replacements,
+                        })
+                    }
+                    protocol::Response::Error(x) => return Err(io::Error::from(x)),
+                    x => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("Unexpected response: {x:?}"),
+                        ))
+                    }
+                }
+            }
+        }
+    }
+
+    /// Waits for the replace to finish, returning every file whose matches were replaced
+    /// alongside the totals
+    pub async fn wait(mut self) -> io::Result<(Vec<ReplacedFile>, ReplaceSummary)> {
+        let mut files = Vec::new();
+        while let Some(file) = self.next().await? {
+            files.push(file);
+        }
+
+        Ok((files, self.summary.unwrap_or_default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distant_net::common::{FramedTransport, InmemoryTransport, Response};
+    use distant_net::Client;
+    use test_log::test;
+
+    use super::*;
+    use crate::protocol::{SearchQueryCondition, SearchQueryTarget};
+    use crate::DistantClient;
+
+    fn make_session() -> (FramedTransport<InmemoryTransport>, DistantClient) {
+        let (t1, t2) = FramedTransport::pair(100);
+        (t1, Client::spawn_inmemory(t2, Default::default()))
+    }
+
+    fn make_query() -> SearchQuery {
+        SearchQuery {
+            target: SearchQueryTarget::Contents,
+            condition: SearchQueryCondition::regex("hello"),
+            paths: vec![PathBuf::from("/some/path")],
+            options: Default::default(),
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn replacer_should_report_each_file_until_done() {
+        let (mut transport, session) = make_session();
+
+        let mut replacer = Replacer::replace(session.clone_channel(), make_query(), "bye", true)
+            .await
+            .unwrap();
+
+        let req: Request<protocol::Request> = transport.read_frame_as().await.unwrap().unwrap();
+        assert_eq!(
+            req.payload,
+            protocol::Request::Replace {
+                query: make_query(),
+                replacement: String::from("bye"),
+                dry_run: true,
+            }
+        );
+
+        // NOTE: The totals are the response to the request, so they arrive before the files
+        transport
+            .write_frame_for(&Response::new(
+                req.id.clone(),
+                protocol::Response::ReplaceDone {
+                    files: 2,
+                    replacements: 3,
+                },
+            ))
+            .await
+            .unwrap();
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                vec![
+                    protocol::Response::Replaced {
+                        path: PathBuf::from("/some/path/a"),
+                        replacements: 1,
+                        diff: Some(String::from("-hello\n+bye\n")),
+                    },
+                    protocol::Response::Replaced {
+                        path: PathBuf::from("/some/path/b"),
+                        replacements: 2,
+                        diff: None,
+                    },
+                ],
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            replacer.next().await.unwrap(),
+            Some(ReplacedFile {
+                path: PathBuf::from("/some/path/a"),
+                replacements: 1,
+                diff: Some(String::from("-hello\n+bye\n")),
+            })
+        );
+        assert_eq!(
+            replacer.summary(),
+            Some(ReplaceSummary {
+                files: 2,
+                replacements: 3,
+            })
+        );
+
+        let (files, summary) = replacer.wait().await.unwrap();
+        assert_eq!(
+            files,
+            vec![ReplacedFile {
+                path: PathBuf::from("/some/path/b"),
+                replacements: 2,
+                diff: None,
+            }]
+        );
+        assert_eq!(
+            summary,
+            ReplaceSummary {
+                files: 2,
+                replacements: 3,
+            }
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn replacer_should_fail_if_server_responds_with_error() {
+        let (mut transport, session) = make_session();
+
+        let replacer = Replacer::replace(session.clone_channel(), make_query(), "bye", false)
+            .await
+            .unwrap();
+
+        let req: Request<protocol::Request> = transport.read_frame_as().await.unwrap().unwrap();
+
+        transport
+            .write_frame_for(&Response::new(
+                req.id,
+                protocol::Response::Error(protocol::Error {
+                    kind: protocol::ErrorKind::InvalidInput,
+                    description: String::from("bad query"),
+                }),
+            ))
+            .await
+            .unwrap();
+
+        let err = replacer.wait().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
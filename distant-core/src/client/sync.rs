@@ -0,0 +1,599 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use log::*;
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+use walkdir::WalkDir;
+
+use crate::client::{DistantChannel, DistantChannelExt};
+use crate::constants::SYNC_BLOCK_SIZE;
+use crate::protocol::{CapabilityKind, FileBlock, FileType, HashAlgorithm};
+
+/// Options that control how a local directory is synchronized to a remote machine
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// If true, files and directories on the remote machine that do not exist locally will be
+    /// removed
+    pub delete: bool,
+
+    /// If true, changes are only reported and never applied to the remote machine
+    pub dry_run: bool,
+
+    /// Globs matched against paths relative to the local directory, where only files matching
+    /// at least one of them are synchronized; if empty, all files are synchronized
+    pub include: Vec<String>,
+
+    /// Globs matched against paths relative to the local directory, where matching files and
+    /// directories are skipped and never removed from the remote machine
+    pub exclude: Vec<String>,
+
+    /// Size (in bytes) of the blocks compared between local and remote files
+    pub block_size: u64,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            delete: false,
+            dry_run: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            block_size: SYNC_BLOCK_SIZE,
+        }
+    }
+}
+
+/// Represents a change made to the remote machine while synchronizing, or one that would be made
+/// when doing a dry run
+///
+/// All paths are relative to the directories being synchronized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyncChange {
+    /// A directory was created
+    CreateDir { path: PathBuf },
+
+    /// A file that did not exist was copied in full
+    CreateFile { path: PathBuf, len: u64 },
+
+    /// A file that already existed had the blocks that differed replaced
+    UpdateFile {
+        path: PathBuf,
+        len: u64,
+        blocks: usize,
+    },
+
+    /// A file or directory was removed
+    Remove { path: PathBuf },
+}
+
+impl SyncChange {
+    /// Returns the path, relative to the directories being synchronized, that was changed
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::CreateDir { path } => path,
+            Self::CreateFile { path, .. } => path,
+            Self::UpdateFile { path, .. } => path,
+            Self::Remove { path } => path,
+        }
+    }
+}
+
+impl fmt::Display for SyncChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateDir { path } => write!(f, "mkdir {}", path.to_string_lossy()),
+            Self::CreateFile { path, len } => {
+                write!(f, "create {} ({len} bytes)", path.to_string_lossy())
+            }
+            Self::UpdateFile { path, len, blocks } => write!(
+                f,
+                "update {} ({len} bytes, {blocks} changed blocks)",
+                path.to_string_lossy()
+            ),
+            Self::Remove { path } => write!(f, "remove {}", path.to_string_lossy()),
+        }
+    }
+}
+
+/// Synchronizes the contents of the local directory `src` to the directory `dst` on the remote
+/// machine, returning the changes that were made
+///
+/// Files on both sides are compared block by block using checksums computed by the server so
+/// only the blocks that changed are transferred. If the server does not support this, every file
+/// is written in full instead.
+pub(crate) async fn sync_dir(
+    channel: DistantChannel,
+    src: PathBuf,
+    dst: PathBuf,
+    options: SyncOptions,
+) -> io::Result<Vec<SyncChange>> {
+    if options.block_size == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "block size must be greater than zero",
+        ));
+    }
+
+    if !tokio::fs::metadata(src.as_path()).await?.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{src:?} is not a directory"),
+        ));
+    }
+
+    let filter = SyncFilter::new(&options.include, &options.exclude)?;
+    let local = tokio::task::spawn_blocking({
+        let src = src.clone();
+        let filter = filter.clone();
+        move || scan_local(src.as_path(), &filter)
+    })
+    .await
+    .map_err(|x| io::Error::new(io::ErrorKind::Other, x))??;
+
+    let mut syncer = Syncer {
+        channel,
+        src,
+        dst,
+        options,
+        patching: false,
+        removed: Vec::new(),
+        changes: Vec::new(),
+    };
+
+    // Comparing blocks requires the server to both compute checksums and patch files
+    let capabilities = syncer.channel.version().await?.capabilities;
+    syncer.patching = capabilities.contains(CapabilityKind::FileBlockChecksums)
+        && capabilities.contains(CapabilityKind::FilePatch);
+    if !syncer.patching {
+        debug!("Server does not support patching files, so files will be written in full");
+    }
+
+    let remote = syncer.scan_remote().await?;
+
+    for (path, entry) in local.iter() {
+        match (entry, remote.get(path)) {
+            (LocalEntry::Dir, Some(FileType::Dir)) => {}
+            (LocalEntry::Dir, existing) => {
+                if existing.is_some() {
+                    syncer.remove(path).await?;
+                }
+                syncer.create_dir(path).await?;
+            }
+            (LocalEntry::File, Some(FileType::File)) => syncer.update_file(path).await?,
+            (LocalEntry::File, existing) => {
+                if existing.is_some() {
+                    syncer.remove(path).await?;
+                }
+                syncer.create_file(path).await?;
+            }
+            (LocalEntry::Other, _) => {}
+        }
+    }
+
+    if syncer.options.delete {
+        // Anything that exists locally or is protected by the filters must be kept, along with
+        // every directory containing it
+        let mut kept: HashSet<&Path> = HashSet::new();
+        for (path, file_type) in remote.iter() {
+            if local.contains_key(path) || !filter.is_removable(path, file_type.is_dir()) {
+                kept.extend(path.ancestors());
+            }
+        }
+
+        // Paths are ordered with parents before their children, so removing a directory
+        // means that we can skip everything within it
+        for path in remote.keys() {
+            if !kept.contains(path.as_path()) && !syncer.is_removed(path) {
+                syncer.remove(path).await?;
+            }
+        }
+    }
+
+    Ok(syncer.changes)
+}
+
+/// State of a sync that is in progress
+struct Syncer {
+    channel: DistantChannel,
+    src: PathBuf,
+    dst: PathBuf,
+    options: SyncOptions,
+
+    /// If true, files are compared and written block by block, otherwise in full
+    patching: bool,
+
+    /// Remote paths (relative to `dst`) that were removed, including everything within them
+    removed: Vec<PathBuf>,
+
+    changes: Vec<SyncChange>,
+}
+
+impl Syncer {
+    /// Retrieves the type of every path within the remote directory, creating the directory if
+    /// it does not exist yet
+    async fn scan_remote(&mut self) -> io::Result<BTreeMap<PathBuf, FileType>> {
+        match self.channel.metadata(self.dst.as_path(), false, true).await {
+            Ok(metadata) if metadata.file_type.is_dir() => {}
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{:?} is not a directory", self.dst),
+                ))
+            }
+            Err(x) if x.kind() == io::ErrorKind::NotFound => {
+                if !self.options.dry_run {
+                    debug!("Creating remote directory {:?}", self.dst);
+                    self.channel.create_dir(self.dst.as_path(), true).await?;
+                }
+                return Ok(BTreeMap::new());
+            }
+            Err(x) => return Err(x),
+        }
+
+        let (entries, errors) = self
+            .channel
+            .read_dir(self.dst.as_path(), 0, false, false, false)
+            .await?;
+        for error in errors {
+            warn!("Failed to read remote entry: {error}");
+        }
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.path, entry.file_type))
+            .collect())
+    }
+
+    async fn create_dir(&mut self, path: &Path) -> io::Result<()> {
+        if !self.options.dry_run {
+            self.channel.create_dir(self.dst.join(path), false).await?;
+        }
+
+        self.changes.push(SyncChange::CreateDir {
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    async fn remove(&mut self, path: &Path) -> io::Result<()> {
+        if !self.options.dry_run {
+            self.channel.remove(self.dst.join(path), true).await?;
+        }
+
+        self.removed.push(path.to_path_buf());
+        self.changes.push(SyncChange::Remove {
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Returns true if the remote `path` was removed, either directly or as part of a directory
+    fn is_removed(&self, path: &Path) -> bool {
+        self.removed.iter().any(|removed| path.starts_with(removed))
+    }
+
+    async fn create_file(&mut self, path: &Path) -> io::Result<()> {
+        if self.patching {
+            self.patch_file(path, None).await
+        } else {
+            self.write_file(path, true).await
+        }
+    }
+
+    async fn update_file(&mut self, path: &Path) -> io::Result<()> {
+        if !self.patching {
+            return self.write_file(path, false).await;
+        }
+
+        let remote = self
+            .channel
+            .block_checksums(
+                self.dst.join(path),
+                HashAlgorithm::Sha256,
+                self.options.block_size,
+            )
+            .await?;
+        self.patch_file(path, Some(remote)).await
+    }
+
+    /// Sends every block of the local file that does not match the checksum of the same block of
+    /// the remote file, or every block if there is no remote file
+    async fn patch_file(
+        &mut self,
+        path: &Path,
+        remote: Option<(u64, Vec<String>)>,
+    ) -> io::Result<()> {
+        let remote_path = self.dst.join(path);
+        let mut file = tokio::fs::File::open(self.src.join(path)).await?;
+        let len = file.metadata().await?.len();
+
+        let mut buf = vec![0; self.options.block_size as usize];
+        let mut offset = 0;
+        let mut changed = 0;
+        let mut blocks = Vec::new();
+        for index in 0.. {
+            let n = read_block(&mut file, &mut buf).await?;
+            if n == 0 {
+                break;
+            }
+
+            let data = &buf[..n];
+            let unchanged = remote
+                .as_ref()
+                .and_then(|(_, checksums)| checksums.get(index))
+                .map(|checksum| *checksum == sha256_hex(data))
+                .unwrap_or(false);
+
+            if !unchanged {
+                changed += 1;
+                if !self.options.dry_run {
+                    blocks.push(FileBlock {
+                        offset,
+                        data: data.to_vec(),
+                    });
+                }
+            }
+
+            offset += n as u64;
+        }
+
+        let change = match remote {
+            Some((remote_len, _)) if changed == 0 && remote_len == len => {
+                trace!("Skipping unchanged file {path:?}");
+                return Ok(());
+            }
+            Some(_) => SyncChange::UpdateFile {
+                path: path.to_path_buf(),
+                len,
+                blocks: changed,
+            },
+            None => SyncChange::CreateFile {
+                path: path.to_path_buf(),
+                len,
+            },
+        };
+
+        // NOTE: Every block is sent in one patch, which the server applies atomically, so the
+        //       remote file is never left partially synced. The patch is sent even without blocks
+        //       as it is what resizes the file to match the local one
+        if !self.options.dry_run {
+            self.channel.patch_file(remote_path, len, blocks).await?;
+        }
+
+        self.changes.push(change);
+        Ok(())
+    }
+
+    /// Writes the entire local file to the remote machine
+    async fn write_file(&mut self, path: &Path, created: bool) -> io::Result<()> {
+        let data = tokio::fs::read(self.src.join(path)).await?;
+        let len = data.len() as u64;
+
+        if !self.options.dry_run {
+            self.channel.write_file(self.dst.join(path), data).await?;
+        }
+
+        self.changes.push(if created {
+            SyncChange::CreateFile {
+                path: path.to_path_buf(),
+                len,
+            }
+        } else {
+            SyncChange::UpdateFile {
+                path: path.to_path_buf(),
+                len,
+                blocks: ((len + self.options.block_size - 1) / self.options.block_size) as usize,
+            }
+        });
+        Ok(())
+    }
+}
+
+/// Type of a path found within the local directory
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum LocalEntry {
+    Dir,
+    File,
+
+    /// Anything that is not synchronized, such as a symlink, but still protects the remote path
+    /// from being removed
+    Other,
+}
+
+/// Walks the local directory, returning every path (relative to `root`) that passes the filter
+fn scan_local(root: &Path, filter: &SyncFilter) -> io::Result<BTreeMap<PathBuf, LocalEntry>> {
+    let mut entries = BTreeMap::new();
+
+    // NOTE: Symlinks are not followed, and excluded directories are skipped entirely
+    let walk = WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| match entry.path().strip_prefix(root) {
+            Ok(path) => !filter.is_excluded(path),
+            Err(_) => true,
+        });
+
+    for entry in walk {
+        let entry = entry.map_err(io::Error::from)?;
+        let path = entry
+            .path()
+            .strip_prefix(root)
+            .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?
+            .to_path_buf();
+
+        let file_type = entry.file_type();
+        let entry = if file_type.is_dir() {
+            LocalEntry::Dir
+        } else if file_type.is_file() {
+            if !filter.is_included(&path) {
+                continue;
+            }
+            LocalEntry::File
+        } else {
+            debug!("Skipping {path:?} as it is not a file or directory");
+            LocalEntry::Other
+        };
+
+        entries.insert(path, entry);
+    }
+
+    Ok(entries)
+}
+
+/// Include and exclude globs used to determine which paths are synchronized
+#[derive(Clone, Debug)]
+struct SyncFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl SyncFilter {
+    fn new(include: &[String], exclude: &[String]) -> io::Result<Self> {
+        fn build(globs: &[String]) -> io::Result<Option<GlobSet>> {
+            if globs.is_empty() {
+                return Ok(None);
+            }
+
+            let mut builder = GlobSetBuilder::new();
+            for glob in globs {
+                builder.add(
+                    Glob::new(glob).map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?,
+                );
+            }
+
+            builder
+                .build()
+                .map(Some)
+                .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))
+        }
+
+        Ok(Self {
+            include: build(include)?,
+            exclude: build(exclude)?,
+        })
+    }
+
+    /// Returns true if the file at the relative `path` matches the include globs
+    fn is_included(&self, path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .map(|globs| globs.is_match(path))
+            .unwrap_or(true)
+    }
+
+    /// Returns true if the relative `path` or any of its parent directories match the exclude
+    /// globs
+    fn is_excluded(&self, path: &Path) -> bool {
+        match self.exclude.as_ref() {
+            Some(globs) => path
+                .ancestors()
+                .filter(|p| !p.as_os_str().is_empty())
+                .any(|p| globs.is_match(p)),
+            None => false,
+        }
+    }
+
+    /// Returns true if the relative `path` on the remote machine is allowed to be removed
+    fn is_removable(&self, path: &Path, is_dir: bool) -> bool {
+        !self.is_excluded(path) && (is_dir || self.is_included(path))
+    }
+}
+
+/// Reads from `file` until `buf` is full or the end of the file is reached, returning the total
+/// bytes read
+async fn read_block(file: &mut tokio::fs::File, buf: &mut [u8]) -> io::Result<usize> {
+    let mut total = 0;
+    while total < buf.len() {
+        let n = file.read(&mut buf[total..]).await?;
+        if n == 0 {
+            break;
+        }
+        total += n;
+    }
+    Ok(total)
+}
+
+/// Returns the SHA-256 digest of `data` as a lowercase hex string, matching the checksums used
+/// by uploads and block comparisons
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use test_log::test;
+
+    use super::*;
+
+    fn globs(globs: &[&str]) -> Vec<String> {
+        globs.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn sync_filter_should_include_all_files_if_no_include_globs() {
+        let filter = SyncFilter::new(&[], &[]).unwrap();
+        assert!(filter.is_included(Path::new("file.txt")));
+        assert!(!filter.is_excluded(Path::new("file.txt")));
+    }
+
+    #[test]
+    fn sync_filter_should_fail_if_glob_is_invalid() {
+        let _ = SyncFilter::new(&globs(&["a[b"]), &[]).unwrap_err();
+    }
+
+    #[test]
+    fn sync_filter_should_exclude_paths_within_excluded_directories() {
+        let filter = SyncFilter::new(&[], &globs(&["target"])).unwrap();
+        assert!(filter.is_excluded(Path::new("target")));
+        assert!(filter.is_excluded(&Path::new("target").join("file")));
+        assert!(!filter.is_excluded(Path::new("src")));
+    }
+
+    #[test]
+    fn sync_filter_should_only_allow_removing_paths_that_could_have_been_synced() {
+        let filter = SyncFilter::new(&globs(&["*.rs"]), &globs(&["*.log"])).unwrap();
+        assert!(filter.is_removable(Path::new("main.rs"), false));
+        assert!(filter.is_removable(Path::new("src"), true));
+        assert!(!filter.is_removable(Path::new("file.txt"), false));
+        assert!(!filter.is_removable(Path::new("out.log"), false));
+    }
+
+    #[test]
+    fn scan_local_should_return_relative_paths_that_pass_filter() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file.rs").touch().unwrap();
+        temp.child("file.txt").touch().unwrap();
+        temp.child("dir").child("nested.rs").touch().unwrap();
+        temp.child("target").child("out.rs").touch().unwrap();
+
+        let filter = SyncFilter::new(&globs(&["*.rs"]), &globs(&["target"])).unwrap();
+        let entries = scan_local(temp.path(), &filter).unwrap();
+        assert_eq!(
+            entries.into_iter().collect::<Vec<_>>(),
+            vec![
+                (PathBuf::from("dir"), LocalEntry::Dir),
+                (Path::new("dir").join("nested.rs"), LocalEntry::File),
+                (PathBuf::from("file.rs"), LocalEntry::File),
+            ]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn read_block_should_fill_buffer_until_end_of_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.write_str("abcdefg").unwrap();
+
+        let mut file = tokio::fs::File::open(file.path()).await.unwrap();
+        let mut buf = [0; 4];
+        assert_eq!(read_block(&mut file, &mut buf).await.unwrap(), 4);
+        assert_eq!(&buf, b"abcd");
+        assert_eq!(read_block(&mut file, &mut buf).await.unwrap(), 3);
+        assert_eq!(&buf[..3], b"efg");
+        assert_eq!(read_block(&mut file, &mut buf).await.unwrap(), 0);
+    }
+}
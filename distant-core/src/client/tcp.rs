@@ -0,0 +1,371 @@
+use std::collections::HashMap;
+use std::{fmt, io};
+
+use distant_net::common::Request;
+use log::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::client::{DistantChannel, DistantChannelExt};
+use crate::constants::{CLIENT_TCP_CAPACITY, MAX_TCP_CHUNK_SIZE};
+use crate::protocol::{self, TcpListenerId, TcpStreamId};
+
+/// Represents a tcp stream opened on a remote machine whose data is forwarded over a channel
+pub struct RemoteTcpStream {
+    id: TcpStreamId,
+    channel: DistantChannel,
+    task: Option<JoinHandle<()>>,
+    rx: mpsc::Receiver<Vec<u8>>,
+    closed: bool,
+}
+
+impl fmt::Debug for RemoteTcpStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteTcpStream")
+            .field("id", &self.id)
+            .finish()
+    }
+}
+
+impl RemoteTcpStream {
+    /// Opens a tcp stream from the remote machine to `host` and `port`
+    pub async fn connect(
+        mut channel: DistantChannel,
+        host: impl Into<String>,
+        port: u16,
+    ) -> io::Result<Self> {
+        let host = host.into();
+        trace!("Connecting to {host}:{port} from remote machine");
+
+        // Submit our connect request and get back a mailbox for responses
+        let mut mailbox = channel
+            .mail(Request::new(protocol::Msg::Single(
+                protocol::Request::TcpConnect { host, port },
+            )))
+            .await?;
+
+        let (tx, rx) = mpsc::channel(CLIENT_TCP_CAPACITY);
+
+        // Wait to get the confirmation of the stream as either connected or error
+        let mut queue: Vec<Vec<u8>> = Vec::new();
+        let mut id = None;
+        let mut done = false;
+        while let Some(res) = mailbox.next().await {
+            for data in res.payload.into_vec() {
+                match data {
+                    // If we get data before the confirmation, queue it up
+                    protocol::Response::TcpData { data, .. } => queue.push(data),
+
+                    // If the stream closed before we processed the confirmation, we still
+                    // want to forward everything that we have queued
+                    protocol::Response::TcpClosed { .. } => done = true,
+
+                    // Once we get the confirmation, mark as ready to go
+                    protocol::Response::TcpConnected { id: x } => id = Some(x),
+
+                    // If we get an explicit error, convert and return it
+                    protocol::Response::Error(x) => return Err(io::Error::from(x)),
+
+                    // Otherwise, we got something unexpected, and report as such
+                    x => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("Unexpected response: {x:?}"),
+                        ))
+                    }
+                }
+            }
+
+            // Exit if we got the confirmation
+            // NOTE: Doing this later because we want to make sure the entire payload is processed
+            //       first before exiting the loop
+            if id.is_some() {
+                break;
+            }
+        }
+
+        let id = match id {
+            Some(id) => id,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "Tcp stream missing connected confirmation",
+                ))
+            }
+        };
+
+        // Spawn a task that forwards along any data, including what was queued up prior to the
+        // confirmation, until the stream is closed
+        let task = tokio::spawn(async move {
+            for data in queue {
+                if tx.send(data).await.is_err() {
+                    return;
+                }
+            }
+
+            while !done {
+                let res = match mailbox.next().await {
+                    Some(res) => res,
+                    None => return,
+                };
+
+                for data in res.payload.into_vec() {
+                    match data {
+                        protocol::Response::TcpData { data, .. } => {
+                            // If we can't forward the data anymore, the stream has been
+                            // dropped and we want to quit
+                            if tx.send(data).await.is_err() {
+                                return;
+                            }
+                        }
+                        protocol::Response::TcpClosed { .. } => {
+                            trace!("Tcp stream {id} closed by remote machine");
+                            done = true;
+                            break;
+                        }
+                        _ => continue,
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            id,
+            channel,
+            task: Some(task),
+            rx,
+            closed: false,
+        })
+    }
+
+    /// Returns the id of the stream on the remote machine
+    pub fn id(&self) -> TcpStreamId {
+        self.id
+    }
+
+    /// Sends `data` out through the stream on the remote machine
+    pub async fn write(&mut self, data: impl Into<Vec<u8>>) -> io::Result<()> {
+        self.channel.tcp_write(self.id, data).await
+    }
+
+    /// Returns the next chunk of data received by the stream on the remote machine, or none
+    /// once the stream has been closed
+    pub async fn read(&mut self) -> Option<Vec<u8>> {
+        self.rx.recv().await
+    }
+
+    /// Closes the stream on the remote machine
+    pub async fn close(&mut self) -> io::Result<()> {
+        self.closed = true;
+        self.rx.close();
+        self.channel.tcp_close(self.id).await
+    }
+
+    /// Forwards data between this stream and a local `stream` until either side is closed,
+    /// closing the other side when that happens
+    pub async fn pipe(mut self, stream: TcpStream) -> io::Result<()> {
+        let (mut reader, mut writer) = stream.into_split();
+        let mut buf = vec![0; MAX_TCP_CHUNK_SIZE];
+
+        let result = loop {
+            tokio::select! {
+                res = reader.read(&mut buf) => match res {
+                    Ok(0) => break Ok(()),
+                    // NOTE: We wait for each write to be acknowledged rather than firing it off as
+                    //       the server may otherwise process writes out of order
+                    Ok(n) => if let Err(x) = self.write(&buf[..n]).await {
+                        break Err(x);
+                    },
+                    Err(x) => break Err(x),
+                },
+                data = self.rx.recv() => match data {
+                    Some(data) => if let Err(x) = writer.write_all(&data).await {
+                        break Err(x);
+                    },
+                    None => {
+                        self.closed = true;
+                        let _ = writer.shutdown().await;
+                        break Ok(());
+                    }
+                },
+            }
+        };
+
+        if !self.closed {
+            let _ = self.close().await;
+        }
+
+        result
+    }
+}
+
+impl Drop for RemoteTcpStream {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.rx.close();
+    }
+}
+
+/// Represents a listener on a remote machine whose accepted tcp streams are forwarded over a
+/// channel
+pub struct RemoteTcpListener {
+    id: TcpListenerId,
+    port: u16,
+    channel: DistantChannel,
+    task: JoinHandle<()>,
+    rx: mpsc::Receiver<(RemoteTcpStream, String)>,
+}
+
+impl fmt::Debug for RemoteTcpListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteTcpListener")
+            .field("id", &self.id)
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+impl RemoteTcpListener {
+    /// Listens for tcp streams on the remote machine at `host` and `port`, where a `port` of 0
+    /// will have the remote machine pick an available port
+    pub async fn bind(
+        mut channel: DistantChannel,
+        host: impl Into<String>,
+        port: u16,
+    ) -> io::Result<Self> {
+        let host = host.into();
+        trace!("Listening on {host}:{port} from remote machine");
+
+        // Submit our listen request and get back a mailbox for responses
+        let mut mailbox = channel
+            .mail(Request::new(protocol::Msg::Single(
+                protocol::Request::TcpListen { host, port },
+            )))
+            .await?;
+
+        // Wait to get the confirmation of the listener as either listening or error; nothing can
+        // be accepted until after the confirmation has been sent
+        let mut listening = None;
+        while let Some(res) = mailbox.next().await {
+            for data in res.payload.into_vec() {
+                match data {
+                    protocol::Response::TcpListening { id, port } => listening = Some((id, port)),
+                    protocol::Response::Error(x) => return Err(io::Error::from(x)),
+                    x => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("Unexpected response: {x:?}"),
+                        ))
+                    }
+                }
+            }
+
+            if listening.is_some() {
+                break;
+            }
+        }
+
+        let (id, port) = match listening {
+            Some(x) => x,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "Tcp listener missing listening confirmation",
+                ))
+            }
+        };
+
+        let (tx, rx) = mpsc::channel(CLIENT_TCP_CAPACITY);
+
+        // Spawn a task that hands out accepted streams and routes the data of each stream to
+        // its receiver until the listener is closed
+        let task = tokio::spawn({
+            let channel = channel.clone();
+            async move {
+                let mut streams: HashMap<TcpStreamId, mpsc::Sender<Vec<u8>>> = HashMap::new();
+                while let Some(res) = mailbox.next().await {
+                    for data in res.payload.into_vec() {
+                        match data {
+                            protocol::Response::TcpAccepted { id, peer_addr, .. } => {
+                                let (stream_tx, stream_rx) = mpsc::channel(CLIENT_TCP_CAPACITY);
+                                let stream = RemoteTcpStream {
+                                    id,
+                                    channel: channel.clone(),
+                                    task: None,
+                                    rx: stream_rx,
+                                    closed: false,
+                                };
+
+                                if tx.send((stream, peer_addr)).await.is_err() {
+                                    return;
+                                }
+
+                                streams.insert(id, stream_tx);
+                            }
+                            protocol::Response::TcpData { id, data } => {
+                                // If the stream has been dropped, stop forwarding its data
+                                let dropped = match streams.get(&id) {
+                                    Some(stream_tx) => stream_tx.send(data).await.is_err(),
+                                    None => false,
+                                };
+
+                                if dropped {
+                                    streams.remove(&id);
+                                }
+                            }
+                            protocol::Response::TcpClosed { id } => {
+                                trace!("Tcp stream {id} closed by remote machine");
+                                streams.remove(&id);
+                            }
+                            _ => continue,
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            id,
+            port,
+            channel,
+            task,
+            rx,
+        })
+    }
+
+    /// Returns the id of the listener on the remote machine
+    pub fn id(&self) -> TcpListenerId {
+        self.id
+    }
+
+    /// Returns the port the remote machine is listening on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Waits for the next tcp stream accepted by the remote machine, returning it alongside the
+    /// address of its peer, or none once the listener has been closed
+    pub async fn accept(&mut self) -> Option<(RemoteTcpStream, String)> {
+        self.rx.recv().await
+    }
+
+    /// Stops listening on the remote machine
+    ///
+    /// Streams that were already accepted remain open and keep receiving data for as long as the
+    /// listener itself is not dropped.
+    pub async fn close(&mut self) -> io::Result<()> {
+        self.rx.close();
+        self.channel.tcp_unlisten(self.id).await
+    }
+}
+
+impl Drop for RemoteTcpListener {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.rx.close();
+    }
+}
@@ -0,0 +1,455 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use walkdir::WalkDir;
+
+use crate::client::{DistantChannel, DistantChannelExt};
+use crate::constants::MAX_TRANSFER_CHUNK_SIZE;
+use crate::protocol::{CapabilityKind, FileType, Metadata, Permissions, UploadId};
+
+/// Options that control how files are transferred between the local and remote machines
+#[derive(Clone, Debug, Default)]
+pub struct TransferOptions {
+    /// If true, directories are transferred along with everything within them
+    pub recursive: bool,
+
+    /// If provided, progress of each file being transferred is sent over this channel
+    pub progress: Option<mpsc::UnboundedSender<TransferProgress>>,
+}
+
+/// Represents the progress of a single file being transferred
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferProgress {
+    /// Path of the file being written, which is remote when uploading and local when downloading
+    pub path: PathBuf,
+
+    /// Number of bytes transferred so far
+    pub transferred: u64,
+
+    /// Total size (in bytes) of the file
+    pub len: u64,
+}
+
+/// Uploads the local file or directory `src` to `dst` on the remote machine, preserving the
+/// permissions of everything uploaded and the modification time of files where supported
+///
+/// Like `cp`, if `dst` is an existing directory, `src` is placed within it.
+pub(crate) async fn upload(
+    mut channel: DistantChannel,
+    src: PathBuf,
+    dst: PathBuf,
+    options: TransferOptions,
+) -> io::Result<()> {
+    let is_dir = tokio::fs::metadata(src.as_path()).await?.is_dir();
+    if is_dir && !options.recursive {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{src:?} is a directory, so it can only be uploaded recursively"),
+        ));
+    }
+
+    let dst = match channel.metadata(dst.as_path(), false, true).await {
+        Ok(metadata) if metadata.file_type.is_dir() => join_file_name(dst, src.as_path()),
+        _ => dst,
+    };
+
+    let capabilities = channel.version().await?.capabilities;
+    let mut uploader = Uploader {
+        channel,
+        options,
+        resumable: capabilities.contains(CapabilityKind::UploadOpen)
+            && capabilities.contains(CapabilityKind::UploadWrite)
+            && capabilities.contains(CapabilityKind::UploadCommit),
+        permissions: capabilities.contains(CapabilityKind::SetPermissions),
+    };
+
+    if !uploader.resumable {
+        debug!("Server does not support upload sessions, so modification times will be lost");
+    }
+
+    if !is_dir {
+        return uploader.upload_file(src.as_path(), dst.as_path()).await;
+    }
+
+    let entries = tokio::task::spawn_blocking({
+        let src = src.clone();
+        move || {
+            WalkDir::new(src)
+                .follow_links(true)
+                .sort_by_file_name()
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()
+        }
+    })
+    .await
+    .map_err(|x| io::Error::new(io::ErrorKind::Other, x))??;
+
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = match entry.path().strip_prefix(src.as_path()) {
+            Ok(path) if path.as_os_str().is_empty() => dst.clone(),
+            Ok(path) => dst.join(path),
+            Err(_) => continue,
+        };
+
+        if entry.file_type().is_dir() {
+            trace!("Creating remote directory {path:?}");
+            uploader.channel.create_dir(path.as_path(), true).await?;
+            dirs.push((entry.path().to_path_buf(), path));
+        } else if entry.file_type().is_file() {
+            uploader.upload_file(entry.path(), path.as_path()).await?;
+        } else {
+            debug!(
+                "Skipping {:?} as it is not a file or directory",
+                entry.path()
+            );
+        }
+    }
+
+    // Permissions of directories are applied last in case they would prevent writing within them
+    for (local, remote) in dirs.into_iter().rev() {
+        let metadata = tokio::fs::metadata(local).await?;
+        uploader
+            .set_permissions(remote.as_path(), metadata.permissions())
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Downloads the remote file or directory `src` to `dst` on the local machine, preserving the
+/// permissions of everything downloaded and the modification time of files
+///
+/// Like `cp`, if `dst` is an existing directory, `src` is placed within it.
+pub(crate) async fn download(
+    mut channel: DistantChannel,
+    src: PathBuf,
+    dst: PathBuf,
+    options: TransferOptions,
+) -> io::Result<()> {
+    let metadata = channel.metadata(src.as_path(), false, true).await?;
+    let is_dir = metadata.file_type.is_dir();
+    if is_dir && !options.recursive {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{src:?} is a directory, so it can only be downloaded recursively"),
+        ));
+    }
+
+    let dst = match tokio::fs::metadata(dst.as_path()).await {
+        Ok(metadata) if metadata.is_dir() => join_file_name(dst, src.as_path()),
+        _ => dst,
+    };
+
+    let mut downloader = Downloader { channel, options };
+    if !is_dir {
+        return downloader
+            .download_file(src.as_path(), dst.as_path(), &metadata)
+            .await;
+    }
+
+    let (mut entries, errors) = downloader
+        .channel
+        .read_dir(src.as_path(), 0, false, false, false)
+        .await?;
+    for error in errors {
+        warn!("Failed to read remote entry: {error}");
+    }
+
+    // Ensure that every directory is created before anything within it
+    entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+    tokio::fs::create_dir_all(dst.as_path()).await?;
+    let mut dirs = vec![(dst.clone(), metadata)];
+    for entry in entries {
+        let remote = src.join(entry.path.as_path());
+        let local = dst.join(entry.path.as_path());
+
+        // Symlinks to files are followed, but symlinks to directories are skipped to avoid
+        // cycles, which means we need to look up the type of what each entry points to
+        let metadata = downloader
+            .channel
+            .metadata(remote.as_path(), false, true)
+            .await?;
+        match metadata.file_type {
+            FileType::Dir if entry.file_type == FileType::Dir => {
+                trace!("Creating local directory {local:?}");
+                tokio::fs::create_dir_all(local.as_path()).await?;
+                dirs.push((local, metadata));
+            }
+            FileType::File => {
+                downloader
+                    .download_file(remote.as_path(), local.as_path(), &metadata)
+                    .await?
+            }
+            _ => debug!("Skipping {remote:?} as it is not a file or directory"),
+        }
+    }
+
+    // Permissions of directories are applied last in case they would prevent writing within them
+    for (path, metadata) in dirs.into_iter().rev() {
+        let permissions = tokio::fs::metadata(path.as_path()).await?.permissions();
+        tokio::fs::set_permissions(path, local_permissions(&metadata, permissions)).await?;
+    }
+
+    Ok(())
+}
+
+/// Sends local files to the remote machine
+struct Uploader {
+    channel: DistantChannel,
+    options: TransferOptions,
+
+    /// If true, files are written to an upload session that is committed once finished
+    resumable: bool,
+
+    /// If true, permissions of local files are applied to the uploaded files
+    permissions: bool,
+}
+
+impl Uploader {
+    async fn upload_file(&mut self, src: &Path, dst: &Path) -> io::Result<()> {
+        let mut file = tokio::fs::File::open(src).await?;
+        let metadata = file.metadata().await?;
+        let len = metadata.len();
+
+        debug!("Uploading {src:?} to {dst:?} ({len} bytes)");
+        report(&self.options, dst, 0, len);
+
+        if self.resumable {
+            let (id, _) = self.channel.upload_open(dst, false).await?;
+            let modified = metadata.modified().ok().and_then(unix_secs);
+            if let Err(x) = self.write_upload(id, &mut file, dst, len, modified).await {
+                if let Err(x) = self.channel.upload_abort(id).await {
+                    warn!("Failed to abort upload of {dst:?}: {x}");
+                }
+                return Err(x);
+            }
+        } else {
+            // Without upload sessions, we create the file with the first chunk and then append
+            // the rest of the chunks to avoid loading the entire file into memory
+            let mut buf = vec![0; MAX_TRANSFER_CHUNK_SIZE];
+            let mut transferred = 0;
+            loop {
+                let n = file.read(&mut buf).await?;
+                if transferred == 0 {
+                    self.channel.write_file(dst, &buf[..n]).await?;
+                } else if n > 0 {
+                    self.channel.append_file(dst, &buf[..n]).await?;
+                }
+
+                if n == 0 {
+                    break;
+                }
+
+                transferred += n as u64;
+                report(&self.options, dst, transferred, len);
+            }
+        }
+
+        self.set_permissions(dst, metadata.permissions()).await
+    }
+
+    /// Writes the contents of `file` to the upload session `id` before committing it
+    async fn write_upload(
+        &mut self,
+        id: UploadId,
+        file: &mut tokio::fs::File,
+        dst: &Path,
+        len: u64,
+        modified: Option<u64>,
+    ) -> io::Result<()> {
+        let mut buf = vec![0; MAX_TRANSFER_CHUNK_SIZE];
+        let mut offset = 0;
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+
+            offset = self.channel.upload_write(id, offset, &buf[..n]).await?;
+            report(&self.options, dst, offset, len);
+        }
+
+        self.channel.upload_commit(id, None, modified).await
+    }
+
+    async fn set_permissions(
+        &mut self,
+        path: &Path,
+        permissions: std::fs::Permissions,
+    ) -> io::Result<()> {
+        if self.permissions {
+            self.channel
+                .set_permissions(path, Permissions::from(permissions), Default::default())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Receives remote files on the local machine
+struct Downloader {
+    channel: DistantChannel,
+    options: TransferOptions,
+}
+
+impl Downloader {
+    async fn download_file(
+        &mut self,
+        src: &Path,
+        dst: &Path,
+        metadata: &Metadata,
+    ) -> io::Result<()> {
+        let len = metadata.len;
+
+        debug!("Downloading {src:?} to {dst:?} ({len} bytes)");
+        report(&self.options, dst, 0, len);
+
+        let mut reader = self.channel.read_file_stream(src, 0, None).await?;
+        let mut file = tokio::fs::File::create(dst).await?;
+        let mut buf = vec![0; MAX_TRANSFER_CHUNK_SIZE];
+        let mut transferred = 0;
+        loop {
+            let n = reader.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+
+            file.write_all(&buf[..n]).await?;
+            transferred += n as u64;
+            report(&self.options, dst, transferred, len);
+        }
+
+        file.flush().await?;
+
+        let permissions = file.metadata().await?.permissions();
+        file.set_permissions(local_permissions(metadata, permissions))
+            .await?;
+
+        if let Some(modified) = metadata.modified {
+            let file = file.into_std().await;
+            let mtime = filetime::FileTime::from_unix_time(modified as i64, 0);
+            filetime::set_file_handle_times(&file, None, Some(mtime))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Sends the progress of the file at `path` if requested
+fn report(options: &TransferOptions, path: &Path, transferred: u64, len: u64) {
+    if let Some(progress) = options.progress.as_ref() {
+        let _ = progress.send(TransferProgress {
+            path: path.to_path_buf(),
+            transferred,
+            len,
+        });
+    }
+}
+
+/// Places the file name of `src` within the directory `dst`, falling back to `dst` itself if
+/// `src` has no file name (e.g. `..`)
+fn join_file_name(dst: PathBuf, src: &Path) -> PathBuf {
+    match src.file_name() {
+        Some(name) => dst.join(name),
+        None => dst,
+    }
+}
+
+/// Converts the permissions of a remote file into local permissions, using the full unix mode
+/// if available and otherwise only updating the readonly flag of `permissions`
+fn local_permissions(
+    metadata: &Metadata,
+    mut permissions: std::fs::Permissions,
+) -> std::fs::Permissions {
+    #[cfg(unix)]
+    if let Some(unix) = metadata.unix {
+        return Permissions::from_unix_mode(u32::from(unix)).into();
+    }
+
+    permissions.set_readonly(metadata.readonly);
+    permissions
+}
+
+/// Converts a time into seconds since the unix epoch
+fn unix_secs(time: SystemTime) -> Option<u64> {
+    time.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn join_file_name_should_place_file_name_of_src_within_dst() {
+        assert_eq!(
+            join_file_name(
+                PathBuf::from("dst"),
+                Path::new("some").join("file").as_path()
+            ),
+            Path::new("dst").join("file")
+        );
+    }
+
+    #[test]
+    fn join_file_name_should_return_dst_if_src_has_no_file_name() {
+        assert_eq!(
+            join_file_name(PathBuf::from("dst"), Path::new("..")),
+            PathBuf::from("dst")
+        );
+    }
+
+    #[test]
+    fn local_permissions_should_only_change_readonly_flag_if_no_unix_metadata() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let permissions = std::fs::metadata(temp.path()).unwrap().permissions();
+
+        let metadata = Metadata {
+            canonicalized_path: None,
+            file_type: FileType::File,
+            len: 0,
+            readonly: true,
+            accessed: None,
+            created: None,
+            modified: None,
+            unix: None,
+            windows: None,
+        };
+
+        assert!(local_permissions(&metadata, permissions).readonly());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn local_permissions_should_use_unix_mode_if_available() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let permissions = std::fs::metadata(temp.path()).unwrap().permissions();
+
+        let metadata = Metadata {
+            canonicalized_path: None,
+            file_type: FileType::File,
+            len: 0,
+            readonly: false,
+            accessed: None,
+            created: None,
+            modified: None,
+            unix: Some(crate::protocol::UnixMetadata::from(0o640)),
+            windows: None,
+        };
+
+        assert_eq!(
+            local_permissions(&metadata, permissions).mode() & 0o777,
+            0o640
+        );
+    }
+}
@@ -6,3 +6,25 @@ pub const CLIENT_WATCHER_CAPACITY: usize = 100;
 
 /// Capacity associated with a client searcher receiving matches
 pub const CLIENT_SEARCHER_CAPACITY: usize = 10000;
+
+/// Capacity associated with a client file reader receiving chunks of a file
+pub const CLIENT_READER_CAPACITY: usize = 100;
+
+/// Capacity associated with a client tcp stream or listener receiving data or accepted streams
+pub const CLIENT_TCP_CAPACITY: usize = 100;
+
+/// Default size (in bytes) of the blocks compared between local and remote files when syncing
+///
+/// Current setting is 64k size
+pub const SYNC_BLOCK_SIZE: u64 = 65536;
+
+/// Maximum size (in bytes) of a chunk of a file read or written at a time when uploading or
+/// downloading
+///
+/// Current setting is 1M size
+pub const MAX_TRANSFER_CHUNK_SIZE: usize = 1048576;
+
+/// Maximum size (in bytes) of data read from a local tcp stream before being forwarded
+///
+/// Current setting is 64k size
+pub const MAX_TCP_CHUNK_SIZE: usize = 65536;
@@ -1,22 +1,30 @@
+use std::collections::BTreeSet;
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use std::{env, io};
 
 use async_trait::async_trait;
 use distant_core::protocol::{
-    Capabilities, ChangeKind, ChangeKindSet, DirEntry, Environment, FileType, Metadata,
-    Permissions, ProcessId, PtySize, SearchId, SearchQuery, SetPermissionsOptions, SystemInfo,
-    Version, PROTOCOL_VERSION,
+    Capabilities, ChangeKind, ChangeKindSet, DirEntry, Environment, FileBlock, FileHash, FileType,
+    HashAlgorithm, Metadata, Permissions, ProcSpawnOptions, ProcessId, ProcessInfo, PtySize,
+    Response, SearchId, SearchQuery, SearchQueryMatch, SetPermissionsOptions, Signal, SystemInfo,
+    TcpListenerId, TcpStreamId, UploadId, Version, PROTOCOL_VERSION,
 };
 use distant_core::{DistantApi, DistantCtx};
 use ignore::{DirEntry as WalkDirEntry, WalkBuilder};
 use log::*;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc;
 use walkdir::WalkDir;
 
 use crate::config::Config;
+use crate::constants::{MAX_FILE_CHUNK_SIZE, MAX_FILE_RANGE_SIZE};
 
+mod hash;
+mod patch;
 mod process;
+mod replace;
 mod state;
 use state::*;
 
@@ -67,6 +75,104 @@ impl DistantApi for Api {
         tokio::fs::read_to_string(path).await
     }
 
+    async fn read_file_range(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<Vec<u8>> {
+        debug!(
+            "[Conn {}] Reading bytes from file {:?} {{offset: {}, len: {:?}}}",
+            ctx.connection_id, path, offset, len
+        );
+
+        if let Some(len) = len {
+            if len > MAX_FILE_RANGE_SIZE {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Cannot read {len} bytes from file {path:?} at once as it is more than \
+                         {MAX_FILE_RANGE_SIZE} bytes, so it must be streamed instead"
+                    ),
+                ));
+            }
+        }
+
+        let mut file = tokio::fs::File::open(&path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+
+        // NOTE: Without a length, we read one byte past the maximum to detect files that are too
+        //       large to send back as a single blob
+        let max = len.unwrap_or(MAX_FILE_RANGE_SIZE + 1);
+        let mut data = Vec::new();
+        file.take(max).read_to_end(&mut data).await?;
+
+        if len.is_none() && data.len() as u64 > MAX_FILE_RANGE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "File {path:?} has more than {MAX_FILE_RANGE_SIZE} bytes past offset \
+                     {offset}, so it must be read with a length or streamed"
+                ),
+            ));
+        }
+
+        Ok(data)
+    }
+
+    async fn read_file_stream(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Streaming bytes from file {:?} {{offset: {}, len: {:?}}}",
+            ctx.connection_id, path, offset, len
+        );
+
+        // Open and seek the file upfront so failures are reported as the response
+        let mut file = tokio::fs::File::open(&path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut file = file.take(len.unwrap_or(u64::MAX));
+
+        let connection_id = ctx.connection_id;
+        let reply = ctx.reply;
+        tokio::spawn(async move {
+            let mut buf = vec![0; MAX_FILE_CHUNK_SIZE];
+            loop {
+                let res = match file.read(&mut buf).await {
+                    Ok(0) => {
+                        trace!("[Conn {connection_id}] Finished streaming file {path:?}");
+                        let _ = reply.send(Response::FileReadDone).await;
+                        break;
+                    }
+                    Ok(n) => {
+                        reply
+                            .send(Response::Blob {
+                                data: buf[..n].to_vec(),
+                            })
+                            .await
+                    }
+                    Err(x) => {
+                        error!("[Conn {connection_id}] Failed to stream file {path:?}: {x}");
+                        let _ = reply.send(Response::from(x)).await;
+                        break;
+                    }
+                };
+
+                // If the connection is gone, there is no point in reading any further
+                if res.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     async fn write_file(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -114,6 +220,24 @@ impl DistantApi for Api {
         file.write_all(data.as_ref()).await
     }
 
+    async fn patch_file(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        len: u64,
+        blocks: Vec<FileBlock>,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Patching file {:?} {{len: {}, blocks: {}}}",
+            ctx.connection_id,
+            path,
+            len,
+            blocks.len()
+        );
+
+        patch::patch_file(path.as_path(), len, blocks).await
+    }
+
     async fn append_file_text(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -133,6 +257,64 @@ impl DistantApi for Api {
         file.write_all(data.as_ref()).await
     }
 
+    async fn upload_open(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        resume: bool,
+    ) -> io::Result<(UploadId, u64)> {
+        debug!(
+            "[Conn {}] Opening upload of {:?} {{resume: {}}}",
+            ctx.connection_id, path, resume
+        );
+        self.state
+            .upload
+            .open(ctx.connection_id, path, resume)
+            .await
+    }
+
+    async fn upload_write(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: UploadId,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> io::Result<u64> {
+        debug!(
+            "[Conn {}] Writing {} bytes to upload {} at offset {}",
+            ctx.connection_id,
+            data.len(),
+            id,
+            offset
+        );
+        self.state
+            .upload
+            .write(ctx.connection_id, id, offset, data)
+            .await
+    }
+
+    async fn upload_commit(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: UploadId,
+        checksum: Option<String>,
+        modified: Option<u64>,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Committing upload {} {{checksum: {:?}, modified: {:?}}}",
+            ctx.connection_id, id, checksum, modified
+        );
+        self.state
+            .upload
+            .commit(ctx.connection_id, id, checksum, modified)
+            .await
+    }
+
+    async fn upload_abort(&self, ctx: DistantCtx<Self::LocalData>, id: UploadId) -> io::Result<()> {
+        debug!("[Conn {}] Aborting upload {}", ctx.connection_id, id);
+        self.state.upload.discard(ctx.connection_id, id).await
+    }
+
     async fn read_dir(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -342,6 +524,137 @@ impl DistantApi for Api {
         tokio::fs::rename(src, dst).await
     }
 
+    async fn symlink(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        src: PathBuf,
+        dst: PathBuf,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Creating symlink {:?} pointing to {:?}",
+            ctx.connection_id, dst, src
+        );
+
+        #[cfg(unix)]
+        {
+            tokio::fs::symlink(src, dst).await
+        }
+
+        #[cfg(windows)]
+        {
+            // Windows needs to know whether the link points to a directory, which we determine
+            // by resolving a relative target against the directory containing the link
+            let target = match dst.parent() {
+                Some(parent) if src.is_relative() => parent.join(src.as_path()),
+                _ => src.to_path_buf(),
+            };
+
+            if tokio::fs::metadata(target)
+                .await
+                .map(|m| m.is_dir())
+                .unwrap_or_default()
+            {
+                tokio::fs::symlink_dir(src, dst).await
+            } else {
+                tokio::fs::symlink_file(src, dst).await
+            }
+        }
+    }
+
+    async fn hard_link(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        src: PathBuf,
+        dst: PathBuf,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Creating hard link {:?} to {:?}",
+            ctx.connection_id, dst, src
+        );
+        tokio::fs::hard_link(src, dst).await
+    }
+
+    async fn read_link(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+    ) -> io::Result<PathBuf> {
+        debug!("[Conn {}] Reading link {:?}", ctx.connection_id, path);
+        tokio::fs::read_link(path).await
+    }
+
+    async fn hash(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<String> {
+        debug!(
+            "[Conn {}] Hashing file {:?} {{algorithm: {}, offset: {}, len: {:?}}}",
+            ctx.connection_id, path, algorithm, offset, len
+        );
+        hash::hash_file(path.as_path(), algorithm, offset, len).await
+    }
+
+    async fn hash_dir(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<Vec<FileHash>> {
+        debug!(
+            "[Conn {}] Hashing directory {:?} {{algorithm: {}, offset: {}, len: {:?}}}",
+            ctx.connection_id, path, algorithm, offset, len
+        );
+
+        if !tokio::fs::metadata(path.as_path()).await?.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{path:?} is not a directory"),
+            ));
+        }
+
+        // NOTE: Symlinks are not followed, so only regular files within the directory are hashed
+        let mut entries = Vec::new();
+        for entry in WalkDir::new(path.as_path()) {
+            let entry = entry.map_err(io::Error::from)?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+
+            let digest = hash::hash_file(entry.path(), algorithm, offset, len).await?;
+            entries.push(FileHash {
+                path: entry
+                    .path()
+                    .strip_prefix(path.as_path())
+                    .map(Path::to_path_buf)
+                    .unwrap_or_else(|_| entry.path().to_path_buf()),
+                digest,
+            });
+        }
+
+        entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    async fn block_checksums(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        algorithm: HashAlgorithm,
+        block_size: u64,
+    ) -> io::Result<(u64, Vec<String>)> {
+        debug!(
+            "[Conn {}] Computing block checksums of {:?} {{algorithm: {}, block_size: {}}}",
+            ctx.connection_id, path, algorithm, block_size
+        );
+        hash::hash_blocks(path.as_path(), algorithm, block_size).await
+    }
+
     async fn watch(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -619,6 +932,110 @@ impl DistantApi for Api {
         self.state.search.cancel(id).await
     }
 
+    async fn replace(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        query: SearchQuery,
+        replacement: String,
+        dry_run: bool,
+    ) -> io::Result<(u64, u64)> {
+        debug!(
+            "[Conn {}] Replacing matches of {query:?} with {replacement:?} {{dry_run: {dry_run}}}",
+            ctx.connection_id,
+        );
+
+        let replacer = replace::Replacer::new(&query, replacement)?;
+
+        // Run the query as an ordinary search to find the files that contain matches, which we
+        // then rewrite one at a time
+        let (tx, mut rx) = mpsc::channel(1);
+        self.state.search.start(query, Box::new(tx)).await?;
+
+        let mut paths = BTreeSet::new();
+        while let Some(res) = rx.recv().await {
+            match res {
+                Response::SearchResults { matches, .. } => {
+                    paths.extend(matches.into_iter().filter_map(|m| match m {
+                        SearchQueryMatch::Contents(m) => Some(m.path),
+                        SearchQueryMatch::Path(_) => None,
+                    }));
+                }
+                Response::SearchDone { .. } => break,
+                _ => continue,
+            }
+        }
+
+        let mut files = 0;
+        let mut replacements = 0;
+
+        // Every file is rewritten to a temporary file before any of them are renamed into
+        // place, so a failure with any one of them leaves all of the files untouched
+        let mut staged = Vec::new();
+        for path in paths {
+            let res = match replacer.rewrite_file(&path).await {
+                Ok(Some(rewrite)) if dry_run => {
+                    files += 1;
+                    replacements += rewrite.replacements;
+                    ctx.reply
+                        .send(Response::Replaced {
+                            diff: Some(rewrite.diff()),
+                            path: rewrite.path,
+                            replacements: rewrite.replacements,
+                        })
+                        .await
+                }
+                Ok(Some(rewrite)) => rewrite.stage().await.map(|rewrite| staged.push(rewrite)),
+                Ok(None) => Ok(()),
+                Err(x) => Err(x),
+            };
+
+            if let Err(x) = res {
+                for rewrite in staged {
+                    rewrite.discard().await;
+                }
+                return Err(x);
+            }
+        }
+
+        // Make sure that no file was modified since it was read before replacing any of them
+        for rewrite in staged.iter() {
+            if let Err(x) = rewrite.check().await {
+                for rewrite in staged.iter() {
+                    rewrite.discard().await;
+                }
+                return Err(x);
+            }
+        }
+
+        let mut staged = staged.into_iter();
+        while let Some(rewrite) = staged.next() {
+            if let Err(x) = rewrite.commit().await {
+                for rewrite in staged {
+                    rewrite.discard().await;
+                }
+                return Err(io::Error::new(
+                    x.kind(),
+                    format!(
+                        "Failed to replace {:?} after replacing {files} other file(s): {x}",
+                        rewrite.path
+                    ),
+                ));
+            }
+
+            files += 1;
+            replacements += rewrite.replacements;
+            ctx.reply
+                .send(Response::Replaced {
+                    path: rewrite.path,
+                    replacements: rewrite.replacements,
+                    diff: None,
+                })
+                .await?;
+        }
+
+        Ok((files, replacements))
+    }
+
     async fn proc_spawn(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -626,14 +1043,23 @@ impl DistantApi for Api {
         environment: Environment,
         current_dir: Option<PathBuf>,
         pty: Option<PtySize>,
+        options: ProcSpawnOptions,
     ) -> io::Result<ProcessId> {
         debug!(
-            "[Conn {}] Spawning {} {{environment: {:?}, current_dir: {:?}, pty: {:?}}}",
-            ctx.connection_id, cmd, environment, current_dir, pty
+            "[Conn {}] Spawning {} {{environment: {:?}, current_dir: {:?}, pty: {:?}, options: {:?}}}",
+            ctx.connection_id, cmd, environment, current_dir, pty, options
         );
         self.state
             .process
-            .spawn(cmd, environment, current_dir, pty, ctx.reply)
+            .spawn(
+                ctx.connection_id,
+                cmd,
+                environment,
+                current_dir,
+                pty,
+                options,
+                ctx.reply,
+            )
             .await
     }
 
@@ -642,6 +1068,19 @@ impl DistantApi for Api {
         self.state.process.kill(id).await
     }
 
+    async fn proc_signal(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: ProcessId,
+        signal: Signal,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Sending {} to process {}",
+            ctx.connection_id, signal, id
+        );
+        self.state.process.signal(id, signal).await
+    }
+
     async fn proc_stdin(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -668,6 +1107,80 @@ impl DistantApi for Api {
         self.state.process.resize_pty(id, size).await
     }
 
+    async fn proc_list(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Vec<ProcessInfo>> {
+        debug!("[Conn {}] Listing processes", ctx.connection_id);
+        self.state.process.list().await
+    }
+
+    async fn proc_attach(&self, ctx: DistantCtx<Self::LocalData>, id: ProcessId) -> io::Result<()> {
+        debug!("[Conn {}] Attaching to process {}", ctx.connection_id, id);
+        self.state
+            .process
+            .attach(ctx.connection_id, id, ctx.reply)
+            .await
+    }
+
+    async fn tcp_connect(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        host: String,
+        port: u16,
+    ) -> io::Result<TcpStreamId> {
+        debug!(
+            "[Conn {}] Connecting to tcp {}:{}",
+            ctx.connection_id, host, port
+        );
+        self.state
+            .tcp
+            .connect(ctx.connection_id, host, port, ctx.reply)
+            .await
+    }
+
+    async fn tcp_listen(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        host: String,
+        port: u16,
+    ) -> io::Result<(TcpListenerId, u16)> {
+        debug!(
+            "[Conn {}] Listening on tcp {}:{}",
+            ctx.connection_id, host, port
+        );
+        self.state
+            .tcp
+            .listen(ctx.connection_id, host, port, ctx.reply)
+            .await
+    }
+
+    async fn tcp_write(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: TcpStreamId,
+        data: Vec<u8>,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Writing {} bytes to tcp stream {}",
+            ctx.connection_id,
+            data.len(),
+            id
+        );
+        self.state.tcp.write(ctx.connection_id, id, data).await
+    }
+
+    async fn tcp_close(&self, ctx: DistantCtx<Self::LocalData>, id: TcpStreamId) -> io::Result<()> {
+        debug!("[Conn {}] Closing tcp stream {}", ctx.connection_id, id);
+        self.state.tcp.close(ctx.connection_id, id).await
+    }
+
+    async fn tcp_unlisten(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: TcpListenerId,
+    ) -> io::Result<()> {
+        debug!("[Conn {}] Closing tcp listener {}", ctx.connection_id, id);
+        self.state.tcp.unlisten(ctx.connection_id, id).await
+    }
+
     async fn system_info(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<SystemInfo> {
         debug!("[Conn {}] Reading system information", ctx.connection_id);
         Ok(SystemInfo {
@@ -677,14 +1190,22 @@ impl DistantApi for Api {
             current_dir: env::current_dir().unwrap_or_default(),
             main_separator: std::path::MAIN_SEPARATOR,
             username: whoami::username(),
-            shell: if cfg!(windows) {
-                env::var("ComSpec").unwrap_or_else(|_| String::from("cmd.exe"))
-            } else {
-                env::var("SHELL").unwrap_or_else(|_| String::from("/bin/sh"))
-            },
+            shell: process::default_shell(),
         })
     }
 
+    async fn environment(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Environment> {
+        debug!("[Conn {}] Reading environment variables", ctx.connection_id);
+        Ok(env::vars_os()
+            .map(|(key, value)| {
+                (
+                    key.to_string_lossy().into_owned(),
+                    value.to_string_lossy().into_owned(),
+                )
+            })
+            .collect())
+    }
+
     async fn version(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Version> {
         debug!("[Conn {}] Querying version", ctx.connection_id);
 
@@ -703,14 +1224,14 @@ mod tests {
 
     use assert_fs::prelude::*;
     use distant_core::net::server::{ConnectionCtx, Reply};
-    use distant_core::protocol::Response;
+    use distant_core::protocol::{ProcessLimit, Response, SearchQueryCondition, SearchQueryTarget};
     use once_cell::sync::Lazy;
     use predicates::prelude::*;
     use test_log::test;
     use tokio::sync::mpsc;
 
     use super::*;
-    use crate::config::WatchConfig;
+    use crate::config::{ProcessConfig, WatchConfig};
 
     static TEMP_SCRIPT_DIR: Lazy<assert_fs::TempDir> =
         Lazy::new(|| assert_fs::TempDir::new().unwrap());
@@ -774,7 +1295,15 @@ mod tests {
     const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
 
     async fn setup(buffer: usize) -> (Api, DistantCtx<()>, mpsc::Receiver<Response>) {
+        setup_with_process_config(buffer, ProcessConfig::default()).await
+    }
+
+    async fn setup_with_process_config(
+        buffer: usize,
+        process: ProcessConfig,
+    ) -> (Api, DistantCtx<()>, mpsc::Receiver<Response>) {
         let api = Api::initialize(Config {
+            process,
             watch: WatchConfig {
                 debounce_timeout: DEBOUNCE_TIMEOUT,
                 ..Default::default()
@@ -853,49 +1382,185 @@ mod tests {
     }
 
     #[test(tokio::test)]
-    async fn write_file_should_send_error_if_fails_to_write_file() {
+    async fn read_file_range_should_fail_if_file_missing() {
         let (api, ctx, _rx) = setup(1).await;
-
-        // Create a temporary path and add to it to ensure that there are
-        // extra components that don't exist to cause writing to fail
         let temp = assert_fs::TempDir::new().unwrap();
-        let file = temp.child("dir").child("test-file");
-
-        let _ = api
-            .write_file(ctx, file.path().to_path_buf(), b"some text".to_vec())
-            .await
-            .unwrap_err();
+        let path = temp.child("missing-file").path().to_path_buf();
 
-        // Also verify that we didn't actually create the file
-        file.assert(predicate::path::missing());
+        let _ = api.read_file_range(ctx, path, 0, None).await.unwrap_err();
     }
 
     #[test(tokio::test)]
-    async fn write_file_should_send_ok_when_successful() {
+    async fn read_file_range_should_send_blob_with_bytes_within_range() {
         let (api, ctx, _rx) = setup(1).await;
 
-        // Path should point to a file that does not exist, but all
-        // other components leading up to it do
         let temp = assert_fs::TempDir::new().unwrap();
         let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
 
-        api.write_file(ctx, file.path().to_path_buf(), b"some text".to_vec())
+        let bytes = api
+            .read_file_range(ctx, file.path().to_path_buf(), 5, Some(4))
             .await
             .unwrap();
-
-        // Also verify that we actually did create the file
-        // with the associated contents
-        file.assert("some text");
+        assert_eq!(bytes, b"file");
     }
 
     #[test(tokio::test)]
-    async fn write_file_text_should_send_error_if_fails_to_write_file() {
+    async fn read_file_range_should_read_until_end_of_file_if_no_len_provided() {
         let (api, ctx, _rx) = setup(1).await;
 
-        // Create a temporary path and add to it to ensure that there are
-        // extra components that don't exist to cause writing to fail
         let temp = assert_fs::TempDir::new().unwrap();
-        let file = temp.child("dir").child("test-file");
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+
+        let bytes = api
+            .read_file_range(ctx, file.path().to_path_buf(), 5, None)
+            .await
+            .unwrap();
+        assert_eq!(bytes, b"file contents");
+    }
+
+    #[test(tokio::test)]
+    async fn read_file_range_should_fail_if_no_len_provided_and_file_is_too_large() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        let f = std::fs::File::create(file.path()).unwrap();
+        f.set_len(MAX_FILE_RANGE_SIZE + 10).unwrap();
+
+        let err = api
+            .read_file_range(ctx, file.path().to_path_buf(), 5, None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test(tokio::test)]
+    async fn read_file_range_should_fail_if_len_is_too_large() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+
+        let err = api
+            .read_file_range(
+                ctx,
+                file.path().to_path_buf(),
+                0,
+                Some(MAX_FILE_RANGE_SIZE + 1),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test(tokio::test)]
+    async fn read_file_stream_should_fail_if_file_missing() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.child("missing-file").path().to_path_buf();
+
+        let _ = api.read_file_stream(ctx, path, 0, None).await.unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn read_file_stream_should_send_blobs_followed_by_done() {
+        let (api, ctx, mut rx) = setup(100).await;
+
+        // Make the file larger than a single chunk so we get multiple blobs
+        let contents = (0..MAX_FILE_CHUNK_SIZE * 2 + 10)
+            .map(|i| (i % 256) as u8)
+            .collect::<Vec<u8>>();
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_binary(&contents).unwrap();
+
+        api.read_file_stream(ctx, file.path().to_path_buf(), 3, None)
+            .await
+            .unwrap();
+
+        let mut data = Vec::new();
+        loop {
+            match rx.recv().await.expect("Channel closed before done") {
+                Response::Blob { data: chunk } => {
+                    assert!(chunk.len() <= MAX_FILE_CHUNK_SIZE, "Chunk too big");
+                    data.extend(chunk);
+                }
+                Response::FileReadDone => break,
+                x => panic!("Unexpected response: {x:?}"),
+            }
+        }
+        assert_eq!(data, &contents[3..]);
+    }
+
+    #[test(tokio::test)]
+    async fn read_file_stream_should_stop_after_len_bytes() {
+        let (api, ctx, mut rx) = setup(100).await;
+
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+
+        api.read_file_stream(ctx, file.path().to_path_buf(), 5, Some(4))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            rx.recv().await.unwrap(),
+            Response::Blob {
+                data: b"file".to_vec()
+            }
+        );
+        assert_eq!(rx.recv().await.unwrap(), Response::FileReadDone);
+    }
+
+    #[test(tokio::test)]
+    async fn write_file_should_send_error_if_fails_to_write_file() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Create a temporary path and add to it to ensure that there are
+        // extra components that don't exist to cause writing to fail
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("dir").child("test-file");
+
+        let _ = api
+            .write_file(ctx, file.path().to_path_buf(), b"some text".to_vec())
+            .await
+            .unwrap_err();
+
+        // Also verify that we didn't actually create the file
+        file.assert(predicate::path::missing());
+    }
+
+    #[test(tokio::test)]
+    async fn write_file_should_send_ok_when_successful() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Path should point to a file that does not exist, but all
+        // other components leading up to it do
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+
+        api.write_file(ctx, file.path().to_path_buf(), b"some text".to_vec())
+            .await
+            .unwrap();
+
+        // Also verify that we actually did create the file
+        // with the associated contents
+        file.assert("some text");
+    }
+
+    #[test(tokio::test)]
+    async fn write_file_text_should_send_error_if_fails_to_write_file() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Create a temporary path and add to it to ensure that there are
+        // extra components that don't exist to cause writing to fail
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("dir").child("test-file");
 
         api.write_file_text(ctx, file.path().to_path_buf(), "some text".to_string())
             .await
@@ -992,6 +1657,133 @@ mod tests {
         file.assert("some file contentssome extra contents");
     }
 
+    #[test(tokio::test)]
+    async fn patch_file_should_create_file_if_missing() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+
+        api.patch_file(
+            ctx,
+            file.path().to_path_buf(),
+            4,
+            vec![FileBlock {
+                offset: 0,
+                data: b"abcd".to_vec(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        file.assert("abcd");
+    }
+
+    #[test(tokio::test)]
+    async fn patch_file_should_only_overwrite_provided_blocks() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+
+        api.patch_file(
+            ctx,
+            file.path().to_path_buf(),
+            18,
+            vec![
+                FileBlock {
+                    offset: 0,
+                    data: b"SOME".to_vec(),
+                },
+                FileBlock {
+                    offset: 10,
+                    data: b"CON".to_vec(),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+        file.assert("SOME file CONtents");
+    }
+
+    #[test(tokio::test)]
+    async fn patch_file_should_resize_file_to_len() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+
+        api.patch_file(ctx, file.path().to_path_buf(), 4, Vec::new())
+            .await
+            .unwrap();
+
+        file.assert("some");
+    }
+
+    #[test(tokio::test)]
+    async fn patch_file_should_leave_original_untouched_if_patch_fails_partway() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+
+        // First block can be written, but the second is beyond the largest possible file
+        let _ = api
+            .patch_file(
+                ctx,
+                file.path().to_path_buf(),
+                18,
+                vec![
+                    FileBlock {
+                        offset: 0,
+                        data: b"SOME".to_vec(),
+                    },
+                    FileBlock {
+                        offset: u64::MAX - 1,
+                        data: b"CON".to_vec(),
+                    },
+                ],
+            )
+            .await
+            .unwrap_err();
+
+        // Neither block was applied and the partially-patched copy was removed
+        file.assert("some file contents");
+        let names: Vec<_> = std::fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["test-file"]);
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn patch_file_should_keep_permissions_of_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some file contents").unwrap();
+        std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(0o754)).unwrap();
+
+        api.patch_file(
+            ctx,
+            file.path().to_path_buf(),
+            18,
+            vec![FileBlock {
+                offset: 0,
+                data: b"SOME".to_vec(),
+            }],
+        )
+        .await
+        .unwrap();
+
+        file.assert("SOME file contents");
+        let mode = std::fs::metadata(file.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o754);
+    }
+
     #[test(tokio::test)]
     async fn append_file_text_should_send_error_if_fails_to_create_file() {
         let (api, ctx, _rx) = setup(1).await;
@@ -1546,66 +2338,283 @@ mod tests {
         dst.assert("some text");
     }
 
-    /// Validates a response as being a series of changes that include the provided paths
-    fn validate_changed_path(data: &Response, expected_path: &Path, should_panic: bool) -> bool {
-        match data {
-            Response::Changed(change) if should_panic => {
-                let path = change.path.canonicalize().unwrap();
-                assert_eq!(path, expected_path, "Wrong path reported: {:?}", change);
+    #[test(tokio::test)]
+    async fn symlink_should_create_a_symlink_pointing_to_src() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let src = temp.child("src");
+        src.write_str("some text").unwrap();
+        let dst = temp.child("dst");
 
-                true
-            }
-            Response::Changed(change) => {
-                let path = change.path.canonicalize().unwrap();
-                path == expected_path
-            }
-            x if should_panic => panic!("Unexpected response: {:?}", x),
-            _ => false,
-        }
+        api.symlink(ctx, src.path().to_path_buf(), dst.path().to_path_buf())
+            .await
+            .unwrap();
+
+        dst.assert(predicate::path::is_symlink());
+        dst.assert("some text");
     }
 
     #[test(tokio::test)]
-    async fn watch_should_support_watching_a_single_file() {
-        // NOTE: Supporting multiple replies being sent back as part of creating, modifying, etc.
-        let (api, ctx, mut rx) = setup(100).await;
+    async fn symlink_should_fail_if_dst_already_exists() {
+        let (api, ctx, _rx) = setup(1).await;
         let temp = assert_fs::TempDir::new().unwrap();
+        let src = temp.child("src");
+        src.write_str("some text").unwrap();
+        let dst = temp.child("dst");
+        dst.write_str("other text").unwrap();
 
-        let file = temp.child("file");
-        file.touch().unwrap();
+        let _ = api
+            .symlink(ctx, src.path().to_path_buf(), dst.path().to_path_buf())
+            .await
+            .unwrap_err();
 
-        api.watch(
-            ctx,
-            file.path().to_path_buf(),
-            /* recursive */ false,
-            /* only */ Default::default(),
-            /* except */ Default::default(),
-        )
-        .await
-        .unwrap();
+        // Verify that the destination was left alone
+        dst.assert("other text");
+    }
 
-        // Update the file and verify we get a notification
-        file.write_str("some text").unwrap();
+    #[test(tokio::test)]
+    async fn hard_link_should_fail_if_src_missing() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let src = temp.child("src");
+        let dst = temp.child("dst");
 
-        let data = rx
-            .recv()
+        let _ = api
+            .hard_link(ctx, src.path().to_path_buf(), dst.path().to_path_buf())
             .await
-            .expect("Channel closed before we got change");
-        validate_changed_path(
-            &data,
-            &file.path().to_path_buf().canonicalize().unwrap(),
-            /* should_panic */ true,
-        );
+            .unwrap_err();
+
+        dst.assert(predicate::path::missing());
     }
 
     #[test(tokio::test)]
-    async fn watch_should_support_watching_a_directory_recursively() {
-        // NOTE: Supporting multiple replies being sent back as part of creating, modifying, etc.
-        let (api, ctx, mut rx) = setup(100).await;
+    async fn hard_link_should_create_a_link_sharing_contents_with_src() {
+        let (api, ctx, _rx) = setup(1).await;
         let temp = assert_fs::TempDir::new().unwrap();
+        let src = temp.child("src");
+        src.write_str("some text").unwrap();
+        let dst = temp.child("dst");
+
+        api.hard_link(ctx, src.path().to_path_buf(), dst.path().to_path_buf())
+            .await
+            .unwrap();
+
+        // Verify that changes through one path are seen through the other
+        dst.assert(predicate::path::is_file());
+        dst.write_str("new text").unwrap();
+        src.assert("new text");
+    }
+
+    #[test(tokio::test)]
+    async fn read_link_should_fail_if_path_is_not_a_symlink() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.touch().unwrap();
+
+        let _ = api
+            .read_link(ctx, file.path().to_path_buf())
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn read_link_should_return_target_without_canonicalizing() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.touch().unwrap();
+        let link = temp.child("link");
+        link.symlink_to_file("file").unwrap();
+
+        let target = api.read_link(ctx, link.path().to_path_buf()).await.unwrap();
+        assert_eq!(target, PathBuf::from("file"));
+    }
+
+    #[test(tokio::test)]
+    async fn hash_should_fail_if_file_missing() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.child("missing-file").path().to_path_buf();
+
+        let _ = api
+            .hash(ctx, path, HashAlgorithm::Sha256, 0, None)
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn hash_should_return_digest_of_bytes_within_range() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some abc contents").unwrap();
+
+        let digest = api
+            .hash(
+                ctx,
+                file.path().to_path_buf(),
+                HashAlgorithm::Sha256,
+                5,
+                Some(3),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn hash_dir_should_fail_if_path_is_not_a_directory() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.touch().unwrap();
+
+        let _ = api
+            .hash_dir(
+                ctx,
+                file.path().to_path_buf(),
+                HashAlgorithm::Sha256,
+                0,
+                None,
+            )
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn hash_dir_should_return_digest_of_each_file_relative_to_directory() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file1").write_str("abc").unwrap();
+        temp.child("dir").create_dir_all().unwrap();
+        temp.child("dir").child("file2").touch().unwrap();
+
+        let entries = api
+            .hash_dir(
+                ctx,
+                temp.path().to_path_buf(),
+                HashAlgorithm::Sha256,
+                0,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                FileHash {
+                    path: Path::new("dir").join("file2"),
+                    digest: String::from(
+                        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                    ),
+                },
+                FileHash {
+                    path: PathBuf::from("file1"),
+                    digest: String::from(
+                        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn block_checksums_should_fail_if_file_missing() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.child("missing-file").path().to_path_buf();
+
+        let _ = api
+            .block_checksums(ctx, path, HashAlgorithm::Sha256, 4)
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn block_checksums_should_return_len_and_digest_of_each_block() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("abcdabc").unwrap();
+
+        let (len, checksums) = api
+            .block_checksums(ctx, file.path().to_path_buf(), HashAlgorithm::Sha256, 4)
+            .await
+            .unwrap();
+        assert_eq!(len, 7);
+        assert_eq!(
+            checksums,
+            vec![
+                String::from("88d4266fd4e6338d13b845fcf289579d209c897823b9217da3e161936f031589"),
+                String::from("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            ]
+        );
+    }
+
+    /// Validates a response as being a series of changes that include the provided paths
+    fn validate_changed_path(data: &Response, expected_path: &Path, should_panic: bool) -> bool {
+        match data {
+            Response::Changed(change) if should_panic => {
+                let path = change.path.canonicalize().unwrap();
+                assert_eq!(path, expected_path, "Wrong path reported: {:?}", change);
+
+                true
+            }
+            Response::Changed(change) => {
+                let path = change.path.canonicalize().unwrap();
+                path == expected_path
+            }
+            x if should_panic => panic!("Unexpected response: {:?}", x),
+            _ => false,
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn watch_should_support_watching_a_single_file() {
+        // NOTE: Supporting multiple replies being sent back as part of creating, modifying, etc.
+        let (api, ctx, mut rx) = setup(100).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+
+        let file = temp.child("file");
+        file.touch().unwrap();
+
+        api.watch(
+            ctx,
+            file.path().to_path_buf(),
+            /* recursive */ false,
+            /* only */ Default::default(),
+            /* except */ Default::default(),
+        )
+        .await
+        .unwrap();
+
+        // Update the file and verify we get a notification
+        file.write_str("some text").unwrap();
+
+        let data = rx
+            .recv()
+            .await
+            .expect("Channel closed before we got change");
+        validate_changed_path(
+            &data,
+            &file.path().to_path_buf().canonicalize().unwrap(),
+            /* should_panic */ true,
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn watch_should_support_watching_a_directory_recursively() {
+        // NOTE: Supporting multiple replies being sent back as part of creating, modifying, etc.
+        let (api, ctx, mut rx) = setup(100).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+
+        let file = temp.child("file");
+        file.touch().unwrap();
 
-        let file = temp.child("file");
-        file.touch().unwrap();
-
         let dir = temp.child("dir");
         dir.create_dir_all().unwrap();
 
@@ -2463,6 +3472,7 @@ mod tests {
                 /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
             )
             .await
             .unwrap_err();
@@ -2487,6 +3497,7 @@ mod tests {
                 /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
             )
             .await
             .unwrap();
@@ -2512,6 +3523,7 @@ mod tests {
                 /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
             )
             .await
             .unwrap();
@@ -2530,7 +3542,7 @@ mod tests {
         let mut got_done = false;
 
         let mut check_data = |data: &Response| match data {
-            Response::ProcStdout { id, data } => {
+            Response::ProcStdout { id, data, .. } => {
                 assert_eq!(
                     *id, proc_id,
                     "Got {}, but expected {} as process id",
@@ -2576,6 +3588,7 @@ mod tests {
                 /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
             )
             .await
             .unwrap();
@@ -2594,7 +3607,7 @@ mod tests {
         let mut got_done = false;
 
         let mut check_data = |data: &Response| match data {
-            Response::ProcStderr { id, data } => {
+            Response::ProcStderr { id, data, .. } => {
                 assert_eq!(
                     *id, proc_id,
                     "Got {}, but expected {} as process id",
@@ -2636,6 +3649,7 @@ mod tests {
                 /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
             )
             .await
             .unwrap();
@@ -2651,126 +3665,654 @@ mod tests {
         }
     }
 
-    // NOTE: Ignoring on windows because it's using WSL which wants a Linux path
-    //       with / but thinks it's on windows and is providing \
     #[test(tokio::test)]
     #[cfg_attr(windows, ignore)]
-    async fn proc_spawn_should_clear_process_from_state_when_killed() {
-        let (api, ctx_1, mut rx) = setup(1).await;
-        let (ctx_2, _rx) = {
-            let (reply, rx) = make_reply(1);
-            let ctx = DistantCtx {
-                connection_id: ctx_1.connection_id,
-                reply,
-                local_data: Arc::clone(&ctx_1.local_data),
-            };
-            (ctx, rx)
-        };
+    async fn proc_spawn_should_apply_resource_limits_to_process() {
+        let (api, ctx, mut rx) = setup(1).await;
 
         let proc_id = api
             .proc_spawn(
-                ctx_1,
-                /* cmd */
-                format!("{} {} 1", *SCRIPT_RUNNER, SLEEP_SH.to_str().unwrap()),
+                ctx,
+                /* cmd */ String::from("sh -c 'ulimit -n'"),
                 /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */
+                ProcSpawnOptions {
+                    max_open_files: Some(64),
+                    ..Default::default()
+                },
             )
             .await
             .unwrap();
 
-        // Send kill signal
-        api.proc_kill(ctx_2, proc_id).await.unwrap();
-
-        // Wait for the completion response to come in
         match rx.recv().await.unwrap() {
-            Response::ProcDone { id, .. } => assert_eq!(
-                id, proc_id,
-                "Got {}, but expected {} as process id",
-                id, proc_id
-            ),
+            Response::ProcStdout { id, data, .. } => {
+                assert_eq!(id, proc_id);
+                assert_eq!(data, b"64\n", "Got wrong stdout");
+            }
             x => panic!("Unexpected response: {:?}", x),
         }
-    }
-
-    #[test(tokio::test)]
-    async fn proc_kill_should_fail_if_given_non_existent_process() {
-        let (api, ctx, _rx) = setup(1).await;
-
-        // Send kill to a non-existent process
-        let _ = api.proc_kill(ctx, 0xDEADBEEF).await.unwrap_err();
-    }
-
-    #[test(tokio::test)]
-    async fn proc_stdin_should_fail_if_given_non_existent_process() {
-        let (api, ctx, _rx) = setup(1).await;
 
-        // Send stdin to a non-existent process
-        let _ = api
-            .proc_stdin(ctx, 0xDEADBEEF, b"some input".to_vec())
-            .await
-            .unwrap_err();
+        match rx.recv().await.unwrap() {
+            Response::ProcDone { id, success, .. } => {
+                assert_eq!(id, proc_id);
+                assert!(success, "Process should have completed successfully");
+            }
+            x => panic!("Unexpected response: {:?}", x),
+        }
     }
 
-    // NOTE: Ignoring on windows because it's using WSL which wants a Linux path
-    //       with / but thinks it's on windows and is providing \
     #[test(tokio::test)]
     #[cfg_attr(windows, ignore)]
-    async fn proc_stdin_should_send_stdin_to_process() {
-        let (api, ctx_1, mut rx) = setup(1).await;
-        let (ctx_2, _rx) = {
-            let (reply, rx) = make_reply(1);
-            let ctx = DistantCtx {
-                connection_id: ctx_1.connection_id,
-                reply,
-                local_data: Arc::clone(&ctx_1.local_data),
-            };
-            (ctx, rx)
-        };
+    async fn proc_spawn_should_report_cpu_time_limit_when_hit() {
+        let (api, ctx, mut rx) = setup(1).await;
 
-        // First, run a program that listens for stdin
-        let id = api
+        let proc_id = api
             .proc_spawn(
-                ctx_1,
-                /* cmd */
-                format!(
-                    "{} {}",
-                    *SCRIPT_RUNNER,
-                    ECHO_STDIN_TO_STDOUT_SH.to_str().unwrap()
-                ),
-                Environment::new(),
+                ctx,
+                /* cmd */ String::from("sh -c 'while :; do :; done'"),
+                /* environment */ Environment::new(),
                 /* current_dir */ None,
                 /* pty */ None,
+                /* options */
+                ProcSpawnOptions {
+                    max_cpu_time: Some(1),
+                    ..Default::default()
+                },
             )
             .await
             .unwrap();
 
-        // Second, send stdin to the remote process
-        api.proc_stdin(ctx_2, id, b"hello world\n".to_vec())
-            .await
-            .unwrap();
-
-        // Third, check the async response of stdout to verify we got stdin
         match rx.recv().await.unwrap() {
-            Response::ProcStdout { data, .. } => {
-                assert_eq!(data, b"hello world\n", "Mirrored data didn't match");
+            Response::ProcDone {
+                id, success, limit, ..
+            } => {
+                assert_eq!(id, proc_id);
+                assert!(!success, "Process should have been terminated");
+                assert_eq!(limit, Some(ProcessLimit::CpuTime));
             }
             x => panic!("Unexpected response: {:?}", x),
         }
     }
 
     #[test(tokio::test)]
-    async fn system_info_should_return_system_info_based_on_binary() {
-        let (api, ctx, _rx) = setup(1).await;
+    #[cfg_attr(windows, ignore)]
+    async fn proc_spawn_should_merge_stderr_into_stdout_if_requested() {
+        let (api, ctx, mut rx) = setup(100).await;
 
-        let system_info = api.system_info(ctx).await.unwrap();
-        assert_eq!(
-            system_info,
-            SystemInfo {
-                family: std::env::consts::FAMILY.to_string(),
-                os: std::env::consts::OS.to_string(),
-                arch: std::env::consts::ARCH.to_string(),
-                current_dir: std::env::current_dir().unwrap_or_default(),
+        let proc_id = api
+            .proc_spawn(
+                ctx,
+                /* cmd */ String::from("sh -c 'printf a; printf b 1>&2; printf c'"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */
+                ProcSpawnOptions {
+                    merge_stderr: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        // Gather stdout until the process completes, verifying that it is numbered in order
+        let mut stdout = Vec::new();
+        let mut last_seq = None;
+        loop {
+            match rx.recv().await.unwrap() {
+                Response::ProcStdout { id, data, seq } => {
+                    assert_eq!(id, proc_id);
+                    assert!(seq.is_some(), "Stdout missing sequence");
+                    assert!(seq > last_seq, "Stdout sequence out of order");
+                    last_seq = seq;
+                    stdout.extend(data);
+                }
+                Response::ProcDone { id, success, .. } => {
+                    assert_eq!(id, proc_id);
+                    assert!(success, "Process should have completed successfully");
+                    break;
+                }
+                x => panic!("Unexpected response: {:?}", x),
+            }
+        }
+
+        assert_eq!(stdout, b"abc", "Got wrong stdout");
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn proc_spawn_should_report_signal_that_terminated_process() {
+        let (api, ctx, mut rx) = setup(100).await;
+
+        let proc_id = api
+            .proc_spawn(
+                ctx,
+                /* cmd */ String::from("sh -c 'kill -TERM $$'"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ Default::default(),
+            )
+            .await
+            .unwrap();
+
+        loop {
+            match rx.recv().await.unwrap() {
+                Response::ProcDone {
+                    id,
+                    success,
+                    code,
+                    signal,
+                    core_dumped,
+                    ..
+                } => {
+                    assert_eq!(id, proc_id);
+                    assert!(!success, "Process should have failed");
+                    assert_eq!(code, None, "Signaled process should not have a code");
+                    assert_eq!(signal, Some(libc::SIGTERM), "Got wrong signal");
+                    assert!(!core_dumped, "Process should not have dumped core");
+                    break;
+                }
+                Response::ProcStdout { .. } | Response::ProcStderr { .. } => {}
+                x => panic!("Unexpected response: {:?}", x),
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn proc_spawn_should_report_resource_usage_of_process() {
+        let (api, ctx, mut rx) = setup(100).await;
+
+        let proc_id = api
+            .proc_spawn(
+                ctx,
+                /* cmd */ String::from("sh -c 'exit 3'"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ Default::default(),
+            )
+            .await
+            .unwrap();
+
+        loop {
+            match rx.recv().await.unwrap() {
+                Response::ProcDone {
+                    id,
+                    code,
+                    signal,
+                    usage,
+                    ..
+                } => {
+                    assert_eq!(id, proc_id);
+                    assert_eq!(code, Some(3), "Got wrong exit code");
+                    assert_eq!(signal, None, "Process should not have been signaled");
+
+                    let usage = usage.expect("Process usage missing");
+                    assert!(usage.max_rss > 0, "Max rss should be non-zero: {usage:?}");
+                    break;
+                }
+                Response::ProcStdout { .. } | Response::ProcStderr { .. } => {}
+                x => panic!("Unexpected response: {:?}", x),
+            }
+        }
+    }
+
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_spawn_should_run_command_through_login_shell_if_requested() {
+        let (api, ctx, mut rx) = setup(100).await;
+
+        // NOTE: Piping is only understood by a shell, so this would fail if run directly
+        let proc_id = api
+            .proc_spawn(
+                ctx,
+                /* cmd */ String::from("printf %s hello | tr a-z A-Z"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */
+                ProcSpawnOptions {
+                    login_shell: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut stdout = Vec::new();
+        loop {
+            match rx.recv().await.unwrap() {
+                Response::ProcStdout { id, data, .. } => {
+                    assert_eq!(id, proc_id);
+                    stdout.extend(data);
+                }
+                Response::ProcStderr { .. } => {}
+                Response::ProcDone { id, success, .. } => {
+                    assert_eq!(id, proc_id);
+                    assert!(success, "Process should have completed successfully");
+                    break;
+                }
+                x => panic!("Unexpected response: {:?}", x),
+            }
+        }
+
+        assert!(stdout.ends_with(b"HELLO"), "Got wrong stdout");
+    }
+
+    #[test(tokio::test)]
+    async fn proc_spawn_should_fail_if_resource_limits_given_with_pty() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        let _ = api
+            .proc_spawn(
+                ctx,
+                /* cmd */ String::from("sh -c 'ulimit -n'"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ Some(PtySize::default()),
+                /* options */
+                ProcSpawnOptions {
+                    max_open_files: Some(64),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_spawn_should_kill_process_once_timeout_elapses() {
+        let (api, ctx, mut rx) = setup(1).await;
+
+        let proc_id = api
+            .proc_spawn(
+                ctx,
+                /* cmd */ String::from("sleep 10"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */
+                ProcSpawnOptions {
+                    timeout: Some(100),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        match rx.recv().await.unwrap() {
+            Response::ProcDone {
+                id,
+                success,
+                timed_out,
+                ..
+            } => {
+                assert_eq!(id, proc_id);
+                assert!(!success, "Process should have been killed");
+                assert!(timed_out, "Process should have been reported as timed out");
+            }
+            x => panic!("Unexpected response: {:?}", x),
+        }
+    }
+
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_spawn_should_fail_if_connection_already_has_maximum_processes() {
+        let (api, ctx_1, _rx_1) = setup_with_process_config(
+            1,
+            ProcessConfig {
+                max_per_connection: Some(1),
+            },
+        )
+        .await;
+        let (ctx_2, _rx_2) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+        let (ctx_3, _rx_3) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id.wrapping_add(1),
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+
+        let spawn = |ctx| {
+            api.proc_spawn(
+                ctx,
+                /* cmd */ String::from("sleep 10"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
+            )
+        };
+
+        // First process for the connection is allowed, but not a second one
+        spawn(ctx_1).await.unwrap();
+        spawn(ctx_2).await.unwrap_err();
+
+        // Other connections have their own maximum
+        spawn(ctx_3).await.unwrap();
+    }
+
+    // NOTE: Ignoring on windows because it's using WSL which wants a Linux path
+    //       with / but thinks it's on windows and is providing \
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_spawn_should_clear_process_from_state_when_killed() {
+        let (api, ctx_1, mut rx) = setup(1).await;
+        let (ctx_2, _rx) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+
+        let proc_id = api
+            .proc_spawn(
+                ctx_1,
+                /* cmd */
+                format!("{} {} 1", *SCRIPT_RUNNER, SLEEP_SH.to_str().unwrap()),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Send kill signal
+        api.proc_kill(ctx_2, proc_id).await.unwrap();
+
+        // Wait for the completion response to come in
+        match rx.recv().await.unwrap() {
+            Response::ProcDone { id, .. } => assert_eq!(
+                id, proc_id,
+                "Got {}, but expected {} as process id",
+                id, proc_id
+            ),
+            x => panic!("Unexpected response: {:?}", x),
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn proc_kill_should_fail_if_given_non_existent_process() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Send kill to a non-existent process
+        let _ = api.proc_kill(ctx, 0xDEADBEEF).await.unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn proc_signal_should_fail_if_given_non_existent_process() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Send signal to a non-existent process
+        let _ = api
+            .proc_signal(ctx, 0xDEADBEEF, Signal::Term)
+            .await
+            .unwrap_err();
+    }
+
+    // NOTE: Ignoring on windows as only killing is supported there and it lacks sleep
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_signal_should_send_signal_to_process() {
+        let (api, ctx_1, mut rx) = setup(1).await;
+        let (ctx_2, _rx) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+
+        let proc_id = api
+            .proc_spawn(
+                ctx_1,
+                /* cmd */ String::from("sleep 10"),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Send a termination signal rather than a kill
+        api.proc_signal(ctx_2, proc_id, Signal::Term).await.unwrap();
+
+        // Wait for the completion response to come in, which should be unsuccessful without an
+        // exit code as the process was terminated by the signal
+        match rx.recv().await.unwrap() {
+            Response::ProcDone {
+                id, success, code, ..
+            } => {
+                assert_eq!(id, proc_id);
+                assert!(!success, "Process should have been terminated");
+                assert_eq!(code, None);
+            }
+            x => panic!("Unexpected response: {:?}", x),
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn proc_stdin_should_fail_if_given_non_existent_process() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Send stdin to a non-existent process
+        let _ = api
+            .proc_stdin(ctx, 0xDEADBEEF, b"some input".to_vec())
+            .await
+            .unwrap_err();
+    }
+
+    // NOTE: Ignoring on windows because it's using WSL which wants a Linux path
+    //       with / but thinks it's on windows and is providing \
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_stdin_should_send_stdin_to_process() {
+        let (api, ctx_1, mut rx) = setup(1).await;
+        let (ctx_2, _rx) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+
+        // First, run a program that listens for stdin
+        let id = api
+            .proc_spawn(
+                ctx_1,
+                /* cmd */
+                format!(
+                    "{} {}",
+                    *SCRIPT_RUNNER,
+                    ECHO_STDIN_TO_STDOUT_SH.to_str().unwrap()
+                ),
+                Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Second, send stdin to the remote process
+        api.proc_stdin(ctx_2, id, b"hello world\n".to_vec())
+            .await
+            .unwrap();
+
+        // Third, check the async response of stdout to verify we got stdin
+        match rx.recv().await.unwrap() {
+            Response::ProcStdout { data, .. } => {
+                assert_eq!(data, b"hello world\n", "Mirrored data didn't match");
+            }
+            x => panic!("Unexpected response: {:?}", x),
+        }
+    }
+
+    // NOTE: Ignoring on windows because it's using WSL which wants a Linux path
+    //       with / but thinks it's on windows and is providing \
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_list_should_include_spawned_processes() {
+        let (api, ctx_1, _rx) = setup(1).await;
+        let (ctx_2, _rx) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+
+        let cmd = format!("{} {} 1", *SCRIPT_RUNNER, SLEEP_SH.to_str().unwrap());
+        let id = api
+            .proc_spawn(
+                ctx_1,
+                /* cmd */ cmd.clone(),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        let entries = api.proc_list(ctx_2).await.unwrap();
+        let entry = entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .expect("Missing spawned process");
+        assert_eq!(entry.cmd, cmd);
+        assert_eq!(entry.pty, None);
+        assert!(entry.started > 0, "Missing start time");
+    }
+
+    #[test(tokio::test)]
+    async fn proc_attach_should_fail_if_given_non_existent_process() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        // Attach to a non-existent process
+        let _ = api.proc_attach(ctx, 0xDEADBEEF).await.unwrap_err();
+    }
+
+    // NOTE: Ignoring on windows because it's using WSL which wants a Linux path
+    //       with / but thinks it's on windows and is providing \
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn proc_attach_should_send_output_missed_while_detached() {
+        let (api, ctx_1, rx_1) = setup(1).await;
+
+        // NOTE: Buffered output is flushed before attaching completes, so we need enough room
+        let (ctx_2, mut rx_2) = {
+            let (reply, rx) = make_reply(10);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+        let (ctx_3, _rx) = {
+            let (reply, rx) = make_reply(1);
+            let ctx = DistantCtx {
+                connection_id: ctx_1.connection_id,
+                reply,
+                local_data: Arc::clone(&ctx_1.local_data),
+            };
+            (ctx, rx)
+        };
+
+        // Simulate the spawning connection going away so nothing can be delivered to it
+        drop(rx_1);
+
+        let proc_id = api
+            .proc_spawn(
+                ctx_1,
+                /* cmd */
+                format!(
+                    "{} {} some stdout",
+                    *SCRIPT_RUNNER,
+                    ECHO_ARGS_TO_STDOUT_SH.to_str().unwrap()
+                ),
+                /* environment */ Environment::new(),
+                /* current_dir */ None,
+                /* pty */ None,
+                /* options */ ProcSpawnOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        // Give the process time to finish, verifying that it is still tracked as its completion
+        // has not been delivered anywhere
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let entries = api.proc_list(ctx_3).await.unwrap();
+        assert!(
+            entries.iter().any(|entry| entry.id == proc_id),
+            "Process no longer tracked"
+        );
+
+        // Attach and verify that we get both the stdout and completion of the process, in
+        // either order
+        api.proc_attach(ctx_2, proc_id).await.unwrap();
+
+        let mut got_stdout = false;
+        let mut got_done = false;
+        for _ in 0..2 {
+            match rx_2.recv().await.expect("Missing response") {
+                Response::ProcStdout { id, data, .. } => {
+                    assert_eq!(id, proc_id);
+                    assert_eq!(data, b"some stdout", "Got wrong stdout");
+                    got_stdout = true;
+                }
+                Response::ProcDone { id, success, .. } => {
+                    assert_eq!(id, proc_id);
+                    assert!(success, "Process should have completed successfully");
+                    got_done = true;
+                }
+                x => panic!("Unexpected response: {:?}", x),
+            }
+        }
+        assert!(got_stdout, "Missing stdout response");
+        assert!(got_done, "Missing done response");
+    }
+
+    #[test(tokio::test)]
+    async fn system_info_should_return_system_info_based_on_binary() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        let system_info = api.system_info(ctx).await.unwrap();
+        assert_eq!(
+            system_info,
+            SystemInfo {
+                family: std::env::consts::FAMILY.to_string(),
+                os: std::env::consts::OS.to_string(),
+                arch: std::env::consts::ARCH.to_string(),
+                current_dir: std::env::current_dir().unwrap_or_default(),
                 main_separator: std::path::MAIN_SEPARATOR,
                 username: whoami::username(),
                 shell: if cfg!(windows) {
@@ -2781,4 +4323,199 @@ mod tests {
             }
         );
     }
+
+    #[test(tokio::test)]
+    async fn environment_should_return_environment_variables_of_server() {
+        let (api, ctx, _rx) = setup(1).await;
+
+        let vars = api.environment(ctx).await.unwrap();
+        assert_eq!(vars, std::env::vars().collect::<Environment>());
+    }
+
+    fn make_replace_query(root: &Path, regex: &str) -> SearchQuery {
+        SearchQuery {
+            target: SearchQueryTarget::Contents,
+            condition: SearchQueryCondition::regex(regex),
+            paths: vec![root.to_path_buf()],
+            options: Default::default(),
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn replace_should_fail_if_query_does_not_target_contents() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+
+        let mut query = make_replace_query(temp.path(), "hello");
+        query.target = SearchQueryTarget::Path;
+
+        let err = api
+            .replace(ctx, query, String::from("bye"), false)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test(tokio::test)]
+    async fn replace_should_fail_if_query_has_and_or_not_conditions() {
+        let (api, ctx, _rx) = setup(1).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+
+        let mut query = make_replace_query(temp.path(), "hello");
+        query.condition = SearchQueryCondition::and([
+            SearchQueryCondition::contains("hello"),
+            SearchQueryCondition::not(SearchQueryCondition::contains("world")),
+        ]);
+
+        let err = api
+            .replace(ctx, query, String::from("bye"), false)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test(tokio::test)]
+    async fn replace_should_rewrite_matches_in_files_and_report_counts() {
+        let (api, ctx, mut rx) = setup(100).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file1")
+            .write_str("hello world\nhello\n")
+            .unwrap();
+        temp.child("file2").write_str("goodbye world\n").unwrap();
+        temp.child("file3").write_str("say hello").unwrap();
+
+        let (files, replacements) = api
+            .replace(
+                ctx,
+                make_replace_query(temp.path(), "hello"),
+                String::from("bye"),
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!((files, replacements), (2, 3));
+
+        temp.child("file1").assert("bye world\nbye\n");
+        temp.child("file2").assert("goodbye world\n");
+        temp.child("file3").assert("say bye");
+
+        let mut results = Vec::new();
+        while let Ok(res) = rx.try_recv() {
+            match res {
+                Response::Replaced {
+                    path,
+                    replacements,
+                    diff,
+                } => {
+                    assert_eq!(diff, None, "Unexpected diff when not a dry run");
+                    results.push((path.file_name().unwrap().to_owned(), replacements));
+                }
+                x => panic!("Unexpected response: {x:?}"),
+            }
+        }
+        assert_eq!(
+            results,
+            vec![("file1".into(), 2), ("file3".into(), 1)],
+            "Unexpected per-file results"
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn replace_should_support_capture_groups_in_replacement() {
+        let (api, ctx, _rx) = setup(100).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        temp.child("file")
+            .write_str("let a = 1;\nlet b = 2;\n")
+            .unwrap();
+
+        let (_, replacements) = api
+            .replace(
+                ctx,
+                make_replace_query(temp.path(), r"let (?P<name>\w+) = (\d+)"),
+                String::from("const ${name}: u32 = $2"),
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(replacements, 2);
+
+        temp.child("file")
+            .assert("const a: u32 = 1;\nconst b: u32 = 2;\n");
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn replace_should_keep_the_permissions_and_owner_of_files() {
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        // NOTE: Only root can give a file to another user, so we cannot check ownership otherwise
+        let is_root = unsafe { libc::geteuid() } == 0;
+
+        let (api, ctx, _rx) = setup(100).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.write_str("hello world\n").unwrap();
+        std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(0o640)).unwrap();
+        if is_root {
+            let path = std::ffi::CString::new(file.path().as_os_str().as_bytes()).unwrap();
+            assert_eq!(unsafe { libc::chown(path.as_ptr(), 1234, 5678) }, 0);
+        }
+
+        api.replace(
+            ctx,
+            make_replace_query(temp.path(), "hello"),
+            String::from("bye"),
+            false,
+        )
+        .await
+        .unwrap();
+
+        file.assert("bye world\n");
+        let metadata = std::fs::metadata(file.path()).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+        if is_root {
+            assert_eq!((metadata.uid(), metadata.gid()), (1234, 5678));
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn replace_should_report_diffs_without_changing_files_if_dry_run() {
+        let (api, ctx, mut rx) = setup(100).await;
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.write_str("one\ntwo\nthree\n").unwrap();
+
+        let (files, replacements) = api
+            .replace(
+                ctx,
+                make_replace_query(temp.path(), "two"),
+                String::from("2"),
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!((files, replacements), (1, 1));
+
+        file.assert("one\ntwo\nthree\n");
+
+        match rx.recv().await.unwrap() {
+            Response::Replaced {
+                path,
+                replacements,
+                diff,
+            } => {
+                assert_eq!(path, file.path());
+                assert_eq!(replacements, 1);
+                assert_eq!(
+                    diff.unwrap(),
+                    format!(
+                        "--- a/{path}\n+++ b/{path}\n@@ -1,3 +1,3 @@\n one\n-two\n+2\n three\n",
+                        path = file.path().display()
+                    )
+                );
+            }
+            x => panic!("Unexpected response: {x:?}"),
+        }
+    }
 }
@@ -0,0 +1,232 @@
+use std::io::{self, SeekFrom};
+use std::path::Path;
+
+use distant_core::protocol::HashAlgorithm;
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::constants::MAX_FILE_CHUNK_SIZE;
+
+/// Computes the digest of up to `len` bytes of the file at `path` starting at `offset`, hashing
+/// until the end of the file if `len` is none, and returns it as a lowercase hex string
+pub async fn hash_file(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    offset: u64,
+    len: Option<u64>,
+) -> io::Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(SeekFrom::Start(offset)).await?;
+    let mut file = file.take(len.unwrap_or(u64::MAX));
+
+    let mut hasher = Hasher::new(algorithm);
+    let mut buf = vec![0; MAX_FILE_CHUNK_SIZE];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// Computes the digest of each `block_size` chunk of the file at `path`, returning the length of
+/// the file alongside the digests as lowercase hex strings
+pub async fn hash_blocks(
+    path: &Path,
+    algorithm: HashAlgorithm,
+    block_size: u64,
+) -> io::Result<(u64, Vec<String>)> {
+    if block_size == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "block size must be greater than zero",
+        ));
+    }
+
+    let file = tokio::fs::File::open(path).await?;
+    let len = file.metadata().await?.len();
+
+    let mut file = tokio::io::BufReader::with_capacity(MAX_FILE_CHUNK_SIZE, file);
+    let mut checksums = Vec::new();
+    let mut buf = vec![0; MAX_FILE_CHUNK_SIZE];
+    loop {
+        // Feed the hasher until we have consumed an entire block or reached the end of the file
+        let mut hasher = Hasher::new(algorithm);
+        let mut block = (&mut file).take(block_size);
+        let mut block_len = 0;
+        loop {
+            let n = block.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            block_len += n;
+        }
+
+        if block_len == 0 {
+            break;
+        }
+
+        checksums.push(hasher.finish());
+    }
+
+    Ok((len, checksums))
+}
+
+/// Incremental hasher for any of the supported algorithms
+enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Xxh3(Box<Xxh3>),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::Blake3 => Self::Blake3(Box::new(blake3::Hasher::new())),
+            HashAlgorithm::Xxh3 => Self::Xxh3(Box::new(Xxh3::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(data),
+            Self::Blake3(hasher) => {
+                hasher.update(data);
+            }
+            Self::Xxh3(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Consumes the hasher, returning the digest as a lowercase hex string
+    fn finish(self) -> String {
+        match self {
+            Self::Sha256(hasher) => hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            Self::Blake3(hasher) => hasher.finalize().to_hex().to_string(),
+            Self::Xxh3(hasher) => format!("{:016x}", hasher.digest()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use test_log::test;
+
+    use super::*;
+
+    #[test(tokio::test)]
+    async fn hash_file_should_fail_if_file_missing() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.child("missing-file");
+
+        let _ = hash_file(path.path(), HashAlgorithm::Sha256, 0, None)
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn hash_file_should_support_sha256() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("abc").unwrap();
+
+        let digest = hash_file(file.path(), HashAlgorithm::Sha256, 0, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn hash_file_should_support_blake3() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.touch().unwrap();
+
+        let digest = hash_file(file.path(), HashAlgorithm::Blake3, 0, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            digest,
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn hash_file_should_support_xxh3() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.touch().unwrap();
+
+        let digest = hash_file(file.path(), HashAlgorithm::Xxh3, 0, None)
+            .await
+            .unwrap();
+        assert_eq!(digest, "2d06800538d394c2");
+    }
+
+    #[test(tokio::test)]
+    async fn hash_file_should_only_hash_bytes_within_range() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("some abc contents").unwrap();
+
+        let digest = hash_file(file.path(), HashAlgorithm::Sha256, 5, Some(3))
+            .await
+            .unwrap();
+        assert_eq!(
+            digest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn hash_blocks_should_fail_if_block_size_is_zero() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("abc").unwrap();
+
+        let _ = hash_blocks(file.path(), HashAlgorithm::Sha256, 0)
+            .await
+            .unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn hash_blocks_should_return_digest_of_each_block() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.write_str("abcabca").unwrap();
+
+        let (len, checksums) = hash_blocks(file.path(), HashAlgorithm::Xxh3, 3)
+            .await
+            .unwrap();
+        assert_eq!(len, 7);
+        assert_eq!(checksums.len(), 3);
+        assert_eq!(checksums[0], checksums[1]);
+        assert_ne!(checksums[1], checksums[2]);
+    }
+
+    #[test(tokio::test)]
+    async fn hash_blocks_should_return_no_digests_for_empty_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("test-file");
+        file.touch().unwrap();
+
+        let (len, checksums) = hash_blocks(file.path(), HashAlgorithm::Sha256, 3)
+            .await
+            .unwrap();
+        assert_eq!(len, 0);
+        assert!(checksums.is_empty());
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use distant_core::protocol::FileBlock;
+
+use super::replace::keep_owner;
+
+/// Writes `blocks` at their offsets within the file at `path`, creating it if it does not exist,
+/// and then resizes the file to `len`
+///
+/// The blocks are written to a copy of the file in the same directory with the same permissions
+/// and owner, which is renamed over the original once fully patched so that a failure partway
+/// through leaves the original untouched
+pub async fn patch_file(path: &Path, len: u64, blocks: Vec<FileBlock>) -> io::Result<()> {
+    // NOTE: Symlinks are resolved so that the file they point to is patched rather than replaced
+    let path = match tokio::fs::canonicalize(path).await {
+        Ok(path) => path,
+        Err(x) if x.kind() == io::ErrorKind::NotFound => path.to_path_buf(),
+        Err(x) => return Err(x),
+    };
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "file has no name"))?;
+    let tmp = parent.join(format!(
+        ".{}.{:x}.tmp",
+        name.to_string_lossy(),
+        rand::random::<u32>()
+    ));
+
+    let result = {
+        let path = path.clone();
+        let tmp = tmp.clone();
+        tokio::task::spawn_blocking(move || write_patched_copy(&path, &tmp, len, &blocks))
+            .await
+            .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?
+    };
+
+    let result = match result {
+        Ok(()) => tokio::fs::rename(&tmp, &path).await,
+        Err(x) => Err(x),
+    };
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
+    result
+}
+
+/// Copies the file at `path`, if it exists, to `tmp` and then writes `blocks` to the copy
+fn write_patched_copy(path: &Path, tmp: &Path, len: u64, blocks: &[FileBlock]) -> io::Result<()> {
+    let mut file = match std::fs::File::open(path) {
+        Ok(mut original) => {
+            let metadata = original.metadata()?;
+            let mut file = std::fs::File::create(tmp)?;
+            io::copy(&mut original, &mut file)?;
+            keep_owner(&file, &metadata)?;
+            file.set_permissions(metadata.permissions())?;
+            file
+        }
+        Err(x) if x.kind() == io::ErrorKind::NotFound => std::fs::File::create(tmp)?,
+        Err(x) => return Err(x),
+    };
+
+    for block in blocks {
+        file.seek(SeekFrom::Start(block.offset))?;
+        file.write_all(&block.data)?;
+    }
+
+    file.set_len(len)?;
+    file.sync_all()
+}
@@ -1,7 +1,8 @@
+use std::env;
 use std::future::Future;
 use std::pin::Pin;
 
-use distant_core::protocol::{ProcessId, PtySize};
+use distant_core::protocol::{ProcessId, PtySize, Signal};
 use tokio::io;
 use tokio::sync::mpsc;
 
@@ -12,11 +13,20 @@ mod simple;
 pub use simple::*;
 
 mod wait;
-pub use wait::{ExitStatus, WaitRx, WaitTx};
+pub use wait::{ExitStatus, ProcessPid, WaitRx, WaitTx};
 
 /// Alias to the return type of an async function (for use with traits)
 pub type FutureReturn<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
+/// Returns the default shell of the user running the server
+pub fn default_shell() -> String {
+    if cfg!(windows) {
+        env::var("ComSpec").unwrap_or_else(|_| String::from("cmd.exe"))
+    } else {
+        env::var("SHELL").unwrap_or_else(|_| String::from("/bin/sh"))
+    }
+}
+
 /// Represents a process on the remote server
 pub trait Process: ProcessKiller + ProcessPty {
     /// Represents the id of the process
@@ -100,6 +110,12 @@ pub trait ProcessKiller: Send + Sync {
     /// an error.
     fn kill(&mut self) -> FutureReturn<'_, io::Result<()>>;
 
+    /// Send a signal to the process, where [`Signal::Kill`] is the same as calling `kill`
+    ///
+    /// If the process is dead or the signal is not supported, this will return
+    /// an error.
+    fn signal(&mut self, signal: Signal) -> FutureReturn<'_, io::Result<()>>;
+
     /// Clone a process killer to support sending signals independently
     fn clone_killer(&self) -> Box<dyn ProcessKiller>;
 }
@@ -114,11 +130,72 @@ impl ProcessKiller for mpsc::Sender<()> {
         Box::pin(inner(self))
     }
 
+    fn signal(&mut self, signal: Signal) -> FutureReturn<'_, io::Result<()>> {
+        Box::pin(send_signal(self, ProcessPid::new(None), signal))
+    }
+
     fn clone_killer(&self) -> Box<dyn ProcessKiller> {
         Box::new(self.clone())
     }
 }
 
+/// Sends `signal` to the process with the os-level `pid`, where killing is done by sending a
+/// request through `kill_tx` to be consistent with [`ProcessKiller::kill`]
+async fn send_signal(
+    kill_tx: &mpsc::Sender<()>,
+    pid: ProcessPid,
+    signal: Signal,
+) -> io::Result<()> {
+    if signal == Signal::Kill {
+        return kill_tx
+            .send(())
+            .await
+            .map_err(|x| io::Error::new(io::ErrorKind::BrokenPipe, x));
+    }
+
+    pid.with_unreaped(|pid| match pid {
+        Some(pid) => send_os_signal(pid, signal),
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Process does not support {signal}"),
+        )),
+    })
+}
+
+#[cfg(unix)]
+fn send_os_signal(pid: u32, signal: Signal) -> io::Result<()> {
+    let signum = match signal {
+        Signal::Hup => libc::SIGHUP,
+        Signal::Int => libc::SIGINT,
+        Signal::Quit => libc::SIGQUIT,
+        Signal::Abrt => libc::SIGABRT,
+        Signal::Kill => libc::SIGKILL,
+        Signal::Alrm => libc::SIGALRM,
+        Signal::Term => libc::SIGTERM,
+        Signal::Usr1 => libc::SIGUSR1,
+        Signal::Usr2 => libc::SIGUSR2,
+        Signal::Cont => libc::SIGCONT,
+        Signal::Stop => libc::SIGSTOP,
+        Signal::Tstp => libc::SIGTSTP,
+        Signal::Winch => libc::SIGWINCH,
+    };
+
+    // SAFETY: kill does not access any memory and only reports failure through its result
+    if unsafe { libc::kill(pid as libc::pid_t, signum) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(windows)]
+fn send_os_signal(_pid: u32, signal: Signal) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!("{signal} is not supported on windows"),
+    ))
+}
+
 /// Represents an input channel of a process such as stdin
 pub trait InputChannel: Send + Sync {
     /// Sends input through channel, returning unit if succeeds or an error if fails
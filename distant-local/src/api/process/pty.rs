@@ -3,15 +3,15 @@ use std::io::{self, Read, Write};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex, Weak};
 
-use distant_core::protocol::Environment;
+use distant_core::protocol::{Environment, ProcSpawnOptions, Signal};
 use log::*;
 use portable_pty::{CommandBuilder, MasterPty, PtySize as PortablePtySize};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use super::{
-    wait, ExitStatus, FutureReturn, InputChannel, OutputChannel, Process, ProcessId, ProcessKiller,
-    ProcessPty, PtySize, WaitRx,
+    send_signal, wait, ExitStatus, FutureReturn, InputChannel, OutputChannel, Process, ProcessId,
+    ProcessKiller, ProcessPid, ProcessPty, PtySize, WaitRx,
 };
 use crate::constants::{MAX_PIPE_CHUNK_SIZE, READ_PAUSE_DURATION};
 
@@ -24,6 +24,7 @@ pub struct PtyProcess {
     stdin_task: Option<JoinHandle<()>>,
     stdout_task: Option<JoinHandle<io::Result<()>>>,
     kill_tx: mpsc::Sender<()>,
+    pid: ProcessPid,
     wait: WaitRx,
 }
 
@@ -35,6 +36,7 @@ impl PtyProcess {
         environment: Environment,
         current_dir: Option<PathBuf>,
         size: PtySize,
+        options: ProcSpawnOptions,
     ) -> io::Result<Self>
     where
         S: AsRef<OsStr>,
@@ -43,6 +45,15 @@ impl PtyProcess {
     {
         let id = rand::random();
 
+        // NOTE: portable_pty does not give us a way to run code in the child prior to exec, which
+        //       is needed to apply resource limits or switch users
+        if options.has_limits() || options.uid.is_some() || options.gid.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Resource limits, uid, and gid are not supported for pty processes",
+            ));
+        }
+
         // Establish our new pty for the given size
         let pty_system = portable_pty::native_pty_system();
         let pty_pair = pty_system
@@ -65,10 +76,21 @@ impl PtyProcess {
         for (key, value) in environment {
             cmd.env(key, value);
         }
+        #[cfg(unix)]
+        cmd.umask(options.umask.map(|mask| mask as libc::mode_t));
+        #[cfg(windows)]
+        if options.umask.is_some() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Umask is not supported on Windows",
+            ));
+        }
         let mut child = pty_slave
             .spawn_command(cmd)
             .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?;
 
+        let pid = ProcessPid::new(child.process_id());
+
         // NOTE: Need to drop slave to close out file handles and avoid deadlock when waiting on
         //       the child
         drop(pty_slave);
@@ -109,22 +131,14 @@ impl PtyProcess {
         let (kill_tx, mut kill_rx) = mpsc::channel(1);
         let (mut wait_tx, wait_rx) = wait::channel();
 
+        let wait_pid = pid.clone();
         tokio::spawn(async move {
             loop {
-                match (child.try_wait(), kill_rx.try_recv()) {
+                match (try_wait_child(&mut child, &wait_pid), kill_rx.try_recv()) {
                     (Ok(Some(status)), _) => {
-                        trace!(
-                            "Pty process {id} has exited: success = {}",
-                            status.success()
-                        );
-
-                        if let Err(x) = wait_tx
-                            .send(ExitStatus {
-                                success: status.success(),
-                                code: None,
-                            })
-                            .await
-                        {
+                        trace!("Pty process {id} has exited: success = {}", status.success);
+
+                        if let Err(x) = wait_tx.send(status).await {
                             error!("Pty process {id} exit status lost: {x}");
                         }
 
@@ -164,6 +178,7 @@ impl PtyProcess {
             stdin_task: Some(stdin_task),
             stdout_task: Some(stdout_task),
             kill_tx,
+            pid,
             wait: wait_rx,
         })
     }
@@ -177,6 +192,33 @@ impl PtyProcess {
     }
 }
 
+/// Checks if the child has exited, collecting the resources that it consumed where supported
+///
+/// NOTE: On unix, portable_pty spawns a standard library child, which is never waited on except
+///       through us and is dropped without being touched once reaped here, so nothing else can
+///       wait on the pid after it is freed.
+fn try_wait_child(
+    child: &mut Box<dyn portable_pty::Child + Send + Sync>,
+    pid: &ProcessPid,
+) -> io::Result<Option<ExitStatus>> {
+    #[cfg(unix)]
+    if pid.get().is_some() {
+        return wait::try_wait_pid(pid);
+    }
+
+    #[cfg(windows)]
+    let _ = pid;
+
+    Ok(child.try_wait()?.map(|status| ExitStatus {
+        success: status.success(),
+        code: None,
+        limit: None,
+        signal: None,
+        core_dumped: false,
+        usage: None,
+    }))
+}
+
 impl Process for PtyProcess {
     fn id(&self) -> ProcessId {
         self.id
@@ -253,18 +295,28 @@ impl ProcessKiller for PtyProcess {
         Box::pin(inner(self))
     }
 
+    fn signal(&mut self, signal: Signal) -> FutureReturn<'_, io::Result<()>> {
+        Box::pin(send_signal(&self.kill_tx, self.pid.clone(), signal))
+    }
+
     fn clone_killer(&self) -> Box<dyn ProcessKiller> {
-        Box::new(self.kill_tx.clone())
+        Box::new(PtyProcessKiller {
+            kill_tx: self.kill_tx.clone(),
+            pid: self.pid.clone(),
+        })
     }
 }
 
 #[derive(Clone)]
-pub struct PtyProcessKiller(mpsc::Sender<()>);
+pub struct PtyProcessKiller {
+    kill_tx: mpsc::Sender<()>,
+    pid: ProcessPid,
+}
 
 impl ProcessKiller for PtyProcessKiller {
     fn kill(&mut self) -> FutureReturn<'_, io::Result<()>> {
         async fn inner(this: &mut PtyProcessKiller) -> io::Result<()> {
-            this.0
+            this.kill_tx
                 .send(())
                 .await
                 .map_err(|x| io::Error::new(io::ErrorKind::BrokenPipe, x))
@@ -272,6 +324,10 @@ impl ProcessKiller for PtyProcessKiller {
         Box::pin(inner(self))
     }
 
+    fn signal(&mut self, signal: Signal) -> FutureReturn<'_, io::Result<()>> {
+        Box::pin(send_signal(&self.kill_tx, self.pid.clone(), signal))
+    }
+
     fn clone_killer(&self) -> Box<dyn ProcessKiller> {
         Box::new(self.clone())
     }
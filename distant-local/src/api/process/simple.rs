@@ -2,16 +2,16 @@ use std::ffi::OsStr;
 use std::path::PathBuf;
 use std::process::Stdio;
 
-use distant_core::protocol::Environment;
+use distant_core::protocol::{Environment, ProcSpawnOptions, Signal};
 use log::*;
 use tokio::io;
-use tokio::process::Command;
+use tokio::process::{ChildStderr, ChildStdin, ChildStdout};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 
 use super::{
-    wait, ExitStatus, FutureReturn, InputChannel, NoProcessPty, OutputChannel, Process, ProcessId,
-    ProcessKiller, WaitRx,
+    send_signal, wait, ExitStatus, FutureReturn, InputChannel, NoProcessPty, OutputChannel,
+    Process, ProcessId, ProcessKiller, ProcessPid, WaitRx,
 };
 
 mod tasks;
@@ -26,6 +26,7 @@ pub struct SimpleProcess {
     stdout_task: Option<JoinHandle<io::Result<()>>>,
     stderr_task: Option<JoinHandle<io::Result<()>>>,
     kill_tx: mpsc::Sender<()>,
+    pid: ProcessPid,
     wait: WaitRx,
 }
 
@@ -36,6 +37,7 @@ impl SimpleProcess {
         args: I,
         environment: Environment,
         current_dir: Option<PathBuf>,
+        options: ProcSpawnOptions,
     ) -> io::Result<Self>
     where
         S: AsRef<OsStr>,
@@ -43,70 +45,86 @@ impl SimpleProcess {
         S2: AsRef<OsStr>,
     {
         let id = rand::random();
+
+        // When merging stderr into stdout, both are given the write end of a single pipe so that
+        // the output is read in the same order that the process wrote it
+        let mut merged = None;
         let mut child = {
-            let mut command = Command::new(program);
+            let mut command = std::process::Command::new(program);
 
             if let Some(path) = current_dir {
                 command.current_dir(path);
             }
 
-            command
-                .envs(environment)
-                .args(args)
-                .stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()?
+            apply_options(&mut command, options)?;
+
+            if options.merge_stderr {
+                let (reader, writer) = os_pipe::pipe()?;
+                command
+                    .stdout(Stdio::from(writer.try_clone()?))
+                    .stderr(Stdio::from(writer));
+                merged = Some(reader);
+            } else {
+                command.stdout(Stdio::piped()).stderr(Stdio::piped());
+            }
+
+            command.envs(environment).args(args).stdin(Stdio::piped());
+            spawn_child(command)?
         };
 
-        let stdout = child.stdout.take().unwrap();
-        let (stdout_task, stdout_ch) = tasks::spawn_read_task(stdout, 1);
+        let pid = ProcessPid::new(child_id(&child));
+        let (stdout_task, stdout_ch, stderr_task, stderr_ch) = match merged {
+            Some(reader) => {
+                let (stdout_task, stdout_ch) =
+                    tasks::spawn_read_task(tokio::fs::File::from_std(pipe_to_file(reader)), 1);
+                (stdout_task, stdout_ch, None, None)
+            }
+            None => {
+                let stdout = take_stdout(&mut child)?.unwrap();
+                let (stdout_task, stdout_ch) = tasks::spawn_read_task(stdout, 1);
 
-        let stderr = child.stderr.take().unwrap();
-        let (stderr_task, stderr_ch) = tasks::spawn_read_task(stderr, 1);
+                let stderr = take_stderr(&mut child)?.unwrap();
+                let (stderr_task, stderr_ch) = tasks::spawn_read_task(stderr, 1);
+                (stdout_task, stdout_ch, Some(stderr_task), Some(stderr_ch))
+            }
+        };
 
-        let stdin = child.stdin.take().unwrap();
+        let stdin = take_stdin(&mut child)?.unwrap();
         let (stdin_task, stdin_ch) = tasks::spawn_write_task(stdin, 1);
 
         let (kill_tx, mut kill_rx) = mpsc::channel(1);
         let (mut wait_tx, wait_rx) = wait::channel();
 
+        let wait_pid = pid.clone();
         tokio::spawn(async move {
-            tokio::select! {
+            let status = tokio::select! {
                 _ = kill_rx.recv() => {
-                    trace!("Pty process {id} received kill request");
-                    let status = match child.kill().await {
-                        Ok(_) => ExitStatus::killed(),
-                        Err(x) => ExitStatus::from(x),
-                    };
-
-                    trace!(
-                        "Simple process {id} has exited: success = {}, code = {}",
-                        status.success,
-                        status.code.map(|code| code.to_string())
-                            .unwrap_or_else(|| "<terminated>".to_string()),
-                    );
-
-                    if let Err(x) = wait_tx.send(status).await {
-                        error!("Simple process {id} exit status lost: {x}");
-                    }
-                }
-                status = child.wait() => {
-                    match &status {
-                        Ok(status) => trace!(
-                            "Simple process {id} has exited: success = {}, code = {}",
-                            status.success(),
-                            status.code()
-                                .map(|code| code.to_string())
-                                .unwrap_or_else(|| "<terminated>".to_string()),
-                        ),
-                        Err(_) => trace!("Simple process {id} failed to wait"),
-                    }
+                    trace!("Simple process {id} received kill request");
 
-                    if let Err(x) = wait_tx.send(status).await {
-                        error!("Simple process {id} exit status lost: {x}");
+                    // Continue waiting after the kill so the signal and usage are still reported
+                    match start_kill(&mut child) {
+                        Ok(_) => wait_child(&mut child, &wait_pid).await,
+                        Err(x) => Err(x),
                     }
                 }
+                status = wait_child(&mut child, &wait_pid) => status,
+            };
+
+            match &status {
+                Ok(status) => trace!(
+                    "Simple process {id} has exited: success = {}, code = {}",
+                    status.success,
+                    status
+                        .code
+                        .map(|code| code.to_string())
+                        .unwrap_or_else(|| "<terminated>".to_string()),
+                ),
+                Err(_) => trace!("Simple process {id} failed to wait"),
+            }
+
+            let status = status.unwrap_or_else(ExitStatus::from);
+            if let Err(x) = wait_tx.send(status).await {
+                error!("Simple process {id} exit status lost: {x}");
             }
         });
 
@@ -114,16 +132,204 @@ impl SimpleProcess {
             id,
             stdin: Some(Box::new(stdin_ch)),
             stdout: Some(Box::new(stdout_ch)),
-            stderr: Some(Box::new(stderr_ch)),
+            stderr: stderr_ch.map(|ch| Box::new(ch) as Box<dyn OutputChannel>),
             stdin_task: Some(stdin_task),
             stdout_task: Some(stdout_task),
-            stderr_task: Some(stderr_task),
+            stderr_task,
             kill_tx,
+            pid,
             wait: wait_rx,
         })
     }
 }
 
+/// Child process that is spawned and waited on by us
+///
+/// On unix, this is a standard library child rather than one from tokio as we reap the child
+/// ourselves to collect the resources that it consumed. Tokio would otherwise hand its child to
+/// an orphan reaper when dropped, which could later reap an unrelated process reusing the pid.
+#[cfg(unix)]
+type Child = std::process::Child;
+
+#[cfg(windows)]
+type Child = tokio::process::Child;
+
+#[cfg(unix)]
+fn spawn_child(mut command: std::process::Command) -> io::Result<Child> {
+    command.spawn()
+}
+
+#[cfg(windows)]
+fn spawn_child(command: std::process::Command) -> io::Result<Child> {
+    tokio::process::Command::from(command).spawn()
+}
+
+#[cfg(unix)]
+fn child_id(child: &Child) -> Option<u32> {
+    Some(child.id())
+}
+
+#[cfg(windows)]
+fn child_id(child: &Child) -> Option<u32> {
+    child.id()
+}
+
+#[cfg(unix)]
+fn take_stdin(child: &mut Child) -> io::Result<Option<ChildStdin>> {
+    child.stdin.take().map(ChildStdin::from_std).transpose()
+}
+
+#[cfg(windows)]
+fn take_stdin(child: &mut Child) -> io::Result<Option<ChildStdin>> {
+    Ok(child.stdin.take())
+}
+
+#[cfg(unix)]
+fn take_stdout(child: &mut Child) -> io::Result<Option<ChildStdout>> {
+    child.stdout.take().map(ChildStdout::from_std).transpose()
+}
+
+#[cfg(windows)]
+fn take_stdout(child: &mut Child) -> io::Result<Option<ChildStdout>> {
+    Ok(child.stdout.take())
+}
+
+#[cfg(unix)]
+fn take_stderr(child: &mut Child) -> io::Result<Option<ChildStderr>> {
+    child.stderr.take().map(ChildStderr::from_std).transpose()
+}
+
+#[cfg(windows)]
+fn take_stderr(child: &mut Child) -> io::Result<Option<ChildStderr>> {
+    Ok(child.stderr.take())
+}
+
+/// Sends a kill signal to the child without waiting for it to exit
+///
+/// NOTE: This is only called prior to the child being reaped by [`wait_child`], so the pid still
+///       belongs to the child.
+#[cfg(unix)]
+fn start_kill(child: &mut Child) -> io::Result<()> {
+    child.kill()
+}
+
+#[cfg(windows)]
+fn start_kill(child: &mut Child) -> io::Result<()> {
+    child.start_kill()
+}
+
+/// Waits for the child to exit, collecting the resources that it consumed where supported
+#[cfg(unix)]
+async fn wait_child(_child: &mut Child, pid: &ProcessPid) -> io::Result<ExitStatus> {
+    wait::wait_pid(pid).await
+}
+
+#[cfg(windows)]
+async fn wait_child(child: &mut Child, _pid: &ProcessPid) -> io::Result<ExitStatus> {
+    child.wait().await.map(ExitStatus::from)
+}
+
+/// Converts the read end of a pipe into a file so it can be read asynchronously
+#[cfg(unix)]
+fn pipe_to_file(reader: os_pipe::PipeReader) -> std::fs::File {
+    std::fs::File::from(std::os::fd::OwnedFd::from(reader))
+}
+
+#[cfg(windows)]
+fn pipe_to_file(reader: os_pipe::PipeReader) -> std::fs::File {
+    std::fs::File::from(std::os::windows::io::OwnedHandle::from(reader))
+}
+
+/// Applies `options` to `command` such that they take effect in the child process before the
+/// program is executed
+#[cfg(unix)]
+fn apply_options(command: &mut std::process::Command, options: ProcSpawnOptions) -> io::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    if let Some(uid) = options.uid {
+        command.uid(uid);
+    }
+
+    if let Some(gid) = options.gid {
+        command.gid(gid);
+    }
+
+    if !options.has_limits() && options.umask.is_none() {
+        return Ok(());
+    }
+
+    // SAFETY: The closure runs in the child between fork and exec, so it only makes use of
+    //         getrlimit, setrlimit, and umask, which are all async-signal-safe
+    unsafe {
+        command.pre_exec(move || {
+            let limits = [
+                (libc::RLIMIT_CPU, options.max_cpu_time),
+                (libc::RLIMIT_AS, options.max_address_space),
+                (libc::RLIMIT_NOFILE, options.max_open_files),
+                (libc::RLIMIT_NPROC, options.max_processes),
+            ];
+
+            for (resource, limit) in limits {
+                let Some(limit) = limit else {
+                    continue;
+                };
+
+                let mut rlimit = libc::rlimit {
+                    rlim_cur: 0,
+                    rlim_max: 0,
+                };
+                if libc::getrlimit(resource, &mut rlimit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+
+                // NOTE: Exceeding the soft cpu limit sends SIGXCPU, which we report as the limit
+                //       being hit, whereas the hard limit sends SIGKILL; so, we leave a second
+                //       between the two. Limits are never raised above the current hard limit.
+                let limit = limit as libc::rlim_t;
+                let hard = if resource == libc::RLIMIT_CPU {
+                    limit.saturating_add(1)
+                } else {
+                    limit
+                };
+                rlimit.rlim_max = hard.min(rlimit.rlim_max);
+                rlimit.rlim_cur = limit.min(rlimit.rlim_max);
+                if libc::setrlimit(resource, &rlimit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            if let Some(mask) = options.umask {
+                libc::umask(mask as libc::mode_t);
+            }
+
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+fn apply_options(
+    _command: &mut std::process::Command,
+    options: ProcSpawnOptions,
+) -> io::Result<()> {
+    let options = ProcSpawnOptions {
+        timeout: None,
+        merge_stderr: false,
+        ..options
+    };
+
+    if options.is_default() {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Resource limits, uid, gid, and umask are not supported on Windows",
+        ))
+    }
+}
+
 impl Process for SimpleProcess {
     fn id(&self) -> ProcessId {
         self.id
@@ -201,18 +407,28 @@ impl ProcessKiller for SimpleProcess {
         Box::pin(inner(self))
     }
 
+    fn signal(&mut self, signal: Signal) -> FutureReturn<'_, io::Result<()>> {
+        Box::pin(send_signal(&self.kill_tx, self.pid.clone(), signal))
+    }
+
     fn clone_killer(&self) -> Box<dyn ProcessKiller> {
-        Box::new(self.kill_tx.clone())
+        Box::new(SimpleProcessKiller {
+            kill_tx: self.kill_tx.clone(),
+            pid: self.pid.clone(),
+        })
     }
 }
 
 #[derive(Clone)]
-pub struct SimpleProcessKiller(mpsc::Sender<()>);
+pub struct SimpleProcessKiller {
+    kill_tx: mpsc::Sender<()>,
+    pid: ProcessPid,
+}
 
 impl ProcessKiller for SimpleProcessKiller {
     fn kill(&mut self) -> FutureReturn<'_, io::Result<()>> {
         async fn inner(this: &mut SimpleProcessKiller) -> io::Result<()> {
-            this.0
+            this.kill_tx
                 .send(())
                 .await
                 .map_err(|x| io::Error::new(io::ErrorKind::BrokenPipe, x))
@@ -220,6 +436,10 @@ impl ProcessKiller for SimpleProcessKiller {
         Box::pin(inner(self))
     }
 
+    fn signal(&mut self, signal: Signal) -> FutureReturn<'_, io::Result<()>> {
+        Box::pin(send_signal(&self.kill_tx, self.pid.clone(), signal))
+    }
+
     fn clone_killer(&self) -> Box<dyn ProcessKiller> {
         Box::new(self.clone())
     }
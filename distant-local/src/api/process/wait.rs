@@ -1,3 +1,6 @@
+use std::sync::{Arc, Mutex};
+
+use distant_core::protocol::{ProcessLimit, ProcessUsage};
 use tokio::io;
 use tokio::sync::mpsc;
 
@@ -6,6 +9,10 @@ use tokio::sync::mpsc;
 pub struct ExitStatus {
     pub success: bool,
     pub code: Option<i32>,
+    pub limit: Option<ProcessLimit>,
+    pub signal: Option<i32>,
+    pub core_dumped: bool,
+    pub usage: Option<ProcessUsage>,
 }
 
 impl ExitStatus {
@@ -14,6 +21,10 @@ impl ExitStatus {
         Self {
             success: false,
             code: None,
+            limit: None,
+            signal: None,
+            core_dumped: false,
+            usage: None,
         }
     }
 }
@@ -36,19 +47,176 @@ impl From<io::Error> for ExitStatus {
         Self {
             success: false,
             code: err.raw_os_error(),
+            limit: None,
+            signal: None,
+            core_dumped: false,
+            usage: None,
         }
     }
 }
 
 impl From<std::process::ExitStatus> for ExitStatus {
     fn from(status: std::process::ExitStatus) -> Self {
+        let (signal, core_dumped) = termination_signal(&status);
         Self {
             success: status.success(),
             code: status.code(),
+            limit: limit_hit(&status),
+            signal,
+            core_dumped,
+            usage: None,
+        }
+    }
+}
+
+/// Returns the signal that terminated a process, if any, and whether it dumped core
+#[cfg(unix)]
+fn termination_signal(status: &std::process::ExitStatus) -> (Option<i32>, bool) {
+    use std::os::unix::process::ExitStatusExt;
+    (status.signal(), status.core_dumped())
+}
+
+#[cfg(windows)]
+fn termination_signal(_status: &std::process::ExitStatus) -> (Option<i32>, bool) {
+    (None, false)
+}
+
+/// Determines the resource limit that terminated a process, if any, from the signal that the
+/// operating system sent when the limit was exceeded
+#[cfg(unix)]
+fn limit_hit(status: &std::process::ExitStatus) -> Option<ProcessLimit> {
+    use std::os::unix::process::ExitStatusExt;
+    match status.signal() {
+        Some(libc::SIGXCPU) => Some(ProcessLimit::CpuTime),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn limit_hit(_status: &std::process::ExitStatus) -> Option<ProcessLimit> {
+    None
+}
+
+/// Os-level pid of a process alongside whether the process has been reaped, shared between the
+/// task waiting on the process and anything sending it signals
+///
+/// NOTE: Once a process is reaped, its pid can be reused by some other process. Reaping and
+///       signaling both hold the same lock, so a signal is never sent to a reaped pid.
+#[derive(Clone, Debug)]
+pub struct ProcessPid {
+    pid: Option<u32>,
+    reaped: Arc<Mutex<bool>>,
+}
+
+impl ProcessPid {
+    /// Creates a new pid for a process that has not been reaped yet
+    pub fn new(pid: Option<u32>) -> Self {
+        Self {
+            pid,
+            reaped: Arc::new(Mutex::new(false)),
+        }
+    }
+
+    /// Returns the os-level pid, if known, regardless of whether the process has been reaped
+    pub fn get(&self) -> Option<u32> {
+        self.pid
+    }
+
+    /// Invokes `f` with the os-level pid while preventing the process from being reaped, failing
+    /// if the process has already been reaped
+    pub fn with_unreaped<T>(&self, f: impl FnOnce(Option<u32>) -> io::Result<T>) -> io::Result<T> {
+        let reaped = self.reaped.lock().unwrap();
+        if *reaped {
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "Process has already exited",
+            ));
+        }
+
+        f(self.pid)
+    }
+}
+
+/// Checks if the process with `pid` has exited without blocking, reaping it if so and returning
+/// its exit status alongside the resources that it consumed
+#[cfg(unix)]
+pub fn try_wait_pid(pid: &ProcessPid) -> io::Result<Option<ExitStatus>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    // NOTE: Lock is held while reaping so that no signal can be sent once the pid is freed
+    let mut reaped = pid.reaped.lock().unwrap();
+    let pid = match pid.pid {
+        Some(pid) if !*reaped => pid,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "Process cannot be waited on",
+            ))
+        }
+    };
+
+    let mut status = 0;
+
+    // SAFETY: rusage is plain old data that wait4 fills in when the process is reaped
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    let res = unsafe { libc::wait4(pid as libc::pid_t, &mut status, libc::WNOHANG, &mut rusage) };
+
+    match res {
+        0 => Ok(None),
+        -1 => Err(io::Error::last_os_error()),
+        _ => {
+            *reaped = true;
+            let mut exit_status = ExitStatus::from(std::process::ExitStatus::from_raw(status));
+            exit_status.usage = Some(usage_of(&rusage));
+            Ok(Some(exit_status))
         }
     }
 }
 
+/// Waits for the process with `pid` to exit, reaping it and returning its exit status alongside
+/// the resources that it consumed
+///
+/// NOTE: The process must not be waited on by anything else, including the handle that spawned
+///       it, as only one waiter can reap a process. This rules out handles from tokio, whose
+///       orphan reaper would otherwise wait on the pid after it was reaped and possibly reused.
+#[cfg(unix)]
+pub async fn wait_pid(pid: &ProcessPid) -> io::Result<ExitStatus> {
+    use std::time::Duration;
+
+    use tokio::signal::unix::{signal, SignalKind};
+
+    // Check again whenever any child exits, falling back to polling in case the signal is missed
+    let mut sigchld = signal(SignalKind::child())?;
+    loop {
+        if let Some(status) = try_wait_pid(pid)? {
+            return Ok(status);
+        }
+
+        tokio::select! {
+            _ = sigchld.recv() => {}
+            _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+        }
+    }
+}
+
+/// Converts the usage reported by the operating system into microseconds and bytes
+#[cfg(unix)]
+fn usage_of(rusage: &libc::rusage) -> ProcessUsage {
+    let micros = |time: libc::timeval| time.tv_sec as u64 * 1_000_000 + time.tv_usec as u64;
+
+    // NOTE: Max rss is reported in bytes on macOS, but kilobytes everywhere else
+    let max_rss = rusage.ru_maxrss as u64;
+    ProcessUsage {
+        user_time: micros(rusage.ru_utime),
+        system_time: micros(rusage.ru_stime),
+        max_rss: if cfg!(target_os = "macos") {
+            max_rss
+        } else {
+            max_rss * 1024
+        },
+    }
+}
+
 /// Creates a new channel for when the exit status will be ready
 pub fn channel() -> (WaitTx, WaitRx) {
     let (tx, rx) = mpsc::channel(1);
@@ -135,3 +303,34 @@ impl WaitRx {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn process_pid_should_refuse_to_be_used_once_reaped() {
+        // NOTE: Child is reaped through its pid rather than the handle
+        #[allow(clippy::zombie_processes)]
+        let child = std::process::Command::new("true").spawn().unwrap();
+        let pid = ProcessPid::new(Some(child.id()));
+
+        // Before reaping, the pid is still available to send signals to
+        pid.with_unreaped(|x| {
+            assert_eq!(x, Some(child.id()));
+            Ok(())
+        })
+        .unwrap();
+
+        let status = wait_pid(&pid).await.unwrap();
+        assert!(status.success, "Unexpected status: {status:?}");
+
+        // After reaping, the pid could belong to another process and must not be used
+        let err = pid.with_unreaped(|_| Ok(())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::BrokenPipe, "{err}");
+        assert!(try_wait_pid(&pid).is_err(), "Reaped twice");
+    }
+}
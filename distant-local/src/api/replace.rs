@@ -0,0 +1,356 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use distant_core::protocol::{SearchQuery, SearchQueryTarget};
+use grep::matcher::{Captures, Matcher};
+use grep::regex::RegexMatcher;
+use tokio::io::AsyncReadExt;
+
+use super::state::new_search_matcher_builder;
+
+/// Rewrites matches of a query's condition within the contents of files
+pub struct Replacer {
+    matcher: RegexMatcher,
+    replacement: Vec<u8>,
+}
+
+impl Replacer {
+    /// Creates a new replacer using the condition of `query`, which must target the contents of
+    /// files, and the `replacement` template, which can reference capture groups via `$1`,
+    /// `${name}`, and so on
+    ///
+    /// The condition is matched exactly as it is by a search with the same query, meaning that
+    /// the files reported by the search are the ones that get rewritten
+    pub fn new(query: &SearchQuery, replacement: impl Into<String>) -> io::Result<Self> {
+        if query.target != SearchQueryTarget::Contents {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "replace only supports queries targeting contents",
+            ));
+        }
+
+        // NOTE: The parts of an And or Not condition that would be replaced are ambiguous, as
+        //       their negated conditions match nothing and their other conditions match anywhere
+        //       in a file that meets the condition as a whole
+        if query.condition.is_structural() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "replace does not support queries with and or not conditions",
+            ));
+        }
+
+        let matcher = new_search_matcher_builder(&query.options)
+            .build(&query.condition.to_regex_string())
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
+
+        Ok(Self {
+            matcher,
+            replacement: replacement.into().into_bytes(),
+        })
+    }
+
+    /// Replaces all matches within `data`, returning the new data alongside the total number of
+    /// replacements made
+    ///
+    /// NOTE: Without multiline matching, the matcher is built with a line terminator, so no
+    ///       match can span lines.
+    pub fn replace(&self, data: &[u8]) -> io::Result<(Vec<u8>, u64)> {
+        let mut cnt = 0;
+        let mut out = Vec::with_capacity(data.len());
+        let mut caps = self
+            .matcher
+            .new_captures()
+            .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?;
+
+        self.matcher
+            .replace_with_captures(data, &mut caps, &mut out, |caps, dst| {
+                cnt += 1;
+                caps.interpolate(
+                    |name| self.matcher.capture_index(name),
+                    data,
+                    &self.replacement,
+                    dst,
+                );
+                true
+            })
+            .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?;
+
+        Ok((out, cnt))
+    }
+
+    /// Replaces all matches within the contents of the file at `path` without modifying it,
+    /// returning `None` if there is nothing to replace
+    pub async fn rewrite_file(&self, path: &Path) -> io::Result<Option<Rewrite>> {
+        // NOTE: The version is taken before reading so that any change made while reading is
+        //       caught as a conflict rather than missed
+        let mut file = tokio::fs::File::open(path).await?;
+        let version = FileVersion::of(&file.metadata().await?);
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).await?;
+
+        let (new_data, replacements) = self.replace(&data)?;
+
+        if replacements == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(Rewrite {
+            path: path.to_path_buf(),
+            old_data: data,
+            new_data,
+            replacements,
+            version,
+        }))
+    }
+}
+
+/// Length and modification time of a file, used to detect the file changing after it was read
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct FileVersion {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileVersion {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        }
+    }
+
+    /// Fails with a conflict if the file at `path` is no longer at this version
+    async fn check(&self, path: &Path) -> io::Result<()> {
+        let current = Self::of(&tokio::fs::metadata(path).await?);
+        if current != *self {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Conflict as {path:?} was modified after being read"),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents the new contents of a file whose matches were replaced
+pub struct Rewrite {
+    pub path: PathBuf,
+    pub old_data: Vec<u8>,
+    pub new_data: Vec<u8>,
+    pub replacements: u64,
+    version: FileVersion,
+}
+
+impl Rewrite {
+    /// Produces a unified diff between the old and new contents of the file
+    pub fn diff(&self) -> String {
+        let old = String::from_utf8_lossy(&self.old_data);
+        let new = String::from_utf8_lossy(&self.new_data);
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+        let path = self.path.display();
+        let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+
+        // NOTE: Skip the file headers produced by difflib as we supply our own above
+        for line in difflib::unified_diff(&old_lines, &new_lines, "", "", "", "", 3)
+            .into_iter()
+            .skip(2)
+        {
+            diff.push_str(&line);
+            if !line.ends_with('\n') {
+                diff.push('\n');
+            }
+        }
+
+        diff
+    }
+
+    /// Writes the new contents to a temporary file in the same directory as the original with
+    /// the same permissions and owner, which can then be renamed over the original
+    pub async fn stage(self) -> io::Result<StagedRewrite> {
+        let path = tokio::fs::canonicalize(&self.path).await?;
+        let metadata = tokio::fs::metadata(&path).await?;
+        self.version.check(&path).await?;
+
+        let parent = path.parent().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "file has no parent directory")
+        })?;
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "file has no name"))?;
+        let tmp = parent.join(format!(
+            ".{}.{:x}.tmp",
+            name.to_string_lossy(),
+            rand::random::<u32>()
+        ));
+
+        let result = {
+            let tmp = tmp.clone();
+            let data = self.new_data;
+            tokio::task::spawn_blocking(move || {
+                let mut file = std::fs::File::create(&tmp)?;
+                file.write_all(&data)?;
+                file.sync_all()?;
+                keep_owner(&file, &metadata)?;
+                file.set_permissions(metadata.permissions())
+            })
+            .await
+            .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?
+        };
+
+        match result {
+            Ok(()) => Ok(StagedRewrite {
+                path,
+                tmp,
+                replacements: self.replacements,
+                version: self.version,
+            }),
+            Err(x) => {
+                let _ = tokio::fs::remove_file(&tmp).await;
+                Err(x)
+            }
+        }
+    }
+}
+
+/// Represents the new contents of a file written to a temporary file alongside the original
+pub struct StagedRewrite {
+    pub path: PathBuf,
+    tmp: PathBuf,
+    pub replacements: u64,
+    version: FileVersion,
+}
+
+impl StagedRewrite {
+    /// Fails with a conflict if the original was modified since it was read, in which case
+    /// renaming over it would lose those modifications
+    pub async fn check(&self) -> io::Result<()> {
+        self.version.check(&self.path).await
+    }
+
+    /// Renames the temporary file over the original if it has not been modified since it was
+    /// read, removing the temporary file if either fails
+    pub async fn commit(&self) -> io::Result<()> {
+        let result = match self.check().await {
+            Ok(()) => tokio::fs::rename(&self.tmp, &self.path).await,
+            Err(x) => Err(x),
+        };
+        if result.is_err() {
+            self.discard().await;
+        }
+        result
+    }
+
+    /// Removes the temporary file, leaving the original untouched
+    pub async fn discard(&self) {
+        let _ = tokio::fs::remove_file(&self.tmp).await;
+    }
+}
+
+/// Changes the owner of `file` to match `metadata` of the original file, failing if that is not
+/// permitted as the rewritten file would otherwise change hands
+#[cfg(unix)]
+pub(super) fn keep_owner(file: &std::fs::File, metadata: &std::fs::Metadata) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::io::AsRawFd;
+
+    let current = file.metadata()?;
+    if current.uid() == metadata.uid() && current.gid() == metadata.gid() {
+        return Ok(());
+    }
+
+    // SAFETY: The file descriptor is valid for as long as `file` is borrowed
+    if unsafe { libc::fchown(file.as_raw_fd(), metadata.uid(), metadata.gid()) } != 0 {
+        let x = io::Error::last_os_error();
+        return Err(io::Error::new(
+            x.kind(),
+            format!("Unable to keep the owner of the file: {x}"),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(windows)]
+pub(super) fn keep_owner(_file: &std::fs::File, _metadata: &std::fs::Metadata) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_fs::prelude::*;
+    use distant_core::protocol::SearchQueryCondition;
+    use test_log::test;
+
+    use super::*;
+
+    fn make_replacer(regex: &str, replacement: &str) -> Replacer {
+        let query = SearchQuery {
+            target: SearchQueryTarget::Contents,
+            condition: SearchQueryCondition::regex(regex),
+            paths: Vec::new(),
+            options: Default::default(),
+        };
+        Replacer::new(&query, replacement).unwrap()
+    }
+
+    #[test(tokio::test)]
+    async fn commit_should_fail_with_conflict_if_file_was_modified_after_being_read() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.write_str("hello world\n").unwrap();
+
+        let replacer = make_replacer("hello", "bye");
+        let rewrite = replacer.rewrite_file(file.path()).await.unwrap().unwrap();
+        let staged = rewrite.stage().await.unwrap();
+
+        // Modify the file after it was read and staged, but before it is replaced
+        file.write_str("hello there, world\n").unwrap();
+
+        let err = staged.check().await.unwrap_err();
+        assert!(err.to_string().contains("Conflict"), "{err}");
+        let err = staged.commit().await.unwrap_err();
+        assert!(err.to_string().contains("Conflict"), "{err}");
+
+        // Modifications are kept and the staged contents are discarded
+        file.assert("hello there, world\n");
+        let names: Vec<_> = std::fs::read_dir(temp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["file"]);
+    }
+
+    #[test(tokio::test)]
+    async fn stage_should_fail_with_conflict_if_file_was_modified_after_being_read() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.write_str("hello world\n").unwrap();
+
+        let replacer = make_replacer("hello", "bye");
+        let rewrite = replacer.rewrite_file(file.path()).await.unwrap().unwrap();
+
+        file.write_str("hello there, world\n").unwrap();
+
+        let err = rewrite.stage().await.err().unwrap();
+        assert!(err.to_string().contains("Conflict"), "{err}");
+        file.assert("hello there, world\n");
+    }
+
+    #[test(tokio::test)]
+    async fn commit_should_replace_file_if_unmodified_since_being_read() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        file.write_str("hello world\n").unwrap();
+
+        let replacer = make_replacer("hello", "bye");
+        let rewrite = replacer.rewrite_file(file.path()).await.unwrap().unwrap();
+        let staged = rewrite.stage().await.unwrap();
+        staged.commit().await.unwrap();
+
+        file.assert("bye world\n");
+    }
+}
@@ -8,6 +8,12 @@ pub use process::*;
 mod search;
 pub use search::*;
 
+mod tcp;
+pub use tcp::*;
+
+mod upload;
+pub use upload::*;
+
 mod watcher;
 pub use watcher::*;
 
@@ -19,6 +25,12 @@ pub struct GlobalState {
     /// State that holds information about searches running on the server
     pub search: SearchState,
 
+    /// State that holds information about forwarded tcp streams and listeners on the server
+    pub tcp: TcpState,
+
+    /// State that holds information about uploads in progress on the server
+    pub upload: UploadState,
+
     /// Watcher used for filesystem events
     pub watcher: WatcherState,
 }
@@ -26,8 +38,10 @@ pub struct GlobalState {
 impl GlobalState {
     pub fn initialize(config: Config) -> io::Result<Self> {
         Ok(Self {
-            process: ProcessState::new(),
+            process: ProcessState::new(config.process),
             search: SearchState::new(),
+            tcp: TcpState::new(),
+            upload: UploadState::new(),
             watcher: WatcherBuilder::new()
                 .with_config(config.watch)
                 .initialize()?,
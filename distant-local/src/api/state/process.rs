@@ -2,15 +2,29 @@ use std::collections::HashMap;
 use std::io;
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use distant_core::net::common::ConnectionId;
 use distant_core::net::server::Reply;
-use distant_core::protocol::{Environment, ProcessId, PtySize, Response};
+use distant_core::protocol::{
+    Environment, ProcSpawnOptions, ProcessId, ProcessInfo, PtySize, Response, Signal,
+};
+use log::*;
 use tokio::sync::{mpsc, oneshot};
 use tokio::task::JoinHandle;
 
+use crate::config::ProcessConfig;
+
 mod instance;
 pub use instance::*;
 
+/// Time that a finished process whose completion was never delivered is kept around for a
+/// connection to attach to before it is discarded along with its output
+const FINISHED_PROCESS_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum time between checks for finished processes that have been kept around for too long
+const FINISHED_PROCESS_EXPIRE_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Holds information related to spawned processes on the server.
 pub struct ProcessState {
     channel: ProcessChannel,
@@ -25,9 +39,15 @@ impl Drop for ProcessState {
 }
 
 impl ProcessState {
-    pub fn new() -> Self {
+    pub fn new(config: ProcessConfig) -> Self {
+        Self::with_finished_timeout(config, FINISHED_PROCESS_TIMEOUT)
+    }
+
+    /// Creates a new state where processes that finish without their completion being delivered
+    /// are discarded once `finished_timeout` has passed
+    pub fn with_finished_timeout(config: ProcessConfig, finished_timeout: Duration) -> Self {
         let (tx, rx) = mpsc::channel(1);
-        let task = tokio::spawn(process_task(tx.clone(), rx));
+        let task = tokio::spawn(process_task(config, finished_timeout, tx.clone(), rx));
 
         Self {
             channel: ProcessChannel { tx },
@@ -63,22 +83,27 @@ impl Default for ProcessChannel {
 }
 
 impl ProcessChannel {
-    /// Spawns a new process, returning the id associated with it.
+    /// Spawns a new process held by the connection with `connection_id`, returning the id
+    /// associated with the process.
     pub async fn spawn(
         &self,
+        connection_id: ConnectionId,
         cmd: String,
         environment: Environment,
         current_dir: Option<PathBuf>,
         pty: Option<PtySize>,
+        options: ProcSpawnOptions,
         reply: Box<dyn Reply<Data = Response>>,
     ) -> io::Result<ProcessId> {
         let (cb, rx) = oneshot::channel();
         self.tx
             .send(InnerProcessMsg::Spawn {
+                connection_id,
                 cmd,
                 environment,
                 current_dir,
                 pty,
+                options,
                 reply,
                 cb,
             })
@@ -88,6 +113,41 @@ impl ProcessChannel {
             .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to spawn dropped"))?
     }
 
+    /// Lists the processes that are still being tracked, including those that have finished but
+    /// whose completion has not been delivered to any connection.
+    pub async fn list(&self) -> io::Result<Vec<ProcessInfo>> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerProcessMsg::List { cb })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal process task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to list dropped"))
+    }
+
+    /// Attaches to a process, sending any output that was buffered while detached followed by
+    /// all future output and its completion to `reply`. The process is then held by the
+    /// connection with `connection_id`.
+    pub async fn attach(
+        &self,
+        connection_id: ConnectionId,
+        id: ProcessId,
+        reply: Box<dyn Reply<Data = Response>>,
+    ) -> io::Result<()> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerProcessMsg::Attach {
+                connection_id,
+                id,
+                reply,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal process task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to attach dropped"))?
+    }
+
     /// Resizes the pty of a running process.
     pub async fn resize_pty(&self, id: ProcessId, size: PtySize) -> io::Result<()> {
         let (cb, rx) = oneshot::channel();
@@ -110,6 +170,17 @@ impl ProcessChannel {
             .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to stdin dropped"))?
     }
 
+    /// Sends a signal to a running process.
+    pub async fn signal(&self, id: ProcessId, signal: Signal) -> io::Result<()> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerProcessMsg::Signal { id, signal, cb })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal process task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to signal dropped"))?
+    }
+
     /// Kills a running process, including persistent processes if `force` is true. Will fail if
     /// unable to kill the process or `force` is false when the process is persistent.
     pub async fn kill(&self, id: ProcessId) -> io::Result<()> {
@@ -126,13 +197,24 @@ impl ProcessChannel {
 /// Internal message to pass to our task below to perform some action.
 enum InnerProcessMsg {
     Spawn {
+        connection_id: ConnectionId,
         cmd: String,
         environment: Environment,
         current_dir: Option<PathBuf>,
         pty: Option<PtySize>,
+        options: ProcSpawnOptions,
         reply: Box<dyn Reply<Data = Response>>,
         cb: oneshot::Sender<io::Result<ProcessId>>,
     },
+    List {
+        cb: oneshot::Sender<Vec<ProcessInfo>>,
+    },
+    Attach {
+        connection_id: ConnectionId,
+        id: ProcessId,
+        reply: Box<dyn Reply<Data = Response>>,
+        cb: oneshot::Sender<io::Result<()>>,
+    },
     Resize {
         id: ProcessId,
         size: PtySize,
@@ -143,38 +225,98 @@ enum InnerProcessMsg {
         data: Vec<u8>,
         cb: oneshot::Sender<io::Result<()>>,
     },
+    Signal {
+        id: ProcessId,
+        signal: Signal,
+        cb: oneshot::Sender<io::Result<()>>,
+    },
     Kill {
         id: ProcessId,
         cb: oneshot::Sender<io::Result<()>>,
     },
+    InternalFinished {
+        id: ProcessId,
+    },
     InternalRemove {
         id: ProcessId,
     },
 }
 
-async fn process_task(tx: mpsc::Sender<InnerProcessMsg>, mut rx: mpsc::Receiver<InnerProcessMsg>) {
+async fn process_task(
+    config: ProcessConfig,
+    finished_timeout: Duration,
+    tx: mpsc::Sender<InnerProcessMsg>,
+    mut rx: mpsc::Receiver<InnerProcessMsg>,
+) {
     let mut processes: HashMap<ProcessId, ProcessInstance> = HashMap::new();
+    let mut interval =
+        tokio::time::interval(finished_timeout.min(FINISHED_PROCESS_EXPIRE_INTERVAL));
+
+    loop {
+        let msg = tokio::select! {
+            msg = rx.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            _ = interval.tick() => {
+                expire_finished_processes(&mut processes, finished_timeout);
+                continue;
+            }
+        };
 
-    while let Some(msg) = rx.recv().await {
         match msg {
             InnerProcessMsg::Spawn {
+                connection_id,
                 cmd,
                 environment,
                 current_dir,
                 pty,
+                options,
                 reply,
                 cb,
             } => {
+                // NOTE: Finished processes are only kept around to deliver their completion, so
+                //       they do not count against the maximum
+                if let Some(max) = config.max_per_connection {
+                    let cnt = processes
+                        .values()
+                        .filter(|process| {
+                            process.connection_id == connection_id && process.finished.is_none()
+                        })
+                        .count();
+                    if cnt >= max {
+                        let _ = cb.send(Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("Connection already has maximum of {max} processes"),
+                        )));
+                        continue;
+                    }
+                }
+
                 let _ = cb.send(
-                    match ProcessInstance::spawn(cmd, environment, current_dir, pty, reply) {
+                    match ProcessInstance::spawn(
+                        connection_id,
+                        cmd,
+                        environment,
+                        current_dir,
+                        pty,
+                        options,
+                        reply,
+                    ) {
                         Ok(mut process) => {
                             let id = process.id;
 
                             // Attach a callback for when the process is finished where
-                            // we will remove it from our above list
+                            // we will remove it from our above list, unless its completion
+                            // still needs to be delivered to a connection that attaches later
                             let tx = tx.clone();
-                            process.on_done(move |_| async move {
-                                let _ = tx.send(InnerProcessMsg::InternalRemove { id }).await;
+                            process.on_done(move |delivered| async move {
+                                let msg = if delivered.unwrap_or(true) {
+                                    InnerProcessMsg::InternalRemove { id }
+                                } else {
+                                    InnerProcessMsg::InternalFinished { id }
+                                };
+                                let _ = tx.send(msg).await;
                             });
 
                             processes.insert(id, process);
@@ -184,6 +326,47 @@ async fn process_task(tx: mpsc::Sender<InnerProcessMsg>, mut rx: mpsc::Receiver<
                     },
                 );
             }
+            InnerProcessMsg::List { cb } => {
+                let mut entries: Vec<ProcessInfo> = processes
+                    .values()
+                    .map(|process| ProcessInfo {
+                        id: process.id,
+                        cmd: process.full_cmd(),
+                        pty: process.pty.pty_size(),
+                        started: process.started,
+                    })
+                    .collect();
+                entries.sort_unstable_by_key(|entry| (entry.started, entry.id));
+                let _ = cb.send(entries);
+            }
+            InnerProcessMsg::Attach {
+                connection_id,
+                id,
+                reply,
+                cb,
+            } => {
+                let result = match processes.get_mut(&id) {
+                    Some(process) => {
+                        let result = process.output.attach(reply).await;
+                        if result.is_ok() {
+                            process.connection_id = connection_id;
+                        }
+                        result
+                    }
+                    None => Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("No process found with id {id}"),
+                    )),
+                };
+
+                // If the process already finished, its completion has now been delivered
+                // and we no longer need to track it
+                if let Ok(true) = result {
+                    processes.remove(&id);
+                }
+
+                let _ = cb.send(result.map(|_| ()));
+            }
             InnerProcessMsg::Resize { id, size, cb } => {
                 let _ = cb.send(match processes.get(&id) {
                     Some(process) => process.pty.resize_pty(size),
@@ -208,6 +391,15 @@ async fn process_task(tx: mpsc::Sender<InnerProcessMsg>, mut rx: mpsc::Receiver<
                     )),
                 });
             }
+            InnerProcessMsg::Signal { id, signal, cb } => {
+                let _ = cb.send(match processes.get_mut(&id) {
+                    Some(process) => process.killer.signal(signal).await,
+                    None => Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        format!("No process found with id {id}"),
+                    )),
+                });
+            }
             InnerProcessMsg::Kill { id, cb } => {
                 let _ = cb.send(match processes.get_mut(&id) {
                     Some(process) => process.killer.kill().await,
@@ -217,9 +409,109 @@ async fn process_task(tx: mpsc::Sender<InnerProcessMsg>, mut rx: mpsc::Receiver<
                     )),
                 });
             }
+            InnerProcessMsg::InternalFinished { id } => {
+                if let Some(process) = processes.get_mut(&id) {
+                    process.finished = Some(Instant::now());
+                }
+            }
             InnerProcessMsg::InternalRemove { id } => {
                 processes.remove(&id);
             }
         }
     }
 }
+
+/// Stops tracking processes that finished without their completion being delivered more than
+/// `finished_timeout` ago, discarding their buffered output
+fn expire_finished_processes(
+    processes: &mut HashMap<ProcessId, ProcessInstance>,
+    finished_timeout: Duration,
+) {
+    processes.retain(|id, process| match process.finished {
+        Some(finished) if finished.elapsed() >= finished_timeout => {
+            debug!("Finished process {id} expired before its completion was delivered");
+            false
+        }
+        _ => true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    /// Creates a reply whose receiving side is already gone, so nothing sent to it is delivered
+    fn make_closed_reply() -> Box<dyn Reply<Data = Response>> {
+        let (tx, _) = mpsc::channel(1);
+        Box::new(tx)
+    }
+
+    async fn spawn(state: &ProcessState, cmd: &str) -> io::Result<ProcessId> {
+        state
+            .spawn(
+                0,
+                cmd.to_string(),
+                Environment::new(),
+                None,
+                None,
+                ProcSpawnOptions::default(),
+                make_closed_reply(),
+            )
+            .await
+    }
+
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn finished_processes_should_be_discarded_if_never_delivered_for_too_long() {
+        let state = ProcessState::with_finished_timeout(
+            ProcessConfig::default(),
+            Duration::from_millis(50),
+        );
+        let id = spawn(&state, "true").await.unwrap();
+
+        let start = Instant::now();
+        while state
+            .list()
+            .await
+            .unwrap()
+            .iter()
+            .any(|entry| entry.id == id)
+        {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Finished process never discarded"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn finished_processes_should_not_count_towards_maximum_per_connection() {
+        let state = ProcessState::new(ProcessConfig {
+            max_per_connection: Some(1),
+        });
+        let id = spawn(&state, "true").await.unwrap();
+
+        // Once the first process has finished, another can be spawned even though the first is
+        // still tracked as its completion was never delivered
+        let start = Instant::now();
+        while spawn(&state, "sleep 10").await.is_err() {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Finished process still counted"
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        assert!(state
+            .list()
+            .await
+            .unwrap()
+            .iter()
+            .any(|entry| entry.id == id));
+        spawn(&state, "sleep 10").await.unwrap_err();
+    }
+}
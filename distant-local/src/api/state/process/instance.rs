@@ -1,15 +1,23 @@
+use std::collections::VecDeque;
 use std::future::Future;
 use std::io;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
+use distant_core::net::common::ConnectionId;
 use distant_core::net::server::Reply;
-use distant_core::protocol::{Environment, ProcessId, PtySize, Response};
+use distant_core::protocol::{Environment, ProcSpawnOptions, ProcessId, PtySize, Response};
 use log::*;
+use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
 
 use crate::api::process::{
-    InputChannel, OutputChannel, Process, ProcessKiller, ProcessPty, PtyProcess, SimpleProcess,
+    default_shell, ExitStatus, InputChannel, OutputChannel, Process, ProcessKiller, ProcessPty,
+    PtyProcess, SimpleProcess,
 };
+use crate::constants::MAX_PROCESS_BACKLOG_SIZE;
 
 /// Holds information related to a spawned process on the server
 pub struct ProcessInstance {
@@ -21,9 +29,21 @@ pub struct ProcessInstance {
     pub killer: Box<dyn ProcessKiller>,
     pub pty: Box<dyn ProcessPty>,
 
+    /// Time when the process was spawned, as seconds since the Unix epoch
+    pub started: u64,
+
+    /// Where responses of the process are sent, buffering them while detached
+    pub output: ProcessOutput,
+
+    /// Connection that holds the process, which changes when another connection attaches
+    pub connection_id: ConnectionId,
+
+    /// Time when the process finished without its completion being delivered to a connection
+    pub finished: Option<Instant>,
+
     stdout_task: Option<JoinHandle<io::Result<()>>>,
     stderr_task: Option<JoinHandle<io::Result<()>>>,
-    wait_task: Option<JoinHandle<io::Result<()>>>,
+    wait_task: Option<JoinHandle<bool>>,
 }
 
 impl Drop for ProcessInstance {
@@ -61,14 +81,30 @@ impl Drop for ProcessInstance {
 
 impl ProcessInstance {
     pub fn spawn(
+        connection_id: ConnectionId,
         cmd: String,
         environment: Environment,
         current_dir: Option<PathBuf>,
         pty: Option<PtySize>,
+        options: ProcSpawnOptions,
         reply: Box<dyn Reply<Data = Response>>,
     ) -> io::Result<Self> {
-        // Build out the command and args from our string
-        let mut cmd_and_args = if cfg!(windows) {
+        // Build out the command and args from our string, leaving it to the login shell to
+        // interpret the string as a whole if requested
+        let mut cmd_and_args = if options.login_shell {
+            if cfg!(windows) {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Login shell is not supported on Windows",
+                ));
+            }
+
+            if cmd.trim().is_empty() {
+                Vec::new()
+            } else {
+                vec![default_shell(), "-l".to_string(), "-c".to_string(), cmd]
+            }
+        } else if cfg!(windows) {
             winsplit::split(&cmd)
         } else {
             shell_words::split(&cmd).map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?
@@ -92,12 +128,14 @@ impl ProcessInstance {
                 environment,
                 current_dir,
                 size,
+                options,
             )?),
             None => Box::new(SimpleProcess::spawn(
                 cmd.clone(),
                 args.clone(),
                 environment,
                 current_dir,
+                options,
             )?),
         };
 
@@ -107,12 +145,16 @@ impl ProcessInstance {
         let stderr = child.take_stderr();
         let killer = child.clone_killer();
         let pty = child.clone_pty();
+        let started = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let output = ProcessOutput::new(reply);
 
         // Spawn a task that sends stdout as a response
         let stdout_task = match stdout {
             Some(stdout) => {
-                let reply = reply.clone_reply();
-                let task = tokio::spawn(stdout_task(id, stdout, reply));
+                let task = tokio::spawn(stdout_task(id, stdout, output.clone()));
                 Some(task)
             }
             None => None,
@@ -121,16 +163,40 @@ impl ProcessInstance {
         // Spawn a task that sends stderr as a response
         let stderr_task = match stderr {
             Some(stderr) => {
-                let reply = reply.clone_reply();
-                let task = tokio::spawn(stderr_task(id, stderr, reply));
+                let task = tokio::spawn(stderr_task(id, stderr, output.clone()));
                 Some(task)
             }
             None => None,
         };
 
+        // Spawn a task that kills the process if it is still running once its timeout elapses
+        //
+        // NOTE: The kill is only queued and the process may still exit on its own around the
+        //       deadline, so whether it timed out is decided from how it exited in the wait task
+        let deadline_hit = Arc::new(AtomicBool::new(false));
+        let timeout_task = options.timeout.map(|timeout| {
+            let mut killer = child.clone_killer();
+            let deadline_hit = Arc::clone(&deadline_hit);
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(timeout)).await;
+                deadline_hit.store(true, Ordering::SeqCst);
+
+                debug!("Process {id} timed out after {timeout}ms");
+                if let Err(x) = killer.kill().await {
+                    error!("Failed to kill process {id} after timing out: {x}");
+                }
+            })
+        });
+
         // Spawn a task that waits on the process to exit but can also
         // kill the process when triggered
-        let wait_task = Some(tokio::spawn(wait_task(id, child, reply)));
+        let wait_task = Some(tokio::spawn(wait_task(
+            id,
+            child,
+            output.clone(),
+            timeout_task,
+            deadline_hit,
+        )));
 
         Ok(ProcessInstance {
             cmd,
@@ -139,41 +205,167 @@ impl ProcessInstance {
             stdin,
             killer,
             pty,
+            started,
+            output,
+            connection_id,
+            finished: None,
             stdout_task,
             stderr_task,
             wait_task,
         })
     }
 
-    /// Invokes the function once the process has completed
+    /// Returns the full command that was run, including arguments
+    pub fn full_cmd(&self) -> String {
+        std::iter::once(&self.cmd)
+            .chain(self.args.iter())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Invokes the function once the process has completed, indicating whether or not its
+    /// completion was delivered to an attached connection
     ///
     /// NOTE: Can only be used with one function. All future calls
     ///       will do nothing
     pub fn on_done<F, R>(&mut self, f: F)
     where
-        F: FnOnce(io::Result<()>) -> R + Send + 'static,
+        F: FnOnce(io::Result<bool>) -> R + Send + 'static,
         R: Future<Output = ()> + Send,
     {
         if let Some(task) = self.wait_task.take() {
             tokio::spawn(async move {
                 f(task
                     .await
-                    .unwrap_or_else(|x| Err(io::Error::new(io::ErrorKind::Other, x))))
+                    .map_err(|x| io::Error::new(io::ErrorKind::Other, x)))
                 .await
             });
         }
     }
 }
 
+/// Destination for the responses of a process that can be swapped to a new connection, buffering
+/// responses that could not be delivered while no connection is attached
+#[derive(Clone)]
+pub struct ProcessOutput(Arc<Mutex<ProcessOutputInner>>);
+
+struct ProcessOutputInner {
+    reply: Option<Box<dyn Reply<Data = Response>>>,
+    backlog: VecDeque<Response>,
+    backlog_size: usize,
+
+    /// Position given to the next stdout or stderr, shared so that the order in which they were
+    /// read can be reconstructed
+    next_seq: u64,
+}
+
+impl ProcessOutput {
+    pub fn new(reply: Box<dyn Reply<Data = Response>>) -> Self {
+        Self(Arc::new(Mutex::new(ProcessOutputInner {
+            reply: Some(reply),
+            backlog: VecDeque::new(),
+            backlog_size: 0,
+            next_seq: 0,
+        })))
+    }
+
+    /// Sends `res` to the attached connection, returning true if it was delivered. If it could
+    /// not be delivered, the connection is detached and `res` is added to the backlog.
+    pub async fn send(&self, res: Response) -> bool {
+        self.0.lock().await.send(res).await
+    }
+
+    /// Sends the response produced by `f` given the next position amongst the stdout and stderr
+    /// of the process, returning true if it was delivered
+    ///
+    /// NOTE: The position is taken and the response sent while holding the same lock, so that
+    ///       responses always go out in the order of their positions
+    pub async fn send_with_seq(&self, f: impl FnOnce(u64) -> Response) -> bool {
+        let mut inner = self.0.lock().await;
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        inner.send(f(seq)).await
+    }
+
+    /// Attaches `reply` as the new destination for responses, first sending it everything in
+    /// the backlog. Returns true if the backlog included the completion of the process.
+    pub async fn attach(&self, reply: Box<dyn Reply<Data = Response>>) -> io::Result<bool> {
+        let mut inner = self.0.lock().await;
+
+        let mut done = false;
+        while let Some(res) = inner.backlog.pop_front() {
+            let size = backlog_size_of(&res);
+            let is_done = matches!(res, Response::ProcDone { .. } | Response::Error(_));
+            if let Err(x) = reply.send(res.clone()).await {
+                inner.backlog.push_front(res);
+                return Err(x);
+            }
+
+            inner.backlog_size -= size;
+            done |= is_done;
+        }
+
+        inner.reply = Some(reply);
+        Ok(done)
+    }
+}
+
+impl ProcessOutputInner {
+    async fn send(&mut self, res: Response) -> bool {
+        if let Some(reply) = self.reply.as_ref() {
+            if reply.send(res.clone()).await.is_ok() {
+                return true;
+            }
+
+            debug!("Process output detached as reply failed");
+            self.reply = None;
+        }
+
+        self.push_backlog(res);
+        false
+    }
+
+    /// Adds `res` to the backlog, discarding the oldest output once the backlog is full
+    fn push_backlog(&mut self, res: Response) {
+        self.backlog_size += backlog_size_of(&res);
+        self.backlog.push_back(res);
+
+        while self.backlog_size > MAX_PROCESS_BACKLOG_SIZE {
+            match self.backlog.front() {
+                Some(Response::ProcStdout { .. } | Response::ProcStderr { .. }) => {
+                    let res = self.backlog.pop_front().unwrap();
+                    self.backlog_size -= backlog_size_of(&res);
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+/// Returns the size (in bytes) that `res` counts against the backlog
+fn backlog_size_of(res: &Response) -> usize {
+    match res {
+        Response::ProcStdout { data, .. } | Response::ProcStderr { data, .. } => data.len(),
+        _ => 0,
+    }
+}
+
 async fn stdout_task(
     id: ProcessId,
     mut stdout: Box<dyn OutputChannel>,
-    reply: Box<dyn Reply<Data = Response>>,
+    output: ProcessOutput,
 ) -> io::Result<()> {
     loop {
         match stdout.recv().await {
             Ok(Some(data)) => {
-                reply.send(Response::ProcStdout { id, data }).await?;
+                output
+                    .send_with_seq(|seq| Response::ProcStdout {
+                        id,
+                        data,
+                        seq: Some(seq),
+                    })
+                    .await;
             }
             Ok(None) => return Ok(()),
             Err(x) => return Err(x),
@@ -184,12 +376,18 @@ async fn stdout_task(
 async fn stderr_task(
     id: ProcessId,
     mut stderr: Box<dyn OutputChannel>,
-    reply: Box<dyn Reply<Data = Response>>,
+    output: ProcessOutput,
 ) -> io::Result<()> {
     loop {
         match stderr.recv().await {
             Ok(Some(data)) => {
-                reply.send(Response::ProcStderr { id, data }).await?;
+                output
+                    .send_with_seq(|seq| Response::ProcStderr {
+                        id,
+                        data,
+                        seq: Some(seq),
+                    })
+                    .await;
             }
             Ok(None) => return Ok(()),
             Err(x) => return Err(x),
@@ -200,20 +398,160 @@ async fn stderr_task(
 async fn wait_task(
     id: ProcessId,
     mut child: Box<dyn Process>,
-    reply: Box<dyn Reply<Data = Response>>,
-) -> io::Result<()> {
+    output: ProcessOutput,
+    timeout_task: Option<JoinHandle<()>>,
+    deadline_hit: Arc<AtomicBool>,
+) -> bool {
     let status = child.wait().await;
+    if let Some(task) = timeout_task {
+        task.abort();
+    }
 
     match status {
         Ok(status) => {
-            reply
+            output
                 .send(Response::ProcDone {
                     id,
                     success: status.success,
                     code: status.code,
+                    limit: status.limit,
+                    timed_out: deadline_hit.load(Ordering::SeqCst) && was_killed(&status),
+                    signal: status.signal,
+                    core_dumped: status.core_dumped,
+                    usage: status.usage,
                 })
                 .await
         }
-        Err(x) => reply.send(Response::from(x)).await,
+        Err(x) => output.send(Response::from(x)).await,
+    }
+}
+
+/// Returns true if the process was terminated by a kill rather than exiting on its own
+#[cfg(unix)]
+fn was_killed(status: &ExitStatus) -> bool {
+    status.signal == Some(libc::SIGKILL) || *status == ExitStatus::killed()
+}
+
+/// Returns true if the process was terminated by a kill rather than exiting on its own
+///
+/// NOTE: Windows does not report how a process was terminated, so any failure is assumed to come
+///       from the kill
+#[cfg(windows)]
+fn was_killed(status: &ExitStatus) -> bool {
+    !status.success
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[test(tokio::test)]
+    async fn send_with_seq_should_send_responses_in_order_of_their_positions() {
+        let (tx, mut rx) = mpsc::channel(1000);
+        let output = ProcessOutput::new(Box::new(tx));
+
+        let tasks: Vec<_> = (0..4)
+            .map(|_| {
+                let output = output.clone();
+                tokio::spawn(async move {
+                    for _ in 0..100 {
+                        output
+                            .send_with_seq(|seq| Response::ProcStdout {
+                                id: 0,
+                                data: Vec::new(),
+                                seq: Some(seq),
+                            })
+                            .await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        for expected in 0..400 {
+            match rx.recv().await.unwrap() {
+                Response::ProcStdout { seq, .. } => assert_eq!(seq, Some(expected)),
+                x => panic!("Unexpected response: {x:?}"),
+            }
+        }
+    }
+
+    #[test(tokio::test)]
+    #[cfg_attr(windows, ignore)]
+    async fn wait_task_should_not_report_timeout_if_process_exited_on_its_own_after_deadline() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let child = SimpleProcess::spawn(
+            "true",
+            Vec::<String>::new(),
+            Environment::new(),
+            None,
+            ProcSpawnOptions::default(),
+        )
+        .unwrap();
+
+        // Deadline has already been reached, but the process exits without being killed
+        let deadline_hit = Arc::new(AtomicBool::new(true));
+        let output = ProcessOutput::new(Box::new(tx));
+        wait_task(0, Box::new(child), output, None, deadline_hit).await;
+
+        match rx.recv().await.unwrap() {
+            Response::ProcDone {
+                success, timed_out, ..
+            } => {
+                assert!(success, "Process should have exited on its own");
+                assert!(
+                    !timed_out,
+                    "Process should not have been reported as timed out"
+                );
+            }
+            x => panic!("Unexpected response: {x:?}"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test(tokio::test)]
+    async fn spawn_should_only_report_timeout_if_process_finishing_near_deadline_was_killed() {
+        // Process finishes right around its deadline, so it may or may not be killed each time
+        for _ in 0..5 {
+            let (tx, mut rx) = mpsc::channel(100);
+            let _instance = ProcessInstance::spawn(
+                0,
+                String::from("sleep 0.1"),
+                Environment::new(),
+                None,
+                None,
+                ProcSpawnOptions {
+                    timeout: Some(100),
+                    ..Default::default()
+                },
+                Box::new(tx),
+            )
+            .unwrap();
+
+            loop {
+                match rx.recv().await.unwrap() {
+                    Response::ProcDone {
+                        success,
+                        timed_out,
+                        signal,
+                        ..
+                    } => {
+                        assert_eq!(
+                            timed_out,
+                            signal == Some(libc::SIGKILL),
+                            "Timeout reported for process that was not killed: {signal:?}"
+                        );
+                        assert!(!(success && timed_out), "Successful process timed out");
+                        break;
+                    }
+                    _ => continue,
+                }
+            }
+        }
     }
 }
@@ -1,17 +1,21 @@
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 use std::{cmp, io};
 
 use distant_core::net::server::Reply;
 use distant_core::protocol::{
-    Response, SearchId, SearchQuery, SearchQueryContentsMatch, SearchQueryMatch,
-    SearchQueryMatchData, SearchQueryOptions, SearchQueryPathMatch, SearchQuerySubmatch,
-    SearchQueryTarget,
+    Response, SearchId, SearchQuery, SearchQueryCondition, SearchQueryContentsMatch,
+    SearchQueryContextKind, SearchQueryContextLine, SearchQueryMatch, SearchQueryMatchData,
+    SearchQueryOptions, SearchQueryPathMatch, SearchQuerySubmatch, SearchQueryTarget,
 };
 use grep::matcher::Matcher;
 use grep::regex::{RegexMatcher, RegexMatcherBuilder};
-use grep::searcher::{BinaryDetection, Searcher, SearcherBuilder, Sink, SinkMatch};
+use grep::searcher::{
+    BinaryDetection, Searcher, SearcherBuilder, Sink, SinkContext, SinkContextKind, SinkFinish,
+    SinkMatch,
+};
 use ignore::types::TypesBuilder;
 use ignore::{DirEntry, ParallelVisitor, ParallelVisitorBuilder, WalkBuilder, WalkParallel};
 use log::*;
@@ -20,6 +24,10 @@ use tokio::task::JoinHandle;
 
 const MAXIMUM_SEARCH_THREADS: usize = 12;
 
+/// Maximum size (in bytes) of a file whose contents are evaluated against an `And` or `Not`
+/// condition, which requires loading the entire file into memory
+const MAXIMUM_STRUCTURAL_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
 /// Holds information related to active searches on the server
 pub struct SearchState {
     channel: SearchChannel,
@@ -253,11 +261,30 @@ impl SearchQueryReporter {
     }
 }
 
+/// Creates a builder for the regex used to match the condition of a search with `options`
+pub fn new_search_matcher_builder(options: &SearchQueryOptions) -> RegexMatcherBuilder {
+    let mut builder = RegexMatcherBuilder::new();
+    builder
+        .case_insensitive(false)
+        .case_smart(false)
+        .multi_line(true)
+        .dot_matches_new_line(false)
+        .swap_greed(false)
+        .ignore_whitespace(false)
+        .unicode(true)
+        .octal(false)
+        // NOTE: A line terminator prevents matches from spanning lines, so we can only use it
+        //       when multiline matching is disabled
+        .line_terminator(if options.multiline { None } else { Some(b'\n') });
+    builder
+}
+
 struct SearchQueryExecutor {
     id: SearchId,
     query: SearchQuery,
     walker: WalkParallel,
     matcher: RegexMatcher,
+    condition_matcher: Option<SearchQueryConditionMatcher>,
 
     cancel_tx: Option<broadcast::Sender<()>>,
     cancel_rx: broadcast::Receiver<()>,
@@ -272,22 +299,24 @@ impl SearchQueryExecutor {
         let (cancel_tx, cancel_rx) = broadcast::channel(1);
         let (match_tx, match_rx) = mpsc::unbounded_channel();
 
-        let regex = query.condition.to_regex_string();
-        let mut matcher_builder = RegexMatcherBuilder::new();
-        matcher_builder
-            .case_insensitive(false)
-            .case_smart(false)
-            .multi_line(true)
-            .dot_matches_new_line(false)
-            .swap_greed(false)
-            .ignore_whitespace(false)
-            .unicode(true)
-            .octal(false)
-            .line_terminator(Some(b'\n'));
+        let regex = to_locating_regex_string(&query.condition);
+        let matcher_builder = new_search_matcher_builder(&query.options);
         let matcher = matcher_builder
             .build(&regex)
             .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
 
+        // NOTE: Conditions with And/Not cannot be flattened into a single regex, so we evaluate
+        //       them structurally against each path or file and only use the regex above to
+        //       report the parts that matched
+        let condition_matcher = if query.condition.is_structural() {
+            Some(SearchQueryConditionMatcher::new(
+                &query.condition,
+                &matcher_builder,
+            )?)
+        } else {
+            None
+        };
+
         if query.paths.is_empty() {
             return Err(io::Error::new(io::ErrorKind::InvalidInput, "missing paths"));
         }
@@ -344,7 +373,15 @@ impl SearchQueryExecutor {
                     .build()
                     .map_err(|x| io::Error::new(io::ErrorKind::Other, x))?,
             )
-            .skip_stdout(true);
+            .skip_stdout(true)
+            .standard_filters(!query.options.no_ignore)
+            .hidden(!query.options.hidden);
+
+        for path in &query.options.ignore_files {
+            if let Some(x) = walker_builder.add_ignore(path) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, x));
+            }
+        }
 
         if query.options.upward {
             // If traversing upward, we need to use max depth to determine how many
@@ -368,6 +405,7 @@ impl SearchQueryExecutor {
             id: rand::random(),
             query,
             matcher,
+            condition_matcher,
             walker: walker_builder.build_parallel(),
             cancel_tx: Some(cancel_tx),
             cancel_rx,
@@ -406,12 +444,13 @@ impl SearchQueryExecutor {
         let tx = self.match_tx;
         let cancel = self.cancel_rx;
         let matcher = self.matcher;
+        let condition_matcher = self.condition_matcher;
 
         // Create our path filter we will use to filter out entries that do not match filter
         let include_path_filter = match self.query.options.include.as_ref() {
-            Some(condition) => match SearchQueryPathFilter::new(&condition.to_regex_string()) {
+            Some(condition) => match SearchQueryPathFilter::new(condition) {
                 Ok(filter) => {
-                    trace!("[Query {id}] Using regex include path filter for {condition:?}");
+                    trace!("[Query {id}] Using include path filter for {condition:?}");
                     filter
                 }
                 Err(x) => {
@@ -427,9 +466,9 @@ impl SearchQueryExecutor {
 
         // Create our path filter we will use to filter out entries that match filter
         let exclude_path_filter = match self.query.options.exclude.as_ref() {
-            Some(condition) => match SearchQueryPathFilter::new(&condition.to_regex_string()) {
+            Some(condition) => match SearchQueryPathFilter::new(condition) {
                 Ok(filter) => {
-                    trace!("[Query {id}] Using regex exclude path filter for {condition:?}");
+                    trace!("[Query {id}] Using exclude path filter for {condition:?}");
                     filter
                 }
                 Err(x) => {
@@ -454,6 +493,7 @@ impl SearchQueryExecutor {
             cancel,
             tx,
             matcher: &matcher,
+            condition_matcher: condition_matcher.as_ref(),
             include_path_filter: &include_path_filter,
             exclude_path_filter: &exclude_path_filter,
             options_filter: &options_filter,
@@ -472,6 +512,7 @@ struct SearchQueryExecutorParallelVistorBuilder<'a> {
     cancel: broadcast::Receiver<()>,
     tx: mpsc::UnboundedSender<SearchQueryMatch>,
     matcher: &'a RegexMatcher,
+    condition_matcher: Option<&'a SearchQueryConditionMatcher>,
     include_path_filter: &'a SearchQueryPathFilter,
     exclude_path_filter: &'a SearchQueryPathFilter,
     options_filter: &'a SearchQueryOptionsFilter,
@@ -485,7 +526,8 @@ impl<'a> ParallelVisitorBuilder<'a> for SearchQueryExecutorParallelVistorBuilder
         //     * Will quit early if detecting binary file due to null byte
         //
         // NOTE: Searchers are not Send/Sync so we must create them here
-        let implicit_searcher = SearcherBuilder::new()
+        let implicit_searcher = self
+            .new_searcher_builder()
             .binary_detection(BinaryDetection::quit(0))
             .build();
 
@@ -496,7 +538,8 @@ impl<'a> ParallelVisitorBuilder<'a> for SearchQueryExecutorParallelVistorBuilder
         //     * Will convert binary data with null bytes into newlines
         //
         // NOTE: Searchers are not Send/Sync so we must create them here
-        let explicit_searcher = SearcherBuilder::new()
+        let explicit_searcher = self
+            .new_searcher_builder()
             .binary_detection(BinaryDetection::convert(0))
             .build();
 
@@ -506,6 +549,7 @@ impl<'a> ParallelVisitorBuilder<'a> for SearchQueryExecutorParallelVistorBuilder
             cancel: self.cancel.resubscribe(),
             tx: self.tx.clone(),
             matcher: self.matcher,
+            condition_matcher: self.condition_matcher,
             implicit_searcher,
             explicit_searcher,
             include_path_filter: self.include_path_filter,
@@ -515,12 +559,26 @@ impl<'a> ParallelVisitorBuilder<'a> for SearchQueryExecutorParallelVistorBuilder
     }
 }
 
+impl<'a> SearchQueryExecutorParallelVistorBuilder<'a> {
+    /// Creates a searcher builder configured with the context and multiline options of the query
+    fn new_searcher_builder(&self) -> SearcherBuilder {
+        let options = &self.options_filter.options;
+        let mut builder = SearcherBuilder::new();
+        builder
+            .before_context(options.before_context.unwrap_or_default() as usize)
+            .after_context(options.after_context.unwrap_or_default() as usize)
+            .multi_line(options.multiline);
+        builder
+    }
+}
+
 struct SearchQueryExecutorParallelVistor<'a> {
     search_id: SearchId,
     target: SearchQueryTarget,
     cancel: broadcast::Receiver<()>,
     tx: mpsc::UnboundedSender<SearchQueryMatch>,
     matcher: &'a RegexMatcher,
+    condition_matcher: Option<&'a SearchQueryConditionMatcher>,
     implicit_searcher: Searcher,
     explicit_searcher: Searcher,
     include_path_filter: &'a SearchQueryPathFilter,
@@ -567,9 +625,69 @@ impl<'a> ParallelVisitor for SearchQueryExecutorParallelVistor<'a> {
             &mut self.implicit_searcher
         };
 
-        let res = match self.target {
+        let res = match (self.target, self.condition_matcher) {
+            // Evaluate the condition against the path as a whole before searching it, reporting
+            // the path without submatches if only negated conditions were involved
+            (SearchQueryTarget::Path, Some(condition_matcher)) => {
+                let path_str = entry.path().to_string_lossy();
+                match condition_matcher.is_match(path_str.as_bytes()) {
+                    Ok(true)
+                        if !self
+                            .matcher
+                            .is_match(path_str.as_bytes())
+                            .unwrap_or_default() =>
+                    {
+                        let _ = self.tx.send(SearchQueryMatch::Path(SearchQueryPathMatch {
+                            path: entry.path().to_path_buf(),
+                            submatches: Vec::new(),
+                        }));
+                        Ok(())
+                    }
+                    Ok(true) => searcher.search_slice(
+                        self.matcher,
+                        path_str.as_bytes(),
+                        SearchQueryPathSink {
+                            search_id: id,
+                            path: entry.path(),
+                            matcher: self.matcher,
+                            callback: |m| Ok(self.tx.send(m).is_ok()),
+                        },
+                    ),
+                    Ok(false) => Ok(()),
+                    Err(x) => Err(x),
+                }
+            }
+
+            // Evaluate the condition against the file's contents as a whole before searching
+            // them, which only reports lines matching conditions that are not negated
+            (SearchQueryTarget::Contents, Some(condition_matcher)) => {
+                match read_structural_file(entry.path(), entry.depth() == 0).and_then(|data| {
+                    match data {
+                        Some(data) => condition_matcher
+                            .is_match(&data)
+                            .map(|matched| matched.then_some(data)),
+                        None => Ok(None),
+                    }
+                }) {
+                    Ok(Some(data)) => searcher.search_slice(
+                        self.matcher,
+                        &data,
+                        SearchQueryContentsSink {
+                            search_id: id,
+                            path: entry.path(),
+                            matcher: self.matcher,
+                            callback: |m| Ok(self.tx.send(m).is_ok()),
+                            before: Vec::new(),
+                            pending: None,
+                        },
+                    ),
+                    Ok(None) => Ok(()),
+                    Err(x) => Err(x),
+                }
+            }
+
             // Perform the search against the path itself
-            SearchQueryTarget::Path => {
+            (SearchQueryTarget::Path, None) => {
                 let path_str = entry.path().to_string_lossy();
                 searcher.search_slice(
                     self.matcher,
@@ -584,7 +702,7 @@ impl<'a> ParallelVisitor for SearchQueryExecutorParallelVistor<'a> {
             }
 
             // Perform the search against the file's contents
-            SearchQueryTarget::Contents => searcher.search_path(
+            (SearchQueryTarget::Contents, None) => searcher.search_path(
                 self.matcher,
                 entry.path(),
                 SearchQueryContentsSink {
@@ -592,6 +710,8 @@ impl<'a> ParallelVisitor for SearchQueryExecutorParallelVistor<'a> {
                     path: entry.path(),
                     matcher: self.matcher,
                     callback: |m| Ok(self.tx.send(m).is_ok()),
+                    before: Vec::new(),
+                    pending: None,
                 },
             ),
         };
@@ -604,18 +724,121 @@ impl<'a> ParallelVisitor for SearchQueryExecutorParallelVistor<'a> {
     }
 }
 
+/// Reads the contents of the file at `path` so they can be evaluated against a structural
+/// condition, returning `None` if the file is too large or, unless `explicit`, binary, in which
+/// case it is skipped in the same way that the implicit searcher skips binary files
+fn read_structural_file(path: &Path, explicit: bool) -> io::Result<Option<Vec<u8>>> {
+    use std::io::Read;
+
+    let mut data = Vec::new();
+    std::fs::File::open(path)?
+        .take(MAXIMUM_STRUCTURAL_FILE_SIZE + 1)
+        .read_to_end(&mut data)?;
+
+    if data.len() as u64 > MAXIMUM_STRUCTURAL_FILE_SIZE {
+        debug!("Skipping {path:?} as it is too large to evaluate against the condition");
+        return Ok(None);
+    }
+
+    if !explicit && data.contains(&0) {
+        return Ok(None);
+    }
+
+    Ok(Some(data))
+}
+
+/// Converts `condition` into a regex string that only captures the conditions that are not
+/// negated, used to locate the parts of a path or file that matched once a structural condition
+/// as a whole has been evaluated
+fn to_locating_regex_string(condition: &SearchQueryCondition) -> String {
+    match condition {
+        // NOTE: Nothing can both be and not be at a word boundary, so this never matches
+        SearchQueryCondition::Not { .. } => String::from(r"\b\B"),
+        SearchQueryCondition::And { value } | SearchQueryCondition::Or { value } => value
+            .iter()
+            .map(to_locating_regex_string)
+            .collect::<Vec<_>>()
+            .join("|"),
+        condition => condition.to_regex_string(),
+    }
+}
+
+/// Evaluates a condition structurally against some data, which is needed for `And` and `Not` as
+/// they cannot be represented as a single regex
+enum SearchQueryConditionMatcher {
+    And(Vec<SearchQueryConditionMatcher>),
+    Not(Box<SearchQueryConditionMatcher>),
+    Or(Vec<SearchQueryConditionMatcher>),
+    Regex(Box<RegexMatcher>),
+}
+
+impl SearchQueryConditionMatcher {
+    /// Builds a matcher for `condition`, using `builder` to construct a regex for each part of
+    /// the condition that can be represented as one
+    pub fn new(
+        condition: &SearchQueryCondition,
+        builder: &RegexMatcherBuilder,
+    ) -> io::Result<Self> {
+        let build_all = |conditions: &[SearchQueryCondition]| {
+            conditions
+                .iter()
+                .map(|c| Self::new(c, builder))
+                .collect::<io::Result<Vec<_>>>()
+        };
+
+        Ok(match condition {
+            SearchQueryCondition::And { value } => Self::And(build_all(value)?),
+            SearchQueryCondition::Not { value } => Self::Not(Box::new(Self::new(value, builder)?)),
+            SearchQueryCondition::Or { value } if condition.is_structural() => {
+                Self::Or(build_all(value)?)
+            }
+            condition => Self::Regex(Box::new(
+                builder
+                    .build(&condition.to_regex_string())
+                    .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?,
+            )),
+        })
+    }
+
+    /// Returns true if the condition is met anywhere within `data`
+    pub fn is_match(&self, data: &[u8]) -> io::Result<bool> {
+        match self {
+            Self::And(matchers) => {
+                for matcher in matchers {
+                    if !matcher.is_match(data)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Self::Not(matcher) => Ok(!matcher.is_match(data)?),
+            Self::Or(matchers) => {
+                for matcher in matchers {
+                    if matcher.is_match(data)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Self::Regex(matcher) => matcher
+                .is_match(data)
+                .map_err(|x| io::Error::new(io::ErrorKind::Other, x)),
+        }
+    }
+}
+
 struct SearchQueryPathFilter {
-    matcher: Option<RegexMatcher>,
+    matcher: Option<SearchQueryConditionMatcher>,
     default_value: bool,
 }
 
 impl SearchQueryPathFilter {
-    pub fn new(regex: &str) -> io::Result<Self> {
+    pub fn new(condition: &SearchQueryCondition) -> io::Result<Self> {
         Ok(Self {
-            matcher: Some(
-                RegexMatcher::new(regex)
-                    .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?,
-            ),
+            matcher: Some(SearchQueryConditionMatcher::new(
+                condition,
+                &RegexMatcherBuilder::new(),
+            )?),
             default_value: false,
         })
     }
@@ -635,9 +858,7 @@ impl SearchQueryPathFilter {
 
     fn try_filter(&self, path: impl AsRef<Path>) -> io::Result<bool> {
         match &self.matcher {
-            Some(matcher) => matcher
-                .is_match(path.as_ref().to_string_lossy().as_bytes())
-                .map_err(|x| io::Error::new(io::ErrorKind::Other, x)),
+            Some(matcher) => matcher.is_match(path.as_ref().to_string_lossy().as_bytes()),
             None => Ok(self.default_value),
         }
     }
@@ -665,7 +886,53 @@ impl SearchQueryOptionsFilter {
             _ => true,
         };
 
-        file_type_allowed && targeted
+        file_type_allowed && targeted && self.metadata_allowed(entry)
+    }
+
+    /// Returns true if the size and modification time of the entry are within the bounds of the
+    /// options, only looking up metadata if any bounds are set
+    fn metadata_allowed(&self, entry: &DirEntry) -> bool {
+        let options = &self.options;
+        let check_size = options.min_size.is_some() || options.max_size.is_some();
+        let check_modified = options.modified_after.is_some() || options.modified_before.is_some();
+        if !check_size && !check_modified {
+            return true;
+        }
+
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => return false,
+        };
+
+        // Size bounds only make sense for files, so anything else is excluded
+        if check_size {
+            let len = metadata.len();
+            if !metadata.is_file()
+                || matches!(options.min_size, Some(min) if len < min)
+                || matches!(options.max_size, Some(max) if len > max)
+            {
+                return false;
+            }
+        }
+
+        if check_modified {
+            let modified = match metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            {
+                Some(duration) => duration.as_secs(),
+                None => return false,
+            };
+
+            if matches!(options.modified_after, Some(after) if modified < after)
+                || matches!(options.modified_before, Some(before) if modified > before)
+            {
+                return false;
+            }
+        }
+
+        true
     }
 }
 
@@ -739,6 +1006,26 @@ where
     path: &'a Path,
     matcher: &'a M,
     callback: F,
+
+    /// Context lines seen ahead of the next match
+    before: Vec<SearchQueryContextLine>,
+
+    /// Match held back until its trailing context lines have been collected
+    pending: Option<SearchQueryContentsMatch>,
+}
+
+impl<'a, M, F> SearchQueryContentsSink<'a, M, F>
+where
+    M: Matcher,
+    F: FnMut(SearchQueryMatch) -> Result<bool, io::Error>,
+{
+    /// Reports the pending match (if any), returning whether the search should continue
+    fn flush(&mut self) -> Result<bool, io::Error> {
+        match self.pending.take() {
+            Some(r#match) => (self.callback)(SearchQueryMatch::Contents(r#match)),
+            None => Ok(true),
+        }
+    }
 }
 
 impl<'a, M, F> Sink for SearchQueryContentsSink<'a, M, F>
@@ -748,6 +1035,46 @@ where
 {
     type Error = io::Error;
 
+    fn context(
+        &mut self,
+        _searcher: &Searcher,
+        context: &SinkContext<'_>,
+    ) -> Result<bool, io::Error> {
+        let kind = match context.kind() {
+            SinkContextKind::Before => SearchQueryContextKind::Before,
+            SinkContextKind::After => SearchQueryContextKind::After,
+            SinkContextKind::Other => return Ok(true),
+        };
+
+        let line = SearchQueryContextLine {
+            kind,
+            line: match std::str::from_utf8(context.bytes()) {
+                Ok(s) => SearchQueryMatchData::Text(s.to_string()),
+                Err(_) => SearchQueryMatchData::Bytes(context.bytes().to_vec()),
+            },
+
+            // NOTE: Since we are defining the searcher, we control always including the line
+            //       number, so we can safely unwrap here
+            line_number: context.line_number().unwrap(),
+            absolute_offset: context.absolute_byte_offset(),
+        };
+
+        // Trailing context belongs to the match before it, whereas leading context waits for the
+        // match that follows
+        match (kind, self.pending.as_mut()) {
+            (SearchQueryContextKind::After, Some(r#match)) => r#match.context.push(line),
+            (SearchQueryContextKind::Before, _) => self.before.push(line),
+            _ => (),
+        }
+
+        Ok(true)
+    }
+
+    fn finish(&mut self, _searcher: &Searcher, _finish: &SinkFinish) -> Result<(), io::Error> {
+        self.flush()?;
+        Ok(())
+    }
+
     fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> Result<bool, io::Error> {
         let mut submatches = Vec::new();
 
@@ -773,9 +1100,11 @@ where
             );
         }
 
-        // If we have at least one submatch, then we have a match
+        // If we have at least one submatch, then we have a match, which is held back until we
+        // know that no more trailing context is coming for it
         let should_continue = if !submatches.is_empty() {
-            let r#match = SearchQueryMatch::Contents(SearchQueryContentsMatch {
+            let should_continue = self.flush()?;
+            self.pending = Some(SearchQueryContentsMatch {
                 path: self.path.to_path_buf(),
                 lines: match std::str::from_utf8(mat.bytes()) {
                     Ok(s) => SearchQueryMatchData::Text(s.to_string()),
@@ -790,10 +1119,12 @@ where
                 //       match, but not inclusive of where within the match
                 absolute_offset: mat.absolute_byte_offset(),
                 submatches,
+                context: std::mem::take(&mut self.before),
             });
 
-            (self.callback)(r#match)?
+            should_continue
         } else {
+            self.before.clear();
             true
         };
 
@@ -806,7 +1137,7 @@ mod tests {
     use std::path::PathBuf;
 
     use assert_fs::prelude::*;
-    use distant_core::protocol::{FileType, SearchQueryCondition, SearchQueryMatchData};
+    use distant_core::protocol::{FileType, SearchQueryMatchData};
     use test_log::test;
 
     use super::*;
@@ -1051,7 +1382,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 21,
                         end: 25,
-                    }]
+                    }],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file1.txt")).to_path_buf(),
@@ -1062,7 +1394,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 9,
                         end: 13,
-                    }]
+                    }],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file2.txt")).to_path_buf(),
@@ -1073,7 +1406,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 5,
                         end: 9,
-                    }]
+                    }],
+                    context: Vec::new(),
                 }
             ]
         );
@@ -1126,7 +1460,8 @@ mod tests {
                             start: 3,
                             end: 5,
                         }
-                    ]
+                    ],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file.txt")).to_path_buf(),
@@ -1144,7 +1479,8 @@ mod tests {
                             start: 3,
                             end: 5,
                         }
-                    ]
+                    ],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file.txt")).to_path_buf(),
@@ -1162,7 +1498,8 @@ mod tests {
                             start: 3,
                             end: 5,
                         }
-                    ]
+                    ],
+                    context: Vec::new(),
                 },
             ]
         );
@@ -1233,7 +1570,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 21,
                         end: 25,
-                    }]
+                    }],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file1.txt")).to_path_buf(),
@@ -1244,7 +1582,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 9,
                         end: 13,
-                    }]
+                    }],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file2.txt")).to_path_buf(),
@@ -1255,7 +1594,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 5,
                         end: 9,
-                    }]
+                    }],
+                    context: Vec::new(),
                 }
             ]
         );
@@ -1473,7 +1813,8 @@ mod tests {
                     r#match: SearchQueryMatchData::Text("text".to_string()),
                     start: 21,
                     end: 25,
-                }]
+                }],
+                context: Vec::new(),
             }]
         );
 
@@ -1526,7 +1867,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 9,
                         end: 13,
-                    }]
+                    }],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file2.txt")).to_path_buf(),
@@ -1537,7 +1879,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 5,
                         end: 9,
-                    }]
+                    }],
+                    context: Vec::new(),
                 }
             ]
         );
@@ -1590,7 +1933,8 @@ mod tests {
                     r#match: SearchQueryMatchData::bytes([159]),
                     start: 0,
                     end: 1,
-                }]
+                }],
+                context: Vec::new(),
             },]
         );
 
@@ -1871,7 +2215,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 9,
                         end: 13,
-                    }]
+                    }],
+                    context: Vec::new(),
                 },
                 SearchQueryContentsMatch {
                     path: root.child(make_path("path/to/file2.txt")).to_path_buf(),
@@ -1882,7 +2227,8 @@ mod tests {
                         r#match: SearchQueryMatchData::Text("text".to_string()),
                         start: 5,
                         end: 9,
-                    }]
+                    }],
+                    context: Vec::new(),
                 }
             ]
         );
@@ -2016,4 +2362,514 @@ mod tests {
         )
         .await;
     }
+
+    /// Searches all paths under `root` using `options`, returning the sorted paths that matched
+    async fn search_paths(root: &assert_fs::TempDir, options: SearchQueryOptions) -> Vec<PathBuf> {
+        let state = SearchState::new();
+        let (reply, mut rx) = mpsc::channel(100);
+        let query = SearchQuery {
+            paths: vec![root.path().to_path_buf()],
+            target: SearchQueryTarget::Path,
+            condition: SearchQueryCondition::regex(".*"),
+            options,
+        };
+
+        let search_id = state.start(query, Box::new(reply)).await.unwrap();
+
+        let mut paths = Vec::new();
+        loop {
+            match rx.recv().await {
+                Some(Response::SearchResults { matches, .. }) => paths.extend(
+                    matches
+                        .into_iter()
+                        .filter_map(|m| m.into_path_match())
+                        .map(|m| m.path),
+                ),
+                Some(Response::SearchDone { id }) => {
+                    assert_eq!(id, search_id);
+                    break;
+                }
+                x => panic!("Unexpected response: {x:?}"),
+            }
+        }
+
+        paths.sort_unstable();
+        paths
+    }
+
+    #[test(tokio::test)]
+    async fn should_filter_searched_paths_by_file_size_if_specified() {
+        let root = setup_dir(vec![
+            ("empty.txt", ""),
+            ("small.txt", "abc"),
+            ("large.txt", "abcdefghijklmnopqrstuvwxyz"),
+        ]);
+
+        // Directories are excluded once a size bound is given
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    min_size: Some(1),
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![
+                root.child("large.txt").to_path_buf(),
+                root.child("small.txt").to_path_buf(),
+            ]
+        );
+
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    max_size: Some(3),
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![
+                root.child("empty.txt").to_path_buf(),
+                root.child("small.txt").to_path_buf(),
+            ]
+        );
+
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    min_size: Some(1),
+                    max_size: Some(3),
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![root.child("small.txt").to_path_buf()]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_filter_searched_paths_by_modification_time_if_specified() {
+        let root = setup_dir(vec![("file.txt", "")]);
+
+        let now = std::time::SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        // Everything was modified before an hour from now, and nothing after it
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    modified_before: Some(now + 3600),
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![root.to_path_buf(), root.child("file.txt").to_path_buf()]
+        );
+
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    modified_after: Some(now + 3600),
+                    ..Default::default()
+                }
+            )
+            .await,
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_only_search_hidden_paths_if_specified() {
+        let root = setup_dir(vec![("file.txt", ""), (".hidden.txt", "")]);
+
+        assert_eq!(
+            search_paths(&root, Default::default()).await,
+            vec![root.to_path_buf(), root.child("file.txt").to_path_buf()]
+        );
+
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    hidden: true,
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![
+                root.to_path_buf(),
+                root.child(".hidden.txt").to_path_buf(),
+                root.child("file.txt").to_path_buf(),
+            ]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_only_search_paths_excluded_by_ignore_files_if_no_ignore_specified() {
+        let root = setup_dir(vec![("file.txt", ""), ("ignored.txt", "")]);
+        root.child(".ignore").write_str("ignored.txt").unwrap();
+
+        assert_eq!(
+            search_paths(&root, Default::default()).await,
+            vec![root.to_path_buf(), root.child("file.txt").to_path_buf()]
+        );
+
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    no_ignore: true,
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![
+                root.to_path_buf(),
+                root.child("file.txt").to_path_buf(),
+                root.child("ignored.txt").to_path_buf(),
+            ]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_apply_custom_ignore_files_if_specified() {
+        let root = setup_dir(vec![("file.txt", ""), ("ignored.txt", "")]);
+
+        // Place the ignore file outside of the searched directory
+        let ignore_dir = assert_fs::TempDir::new().unwrap();
+        let ignore_file = ignore_dir.child("custom-ignore");
+        ignore_file.write_str("ignored.txt").unwrap();
+
+        assert_eq!(
+            search_paths(
+                &root,
+                SearchQueryOptions {
+                    ignore_files: vec![ignore_file.to_path_buf()],
+                    ..Default::default()
+                }
+            )
+            .await,
+            vec![root.to_path_buf(), root.child("file.txt").to_path_buf()]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_fail_to_start_search_if_custom_ignore_file_is_missing() {
+        let root = setup_dir(Vec::new());
+
+        let state = SearchState::new();
+        let (reply, _rx) = mpsc::channel(100);
+        let query = SearchQuery {
+            paths: vec![root.path().to_path_buf()],
+            target: SearchQueryTarget::Path,
+            condition: SearchQueryCondition::regex(".*"),
+            options: SearchQueryOptions {
+                ignore_files: vec![root.child("missing").to_path_buf()],
+                ..Default::default()
+            },
+        };
+
+        let err = state.start(query, Box::new(reply)).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{err}");
+    }
+
+    /// Searches the contents of all files under `root` using `condition` and `options`,
+    /// returning the matches sorted by path and line number
+    async fn search_contents(
+        root: &assert_fs::TempDir,
+        condition: SearchQueryCondition,
+        options: SearchQueryOptions,
+    ) -> Vec<SearchQueryContentsMatch> {
+        let state = SearchState::new();
+        let (reply, mut rx) = mpsc::channel(100);
+        let query = SearchQuery {
+            paths: vec![root.path().to_path_buf()],
+            target: SearchQueryTarget::Contents,
+            condition,
+            options,
+        };
+
+        let search_id = state.start(query, Box::new(reply)).await.unwrap();
+
+        let mut matches = get_matches(rx.recv().await.unwrap())
+            .into_iter()
+            .filter_map(|m| m.into_contents_match())
+            .collect::<Vec<_>>();
+        matches.sort_unstable_by_key(|m| (m.path.to_path_buf(), m.line_number));
+
+        let data = rx.recv().await;
+        assert_eq!(data, Some(Response::SearchDone { id: search_id }));
+
+        matches
+    }
+
+    #[test(tokio::test)]
+    async fn should_include_context_lines_around_matches_if_specified() {
+        let root = setup_dir(vec![(
+            "file.txt",
+            "one\ntwo\nthree\nfour\nfive\nsix\nseven",
+        )]);
+
+        let context_line =
+            |kind, line: &str, line_number, absolute_offset| SearchQueryContextLine {
+                kind,
+                line: SearchQueryMatchData::text(line),
+                line_number,
+                absolute_offset,
+            };
+
+        let matches = search_contents(
+            &root,
+            SearchQueryCondition::regex("three|four|seven"),
+            SearchQueryOptions {
+                before_context: Some(1),
+                after_context: Some(1),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        // Context shared by neighboring matches belongs to the earlier match
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.line_number, m.context.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    vec![context_line(SearchQueryContextKind::Before, "two\n", 2, 4)]
+                ),
+                (
+                    4,
+                    vec![context_line(SearchQueryContextKind::After, "five\n", 5, 19)]
+                ),
+                (
+                    7,
+                    vec![context_line(SearchQueryContextKind::Before, "six\n", 6, 24)]
+                ),
+            ]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_not_include_context_lines_by_default() {
+        let root = setup_dir(vec![("file.txt", "one\ntwo\nthree")]);
+
+        let matches = search_contents(
+            &root,
+            SearchQueryCondition::regex("two"),
+            Default::default(),
+        )
+        .await;
+
+        assert_eq!(matches.len(), 1, "{matches:?}");
+        assert_eq!(matches[0].context, Vec::new());
+    }
+
+    #[test(tokio::test)]
+    async fn should_support_matches_spanning_multiple_lines_if_multiline_specified() {
+        let root = setup_dir(vec![("file.txt", "one\ntwo\nthree\nfour")]);
+
+        // Without multiline, a pattern that crosses the end of a line is rejected
+        let state = SearchState::new();
+        let (reply, _rx) = mpsc::channel(100);
+        let query = SearchQuery {
+            paths: vec![root.path().to_path_buf()],
+            target: SearchQueryTarget::Contents,
+            condition: SearchQueryCondition::regex("two\nthree"),
+            options: Default::default(),
+        };
+        let err = state.start(query, Box::new(reply)).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{err}");
+
+        let matches = search_contents(
+            &root,
+            SearchQueryCondition::regex("two\nthree"),
+            SearchQueryOptions {
+                multiline: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(
+            matches,
+            vec![SearchQueryContentsMatch {
+                path: root.child("file.txt").to_path_buf(),
+                lines: SearchQueryMatchData::text("two\nthree\n"),
+                line_number: 2,
+                absolute_offset: 4,
+                submatches: vec![SearchQuerySubmatch::new("two\nthree", 0, 9)],
+                context: Vec::new(),
+            }]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_only_match_files_whose_contents_meet_and_and_not_conditions() {
+        let root = setup_dir(vec![
+            ("foo.txt", "foo\nbaz\n"),
+            ("foo-bar.txt", "foo\nbar\n"),
+            ("foo-baz.txt", "baz foo\nother\n"),
+            ("bar.txt", "bar\n"),
+        ]);
+
+        let matches = search_contents(
+            &root,
+            SearchQueryCondition::and([
+                SearchQueryCondition::contains("foo"),
+                SearchQueryCondition::not(SearchQueryCondition::contains("bar")),
+            ]),
+            Default::default(),
+        )
+        .await;
+
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.path.to_path_buf(), m.lines.to_string_lossy().into_owned()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    root.child("foo-baz.txt").to_path_buf(),
+                    String::from("baz foo\n")
+                ),
+                (root.child("foo.txt").to_path_buf(), String::from("foo\n")),
+            ]
+        );
+        assert_eq!(
+            matches[0].submatches,
+            vec![SearchQuerySubmatch::new("foo", 4, 7)]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_skip_binary_files_found_while_evaluating_and_and_not_conditions() {
+        let root = setup_dir(vec![("foo.txt", "foo\n"), ("foo.bin", "foo\n\0")]);
+
+        let matches = search_contents(
+            &root,
+            SearchQueryCondition::and([
+                SearchQueryCondition::contains("foo"),
+                SearchQueryCondition::not(SearchQueryCondition::contains("bar")),
+            ]),
+            Default::default(),
+        )
+        .await;
+
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| m.path.to_path_buf())
+                .collect::<Vec<_>>(),
+            vec![root.child("foo.txt").to_path_buf()]
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_only_match_paths_that_meet_and_and_not_conditions() {
+        let root = setup_dir(vec![("file1.txt", ""), ("file2.txt", ""), ("file1.md", "")]);
+
+        let state = SearchState::new();
+        let (reply, mut rx) = mpsc::channel(100);
+        let query = SearchQuery {
+            paths: vec![root.path().to_path_buf()],
+            target: SearchQueryTarget::Path,
+            condition: SearchQueryCondition::and([
+                SearchQueryCondition::ends_with(".txt"),
+                SearchQueryCondition::not(SearchQueryCondition::contains("file2")),
+            ]),
+            options: Default::default(),
+        };
+
+        let search_id = state.start(query, Box::new(reply)).await.unwrap();
+
+        let matches = get_matches(rx.recv().await.unwrap())
+            .into_iter()
+            .filter_map(|m| m.into_path_match())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            vec![SearchQueryPathMatch {
+                path: root.child("file1.txt").to_path_buf(),
+                submatches: vec![SearchQuerySubmatch::new(
+                    ".txt",
+                    root.child("file1.txt").to_string_lossy().len() as u64 - 4,
+                    root.child("file1.txt").to_string_lossy().len() as u64,
+                )],
+            }]
+        );
+
+        let data = rx.recv().await;
+        assert_eq!(data, Some(Response::SearchDone { id: search_id }));
+    }
+
+    #[test(tokio::test)]
+    async fn should_report_paths_without_submatches_if_condition_is_only_negated() {
+        let root = setup_dir(vec![("file1.txt", ""), ("file2.txt", "")]);
+
+        let state = SearchState::new();
+        let (reply, mut rx) = mpsc::channel(100);
+        let query = SearchQuery {
+            paths: vec![root.path().to_path_buf()],
+            target: SearchQueryTarget::Path,
+            condition: SearchQueryCondition::not(SearchQueryCondition::ends_with("file2.txt")),
+            options: SearchQueryOptions {
+                allowed_file_types: vec![FileType::File].into_iter().collect(),
+                ..Default::default()
+            },
+        };
+
+        let search_id = state.start(query, Box::new(reply)).await.unwrap();
+
+        let matches = get_matches(rx.recv().await.unwrap())
+            .into_iter()
+            .filter_map(|m| m.into_path_match())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            vec![SearchQueryPathMatch {
+                path: root.child("file1.txt").to_path_buf(),
+                submatches: Vec::new(),
+            }]
+        );
+
+        let data = rx.recv().await;
+        assert_eq!(data, Some(Response::SearchDone { id: search_id }));
+    }
+
+    #[test(tokio::test)]
+    async fn should_support_and_and_not_conditions_when_filtering_searched_paths() {
+        let root = setup_dir(vec![
+            ("src/lib.rs", "hello"),
+            ("src/tests.rs", "hello"),
+            ("README.md", "hello"),
+        ]);
+
+        let matches = search_contents(
+            &root,
+            SearchQueryCondition::contains("hello"),
+            SearchQueryOptions {
+                include: Some(SearchQueryCondition::and([
+                    SearchQueryCondition::ends_with(".rs"),
+                    SearchQueryCondition::not(SearchQueryCondition::contains("tests")),
+                ])),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(
+            matches.into_iter().map(|m| m.path).collect::<Vec<_>>(),
+            vec![root.child("src").child("lib.rs").to_path_buf()]
+        );
+    }
 }
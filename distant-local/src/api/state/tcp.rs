@@ -0,0 +1,715 @@
+use std::collections::HashMap;
+use std::io;
+use std::ops::Deref;
+
+use distant_core::net::common::ConnectionId;
+use distant_core::net::server::Reply;
+use distant_core::protocol::{Response, TcpListenerId, TcpStreamId};
+use log::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::constants::{MAX_TCP_CHUNK_SIZE, TCP_LISTENER_CHECK_INTERVAL, TCP_WRITE_QUEUE_SIZE};
+
+/// Holds information related to forwarded tcp streams and listeners on the server
+pub struct TcpState {
+    channel: TcpChannel,
+    task: JoinHandle<()>,
+}
+
+impl Drop for TcpState {
+    /// Aborts the task that handles tcp operations and management
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+impl TcpState {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel(1);
+        let task = tokio::spawn(tcp_task(tx.clone(), rx));
+
+        Self {
+            channel: TcpChannel { tx },
+            task,
+        }
+    }
+
+    /// Aborts the tcp task
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl Deref for TcpState {
+    type Target = TcpChannel;
+
+    fn deref(&self) -> &Self::Target {
+        &self.channel
+    }
+}
+
+#[derive(Clone)]
+pub struct TcpChannel {
+    tx: mpsc::Sender<InnerTcpMsg>,
+}
+
+impl Default for TcpChannel {
+    /// Creates a new channel that is closed by default
+    fn default() -> Self {
+        let (tx, _) = mpsc::channel(1);
+        Self { tx }
+    }
+}
+
+impl TcpChannel {
+    /// Connects to `host` and `port` for the connection with `connection_id`, returning the id of
+    /// the new stream whose data will be sent out using `reply`
+    pub async fn connect(
+        &self,
+        connection_id: ConnectionId,
+        host: String,
+        port: u16,
+        reply: Box<dyn Reply<Data = Response>>,
+    ) -> io::Result<TcpStreamId> {
+        // NOTE: Connecting happens outside of the task so a slow peer does not hold up others
+        let stream = TcpStream::connect((host.as_str(), port)).await?;
+
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerTcpMsg::Connect {
+                connection_id,
+                stream,
+                reply,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal tcp task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to connect dropped"))?
+    }
+
+    /// Listens on `host` and `port` for the connection with `connection_id`, returning the id of
+    /// the new listener and the port it is bound to; streams accepted by the listener and their
+    /// data will be sent out using `reply`, and the listener stops once `reply` is closed
+    pub async fn listen(
+        &self,
+        connection_id: ConnectionId,
+        host: String,
+        port: u16,
+        reply: Box<dyn Reply<Data = Response>>,
+    ) -> io::Result<(TcpListenerId, u16)> {
+        let listener = TcpListener::bind((host.as_str(), port)).await?;
+
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerTcpMsg::Listen {
+                connection_id,
+                listener,
+                reply,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal tcp task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to listen dropped"))?
+    }
+
+    /// Queues `data` to be written out through a stream held by the connection with
+    /// `connection_id`, waiting only if too many writes are already queued up for that stream
+    pub async fn write(
+        &self,
+        connection_id: ConnectionId,
+        id: TcpStreamId,
+        data: Vec<u8>,
+    ) -> io::Result<()> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerTcpMsg::GetWriter {
+                connection_id,
+                id,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal tcp task closed"))?;
+        let writer = rx
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to write dropped"))??;
+
+        // NOTE: Queueing happens outside of the task so a stalled peer does not hold up others
+        writer.send(data).await.map_err(|_| {
+            io::Error::new(io::ErrorKind::BrokenPipe, format!("Tcp stream {id} closed"))
+        })
+    }
+
+    /// Closes a stream held by the connection with `connection_id`
+    pub async fn close(&self, connection_id: ConnectionId, id: TcpStreamId) -> io::Result<()> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerTcpMsg::Close {
+                connection_id,
+                id,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal tcp task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to close dropped"))?
+    }
+
+    /// Stops a listener held by the connection with `connection_id` from accepting any more
+    /// streams
+    pub async fn unlisten(&self, connection_id: ConnectionId, id: TcpListenerId) -> io::Result<()> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerTcpMsg::Unlisten {
+                connection_id,
+                id,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal tcp task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to unlisten dropped"))?
+    }
+}
+
+/// Internal message to pass to our task below to perform some action
+enum InnerTcpMsg {
+    Connect {
+        connection_id: ConnectionId,
+        stream: TcpStream,
+        reply: Box<dyn Reply<Data = Response>>,
+        cb: oneshot::Sender<io::Result<TcpStreamId>>,
+    },
+    Listen {
+        connection_id: ConnectionId,
+        listener: TcpListener,
+        reply: Box<dyn Reply<Data = Response>>,
+        cb: oneshot::Sender<io::Result<(TcpListenerId, u16)>>,
+    },
+    GetWriter {
+        connection_id: ConnectionId,
+        id: TcpStreamId,
+        cb: oneshot::Sender<io::Result<mpsc::Sender<Vec<u8>>>>,
+    },
+    Close {
+        connection_id: ConnectionId,
+        id: TcpStreamId,
+        cb: oneshot::Sender<io::Result<()>>,
+    },
+    Unlisten {
+        connection_id: ConnectionId,
+        id: TcpListenerId,
+        cb: oneshot::Sender<io::Result<()>>,
+    },
+    InternalAccept {
+        connection_id: ConnectionId,
+        id: TcpStreamId,
+        stream: TcpStream,
+        reply: Box<dyn Reply<Data = Response>>,
+    },
+    InternalRemove {
+        id: TcpStreamId,
+    },
+    InternalUnlisten {
+        id: TcpListenerId,
+    },
+}
+
+async fn tcp_task(tx: mpsc::Sender<InnerTcpMsg>, mut rx: mpsc::Receiver<InnerTcpMsg>) {
+    let mut streams: HashMap<TcpStreamId, TcpStreamInstance> = HashMap::new();
+    let mut listeners: HashMap<TcpListenerId, TcpListenerInstance> = HashMap::new();
+
+    while let Some(msg) = rx.recv().await {
+        match msg {
+            InnerTcpMsg::Connect {
+                connection_id,
+                stream,
+                reply,
+                cb,
+            } => {
+                let id = rand::random();
+                let stream = TcpStreamInstance::spawn(id, connection_id, stream, reply, tx.clone());
+                streams.insert(id, stream);
+                let _ = cb.send(Ok(id));
+            }
+            InnerTcpMsg::Listen {
+                connection_id,
+                listener,
+                reply,
+                cb,
+            } => {
+                let _ = cb.send(match listener.local_addr() {
+                    Ok(addr) => {
+                        let id = rand::random();
+                        listeners.insert(
+                            id,
+                            TcpListenerInstance::spawn(
+                                id,
+                                connection_id,
+                                listener,
+                                reply,
+                                tx.clone(),
+                            ),
+                        );
+                        Ok((id, addr.port()))
+                    }
+                    Err(x) => Err(x),
+                });
+            }
+            InnerTcpMsg::GetWriter {
+                connection_id,
+                id,
+                cb,
+            } => {
+                let _ = cb.send(match streams.get(&id) {
+                    Some(stream) if stream.connection_id == connection_id => {
+                        Ok(stream.writer.clone())
+                    }
+                    Some(_) => Err(held_by_another_connection("stream", id)),
+                    None => Err(missing_stream(id)),
+                });
+            }
+            InnerTcpMsg::Close {
+                connection_id,
+                id,
+                cb,
+            } => {
+                let _ = cb.send(match streams.get(&id) {
+                    Some(stream) if stream.connection_id == connection_id => {
+                        remove_stream(&mut streams, id);
+                        Ok(())
+                    }
+                    Some(_) => Err(held_by_another_connection("stream", id)),
+                    None => Err(missing_stream(id)),
+                });
+            }
+            InnerTcpMsg::Unlisten {
+                connection_id,
+                id,
+                cb,
+            } => {
+                let _ = cb.send(match listeners.get(&id) {
+                    Some(listener) if listener.connection_id == connection_id => {
+                        listeners.remove(&id);
+                        Ok(())
+                    }
+                    Some(_) => Err(held_by_another_connection("listener", id)),
+                    None => Err(missing_listener(id)),
+                });
+            }
+            // NOTE: The stream is tracked even if its listener has since been removed as the
+            //       client was already told about it
+            InnerTcpMsg::InternalAccept {
+                connection_id,
+                id,
+                stream,
+                reply,
+            } => {
+                let stream = TcpStreamInstance::spawn(id, connection_id, stream, reply, tx.clone());
+                streams.insert(id, stream);
+            }
+            InnerTcpMsg::InternalRemove { id } => {
+                remove_stream(&mut streams, id);
+            }
+            InnerTcpMsg::InternalUnlisten { id } => {
+                listeners.remove(&id);
+            }
+        }
+    }
+}
+
+/// Stops tracking a stream, letting its queued writes finish before it is shut down and telling
+/// its connection that it has closed
+fn remove_stream(streams: &mut HashMap<TcpStreamId, TcpStreamInstance>, id: TcpStreamId) {
+    if let Some(stream) = streams.remove(&id) {
+        // NOTE: Sending happens outside of the task so a slow client does not hold up others
+        let reply = stream.reply.clone_reply();
+        tokio::spawn(async move {
+            let _ = reply.send(Response::TcpClosed { id }).await;
+        });
+    }
+}
+
+fn missing_stream(id: TcpStreamId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        format!("No tcp stream found with id {id}"),
+    )
+}
+
+fn missing_listener(id: TcpListenerId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Other,
+        format!("No tcp listener found with id {id}"),
+    )
+}
+
+fn held_by_another_connection(kind: &str, id: u32) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!("Tcp {kind} {id} is held by another connection"),
+    )
+}
+
+/// Represents an open tcp stream whose received data is sent out using a reply and whose writes
+/// are queued up for a task of its own
+struct TcpStreamInstance {
+    connection_id: ConnectionId,
+    writer: mpsc::Sender<Vec<u8>>,
+    reply: Box<dyn Reply<Data = Response>>,
+    task: JoinHandle<()>,
+}
+
+impl TcpStreamInstance {
+    /// Spawns a task that reads from `stream`, sending each chunk of data out using `reply`, and
+    /// a task that writes queued data to `stream`, shutting it down once the queue is dropped
+    fn spawn(
+        id: TcpStreamId,
+        connection_id: ConnectionId,
+        stream: TcpStream,
+        reply: Box<dyn Reply<Data = Response>>,
+        tx: mpsc::Sender<InnerTcpMsg>,
+    ) -> Self {
+        let (mut reader, writer) = stream.into_split();
+        let task = tokio::spawn({
+            let reply = reply.clone_reply();
+            let tx = tx.clone();
+            async move {
+                let mut buf = vec![0; MAX_TCP_CHUNK_SIZE];
+                loop {
+                    match reader.read(&mut buf).await {
+                        Ok(0) => break,
+                        Ok(n) => {
+                            let data = buf[..n].to_vec();
+                            if let Err(x) = reply.send(Response::TcpData { id, data }).await {
+                                error!("Tcp stream {id} failed to send data: {x}");
+                                break;
+                            }
+                        }
+                        Err(x) => {
+                            error!("Tcp stream {id} failed to read: {x}");
+                            break;
+                        }
+                    }
+                }
+
+                let _ = tx.send(InnerTcpMsg::InternalRemove { id }).await;
+            }
+        });
+
+        // NOTE: The writer task is not aborted alongside the reader so queued data still reaches
+        //       the peer; it ends on its own once every sender of the queue has been dropped
+        let (queue_tx, queue_rx) = mpsc::channel(TCP_WRITE_QUEUE_SIZE);
+        tokio::spawn(write_task(id, writer, queue_rx, tx));
+
+        Self {
+            connection_id,
+            writer: queue_tx,
+            reply,
+            task,
+        }
+    }
+}
+
+impl Drop for TcpStreamInstance {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Writes queued data out to a stream, shutting the stream down once the queue closes or asking
+/// for the stream to be removed if a write fails
+async fn write_task(
+    id: TcpStreamId,
+    mut writer: OwnedWriteHalf,
+    mut rx: mpsc::Receiver<Vec<u8>>,
+    tx: mpsc::Sender<InnerTcpMsg>,
+) {
+    while let Some(data) = rx.recv().await {
+        if let Err(x) = writer.write_all(&data).await {
+            error!("Tcp stream {id} failed to write: {x}");
+            let _ = tx.send(InnerTcpMsg::InternalRemove { id }).await;
+            return;
+        }
+    }
+
+    let _ = writer.shutdown().await;
+}
+
+/// Represents a tcp listener whose accepted streams are passed back to the tcp task
+struct TcpListenerInstance {
+    connection_id: ConnectionId,
+    task: JoinHandle<()>,
+}
+
+impl TcpListenerInstance {
+    /// Spawns a task that accepts streams from `listener`, announcing each using `reply`, until
+    /// dropped or until `reply` is closed
+    fn spawn(
+        id: TcpListenerId,
+        connection_id: ConnectionId,
+        listener: TcpListener,
+        reply: Box<dyn Reply<Data = Response>>,
+        tx: mpsc::Sender<InnerTcpMsg>,
+    ) -> Self {
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TCP_LISTENER_CHECK_INTERVAL);
+
+            loop {
+                let (stream, addr) = tokio::select! {
+                    res = listener.accept() => match res {
+                        Ok(x) => x,
+                        Err(x) => {
+                            error!("Tcp listener {id} failed to accept: {x}");
+                            break;
+                        }
+                    },
+                    _ = interval.tick() => {
+                        // If the client is no longer around, there is no point in listening
+                        if reply.is_closed() {
+                            debug!("Tcp listener {id} closing as its connection is gone");
+                            break;
+                        }
+                        continue;
+                    }
+                };
+
+                // Announce the stream before spawning its reader so the client knows about
+                // it prior to receiving any of its data
+                let stream_id = rand::random();
+                let res = reply
+                    .send(Response::TcpAccepted {
+                        listener: id,
+                        id: stream_id,
+                        peer_addr: addr.to_string(),
+                    })
+                    .await;
+
+                if let Err(x) = res {
+                    warn!("Tcp listener {id} failed to announce stream: {x}");
+                    break;
+                }
+
+                let msg = InnerTcpMsg::InternalAccept {
+                    connection_id,
+                    id: stream_id,
+                    stream,
+                    reply: reply.clone_reply(),
+                };
+
+                if tx.send(msg).await.is_err() {
+                    return;
+                }
+            }
+
+            let _ = tx.send(InnerTcpMsg::InternalUnlisten { id }).await;
+        });
+
+        Self {
+            connection_id,
+            task,
+        }
+    }
+}
+
+impl Drop for TcpListenerInstance {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    fn make_reply() -> (Box<dyn Reply<Data = Response>>, mpsc::Receiver<Response>) {
+        let (tx, rx) = mpsc::channel(100);
+        (Box::new(tx), rx)
+    }
+
+    #[test(tokio::test)]
+    async fn connect_should_forward_data_in_both_directions() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let state = TcpState::new();
+        let (reply, mut rx) = make_reply();
+        let id = state
+            .connect(0, String::from("127.0.0.1"), port, reply)
+            .await
+            .unwrap();
+        let (mut peer, _) = listener.accept().await.unwrap();
+
+        // Data written to the stream should arrive at the peer
+        state.write(0, id, b"hello".to_vec()).await.unwrap();
+        let mut buf = [0; 5];
+        peer.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+
+        // Data sent by the peer should be sent out as a reply
+        peer.write_all(b"world").await.unwrap();
+        match rx.recv().await.unwrap() {
+            Response::TcpData { id: x, data } => {
+                assert_eq!(x, id);
+                assert_eq!(data, b"world");
+            }
+            x => panic!("Unexpected response: {x:?}"),
+        }
+
+        // Closing the peer should close the stream
+        drop(peer);
+        match rx.recv().await.unwrap() {
+            Response::TcpClosed { id: x } => assert_eq!(x, id),
+            x => panic!("Unexpected response: {x:?}"),
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn close_should_fail_if_stream_missing() {
+        let state = TcpState::new();
+        let _ = state.close(0, 12345).await.unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn listen_should_announce_accepted_streams() {
+        let state = TcpState::new();
+        let (reply, mut rx) = make_reply();
+        let (listener_id, port) = state
+            .listen(0, String::from("127.0.0.1"), 0, reply)
+            .await
+            .unwrap();
+        assert_ne!(port, 0);
+
+        let mut peer = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let id = match rx.recv().await.unwrap() {
+            Response::TcpAccepted { listener, id, .. } => {
+                assert_eq!(listener, listener_id);
+                id
+            }
+            x => panic!("Unexpected response: {x:?}"),
+        };
+
+        peer.write_all(b"hello").await.unwrap();
+        match rx.recv().await.unwrap() {
+            Response::TcpData { id: x, data } => {
+                assert_eq!(x, id);
+                assert_eq!(data, b"hello");
+            }
+            x => panic!("Unexpected response: {x:?}"),
+        }
+
+        // Closing the stream from our side should shut it down for the peer
+        state.close(0, id).await.unwrap();
+        let mut buf = Vec::new();
+        peer.read_to_end(&mut buf).await.unwrap();
+        assert!(buf.is_empty());
+
+        match rx.recv().await.unwrap() {
+            Response::TcpClosed { id: x } => assert_eq!(x, id),
+            x => panic!("Unexpected response: {x:?}"),
+        }
+
+        state.unlisten(0, listener_id).await.unwrap();
+        let _ = state.unlisten(0, listener_id).await.unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn streams_and_listeners_should_only_be_usable_by_their_connection() {
+        let state = TcpState::new();
+        let (reply, mut rx) = make_reply();
+        let (listener_id, port) = state
+            .listen(0, String::from("127.0.0.1"), 0, reply)
+            .await
+            .unwrap();
+
+        let _peer = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+        let id = match rx.recv().await.unwrap() {
+            Response::TcpAccepted { id, .. } => id,
+            x => panic!("Unexpected response: {x:?}"),
+        };
+
+        let err = state.write(1, id, b"hello".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = state.close(1, id).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = state.unlisten(1, listener_id).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        state.write(0, id, b"hello".to_vec()).await.unwrap();
+        state.close(0, id).await.unwrap();
+        state.unlisten(0, listener_id).await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn listener_should_stop_once_its_reply_is_closed() {
+        let state = TcpState::new();
+        let (reply, rx) = make_reply();
+        let (listener_id, port) = state
+            .listen(0, String::from("127.0.0.1"), 0, reply)
+            .await
+            .unwrap();
+
+        // Dropping the receiving side is what happens once the client disconnects
+        drop(rx);
+
+        let start = std::time::Instant::now();
+        while TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            assert!(
+                start.elapsed() < TCP_LISTENER_CHECK_INTERVAL * 5,
+                "Listener still bound"
+            );
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+
+        let _ = state.unlisten(0, listener_id).await.unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn write_should_not_be_held_up_by_another_stalled_stream() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let state = TcpState::new();
+        let (reply, _rx) = make_reply();
+        let stalled = state
+            .connect(0, String::from("127.0.0.1"), port, reply)
+            .await
+            .unwrap();
+        let (_stalled_peer, _) = listener.accept().await.unwrap();
+
+        let (reply, _rx) = make_reply();
+        let id = state
+            .connect(0, String::from("127.0.0.1"), port, reply)
+            .await
+            .unwrap();
+        let (mut peer, _) = listener.accept().await.unwrap();
+
+        // Keep writing to the stream whose peer never reads until its queue fills up
+        let chunk = vec![0; 1024 * 1024];
+        let mut filled = false;
+        for _ in 0..1000 {
+            let res = tokio::time::timeout(
+                std::time::Duration::from_millis(100),
+                state.write(0, stalled, chunk.clone()),
+            )
+            .await;
+            if res.is_err() {
+                filled = true;
+                break;
+            }
+        }
+        assert!(filled, "Stalled stream never filled up");
+
+        state.write(0, id, b"hello".to_vec()).await.unwrap();
+        let mut buf = [0; 5];
+        peer.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+    }
+}
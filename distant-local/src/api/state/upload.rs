@@ -0,0 +1,736 @@
+use std::collections::HashMap;
+use std::io::{self, SeekFrom};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use distant_core::net::common::ConnectionId;
+use distant_core::protocol::{HashAlgorithm, UploadId};
+use log::*;
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{mpsc, oneshot, Mutex, OwnedMutexGuard};
+use tokio::task::JoinHandle;
+
+use crate::api::hash::hash_file;
+
+/// Suffix added to the name of a file to produce the name of its partial upload
+const PARTIAL_UPLOAD_SUFFIX: &str = "distant-upload";
+
+/// Time that an upload can go unused before it is discarded along with its partial file
+const UPLOAD_IDLE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum time between checks for uploads that have gone unused
+const UPLOAD_EXPIRE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Upload shared between the task that tracks uploads and the connections operating on it, which
+/// is none once it has been committed or discarded
+type SharedUpload = Arc<Mutex<Option<UploadInstance>>>;
+
+/// Holds information related to active uploads on the server
+pub struct UploadState {
+    channel: UploadChannel,
+    task: JoinHandle<()>,
+}
+
+impl Drop for UploadState {
+    /// Aborts the task that handles upload management
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+impl UploadState {
+    pub fn new() -> Self {
+        Self::with_idle_timeout(UPLOAD_IDLE_TIMEOUT)
+    }
+
+    /// Creates a new state where uploads that go unused for `idle_timeout` are discarded
+    pub fn with_idle_timeout(idle_timeout: Duration) -> Self {
+        let (tx, rx) = mpsc::channel(1);
+        let task = tokio::spawn(upload_task(idle_timeout, rx));
+
+        Self {
+            channel: UploadChannel { tx },
+            task,
+        }
+    }
+
+    /// Aborts the upload task
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+impl Deref for UploadState {
+    type Target = UploadChannel;
+
+    fn deref(&self) -> &Self::Target {
+        &self.channel
+    }
+}
+
+#[derive(Clone)]
+pub struct UploadChannel {
+    tx: mpsc::Sender<InnerUploadMsg>,
+}
+
+impl Default for UploadChannel {
+    /// Creates a new channel that is closed by default
+    fn default() -> Self {
+        let (tx, _) = mpsc::channel(1);
+        Self { tx }
+    }
+}
+
+impl UploadChannel {
+    /// Opens an upload of the file at `path` for the connection with `connection_id`, returning
+    /// the id of the upload and the offset where it should continue. Resuming an upload that is
+    /// already open hands it over to the connection.
+    pub async fn open(
+        &self,
+        connection_id: ConnectionId,
+        path: PathBuf,
+        resume: bool,
+    ) -> io::Result<(UploadId, u64)> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerUploadMsg::Open {
+                connection_id,
+                path: path.clone(),
+                resume,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal upload task closed"))?;
+        let (id, opened) = rx
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to open dropped"))?;
+
+        match opened {
+            Opened::Existing(upload) => match upload.lock().await.as_mut() {
+                Some(upload) => {
+                    upload.last_active = Instant::now();
+                    Ok((id, upload.offset))
+                }
+                None => Err(missing_upload(id)),
+            },
+
+            // NOTE: The new upload stays locked until its partial file is ready, which keeps
+            //       anyone else from using it or discarding it in the meantime
+            Opened::New {
+                mut guard,
+                replaced,
+            } => {
+                if let Some(upload) = replaced {
+                    if let Some(upload) = upload.lock().await.take() {
+                        if let Err(x) = upload.discard().await {
+                            warn!("Failed to discard upload of {path:?}: {x}");
+                        }
+                    }
+                }
+
+                match UploadInstance::open(path, resume).await {
+                    Ok(upload) => {
+                        let offset = upload.offset;
+                        *guard = Some(upload);
+                        Ok((id, offset))
+                    }
+                    Err(x) => {
+                        drop(guard);
+                        self.remove(id).await;
+                        Err(x)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes `data` at `offset` within an upload held by the connection with `connection_id`,
+    /// returning the offset confirmed as written
+    pub async fn write(
+        &self,
+        connection_id: ConnectionId,
+        id: UploadId,
+        offset: u64,
+        data: Vec<u8>,
+    ) -> io::Result<u64> {
+        let upload = self.get(connection_id, id).await?;
+        let mut upload = upload.lock().await;
+        match upload.as_mut() {
+            Some(upload) => upload.write(offset, &data).await,
+            None => Err(missing_upload(id)),
+        }
+    }
+
+    /// Commits an upload held by the connection with `connection_id`, moving the file into place
+    /// if it matches the optional checksum and setting its last modification time if provided
+    pub async fn commit(
+        &self,
+        connection_id: ConnectionId,
+        id: UploadId,
+        checksum: Option<String>,
+        modified: Option<u64>,
+    ) -> io::Result<()> {
+        let upload = self.get(connection_id, id).await?;
+        let mut guard = upload.lock().await;
+
+        // NOTE: Only take the upload once its contents are verified so a failed checksum can
+        //       still be aborted or retried by the client
+        match guard.as_mut() {
+            Some(upload) => upload.prepare(checksum.as_deref(), modified).await?,
+            None => return Err(missing_upload(id)),
+        }
+
+        let upload = guard.take().ok_or_else(|| missing_upload(id))?;
+        drop(guard);
+        self.remove(id).await;
+        upload.persist().await
+    }
+
+    /// Aborts an upload held by the connection with `connection_id`, removing any
+    /// partially-uploaded data
+    pub async fn discard(&self, connection_id: ConnectionId, id: UploadId) -> io::Result<()> {
+        let upload = self.get(connection_id, id).await?;
+        let upload = upload.lock().await.take();
+        self.remove(id).await;
+
+        match upload {
+            Some(upload) => upload.discard().await,
+            None => Err(missing_upload(id)),
+        }
+    }
+
+    /// Looks up an upload, failing if it is not held by the connection with `connection_id`
+    async fn get(&self, connection_id: ConnectionId, id: UploadId) -> io::Result<SharedUpload> {
+        let (cb, rx) = oneshot::channel();
+        self.tx
+            .send(InnerUploadMsg::Get {
+                connection_id,
+                id,
+                cb,
+            })
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Internal upload task closed"))?;
+        rx.await
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Response to get dropped"))?
+    }
+
+    /// Stops tracking an upload
+    async fn remove(&self, id: UploadId) {
+        let _ = self.tx.send(InnerUploadMsg::InternalRemove { id }).await;
+    }
+}
+
+/// Upload handed back when opening a path
+enum Opened {
+    /// Upload that was already open for the path
+    Existing(SharedUpload),
+
+    /// Newly-tracked upload that is locked until its partial file is opened, alongside the
+    /// upload it replaced that needs to be discarded first
+    New {
+        guard: OwnedMutexGuard<Option<UploadInstance>>,
+        replaced: Option<SharedUpload>,
+    },
+}
+
+/// Internal message to pass to our task below to perform some action
+enum InnerUploadMsg {
+    Open {
+        connection_id: ConnectionId,
+        path: PathBuf,
+        resume: bool,
+        cb: oneshot::Sender<(UploadId, Opened)>,
+    },
+    Get {
+        connection_id: ConnectionId,
+        id: UploadId,
+        cb: oneshot::Sender<io::Result<SharedUpload>>,
+    },
+    InternalRemove {
+        id: UploadId,
+    },
+}
+
+/// Upload tracked by our task below
+struct UploadEntry {
+    /// Path where the file will be placed once committed
+    path: PathBuf,
+
+    /// Connection that is allowed to operate on the upload
+    connection_id: ConnectionId,
+
+    upload: SharedUpload,
+}
+
+async fn upload_task(idle_timeout: Duration, mut rx: mpsc::Receiver<InnerUploadMsg>) {
+    let mut uploads: HashMap<UploadId, UploadEntry> = HashMap::new();
+    let mut interval = tokio::time::interval(idle_timeout.min(UPLOAD_EXPIRE_INTERVAL));
+
+    loop {
+        let msg = tokio::select! {
+            msg = rx.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            _ = interval.tick() => {
+                expire_idle_uploads(&mut uploads, idle_timeout);
+                continue;
+            }
+        };
+
+        match msg {
+            InnerUploadMsg::Open {
+                connection_id,
+                path,
+                resume,
+                cb,
+            } => {
+                // Uploads outlive the connection that opened them, so look for one that is
+                // already open for the same path before going to the filesystem
+                let existing = uploads
+                    .iter()
+                    .find(|(_, entry)| entry.path == path)
+                    .map(|(id, _)| *id);
+
+                let opened = match existing {
+                    Some(id) if resume => {
+                        let entry = uploads.get_mut(&id).unwrap();
+                        entry.connection_id = connection_id;
+                        (id, Opened::Existing(Arc::clone(&entry.upload)))
+                    }
+                    _ => {
+                        let replaced = existing
+                            .and_then(|id| uploads.remove(&id))
+                            .map(|entry| entry.upload);
+
+                        let id = rand::random();
+                        let upload = Arc::new(Mutex::new(None));
+                        let guard = Arc::clone(&upload)
+                            .try_lock_owned()
+                            .expect("New upload is already locked");
+                        uploads.insert(
+                            id,
+                            UploadEntry {
+                                path,
+                                connection_id,
+                                upload,
+                            },
+                        );
+                        (id, Opened::New { guard, replaced })
+                    }
+                };
+
+                let _ = cb.send(opened);
+            }
+            InnerUploadMsg::Get {
+                connection_id,
+                id,
+                cb,
+            } => {
+                let _ = cb.send(match uploads.get(&id) {
+                    Some(entry) if entry.connection_id == connection_id => {
+                        Ok(Arc::clone(&entry.upload))
+                    }
+                    Some(_) => Err(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        format!("Upload {id} is held by another connection"),
+                    )),
+                    None => Err(missing_upload(id)),
+                });
+            }
+            InnerUploadMsg::InternalRemove { id } => {
+                uploads.remove(&id);
+            }
+        }
+    }
+}
+
+/// Stops tracking uploads that have gone unused for `idle_timeout`, discarding them in the
+/// background
+fn expire_idle_uploads(uploads: &mut HashMap<UploadId, UploadEntry>, idle_timeout: Duration) {
+    // NOTE: An upload that is locked is in use, so we only check the ones we can lock
+    let expired: Vec<UploadId> = uploads
+        .iter()
+        .filter(|(_, entry)| match entry.upload.try_lock() {
+            Ok(upload) => !matches!(
+                upload.as_ref(),
+                Some(upload) if upload.last_active.elapsed() < idle_timeout
+            ),
+            Err(_) => false,
+        })
+        .map(|(id, _)| *id)
+        .collect();
+
+    for id in expired {
+        let entry = uploads.remove(&id).unwrap();
+        debug!("Upload {id} of {:?} expired", entry.path);
+        tokio::spawn(async move {
+            if let Some(upload) = entry.upload.lock().await.take() {
+                if let Err(x) = upload.discard().await {
+                    warn!("Failed to discard expired upload of {:?}: {x}", entry.path);
+                }
+            }
+        });
+    }
+}
+
+fn missing_upload(id: UploadId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No upload found with id {id}"),
+    )
+}
+
+/// Represents a file being uploaded, whose contents are written to a partial file next to the
+/// destination until committed
+struct UploadInstance {
+    /// Path where the file will be placed once committed
+    path: PathBuf,
+
+    /// Path to the partial file receiving the uploaded contents
+    partial_path: PathBuf,
+
+    /// Handle to the partial file
+    file: File,
+
+    /// Offset (in bytes) of data confirmed as written
+    offset: u64,
+
+    /// When the upload was last opened or written to
+    last_active: Instant,
+}
+
+impl UploadInstance {
+    /// Opens the partial file for `path`, keeping any existing contents if `resume` is true
+    async fn open(path: PathBuf, resume: bool) -> io::Result<Self> {
+        let partial_path = partial_path(&path)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .truncate(!resume)
+            .open(&partial_path)
+            .await?;
+        let offset = file.metadata().await?.len();
+
+        debug!("Opened upload of {path:?} at offset {offset}");
+        Ok(Self {
+            path,
+            partial_path,
+            file,
+            offset,
+            last_active: Instant::now(),
+        })
+    }
+
+    /// Writes `data` at `offset`, returning the offset confirmed as written
+    async fn write(&mut self, offset: u64, data: &[u8]) -> io::Result<u64> {
+        self.last_active = Instant::now();
+
+        // Writing past what we have would leave a hole in the file
+        if offset > self.offset {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Offset {offset} is past the confirmed offset {} of the upload",
+                    self.offset
+                ),
+            ));
+        }
+
+        self.file.seek(SeekFrom::Start(offset)).await?;
+        self.file.write_all(data).await?;
+        self.file.flush().await?;
+
+        self.offset = std::cmp::max(self.offset, offset + data.len() as u64);
+        Ok(self.offset)
+    }
+
+    /// Verifies the contents against `checksum` (if provided) and flushes them to disk, setting
+    /// the last modification time to `modified` seconds since the unix epoch (if provided)
+    async fn prepare(&mut self, checksum: Option<&str>, modified: Option<u64>) -> io::Result<()> {
+        self.last_active = Instant::now();
+
+        if let Some(checksum) = checksum {
+            let digest = hash_file(
+                &self.partial_path,
+                HashAlgorithm::Sha256,
+                0,
+                Some(self.offset),
+            )
+            .await?;
+            if !digest.eq_ignore_ascii_case(checksum.trim()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Checksum mismatch: expected {checksum}, but upload has {digest}"),
+                ));
+            }
+        }
+
+        self.file.sync_all().await?;
+        if let Some(modified) = modified {
+            let file = self.file.try_clone().await?.into_std().await;
+            let mtime = filetime::FileTime::from_unix_time(modified as i64, 0);
+            filetime::set_file_handle_times(&file, None, Some(mtime))?;
+        }
+
+        Ok(())
+    }
+
+    /// Closes the partial file and moves it into place. If this fails, the partial file is left
+    /// behind so the upload can be resumed from it.
+    async fn persist(self) -> io::Result<()> {
+        // NOTE: Renaming a file that is still open fails on Windows
+        drop(self.file);
+        fs::rename(&self.partial_path, &self.path).await?;
+
+        debug!("Committed upload of {:?}", self.path);
+        Ok(())
+    }
+
+    /// Removes the partial file
+    async fn discard(self) -> io::Result<()> {
+        drop(self.file);
+        fs::remove_file(&self.partial_path).await
+    }
+}
+
+/// Returns the path of the partial file for an upload of `path`, which lives in the same
+/// directory so that committing can atomically rename it into place
+fn partial_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{path:?} is not a valid upload path"),
+        )
+    })?;
+
+    let mut partial_name = std::ffi::OsString::from(".");
+    partial_name.push(file_name);
+    partial_name.push(".");
+    partial_name.push(PARTIAL_UPLOAD_SUFFIX);
+    Ok(path.with_file_name(partial_name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use assert_fs::prelude::*;
+    use predicates::prelude::*;
+    use test_log::test;
+
+    use distant_core::sha256_hex;
+
+    use super::*;
+
+    /// Id of the connection used by tests that do not care about ownership
+    const CONN: ConnectionId = 1;
+
+    #[test(tokio::test)]
+    async fn commit_should_move_uploaded_contents_into_place() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        let state = UploadState::new();
+
+        let (id, offset) = state.open(CONN, file.to_path_buf(), false).await.unwrap();
+        assert_eq!(offset, 0);
+
+        assert_eq!(
+            state.write(CONN, id, 0, b"some ".to_vec()).await.unwrap(),
+            5
+        );
+        assert_eq!(
+            state
+                .write(CONN, id, 5, b"contents".to_vec())
+                .await
+                .unwrap(),
+            13
+        );
+
+        // Nothing should be at the destination until committed
+        file.assert(predicate::path::missing());
+
+        state.commit(CONN, id, None, None).await.unwrap();
+        file.assert("some contents");
+        temp.child(".file.distant-upload")
+            .assert(predicate::path::missing());
+
+        // Upload should no longer exist
+        state.discard(CONN, id).await.unwrap_err();
+    }
+
+    #[test(tokio::test)]
+    async fn write_should_fail_if_offset_past_confirmed_offset() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let state = UploadState::new();
+
+        let (id, _) = state
+            .open(CONN, temp.child("file").to_path_buf(), false)
+            .await
+            .unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+
+        let err = state.write(CONN, id, 4, b"def".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // Rewriting data that was already confirmed is fine
+        assert_eq!(state.write(CONN, id, 2, b"cdef".to_vec()).await.unwrap(), 6);
+    }
+
+    #[test(tokio::test)]
+    async fn open_should_resume_an_existing_upload_of_the_same_path() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        let state = UploadState::new();
+
+        let (id, _) = state.open(CONN, file.to_path_buf(), false).await.unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+
+        assert_eq!(
+            state.open(CONN, file.to_path_buf(), true).await.unwrap(),
+            (id, 3)
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn open_should_resume_from_partial_file_left_on_disk() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        temp.child(".file.distant-upload").write_str("abc").unwrap();
+
+        let state = UploadState::new();
+        let (id, offset) = state.open(CONN, file.to_path_buf(), true).await.unwrap();
+        assert_eq!(offset, 3);
+
+        state
+            .write(CONN, id, offset, b"def".to_vec())
+            .await
+            .unwrap();
+        state.commit(CONN, id, None, None).await.unwrap();
+        file.assert("abcdef");
+    }
+
+    #[test(tokio::test)]
+    async fn open_should_start_over_if_not_resuming() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        let state = UploadState::new();
+
+        let (id, _) = state.open(CONN, file.to_path_buf(), false).await.unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+
+        let (new_id, offset) = state.open(CONN, file.to_path_buf(), false).await.unwrap();
+        assert_eq!(offset, 0);
+        state.write(CONN, new_id, 0, b"def".to_vec()).await.unwrap();
+        state.commit(CONN, new_id, None, None).await.unwrap();
+        file.assert("def");
+    }
+
+    #[test(tokio::test)]
+    async fn commit_should_fail_and_keep_upload_if_checksum_does_not_match() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        let state = UploadState::new();
+
+        let (id, _) = state.open(CONN, file.to_path_buf(), false).await.unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+
+        let err = state
+            .commit(CONN, id, Some(sha256_hex(b"def")), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        file.assert(predicate::path::missing());
+
+        state
+            .commit(CONN, id, Some(sha256_hex(b"abc")), None)
+            .await
+            .unwrap();
+        file.assert("abc");
+    }
+
+    #[test(tokio::test)]
+    async fn commit_should_set_modification_time_if_provided() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        let state = UploadState::new();
+
+        let (id, _) = state.open(CONN, file.to_path_buf(), false).await.unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+        state
+            .commit(CONN, id, None, Some(1234567890))
+            .await
+            .unwrap();
+
+        let modified = std::fs::metadata(file.path()).unwrap().modified().unwrap();
+        assert_eq!(modified, UNIX_EPOCH + Duration::from_secs(1234567890));
+    }
+
+    #[test(tokio::test)]
+    async fn abort_should_remove_partial_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let state = UploadState::new();
+
+        let (id, _) = state
+            .open(CONN, temp.child("file").to_path_buf(), false)
+            .await
+            .unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+        temp.child(".file.distant-upload").assert("abc");
+
+        state.discard(CONN, id).await.unwrap();
+        temp.child(".file.distant-upload")
+            .assert(predicate::path::missing());
+    }
+
+    #[test(tokio::test)]
+    async fn operations_should_fail_if_upload_is_held_by_another_connection() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let file = temp.child("file");
+        let state = UploadState::new();
+
+        let (id, _) = state.open(1, file.to_path_buf(), false).await.unwrap();
+        state.write(1, id, 0, b"abc".to_vec()).await.unwrap();
+
+        let err = state.write(2, id, 3, b"def".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = state.commit(2, id, None, None).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        let err = state.discard(2, id).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        // Resuming the upload hands it over to the other connection
+        assert_eq!(
+            state.open(2, file.to_path_buf(), true).await.unwrap(),
+            (id, 3)
+        );
+        let err = state.write(1, id, 3, b"def".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        state.write(2, id, 3, b"def".to_vec()).await.unwrap();
+        state.commit(2, id, None, None).await.unwrap();
+        file.assert("abcdef");
+    }
+
+    #[test(tokio::test)]
+    async fn uploads_should_be_discarded_once_idle_for_too_long() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let state = UploadState::with_idle_timeout(Duration::from_millis(50));
+
+        let (id, _) = state
+            .open(CONN, temp.child("file").to_path_buf(), false)
+            .await
+            .unwrap();
+        state.write(CONN, id, 0, b"abc".to_vec()).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let err = state.write(CONN, id, 3, b"def".to_vec()).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        temp.child(".file.distant-upload")
+            .assert(predicate::path::missing());
+    }
+}
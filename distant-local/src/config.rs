@@ -2,9 +2,17 @@ use std::time::Duration;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Config {
+    pub process: ProcessConfig,
     pub watch: WatchConfig,
 }
 
+/// Configuration specifically for spawning processes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProcessConfig {
+    /// Maximum number of processes that a single connection can hold at once
+    pub max_per_connection: Option<usize>,
+}
+
 /// Configuration specifically for watching files and directories.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WatchConfig {
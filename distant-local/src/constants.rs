@@ -12,3 +12,34 @@ pub const MAX_PIPE_CHUNK_SIZE: usize = 16384;
 /// Duration in milliseconds to sleep between reading stdout/stderr chunks
 /// to avoid sending many small messages to clients
 pub const READ_PAUSE_DURATION: Duration = Duration::from_millis(1);
+
+/// Represents the maximum size (in bytes) of each chunk sent back to a client
+/// when streaming the contents of a file
+///
+/// Current setting is 64k size
+pub const MAX_FILE_CHUNK_SIZE: usize = 65536;
+
+/// Represents the maximum size (in bytes) of a range read from a file without a length, beyond
+/// which the file needs to be streamed instead
+///
+/// Current setting is 16MB size
+pub const MAX_FILE_RANGE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Represents the maximum size (in bytes) of each chunk sent back to a client
+/// when forwarding data received by a tcp stream
+///
+/// Current setting is 64k size
+pub const MAX_TCP_CHUNK_SIZE: usize = 65536;
+
+/// Represents the maximum number of writes queued up for a tcp stream before writing to it waits
+/// for the peer to catch up
+pub const TCP_WRITE_QUEUE_SIZE: usize = 16;
+
+/// Duration between checks of whether the client that owns a tcp listener is still connected
+pub const TCP_LISTENER_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Represents the maximum size (in bytes) of stdout and stderr buffered for a process while no
+/// connection is attached to it, where the oldest output is discarded first
+///
+/// Current setting is 1MB size
+pub const MAX_PROCESS_BACKLOG_SIZE: usize = 1024 * 1024;
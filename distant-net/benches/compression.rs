@@ -0,0 +1,78 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use distant_net::common::{AdaptiveCodec, Codec, CompressionCodec, CompressionLevel, Frame};
+use rand::RngCore;
+
+/// Sizes of frame items to benchmark, ranging from small requests to large file payloads
+const SIZES: &[usize] = &[256, 16 * 1024, 1024 * 1024];
+
+/// Produces text-like data that compresses well
+fn text(len: usize) -> Vec<u8> {
+    b"The quick brown fox jumps over the lazy dog. "
+        .iter()
+        .copied()
+        .cycle()
+        .take(len)
+        .collect()
+}
+
+/// Produces random data that resembles already-compressed content
+fn random(len: usize) -> Vec<u8> {
+    let mut data = vec![0; len];
+    rand::thread_rng().fill_bytes(&mut data);
+    data
+}
+
+fn codecs() -> Vec<(&'static str, Box<dyn Codec>)> {
+    let level = CompressionLevel::default();
+    vec![
+        ("deflate", Box::new(CompressionCodec::deflate(level))),
+        ("gzip", Box::new(CompressionCodec::gzip(level))),
+        ("zlib", Box::new(CompressionCodec::zlib(level))),
+        ("lz4", Box::new(CompressionCodec::lz4())),
+        (
+            "zstd",
+            Box::new(CompressionCodec::zstd(CompressionLevel::Three)),
+        ),
+        (
+            "adaptive-zstd",
+            Box::new(AdaptiveCodec::new(CompressionCodec::zstd(
+                CompressionLevel::Three,
+            ))),
+        ),
+    ]
+}
+
+fn bench(c: &mut Criterion, name: &str, make_data: fn(usize) -> Vec<u8>) {
+    let mut group = c.benchmark_group(name);
+    for size in SIZES {
+        let data = make_data(*size);
+        group.throughput(Throughput::Bytes(*size as u64));
+
+        for (codec_name, mut codec) in codecs() {
+            group.bench_with_input(
+                BenchmarkId::new(format!("{codec_name}/encode"), size),
+                &data,
+                |b, data| b.iter(|| codec.encode(Frame::new(data)).unwrap()),
+            );
+
+            let encoded = codec.encode(Frame::new(&data)).unwrap().into_owned();
+            group.bench_with_input(
+                BenchmarkId::new(format!("{codec_name}/decode"), size),
+                &encoded,
+                |b, encoded| b.iter(|| codec.decode(encoded.as_borrowed()).unwrap()),
+            );
+        }
+    }
+    group.finish();
+}
+
+fn bench_text(c: &mut Criterion) {
+    bench(c, "compression/text", text);
+}
+
+fn bench_random(c: &mut Criterion) {
+    bench(c, "compression/random", random);
+}
+
+criterion_group!(benches, bench_text, bench_random);
+criterion_main!(benches);
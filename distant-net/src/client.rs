@@ -10,8 +10,8 @@ use tokio::sync::{mpsc, oneshot, watch};
 use tokio::task::JoinHandle;
 
 use crate::common::{
-    Connection, FramedTransport, HeapSecretKey, InmemoryTransport, Interest, Reconnectable,
-    Transport, UntypedRequest, UntypedResponse,
+    Connection, FramedTransport, Handshake, HeapSecretKey, InmemoryTransport, Interest,
+    Reconnectable, Transport, UntypedRequest, UntypedResponse,
 };
 
 mod builder;
@@ -173,6 +173,7 @@ impl UntypedClient {
         let connection = Connection::Client {
             id: rand::random(),
             reauth_otp: HeapSecretKey::generate(32).unwrap(),
+            handshake: Handshake::client(),
             transport,
         };
         Self::spawn(connection, config)
@@ -543,6 +544,13 @@ impl Client<(), ()> {
     ) -> ClientBuilder<(), WindowsPipeConnector> {
         ClientBuilder::new().connector(connector.into())
     }
+
+    /// Creates a new [`ClientBuilder`] configured to use a [`WebSocketConnector`].
+    pub fn websocket(
+        connector: impl Into<WebSocketConnector>,
+    ) -> ClientBuilder<(), WebSocketConnector> {
+        ClientBuilder::new().connector(connector.into())
+    }
 }
 
 impl<T, U> Client<T, U> {
@@ -7,6 +7,9 @@ mod unix;
 #[cfg(unix)]
 pub use unix::*;
 
+mod websocket;
+pub use websocket::*;
+
 #[cfg(windows)]
 mod windows;
 
@@ -20,7 +23,7 @@ pub use windows::*;
 
 use super::ClientConfig;
 use crate::client::{Client, UntypedClient};
-use crate::common::{Connection, Transport};
+use crate::common::{Connection, Handshake, HostKeyVerifier, Transport};
 
 /// Interface that performs the connection to produce a [`Transport`] for use by the [`Client`].
 #[async_trait]
@@ -46,6 +49,8 @@ pub struct ClientBuilder<H, C> {
     connector: C,
     config: ClientConfig,
     connect_timeout: Option<Duration>,
+    host_key_verifier: Option<HostKeyVerifier>,
+    handshake: Handshake,
 }
 
 impl<H, C> ClientBuilder<H, C> {
@@ -56,6 +61,8 @@ impl<H, C> ClientBuilder<H, C> {
             config: self.config,
             connector: self.connector,
             connect_timeout: self.connect_timeout,
+            host_key_verifier: self.host_key_verifier,
+            handshake: self.handshake,
         }
     }
 
@@ -66,6 +73,8 @@ impl<H, C> ClientBuilder<H, C> {
             config,
             connector: self.connector,
             connect_timeout: self.connect_timeout,
+            host_key_verifier: self.host_key_verifier,
+            handshake: self.handshake,
         }
     }
 
@@ -76,6 +85,8 @@ impl<H, C> ClientBuilder<H, C> {
             config: self.config,
             connector,
             connect_timeout: self.connect_timeout,
+            host_key_verifier: self.host_key_verifier,
+            handshake: self.handshake,
         }
     }
 
@@ -86,6 +97,38 @@ impl<H, C> ClientBuilder<H, C> {
             config: self.config,
             connector: self.connector,
             connect_timeout: connect_timeout.into(),
+            host_key_verifier: self.host_key_verifier,
+            handshake: self.handshake,
+        }
+    }
+
+    /// Configure the verifier used to check the host key presented by the server against the
+    /// hosts known to the client.
+    pub fn host_key_verifier(self, host_key_verifier: impl Into<Option<HostKeyVerifier>>) -> Self {
+        Self {
+            auth_handler: self.auth_handler,
+            config: self.config,
+            connector: self.connector,
+            connect_timeout: self.connect_timeout,
+            host_key_verifier: host_key_verifier.into(),
+            handshake: self.handshake,
+        }
+    }
+
+    /// Configure the handshake performed with the server, which determines the preferred
+    /// compression and encryption. Server-side handshakes are ignored.
+    pub fn handshake(self, handshake: Handshake) -> Self {
+        Self {
+            auth_handler: self.auth_handler,
+            config: self.config,
+            connector: self.connector,
+            connect_timeout: self.connect_timeout,
+            host_key_verifier: self.host_key_verifier,
+            handshake: if handshake.is_client() {
+                handshake
+            } else {
+                self.handshake
+            },
         }
     }
 }
@@ -97,6 +140,8 @@ impl ClientBuilder<(), ()> {
             config: Default::default(),
             connector: (),
             connect_timeout: None,
+            host_key_verifier: None,
+            handshake: Handshake::client(),
         }
     }
 }
@@ -119,6 +164,8 @@ where
         let auth_handler = self.auth_handler;
         let config = self.config;
         let connect_timeout = self.connect_timeout;
+        let host_key_verifier = self.host_key_verifier;
+        let handshake = self.handshake;
 
         let f = async move {
             let transport = match connect_timeout {
@@ -128,7 +175,8 @@ where
                     .and_then(convert::identity)?,
                 None => self.connector.connect().await?,
             };
-            let connection = Connection::client(transport, auth_handler).await?;
+            let connection =
+                Connection::client(transport, handshake, auth_handler, host_key_verifier).await?;
             Ok(UntypedClient::spawn(connection, config))
         };
 
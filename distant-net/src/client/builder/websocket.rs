@@ -0,0 +1,33 @@
+use std::io;
+
+use async_trait::async_trait;
+
+use super::Connector;
+use crate::common::WebSocketTransport;
+
+/// Implementation of [`Connector`] to support connecting via WebSocket, going through the HTTP
+/// proxy configured in the environment if there is one.
+pub struct WebSocketConnector {
+    url: String,
+}
+
+impl WebSocketConnector {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+impl<T: Into<String>> From<T> for WebSocketConnector {
+    fn from(url: T) -> Self {
+        Self::new(url)
+    }
+}
+
+#[async_trait]
+impl Connector for WebSocketConnector {
+    type Transport = WebSocketTransport;
+
+    async fn connect(self) -> io::Result<Self::Transport> {
+        WebSocketTransport::connect(self.url).await
+    }
+}
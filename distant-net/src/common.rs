@@ -1,8 +1,10 @@
 mod any;
 mod connection;
 mod destination;
+mod identity;
 mod key;
 mod keychain;
+mod known_hosts;
 mod listener;
 mod map;
 mod packet;
@@ -14,8 +16,10 @@ pub use any::*;
 pub(crate) use connection::Connection;
 pub use connection::ConnectionId;
 pub use destination::*;
+pub use identity::*;
 pub use key::*;
 pub use keychain::*;
+pub use known_hosts::*;
 pub use listener::*;
 pub use map::*;
 pub use packet::*;
@@ -10,7 +10,8 @@ use tokio::sync::oneshot;
 #[cfg(test)]
 use crate::common::InmemoryTransport;
 use crate::common::{
-    Backup, FramedTransport, HeapSecretKey, Keychain, KeychainResult, Reconnectable, Transport,
+    Backup, FramedTransport, Handshake, HeapSecretKey, HostKeyVerifier, Keychain, KeychainResult,
+    Reconnectable, ServerIdentity, Transport,
 };
 
 /// Id of the connection
@@ -27,6 +28,9 @@ pub enum Connection<T> {
         /// One-time password (OTP) for use in reauthenticating with the server
         reauth_otp: HeapSecretKey,
 
+        /// Handshake to perform with the server whenever the connection is re-established
+        handshake: Handshake,
+
         /// Underlying transport used to communicate
         transport: FramedTransport<T>,
     },
@@ -104,6 +108,7 @@ where
         async fn reconnect_client<T: Transport>(
             id: ConnectionId,
             reauth_otp: HeapSecretKey,
+            handshake: Handshake,
             transport: &mut FramedTransport<T>,
         ) -> io::Result<(ConnectionId, HeapSecretKey)> {
             // Re-establish a raw connection
@@ -112,7 +117,16 @@ where
 
             // Perform a handshake to ensure that the connection is properly established and encrypted
             debug!("[Conn {id}] Performing handshake");
-            transport.client_handshake().await?;
+            let host_key = transport.host_key().copied();
+            transport.handshake(handshake).await?;
+
+            // Ensure that we are still talking to the same server as before
+            if transport.host_key() != host_key.as_ref() {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "Host key changed during reconnect",
+                ));
+            }
 
             // Communicate that we are an existing connection
             debug!("[Conn {id}] Performing re-authentication");
@@ -135,12 +149,15 @@ where
                 id,
                 transport,
                 reauth_otp,
+                handshake,
             } => {
                 // Freeze our backup as we don't want the connection logic to alter it, attempt to
                 // perform the reconnection, and unfreeze our backup regardless of the result
                 let (new_id, new_reauth_otp) = {
                     transport.backup.freeze();
-                    let result = reconnect_client(*id, reauth_otp.clone(), transport).await;
+                    let result =
+                        reconnect_client(*id, reauth_otp.clone(), handshake.clone(), transport)
+                            .await;
                     transport.backup.unfreeze();
                     result?
                 };
@@ -190,17 +207,29 @@ where
     /// Transforms a raw [`Transport`] into an established [`Connection`] from the client-side by
     /// performing the following:
     ///
-    /// 1. Handshakes to derive the appropriate [`Codec`](crate::Codec) to use
-    /// 2. Authenticates the established connection to ensure it is valid
-    /// 3. Restores pre-existing state using the provided backup, replaying any missing frames and
+    /// 1. Handshakes using `handshake` to derive the appropriate [`Codec`](crate::Codec) to use
+    /// 2. Verifies the host key presented by the server if given a `host_key_verifier`
+    /// 3. Authenticates the established connection to ensure it is valid
+    /// 4. Restores pre-existing state using the provided backup, replaying any missing frames and
     ///    receiving any frames from the other side
-    pub async fn client<H: AuthHandler + Send>(transport: T, handler: H) -> io::Result<Self> {
+    pub async fn client<H: AuthHandler + Send>(
+        transport: T,
+        handshake: Handshake,
+        mut handler: H,
+        host_key_verifier: Option<HostKeyVerifier>,
+    ) -> io::Result<Self> {
         let id: ConnectionId = rand::random();
 
         // Perform a handshake to ensure that the connection is properly established and encrypted
         debug!("[Conn {id}] Performing handshake");
-        let mut transport: FramedTransport<T> =
-            FramedTransport::from_client_handshake(transport).await?;
+        let mut transport = FramedTransport::plain(transport);
+        transport.handshake(handshake.clone()).await?;
+
+        // Ensure that the server is who we expect before sending anything sensitive
+        if let Some(mut verifier) = host_key_verifier {
+            debug!("[Conn {id}] Verifying host key");
+            verifier.verify(transport.host_key(), &mut handler).await?;
+        }
 
         // Communicate that we are a new connection
         debug!("[Conn {id}] Communicating that this is a new connection");
@@ -231,6 +260,7 @@ where
         Ok(Self::Client {
             id,
             reauth_otp,
+            handshake,
             transport,
         })
     }
@@ -238,7 +268,8 @@ where
     /// Transforms a raw [`Transport`] into an established [`Connection`] from the server-side by
     /// performing the following:
     ///
-    /// 1. Handshakes to derive the appropriate [`Codec`](crate::Codec) to use
+    /// 1. Handshakes to derive the appropriate [`Codec`](crate::Codec) to use, signing the key
+    ///    exchange with `identity` if provided
     /// 2. Authenticates the established connection to ensure it is valid by either using the
     ///    given `verifier` or, if working with an existing client connection, will validate an OTP
     ///    from our database
@@ -247,14 +278,17 @@ where
     pub async fn server(
         transport: T,
         verifier: &Verifier,
+        identity: Option<&ServerIdentity>,
         keychain: Keychain<oneshot::Receiver<Backup>>,
     ) -> io::Result<Self> {
         let id: ConnectionId = rand::random();
 
         // Perform a handshake to ensure that the connection is properly established and encrypted
         debug!("[Conn {id}] Performing handshake");
-        let mut transport: FramedTransport<T> =
-            FramedTransport::from_server_handshake(transport).await?;
+        let mut transport = FramedTransport::plain(transport);
+        transport
+            .handshake(Handshake::server().with_identity(identity.cloned()))
+            .await?;
 
         // Receive a client id, look up to see if the client id exists already
         //
@@ -392,6 +426,7 @@ impl Connection<InmemoryTransport> {
         let client = Connection::Client {
             id,
             reauth_otp: HeapSecretKey::generate(32).unwrap(),
+            handshake: Handshake::client(),
             transport: t1,
         };
 
@@ -448,6 +483,7 @@ impl<T: Transport> Connection<T> {
         Self::Client {
             id: rand::random(),
             reauth_otp: HeapSecretKey::generate(32).unwrap(),
+            handshake: Handshake::client(),
             transport: FramedTransport::plain(transport),
         }
     }
@@ -457,12 +493,13 @@ impl<T: Transport> Connection<T> {
 mod tests {
     use std::sync::Arc;
 
-    use distant_auth::msg::Challenge;
+    use distant_auth::msg::{Challenge, VerificationResponse};
+    use distant_auth::tests::TestAuthHandler;
     use distant_auth::{Authenticator, DummyAuthHandler};
     use test_log::test;
 
     use super::*;
-    use crate::common::Frame;
+    use crate::common::{Frame, HostKeyPolicy, KnownHosts};
 
     #[test(tokio::test)]
     async fn client_should_fail_if_codec_handshake_fails() {
@@ -471,7 +508,7 @@ mod tests {
         // Spawn a task to perform the client connection so we don't deadlock while simulating the
         // server actions on the other side
         let task = tokio::spawn(async move {
-            Connection::client(t2.into_inner(), DummyAuthHandler)
+            Connection::client(t2.into_inner(), Handshake::client(), DummyAuthHandler, None)
                 .await
                 .unwrap()
         });
@@ -490,7 +527,7 @@ mod tests {
         // Spawn a task to perform the client connection so we don't deadlock while simulating the
         // server actions on the other side
         let task = tokio::spawn(async move {
-            Connection::client(t2.into_inner(), DummyAuthHandler)
+            Connection::client(t2.into_inner(), Handshake::client(), DummyAuthHandler, None)
                 .await
                 .unwrap()
         });
@@ -519,7 +556,7 @@ mod tests {
         // Spawn a task to perform the client connection so we don't deadlock while simulating the
         // server actions on the other side
         let task = tokio::spawn(async move {
-            Connection::client(t2.into_inner(), DummyAuthHandler)
+            Connection::client(t2.into_inner(), Handshake::client(), DummyAuthHandler, None)
                 .await
                 .unwrap()
         });
@@ -559,7 +596,7 @@ mod tests {
         // Spawn a task to perform the client connection so we don't deadlock while simulating the
         // server actions on the other side
         let task = tokio::spawn(async move {
-            Connection::client(t2.into_inner(), DummyAuthHandler)
+            Connection::client(t2.into_inner(), Handshake::client(), DummyAuthHandler, None)
                 .await
                 .unwrap()
         });
@@ -597,7 +634,7 @@ mod tests {
         // Spawn a task to perform the client connection so we don't deadlock while simulating the
         // server actions on the other side
         let task = tokio::spawn(async move {
-            Connection::client(t2.into_inner(), DummyAuthHandler)
+            Connection::client(t2.into_inner(), Handshake::client(), DummyAuthHandler, None)
                 .await
                 .unwrap()
         });
@@ -629,6 +666,43 @@ mod tests {
         assert_eq!(client.otp(), Some(&otp));
     }
 
+    #[test(tokio::test)]
+    async fn client_should_fail_if_host_key_is_rejected() {
+        let (mut t1, t2) = FramedTransport::pair(100);
+
+        // Pretend that we previously trusted a different key for the host
+        let mut known_hosts = KnownHosts::new();
+        known_hosts.insert("host", ServerIdentity::generate().host_key());
+        let verifier = HostKeyVerifier::new("host", known_hosts, HostKeyPolicy::TrustOnFirstUse);
+
+        // Spawn a task to perform the client connection so we don't deadlock while simulating the
+        // server actions on the other side
+        let task = tokio::spawn(async move {
+            let handler = TestAuthHandler {
+                on_verification: Box::new(|_| Ok(VerificationResponse { valid: false })),
+                ..Default::default()
+            };
+            Connection::client(
+                t2.into_inner(),
+                Handshake::client(),
+                handler,
+                Some(verifier),
+            )
+            .await
+            .unwrap_err()
+        });
+
+        // Establish the codec using an identity unknown to the client
+        t1.handshake(Handshake::server().with_identity(Some(ServerIdentity::generate())))
+            .await
+            .unwrap();
+
+        // Client should fail before communicating that it is a new connection
+        let err = task.await.unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(t1.read_frame().await.unwrap(), None);
+    }
+
     #[test(tokio::test)]
     async fn server_should_fail_if_codec_handshake_fails() {
         let (mut t1, t2) = FramedTransport::pair(100);
@@ -638,7 +712,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -659,7 +733,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -683,7 +757,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -717,7 +791,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -750,7 +824,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -790,7 +864,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -828,7 +902,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -866,7 +940,7 @@ mod tests {
         // Spawn a task to perform the server connection so we don't deadlock while simulating the
         // client actions on the other side
         let task = tokio::spawn(async move {
-            Connection::server(t2.into_inner(), &verifier, keychain)
+            Connection::server(t2.into_inner(), &verifier, None, keychain)
                 .await
                 .unwrap()
         });
@@ -904,7 +978,7 @@ mod tests {
         let task = tokio::spawn({
             let keychain = keychain.clone();
             async move {
-                Connection::server(t2.into_inner(), &verifier, keychain)
+                Connection::server(t2.into_inner(), &verifier, None, keychain)
                     .await
                     .unwrap()
             }
@@ -969,7 +1043,7 @@ mod tests {
         let task = tokio::spawn({
             let keychain = keychain.clone();
             async move {
-                Connection::server(t2.into_inner(), &verifier, keychain)
+                Connection::server(t2.into_inner(), &verifier, None, keychain)
                     .await
                     .unwrap()
             }
@@ -1029,13 +1103,13 @@ mod tests {
 
         // Spawn a task to perform the server connection so we don't deadlock
         let task = tokio::spawn(async move {
-            Connection::server(t2, &verifier, keychain)
+            Connection::server(t2, &verifier, None, keychain)
                 .await
                 .expect("Failed to connect from server")
         });
 
         // Perform the client-side of the connection
-        let mut client = Connection::client(t1, DummyAuthHandler)
+        let mut client = Connection::client(t1, Handshake::client(), DummyAuthHandler, None)
             .await
             .expect("Failed to connect from client");
         let mut server = task.await.unwrap();
@@ -1063,14 +1137,14 @@ mod tests {
             let verifier = Arc::clone(&verifier);
             let keychain = keychain.clone();
             tokio::spawn(async move {
-                Connection::server(t2, &verifier, keychain)
+                Connection::server(t2, &verifier, None, keychain)
                     .await
                     .expect("Failed to connect from server")
             })
         };
 
         // Perform the client-side of the connection
-        let mut client = Connection::client(t1, DummyAuthHandler)
+        let mut client = Connection::client(t1, Handshake::client(), DummyAuthHandler, None)
             .await
             .expect("Failed to connect from client");
 
@@ -1275,7 +1349,7 @@ mod tests {
 
         // Spawn a task to perform the server reconnection so we don't deadlock
         let task = tokio::spawn(async move {
-            Connection::server(transport, &verifier, keychain)
+            Connection::server(transport, &verifier, None, keychain)
                 .await
                 .expect("Failed to connect from server")
         });
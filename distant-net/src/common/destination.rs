@@ -18,6 +18,8 @@ pub use host::{Host, HostParseError};
 /// one of the following forms:
 ///
 /// * `distant://hostname:port` - connect to a distant server
+/// * `ws://hostname[:port]` - connect to a distant server over WebSocket
+/// * `wss://hostname[:port]` - connect to a distant server over secure WebSocket
 /// * `ssh://[user@]hostname[:port]` - connect to an SSH server
 ///
 /// **Note:** Due to the limitations of a URI, an IPv6 address is not supported.
@@ -51,6 +53,21 @@ impl Destination {
             None => false,
         }
     }
+
+    /// Returns the url to use to connect over WebSocket if the destination's scheme is `ws` or
+    /// `wss`, excluding the username and password.
+    pub fn to_websocket_url(&self) -> Option<String> {
+        if self.scheme_eq("ws") || self.scheme_eq("wss") {
+            let destination = Destination {
+                username: None,
+                password: None,
+                ..self.clone()
+            };
+            Some(destination.to_string())
+        } else {
+            None
+        }
+    }
 }
 
 impl AsRef<Destination> for &Destination {
@@ -179,4 +196,22 @@ mod tests {
         };
         assert_eq!(destination, "[::1]:12345");
     }
+
+    #[test]
+    fn to_websocket_url_should_exclude_credentials_for_websocket_schemes() {
+        let destination: Destination = "ws://user:key@[::1]:12345".parse().unwrap();
+        assert_eq!(
+            destination.to_websocket_url().as_deref(),
+            Some("ws://[::1]:12345")
+        );
+
+        let destination: Destination = "WSS://:key@example.com".parse().unwrap();
+        assert_eq!(
+            destination.to_websocket_url().as_deref(),
+            Some("WSS://example.com")
+        );
+
+        let destination: Destination = "distant://:key@example.com:12345".parse().unwrap();
+        assert_eq!(destination.to_websocket_url(), None);
+    }
 }
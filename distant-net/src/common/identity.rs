@@ -0,0 +1,241 @@
+use std::convert::TryFrom;
+use std::path::Path;
+use std::str::FromStr;
+use std::{fmt, io};
+
+use p256::ecdsa::signature::{Signer, Verifier};
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p256::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+/// Represents a long-lived keypair used by a server to prove its identity to clients by signing
+/// the ephemeral keys exchanged during a handshake
+#[derive(Clone)]
+pub struct ServerIdentity(SigningKey);
+
+impl fmt::Debug for ServerIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ServerIdentity")
+            .field(&self.host_key().fingerprint())
+            .finish()
+    }
+}
+
+impl ServerIdentity {
+    /// Generates a new, random server identity
+    pub fn generate() -> Self {
+        Self(SigningKey::random(&mut OsRng))
+    }
+
+    /// Parses a server identity from a PEM-encoded PKCS#8 private key
+    pub fn from_pem(pem: &str) -> io::Result<Self> {
+        SigningKey::from_pkcs8_pem(pem)
+            .map(Self)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x.to_string()))
+    }
+
+    /// Encodes the server identity as a PEM-encoded PKCS#8 private key
+    pub fn to_pem(&self) -> io::Result<String> {
+        self.0
+            .to_pkcs8_pem(LineEnding::LF)
+            .map(|pem| pem.to_string())
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x.to_string()))
+    }
+
+    /// Loads a server identity from the PEM file at `path`, generating and saving a new identity
+    /// to that location if the file does not exist
+    pub async fn load_or_generate(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+
+        match tokio::fs::read_to_string(path).await {
+            Ok(pem) => Self::from_pem(&pem),
+            Err(x) if x.kind() == io::ErrorKind::NotFound => {
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                // NOTE: We write the identity to a temporary file and then link it into place so
+                //       that servers starting at the same time never observe a partially-written
+                //       file and all end up agreeing on the same identity
+                let identity = Self::generate();
+                let tmp = path.with_extension(format!("{:x}.tmp", rand::random::<u32>()));
+                let mut options = tokio::fs::OpenOptions::new();
+                options.write(true).create_new(true);
+
+                // NOTE: Restrict the private key so that only the owner can read it
+                #[cfg(unix)]
+                options.mode(0o600);
+
+                let mut file = options.open(&tmp).await?;
+                let result = async {
+                    file.write_all(identity.to_pem()?.as_bytes()).await?;
+                    file.sync_all().await?;
+                    tokio::fs::hard_link(&tmp, path).await
+                }
+                .await;
+                let _ = tokio::fs::remove_file(&tmp).await;
+
+                match result {
+                    Ok(()) => Ok(identity),
+                    Err(x) if x.kind() == io::ErrorKind::AlreadyExists => {
+                        Self::from_pem(&tokio::fs::read_to_string(path).await?)
+                    }
+                    Err(x) => Err(x),
+                }
+            }
+            Err(x) => Err(x),
+        }
+    }
+
+    /// Returns the public half of the identity that clients use to verify the server
+    pub fn host_key(&self) -> HostKey {
+        HostKey(*self.0.verifying_key())
+    }
+
+    /// Signs `data` using the identity, returning the bytes of the signature
+    pub fn sign(&self, data: &[u8]) -> Vec<u8> {
+        let signature: Signature = self.0.sign(data);
+        signature.to_vec()
+    }
+}
+
+/// Represents the public key of a [`ServerIdentity`], used by clients to verify that they are
+/// talking to the server they expect
+#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "Vec<u8>", try_from = "Vec<u8>")]
+pub struct HostKey(VerifyingKey);
+
+impl fmt::Debug for HostKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("HostKey").field(&self.fingerprint()).finish()
+    }
+}
+
+impl HostKey {
+    /// Creates a host key from its SEC1-encoded bytes
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        VerifyingKey::from_sec1_bytes(bytes)
+            .map(Self)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x.to_string()))
+    }
+
+    /// Returns the compressed SEC1-encoded bytes of the host key
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    /// Returns a human-readable fingerprint of the host key in the form `SHA256:<hex>`
+    pub fn fingerprint(&self) -> String {
+        format!("SHA256:{}", hex::encode(Sha256::digest(self.to_bytes())))
+    }
+
+    /// Verifies that `signature` was produced by the identity of this host key signing `data`
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> io::Result<()> {
+        let signature = Signature::from_slice(signature)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x.to_string()))?;
+        self.0.verify(data, &signature).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "Host key signature verification failed",
+            )
+        })
+    }
+}
+
+impl From<HostKey> for Vec<u8> {
+    fn from(key: HostKey) -> Self {
+        key.to_bytes()
+    }
+}
+
+impl TryFrom<Vec<u8>> for HostKey {
+    type Error = io::Error;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl FromStr for HostKey {
+    type Err = io::Error;
+
+    /// Parse a str of hex as a host key
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl fmt::Display for HostKey {
+    /// Display a host key as a hex string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.to_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    #[test]
+    fn host_key_should_verify_signature_produced_by_identity() {
+        let identity = ServerIdentity::generate();
+        let signature = identity.sign(b"hello");
+
+        identity.host_key().verify(b"hello", &signature).unwrap();
+    }
+
+    #[test]
+    fn host_key_should_reject_signature_from_different_identity() {
+        let identity = ServerIdentity::generate();
+        let signature = ServerIdentity::generate().sign(b"hello");
+
+        let err = identity
+            .host_key()
+            .verify(b"hello", &signature)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn host_key_should_reject_signature_of_different_data() {
+        let identity = ServerIdentity::generate();
+        let signature = identity.sign(b"hello");
+
+        let err = identity
+            .host_key()
+            .verify(b"world", &signature)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test]
+    fn host_key_should_support_round_trip_through_string() {
+        let key = ServerIdentity::generate().host_key();
+        assert_eq!(key.to_string().parse::<HostKey>().unwrap(), key);
+    }
+
+    #[test]
+    fn server_identity_should_support_round_trip_through_pem() {
+        let identity = ServerIdentity::generate();
+        let pem = identity.to_pem().unwrap();
+
+        let loaded = ServerIdentity::from_pem(&pem).unwrap();
+        assert_eq!(loaded.host_key(), identity.host_key());
+    }
+
+    #[test(tokio::test)]
+    async fn load_or_generate_should_reuse_existing_identity() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("identity.pem");
+
+        let identity = ServerIdentity::load_or_generate(&path).await.unwrap();
+        let loaded = ServerIdentity::load_or_generate(&path).await.unwrap();
+        assert_eq!(loaded.host_key(), identity.host_key());
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use derive_more::Display;
+use distant_auth::msg::{Verification, VerificationKind};
+use distant_auth::AuthHandler;
+use log::*;
+
+use super::HostKey;
+
+/// Rules for how a client treats the host key presented by a server that it has not seen before
+#[derive(Copy, Clone, Debug, Default, Display, PartialEq, Eq)]
+pub enum HostKeyPolicy {
+    /// Unknown hosts are trusted automatically and their host key is remembered for future
+    /// connections
+    #[default]
+    #[display(fmt = "tofu")]
+    TrustOnFirstUse,
+
+    /// Unknown hosts must be explicitly accepted through the authentication handler before their
+    /// host key is remembered
+    #[display(fmt = "strict")]
+    Strict,
+}
+
+impl FromStr for HostKeyPolicy {
+    type Err = io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("tofu") {
+            Ok(Self::TrustOnFirstUse)
+        } else if s.eq_ignore_ascii_case("strict") {
+            Ok(Self::Strict)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Unknown host key policy: {s}"),
+            ))
+        }
+    }
+}
+
+/// Collection of host keys that a client has previously trusted, optionally backed by a file
+/// where each line is of the form `<host> <hex key>`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KnownHosts {
+    path: Option<PathBuf>,
+    hosts: BTreeMap<String, HostKey>,
+}
+
+impl KnownHosts {
+    /// Creates an empty, in-memory collection of known hosts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads known hosts from the file at `path`, which will also be where the hosts are saved.
+    /// If the file does not exist, the collection starts out empty.
+    pub async fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let text = match tokio::fs::read_to_string(&path).await {
+            Ok(text) => text,
+            Err(x) if x.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(x) => return Err(x),
+        };
+
+        let mut hosts = BTreeMap::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (host, key) = line.split_once(char::is_whitespace).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Malformed known hosts entry: {line}"),
+                )
+            })?;
+            hosts.insert(host.to_string(), key.trim().parse()?);
+        }
+
+        Ok(Self {
+            path: Some(path),
+            hosts,
+        })
+    }
+
+    /// Returns the path to the file backing the known hosts, if there is one
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Returns the host key trusted for `host`, if there is one
+    pub fn get(&self, host: &str) -> Option<&HostKey> {
+        self.hosts.get(host)
+    }
+
+    /// Trusts `key` for `host`, returning the previously-trusted key if there was one
+    pub fn insert(&mut self, host: impl Into<String>, key: HostKey) -> Option<HostKey> {
+        self.hosts.insert(host.into(), key)
+    }
+
+    /// Writes the known hosts to the file they were loaded from, doing nothing if they are not
+    /// backed by a file
+    ///
+    /// The hosts are written to a temporary file next to the original that is then renamed over
+    /// it, so the file is never left partially written.
+    pub async fn save(&self) -> io::Result<()> {
+        let path = match self.path.as_ref() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        tokio::fs::create_dir_all(parent).await?;
+
+        let mut text = String::new();
+        for (host, key) in self.hosts.iter() {
+            text.push_str(&format!("{host} {key}\n"));
+        }
+
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "file has no name"))?;
+        let tmp = parent.join(format!(
+            ".{}.{:x}.tmp",
+            name.to_string_lossy(),
+            rand::random::<u32>()
+        ));
+
+        let result = match tokio::fs::write(&tmp, text).await {
+            Ok(()) => tokio::fs::rename(&tmp, path).await,
+            Err(x) => Err(x),
+        };
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+        result
+    }
+}
+
+/// Verifies the host key presented by a server against the [`KnownHosts`] of a client
+#[derive(Clone, Debug)]
+pub struct HostKeyVerifier {
+    host: String,
+    known_hosts: KnownHosts,
+    policy: HostKeyPolicy,
+}
+
+impl HostKeyVerifier {
+    /// Creates a new verifier for the server identified by `host`
+    pub fn new(host: impl Into<String>, known_hosts: KnownHosts, policy: HostKeyPolicy) -> Self {
+        Self {
+            host: host.into(),
+            known_hosts,
+            policy,
+        }
+    }
+
+    /// Returns the known hosts used by the verifier
+    pub fn known_hosts(&self) -> &KnownHosts {
+        &self.known_hosts
+    }
+
+    /// Verifies `key`, the host key presented by the server (if any), asking `handler` to confirm
+    /// through [`Verification`] when the key cannot be trusted automatically. Newly-trusted keys
+    /// are saved to the known hosts.
+    pub async fn verify<H: AuthHandler + ?Sized>(
+        &mut self,
+        key: Option<&HostKey>,
+        handler: &mut H,
+    ) -> io::Result<()> {
+        let host = self.host.as_str();
+        let key = match (key, self.known_hosts.get(host)) {
+            (Some(key), Some(known)) if key == known => return Ok(()),
+            (Some(key), Some(known)) => {
+                let text = format!(
+                    "WARNING: The host key for {host} has changed!\n\
+                     This could mean that someone is intercepting your connection.\n\
+                     Expected fingerprint: {}\n\
+                     Received fingerprint: {}\n\
+                     Do you want to trust the new key?",
+                    known.fingerprint(),
+                    key.fingerprint(),
+                );
+                self.ask(handler, text).await?;
+                key
+            }
+            (Some(key), None) => {
+                if self.policy == HostKeyPolicy::Strict {
+                    let text = format!(
+                        "The authenticity of host {host} cannot be established.\n\
+                         Fingerprint: {}\n\
+                         Do you want to trust this host?",
+                        key.fingerprint(),
+                    );
+                    self.ask(handler, text).await?;
+                } else {
+                    debug!("Trusting {host} on first use: {}", key.fingerprint());
+                }
+                key
+            }
+            (None, Some(_)) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("{host} did not present its known host key"),
+                ))
+            }
+            (None, None) => {
+                if self.policy == HostKeyPolicy::Strict {
+                    return Err(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        format!("{host} did not present a host key"),
+                    ));
+                }
+
+                warn!("{host} did not present a host key, so its identity cannot be verified");
+                return Ok(());
+            }
+        };
+
+        self.known_hosts.insert(host, *key);
+        self.known_hosts.save().await
+    }
+
+    async fn ask<H: AuthHandler + ?Sized>(&self, handler: &mut H, text: String) -> io::Result<()> {
+        let response = handler
+            .on_verification(Verification {
+                kind: VerificationKind::Host,
+                text,
+            })
+            .await?;
+
+        if response.valid {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("Host key for {} was rejected", self.host),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use distant_auth::msg::VerificationResponse;
+    use distant_auth::tests::TestAuthHandler;
+    use test_log::test;
+
+    use super::*;
+    use crate::common::ServerIdentity;
+
+    fn handler(valid: bool) -> TestAuthHandler {
+        TestAuthHandler {
+            on_verification: Box::new(move |x| {
+                assert_eq!(x.kind, VerificationKind::Host);
+                Ok(VerificationResponse { valid })
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn unreachable_handler() -> TestAuthHandler {
+        TestAuthHandler {
+            on_verification: Box::new(|_| unreachable!("Unexpected verification")),
+            ..Default::default()
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn verify_should_trust_unknown_host_on_first_use() {
+        let key = ServerIdentity::generate().host_key();
+        let mut verifier =
+            HostKeyVerifier::new("host", KnownHosts::new(), HostKeyPolicy::TrustOnFirstUse);
+
+        verifier
+            .verify(Some(&key), &mut unreachable_handler())
+            .await
+            .unwrap();
+        assert_eq!(verifier.known_hosts().get("host"), Some(&key));
+    }
+
+    #[test(tokio::test)]
+    async fn verify_should_ask_handler_about_unknown_host_when_strict() {
+        let key = ServerIdentity::generate().host_key();
+        let mut verifier = HostKeyVerifier::new("host", KnownHosts::new(), HostKeyPolicy::Strict);
+
+        let err = verifier
+            .verify(Some(&key), &mut handler(false))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(verifier.known_hosts().get("host"), None);
+
+        verifier
+            .verify(Some(&key), &mut handler(true))
+            .await
+            .unwrap();
+        assert_eq!(verifier.known_hosts().get("host"), Some(&key));
+    }
+
+    #[test(tokio::test)]
+    async fn verify_should_ask_handler_about_changed_host_key() {
+        let old_key = ServerIdentity::generate().host_key();
+        let new_key = ServerIdentity::generate().host_key();
+        let mut known_hosts = KnownHosts::new();
+        known_hosts.insert("host", old_key);
+        let mut verifier =
+            HostKeyVerifier::new("host", known_hosts, HostKeyPolicy::TrustOnFirstUse);
+
+        // Matching key should not need the handler
+        verifier
+            .verify(Some(&old_key), &mut unreachable_handler())
+            .await
+            .unwrap();
+
+        let err = verifier
+            .verify(Some(&new_key), &mut handler(false))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert_eq!(verifier.known_hosts().get("host"), Some(&old_key));
+
+        verifier
+            .verify(Some(&new_key), &mut handler(true))
+            .await
+            .unwrap();
+        assert_eq!(verifier.known_hosts().get("host"), Some(&new_key));
+    }
+
+    #[test(tokio::test)]
+    async fn verify_should_fail_if_known_host_presents_no_key() {
+        let mut known_hosts = KnownHosts::new();
+        known_hosts.insert("host", ServerIdentity::generate().host_key());
+        let mut verifier =
+            HostKeyVerifier::new("host", known_hosts, HostKeyPolicy::TrustOnFirstUse);
+
+        let err = verifier
+            .verify(None, &mut unreachable_handler())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+
+    #[test(tokio::test)]
+    async fn known_hosts_should_support_saving_and_loading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        let key = ServerIdentity::generate().host_key();
+
+        let mut known_hosts = KnownHosts::load(&path).await.unwrap();
+        assert_eq!(known_hosts.get("example.com:8080"), None);
+
+        known_hosts.insert("example.com:8080", key);
+        known_hosts.save().await.unwrap();
+
+        let known_hosts = KnownHosts::load(&path).await.unwrap();
+        assert_eq!(known_hosts.get("example.com:8080"), Some(&key));
+    }
+
+    #[test(tokio::test)]
+    async fn known_hosts_save_should_replace_file_without_leaving_temporary_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        std::fs::write(&path, "# old contents\n").unwrap();
+
+        let mut known_hosts = KnownHosts::load(&path).await.unwrap();
+        known_hosts.insert("example.com:8080", ServerIdentity::generate().host_key());
+        known_hosts.save().await.unwrap();
+
+        let names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["known_hosts"]);
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("old"));
+
+        // Failing to replace the file, here as it is a directory, should also clean up after itself
+        let path = dir.path().join("dir");
+        std::fs::create_dir(&path).unwrap();
+        let mut known_hosts = KnownHosts {
+            path: Some(path),
+            ..Default::default()
+        };
+        known_hosts.insert("example.com:8080", ServerIdentity::generate().host_key());
+        known_hosts.save().await.unwrap_err();
+
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["dir", "known_hosts"]);
+    }
+}
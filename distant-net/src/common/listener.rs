@@ -20,6 +20,9 @@ mod unix;
 #[cfg(unix)]
 pub use unix::*;
 
+mod websocket;
+pub use websocket::*;
+
 #[cfg(windows)]
 mod windows;
 
@@ -0,0 +1,196 @@
+use std::net::IpAddr;
+use std::time::Duration;
+use std::{fmt, io};
+
+use async_trait::async_trait;
+use log::*;
+use tokio::net::TcpListener as TokioTcpListener;
+use tokio::task::JoinHandle;
+
+use super::{Listener, MpscListener};
+use crate::common::{PortRange, WebSocketTransport};
+
+/// Maximum number of connections that have completed their WebSocket handshake and are waiting to
+/// be accepted
+const BACKLOG: usize = 32;
+
+/// Maximum time to wait for a connection to complete its WebSocket handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Represents a [`Listener`] for incoming connections over WebSocket
+///
+/// Handshakes are performed in the background so that connections that are slow or are not
+/// WebSockets do not hold up accepting other connections.
+pub struct WebSocketListener {
+    addr: IpAddr,
+    port: u16,
+    inner: MpscListener<WebSocketTransport>,
+    task: JoinHandle<()>,
+}
+
+impl WebSocketListener {
+    /// Creates a new listener by binding to the specified IP address and port
+    /// in the given port range
+    pub async fn bind(addr: IpAddr, port: impl Into<PortRange>) -> io::Result<Self> {
+        let listener =
+            TokioTcpListener::bind(port.into().make_socket_addrs(addr).as_slice()).await?;
+
+        // Get the port that we bound to
+        let port = listener.local_addr()?.port();
+
+        let (tx, inner) = MpscListener::channel(BACKLOG);
+        let task = tokio::spawn(async move {
+            loop {
+                let (stream, peer_addr) = match listener.accept().await {
+                    Ok(x) => x,
+                    Err(x) => {
+                        error!("WebSocket listener failed to accept connection: {x}");
+                        break;
+                    }
+                };
+
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    let handshake = tokio_tungstenite::accept_async(stream);
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake).await {
+                        Ok(Ok(stream)) => {
+                            let _ = tx.send(WebSocketTransport::from_accepted(stream)).await;
+                        }
+                        Ok(Err(x)) => debug!("WebSocket handshake with {peer_addr} failed: {x}"),
+                        Err(_) => debug!("WebSocket handshake with {peer_addr} timed out"),
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            addr,
+            port,
+            inner,
+            task,
+        })
+    }
+
+    /// Returns the IP address that the listener is bound to
+    pub fn ip_addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the port that the listener is bound to
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl fmt::Debug for WebSocketListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketListener")
+            .field("addr", &self.addr)
+            .field("port", &self.port)
+            .finish()
+    }
+}
+
+impl Drop for WebSocketListener {
+    /// Stops accepting connections, freeing up the port
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[async_trait]
+impl Listener for WebSocketListener {
+    type Output = WebSocketTransport;
+
+    async fn accept(&mut self) -> io::Result<Self::Output> {
+        self.inner.accept().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use test_log::test;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpStream;
+
+    use super::*;
+    use crate::common::{Reconnectable, TransportExt};
+
+    #[test(tokio::test)]
+    async fn should_fail_to_bind_if_port_already_bound() {
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        let listener = WebSocketListener::bind(addr, 0)
+            .await
+            .expect("Unexpectedly failed to bind first time");
+        let port = listener.port();
+
+        WebSocketListener::bind(addr, port)
+            .await
+            .expect_err(&format!(
+                "Unexpectedly succeeded in binding a second time to {}:{}",
+                addr, port,
+            ));
+    }
+
+    #[test(tokio::test)]
+    async fn should_be_able_to_receive_connections_and_read_and_write_data_with_them() {
+        let mut listener = WebSocketListener::bind(IpAddr::V4(Ipv4Addr::LOCALHOST), 0)
+            .await
+            .expect("Failed to bind");
+        let url = format!("ws://127.0.0.1:{}", listener.port());
+
+        // A connection that never performs a handshake should not block other connections
+        let mut stalled = TcpStream::connect(("127.0.0.1", listener.port()))
+            .await
+            .expect("Stalled conn failed to connect");
+        stalled
+            .write_all(b"GET / HTTP/1.1\r\n")
+            .await
+            .expect("Stalled conn failed to write");
+
+        let client = WebSocketTransport::connect(url)
+            .await
+            .expect("Conn failed to connect");
+        let server = listener.accept().await.expect("Failed to accept conn");
+
+        client
+            .write_all(b"hello server")
+            .await
+            .expect("Conn failed to write");
+        let mut buf: [u8; 12] = [0; 12];
+        server
+            .read_exact(&mut buf)
+            .await
+            .expect("Server failed to read");
+        assert_eq!(&buf, b"hello server");
+
+        server
+            .write_all(b"hello conn")
+            .await
+            .expect("Server failed to write");
+        let mut buf: [u8; 10] = [0; 10];
+        client
+            .read_exact(&mut buf)
+            .await
+            .expect("Conn failed to read");
+        assert_eq!(&buf, b"hello conn");
+    }
+
+    #[test(tokio::test)]
+    async fn accepted_connections_should_fail_to_reconnect() {
+        let mut listener = WebSocketListener::bind(IpAddr::V4(Ipv4Addr::LOCALHOST), 0)
+            .await
+            .expect("Failed to bind");
+
+        let _client = WebSocketTransport::connect(format!("ws://127.0.0.1:{}", listener.port()))
+            .await
+            .expect("Conn failed to connect");
+        let mut server = listener.accept().await.expect("Failed to accept conn");
+
+        let err = server.reconnect().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
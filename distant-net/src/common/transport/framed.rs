@@ -7,9 +7,10 @@ use bytes::{Buf, BytesMut};
 use log::*;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use super::{InmemoryTransport, Interest, Ready, Reconnectable, Transport};
-use crate::common::{utils, SecretKey32};
+use crate::common::{utils, HostKey, SecretKey32, ServerIdentity};
 
 mod backup;
 mod codec;
@@ -49,6 +50,9 @@ pub struct FramedTransport<T> {
 
     /// Stores outgoing frames in case of transmission issues
     pub backup: Backup,
+
+    /// Host key of the server verified during the last handshake (client-side only)
+    host_key: Option<HostKey>,
 }
 
 impl<T> FramedTransport<T> {
@@ -59,6 +63,7 @@ impl<T> FramedTransport<T> {
             incoming: BytesMut::with_capacity(READ_BUF_SIZE * 2),
             outgoing: BytesMut::with_capacity(READ_BUF_SIZE * 2),
             backup: Backup::new(),
+            host_key: None,
         }
     }
 
@@ -104,6 +109,13 @@ impl<T> FramedTransport<T> {
         self.outgoing.clear();
     }
 
+    /// Returns the host key that the server proved ownership of during the last handshake. This
+    /// is only ever available client-side when the server has an identity and encryption was
+    /// negotiated.
+    pub fn host_key(&self) -> Option<&HostKey> {
+        self.host_key.as_ref()
+    }
+
     /// Returns a reference to the inner value this transport wraps.
     pub fn as_inner(&self) -> &T {
         &self.inner
@@ -139,6 +151,7 @@ impl<T: Transport + 'static> FramedTransport<T> {
             incoming: self.incoming,
             outgoing: self.outgoing,
             backup: self.backup,
+            host_key: self.host_key,
         }
     }
 }
@@ -253,19 +266,28 @@ impl<T: Transport> FramedTransport<T> {
     ///
     /// [`ErrorKind::WouldBlock`]: io::ErrorKind::WouldBlock
     pub fn try_read_frame(&mut self) -> io::Result<Option<OwnedFrame>> {
-        // Attempt to read a frame, returning the decoded frame if we get one, returning any error
-        // that is encountered from reading frames or failing to decode, or otherwise doing nothing
-        // and continuing forward.
+        match self.try_read_raw_frame()? {
+            Some(frame) => {
+                if frame.is_nonempty() {
+                    self.backup.increment_received_cnt();
+                }
+                Ok(Some(self.codec.decode(frame)?.into_owned()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Like [`try_read_frame`], but returns the frame without decoding it and without updating
+    /// the [`Backup`].
+    ///
+    /// [`try_read_frame`]: FramedTransport::try_read_frame
+    fn try_read_raw_frame(&mut self) -> io::Result<Option<OwnedFrame>> {
+        // Attempt to read a frame, returning it if we get one, returning any error that is
+        // encountered from reading frames, or otherwise doing nothing and continuing forward.
         macro_rules! read_next_frame {
             () => {{
-                match Frame::read(&mut self.incoming) {
-                    None => (),
-                    Some(frame) => {
-                        if frame.is_nonempty() {
-                            self.backup.increment_received_cnt();
-                        }
-                        return Ok(Some(self.codec.decode(frame)?.into_owned()));
-                    }
+                if let Some(frame) = Frame::read(&mut self.incoming) {
+                    return Ok(Some(frame));
                 }
             }};
         }
@@ -335,6 +357,25 @@ impl<T: Transport> FramedTransport<T> {
         }
     }
 
+    /// Continues to invoke [`try_read_raw_frame`] until a frame is read, an error is encountered
+    /// that is not [`ErrorKind::WouldBlock`], or the underlying transport has closed.
+    ///
+    /// [`try_read_raw_frame`]: FramedTransport::try_read_raw_frame
+    /// [`ErrorKind::WouldBlock`]: io::ErrorKind::WouldBlock
+    async fn read_raw_frame(&mut self) -> io::Result<Option<OwnedFrame>> {
+        loop {
+            self.readable().await?;
+
+            match self.try_read_raw_frame() {
+                Err(x) if x.kind() == io::ErrorKind::WouldBlock => {
+                    // NOTE: We sleep for a little bit before trying again to avoid pegging CPU
+                    tokio::time::sleep(SLEEP_DURATION).await
+                }
+                x => return x,
+            }
+        }
+    }
+
     /// Reads a frame using [`read_frame`] and then deserializes the bytes into `D`.
     ///
     /// [`read_frame`]: FramedTransport::read_frame
@@ -525,14 +566,18 @@ impl<T: Transport> FramedTransport<T> {
             }
             this.flush().await?;
 
-            // Receive all expected frames, placing their contents into our incoming queue
+            // Receive all expected frames, placing them back at the front of our incoming queue
             //
             // NOTE: We do not increment our counter as this is done during `try_read_frame`, even
             //       when the frame comes from our internal queue. To avoid duplicating the increment,
             //       we do not increment the counter here.
+            //
+            // NOTE: Frames are kept exactly as they were received rather than decoded and encoded
+            //       again, as codecs like the `RekeyCodec` use different state for each direction
             debug!("Waiting for {expected_cnt} frames");
+            let mut received = BytesMut::new();
             for i in 0..expected_cnt {
-                let frame = this.read_frame().await?.ok_or_else(|| {
+                let frame = this.read_raw_frame().await?.ok_or_else(|| {
                     io::Error::new(
                         io::ErrorKind::UnexpectedEof,
                         format!(
@@ -542,10 +587,10 @@ impl<T: Transport> FramedTransport<T> {
                     )
                 })?;
 
-                // Encode our frame and write it to be queued in our incoming data
-                // NOTE: We have to do encoding here as incoming bytes are expected to be encoded
-                this.codec.encode(frame)?.write(&mut this.incoming);
+                frame.write(&mut received);
             }
+            received.extend_from_slice(&this.incoming);
+            this.incoming = received;
 
             // Catch up our read count as we can have the case where the other side has a higher
             // count than frames sent if some frames were fully dropped due to size limits
@@ -678,37 +723,61 @@ impl<T: Transport> FramedTransport<T> {
         struct Choice {
             compression_level: Option<CompressionLevel>,
             compression_type: Option<CompressionType>,
+            #[serde(default)]
+            compression_adaptive: bool,
             encryption_type: Option<EncryptionType>,
+            #[serde(default)]
+            rekey_policy: Option<RekeyPolicy>,
+            #[serde(default)]
+            identity: bool,
         }
 
         #[derive(Debug, Serialize, Deserialize)]
         struct Options {
             compression_types: Vec<CompressionType>,
             encryption_types: Vec<EncryptionType>,
+            #[serde(default)]
+            compression_adaptive: bool,
+            #[serde(default)]
+            rekey: bool,
+            #[serde(default)]
+            identity: bool,
         }
 
+        // Forget any host key from a previous handshake as it is re-established below
+        self.host_key = None;
+        let is_client = handshake.is_client();
+        let identity = match &handshake {
+            Handshake::Server { identity, .. } => identity.clone(),
+            Handshake::Client { .. } => None,
+        };
+
         // Define a label to distinguish log output for client and server
-        let log_label = if handshake.is_client() {
+        let log_label = if is_client {
             "Handshake | Client"
         } else {
             "Handshake | Server"
         };
 
-        // Determine compression and encryption to apply to framed transport
-        let choice = match handshake {
+        // Determine compression and encryption to apply to framed transport, keeping the bytes of
+        // the options and choice as they were sent so that they can be covered by the identity proof
+        let (choice, options_data, choice_data) = match handshake {
             Handshake::Client {
                 preferred_compression_type,
                 preferred_compression_level,
+                adaptive_compression,
                 preferred_encryption_type,
+                rekey_policy,
             } => {
                 // Receive options from the server and pick one
                 debug!("[{log_label}] Waiting on options");
-                let options = self.read_frame_as::<Options>().await?.ok_or_else(|| {
+                let options_data = self.read_frame().await?.ok_or_else(|| {
                     io::Error::new(
                         io::ErrorKind::UnexpectedEof,
                         "Transport closed early while waiting for options",
                     )
                 })?;
+                let options: Options = utils::deserialize_from_slice(options_data.as_item())?;
 
                 // Choose a compression and encryption option from the options
                 debug!("[{log_label}] Selecting from options: {options:?}");
@@ -721,6 +790,10 @@ impl<T: Transport> FramedTransport<T> {
                     // Use preferred compression level, otherwise allowing the server to pick
                     compression_level: preferred_compression_level,
 
+                    // Only compress frames likely to benefit if requested and the server knows how
+                    // to do so, as it would otherwise expect every frame to be compressed
+                    compression_adaptive: adaptive_compression && options.compression_adaptive,
+
                     // Use preferred encryption, otherwise pick first non-unknown encryption type
                     // that is available instead
                     encryption_type: preferred_encryption_type
@@ -732,49 +805,72 @@ impl<T: Transport> FramedTransport<T> {
                                 .find(|ty| !ty.is_unknown())
                                 .copied()
                         }),
+
+                    // Only replace keys if the server knows how to do so
+                    rekey_policy: rekey_policy.filter(|_| options.rekey),
+
+                    // Only wait on proof of identity if the server knows how to provide it
+                    identity: options.identity,
                 };
 
                 // Report back to the server the choice
                 debug!("[{log_label}] Reporting choice: {choice:?}");
-                self.write_frame_for(&choice).await?;
+                let choice_data = utils::serialize_to_vec(&choice)?;
+                self.write_frame(choice_data.as_slice()).await?;
 
-                choice
+                (choice, options_data.into_item().into_owned(), choice_data)
             }
             Handshake::Server {
                 compression_types,
                 encryption_types,
+                ..
             } => {
                 let options = Options {
                     compression_types: compression_types.to_vec(),
                     encryption_types: encryption_types.to_vec(),
+                    compression_adaptive: true,
+                    rekey: true,
+                    identity: true,
                 };
 
                 // Send options to the client
                 debug!("[{log_label}] Sending options: {options:?}");
-                self.write_frame_for(&options).await?;
+                let options_data = utils::serialize_to_vec(&options)?;
+                self.write_frame(options_data.as_slice()).await?;
 
                 // Get client's response with selected compression and encryption
                 debug!("[{log_label}] Waiting on choice");
-                self.read_frame_as::<Choice>().await?.ok_or_else(|| {
+                let choice_data = self.read_frame().await?.ok_or_else(|| {
                     io::Error::new(
                         io::ErrorKind::UnexpectedEof,
                         "Transport closed early while waiting for choice",
                     )
-                })?
+                })?;
+                let choice: Choice = utils::deserialize_from_slice(choice_data.as_item())?;
+
+                (choice, options_data, choice_data.into_item().into_owned())
             }
         };
 
         debug!("[{log_label}] Building compression & encryption codecs based on {choice:?}");
         let compression_level = choice.compression_level.unwrap_or_default();
 
-        // Acquire a codec for the compression type
+        // Acquire a codec for the compression type, wrapping it to only compress some frames if
+        // adaptive compression was chosen
         let compression_codec = choice
             .compression_type
             .map(|ty| ty.new_codec(compression_level))
-            .transpose()?;
+            .transpose()?
+            .map(|codec| -> BoxedCodec {
+                if choice.compression_adaptive {
+                    Box::new(AdaptiveCodec::new(codec))
+                } else {
+                    Box::new(codec)
+                }
+            });
 
         // In the case that we are using encryption, we derive a shared secret key to use with the
-        // encryption type
+        // encryption type, periodically replacing the key if a rekey policy was chosen
         let encryption_codec = match choice.encryption_type {
             // Fail early if we got an unknown encryption type
             Some(EncryptionType::Unknown) => {
@@ -784,8 +880,28 @@ impl<T: Transport> FramedTransport<T> {
                 ))
             }
             Some(ty) => {
-                let key = self.exchange_keys_impl(log_label).await?;
-                Some(ty.new_codec(key.unprotected_as_bytes())?)
+                let (key, transcript) = self.exchange_keys_impl(log_label).await?;
+                if choice.identity {
+                    // Sign the options and choice alongside the key exchange as they are sent in
+                    // plaintext and could otherwise be altered by someone in the middle
+                    let transcript = Sha256::new()
+                        .chain_update(Sha256::digest(&options_data))
+                        .chain_update(Sha256::digest(&choice_data))
+                        .chain_update(transcript)
+                        .finalize()
+                        .to_vec();
+                    self.exchange_identity_impl(
+                        log_label,
+                        is_client,
+                        identity.as_ref(),
+                        &transcript,
+                    )
+                    .await?;
+                }
+                Some(match choice.rekey_policy {
+                    Some(policy) => Box::new(RekeyCodec::new(ty, key, policy)?) as BoxedCodec,
+                    None => Box::new(ty.new_codec(key.unprotected_as_bytes())?),
+                })
             }
             None => None,
         };
@@ -793,15 +909,15 @@ impl<T: Transport> FramedTransport<T> {
         // Bundle our compression and encryption codecs into a single, chained codec
         trace!("[{log_label}] Bundling codecs");
         let codec: BoxedCodec = match (compression_codec, encryption_codec) {
-            // If we have both encryption and compression, do the encryption first and then
-            // compress in order to get smallest result
-            (Some(c), Some(e)) => Box::new(ChainCodec::new(e, c)),
+            // If we have both encryption and compression, do the compression first and then
+            // encrypt as encrypted data looks random and would not compress
+            (Some(c), Some(e)) => Box::new(ChainCodec::new(c, e)),
 
             // If we just have compression, pass along the compression codec
-            (Some(c), None) => Box::new(c),
+            (Some(c), None) => c,
 
             // If we just have encryption, pass along the encryption codec
-            (None, Some(e)) => Box::new(e),
+            (None, Some(e)) => e,
 
             // If we have neither compression nor encryption, use a plaintext codec
             (None, None) => Box::new(PlainCodec::new()),
@@ -813,10 +929,12 @@ impl<T: Transport> FramedTransport<T> {
     /// Places the transport into key-exchange mode where it attempts to derive a shared secret key
     /// with the other transport.
     pub async fn exchange_keys(&mut self) -> io::Result<SecretKey32> {
-        self.exchange_keys_impl("").await
+        Ok(self.exchange_keys_impl("").await?.0)
     }
 
-    async fn exchange_keys_impl(&mut self, label: &str) -> io::Result<SecretKey32> {
+    /// Performs the key exchange, returning the shared secret key alongside a transcript of the
+    /// exchange that both sides compute identically, suitable for signing
+    async fn exchange_keys_impl(&mut self, label: &str) -> io::Result<(SecretKey32, Vec<u8>)> {
         let log_label = if label.is_empty() {
             String::new()
         } else {
@@ -857,9 +975,77 @@ impl<T: Transport> FramedTransport<T> {
                 )
             })?;
 
+        // Both sides hash the exchanged data in the same order, regardless of who sent what
+        let mut ours: Vec<u8> = exchange.pk_bytes().into();
+        ours.extend_from_slice(exchange.salt().as_ref());
+        let mut theirs: Vec<u8> = data.public_key.clone().into();
+        theirs.extend_from_slice(data.salt.as_ref());
+        let (first, second) = if ours <= theirs {
+            (ours, theirs)
+        } else {
+            (theirs, ours)
+        };
+        let transcript = Sha256::new()
+            .chain_update(first)
+            .chain_update(second)
+            .finalize()
+            .to_vec();
+
         trace!("{log_label}Deriving shared secret key");
         let key = exchange.derive_shared_secret(data.public_key, data.salt)?;
-        Ok(key)
+        Ok((key, transcript))
+    }
+
+    /// Has the server prove its identity (if it has one) by signing the `transcript` of the
+    /// handshake, which the client verifies and records as the host key of the transport
+    async fn exchange_identity_impl(
+        &mut self,
+        label: &str,
+        is_client: bool,
+        identity: Option<&ServerIdentity>,
+        transcript: &[u8],
+    ) -> io::Result<()> {
+        #[derive(Serialize, Deserialize)]
+        struct IdentityProof {
+            /// Public key of the server's identity
+            host_key: HostKey,
+
+            /// Signature of the key exchange transcript
+            #[serde(with = "serde_bytes")]
+            signature: Vec<u8>,
+        }
+
+        if is_client {
+            trace!("[{label}] Waiting on identity proof");
+            let proof = self
+                .read_frame_as::<Option<IdentityProof>>()
+                .await?
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "Transport closed early while waiting for identity proof",
+                    )
+                })?;
+
+            if let Some(proof) = proof {
+                debug!(
+                    "[{label}] Verifying host key {}",
+                    proof.host_key.fingerprint()
+                );
+                proof.host_key.verify(transcript, &proof.signature)?;
+                self.host_key = Some(proof.host_key);
+            }
+        } else {
+            let proof = identity.map(|identity| IdentityProof {
+                host_key: identity.host_key(),
+                signature: identity.sign(transcript),
+            });
+
+            debug!("[{label}] Sending identity proof");
+            self.write_frame_for(&proof).await?;
+        }
+
+        Ok(())
     }
 }
 
@@ -1913,6 +2099,56 @@ mod tests {
         assert_eq!(t2.read_frame().await.unwrap().unwrap(), b"world");
     }
 
+    #[test(tokio::test)]
+    async fn synchronize_should_work_with_codecs_that_rekey() {
+        let (mut t1, _t1_other) = FramedTransport::pair(100);
+        let (mut t2, _t2_other) = FramedTransport::pair(100);
+
+        // Send some frames from each side
+        t1.write_frame(Frame::new(b"hello")).await.unwrap();
+        t1.write_frame(Frame::new(b"world")).await.unwrap();
+        t2.write_frame(Frame::new(b"foo")).await.unwrap();
+        t2.write_frame(Frame::new(b"bar")).await.unwrap();
+
+        // Drop the other transports, link our real transports together, and change the codec to
+        // one that switches keys with every frame
+        drop(_t1_other);
+        drop(_t2_other);
+        t1.link(&mut t2, 100);
+        let codec = RekeyCodec::new(
+            EncryptionType::XChaCha20Poly1305,
+            Default::default(),
+            RekeyPolicy {
+                max_bytes: None,
+                max_interval: Some(Duration::ZERO),
+            },
+        )
+        .unwrap();
+        t1.codec = Box::new(codec.clone());
+        t2.codec = Box::new(codec);
+
+        // Spawn a separate task to do synchronization so we don't deadlock
+        let task = tokio::spawn(async move {
+            t2.synchronize().await.unwrap();
+            t2
+        });
+
+        t1.synchronize().await.unwrap();
+
+        // Verify that we get the appropriate frames from both sides
+        let mut t2 = task.await.unwrap();
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"foo");
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"bar");
+        assert_eq!(t2.read_frame().await.unwrap().unwrap(), b"hello");
+        assert_eq!(t2.read_frame().await.unwrap().unwrap(), b"world");
+
+        // Verify that the transports can still communicate after synchronizing
+        t1.write_frame(Frame::new(b"after")).await.unwrap();
+        assert_eq!(t2.read_frame().await.unwrap().unwrap(), b"after");
+        t2.write_frame(Frame::new(b"after")).await.unwrap();
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"after");
+    }
+
     #[test(tokio::test)]
     async fn handshake_should_configure_transports_with_matching_codec() {
         let (mut t1, mut t2) = FramedTransport::test_pair(100);
@@ -1938,6 +2174,376 @@ mod tests {
         task.await.unwrap();
     }
 
+    #[test(tokio::test)]
+    async fn handshake_should_support_aes256gcm_when_preferred() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.server_handshake().await.unwrap();
+
+            // Receive one frame and echo it back
+            let frame = t2.read_frame().await.unwrap().unwrap();
+            t2.write_frame(frame).await.unwrap();
+        });
+
+        t1.handshake(
+            Handshake::client().with_preferred_encryption_type(Some(EncryptionType::Aes256Gcm)),
+        )
+        .await
+        .unwrap();
+
+        t1.write_frame(b"hello world").await.unwrap();
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"hello world");
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_fall_back_to_another_encryption_type_if_preferred_is_unavailable() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.handshake(Handshake::Server {
+                compression_types: Vec::new(),
+                encryption_types: vec![EncryptionType::XChaCha20Poly1305],
+                identity: None,
+            })
+            .await
+            .unwrap();
+
+            // Receive one frame and echo it back
+            let frame = t2.read_frame().await.unwrap().unwrap();
+            t2.write_frame(frame).await.unwrap();
+        });
+
+        t1.handshake(
+            Handshake::client().with_preferred_encryption_type(Some(EncryptionType::Aes256Gcm)),
+        )
+        .await
+        .unwrap();
+
+        t1.write_frame(b"hello world").await.unwrap();
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"hello world");
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_support_rekeying_while_frames_are_in_flight() {
+        const CNT: usize = 32;
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.server_handshake().await.unwrap();
+
+            // Receive frames and echo them back, switching keys along the way
+            for _ in 0..CNT {
+                let frame = t2.read_frame().await.unwrap().unwrap();
+                t2.write_frame(frame).await.unwrap();
+            }
+        });
+
+        t1.handshake(Handshake::client().with_rekey_policy(Some(RekeyPolicy {
+            max_bytes: Some(64),
+            max_interval: None,
+        })))
+        .await
+        .unwrap();
+
+        // Queue up all frames before reading any echoes so that keys are switched while frames
+        // encoded with earlier keys are still in flight in both directions
+        for i in 0..CNT {
+            t1.write_frame(format!("frame {i}").as_bytes())
+                .await
+                .unwrap();
+        }
+
+        for i in 0..CNT {
+            assert_eq!(
+                t1.read_frame().await.unwrap().unwrap(),
+                format!("frame {i}").as_bytes()
+            );
+        }
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_support_rekeying_on_an_interval() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.server_handshake().await.unwrap();
+
+            // Receive frames and echo them back
+            for _ in 0..3 {
+                let frame = t2.read_frame().await.unwrap().unwrap();
+                t2.write_frame(frame).await.unwrap();
+            }
+        });
+
+        t1.handshake(Handshake::client().with_rekey_policy(Some(RekeyPolicy {
+            max_bytes: None,
+            max_interval: Some(Duration::from_millis(10)),
+        })))
+        .await
+        .unwrap();
+
+        for _ in 0..3 {
+            t1.write_frame(b"hello world").await.unwrap();
+            assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"hello world");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_support_adaptive_compression_when_preferred() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.server_handshake().await.unwrap();
+
+            // Receive frames and echo them back
+            for _ in 0..2 {
+                let frame = t2.read_frame().await.unwrap().unwrap();
+                t2.write_frame(frame).await.unwrap();
+            }
+        });
+
+        t1.handshake(Handshake::Client {
+            preferred_compression_type: Some(CompressionType::Zstd),
+            preferred_compression_level: None,
+            adaptive_compression: true,
+            preferred_encryption_type: None,
+            rekey_policy: None,
+        })
+        .await
+        .unwrap();
+
+        // Verify that both small and large frames make it through
+        let large = b"hello world".repeat(1024);
+        t1.write_frame(b"hello world").await.unwrap();
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"hello world");
+        t1.write_frame(large.as_slice()).await.unwrap();
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), large.as_slice());
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_compress_frames_before_encrypting_them() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.server_handshake().await.unwrap();
+
+            // Read the frame as it was sent over the wire
+            t2.set_codec(Box::new(PlainCodec::new()));
+            t2.read_frame().await.unwrap().unwrap()
+        });
+
+        t1.handshake(
+            Handshake::client().with_preferred_compression_type(Some(CompressionType::Zstd), true),
+        )
+        .await
+        .unwrap();
+
+        let data = b"hello world".repeat(1024);
+        t1.write_frame(data.as_slice()).await.unwrap();
+
+        // Encrypted frame should still be much smaller than the data as it was compressed first
+        let frame = task.await.unwrap();
+        assert_ne!(frame.as_item(), data.as_slice());
+        assert!(
+            frame.len() < data.len() / 10,
+            "Frame was not compressed: {} bytes",
+            frame.len()
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_not_choose_adaptive_compression_if_server_does_not_offer_it() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Options {
+            compression_types: Vec<CompressionType>,
+            encryption_types: Vec<EncryptionType>,
+        }
+
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Choice {
+            compression_type: Option<CompressionType>,
+            compression_adaptive: bool,
+        }
+
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        // Act as a server that predates adaptive compression
+        let task = tokio::spawn(async move {
+            t2.write_frame_for(&Options {
+                compression_types: CompressionType::known_variants().to_vec(),
+                encryption_types: Vec::new(),
+            })
+            .await
+            .unwrap();
+            t2.read_frame_as::<Choice>().await.unwrap().unwrap()
+        });
+
+        t1.handshake(
+            Handshake::client().with_preferred_compression_type(Some(CompressionType::Zstd), true),
+        )
+        .await
+        .unwrap();
+
+        let choice = task.await.unwrap();
+        assert_eq!(choice.compression_type, Some(CompressionType::Zstd));
+        assert!(!choice.compression_adaptive);
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_provide_client_with_host_key_of_server_identity() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+        let identity = ServerIdentity::generate();
+        let host_key = identity.host_key();
+
+        let task = tokio::spawn(async move {
+            t2.handshake(Handshake::server().with_identity(Some(identity)))
+                .await
+                .unwrap();
+            t2
+        });
+
+        t1.client_handshake().await.unwrap();
+        assert_eq!(t1.host_key(), Some(&host_key));
+
+        // Server never learns a host key of its own
+        let t2 = task.await.unwrap();
+        assert_eq!(t2.host_key(), None);
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_fail_if_options_are_altered_in_transit_to_client_with_identity() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Options {
+            compression_types: Vec<CompressionType>,
+            encryption_types: Vec<EncryptionType>,
+            compression_adaptive: bool,
+            rekey: bool,
+            identity: bool,
+        }
+
+        let (mut client, mut m1) = FramedTransport::test_pair(100);
+        let (mut m2, mut server) = FramedTransport::test_pair(100);
+
+        tokio::spawn(async move {
+            let _ = server
+                .handshake(Handshake::server().with_identity(Some(ServerIdentity::generate())))
+                .await;
+        });
+
+        // Sit in the middle, turning off rekeying and otherwise relaying frames untouched
+        tokio::spawn(async move {
+            let mut options = m2.read_frame_as::<Options>().await.unwrap().unwrap();
+            options.rekey = false;
+            m1.write_frame_for(&options).await.unwrap();
+
+            // Relay choice, key exchange, and identity proof
+            let frame = m1.read_frame().await.unwrap().unwrap();
+            m2.write_frame(frame).await.unwrap();
+            let frame = m1.read_frame().await.unwrap().unwrap();
+            m2.write_frame(frame).await.unwrap();
+            for _ in 0..2 {
+                let frame = m2.read_frame().await.unwrap().unwrap();
+                m1.write_frame(frame).await.unwrap();
+            }
+        });
+
+        let err = client.client_handshake().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{err}");
+        assert!(client.host_key().is_none());
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_not_provide_host_key_if_server_has_no_identity() {
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move { t2.server_handshake().await.unwrap() });
+
+        t1.client_handshake().await.unwrap();
+        assert_eq!(t1.host_key(), None);
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_not_wait_on_identity_proof_if_server_does_not_offer_one() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Options {
+            compression_types: Vec<CompressionType>,
+            encryption_types: Vec<EncryptionType>,
+        }
+
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        // Act as a server that predates identity proofs
+        let task = tokio::spawn(async move {
+            t2.write_frame_for(&Options {
+                compression_types: CompressionType::known_variants().to_vec(),
+                encryption_types: EncryptionType::known_variants().to_vec(),
+            })
+            .await
+            .unwrap();
+            t2.read_frame().await.unwrap().unwrap();
+            t2.exchange_keys().await.unwrap();
+        });
+
+        t1.client_handshake().await.unwrap();
+        assert_eq!(t1.host_key(), None);
+
+        task.await.unwrap();
+    }
+
+    #[test(tokio::test)]
+    async fn handshake_should_not_send_identity_proof_if_client_does_not_request_one() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Choice {
+            compression_level: Option<CompressionLevel>,
+            compression_type: Option<CompressionType>,
+            encryption_type: Option<EncryptionType>,
+        }
+
+        let (mut t1, mut t2) = FramedTransport::test_pair(100);
+
+        let task = tokio::spawn(async move {
+            t2.handshake(Handshake::server().with_identity(Some(ServerIdentity::generate())))
+                .await
+                .unwrap();
+            t2.write_frame(b"hello").await.unwrap();
+        });
+
+        // Act as a client that predates identity proofs
+        t1.read_frame().await.unwrap().unwrap();
+        t1.write_frame_for(&Choice {
+            compression_level: None,
+            compression_type: None,
+            encryption_type: Some(EncryptionType::XChaCha20Poly1305),
+        })
+        .await
+        .unwrap();
+        let key = t1.exchange_keys().await.unwrap();
+
+        // The next frame should be the one sent after the handshake rather than a proof
+        t1.set_codec(Box::new(
+            EncryptionType::XChaCha20Poly1305
+                .new_codec(key.unprotected_as_bytes())
+                .unwrap(),
+        ));
+        assert_eq!(t1.read_frame().await.unwrap().unwrap(), b"hello");
+
+        task.await.unwrap();
+    }
+
     #[test(tokio::test)]
     async fn handshake_failing_should_ensure_existing_codec_remains() {
         let (mut t1, t2) = FramedTransport::test_pair(100);
@@ -4,17 +4,21 @@ use dyn_clone::DynClone;
 
 use super::Frame;
 
+mod adaptive;
 mod chain;
 mod compression;
 mod encryption;
 mod plain;
 mod predicate;
+mod rekey;
 
+pub use adaptive::*;
 pub use chain::*;
 pub use compression::*;
 pub use encryption::*;
 pub use plain::*;
 pub use predicate::*;
+pub use rekey::*;
 
 /// Represents abstraction that implements specific encoder and decoder logic to transform an
 /// arbitrary collection of bytes. This can be used to encrypt and authenticate bytes sent and
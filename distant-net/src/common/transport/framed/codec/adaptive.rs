@@ -0,0 +1,192 @@
+use std::io;
+
+use super::{ChainCodec, Codec, Frame, PredicateCodec};
+
+/// Marker appended to frames whose item was compressed
+const COMPRESSED: u8 = 1;
+
+/// Marker appended to frames whose item was left untouched
+const UNCOMPRESSED: u8 = 0;
+
+/// Predicate used to pick between the compressed and uncompressed codecs
+type Predicate = Box<dyn Fn(&Frame) -> bool + Send + Sync>;
+
+/// Represents a codec that only applies the compression of an inner codec to frames that are
+/// likely to benefit from it, skipping frames that are small or whose contents look to already be
+/// compressed (such as images and archives).
+///
+/// A marker byte is appended to each encoded frame to indicate whether or not it was compressed,
+/// which means that both sides of a transport need to be using an [`AdaptiveCodec`].
+#[derive(Clone)]
+pub struct AdaptiveCodec<T> {
+    encoder: PredicateCodec<ChainCodec<T, MarkerCodec>, MarkerCodec, Predicate>,
+    decoder: PredicateCodec<ChainCodec<T, MarkerCodec>, MarkerCodec, Predicate>,
+}
+
+impl<T: Clone> AdaptiveCodec<T> {
+    /// Default minimum size in bytes of a frame's item before compression is attempted
+    pub const DEFAULT_MIN_SIZE: usize = 512;
+
+    /// Default maximum entropy in bits per byte of a frame's item before it is considered already
+    /// compressed
+    pub const DEFAULT_MAX_ENTROPY: f64 = 7.5;
+
+    /// Creates a new adaptive codec around `codec` using the default minimum size and maximum
+    /// entropy
+    pub fn new(codec: T) -> Self {
+        Self::with_limits(codec, Self::DEFAULT_MIN_SIZE, Self::DEFAULT_MAX_ENTROPY)
+    }
+
+    /// Creates a new adaptive codec around `codec` that only compresses frames whose item is at
+    /// least `min_size` bytes and has an estimated entropy no greater than `max_entropy` bits per
+    /// byte (a value between 0 and 8)
+    pub fn with_limits(codec: T, min_size: usize, max_entropy: f64) -> Self {
+        let compressed = ChainCodec::new(codec, MarkerCodec(COMPRESSED));
+        let uncompressed = MarkerCodec(UNCOMPRESSED);
+
+        Self {
+            encoder: PredicateCodec::new(
+                compressed.clone(),
+                uncompressed,
+                Box::new(move |frame: &Frame| {
+                    frame.len() >= min_size && estimate_entropy(frame.as_item()) <= max_entropy
+                }),
+            ),
+            decoder: PredicateCodec::new(
+                compressed,
+                uncompressed,
+                Box::new(|frame: &Frame| frame.as_item().last() == Some(&COMPRESSED)),
+            ),
+        }
+    }
+
+    /// Returns reference to the codec used to compress frames
+    pub fn as_inner(&self) -> &T {
+        self.encoder.as_left().as_left()
+    }
+}
+
+impl<T: Codec + Clone> Codec for AdaptiveCodec<T> {
+    fn encode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
+        Codec::encode(&mut self.encoder, frame)
+    }
+
+    fn decode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
+        Codec::decode(&mut self.decoder, frame)
+    }
+}
+
+/// Estimates the Shannon entropy of `data` in bits per byte by sampling up to the first 4 KiB
+fn estimate_entropy(data: &[u8]) -> f64 {
+    let sample = &data[..data.len().min(4096)];
+    if sample.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0usize; 256];
+    for byte in sample {
+        counts[*byte as usize] += 1;
+    }
+
+    let len = sample.len() as f64;
+    counts
+        .iter()
+        .filter(|cnt| **cnt > 0)
+        .map(|cnt| {
+            let p = *cnt as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Codec that appends a marker byte when encoding and removes it when decoding, failing if the
+/// frame does not end with the marker
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct MarkerCodec(u8);
+
+impl Codec for MarkerCodec {
+    fn encode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
+        let mut item = frame.into_item().into_owned();
+        item.push(self.0);
+        Ok(Frame::from(item))
+    }
+
+    fn decode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
+        let item = frame.into_item();
+        match item.split_last() {
+            Some((marker, item)) if *marker == self.0 => Ok(Frame::from(item.to_vec())),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Decode failed because frame is missing compression marker",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::RngCore;
+    use test_log::test;
+
+    use super::*;
+    use crate::common::{CompressionCodec, CompressionLevel};
+
+    fn compressible(len: usize) -> Vec<u8> {
+        b"some bytes ".iter().copied().cycle().take(len).collect()
+    }
+
+    fn random(len: usize) -> Vec<u8> {
+        let mut data = vec![0; len];
+        rand::thread_rng().fill_bytes(&mut data);
+        data
+    }
+
+    #[test]
+    fn encode_should_compress_large_compressible_frames() {
+        let mut codec = AdaptiveCodec::new(CompressionCodec::zlib(CompressionLevel::BEST));
+        let data = compressible(4096);
+
+        let frame = codec.encode(Frame::new(&data)).unwrap();
+        assert!(frame.len() < data.len(), "Frame was not compressed");
+        assert_eq!(frame.as_item().last(), Some(&COMPRESSED));
+    }
+
+    #[test]
+    fn encode_should_skip_compression_of_small_frames() {
+        let mut codec = AdaptiveCodec::new(CompressionCodec::zlib(CompressionLevel::BEST));
+        let data = compressible(16);
+
+        let frame = codec.encode(Frame::new(&data)).unwrap();
+        assert_eq!(&frame.as_item()[..data.len()], data.as_slice());
+        assert_eq!(frame.as_item().last(), Some(&UNCOMPRESSED));
+    }
+
+    #[test]
+    fn encode_should_skip_compression_of_high_entropy_frames() {
+        let mut codec = AdaptiveCodec::new(CompressionCodec::zlib(CompressionLevel::BEST));
+        let data = random(4096);
+
+        let frame = codec.encode(Frame::new(&data)).unwrap();
+        assert_eq!(&frame.as_item()[..data.len()], data.as_slice());
+        assert_eq!(frame.as_item().last(), Some(&UNCOMPRESSED));
+    }
+
+    #[test]
+    fn decode_should_reverse_encode_regardless_of_whether_compressed() {
+        let mut codec = AdaptiveCodec::new(CompressionCodec::zlib(CompressionLevel::BEST));
+
+        for data in [compressible(16), compressible(4096), random(4096)] {
+            let frame = codec.encode(Frame::new(&data)).unwrap();
+            let frame = codec.decode(frame).unwrap();
+            assert_eq!(frame, data.as_slice());
+        }
+    }
+
+    #[test]
+    fn decode_should_fail_if_frame_has_unknown_marker() {
+        let mut codec = AdaptiveCodec::new(CompressionCodec::zlib(CompressionLevel::BEST));
+
+        let err = codec.decode(Frame::new(b"some bytes\x02")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
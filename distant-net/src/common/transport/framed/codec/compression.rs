@@ -8,6 +8,16 @@ use serde::{Deserialize, Serialize};
 
 use super::{Codec, Frame};
 
+/// Maximum size (in bytes) of an item decompressed from a frame, which guards against frames that
+/// claim or expand to far more data than was sent
+///
+/// Current setting is 256MB size
+const MAX_DECOMPRESSED_SIZE: usize = 256 * 1024 * 1024;
+
+/// Largest ratio between the decompressed and compressed sizes of an LZ4 block, as each byte of a
+/// block can at most expand to 255 bytes
+const MAX_LZ4_RATIO: usize = 255;
+
 /// Represents the level of compression to apply to data
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum CompressionLevel {
@@ -50,7 +60,9 @@ impl Default for CompressionLevel {
 pub enum CompressionType {
     Deflate,
     Gzip,
+    Lz4,
     Zlib,
+    Zstd,
 
     /// Indicates an unknown compression type for use in handshakes
     #[serde(other)]
@@ -63,7 +75,9 @@ impl CompressionType {
         &[
             CompressionType::Deflate,
             CompressionType::Gzip,
+            CompressionType::Lz4,
             CompressionType::Zlib,
+            CompressionType::Zstd,
         ]
     }
 
@@ -88,8 +102,15 @@ pub enum CompressionCodec {
     /// Apply gzip compression/decompression using compression `level`
     Gzip { level: CompressionLevel },
 
+    /// Apply LZ4 compression/decompression, which has no notion of a compression level and
+    /// instead always favors speed
+    Lz4,
+
     /// Apply zlib compression/decompression using compression `level`
     Zlib { level: CompressionLevel },
+
+    /// Apply Zstandard compression/decompression using compression `level`
+    Zstd { level: CompressionLevel },
 }
 
 impl CompressionCodec {
@@ -102,7 +123,9 @@ impl CompressionCodec {
         match ty {
             CompressionType::Deflate => Ok(Self::Deflate { level }),
             CompressionType::Gzip => Ok(Self::Gzip { level }),
+            CompressionType::Lz4 => Ok(Self::Lz4),
             CompressionType::Zlib => Ok(Self::Zlib { level }),
+            CompressionType::Zstd => Ok(Self::Zstd { level }),
             CompressionType::Unknown => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "Unknown compression type",
@@ -124,6 +147,11 @@ impl CompressionCodec {
         }
     }
 
+    /// Create a new LZ4 compression codec
+    pub fn lz4() -> Self {
+        Self::Lz4
+    }
+
     /// Create a new zlib compression codec with the specified `level`
     pub fn zlib(level: impl Into<CompressionLevel>) -> Self {
         Self::Zlib {
@@ -131,12 +159,22 @@ impl CompressionCodec {
         }
     }
 
-    /// Returns the compression level associated with the codec
+    /// Create a new Zstandard compression codec with the specified `level`
+    pub fn zstd(level: impl Into<CompressionLevel>) -> Self {
+        Self::Zstd {
+            level: level.into(),
+        }
+    }
+
+    /// Returns the compression level associated with the codec, which is always
+    /// [`CompressionLevel::FAST`] for LZ4
     pub fn level(&self) -> CompressionLevel {
         match self {
             Self::Deflate { level } => *level,
             Self::Gzip { level } => *level,
+            Self::Lz4 => CompressionLevel::FAST,
             Self::Zlib { level } => *level,
+            Self::Zstd { level } => *level,
         }
     }
 
@@ -145,7 +183,9 @@ impl CompressionCodec {
         match self {
             Self::Deflate { .. } => CompressionType::Deflate,
             Self::Gzip { .. } => CompressionType::Gzip,
+            Self::Lz4 => CompressionType::Lz4,
             Self::Zlib { .. } => CompressionType::Zlib,
+            Self::Zstd { .. } => CompressionType::Zstd,
         }
     }
 }
@@ -157,14 +197,19 @@ impl Codec for CompressionCodec {
         let mut buf = Vec::new();
         match *self {
             Self::Deflate { level } => {
-                DeflateEncoder::new(item, Compression::new(level as u32)).read_to_end(&mut buf)?
+                DeflateEncoder::new(item, Compression::new(level as u32)).read_to_end(&mut buf)?;
             }
             Self::Gzip { level } => {
-                GzEncoder::new(item, Compression::new(level as u32)).read_to_end(&mut buf)?
+                GzEncoder::new(item, Compression::new(level as u32)).read_to_end(&mut buf)?;
             }
+            Self::Lz4 => buf = lz4_flex::compress_prepend_size(item),
             Self::Zlib { level } => {
-                ZlibEncoder::new(item, Compression::new(level as u32)).read_to_end(&mut buf)?
+                ZlibEncoder::new(item, Compression::new(level as u32)).read_to_end(&mut buf)?;
             }
+
+            // NOTE: Zstandard has no level that disables compression, so we treat zero the same
+            //       as the fastest level
+            Self::Zstd { level } => buf = zstd::encode_all(item, (level as i32).max(1))?,
         };
 
         Ok(Frame::from(buf))
@@ -173,17 +218,58 @@ impl Codec for CompressionCodec {
     fn decode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
         let item = frame.as_item();
 
-        let mut buf = Vec::new();
-        match *self {
-            Self::Deflate { .. } => DeflateDecoder::new(item).read_to_end(&mut buf)?,
-            Self::Gzip { .. } => GzDecoder::new(item).read_to_end(&mut buf)?,
-            Self::Zlib { .. } => ZlibDecoder::new(item).read_to_end(&mut buf)?,
+        let buf = match *self {
+            Self::Deflate { .. } => read_at_most(DeflateDecoder::new(item), MAX_DECOMPRESSED_SIZE)?,
+            Self::Gzip { .. } => read_at_most(GzDecoder::new(item), MAX_DECOMPRESSED_SIZE)?,
+            Self::Lz4 => lz4_decode(item)?,
+            Self::Zlib { .. } => read_at_most(ZlibDecoder::new(item), MAX_DECOMPRESSED_SIZE)?,
+            Self::Zstd { .. } => {
+                read_at_most(zstd::stream::Decoder::new(item)?, MAX_DECOMPRESSED_SIZE)?
+            }
         };
 
         Ok(Frame::from(buf))
     }
 }
 
+/// Reads all decompressed data from `reader`, failing once it exceeds `max` bytes rather than
+/// continuing to allocate for it
+fn read_at_most(reader: impl Read, max: usize) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader.take(max as u64 + 1).read_to_end(&mut buf)?;
+    if buf.len() > max {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Decompressed item exceeds {max} bytes"),
+        ));
+    }
+
+    Ok(buf)
+}
+
+/// Decompresses an LZ4 block prefixed with its decompressed size, rejecting sizes that the block
+/// could not possibly expand to before anything is allocated for them
+fn lz4_decode(item: &[u8]) -> io::Result<Vec<u8>> {
+    let (size, block) = match item {
+        [a, b, c, d, block @ ..] => (u32::from_le_bytes([*a, *b, *c, *d]) as usize, block),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Missing decompressed size",
+            ))
+        }
+    };
+
+    if size > MAX_DECOMPRESSED_SIZE || size > block.len().saturating_mul(MAX_LZ4_RATIO) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Invalid decompressed size of {size} bytes"),
+        ));
+    }
+
+    lz4_flex::decompress(block, size).map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))
+}
+
 #[cfg(test)]
 mod tests {
     use test_log::test;
@@ -221,6 +307,20 @@ mod tests {
             .read_to_end(&mut item)
             .unwrap();
         assert_eq!(item, b"some bytes");
+
+        // Encode using LZ4 and verify that the compression was as expected by decompressing
+        let mut codec = CompressionCodec::lz4();
+        let frame = codec.encode(Frame::new(b"some bytes")).unwrap();
+
+        let item = lz4_flex::decompress_size_prepended(frame.as_item()).unwrap();
+        assert_eq!(item, b"some bytes");
+
+        // Encode using Zstandard and verify that the compression was as expected by decompressing
+        let mut codec = CompressionCodec::zstd(CompressionLevel::BEST);
+        let frame = codec.encode(Frame::new(b"some bytes")).unwrap();
+
+        let item = zstd::decode_all(frame.as_item()).unwrap();
+        assert_eq!(item, b"some bytes");
     }
 
     #[test]
@@ -260,5 +360,60 @@ mod tests {
         let mut codec = CompressionCodec::zlib(CompressionLevel::BEST);
         let frame = codec.decode(frame).unwrap();
         assert_eq!(frame, b"some bytes");
+
+        // Decode using LZ4
+        let frame = Frame::from(lz4_flex::compress_prepend_size(b"some bytes"));
+        let mut codec = CompressionCodec::lz4();
+        let frame = codec.decode(frame).unwrap();
+        assert_eq!(frame, b"some bytes");
+
+        // Decode using Zstandard
+        let frame = Frame::from(zstd::encode_all(b"some bytes".as_slice(), 9).unwrap());
+        let mut codec = CompressionCodec::zstd(CompressionLevel::BEST);
+        let frame = codec.decode(frame).unwrap();
+        assert_eq!(frame, b"some bytes");
+    }
+
+    #[test]
+    fn decode_should_fail_if_lz4_size_is_larger_than_the_item_could_expand_to() {
+        let mut codec = CompressionCodec::lz4();
+
+        // Claims to expand a single byte to 4GB
+        let frame = Frame::new(&[0xFF, 0xFF, 0xFF, 0xFF, 0x00]);
+        let err = codec.decode(frame).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Missing the size entirely
+        let frame = Frame::new(&[0x00, 0x00]);
+        let err = codec.decode(frame).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn read_at_most_should_fail_if_decompressed_data_exceeds_max() {
+        let data = vec![0; 1024];
+        let mut deflated = Vec::new();
+        DeflateEncoder::new(data.as_slice(), Compression::best())
+            .read_to_end(&mut deflated)
+            .unwrap();
+        let mut gzipped = Vec::new();
+        GzEncoder::new(data.as_slice(), Compression::best())
+            .read_to_end(&mut gzipped)
+            .unwrap();
+        let mut zlibbed = Vec::new();
+        ZlibEncoder::new(data.as_slice(), Compression::best())
+            .read_to_end(&mut zlibbed)
+            .unwrap();
+
+        let err = read_at_most(DeflateDecoder::new(deflated.as_slice()), 1023).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = read_at_most(GzDecoder::new(gzipped.as_slice()), 1023).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = read_at_most(ZlibDecoder::new(zlibbed.as_slice()), 1023).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Data exactly at the max is still allowed
+        let buf = read_at_most(DeflateDecoder::new(deflated.as_slice()), 1024).unwrap();
+        assert_eq!(buf, data);
     }
 }
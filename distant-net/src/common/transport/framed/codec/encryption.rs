@@ -14,6 +14,11 @@ pub enum EncryptionType {
     #[display(fmt = "xchacha20poly1305")]
     XChaCha20Poly1305,
 
+    /// AES-256 in Galois/Counter Mode with a 96-bit (12-byte) nonce, which is considerably faster
+    /// than [`XChaCha20Poly1305`](Self::XChaCha20Poly1305) on hardware with AES instructions
+    #[display(fmt = "aes256gcm")]
+    Aes256Gcm,
+
     /// Indicates an unknown encryption type for use in handshakes
     #[display(fmt = "unknown")]
     #[serde(other)]
@@ -24,7 +29,7 @@ impl EncryptionType {
     /// Generates bytes for a secret key based on the encryption type
     pub fn generate_secret_key_bytes(&self) -> io::Result<Vec<u8>> {
         match self {
-            Self::XChaCha20Poly1305 => Ok(SecretKey::<32>::generate()
+            Self::XChaCha20Poly1305 | Self::Aes256Gcm => Ok(SecretKey::<32>::generate()
                 .unwrap()
                 .into_heap_secret_key()
                 .unprotected_into_bytes()),
@@ -37,7 +42,7 @@ impl EncryptionType {
 
     /// Returns a list of all variants of the type *except* unknown.
     pub const fn known_variants() -> &'static [EncryptionType] {
-        &[EncryptionType::XChaCha20Poly1305]
+        &[EncryptionType::XChaCha20Poly1305, EncryptionType::Aes256Gcm]
     }
 
     /// Returns true if type is unknown
@@ -60,6 +65,12 @@ pub enum EncryptionCodec {
     XChaCha20Poly1305 {
         cipher: chacha20poly1305::XChaCha20Poly1305,
     },
+
+    /// AES-256 in Galois/Counter Mode with a 96-bit (12-byte) nonce, using [`Aes256Gcm`]
+    /// underneath
+    ///
+    /// [`Aes256Gcm`]: aes_gcm::Aes256Gcm
+    Aes256Gcm { cipher: Box<aes_gcm::Aes256Gcm> },
 }
 
 impl EncryptionCodec {
@@ -73,6 +84,14 @@ impl EncryptionCodec {
                     .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
                 Ok(Self::XChaCha20Poly1305 { cipher })
             }
+            EncryptionType::Aes256Gcm => {
+                use aes_gcm::{Aes256Gcm, KeyInit};
+                let cipher = Aes256Gcm::new_from_slice(key)
+                    .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
+                Ok(Self::Aes256Gcm {
+                    cipher: Box::new(cipher),
+                })
+            }
             EncryptionType::Unknown => Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "Encryption type is unknown",
@@ -89,10 +108,17 @@ impl EncryptionCodec {
         .unwrap()
     }
 
+    pub fn new_aes256gcm(secret_key: SecretKey32) -> EncryptionCodec {
+        // NOTE: This should never fail as we are enforcing the key size at compile time
+        Self::from_type_and_key(EncryptionType::Aes256Gcm, secret_key.unprotected_as_bytes())
+            .unwrap()
+    }
+
     /// Returns the encryption type associa ted with the codec
     pub fn ty(&self) -> EncryptionType {
         match self {
             Self::XChaCha20Poly1305 { .. } => EncryptionType::XChaCha20Poly1305,
+            Self::Aes256Gcm { .. } => EncryptionType::Aes256Gcm,
         }
     }
 
@@ -101,6 +127,9 @@ impl EncryptionCodec {
         match self {
             // XChaCha20Poly1305 uses a 192-bit (24-byte) key
             Self::XChaCha20Poly1305 { .. } => 24,
+
+            // Aes256Gcm uses a 96-bit (12-byte) nonce
+            Self::Aes256Gcm { .. } => 12,
         }
     }
 
@@ -114,6 +143,17 @@ impl EncryptionCodec {
                 .unwrap()
                 .into_heap_secret_key()
                 .unprotected_into_bytes(),
+
+            // NOTE: A 12-byte nonce is too small to rule out collisions by size alone, but a
+            //       stateful counter is not an option either: frames replayed from a backup after
+            //       a reconnect are encoded again under the same key, so a counter restored or
+            //       cloned alongside the codec could repeat. Instead, every encode draws a fresh
+            //       random nonce, which stays within the recommended bound of 2^32 frames per key
+            //       for random 96-bit nonces.
+            Self::Aes256Gcm { .. } => SecretKey::<12>::generate()
+                .unwrap()
+                .into_heap_secret_key()
+                .unprotected_into_bytes(),
         }
     }
 }
@@ -148,6 +188,23 @@ impl Codec for EncryptionCodec {
                 let mut frame = Frame::from(nonce_bytes);
                 frame.extend(ciphertext);
 
+                frame
+            }
+            Self::Aes256Gcm { cipher } => {
+                use aes_gcm::aead::Aead;
+                use aes_gcm::Nonce;
+                let item = frame.into_item();
+                let nonce = Nonce::from_slice(&nonce_bytes);
+
+                // Encrypt the frame's item as our ciphertext
+                let ciphertext = cipher
+                    .encrypt(nonce, item.as_ref())
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Encryption failed"))?;
+
+                // Start our frame with the nonce at the beginning
+                let mut frame = Frame::from(nonce_bytes);
+                frame.extend(ciphertext);
+
                 frame
             }
         })
@@ -173,6 +230,14 @@ impl Codec for EncryptionCodec {
                     .decrypt(nonce, &frame.as_item()[nonce_size..])
                     .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Decryption failed"))?
             }
+            Self::Aes256Gcm { cipher } => {
+                use aes_gcm::aead::Aead;
+                use aes_gcm::Nonce;
+                let nonce = Nonce::from_slice(&frame.as_item()[..nonce_size]);
+                cipher
+                    .decrypt(nonce, &frame.as_item()[nonce_size..])
+                    .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Decryption failed"))?
+            }
         };
 
         Ok(Frame::from(item))
@@ -256,4 +321,57 @@ mod tests {
         let frame = codec.decode(frame).expect("Failed to decode");
         assert_eq!(frame, b"hello, world");
     }
+
+    #[test]
+    fn aes256gcm_should_round_trip_frame() {
+        let ty = EncryptionType::Aes256Gcm;
+        let key = ty.generate_secret_key_bytes().unwrap();
+        let mut codec = EncryptionCodec::from_type_and_key(ty, &key).unwrap();
+        assert_eq!(codec.nonce_size(), 12);
+
+        let frame = codec
+            .encode(Frame::new(b"hello, world"))
+            .expect("Failed to encode");
+
+        // Manually build our key & cipher so we can decrypt the frame manually to ensure it is
+        // correct
+        let item = {
+            use aes_gcm::aead::Aead;
+            use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+            let cipher = Aes256Gcm::new_from_slice(&key).unwrap();
+            cipher
+                .decrypt(
+                    Nonce::from_slice(&frame.as_item()[..12]),
+                    &frame.as_item()[12..],
+                )
+                .expect("Failed to decrypt")
+        };
+        assert_eq!(item, b"hello, world");
+
+        let frame = codec.decode(frame).expect("Failed to decode");
+        assert_eq!(frame, b"hello, world");
+    }
+
+    #[test]
+    fn aes256gcm_should_use_a_new_nonce_when_encoding_the_same_frame_again() {
+        let mut codec = EncryptionCodec::new_aes256gcm(SecretKey32::default());
+
+        // Frames replayed from a backup are encoded again, so they must not reuse a nonce
+        let frame_1 = codec.encode(Frame::new(b"hello")).unwrap();
+        let frame_2 = codec.encode(Frame::new(b"hello")).unwrap();
+        assert_ne!(frame_1.as_item()[..12], frame_2.as_item()[..12]);
+
+        assert_eq!(codec.decode(frame_1).unwrap(), b"hello");
+        assert_eq!(codec.decode(frame_2).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn aes256gcm_should_fail_to_decode_frame_encrypted_with_a_different_key() {
+        let mut codec_1 = EncryptionCodec::new_aes256gcm(SecretKey32::default());
+        let mut codec_2 = EncryptionCodec::new_aes256gcm(SecretKey32::default());
+
+        let frame = codec_1.encode(Frame::new(b"hello")).unwrap();
+        let err = codec_2.decode(frame).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
 }
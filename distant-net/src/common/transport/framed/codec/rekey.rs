@@ -0,0 +1,286 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use hkdf::Hkdf;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use super::{Codec, EncryptionCodec, EncryptionType, Frame};
+use crate::common::SecretKey32;
+
+/// Marker appended to frames after which the sender continues using the same key
+const KEEP: u8 = 0;
+
+/// Marker appended to the last frame sent with a key, after which the sender switches to the next
+/// key in the chain
+const REKEY: u8 = 1;
+
+/// Context used when deriving the next key in the chain from the current key
+const REKEY_INFO: &[u8] = b"distant rekey";
+
+/// Thresholds that determine when a [`RekeyCodec`] switches to a new key
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RekeyPolicy {
+    /// Maximum bytes to encode with a key before switching to a new key
+    pub max_bytes: Option<u64>,
+
+    /// Maximum time to use a key before switching to a new key, checked whenever a frame is
+    /// encoded
+    pub max_interval: Option<Duration>,
+}
+
+impl RekeyPolicy {
+    /// Default maximum bytes to encode with a key (1 GiB)
+    pub const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+
+    /// Default maximum time to use a key (1 hour)
+    pub const DEFAULT_MAX_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+    /// Returns true if a key that has encoded `bytes` since being put to use at `since` should be
+    /// replaced
+    fn is_due(&self, bytes: u64, since: Instant) -> bool {
+        self.max_bytes.map_or(false, |max| bytes >= max)
+            || self
+                .max_interval
+                .map_or(false, |max| since.elapsed() >= max)
+    }
+}
+
+impl Default for RekeyPolicy {
+    /// Rekeys after the default maximum bytes or interval, whichever comes first
+    fn default() -> Self {
+        Self {
+            max_bytes: Some(Self::DEFAULT_MAX_BYTES),
+            max_interval: Some(Self::DEFAULT_MAX_INTERVAL),
+        }
+    }
+}
+
+/// Represents a codec that encrypts frames like an [`EncryptionCodec`], but periodically switches
+/// to a new key based on a [`RekeyPolicy`] so that no single key is used for the entire life of a
+/// long-lived connection.
+///
+/// Each direction follows its own chain of keys starting from the shared key, where the next key
+/// is derived from the current one. When a key is due to be replaced, the sender marks the frame
+/// being encoded as the last frame for that key and switches to the next key, and the receiver
+/// switches after decoding the marked frame. As frames are decoded in the order that they are
+/// encoded, frames that are already in flight are never affected.
+///
+/// A marker byte is appended to each frame before it is encrypted, which means that both sides of
+/// a transport need to be using a [`RekeyCodec`] with the same shared key.
+#[derive(Clone, Debug)]
+pub struct RekeyCodec {
+    policy: RekeyPolicy,
+    encoder: Ratchet,
+    decoder: Ratchet,
+
+    /// Total bytes encoded using the current key of the encoder
+    encoded_bytes: u64,
+
+    /// When the current key of the encoder was put to use
+    encoder_since: Instant,
+}
+
+impl RekeyCodec {
+    /// Creates a new codec for the encryption type `ty` using `key` as the first key of both the
+    /// encoder and decoder, failing if the type is unknown
+    pub fn new(ty: EncryptionType, key: SecretKey32, policy: RekeyPolicy) -> io::Result<Self> {
+        Ok(Self {
+            policy,
+            encoder: Ratchet::new(ty, key.clone())?,
+            decoder: Ratchet::new(ty, key)?,
+            encoded_bytes: 0,
+            encoder_since: Instant::now(),
+        })
+    }
+
+    /// Returns the policy that determines when to switch keys
+    pub fn policy(&self) -> &RekeyPolicy {
+        &self.policy
+    }
+
+    /// Returns the number of times that the encoder has switched keys
+    pub fn encoder_generation(&self) -> u64 {
+        self.encoder.generation
+    }
+
+    /// Returns the number of times that the decoder has switched keys
+    pub fn decoder_generation(&self) -> u64 {
+        self.decoder.generation
+    }
+}
+
+impl Codec for RekeyCodec {
+    fn encode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
+        let rekey = self.policy.is_due(self.encoded_bytes, self.encoder_since);
+
+        let mut item = frame.into_item().into_owned();
+        item.push(if rekey { REKEY } else { KEEP });
+        let frame = self.encoder.codec.encode(Frame::from(item))?;
+        self.encoded_bytes += frame.len() as u64;
+
+        if rekey {
+            self.encoder.advance()?;
+            self.encoded_bytes = 0;
+            self.encoder_since = Instant::now();
+        }
+
+        Ok(frame)
+    }
+
+    fn decode<'a>(&mut self, frame: Frame<'a>) -> io::Result<Frame<'a>> {
+        let mut item = self.decoder.codec.decode(frame)?.into_item().into_owned();
+        match item.pop() {
+            Some(KEEP) => (),
+            Some(REKEY) => self.decoder.advance()?,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Decode failed because frame is missing rekey marker",
+                ))
+            }
+        }
+
+        Ok(Frame::from(item))
+    }
+}
+
+/// Current key of a chain alongside the codec that uses it
+#[derive(Clone, Debug)]
+struct Ratchet {
+    key: SecretKey32,
+    codec: EncryptionCodec,
+    generation: u64,
+}
+
+impl Ratchet {
+    fn new(ty: EncryptionType, key: SecretKey32) -> io::Result<Self> {
+        Ok(Self {
+            codec: ty.new_codec(key.unprotected_as_bytes())?,
+            key,
+            generation: 0,
+        })
+    }
+
+    /// Replaces the key with the next key in the chain, discarding the current key
+    fn advance(&mut self) -> io::Result<()> {
+        let mut next = [0u8; 32];
+        Hkdf::<Sha256>::new(None, self.key.unprotected_as_bytes())
+            .expand(REKEY_INFO, &mut next)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x.to_string()))?;
+
+        let key = SecretKey32::from(next);
+        self.codec = self.codec.ty().new_codec(key.unprotected_as_bytes())?;
+        self.key = key;
+        self.generation += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use test_log::test;
+
+    use super::*;
+
+    fn pair(policy: RekeyPolicy) -> (RekeyCodec, RekeyCodec) {
+        let key = SecretKey32::default();
+        let a = RekeyCodec::new(EncryptionType::XChaCha20Poly1305, key.clone(), policy).unwrap();
+        let b = RekeyCodec::new(EncryptionType::XChaCha20Poly1305, key, policy).unwrap();
+        (a, b)
+    }
+
+    #[test]
+    fn encode_should_switch_keys_once_max_bytes_is_reached() {
+        let (mut a, mut b) = pair(RekeyPolicy {
+            max_bytes: Some(100),
+            max_interval: None,
+        });
+
+        // Each encoded frame is well under the limit, so a few frames are needed to reach it
+        let mut frames = Vec::new();
+        while a.encoder_generation() == 0 {
+            frames.push(a.encode(Frame::new(b"hello world")).unwrap());
+        }
+        assert!(frames.len() > 1, "Switched keys too early");
+
+        // Decoding every frame in order brings the other side to the same key
+        for frame in frames {
+            assert_eq!(b.decode(frame).unwrap(), b"hello world");
+        }
+        assert_eq!(b.decoder_generation(), 1);
+        assert_eq!(b.encoder_generation(), 0);
+
+        let frame = a.encode(Frame::new(b"next key")).unwrap();
+        assert_eq!(b.decode(frame).unwrap(), b"next key");
+    }
+
+    #[test]
+    fn encode_should_switch_keys_once_max_interval_has_elapsed() {
+        let (mut a, mut b) = pair(RekeyPolicy {
+            max_bytes: None,
+            max_interval: Some(Duration::ZERO),
+        });
+
+        for i in 1..=3 {
+            let frame = a.encode(Frame::new(b"hello")).unwrap();
+            assert_eq!(a.encoder_generation(), i);
+
+            assert_eq!(b.decode(frame).unwrap(), b"hello");
+            assert_eq!(b.decoder_generation(), i);
+        }
+    }
+
+    #[test]
+    fn encode_should_never_switch_keys_without_thresholds() {
+        let (mut a, mut b) = pair(RekeyPolicy {
+            max_bytes: None,
+            max_interval: None,
+        });
+
+        for _ in 0..10 {
+            let frame = a.encode(Frame::new(b"hello")).unwrap();
+            assert_eq!(b.decode(frame).unwrap(), b"hello");
+        }
+        assert_eq!(a.encoder_generation(), 0);
+        assert_eq!(b.decoder_generation(), 0);
+    }
+
+    #[test]
+    fn directions_should_switch_keys_independently() {
+        let (mut a, mut b) = pair(RekeyPolicy {
+            max_bytes: Some(1),
+            max_interval: None,
+        });
+
+        // Only a sends enough to switch keys (on every other frame as the first frame with a key
+        // is always sent), while b keeps using its first key
+        for _ in 0..4 {
+            let frame = a.encode(Frame::new(b"from a")).unwrap();
+            assert_eq!(b.decode(frame).unwrap(), b"from a");
+        }
+
+        let frame = b.encode(Frame::new(b"from b")).unwrap();
+        assert_eq!(a.decode(frame).unwrap(), b"from b");
+
+        assert_eq!(a.encoder_generation(), 2);
+        assert_eq!(b.decoder_generation(), 2);
+        assert_eq!(b.encoder_generation(), 0);
+        assert_eq!(a.decoder_generation(), 0);
+    }
+
+    #[test]
+    fn decode_should_fail_if_frame_is_encrypted_with_an_old_key() {
+        let (mut a, mut b) = pair(RekeyPolicy {
+            max_bytes: None,
+            max_interval: Some(Duration::ZERO),
+        });
+
+        let frame = a.encode(Frame::new(b"hello")).unwrap();
+        assert_eq!(b.decode(frame.clone()).unwrap(), b"hello");
+
+        // Replaying the frame fails as the first key has been discarded
+        let err = b.decode(frame).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
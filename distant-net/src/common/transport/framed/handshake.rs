@@ -1,4 +1,5 @@
-use super::{CompressionLevel, CompressionType, EncryptionType};
+use super::{CompressionLevel, CompressionType, EncryptionType, RekeyPolicy};
+use crate::common::ServerIdentity;
 
 /// Definition of the handshake to perform for a transport
 #[derive(Clone, Debug)]
@@ -11,8 +12,16 @@ pub enum Handshake {
         /// Preferred compression level when presented options by server
         preferred_compression_level: Option<CompressionLevel>,
 
+        /// If true and a compression algorithm is chosen, compression is only applied to frames
+        /// likely to benefit from it (see [`AdaptiveCodec`](super::AdaptiveCodec))
+        adaptive_compression: bool,
+
         /// Preferred encryption algorithm when presented options by server
         preferred_encryption_type: Option<EncryptionType>,
+
+        /// If provided and the server supports it, encryption keys are periodically replaced
+        /// based on the policy (see [`RekeyCodec`](super::RekeyCodec))
+        rekey_policy: Option<RekeyPolicy>,
     },
 
     /// Indicates that the handshake is being performed from the server-side
@@ -22,17 +31,22 @@ pub enum Handshake {
 
         /// List of available encryption algorithms for use between client and server
         encryption_types: Vec<EncryptionType>,
+
+        /// Identity used to sign the key exchange so the client can verify the server
+        identity: Option<ServerIdentity>,
     },
 }
 
 impl Handshake {
     /// Creates a new client handshake definition, providing defaults for the preferred compression
-    /// type, compression level, and encryption type
+    /// type, compression level, encryption type, and rekey policy
     pub fn client() -> Self {
         Self::Client {
             preferred_compression_type: None,
             preferred_compression_level: None,
+            adaptive_compression: false,
             preferred_encryption_type: Some(EncryptionType::XChaCha20Poly1305),
+            rekey_policy: Some(RekeyPolicy::default()),
         }
     }
 
@@ -42,7 +56,61 @@ impl Handshake {
         Self::Server {
             compression_types: CompressionType::known_variants().to_vec(),
             encryption_types: EncryptionType::known_variants().to_vec(),
+            identity: None,
+        }
+    }
+
+    /// Sets the identity used by a server-side handshake to sign the key exchange, doing nothing
+    /// for a client-side handshake
+    pub fn with_identity(mut self, identity: Option<ServerIdentity>) -> Self {
+        if let Self::Server { identity: x, .. } = &mut self {
+            *x = identity;
+        }
+        self
+    }
+
+    /// Sets the encryption algorithm preferred by a client-side handshake, doing nothing for a
+    /// server-side handshake. If `None` or not offered by the server, the first encryption
+    /// algorithm offered by the server is used instead.
+    pub fn with_preferred_encryption_type(mut self, ty: Option<EncryptionType>) -> Self {
+        if let Self::Client {
+            preferred_encryption_type,
+            ..
+        } = &mut self
+        {
+            *preferred_encryption_type = ty;
+        }
+        self
+    }
+
+    /// Sets the compression algorithm preferred by a client-side handshake, doing nothing for a
+    /// server-side handshake. If `adaptive` is true, compression is only applied to frames likely
+    /// to benefit from it when the server supports doing so. If `None` or not offered by the
+    /// server, no compression is used.
+    pub fn with_preferred_compression_type(
+        mut self,
+        ty: Option<CompressionType>,
+        adaptive: bool,
+    ) -> Self {
+        if let Self::Client {
+            preferred_compression_type,
+            adaptive_compression,
+            ..
+        } = &mut self
+        {
+            *preferred_compression_type = ty;
+            *adaptive_compression = adaptive;
+        }
+        self
+    }
+
+    /// Sets the policy used by a client-side handshake to periodically replace encryption keys,
+    /// doing nothing for a server-side handshake. If `None`, keys are never replaced.
+    pub fn with_rekey_policy(mut self, policy: Option<RekeyPolicy>) -> Self {
+        if let Self::Client { rekey_policy, .. } = &mut self {
+            *rekey_policy = policy;
         }
+        self
     }
 
     /// Returns true if handshake is from client-side
@@ -0,0 +1,374 @@
+use std::{fmt, io};
+
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use log::*;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::http::Uri;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::WebSocketStream;
+
+use super::{InmemoryTransport, Interest, Ready, Reconnectable, Transport};
+
+mod proxy;
+pub use proxy::*;
+
+/// Capacity of the channels used to pass messages between the transport and the task that drives
+/// the WebSocket
+const CHANNEL_CAPACITY: usize = 32;
+
+/// Represents a [`Transport`] that sends and receives data as binary messages over a WebSocket,
+/// which allows connections to pass through HTTP proxies and load balancers that would otherwise
+/// block raw TCP
+pub struct WebSocketTransport {
+    /// Url the transport connected to, or none if the transport was accepted by a listener
+    url: Option<String>,
+    proxy: Option<HttpProxy>,
+    inner: InmemoryTransport,
+}
+
+impl WebSocketTransport {
+    /// Creates a new transport by connecting to the server at `url`, which is of the form
+    /// `ws://host[:port][/path]` or `wss://host[:port][/path]`, going through the HTTP proxy
+    /// configured in the environment (see [`HttpProxy::from_env`]) if there is one
+    pub async fn connect(url: impl Into<String>) -> io::Result<Self> {
+        let url = url.into();
+        let (secure, host, _) = parse_url(&url)?;
+        let proxy = HttpProxy::from_env(secure, &host)?;
+        Self::connect_with_proxy(url, proxy).await
+    }
+
+    /// Creates a new transport by connecting to the server at `url`, going through `proxy` if
+    /// provided instead of looking up a proxy from the environment
+    pub async fn connect_with_proxy(
+        url: impl Into<String>,
+        proxy: Option<HttpProxy>,
+    ) -> io::Result<Self> {
+        let url = url.into();
+        let (_, host, port) = parse_url(&url)?;
+
+        let stream = match proxy.as_ref() {
+            Some(proxy) => {
+                debug!("Connecting to {url} through proxy {proxy}");
+                proxy.connect(&host, port).await?
+            }
+            None => TcpStream::connect((host.as_str(), port)).await?,
+        };
+
+        let (stream, _) = tokio_tungstenite::client_async_tls(url.as_str(), stream)
+            .await
+            .map_err(into_io_error)?;
+
+        Ok(Self {
+            url: Some(url),
+            proxy,
+            inner: spawn_stream_task(stream),
+        })
+    }
+
+    /// Creates a new transport from a WebSocket that has already completed its handshake as a
+    /// server, which cannot be reconnected
+    pub(crate) fn from_accepted<S>(stream: WebSocketStream<S>) -> Self
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        Self {
+            url: None,
+            proxy: None,
+            inner: spawn_stream_task(stream),
+        }
+    }
+
+    /// Returns the url that the transport connected to, or none if the transport was accepted by
+    /// a listener
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    /// Returns the proxy that the transport connected through, if any
+    pub fn proxy(&self) -> Option<&HttpProxy> {
+        self.proxy.as_ref()
+    }
+}
+
+impl fmt::Debug for WebSocketTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebSocketTransport")
+            .field("url", &self.url)
+            .field("proxy", &self.proxy)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Reconnectable for WebSocketTransport {
+    /// Connects again to the same url through the same proxy, failing with
+    /// [`ErrorKind::ConnectionRefused`] if the transport was accepted by a listener.
+    ///
+    /// [`ErrorKind::ConnectionRefused`]: io::ErrorKind::ConnectionRefused
+    async fn reconnect(&mut self) -> io::Result<()> {
+        let url = self.url.clone().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "Accepted WebSocket connections cannot be reconnected",
+            )
+        })?;
+
+        *self = Self::connect_with_proxy(url, self.proxy.clone()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    fn try_read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.try_read(buf)
+    }
+
+    fn try_write(&self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.try_write(buf)
+    }
+
+    async fn ready(&self, interest: Interest) -> io::Result<Ready> {
+        self.inner.ready(interest).await
+    }
+}
+
+/// Spawns a task that forwards binary messages between `stream` and the returned transport,
+/// closing the WebSocket once the transport is dropped
+fn spawn_stream_task<S>(mut stream: WebSocketStream<S>) -> InmemoryTransport
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (incoming_tx, mut outgoing_rx, transport) = InmemoryTransport::make(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                msg = stream.next() => match msg {
+                    Some(Ok(Message::Binary(data))) => {
+                        if incoming_tx.send(data).await.is_err() {
+                            break;
+                        }
+                    }
+
+                    // NOTE: Pings are answered by the stream itself and everything else is only
+                    //       sent by peers that are not distant, so we ignore them
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(x)) => {
+                        debug!("WebSocket failed to receive message: {x}");
+                        break;
+                    }
+                },
+                data = outgoing_rx.recv() => match data {
+                    Some(data) => {
+                        if let Err(x) = stream.send(Message::Binary(data)).await {
+                            debug!("WebSocket failed to send message: {x}");
+                            break;
+                        }
+                    }
+                    None => {
+                        let _ = stream.close(None).await;
+                        break;
+                    }
+                },
+            }
+        }
+    });
+
+    transport
+}
+
+/// Parses a WebSocket `url`, returning whether it is secure alongside its host and port
+fn parse_url(url: &str) -> io::Result<(bool, String, u16)> {
+    let invalid = |reason: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid WebSocket url {url:?}: {reason}"),
+        )
+    };
+
+    let uri = url.parse::<Uri>().map_err(|x| invalid(&x.to_string()))?;
+    let secure = match uri.scheme_str() {
+        Some(scheme) if scheme.eq_ignore_ascii_case("ws") => false,
+        Some(scheme) if scheme.eq_ignore_ascii_case("wss") => true,
+        _ => return Err(invalid("scheme must be ws or wss")),
+    };
+
+    // NOTE: IPv6 hosts are wrapped in square brackets within a url, which we need to remove
+    let host = uri
+        .host()
+        .ok_or_else(|| invalid("missing host"))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+
+    Ok((secure, host, port))
+}
+
+/// Converts an error from the WebSocket into an [`io::Error`]
+pub(crate) fn into_io_error(x: WsError) -> io::Error {
+    match x {
+        WsError::Io(x) => x,
+        x => io::Error::new(io::ErrorKind::Other, x),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use test_log::test;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    use super::*;
+    use crate::common::TransportExt;
+
+    /// Accepts a single WebSocket connection, sends some data, and then receives some data
+    async fn start_server() -> (SocketAddr, JoinHandle<io::Result<()>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await?;
+            let mut stream = tokio_tungstenite::accept_async(stream)
+                .await
+                .map_err(into_io_error)?;
+
+            stream
+                .send(Message::Binary(b"hello conn".to_vec()))
+                .await
+                .map_err(into_io_error)?;
+
+            match stream.next().await {
+                Some(Ok(Message::Binary(data))) => assert_eq!(data, b"hello server"),
+                x => panic!("Unexpected message: {x:?}"),
+            }
+
+            Ok(())
+        });
+
+        (addr, task)
+    }
+
+    #[test]
+    fn parse_url_should_default_port_based_on_scheme() {
+        assert_eq!(
+            parse_url("ws://example.com").unwrap(),
+            (false, "example.com".to_string(), 80)
+        );
+        assert_eq!(
+            parse_url("wss://example.com/path").unwrap(),
+            (true, "example.com".to_string(), 443)
+        );
+        assert_eq!(
+            parse_url("ws://[::1]:8080").unwrap(),
+            (false, "::1".to_string(), 8080)
+        );
+    }
+
+    #[test]
+    fn parse_url_should_fail_if_scheme_is_not_websocket() {
+        let err = parse_url("http://example.com").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test(tokio::test)]
+    async fn should_be_able_to_read_and_write_data() {
+        let (addr, task) = start_server().await;
+
+        let conn = WebSocketTransport::connect(format!("ws://{addr}"))
+            .await
+            .expect("Conn failed to connect");
+
+        let mut buf: [u8; 10] = [0; 10];
+        conn.read_exact(&mut buf)
+            .await
+            .expect("Conn failed to read");
+        assert_eq!(&buf, b"hello conn");
+
+        conn.write_all(b"hello server")
+            .await
+            .expect("Conn failed to write");
+
+        task.await
+            .expect("Server task failed unexpectedly")
+            .expect("Server failed");
+    }
+
+    #[test(tokio::test)]
+    async fn should_be_able_to_connect_through_http_proxy() {
+        let (addr, task) = start_server().await;
+
+        // Spawn a proxy that tunnels a single connection to the server
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = HttpProxy::new("127.0.0.1", listener.local_addr().unwrap().port());
+        let proxy_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+
+            let mut request = String::new();
+            loop {
+                let n = stream.read_line(&mut request).await.unwrap();
+                if n == 0 || request.ends_with("\r\n\r\n") {
+                    break;
+                }
+            }
+
+            let mut stream = stream.into_inner();
+            stream
+                .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                .await
+                .unwrap();
+
+            let mut server = TcpStream::connect(addr).await.unwrap();
+            let _ = tokio::io::copy_bidirectional(&mut stream, &mut server).await;
+            request
+        });
+
+        let conn = WebSocketTransport::connect_with_proxy(format!("ws://{addr}"), Some(proxy))
+            .await
+            .expect("Conn failed to connect");
+
+        let mut buf: [u8; 10] = [0; 10];
+        conn.read_exact(&mut buf)
+            .await
+            .expect("Conn failed to read");
+        assert_eq!(&buf, b"hello conn");
+
+        conn.write_all(b"hello server")
+            .await
+            .expect("Conn failed to write");
+
+        task.await
+            .expect("Server task failed unexpectedly")
+            .expect("Server failed");
+
+        drop(conn);
+        let request = proxy_task.await.unwrap();
+        assert!(
+            request.starts_with(&format!("CONNECT {addr} HTTP/1.1\r\n")),
+            "Unexpected request: {request:?}"
+        );
+    }
+
+    #[test(tokio::test)]
+    async fn should_fail_to_connect_if_server_is_not_websocket() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\n\r\n").await;
+        });
+
+        WebSocketTransport::connect(format!("ws://{addr}"))
+            .await
+            .expect_err("Unexpectedly connected to server that is not a WebSocket");
+    }
+}
@@ -9,7 +9,7 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 use tokio::sync::{broadcast, RwLock};
 
-use crate::common::{Listener, Response, Transport};
+use crate::common::{Listener, Response, ServerIdentity, Transport};
 
 mod builder;
 pub use builder::*;
@@ -45,6 +45,9 @@ pub struct Server<T> {
 
     /// Performs authentication using various methods
     verifier: Verifier,
+
+    /// Identity used to prove the server to clients during handshakes
+    identity: Option<ServerIdentity>,
 }
 
 /// Interface for a handler that receives connections and requests
@@ -83,6 +86,7 @@ impl Server<()> {
             config: Default::default(),
             handler: (),
             verifier: Verifier::empty(),
+            identity: None,
         }
     }
 
@@ -102,6 +106,11 @@ impl Server<()> {
     pub fn windows_pipe() -> WindowsPipeServerBuilder<()> {
         WindowsPipeServerBuilder::default()
     }
+
+    /// Creates a new [`WebSocketServerBuilder`] that is used to construct a [`Server`].
+    pub fn websocket() -> WebSocketServerBuilder<()> {
+        WebSocketServerBuilder::default()
+    }
 }
 
 impl Default for Server<()> {
@@ -117,6 +126,7 @@ impl<T> Server<T> {
             config,
             handler: self.handler,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -126,6 +136,7 @@ impl<T> Server<T> {
             config: self.config,
             handler,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -135,6 +146,17 @@ impl<T> Server<T> {
             config: self.config,
             handler: self.handler,
             verifier,
+            identity: self.identity,
+        }
+    }
+
+    /// Consumes the current server, replacing its identity with `identity` and returning it.
+    pub fn identity(self, identity: ServerIdentity) -> Self {
+        Self {
+            config: self.config,
+            handler: self.handler,
+            verifier: self.verifier,
+            identity: Some(identity),
         }
     }
 }
@@ -175,6 +197,7 @@ where
             config,
             handler,
             verifier,
+            identity,
         } = self;
 
         let handler = Arc::new(handler);
@@ -224,6 +247,7 @@ where
                     .sleep_duration(config.connection_sleep)
                     .heartbeat_duration(config.connection_heartbeat)
                     .verifier(Arc::downgrade(&verifier))
+                    .identity(identity.clone())
                     .spawn(),
             );
         }
@@ -251,7 +275,9 @@ mod tests {
     use tokio::sync::mpsc;
 
     use super::*;
-    use crate::common::{Connection, InmemoryTransport, MpscListener, Request, Response};
+    use crate::common::{
+        Connection, Handshake, InmemoryTransport, MpscListener, Request, Response,
+    };
 
     pub struct TestServerHandler;
 
@@ -280,6 +306,7 @@ mod tests {
             config,
             handler: TestServerHandler,
             verifier: Verifier::new(methods),
+            identity: None,
         }
     }
 
@@ -309,9 +336,10 @@ mod tests {
             .expect("Failed to start server");
 
         // Perform handshake and authentication with the server before beginning to send data
-        let mut connection = Connection::client(transport, DummyAuthHandler)
-            .await
-            .expect("Failed to connect to server");
+        let mut connection =
+            Connection::client(transport, Handshake::client(), DummyAuthHandler, None)
+                .await
+                .expect("Failed to connect to server");
 
         connection
             .write_frame(Request::new(123).to_vec().unwrap())
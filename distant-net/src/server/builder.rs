@@ -3,11 +3,14 @@ mod tcp;
 #[cfg(unix)]
 mod unix;
 
+mod websocket;
+
 #[cfg(windows)]
 mod windows;
 
 pub use tcp::*;
 #[cfg(unix)]
 pub use unix::*;
+pub use websocket::*;
 #[cfg(windows)]
 pub use windows::*;
@@ -0,0 +1,114 @@
+use std::io;
+use std::net::IpAddr;
+
+use distant_auth::Verifier;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::common::{PortRange, ServerIdentity, WebSocketListener};
+use crate::server::{Server, ServerConfig, ServerHandler, WebSocketServerRef};
+
+pub struct WebSocketServerBuilder<T>(Server<T>);
+
+impl<T> Server<T> {
+    /// Consume [`Server`] and produce a builder for a WebSocket variant.
+    pub fn into_websocket_builder(self) -> WebSocketServerBuilder<T> {
+        WebSocketServerBuilder(self)
+    }
+}
+
+impl Default for WebSocketServerBuilder<()> {
+    fn default() -> Self {
+        Self(Server::new())
+    }
+}
+
+impl<T> WebSocketServerBuilder<T> {
+    pub fn config(self, config: ServerConfig) -> Self {
+        Self(self.0.config(config))
+    }
+
+    pub fn handler<U>(self, handler: U) -> WebSocketServerBuilder<U> {
+        WebSocketServerBuilder(self.0.handler(handler))
+    }
+
+    pub fn verifier(self, verifier: Verifier) -> Self {
+        Self(self.0.verifier(verifier))
+    }
+
+    pub fn identity(self, identity: ServerIdentity) -> Self {
+        Self(self.0.identity(identity))
+    }
+}
+
+impl<T> WebSocketServerBuilder<T>
+where
+    T: ServerHandler + Sync + 'static,
+    T::Request: DeserializeOwned + Send + Sync + 'static,
+    T::Response: Serialize + Send + 'static,
+    T::LocalData: Default + Send + Sync + 'static,
+{
+    pub async fn start<P>(self, addr: IpAddr, port: P) -> io::Result<WebSocketServerRef>
+    where
+        P: Into<PortRange> + Send,
+    {
+        let listener = WebSocketListener::bind(addr, port).await?;
+        let port = listener.port();
+        let inner = self.0.start(listener)?;
+        Ok(WebSocketServerRef { addr, port, inner })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv6Addr;
+
+    use async_trait::async_trait;
+    use distant_auth::DummyAuthHandler;
+    use test_log::test;
+
+    use super::*;
+    use crate::client::Client;
+    use crate::common::Request;
+    use crate::server::ServerCtx;
+
+    pub struct TestServerHandler;
+
+    #[async_trait]
+    impl ServerHandler for TestServerHandler {
+        type LocalData = ();
+        type Request = String;
+        type Response = String;
+
+        async fn on_request(&self, ctx: ServerCtx<Self::Request, Self::Response, Self::LocalData>) {
+            // Echo back what we received
+            ctx.reply
+                .send(ctx.request.payload.to_string())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test(tokio::test)]
+    async fn should_invoke_handler_upon_receiving_a_request() {
+        let server = WebSocketServerBuilder::default()
+            .handler(TestServerHandler)
+            .verifier(Verifier::none())
+            .start(IpAddr::V6(Ipv6Addr::LOCALHOST), 0)
+            .await
+            .expect("Failed to start WebSocket server");
+
+        let mut client: Client<String, String> =
+            Client::websocket(format!("ws://[{}]:{}", server.ip_addr(), server.port()))
+                .auth_handler(DummyAuthHandler)
+                .connect()
+                .await
+                .expect("Client failed to connect");
+
+        let response = client
+            .send(Request::new("hello".to_string()))
+            .await
+            .expect("Failed to send message");
+        assert_eq!(response.payload, "hello");
+    }
+}
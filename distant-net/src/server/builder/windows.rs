@@ -5,7 +5,7 @@ use distant_auth::Verifier;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::common::WindowsPipeListener;
+use crate::common::{ServerIdentity, WindowsPipeListener};
 use crate::server::{Server, ServerConfig, ServerHandler, WindowsPipeServerRef};
 
 pub struct WindowsPipeServerBuilder<T>(Server<T>);
@@ -35,6 +35,10 @@ impl<T> WindowsPipeServerBuilder<T> {
     pub fn verifier(self, verifier: Verifier) -> Self {
         Self(self.0.verifier(verifier))
     }
+
+    pub fn identity(self, identity: ServerIdentity) -> Self {
+        Self(self.0.identity(identity))
+    }
 }
 
 impl<T> WindowsPipeServerBuilder<T>
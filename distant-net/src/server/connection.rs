@@ -17,7 +17,8 @@ use super::{
     ShutdownTimer,
 };
 use crate::common::{
-    Backup, Connection, Frame, Interest, Keychain, Response, Transport, UntypedRequest,
+    Backup, Connection, Frame, Interest, Keychain, Response, ServerIdentity, Transport,
+    UntypedRequest,
 };
 
 pub type ServerKeychain = Keychain<oneshot::Receiver<Backup>>;
@@ -68,6 +69,7 @@ pub(super) struct ConnectionTaskBuilder<H, S, T> {
     sleep_duration: Duration,
     heartbeat_duration: Duration,
     verifier: Weak<Verifier>,
+    identity: Option<ServerIdentity>,
 }
 
 impl ConnectionTaskBuilder<(), (), ()> {
@@ -83,6 +85,7 @@ impl ConnectionTaskBuilder<(), (), ()> {
             sleep_duration: SLEEP_DURATION,
             heartbeat_duration: MINIMUM_HEARTBEAT_DURATION,
             verifier: Weak::new(),
+            identity: None,
         }
     }
 }
@@ -99,6 +102,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -113,6 +117,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -127,6 +132,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -141,6 +147,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -155,6 +162,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -172,6 +180,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -186,6 +195,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -203,6 +213,7 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration,
             verifier: self.verifier,
+            identity: self.identity,
         }
     }
 
@@ -217,6 +228,22 @@ impl<H, S, T> ConnectionTaskBuilder<H, S, T> {
             sleep_duration: self.sleep_duration,
             heartbeat_duration: self.heartbeat_duration,
             verifier,
+            identity: self.identity,
+        }
+    }
+
+    pub fn identity(self, identity: Option<ServerIdentity>) -> ConnectionTaskBuilder<H, S, T> {
+        ConnectionTaskBuilder {
+            handler: self.handler,
+            state: self.state,
+            keychain: self.keychain,
+            transport: self.transport,
+            shutdown: self.shutdown,
+            shutdown_timer: self.shutdown_timer,
+            sleep_duration: self.sleep_duration,
+            heartbeat_duration: self.heartbeat_duration,
+            verifier: self.verifier,
+            identity,
         }
     }
 }
@@ -244,6 +271,7 @@ where
             sleep_duration,
             heartbeat_duration,
             verifier,
+            identity,
         } = self;
 
         // NOTE: This exists purely to make the compiler happy for macro_rules declaration order.
@@ -412,6 +440,7 @@ where
                 match await_or_shutdown!(Box::pin(Connection::server(
                     transport,
                     verifier.as_ref(),
+                    identity.as_ref(),
                     keychain
                 ))) {
                     Ok(connection) => connection,
@@ -598,7 +627,7 @@ mod tests {
 
     use super::*;
     use crate::common::{
-        HeapSecretKey, InmemoryTransport, Ready, Reconnectable, Request, Response,
+        Handshake, HeapSecretKey, InmemoryTransport, Ready, Reconnectable, Request, Response,
     };
     use crate::server::Shutdown;
 
@@ -682,7 +711,7 @@ mod tests {
 
         // Spawn a task to handle establishing connection from client-side
         tokio::spawn(async move {
-            let _client = Connection::client(t2, DummyAuthHandler)
+            let _client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
         });
@@ -715,7 +744,7 @@ mod tests {
 
         // Spawn a task to handle establishing connection from client-side
         tokio::spawn(async move {
-            let _client = Connection::client(t2, DummyAuthHandler)
+            let _client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
         });
@@ -770,7 +799,7 @@ mod tests {
         // Spawn a task to handle establishing connection from client-side, and then closes to
         // trigger the server-side to close
         tokio::spawn(async move {
-            let _client = Connection::client(t2, DummyAuthHandler)
+            let _client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
         });
@@ -844,7 +873,7 @@ mod tests {
         // Spawn a task to handle establishing connection from client-side, set ready to fail
         // for the server-side after client connection completes, and wait a bit
         tokio::spawn(async move {
-            let _client = Connection::client(t2, DummyAuthHandler)
+            let _client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
 
@@ -888,7 +917,7 @@ mod tests {
         // Spawn a task to handle establishing connection from client-side, and then closes to
         // trigger the server-side to close
         tokio::spawn(async move {
-            let _client = Connection::client(t2, DummyAuthHandler)
+            let _client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
         });
@@ -917,7 +946,7 @@ mod tests {
 
         // Spawn a task to handle establishing connection from client-side
         let task = tokio::spawn(async move {
-            let mut client = Connection::client(t2, DummyAuthHandler)
+            let mut client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
 
@@ -954,7 +983,7 @@ mod tests {
 
         // Spawn a task to handle establishing connection from client-side
         let task = tokio::spawn(async move {
-            let mut client = Connection::client(t2, DummyAuthHandler)
+            let mut client = Connection::client(t2, Handshake::client(), DummyAuthHandler, None)
                 .await
                 .expect("Fail to establish client-side connection");
 
@@ -1065,7 +1094,12 @@ mod tests {
             .spawn();
 
         // Spawn a task to handle the client-side establishment of a full connection
-        let _client_task = tokio::spawn(Connection::client(t2, DummyAuthHandler));
+        let _client_task = tokio::spawn(Connection::client(
+            t2,
+            Handshake::client(),
+            DummyAuthHandler,
+            None,
+        ));
 
         // Shutdown server connection task while it is accepting the connection, verifying that we
         // do not get an error in return
@@ -1121,7 +1155,12 @@ mod tests {
             .spawn();
 
         // Spawn a task to handle the client-side establishment of a full connection
-        let _client_task = tokio::spawn(Connection::client(t2, DummyAuthHandler));
+        let _client_task = tokio::spawn(Connection::client(
+            t2,
+            Handshake::client(),
+            DummyAuthHandler,
+            None,
+        ));
 
         // Wait to ensure we complete the accept call first
         let _ = rx.recv().await;
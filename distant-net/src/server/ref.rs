@@ -105,6 +105,9 @@ mod unix;
 #[cfg(unix)]
 pub use unix::*;
 
+mod websocket;
+pub use websocket::*;
+
 #[cfg(windows)]
 mod windows;
 
@@ -19,6 +19,12 @@ pub trait Reply: Send + Sync {
 
     /// Clones this reply
     fn clone_reply(&self) -> Box<dyn Reply<Data = Self::Data>>;
+
+    /// Returns true if the reply can no longer send anything out, such as when the connection it
+    /// belongs to has been dropped; replies that cannot tell always return false
+    fn is_closed(&self) -> bool {
+        false
+    }
 }
 
 impl<T: Send + 'static> Reply for mpsc::Sender<T> {
@@ -40,6 +46,10 @@ impl<T: Send + 'static> Reply for mpsc::Sender<T> {
     fn clone_reply(&self) -> Box<dyn Reply<Data = Self::Data>> {
         Box::new(self.clone())
     }
+
+    fn is_closed(&self) -> bool {
+        mpsc::Sender::is_closed(self)
+    }
 }
 
 /// Utility to send ad-hoc replies from the server back through the connection
@@ -98,6 +108,10 @@ impl<T: Send + 'static> Reply for ServerReply<T> {
     fn clone_reply(&self) -> Box<dyn Reply<Data = Self::Data>> {
         Box::new(self.clone())
     }
+
+    fn is_closed(&self) -> bool {
+        ServerReply::is_closed(self)
+    }
 }
 
 /// Represents a reply where all sends are queued up but not sent until
@@ -200,4 +214,8 @@ impl<T: Send + 'static> Reply for QueuedServerReply<T> {
     fn clone_reply(&self) -> Box<dyn Reply<Data = Self::Data>> {
         Box::new(self.clone())
     }
+
+    fn is_closed(&self) -> bool {
+        QueuedServerReply::is_closed(self)
+    }
 }
@@ -3,10 +3,13 @@ mod change;
 mod cmd;
 mod error;
 mod filesystem;
+mod hash;
 mod metadata;
 mod permissions;
+mod process;
 mod pty;
 mod search;
+mod signal;
 mod system;
 mod version;
 
@@ -15,15 +18,27 @@ pub use change::*;
 pub use cmd::*;
 pub use error::*;
 pub use filesystem::*;
+pub use hash::*;
 pub use metadata::*;
 pub use permissions::*;
+pub use process::*;
 pub use pty::*;
 pub use search::*;
+pub use signal::*;
 pub use system::*;
 pub use version::*;
 
 /// Id for a remote process
 pub type ProcessId = u32;
 
+/// Id for an upload session
+pub type UploadId = u32;
+
+/// Id for a forwarded tcp stream
+pub type TcpStreamId = u32;
+
+/// Id for a tcp listener accepting forwarded tcp streams
+pub type TcpListenerId = u32;
+
 /// Version indicated by the tuple of (major, minor, patch).
 pub type SemVer = (u8, u8, u8);
@@ -20,6 +20,18 @@ pub struct DirEntry {
     pub depth: usize,
 }
 
+/// Represents a block of data to be written at some offset within a file
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct FileBlock {
+    /// Offset (in bytes) within the file where the block will be written
+    pub offset: u64,
+
+    /// Data of the block
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
 /// Represents the type associated with a dir entry
 #[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, AsRefStr, IsVariant, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
@@ -128,6 +140,80 @@ mod tests {
         }
     }
 
+    mod file_block {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let block = FileBlock {
+                offset: 123,
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
+            let value = serde_json::to_value(block).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "offset": 123,
+                    "data": [0, 1, 2, u8::MAX],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "offset": 123,
+                "data": [0, 1, 2, u8::MAX],
+            });
+
+            let block: FileBlock = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                block,
+                FileBlock {
+                    offset: 123,
+                    data: vec![0, 1, 2, u8::MAX],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let block = FileBlock {
+                offset: 123,
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&block).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&FileBlock {
+                offset: 123,
+                data: vec![0, 1, 2, u8::MAX],
+            })
+            .unwrap();
+
+            let block: FileBlock = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                block,
+                FileBlock {
+                    offset: 123,
+                    data: vec![0, 1, 2, u8::MAX],
+                }
+            );
+        }
+    }
+
     mod file_type {
         use super::*;
 
@@ -0,0 +1,189 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+/// Represents the algorithm used to compute the digest of some content
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    strum::Display,
+    EnumString,
+    EnumVariantNames,
+    Hash,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+#[strum(serialize_all = "snake_case")]
+pub enum HashAlgorithm {
+    /// SHA-256, producing a 32-byte digest
+    #[default]
+    Sha256,
+
+    /// BLAKE3, producing a 32-byte digest
+    Blake3,
+
+    /// 64-bit variant of XXH3, producing an 8-byte digest; this is not cryptographically secure
+    Xxh3,
+}
+
+impl HashAlgorithm {
+    /// Returns a list of all variants as str names
+    pub const fn variants() -> &'static [&'static str] {
+        Self::VARIANTS
+    }
+}
+
+/// Represents the digest of a single file found while hashing a directory
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct FileHash {
+    /// Path to the file relative to the directory that was hashed
+    pub path: PathBuf,
+
+    /// Digest of the file's contents as a lowercase hex string
+    pub digest: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod hash_algorithm {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let algorithm = HashAlgorithm::Blake3;
+
+            let value = serde_json::to_value(algorithm).unwrap();
+            assert_eq!(value, serde_json::json!("blake3"));
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!("xxh3");
+
+            let algorithm: HashAlgorithm = serde_json::from_value(value).unwrap();
+            assert_eq!(algorithm, HashAlgorithm::Xxh3);
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let algorithm = HashAlgorithm::Sha256;
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&algorithm).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&HashAlgorithm::Sha256).unwrap();
+
+            let algorithm: HashAlgorithm = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(algorithm, HashAlgorithm::Sha256);
+        }
+
+        #[test]
+        fn should_be_able_to_parse_from_str() {
+            assert_eq!(
+                "sha256".parse::<HashAlgorithm>().unwrap(),
+                HashAlgorithm::Sha256
+            );
+            assert_eq!(
+                "blake3".parse::<HashAlgorithm>().unwrap(),
+                HashAlgorithm::Blake3
+            );
+            assert_eq!(
+                "xxh3".parse::<HashAlgorithm>().unwrap(),
+                HashAlgorithm::Xxh3
+            );
+        }
+    }
+
+    mod file_hash {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let hash = FileHash {
+                path: PathBuf::from("file"),
+                digest: String::from("abcdef"),
+            };
+
+            let value = serde_json::to_value(hash).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "path": "file",
+                    "digest": "abcdef",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "path": "file",
+                "digest": "abcdef",
+            });
+
+            let hash: FileHash = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                hash,
+                FileHash {
+                    path: PathBuf::from("file"),
+                    digest: String::from("abcdef"),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let hash = FileHash {
+                path: PathBuf::from("file"),
+                digest: String::from("abcdef"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&hash).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&FileHash {
+                path: PathBuf::from("file"),
+                digest: String::from("abcdef"),
+            })
+            .unwrap();
+
+            let hash: FileHash = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                hash,
+                FileHash {
+                    path: PathBuf::from("file"),
+                    digest: String::from("abcdef"),
+                }
+            );
+        }
+    }
+}
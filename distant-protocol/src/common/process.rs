@@ -0,0 +1,516 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::{ProcessId, PtySize};
+use crate::utils;
+
+/// Represents information about a process spawned on the remote machine
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ProcessInfo {
+    /// Arbitrary id associated with the process
+    pub id: ProcessId,
+
+    /// The full command that was run including arguments
+    pub cmd: String,
+
+    /// Current size of the process' pty, or none if the process was not spawned in a pty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pty: Option<PtySize>,
+
+    /// Time when the process was spawned, represented as seconds since the Unix epoch
+    pub started: u64,
+}
+
+/// Represents options to apply to a process before it is spawned on the remote machine
+///
+/// Resource limits are applied to the new process prior to running the command, and any of the
+/// options that are not provided leave the process with what it would inherit from the server.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "snake_case")]
+pub struct ProcSpawnOptions {
+    /// Maximum CPU time in seconds that the process can consume before it is terminated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cpu_time: Option<u64>,
+
+    /// Maximum size in bytes of the virtual memory (address space) of the process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_address_space: Option<u64>,
+
+    /// Maximum number of file descriptors that the process can have open at once
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_open_files: Option<u64>,
+
+    /// Maximum number of processes that can exist for the user of the process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+
+    /// User id to switch to before running the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+
+    /// Group id to switch to before running the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+
+    /// File mode creation mask to set before running the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub umask: Option<u32>,
+
+    /// Time in milliseconds after which the process is killed if it is still running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// If true, stderr of the process is redirected into the same pipe as its stdout, akin to
+    /// `2>&1` in a shell, so both are reported as stdout in the order they were written
+    #[serde(skip_serializing_if = "utils::is_false")]
+    pub merge_stderr: bool,
+
+    /// If true, the command is run through the shell of the server as a login shell so that its
+    /// profile, including `PATH`, is loaded beforehand. This is the shell reported by system
+    /// info, which comes from the environment of the server (e.g. `SHELL`) rather than the
+    /// account of the user that the server runs as
+    #[serde(skip_serializing_if = "utils::is_false")]
+    pub login_shell: bool,
+}
+
+impl ProcSpawnOptions {
+    /// Returns true if no options have been set
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns true if any resource limit has been set
+    pub fn has_limits(&self) -> bool {
+        self.max_cpu_time.is_some()
+            || self.max_address_space.is_some()
+            || self.max_open_files.is_some()
+            || self.max_processes.is_some()
+    }
+}
+
+/// Represents a resource limit that was hit by a process, causing it to terminate
+///
+/// Only limits that terminate the process are reported. Other limits cause operations within the
+/// process to fail instead (e.g. opening a file once the maximum open files has been reached).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum ProcessLimit {
+    /// Process consumed its maximum CPU time
+    CpuTime,
+}
+
+/// Represents resources consumed by a process over its lifetime
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ProcessUsage {
+    /// Time in microseconds that the process spent executing in user mode
+    pub user_time: u64,
+
+    /// Time in microseconds that the process spent executing in kernel mode
+    pub system_time: u64,
+
+    /// Peak resident set size of the process in bytes
+    pub max_rss: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod process_info {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_info_to_json() {
+            let info = ProcessInfo {
+                id: 123,
+                cmd: String::from("echo some text"),
+                pty: None,
+                started: 456,
+            };
+
+            let value = serde_json::to_value(info).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "id": 123,
+                    "cmd": "echo some text",
+                    "started": 456,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_info_to_json() {
+            let info = ProcessInfo {
+                id: 123,
+                cmd: String::from("echo some text"),
+                pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                started: 456,
+            };
+
+            let value = serde_json::to_value(info).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "id": 123,
+                    "cmd": "echo some text",
+                    "pty": {
+                        "rows": 24,
+                        "cols": 80,
+                        "pixel_width": 0,
+                        "pixel_height": 0,
+                    },
+                    "started": 456,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_info_from_json() {
+            let value = serde_json::json!({
+                "id": 123,
+                "cmd": "echo some text",
+                "started": 456,
+            });
+
+            let info: ProcessInfo = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                info,
+                ProcessInfo {
+                    id: 123,
+                    cmd: String::from("echo some text"),
+                    pty: None,
+                    started: 456,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_info_from_json() {
+            let value = serde_json::json!({
+                "id": 123,
+                "cmd": "echo some text",
+                "pty": {
+                    "rows": 24,
+                    "cols": 80,
+                },
+                "started": 456,
+            });
+
+            let info: ProcessInfo = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                info,
+                ProcessInfo {
+                    id: 123,
+                    cmd: String::from("echo some text"),
+                    pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                    started: 456,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let info = ProcessInfo {
+                id: 123,
+                cmd: String::from("echo some text"),
+                pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                started: 456,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&info).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&ProcessInfo {
+                id: 123,
+                cmd: String::from("echo some text"),
+                pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                started: 456,
+            })
+            .unwrap();
+
+            let info: ProcessInfo = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                info,
+                ProcessInfo {
+                    id: 123,
+                    cmd: String::from("echo some text"),
+                    pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                    started: 456,
+                }
+            );
+        }
+    }
+
+    mod proc_spawn_options {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_options_to_json() {
+            let options = ProcSpawnOptions::default();
+
+            let value = serde_json::to_value(options).unwrap();
+            assert_eq!(value, serde_json::json!({}));
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_options_to_json() {
+            let options = ProcSpawnOptions {
+                max_cpu_time: Some(1),
+                max_address_space: Some(2),
+                max_open_files: Some(3),
+                max_processes: Some(4),
+                uid: Some(5),
+                gid: Some(6),
+                umask: Some(0o022),
+                timeout: Some(7),
+                merge_stderr: true,
+                login_shell: true,
+            };
+
+            let value = serde_json::to_value(options).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "max_cpu_time": 1,
+                    "max_address_space": 2,
+                    "max_open_files": 3,
+                    "max_processes": 4,
+                    "uid": 5,
+                    "gid": 6,
+                    "umask": 0o022,
+                    "timeout": 7,
+                    "merge_stderr": true,
+                    "login_shell": true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_options_from_json() {
+            let value = serde_json::json!({});
+
+            let options: ProcSpawnOptions = serde_json::from_value(value).unwrap();
+            assert_eq!(options, ProcSpawnOptions::default());
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_options_from_json() {
+            let value = serde_json::json!({
+                "max_cpu_time": 1,
+                "max_address_space": 2,
+                "max_open_files": 3,
+                "max_processes": 4,
+                "uid": 5,
+                "gid": 6,
+                "umask": 0o022,
+                "timeout": 7,
+                "merge_stderr": true,
+                "login_shell": true,
+            });
+
+            let options: ProcSpawnOptions = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                options,
+                ProcSpawnOptions {
+                    max_cpu_time: Some(1),
+                    max_address_space: Some(2),
+                    max_open_files: Some(3),
+                    max_processes: Some(4),
+                    uid: Some(5),
+                    gid: Some(6),
+                    umask: Some(0o022),
+                    timeout: Some(7),
+                    merge_stderr: true,
+                    login_shell: true,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let options = ProcSpawnOptions {
+                max_cpu_time: Some(1),
+                max_address_space: Some(2),
+                max_open_files: Some(3),
+                max_processes: Some(4),
+                uid: Some(5),
+                gid: Some(6),
+                umask: Some(0o022),
+                timeout: Some(7),
+                merge_stderr: true,
+                login_shell: true,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&options).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&ProcSpawnOptions {
+                max_cpu_time: Some(1),
+                max_address_space: None,
+                max_open_files: Some(3),
+                max_processes: None,
+                uid: Some(5),
+                gid: None,
+                umask: Some(0o022),
+                timeout: Some(7),
+                merge_stderr: true,
+                login_shell: true,
+            })
+            .unwrap();
+
+            let options: ProcSpawnOptions = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                options,
+                ProcSpawnOptions {
+                    max_cpu_time: Some(1),
+                    max_address_space: None,
+                    max_open_files: Some(3),
+                    max_processes: None,
+                    uid: Some(5),
+                    gid: None,
+                    umask: Some(0o022),
+                    timeout: Some(7),
+                    merge_stderr: true,
+                    login_shell: true,
+                }
+            );
+        }
+
+        #[test]
+        fn has_limits_should_only_consider_resource_limits() {
+            assert!(!ProcSpawnOptions::default().has_limits());
+            assert!(!ProcSpawnOptions {
+                uid: Some(5),
+                umask: Some(0o022),
+                timeout: Some(7),
+                ..Default::default()
+            }
+            .has_limits());
+            assert!(ProcSpawnOptions {
+                max_open_files: Some(3),
+                ..Default::default()
+            }
+            .has_limits());
+        }
+    }
+
+    mod process_limit {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let value = serde_json::to_value(ProcessLimit::CpuTime).unwrap();
+            assert_eq!(value, serde_json::json!("cpu_time"));
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let limit: ProcessLimit =
+                serde_json::from_value(serde_json::json!("cpu_time")).unwrap();
+            assert_eq!(limit, ProcessLimit::CpuTime);
+        }
+    }
+
+    mod process_usage {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let usage = ProcessUsage {
+                user_time: 1,
+                system_time: 2,
+                max_rss: 3,
+            };
+
+            let value = serde_json::to_value(usage).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "user_time": 1,
+                    "system_time": 2,
+                    "max_rss": 3,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "user_time": 1,
+                "system_time": 2,
+                "max_rss": 3,
+            });
+
+            let usage: ProcessUsage = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                usage,
+                ProcessUsage {
+                    user_time: 1,
+                    system_time: 2,
+                    max_rss: 3,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let usage = ProcessUsage {
+                user_time: 1,
+                system_time: 2,
+                max_rss: 3,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&usage).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&ProcessUsage {
+                user_time: 1,
+                system_time: 2,
+                max_rss: 3,
+            })
+            .unwrap();
+
+            let usage: ProcessUsage = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                usage,
+                ProcessUsage {
+                    user_time: 1,
+                    system_time: 2,
+                    max_rss: 3,
+                }
+            );
+        }
+    }
+}
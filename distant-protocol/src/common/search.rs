@@ -81,6 +81,13 @@ pub enum SearchQueryTarget {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", deny_unknown_fields, tag = "type")]
 pub enum SearchQueryCondition {
+    /// All of the conditions match
+    ///
+    /// Every condition is checked against the same target as the query, meaning that a condition
+    /// on a path cannot be combined with a condition on the contents of a file, and a condition
+    /// with its own `target` fails to deserialize
+    And { value: Vec<SearchQueryCondition> },
+
     /// Text is found anywhere (all regex patterns are escaped)
     Contains { value: String },
 
@@ -90,6 +97,9 @@ pub enum SearchQueryCondition {
     /// Matches some text exactly (all regex patterns are escaped)
     Equals { value: String },
 
+    /// The condition does not match, checked against the same target as the query
+    Not { value: Box<SearchQueryCondition> },
+
     /// Any of the conditions match
     Or { value: Vec<SearchQueryCondition> },
 
@@ -101,6 +111,17 @@ pub enum SearchQueryCondition {
 }
 
 impl SearchQueryCondition {
+    /// Creates a new instance with `And` variant
+    pub fn and<I, C>(value: I) -> Self
+    where
+        I: IntoIterator<Item = C>,
+        C: Into<SearchQueryCondition>,
+    {
+        Self::And {
+            value: value.into_iter().map(|s| s.into()).collect(),
+        }
+    }
+
     /// Creates a new instance with `Contains` variant
     pub fn contains(value: impl Into<String>) -> Self {
         Self::Contains {
@@ -122,6 +143,13 @@ impl SearchQueryCondition {
         }
     }
 
+    /// Creates a new instance with `Not` variant
+    pub fn not(value: impl Into<SearchQueryCondition>) -> Self {
+        Self::Not {
+            value: Box::new(value.into()),
+        }
+    }
+
     /// Creates a new instance with `Or` variant
     pub fn or<I, C>(value: I) -> Self
     where
@@ -147,7 +175,21 @@ impl SearchQueryCondition {
         }
     }
 
+    /// Returns true if the condition contains an `And` or `Not`, meaning that it cannot be
+    /// represented as a single regex and needs to be evaluated structurally
+    pub fn is_structural(&self) -> bool {
+        match self {
+            Self::And { .. } | Self::Not { .. } => true,
+            Self::Or { value } => value.iter().any(Self::is_structural),
+            _ => false,
+        }
+    }
+
     /// Converts the condition in a regex string
+    ///
+    /// A regex cannot express a structural condition (see [`Self::is_structural`]), so `And` is
+    /// converted as if it were an `Or` and `Not` is converted into the regex of the condition it
+    /// negates, leaving it to the caller to evaluate the condition as a whole
     pub fn to_regex_string(&self) -> String {
         match self {
             Self::Contains { value } => regex::escape(value),
@@ -155,7 +197,9 @@ impl SearchQueryCondition {
             Self::Equals { value } => format!(r"^{}$", regex::escape(value)),
             Self::Regex { value } => value.to_string(),
             Self::StartsWith { value } => format!(r"^{}", regex::escape(value)),
-            Self::Or { value } => {
+
+            Self::Not { value } => value.to_regex_string(),
+            Self::And { value } | Self::Or { value } => {
                 let mut s = String::new();
                 for (i, condition) in value.iter().enumerate() {
                     if i > 0 {
@@ -230,6 +274,56 @@ pub struct SearchQueryOptions {
     /// include the remaining results even if less than pagination request.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<u64>,
+
+    /// Minimum size (in bytes) of a file to be included in the search. When set, only files are
+    /// included in the results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_size: Option<u64>,
+
+    /// Maximum size (in bytes) of a file to be included in the search. When set, only files are
+    /// included in the results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_size: Option<u64>,
+
+    /// Only include paths that were last modified at or after this time (in seconds since the
+    /// unix epoch).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_after: Option<u64>,
+
+    /// Only include paths that were last modified at or before this time (in seconds since the
+    /// unix epoch).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_before: Option<u64>,
+
+    /// If true, will also search hidden files and directories, which are skipped by default.
+    #[serde(skip_serializing_if = "utils::is_false")]
+    pub hidden: bool,
+
+    /// If true, will not respect ignore files such as `.gitignore` and `.ignore`, which are
+    /// otherwise used to skip paths during the search.
+    ///
+    /// Servers that cannot read ignore files, such as the ssh implementation, never skip paths
+    /// because of them.
+    #[serde(skip_serializing_if = "utils::is_false")]
+    pub no_ignore: bool,
+
+    /// Additional ignore files (using gitignore syntax) whose rules are applied to the search
+    /// regardless of where they are located.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ignore_files: Vec<PathBuf>,
+
+    /// Number of lines to include before each match on a file's contents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before_context: Option<u64>,
+
+    /// Number of lines to include after each match on a file's contents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_context: Option<u64>,
+
+    /// If true, will allow matches on a file's contents to span multiple lines, in which case
+    /// `lines` of a match contains every line that was matched.
+    #[serde(skip_serializing_if = "utils::is_false")]
+    pub multiline: bool,
 }
 
 /// Represents a match for a search query
@@ -288,6 +382,39 @@ pub struct SearchQueryContentsMatch {
     /// Collection of matches tied to `lines` where each submatch's byte offset is relative to
     /// `lines` and not the overall content
     pub submatches: Vec<SearchQuerySubmatch>,
+
+    /// Lines surrounding `lines` when context was requested, ordered by line number
+    ///
+    /// Context shared between two nearby matches is only included with the earlier match.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<SearchQueryContextLine>,
+}
+
+/// Represents a line of context surrounding a match on a file's contents
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchQueryContextLine {
+    /// Whether the line comes before or after the match
+    pub kind: SearchQueryContextKind,
+
+    /// Content of the line
+    pub line: SearchQueryMatchData,
+
+    /// Line number of the line (base index 1)
+    pub line_number: u64,
+
+    /// Absolute byte offset corresponding to the start of `line` in the data being searched
+    pub absolute_offset: u64,
+}
+
+/// Position of a line of context relative to its match
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchQueryContextKind {
+    /// Line comes before the match
+    Before,
+
+    /// Line comes after the match
+    After,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -563,6 +690,138 @@ mod tests {
             );
         }
 
+        #[test]
+        fn to_regex_string_should_convert_and_like_or_and_not_like_its_negated_condition() {
+            assert_eq!(
+                SearchQueryCondition::and([
+                    SearchQueryCondition::contains("foo"),
+                    SearchQueryCondition::not(SearchQueryCondition::contains("bar")),
+                    SearchQueryCondition::regex("^baz"),
+                ])
+                .to_regex_string(),
+                r"foo|bar|^baz"
+            );
+            assert_eq!(
+                SearchQueryCondition::not(SearchQueryCondition::contains("bar")).to_regex_string(),
+                "bar"
+            );
+        }
+
+        #[test]
+        fn is_structural_should_return_true_if_and_or_not_found_anywhere_in_condition() {
+            assert!(!SearchQueryCondition::contains("foo").is_structural());
+            assert!(!SearchQueryCondition::or([
+                SearchQueryCondition::contains("foo"),
+                SearchQueryCondition::regex("bar"),
+            ])
+            .is_structural());
+            assert!(
+                SearchQueryCondition::and([SearchQueryCondition::contains("foo")]).is_structural()
+            );
+            assert!(
+                SearchQueryCondition::not(SearchQueryCondition::contains("foo")).is_structural()
+            );
+            assert!(SearchQueryCondition::or([
+                SearchQueryCondition::contains("foo"),
+                SearchQueryCondition::not(SearchQueryCondition::regex("bar")),
+            ])
+            .is_structural());
+        }
+
+        mod and {
+            use super::*;
+
+            #[test]
+            fn should_fail_to_deserialize_if_a_condition_has_its_own_target() {
+                let value = serde_json::json!({
+                    "type": "and",
+                    "value": [
+                        { "type": "contains", "value": "text" },
+                        { "type": "ends_with", "value": ".rs", "target": "path" },
+                    ],
+                });
+
+                let err = serde_json::from_value::<SearchQueryCondition>(value).unwrap_err();
+                assert!(err.to_string().contains("unknown field `target`"), "{err}");
+            }
+
+            #[test]
+            fn should_be_able_to_serialize_to_json() {
+                let condition = SearchQueryCondition::and([
+                    SearchQueryCondition::starts_with("start text"),
+                    SearchQueryCondition::ends_with("end text"),
+                ]);
+
+                let value = serde_json::to_value(condition).unwrap();
+                assert_eq!(
+                    value,
+                    serde_json::json!({
+                        "type": "and",
+                        "value": [
+                            { "type": "starts_with", "value": "start text" },
+                            { "type": "ends_with", "value": "end text" },
+                        ],
+                    })
+                );
+            }
+
+            #[test]
+            fn should_be_able_to_deserialize_from_json() {
+                let value = serde_json::json!({
+                    "type": "and",
+                    "value": [
+                        { "type": "starts_with", "value": "start text" },
+                        { "type": "ends_with", "value": "end text" },
+                    ],
+                });
+
+                let condition: SearchQueryCondition = serde_json::from_value(value).unwrap();
+                assert_eq!(
+                    condition,
+                    SearchQueryCondition::and([
+                        SearchQueryCondition::starts_with("start text"),
+                        SearchQueryCondition::ends_with("end text"),
+                    ])
+                );
+            }
+
+            #[test]
+            fn should_be_able_to_serialize_to_msgpack() {
+                let condition = SearchQueryCondition::and([
+                    SearchQueryCondition::starts_with("start text"),
+                    SearchQueryCondition::ends_with("end text"),
+                ]);
+
+                // NOTE: We don't actually check the output here because it's an implementation detail
+                // and could change as we change how serialization is done. This is merely to verify
+                // that we can serialize since there are times when serde fails to serialize at
+                // runtime.
+                let _ = rmp_serde::encode::to_vec_named(&condition).unwrap();
+            }
+
+            #[test]
+            fn should_be_able_to_deserialize_from_msgpack() {
+                // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+                // verify that we are not corrupting or causing issues when serializing on a
+                // client/server and then trying to deserialize on the other side. This has happened
+                // enough times with minor changes that we need tests to verify.
+                let buf = rmp_serde::encode::to_vec_named(&SearchQueryCondition::and([
+                    SearchQueryCondition::starts_with("start text"),
+                    SearchQueryCondition::ends_with("end text"),
+                ]))
+                .unwrap();
+
+                let condition: SearchQueryCondition = rmp_serde::decode::from_slice(&buf).unwrap();
+                assert_eq!(
+                    condition,
+                    SearchQueryCondition::and([
+                        SearchQueryCondition::starts_with("start text"),
+                        SearchQueryCondition::ends_with("end text"),
+                    ])
+                );
+            }
+        }
+
         mod contains {
             use super::*;
 
@@ -725,6 +984,69 @@ mod tests {
             }
         }
 
+        mod not {
+            use super::*;
+
+            #[test]
+            fn should_be_able_to_serialize_to_json() {
+                let condition =
+                    SearchQueryCondition::not(SearchQueryCondition::contains("some text"));
+
+                let value = serde_json::to_value(condition).unwrap();
+                assert_eq!(
+                    value,
+                    serde_json::json!({
+                        "type": "not",
+                        "value": { "type": "contains", "value": "some text" },
+                    })
+                );
+            }
+
+            #[test]
+            fn should_be_able_to_deserialize_from_json() {
+                let value = serde_json::json!({
+                    "type": "not",
+                    "value": { "type": "contains", "value": "some text" },
+                });
+
+                let condition: SearchQueryCondition = serde_json::from_value(value).unwrap();
+                assert_eq!(
+                    condition,
+                    SearchQueryCondition::not(SearchQueryCondition::contains("some text"))
+                );
+            }
+
+            #[test]
+            fn should_be_able_to_serialize_to_msgpack() {
+                let condition =
+                    SearchQueryCondition::not(SearchQueryCondition::contains("some text"));
+
+                // NOTE: We don't actually check the output here because it's an implementation detail
+                // and could change as we change how serialization is done. This is merely to verify
+                // that we can serialize since there are times when serde fails to serialize at
+                // runtime.
+                let _ = rmp_serde::encode::to_vec_named(&condition).unwrap();
+            }
+
+            #[test]
+            fn should_be_able_to_deserialize_from_msgpack() {
+                // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+                // verify that we are not corrupting or causing issues when serializing on a
+                // client/server and then trying to deserialize on the other side. This has happened
+                // enough times with minor changes that we need tests to verify.
+                let buf = rmp_serde::encode::to_vec_named(&SearchQueryCondition::not(
+                    SearchQueryCondition::contains("some text"),
+                ))
+                .unwrap();
+
+                let condition: SearchQueryCondition = rmp_serde::decode::from_slice(&buf).unwrap();
+                assert_eq!(
+                    condition,
+                    SearchQueryCondition::not(SearchQueryCondition::contains("some text"))
+                );
+            }
+        }
+
         mod or {
             use super::*;
 
@@ -929,6 +1251,16 @@ mod tests {
                 limit: None,
                 max_depth: None,
                 pagination: None,
+                min_size: None,
+                max_size: None,
+                modified_after: None,
+                modified_before: None,
+                hidden: false,
+                no_ignore: false,
+                ignore_files: Vec::new(),
+                before_context: None,
+                after_context: None,
+                multiline: false,
             };
 
             let value = serde_json::to_value(options).unwrap();
@@ -950,6 +1282,16 @@ mod tests {
                 limit: Some(u64::MAX),
                 max_depth: Some(u64::MAX),
                 pagination: Some(u64::MAX),
+                min_size: Some(u64::MAX),
+                max_size: Some(u64::MAX),
+                modified_after: Some(u64::MAX),
+                modified_before: Some(u64::MAX),
+                hidden: true,
+                no_ignore: true,
+                ignore_files: vec![PathBuf::from("path")],
+                before_context: Some(u64::MAX),
+                after_context: Some(u64::MAX),
+                multiline: true,
             };
 
             let value = serde_json::to_value(options).unwrap();
@@ -970,6 +1312,16 @@ mod tests {
                     "limit": u64::MAX,
                     "max_depth": u64::MAX,
                     "pagination": u64::MAX,
+                    "min_size": u64::MAX,
+                    "max_size": u64::MAX,
+                    "modified_after": u64::MAX,
+                    "modified_before": u64::MAX,
+                    "hidden": true,
+                    "no_ignore": true,
+                    "ignore_files": ["path"],
+                    "before_context": u64::MAX,
+                    "after_context": u64::MAX,
+                    "multiline": true,
                 })
             );
         }
@@ -990,6 +1342,16 @@ mod tests {
                     limit: None,
                     max_depth: None,
                     pagination: None,
+                    min_size: None,
+                    max_size: None,
+                    modified_after: None,
+                    modified_before: None,
+                    hidden: false,
+                    no_ignore: false,
+                    ignore_files: Vec::new(),
+                    before_context: None,
+                    after_context: None,
+                    multiline: false,
                 }
             );
         }
@@ -1011,6 +1373,16 @@ mod tests {
                 "limit": u64::MAX,
                 "max_depth": u64::MAX,
                 "pagination": u64::MAX,
+                "min_size": u64::MAX,
+                "max_size": u64::MAX,
+                "modified_after": u64::MAX,
+                "modified_before": u64::MAX,
+                "hidden": true,
+                "no_ignore": true,
+                "ignore_files": ["path"],
+                "before_context": u64::MAX,
+                "after_context": u64::MAX,
+                "multiline": true,
             });
 
             let options: SearchQueryOptions = serde_json::from_value(value).unwrap();
@@ -1029,6 +1401,16 @@ mod tests {
                     limit: Some(u64::MAX),
                     max_depth: Some(u64::MAX),
                     pagination: Some(u64::MAX),
+                    min_size: Some(u64::MAX),
+                    max_size: Some(u64::MAX),
+                    modified_after: Some(u64::MAX),
+                    modified_before: Some(u64::MAX),
+                    hidden: true,
+                    no_ignore: true,
+                    ignore_files: vec![PathBuf::from("path")],
+                    before_context: Some(u64::MAX),
+                    after_context: Some(u64::MAX),
+                    multiline: true,
                 }
             );
         }
@@ -1044,6 +1426,16 @@ mod tests {
                 limit: None,
                 max_depth: None,
                 pagination: None,
+                min_size: None,
+                max_size: None,
+                modified_after: None,
+                modified_before: None,
+                hidden: false,
+                no_ignore: false,
+                ignore_files: Vec::new(),
+                before_context: None,
+                after_context: None,
+                multiline: false,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1068,6 +1460,16 @@ mod tests {
                 limit: Some(u64::MAX),
                 max_depth: Some(u64::MAX),
                 pagination: Some(u64::MAX),
+                min_size: Some(u64::MAX),
+                max_size: Some(u64::MAX),
+                modified_after: Some(u64::MAX),
+                modified_before: Some(u64::MAX),
+                hidden: true,
+                no_ignore: true,
+                ignore_files: vec![PathBuf::from("path")],
+                before_context: Some(u64::MAX),
+                after_context: Some(u64::MAX),
+                multiline: true,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1092,6 +1494,16 @@ mod tests {
                 limit: None,
                 max_depth: None,
                 pagination: None,
+                min_size: None,
+                max_size: None,
+                modified_after: None,
+                modified_before: None,
+                hidden: false,
+                no_ignore: false,
+                ignore_files: Vec::new(),
+                before_context: None,
+                after_context: None,
+                multiline: false,
             })
             .unwrap();
 
@@ -1107,6 +1519,16 @@ mod tests {
                     limit: None,
                     max_depth: None,
                     pagination: None,
+                    min_size: None,
+                    max_size: None,
+                    modified_after: None,
+                    modified_before: None,
+                    hidden: false,
+                    no_ignore: false,
+                    ignore_files: Vec::new(),
+                    before_context: None,
+                    after_context: None,
+                    multiline: false,
                 }
             );
         }
@@ -1130,6 +1552,16 @@ mod tests {
                 limit: Some(u64::MAX),
                 max_depth: Some(u64::MAX),
                 pagination: Some(u64::MAX),
+                min_size: Some(u64::MAX),
+                max_size: Some(u64::MAX),
+                modified_after: Some(u64::MAX),
+                modified_before: Some(u64::MAX),
+                hidden: true,
+                no_ignore: true,
+                ignore_files: vec![PathBuf::from("path")],
+                before_context: Some(u64::MAX),
+                after_context: Some(u64::MAX),
+                multiline: true,
             })
             .unwrap();
 
@@ -1149,6 +1581,16 @@ mod tests {
                     limit: Some(u64::MAX),
                     max_depth: Some(u64::MAX),
                     pagination: Some(u64::MAX),
+                    min_size: Some(u64::MAX),
+                    max_size: Some(u64::MAX),
+                    modified_after: Some(u64::MAX),
+                    modified_before: Some(u64::MAX),
+                    hidden: true,
+                    no_ignore: true,
+                    ignore_files: vec![PathBuf::from("path")],
+                    before_context: Some(u64::MAX),
+                    after_context: Some(u64::MAX),
+                    multiline: true,
                 }
             );
         }
@@ -1278,6 +1720,7 @@ mod tests {
                         start: 8,
                         end: 13,
                     }],
+                    context: Vec::new(),
                 });
 
                 let value = serde_json::to_value(r#match).unwrap();
@@ -1326,6 +1769,7 @@ mod tests {
                             start: 8,
                             end: 13,
                         }],
+                        context: Vec::new(),
                     })
                 );
             }
@@ -1342,6 +1786,7 @@ mod tests {
                         start: 8,
                         end: 13,
                     }],
+                    context: Vec::new(),
                 });
 
                 // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1368,6 +1813,7 @@ mod tests {
                             start: 8,
                             end: 13,
                         }],
+                        context: Vec::new(),
                     },
                 ))
                 .unwrap();
@@ -1385,6 +1831,7 @@ mod tests {
                             start: 8,
                             end: 13,
                         }],
+                        context: Vec::new(),
                     })
                 );
             }
@@ -1508,6 +1955,7 @@ mod tests {
                     start: 8,
                     end: 13,
                 }],
+                context: Vec::new(),
             };
 
             let value = serde_json::to_value(r#match).unwrap();
@@ -1554,6 +2002,7 @@ mod tests {
                         start: 8,
                         end: 13,
                     }],
+                    context: Vec::new(),
                 }
             );
         }
@@ -1570,6 +2019,7 @@ mod tests {
                     start: 8,
                     end: 13,
                 }],
+                context: Vec::new(),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1595,6 +2045,7 @@ mod tests {
                     start: 8,
                     end: 13,
                 }],
+                context: Vec::new(),
             })
             .unwrap();
 
@@ -1611,6 +2062,141 @@ mod tests {
                         start: 8,
                         end: 13,
                     }],
+                    context: Vec::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_with_context_to_json() {
+            let r#match = SearchQueryContentsMatch {
+                path: PathBuf::from("path"),
+                lines: SearchQueryMatchData::Text(String::from("some text")),
+                line_number: 12,
+                absolute_offset: 24,
+                submatches: vec![SearchQuerySubmatch {
+                    r#match: SearchQueryMatchData::Text(String::from("text")),
+                    start: 8,
+                    end: 13,
+                }],
+                context: vec![
+                    SearchQueryContextLine {
+                        kind: SearchQueryContextKind::Before,
+                        line: SearchQueryMatchData::Text(String::from("before")),
+                        line_number: 11,
+                        absolute_offset: 17,
+                    },
+                    SearchQueryContextLine {
+                        kind: SearchQueryContextKind::After,
+                        line: SearchQueryMatchData::Bytes(vec![0, 1, 2]),
+                        line_number: 13,
+                        absolute_offset: 34,
+                    },
+                ],
+            };
+
+            let value = serde_json::to_value(r#match).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "path": "path",
+                    "lines": "some text",
+                    "line_number": 12,
+                    "absolute_offset": 24,
+                    "submatches": [{
+                        "match": "text",
+                        "start": 8,
+                        "end": 13,
+                    }],
+                    "context": [
+                        {
+                            "kind": "before",
+                            "line": "before",
+                            "line_number": 11,
+                            "absolute_offset": 17,
+                        },
+                        {
+                            "kind": "after",
+                            "line": [0, 1, 2],
+                            "line_number": 13,
+                            "absolute_offset": 34,
+                        },
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_with_context_from_json() {
+            let value = serde_json::json!({
+                "path": "path",
+                "lines": "some text",
+                "line_number": 12,
+                "absolute_offset": 24,
+                "submatches": [],
+                "context": [{
+                    "kind": "after",
+                    "line": "after",
+                    "line_number": 13,
+                    "absolute_offset": 34,
+                }],
+            });
+
+            let r#match: SearchQueryContentsMatch = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                r#match,
+                SearchQueryContentsMatch {
+                    path: PathBuf::from("path"),
+                    lines: SearchQueryMatchData::Text(String::from("some text")),
+                    line_number: 12,
+                    absolute_offset: 24,
+                    submatches: Vec::new(),
+                    context: vec![SearchQueryContextLine {
+                        kind: SearchQueryContextKind::After,
+                        line: SearchQueryMatchData::Text(String::from("after")),
+                        line_number: 13,
+                        absolute_offset: 34,
+                    }],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_with_context_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&SearchQueryContentsMatch {
+                path: PathBuf::from("path"),
+                lines: SearchQueryMatchData::Text(String::from("some text")),
+                line_number: 12,
+                absolute_offset: 24,
+                submatches: Vec::new(),
+                context: vec![SearchQueryContextLine {
+                    kind: SearchQueryContextKind::Before,
+                    line: SearchQueryMatchData::Text(String::from("before")),
+                    line_number: 11,
+                    absolute_offset: 17,
+                }],
+            })
+            .unwrap();
+
+            let r#match: SearchQueryContentsMatch = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                r#match,
+                SearchQueryContentsMatch {
+                    path: PathBuf::from("path"),
+                    lines: SearchQueryMatchData::Text(String::from("some text")),
+                    line_number: 12,
+                    absolute_offset: 24,
+                    submatches: Vec::new(),
+                    context: vec![SearchQueryContextLine {
+                        kind: SearchQueryContextKind::Before,
+                        line: SearchQueryMatchData::Text(String::from("before")),
+                        line_number: 11,
+                        absolute_offset: 17,
+                    }],
                 }
             );
         }
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::str::FromStr;
+
+use derive_more::{Display, Error};
+use serde::{Deserialize, Serialize};
+
+/// Represents a signal that can be sent to a process, named after its POSIX equivalent
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Signal {
+    /// Hangup detected on controlling terminal or death of controlling process
+    Hup,
+
+    /// Interrupt from keyboard (Ctrl-C)
+    Int,
+
+    /// Quit from keyboard (Ctrl-\)
+    Quit,
+
+    /// Abort signal
+    Abrt,
+
+    /// Kill signal, which cannot be caught or ignored
+    Kill,
+
+    /// Timer signal
+    Alrm,
+
+    /// Termination signal
+    Term,
+
+    /// User-defined signal 1
+    Usr1,
+
+    /// User-defined signal 2
+    Usr2,
+
+    /// Continue if stopped
+    Cont,
+
+    /// Stop process, which cannot be caught or ignored
+    Stop,
+
+    /// Stop typed at terminal (Ctrl-Z)
+    Tstp,
+
+    /// Window resize signal
+    Winch,
+}
+
+impl Signal {
+    /// Collection of all signals
+    pub const ALL: &'static [Signal] = &[
+        Self::Hup,
+        Self::Int,
+        Self::Quit,
+        Self::Abrt,
+        Self::Kill,
+        Self::Alrm,
+        Self::Term,
+        Self::Usr1,
+        Self::Usr2,
+        Self::Cont,
+        Self::Stop,
+        Self::Tstp,
+        Self::Winch,
+    ];
+
+    /// Returns the name of the signal without its `SIG` prefix, e.g. `INT`
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Hup => "HUP",
+            Self::Int => "INT",
+            Self::Quit => "QUIT",
+            Self::Abrt => "ABRT",
+            Self::Kill => "KILL",
+            Self::Alrm => "ALRM",
+            Self::Term => "TERM",
+            Self::Usr1 => "USR1",
+            Self::Usr2 => "USR2",
+            Self::Cont => "CONT",
+            Self::Stop => "STOP",
+            Self::Tstp => "TSTP",
+            Self::Winch => "WINCH",
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SIG{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Display, Error)]
+#[display(fmt = "unknown signal")]
+pub struct SignalParseError;
+
+impl FromStr for Signal {
+    type Err = SignalParseError;
+
+    /// Parses a signal from its name, ignoring case and an optional `SIG` prefix, meaning that
+    /// `INT`, `sigint`, and `SIGINT` are all the same signal
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let name = match s.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("sig") => &s[3..],
+            _ => s,
+        };
+
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|signal| signal.name().eq_ignore_ascii_case(name))
+            .ok_or(SignalParseError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_be_able_to_serialize_to_json() {
+        let signal = Signal::Usr1;
+
+        let value = serde_json::to_value(signal).unwrap();
+        assert_eq!(value, serde_json::json!("usr1"));
+    }
+
+    #[test]
+    fn should_be_able_to_deserialize_from_json() {
+        let value = serde_json::json!("term");
+
+        let signal: Signal = serde_json::from_value(value).unwrap();
+        assert_eq!(signal, Signal::Term);
+    }
+
+    #[test]
+    fn should_be_able_to_serialize_to_msgpack() {
+        let signal = Signal::Int;
+
+        // NOTE: We don't actually check the output here because it's an implementation detail
+        // and could change as we change how serialization is done. This is merely to verify
+        // that we can serialize since there are times when serde fails to serialize at
+        // runtime.
+        let _ = rmp_serde::encode::to_vec_named(&signal).unwrap();
+    }
+
+    #[test]
+    fn should_be_able_to_deserialize_from_msgpack() {
+        // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+        // verify that we are not corrupting or causing issues when serializing on a
+        // client/server and then trying to deserialize on the other side. This has happened
+        // enough times with minor changes that we need tests to verify.
+        let buf = rmp_serde::encode::to_vec_named(&Signal::Hup).unwrap();
+
+        let signal: Signal = rmp_serde::decode::from_slice(&buf).unwrap();
+        assert_eq!(signal, Signal::Hup);
+    }
+
+    #[test]
+    fn should_display_with_sig_prefix() {
+        assert_eq!(Signal::Int.to_string(), "SIGINT");
+        assert_eq!(Signal::Winch.to_string(), "SIGWINCH");
+    }
+
+    #[test]
+    fn should_parse_with_or_without_sig_prefix_ignoring_case() {
+        assert_eq!("INT".parse::<Signal>().unwrap(), Signal::Int);
+        assert_eq!("sigterm".parse::<Signal>().unwrap(), Signal::Term);
+        assert_eq!("SIGUSR1".parse::<Signal>().unwrap(), Signal::Usr1);
+        assert_eq!("SigHup".parse::<Signal>().unwrap(), Signal::Hup);
+    }
+
+    #[test]
+    fn should_fail_to_parse_unknown_signal() {
+        assert_eq!("SIGFOO".parse::<Signal>().unwrap_err(), SignalParseError);
+        assert_eq!("SIG".parse::<Signal>().unwrap_err(), SignalParseError);
+        assert_eq!("9".parse::<Signal>().unwrap_err(), SignalParseError);
+    }
+
+    #[test]
+    fn should_round_trip_every_signal_through_display_and_parse() {
+        for signal in Signal::ALL {
+            assert_eq!(signal.to_string().parse::<Signal>().unwrap(), *signal);
+        }
+    }
+}
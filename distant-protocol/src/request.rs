@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumDiscriminants, EnumIter, EnumMessage, EnumString};
 
 use crate::common::{
-    ChangeKind, Cmd, Permissions, ProcessId, PtySize, SearchId, SearchQuery, SetPermissionsOptions,
+    ChangeKind, Cmd, FileBlock, HashAlgorithm, Permissions, ProcSpawnOptions, ProcessId, PtySize,
+    SearchId, SearchQuery, SetPermissionsOptions, Signal, TcpListenerId, TcpStreamId, UploadId,
 };
 use crate::utils;
 
@@ -47,6 +48,39 @@ pub enum Request {
         path: PathBuf,
     },
 
+    /// Reads a range of bytes from a file at the specified path on the remote machine
+    #[strum_discriminants(strum(message = "Supports reading a range of bytes from a file"))]
+    FileReadRange {
+        /// The path to the file on the remote machine
+        path: PathBuf,
+
+        /// Offset (in bytes) within the file where reading will begin
+        #[serde(default, skip_serializing_if = "utils::is_zero")]
+        offset: u64,
+
+        /// Maximum number of bytes to read, or none to read until the end of the file. Servers
+        /// may refuse to read large ranges, whether or not a length is given, which need to be
+        /// streamed instead
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        len: Option<u64>,
+    },
+
+    /// Reads a file from the specified path on the remote machine, sending the contents back
+    /// as a series of blobs followed by a marker indicating that the read has finished
+    #[strum_discriminants(strum(message = "Supports streaming the contents of a file"))]
+    FileReadStream {
+        /// The path to the file on the remote machine
+        path: PathBuf,
+
+        /// Offset (in bytes) within the file where reading will begin
+        #[serde(default, skip_serializing_if = "utils::is_zero")]
+        offset: u64,
+
+        /// Maximum number of bytes to read, or none to read until the end of the file
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        len: Option<u64>,
+    },
+
     /// Writes a file, creating it if it does not exist, and overwriting any existing content
     /// on the remote machine
     #[strum_discriminants(strum(message = "Supports writing binary file"))]
@@ -91,6 +125,74 @@ pub enum Request {
         text: String,
     },
 
+    /// Writes blocks of data at their offsets within a file, creating it if it does not exist,
+    /// and then resizes the file to the given length on the remote machine, either applying the
+    /// entire patch or leaving the file untouched
+    #[strum_discriminants(strum(message = "Supports writing blocks of data within a file"))]
+    FilePatch {
+        /// The path to the file on the remote machine
+        path: PathBuf,
+
+        /// Length (in bytes) of the file once patched, truncating or extending it as needed
+        len: u64,
+
+        /// Blocks of data to write, leaving the rest of the file untouched
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        blocks: Vec<FileBlock>,
+    },
+
+    /// Opens a session to upload a file in chunks to the remote machine, where the contents are
+    /// written to a partial file that is only moved into place once committed
+    #[strum_discriminants(strum(message = "Supports opening a resumable upload"))]
+    UploadOpen {
+        /// The path where the file will be placed on the remote machine once committed
+        path: PathBuf,
+
+        /// Whether or not to resume a partial upload of the same path, otherwise any partial
+        /// upload is discarded and the upload starts over
+        #[serde(default, skip_serializing_if = "utils::is_false")]
+        resume: bool,
+    },
+
+    /// Writes a chunk of data to an open upload session
+    #[strum_discriminants(strum(message = "Supports writing a chunk to a resumable upload"))]
+    UploadWrite {
+        /// Id of the upload session
+        id: UploadId,
+
+        /// Offset (in bytes) within the file where the chunk will be written, which cannot be
+        /// past the last confirmed offset of the upload
+        offset: u64,
+
+        /// Data for server-side writing of content
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+
+    /// Commits an upload session, moving the uploaded file into place
+    #[strum_discriminants(strum(message = "Supports committing a resumable upload"))]
+    UploadCommit {
+        /// Id of the upload session
+        id: UploadId,
+
+        /// If provided, the hex-encoded SHA-256 digest that the uploaded contents must match
+        /// before the file is moved into place
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        checksum: Option<String>,
+
+        /// If provided, the time (in seconds since the unix epoch) to set as the last
+        /// modification time of the file once moved into place
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        modified: Option<u64>,
+    },
+
+    /// Aborts an upload session, discarding any partially-uploaded data
+    #[strum_discriminants(strum(message = "Supports aborting a resumable upload"))]
+    UploadAbort {
+        /// Id of the upload session
+        id: UploadId,
+    },
+
     /// Reads a directory from the specified path on the remote machine
     #[strum_discriminants(strum(message = "Supports reading directory"))]
     DirRead {
@@ -168,6 +270,69 @@ pub enum Request {
         dst: PathBuf,
     },
 
+    /// Creates a symbolic link on the remote machine
+    #[strum_discriminants(strum(message = "Supports creating symbolic links"))]
+    Symlink {
+        /// The path the symbolic link will point to, which does not need to exist
+        src: PathBuf,
+
+        /// Location on the remote machine of the new symbolic link
+        dst: PathBuf,
+    },
+
+    /// Creates a hard link on the remote machine
+    #[strum_discriminants(strum(message = "Supports creating hard links"))]
+    HardLink {
+        /// The path to the existing file on the remote machine
+        src: PathBuf,
+
+        /// Location on the remote machine of the new hard link
+        dst: PathBuf,
+    },
+
+    /// Reads the target of a symbolic link on the remote machine without canonicalizing it
+    #[strum_discriminants(strum(message = "Supports reading the target of symbolic links"))]
+    ReadLink {
+        /// The path to the symbolic link on the remote machine
+        path: PathBuf,
+    },
+
+    /// Computes the digest of a file, or of every file within a directory, on the remote machine
+    #[strum_discriminants(strum(message = "Supports computing digests of files and directories"))]
+    Hash {
+        /// The path to the file (or directory if recursive) on the remote machine
+        path: PathBuf,
+
+        /// Algorithm used to compute the digest
+        algorithm: HashAlgorithm,
+
+        /// Offset (in bytes) within each file where hashing will begin
+        #[serde(default, skip_serializing_if = "utils::is_zero")]
+        offset: u64,
+
+        /// Maximum number of bytes to hash within each file, or none to hash until the end
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        len: Option<u64>,
+
+        /// If true, will hash every file within the directory and its subdirectories, returning
+        /// a digest per file
+        #[serde(default, skip_serializing_if = "utils::is_false")]
+        recursive: bool,
+    },
+
+    /// Computes the digest of each fixed-size block of a file on the remote machine
+    #[strum_discriminants(strum(message = "Supports computing digests of blocks within a file"))]
+    FileBlockChecksums {
+        /// The path to the file on the remote machine
+        path: PathBuf,
+
+        /// Algorithm used to compute the digests
+        algorithm: HashAlgorithm,
+
+        /// Size (in bytes) of each block, where the last block may be smaller
+        block_size: u64,
+    },
+
     /// Watches a path for changes
     #[strum_discriminants(strum(message = "Supports watching filesystem for changes"))]
     Watch {
@@ -253,6 +418,22 @@ pub enum Request {
         id: SearchId,
     },
 
+    /// Replaces matches of a query within the contents of files
+    #[strum_discriminants(strum(message = "Supports replacing matches of queries within files"))]
+    Replace {
+        /// Query whose matches within the contents of files are replaced, which cannot use `And`
+        /// or `Not` conditions as they do not identify any text to replace
+        query: SearchQuery,
+
+        /// Template used in place of each match, where `$1` or `${name}` refer to capture groups
+        /// of the query's condition
+        replacement: String,
+
+        /// If true, will report a diff of each file that would change instead of modifying it
+        #[serde(default, skip_serializing_if = "utils::is_false")]
+        dry_run: bool,
+    },
+
     /// Spawns a new process on the remote machine
     #[strum_discriminants(strum(message = "Supports spawning a process"))]
     ProcSpawn {
@@ -270,6 +451,10 @@ pub enum Request {
         /// If provided, will spawn process in a pty, otherwise spawns directly
         #[serde(default, skip_serializing_if = "Option::is_none")]
         pty: Option<PtySize>,
+
+        /// Additional options such as resource limits to apply to the process
+        #[serde(default, skip_serializing_if = "ProcSpawnOptions::is_default")]
+        options: ProcSpawnOptions,
     },
 
     /// Kills a process running on the remote machine
@@ -279,6 +464,16 @@ pub enum Request {
         id: ProcessId,
     },
 
+    /// Sends a signal to a process running on the remote machine
+    #[strum_discriminants(strum(message = "Supports sending signals to a spawned process"))]
+    ProcSignal {
+        /// Id of the actively-running process
+        id: ProcessId,
+
+        /// Signal to send to the process
+        signal: Signal,
+    },
+
     /// Sends additional data to stdin of running process
     #[strum_discriminants(strum(message = "Supports sending stdin to a spawned process"))]
     ProcStdin {
@@ -300,10 +495,74 @@ pub enum Request {
         size: PtySize,
     },
 
+    /// Lists processes spawned on the remote machine that are still tracked by the server
+    #[strum_discriminants(strum(message = "Supports listing spawned processes"))]
+    ProcList {},
+
+    /// Resumes receiving stdout, stderr, and completion of a process spawned by another
+    /// connection, replacing where its responses were previously sent
+    #[strum_discriminants(strum(message = "Supports attaching to a spawned process"))]
+    ProcAttach {
+        /// Id of the process to attach to
+        id: ProcessId,
+    },
+
+    /// Opens a tcp stream from the remote machine to the specified address, sending back any
+    /// data received on it
+    #[strum_discriminants(strum(message = "Supports opening a forwarded tcp stream"))]
+    TcpConnect {
+        /// Host (name or ip address) to connect to from the remote machine
+        host: String,
+
+        /// Port to connect to from the remote machine
+        port: u16,
+    },
+
+    /// Listens for tcp connections on the remote machine, opening a forwarded tcp stream for
+    /// each connection accepted
+    #[strum_discriminants(strum(message = "Supports listening for forwarded tcp streams"))]
+    TcpListen {
+        /// Host (name or ip address) to bind to on the remote machine
+        host: String,
+
+        /// Port to bind to on the remote machine, where 0 means any available port
+        port: u16,
+    },
+
+    /// Sends data on a forwarded tcp stream
+    #[strum_discriminants(strum(message = "Supports sending data on a forwarded tcp stream"))]
+    TcpWrite {
+        /// Id of the forwarded tcp stream
+        id: TcpStreamId,
+
+        /// Data to send on the tcp stream
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+
+    /// Closes a forwarded tcp stream
+    #[strum_discriminants(strum(message = "Supports closing a forwarded tcp stream"))]
+    TcpClose {
+        /// Id of the forwarded tcp stream
+        id: TcpStreamId,
+    },
+
+    /// Stops listening for tcp connections on the remote machine, leaving any forwarded tcp
+    /// streams that were already accepted open
+    #[strum_discriminants(strum(message = "Supports no longer listening for tcp connections"))]
+    TcpUnlisten {
+        /// Id of the tcp listener
+        id: TcpListenerId,
+    },
+
     /// Retrieve information about the server and the system it is on
     #[strum_discriminants(strum(message = "Supports retrieving system information"))]
     SystemInfo {},
 
+    /// Retrieve the environment variables of the server
+    #[strum_discriminants(strum(message = "Supports retrieving environment variables"))]
+    Environment {},
+
     /// Retrieve information about the server's protocol version
     #[strum_discriminants(strum(message = "Supports retrieving version"))]
     Version {},
@@ -451,50 +710,91 @@ mod tests {
         }
     }
 
-    mod file_write {
+    mod file_read_range {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_to_json() {
-            let payload = Request::FileWrite {
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::FileReadRange {
                 path: PathBuf::from("path"),
-                data: vec![0, 1, 2, u8::MAX],
+                offset: 0,
+                len: None,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "file_write",
+                    "type": "file_read_range",
                     "path": "path",
-                    "data": [0, 1, 2, u8::MAX],
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_json() {
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::FileReadRange {
+                path: PathBuf::from("path"),
+                offset: 123,
+                len: Some(456),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "file_read_range",
+                    "path": "path",
+                    "offset": 123,
+                    "len": 456,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "file_write",
+                "type": "file_read_range",
                 "path": "path",
-                "data": [0, 1, 2, u8::MAX],
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::FileWrite {
+                Request::FileReadRange {
                     path: PathBuf::from("path"),
-                    data: vec![0, 1, 2, u8::MAX],
+                    offset: 0,
+                    len: None,
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::FileWrite {
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "file_read_range",
+                "path": "path",
+                "offset": 123,
+                "len": 456,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileReadRange {
+                    path: PathBuf::from("path"),
+                    offset: 123,
+                    len: Some(456),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::FileReadRange {
                 path: PathBuf::from("path"),
-                data: vec![0, 1, 2, u8::MAX],
+                offset: 0,
+                len: None,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -505,72 +805,154 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_msgpack() {
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::FileReadRange {
+                path: PathBuf::from("path"),
+                offset: 123,
+                len: Some(456),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::FileWrite {
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileReadRange {
                 path: PathBuf::from("path"),
-                data: vec![0, 1, 2, u8::MAX],
+                offset: 0,
+                len: None,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::FileWrite {
+                Request::FileReadRange {
                     path: PathBuf::from("path"),
-                    data: vec![0, 1, 2, u8::MAX],
+                    offset: 0,
+                    len: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileReadRange {
+                path: PathBuf::from("path"),
+                offset: 123,
+                len: Some(456),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileReadRange {
+                    path: PathBuf::from("path"),
+                    offset: 123,
+                    len: Some(456),
                 }
             );
         }
     }
 
-    mod file_write_text {
+    mod file_read_stream {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_to_json() {
-            let payload = Request::FileWriteText {
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::FileReadStream {
                 path: PathBuf::from("path"),
-                text: String::from("text"),
+                offset: 0,
+                len: None,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "file_write_text",
+                    "type": "file_read_stream",
                     "path": "path",
-                    "text": "text",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_json() {
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::FileReadStream {
+                path: PathBuf::from("path"),
+                offset: 123,
+                len: Some(456),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "file_read_stream",
+                    "path": "path",
+                    "offset": 123,
+                    "len": 456,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "file_write_text",
+                "type": "file_read_stream",
                 "path": "path",
-                "text": "text",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::FileWriteText {
+                Request::FileReadStream {
                     path: PathBuf::from("path"),
-                    text: String::from("text"),
+                    offset: 0,
+                    len: None,
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::FileWriteText {
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "file_read_stream",
+                "path": "path",
+                "offset": 123,
+                "len": 456,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileReadStream {
+                    path: PathBuf::from("path"),
+                    offset: 123,
+                    len: Some(456),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::FileReadStream {
                 path: PathBuf::from("path"),
-                text: String::from("text"),
+                offset: 0,
+                len: None,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -581,38 +963,231 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_msgpack() {
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::FileReadStream {
+                path: PathBuf::from("path"),
+                offset: 123,
+                len: Some(456),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::FileWriteText {
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileReadStream {
                 path: PathBuf::from("path"),
-                text: String::from("text"),
+                offset: 0,
+                len: None,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::FileWriteText {
+                Request::FileReadStream {
                     path: PathBuf::from("path"),
-                    text: String::from("text"),
+                    offset: 0,
+                    len: None,
                 }
             );
         }
-    }
-
-    mod file_append {
-        use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_to_json() {
-            let payload = Request::FileAppend {
-                path: PathBuf::from("path"),
-                data: vec![0, 1, 2, u8::MAX],
-            };
-
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileReadStream {
+                path: PathBuf::from("path"),
+                offset: 123,
+                len: Some(456),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileReadStream {
+                    path: PathBuf::from("path"),
+                    offset: 123,
+                    len: Some(456),
+                }
+            );
+        }
+    }
+
+    mod file_write {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::FileWrite {
+                path: PathBuf::from("path"),
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "file_write",
+                    "path": "path",
+                    "data": [0, 1, 2, u8::MAX],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "file_write",
+                "path": "path",
+                "data": [0, 1, 2, u8::MAX],
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileWrite {
+                    path: PathBuf::from("path"),
+                    data: vec![0, 1, 2, u8::MAX],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::FileWrite {
+                path: PathBuf::from("path"),
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileWrite {
+                path: PathBuf::from("path"),
+                data: vec![0, 1, 2, u8::MAX],
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileWrite {
+                    path: PathBuf::from("path"),
+                    data: vec![0, 1, 2, u8::MAX],
+                }
+            );
+        }
+    }
+
+    mod file_write_text {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::FileWriteText {
+                path: PathBuf::from("path"),
+                text: String::from("text"),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "file_write_text",
+                    "path": "path",
+                    "text": "text",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "file_write_text",
+                "path": "path",
+                "text": "text",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileWriteText {
+                    path: PathBuf::from("path"),
+                    text: String::from("text"),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::FileWriteText {
+                path: PathBuf::from("path"),
+                text: String::from("text"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileWriteText {
+                path: PathBuf::from("path"),
+                text: String::from("text"),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileWriteText {
+                    path: PathBuf::from("path"),
+                    text: String::from("text"),
+                }
+            );
+        }
+    }
+
+    mod file_append {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::FileAppend {
+                path: PathBuf::from("path"),
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
@@ -755,49 +1330,50 @@ mod tests {
         }
     }
 
-    mod dir_read {
+    mod file_patch {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::DirRead {
+            let payload = Request::FilePatch {
                 path: PathBuf::from("path"),
-                depth: 1,
-                absolute: false,
-                canonicalize: false,
-                include_root: false,
+                len: 123,
+                blocks: Vec::new(),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "dir_read",
+                    "type": "file_patch",
                     "path": "path",
+                    "len": 123,
                 })
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::DirRead {
+            let payload = Request::FilePatch {
                 path: PathBuf::from("path"),
-                depth: usize::MAX,
-                absolute: true,
-                canonicalize: true,
-                include_root: true,
+                len: 123,
+                blocks: vec![FileBlock {
+                    offset: 4,
+                    data: vec![0, 1, 2, u8::MAX],
+                }],
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "dir_read",
+                    "type": "file_patch",
                     "path": "path",
-                    "depth": usize::MAX,
-                    "absolute": true,
-                    "canonicalize": true,
-                    "include_root": true,
+                    "len": 123,
+                    "blocks": [{
+                        "offset": 4,
+                        "data": [0, 1, 2, u8::MAX],
+                    }],
                 })
             );
         }
@@ -805,19 +1381,18 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "dir_read",
+                "type": "file_patch",
                 "path": "path",
+                "len": 123,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::DirRead {
+                Request::FilePatch {
                     path: PathBuf::from("path"),
-                    depth: 1,
-                    absolute: false,
-                    canonicalize: false,
-                    include_root: false,
+                    len: 123,
+                    blocks: Vec::new(),
                 }
             );
         }
@@ -825,35 +1400,35 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "dir_read",
+                "type": "file_patch",
                 "path": "path",
-                "depth": usize::MAX,
-                "absolute": true,
-                "canonicalize": true,
-                "include_root": true,
+                "len": 123,
+                "blocks": [{
+                    "offset": 4,
+                    "data": [0, 1, 2, u8::MAX],
+                }],
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::DirRead {
+                Request::FilePatch {
                     path: PathBuf::from("path"),
-                    depth: usize::MAX,
-                    absolute: true,
-                    canonicalize: true,
-                    include_root: true,
+                    len: 123,
+                    blocks: vec![FileBlock {
+                        offset: 4,
+                        data: vec![0, 1, 2, u8::MAX],
+                    }],
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::DirRead {
+            let payload = Request::FilePatch {
                 path: PathBuf::from("path"),
-                depth: 1,
-                absolute: false,
-                canonicalize: false,
-                include_root: false,
+                len: 123,
+                blocks: Vec::new(),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -865,12 +1440,13 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::DirRead {
+            let payload = Request::FilePatch {
                 path: PathBuf::from("path"),
-                depth: usize::MAX,
-                absolute: true,
-                canonicalize: true,
-                include_root: true,
+                len: 123,
+                blocks: vec![FileBlock {
+                    offset: 4,
+                    data: vec![0, 1, 2, u8::MAX],
+                }],
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -886,24 +1462,20 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::DirRead {
+            let buf = rmp_serde::encode::to_vec_named(&Request::FilePatch {
                 path: PathBuf::from("path"),
-                depth: 1,
-                absolute: false,
-                canonicalize: false,
-                include_root: false,
+                len: 123,
+                blocks: Vec::new(),
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::DirRead {
+                Request::FilePatch {
                     path: PathBuf::from("path"),
-                    depth: 1,
-                    absolute: false,
-                    canonicalize: false,
-                    include_root: false,
+                    len: 123,
+                    blocks: Vec::new(),
                 }
             );
         }
@@ -914,44 +1486,46 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::DirRead {
+            let buf = rmp_serde::encode::to_vec_named(&Request::FilePatch {
                 path: PathBuf::from("path"),
-                depth: usize::MAX,
-                absolute: true,
-                canonicalize: true,
-                include_root: true,
+                len: 123,
+                blocks: vec![FileBlock {
+                    offset: 4,
+                    data: vec![0, 1, 2, u8::MAX],
+                }],
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::DirRead {
+                Request::FilePatch {
                     path: PathBuf::from("path"),
-                    depth: usize::MAX,
-                    absolute: true,
-                    canonicalize: true,
-                    include_root: true,
+                    len: 123,
+                    blocks: vec![FileBlock {
+                        offset: 4,
+                        data: vec![0, 1, 2, u8::MAX],
+                    }],
                 }
             );
         }
     }
 
-    mod dir_create {
+    mod upload_open {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::DirCreate {
+            let payload = Request::UploadOpen {
                 path: PathBuf::from("path"),
-                all: false,
+                resume: false,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "dir_create",
+                    "type": "upload_open",
                     "path": "path",
                 })
             );
@@ -959,18 +1533,18 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::DirCreate {
+            let payload = Request::UploadOpen {
                 path: PathBuf::from("path"),
-                all: true,
+                resume: true,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "dir_create",
+                    "type": "upload_open",
                     "path": "path",
-                    "all": true,
+                    "resume": true,
                 })
             );
         }
@@ -978,16 +1552,16 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "dir_create",
+                "type": "upload_open",
                 "path": "path",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::DirCreate {
+                Request::UploadOpen {
                     path: PathBuf::from("path"),
-                    all: false,
+                    resume: false,
                 }
             );
         }
@@ -995,26 +1569,26 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "dir_create",
+                "type": "upload_open",
                 "path": "path",
-                "all": true,
+                "resume": true,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::DirCreate {
+                Request::UploadOpen {
                     path: PathBuf::from("path"),
-                    all: true,
+                    resume: true,
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::DirCreate {
+            let payload = Request::UploadOpen {
                 path: PathBuf::from("path"),
-                all: false,
+                resume: false,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1026,9 +1600,9 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::DirCreate {
+            let payload = Request::UploadOpen {
                 path: PathBuf::from("path"),
-                all: true,
+                resume: true,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1044,18 +1618,18 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::DirCreate {
+            let buf = rmp_serde::encode::to_vec_named(&Request::UploadOpen {
                 path: PathBuf::from("path"),
-                all: false,
+                resume: false,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::DirCreate {
+                Request::UploadOpen {
                     path: PathBuf::from("path"),
-                    all: false,
+                    resume: false,
                 }
             );
         }
@@ -1066,57 +1640,143 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::DirCreate {
+            let buf = rmp_serde::encode::to_vec_named(&Request::UploadOpen {
                 path: PathBuf::from("path"),
-                all: true,
+                resume: true,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::DirCreate {
+                Request::UploadOpen {
                     path: PathBuf::from("path"),
-                    all: true,
+                    resume: true,
                 }
             );
         }
     }
 
-    mod remove {
+    mod upload_write {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::UploadWrite {
+                id: 12345,
+                offset: 123,
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "upload_write",
+                    "id": 12345,
+                    "offset": 123,
+                    "data": [0, 1, 2, u8::MAX],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "upload_write",
+                "id": 12345,
+                "offset": 123,
+                "data": [0, 1, 2, u8::MAX],
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::UploadWrite {
+                    id: 12345,
+                    offset: 123,
+                    data: vec![0, 1, 2, u8::MAX],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::UploadWrite {
+                id: 12345,
+                offset: 123,
+                data: vec![0, 1, 2, u8::MAX],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::UploadWrite {
+                id: 12345,
+                offset: 123,
+                data: vec![0, 1, 2, u8::MAX],
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::UploadWrite {
+                    id: 12345,
+                    offset: 123,
+                    data: vec![0, 1, 2, u8::MAX],
+                }
+            );
+        }
+    }
+
+    mod upload_commit {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::Remove {
-                path: PathBuf::from("path"),
-                force: false,
+            let payload = Request::UploadCommit {
+                id: 12345,
+                checksum: None,
+                modified: None,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "remove",
-                    "path": "path",
+                    "type": "upload_commit",
+                    "id": 12345,
                 })
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::Remove {
-                path: PathBuf::from("path"),
-                force: true,
+            let payload = Request::UploadCommit {
+                id: 12345,
+                checksum: Some(String::from("abcdef")),
+                modified: Some(1234567890),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "remove",
-                    "path": "path",
-                    "force": true,
+                    "type": "upload_commit",
+                    "id": 12345,
+                    "checksum": "abcdef",
+                    "modified": 1234567890,
                 })
             );
         }
@@ -1124,16 +1784,17 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "remove",
-                "path": "path",
+                "type": "upload_commit",
+                "id": 12345,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Remove {
-                    path: PathBuf::from("path"),
-                    force: false,
+                Request::UploadCommit {
+                    id: 12345,
+                    checksum: None,
+                    modified: None,
                 }
             );
         }
@@ -1141,26 +1802,29 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "remove",
-                "path": "path",
-                "force": true,
+                "type": "upload_commit",
+                "id": 12345,
+                "checksum": "abcdef",
+                    "modified": 1234567890,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Remove {
-                    path: PathBuf::from("path"),
-                    force: true,
+                Request::UploadCommit {
+                    id: 12345,
+                    checksum: Some(String::from("abcdef")),
+                    modified: Some(1234567890),
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::Remove {
-                path: PathBuf::from("path"),
-                force: false,
+            let payload = Request::UploadCommit {
+                id: 12345,
+                checksum: None,
+                modified: None,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1172,9 +1836,10 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::Remove {
-                path: PathBuf::from("path"),
-                force: true,
+            let payload = Request::UploadCommit {
+                id: 12345,
+                checksum: Some(String::from("abcdef")),
+                modified: Some(1234567890),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1190,18 +1855,20 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Remove {
-                path: PathBuf::from("path"),
-                force: false,
+            let buf = rmp_serde::encode::to_vec_named(&Request::UploadCommit {
+                id: 12345,
+                checksum: None,
+                modified: None,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Remove {
-                    path: PathBuf::from("path"),
-                    force: false,
+                Request::UploadCommit {
+                    id: 12345,
+                    checksum: None,
+                    modified: None,
                 }
             );
         }
@@ -1212,40 +1879,38 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Remove {
-                path: PathBuf::from("path"),
-                force: true,
+            let buf = rmp_serde::encode::to_vec_named(&Request::UploadCommit {
+                id: 12345,
+                checksum: Some(String::from("abcdef")),
+                modified: Some(1234567890),
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Remove {
-                    path: PathBuf::from("path"),
-                    force: true,
+                Request::UploadCommit {
+                    id: 12345,
+                    checksum: Some(String::from("abcdef")),
+                    modified: Some(1234567890),
                 }
             );
         }
     }
 
-    mod copy {
+    mod upload_abort {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Request::Copy {
-                src: PathBuf::from("src"),
-                dst: PathBuf::from("dst"),
-            };
+            let payload = Request::UploadAbort { id: 12345 };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "copy",
-                    "src": "src",
-                    "dst": "dst",
+                    "type": "upload_abort",
+                    "id": 12345,
                 })
             );
         }
@@ -1253,27 +1918,17 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "copy",
-                "src": "src",
-                "dst": "dst",
+                "type": "upload_abort",
+                "id": 12345,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Request::Copy {
-                    src: PathBuf::from("src"),
-                    dst: PathBuf::from("dst"),
-                }
-            );
+            assert_eq!(payload, Request::UploadAbort { id: 12345 });
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::Copy {
-                src: PathBuf::from("src"),
-                dst: PathBuf::from("dst"),
-            };
+            let payload = Request::UploadAbort { id: 12345 };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -1288,67 +1943,112 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Copy {
-                src: PathBuf::from("src"),
-                dst: PathBuf::from("dst"),
-            })
-            .unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Request::UploadAbort { id: 12345 }).unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(
-                payload,
-                Request::Copy {
-                    src: PathBuf::from("src"),
-                    dst: PathBuf::from("dst"),
-                }
-            );
+            assert_eq!(payload, Request::UploadAbort { id: 12345 });
         }
     }
 
-    mod rename {
+    mod dir_read {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_to_json() {
-            let payload = Request::Rename {
-                src: PathBuf::from("src"),
-                dst: PathBuf::from("dst"),
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::DirRead {
+                path: PathBuf::from("path"),
+                depth: 1,
+                absolute: false,
+                canonicalize: false,
+                include_root: false,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "rename",
-                    "src": "src",
-                    "dst": "dst",
+                    "type": "dir_read",
+                    "path": "path",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_json() {
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::DirRead {
+                path: PathBuf::from("path"),
+                depth: usize::MAX,
+                absolute: true,
+                canonicalize: true,
+                include_root: true,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "dir_read",
+                    "path": "path",
+                    "depth": usize::MAX,
+                    "absolute": true,
+                    "canonicalize": true,
+                    "include_root": true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "rename",
-                "src": "src",
-                "dst": "dst",
+                "type": "dir_read",
+                "path": "path",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Rename {
-                    src: PathBuf::from("src"),
-                    dst: PathBuf::from("dst"),
+                Request::DirRead {
+                    path: PathBuf::from("path"),
+                    depth: 1,
+                    absolute: false,
+                    canonicalize: false,
+                    include_root: false,
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::Rename {
-                src: PathBuf::from("src"),
-                dst: PathBuf::from("dst"),
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "dir_read",
+                "path": "path",
+                "depth": usize::MAX,
+                "absolute": true,
+                "canonicalize": true,
+                "include_root": true,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::DirRead {
+                    path: PathBuf::from("path"),
+                    depth: usize::MAX,
+                    absolute: true,
+                    canonicalize: true,
+                    include_root: true,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::DirRead {
+                path: PathBuf::from("path"),
+                depth: 1,
+                absolute: false,
+                canonicalize: false,
+                include_root: false,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1359,45 +2059,94 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_msgpack() {
-            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
-            // verify that we are not corrupting or causing issues when serializing on a
-            // client/server and then trying to deserialize on the other side. This has happened
-            // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Rename {
-                src: PathBuf::from("src"),
-                dst: PathBuf::from("dst"),
-            })
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::DirRead {
+                path: PathBuf::from("path"),
+                depth: usize::MAX,
+                absolute: true,
+                canonicalize: true,
+                include_root: true,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::DirRead {
+                path: PathBuf::from("path"),
+                depth: 1,
+                absolute: false,
+                canonicalize: false,
+                include_root: false,
+            })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Rename {
-                    src: PathBuf::from("src"),
-                    dst: PathBuf::from("dst"),
+                Request::DirRead {
+                    path: PathBuf::from("path"),
+                    depth: 1,
+                    absolute: false,
+                    canonicalize: false,
+                    include_root: false,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::DirRead {
+                path: PathBuf::from("path"),
+                depth: usize::MAX,
+                absolute: true,
+                canonicalize: true,
+                include_root: true,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::DirRead {
+                    path: PathBuf::from("path"),
+                    depth: usize::MAX,
+                    absolute: true,
+                    canonicalize: true,
+                    include_root: true,
                 }
             );
         }
     }
 
-    mod watch {
+    mod dir_create {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::Watch {
+            let payload = Request::DirCreate {
                 path: PathBuf::from("path"),
-                recursive: false,
-                only: Vec::new(),
-                except: Vec::new(),
+                all: false,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "watch",
+                    "type": "dir_create",
                     "path": "path",
                 })
             );
@@ -1405,22 +2154,18 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::Watch {
+            let payload = Request::DirCreate {
                 path: PathBuf::from("path"),
-                recursive: true,
-                only: vec![ChangeKind::Access],
-                except: vec![ChangeKind::Modify],
+                all: true,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "watch",
+                    "type": "dir_create",
                     "path": "path",
-                    "recursive": true,
-                    "only": ["access"],
-                    "except": ["modify"],
+                    "all": true,
                 })
             );
         }
@@ -1428,18 +2173,16 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "watch",
+                "type": "dir_create",
                 "path": "path",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Watch {
+                Request::DirCreate {
                     path: PathBuf::from("path"),
-                    recursive: false,
-                    only: Vec::new(),
-                    except: Vec::new(),
+                    all: false,
                 }
             );
         }
@@ -1447,32 +2190,26 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "watch",
+                "type": "dir_create",
                 "path": "path",
-                "recursive": true,
-                "only": ["access"],
-                "except": ["modify"],
+                "all": true,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Watch {
+                Request::DirCreate {
                     path: PathBuf::from("path"),
-                    recursive: true,
-                    only: vec![ChangeKind::Access],
-                    except: vec![ChangeKind::Modify],
+                    all: true,
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::Watch {
+            let payload = Request::DirCreate {
                 path: PathBuf::from("path"),
-                recursive: false,
-                only: Vec::new(),
-                except: Vec::new(),
+                all: false,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1484,11 +2221,9 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::Watch {
+            let payload = Request::DirCreate {
                 path: PathBuf::from("path"),
-                recursive: true,
-                only: vec![ChangeKind::Access],
-                except: vec![ChangeKind::Modify],
+                all: true,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1504,22 +2239,18 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Watch {
+            let buf = rmp_serde::encode::to_vec_named(&Request::DirCreate {
                 path: PathBuf::from("path"),
-                recursive: false,
-                only: Vec::new(),
-                except: Vec::new(),
+                all: false,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Watch {
+                Request::DirCreate {
                     path: PathBuf::from("path"),
-                    recursive: false,
-                    only: Vec::new(),
-                    except: Vec::new(),
+                    all: false,
                 }
             );
         }
@@ -1530,66 +2261,101 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Watch {
+            let buf = rmp_serde::encode::to_vec_named(&Request::DirCreate {
                 path: PathBuf::from("path"),
-                recursive: true,
-                only: vec![ChangeKind::Access],
-                except: vec![ChangeKind::Modify],
+                all: true,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Watch {
+                Request::DirCreate {
                     path: PathBuf::from("path"),
-                    recursive: true,
-                    only: vec![ChangeKind::Access],
-                    except: vec![ChangeKind::Modify],
+                    all: true,
                 }
             );
         }
     }
 
-    mod unwatch {
+    mod remove {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_to_json() {
-            let payload = Request::Unwatch {
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::Remove {
                 path: PathBuf::from("path"),
+                force: false,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "unwatch",
+                    "type": "remove",
                     "path": "path",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_json() {
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::Remove {
+                path: PathBuf::from("path"),
+                force: true,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "remove",
+                    "path": "path",
+                    "force": true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "unwatch",
+                "type": "remove",
                 "path": "path",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Unwatch {
-                    path: PathBuf::from("path")
+                Request::Remove {
+                    path: PathBuf::from("path"),
+                    force: false,
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::Unwatch {
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "remove",
+                "path": "path",
+                "force": true,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Remove {
+                    path: PathBuf::from("path"),
+                    force: true,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::Remove {
                 path: PathBuf::from("path"),
+                force: false,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1600,41 +2366,81 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_msgpack() {
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::Remove {
+                path: PathBuf::from("path"),
+                force: true,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Unwatch {
+            let buf = rmp_serde::encode::to_vec_named(&Request::Remove {
                 path: PathBuf::from("path"),
+                force: false,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Unwatch {
+                Request::Remove {
                     path: PathBuf::from("path"),
+                    force: false,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Remove {
+                path: PathBuf::from("path"),
+                force: true,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Remove {
+                    path: PathBuf::from("path"),
+                    force: true,
                 }
             );
         }
     }
 
-    mod exists {
+    mod copy {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Request::Exists {
-                path: PathBuf::from("path"),
+            let payload = Request::Copy {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "exists",
-                    "path": "path",
+                    "type": "copy",
+                    "src": "src",
+                    "dst": "dst",
                 })
             );
         }
@@ -1642,23 +2448,26 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "exists",
-                "path": "path",
+                "type": "copy",
+                "src": "src",
+                "dst": "dst",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Exists {
-                    path: PathBuf::from("path")
+                Request::Copy {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::Exists {
-                path: PathBuf::from("path"),
+            let payload = Request::Copy {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1674,121 +2483,67 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Exists {
-                path: PathBuf::from("path"),
+            let buf = rmp_serde::encode::to_vec_named(&Request::Copy {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Exists {
-                    path: PathBuf::from("path"),
+                Request::Copy {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 }
             );
         }
     }
 
-    mod metadata {
+    mod rename {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::Metadata {
-                path: PathBuf::from("path"),
-                canonicalize: false,
-                resolve_file_type: false,
-            };
-
-            let value = serde_json::to_value(payload).unwrap();
-            assert_eq!(
-                value,
-                serde_json::json!({
-                    "type": "metadata",
-                    "path": "path",
-                })
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::Metadata {
-                path: PathBuf::from("path"),
-                canonicalize: true,
-                resolve_file_type: true,
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::Rename {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "metadata",
-                    "path": "path",
-                    "canonicalize": true,
-                    "resolve_file_type": true,
+                    "type": "rename",
+                    "src": "src",
+                    "dst": "dst",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_json() {
-            let value = serde_json::json!({
-                "type": "metadata",
-                "path": "path",
-            });
-
-            let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Request::Metadata {
-                    path: PathBuf::from("path"),
-                    canonicalize: false,
-                    resolve_file_type: false,
-                }
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_deserialize_full_payload_from_json() {
+        fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "metadata",
-                "path": "path",
-                "canonicalize": true,
-                "resolve_file_type": true,
+                "type": "rename",
+                "src": "src",
+                "dst": "dst",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Metadata {
-                    path: PathBuf::from("path"),
-                    canonicalize: true,
-                    resolve_file_type: true,
+                Request::Rename {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::Metadata {
-                path: PathBuf::from("path"),
-                canonicalize: false,
-                resolve_file_type: false,
-            };
-
-            // NOTE: We don't actually check the output here because it's an implementation detail
-            // and could change as we change how serialization is done. This is merely to verify
-            // that we can serialize since there are times when serde fails to serialize at
-            // runtime.
-            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
-        }
-
-        #[test]
-        fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::Metadata {
-                path: PathBuf::from("path"),
-                canonicalize: true,
-                resolve_file_type: true,
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::Rename {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1799,129 +2554,293 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Metadata {
-                path: PathBuf::from("path"),
-                canonicalize: false,
-                resolve_file_type: false,
+            let buf = rmp_serde::encode::to_vec_named(&Request::Rename {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Metadata {
-                    path: PathBuf::from("path"),
-                    canonicalize: false,
-                    resolve_file_type: false,
+                Request::Rename {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 }
             );
         }
+    }
+
+    mod symlink {
+        use super::*;
 
         #[test]
-        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::Symlink {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "symlink",
+                    "src": "src",
+                    "dst": "dst",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "symlink",
+                "src": "src",
+                "dst": "dst",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Symlink {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::Symlink {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Metadata {
-                path: PathBuf::from("path"),
-                canonicalize: true,
-                resolve_file_type: true,
+            let buf = rmp_serde::encode::to_vec_named(&Request::Symlink {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Metadata {
-                    path: PathBuf::from("path"),
-                    canonicalize: true,
-                    resolve_file_type: true,
+                Request::Symlink {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 }
             );
         }
     }
 
-    mod set_permissions {
+    mod hard_link {
         use super::*;
 
-        const fn full_permissions() -> Permissions {
-            Permissions {
-                owner_read: Some(true),
-                owner_write: Some(true),
-                owner_exec: Some(true),
-                group_read: Some(true),
-                group_write: Some(true),
-                group_exec: Some(true),
-                other_read: Some(true),
-                other_write: Some(true),
-                other_exec: Some(true),
-            }
-        }
-
-        const fn full_options() -> SetPermissionsOptions {
-            SetPermissionsOptions {
-                exclude_symlinks: true,
-                follow_symlinks: true,
-                recursive: true,
-            }
-        }
-
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::SetPermissions {
-                path: PathBuf::from("path"),
-                permissions: Default::default(),
-                options: Default::default(),
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::HardLink {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "set_permissions",
-                    "path": "path",
-                    "permissions": {},
-                    "options": {},
+                    "type": "hard_link",
+                    "src": "src",
+                    "dst": "dst",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::SetPermissions {
-                path: PathBuf::from("path"),
-                permissions: full_permissions(),
-                options: full_options(),
-            };
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "hard_link",
+                "src": "src",
+                "dst": "dst",
+            });
 
-            let value = serde_json::to_value(payload).unwrap();
+            let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
-                value,
-                serde_json::json!({
-                    "type": "set_permissions",
-                    "path": "path",
-                    "permissions": {
-                        "owner_read": true,
-                        "owner_write": true,
-                        "owner_exec": true,
-                        "group_read": true,
-                        "group_write": true,
-                        "group_exec": true,
-                        "other_read": true,
-                        "other_write": true,
-                        "other_exec": true,
-                    },
-                    "options": {
-                        "exclude_symlinks": true,
-                        "follow_symlinks": true,
-                        "recursive": true,
-                    },
+                payload,
+                Request::HardLink {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::HardLink {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::HardLink {
+                src: PathBuf::from("src"),
+                dst: PathBuf::from("dst"),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::HardLink {
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
+                }
+            );
+        }
+    }
+
+    mod read_link {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::ReadLink {
+                path: PathBuf::from("path"),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "read_link",
+                    "path": "path",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "read_link",
+                "path": "path",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::ReadLink {
+                    path: PathBuf::from("path"),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::ReadLink {
+                path: PathBuf::from("path"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::ReadLink {
+                path: PathBuf::from("path"),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::ReadLink {
+                    path: PathBuf::from("path"),
+                }
+            );
+        }
+    }
+
+    mod hash {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::Hash {
+                path: PathBuf::from("path"),
+                algorithm: HashAlgorithm::Sha256,
+                offset: 0,
+                len: None,
+                recursive: false,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "hash",
+                    "path": "path",
+                    "algorithm": "sha256",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::Hash {
+                path: PathBuf::from("path"),
+                algorithm: HashAlgorithm::Blake3,
+                offset: 123,
+                len: Some(456),
+                recursive: true,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "hash",
+                    "path": "path",
+                    "algorithm": "blake3",
+                    "offset": 123,
+                    "len": 456,
+                    "recursive": true,
                 })
             );
         }
@@ -1929,19 +2848,20 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "set_permissions",
+                "type": "hash",
                 "path": "path",
-                "permissions": {},
-                "options": {},
+                "algorithm": "sha256",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::SetPermissions {
+                Request::Hash {
                     path: PathBuf::from("path"),
-                    permissions: Default::default(),
-                    options: Default::default(),
+                    algorithm: HashAlgorithm::Sha256,
+                    offset: 0,
+                    len: None,
+                    recursive: false,
                 }
             );
         }
@@ -1949,43 +2869,35 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "set_permissions",
+                "type": "hash",
                 "path": "path",
-                "permissions": {
-                    "owner_read": true,
-                    "owner_write": true,
-                    "owner_exec": true,
-                    "group_read": true,
-                    "group_write": true,
-                    "group_exec": true,
-                    "other_read": true,
-                    "other_write": true,
-                    "other_exec": true,
-                },
-                "options": {
-                    "exclude_symlinks": true,
-                    "follow_symlinks": true,
-                    "recursive": true,
-                },
+                "algorithm": "blake3",
+                "offset": 123,
+                "len": 456,
+                "recursive": true,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::SetPermissions {
+                Request::Hash {
                     path: PathBuf::from("path"),
-                    permissions: full_permissions(),
-                    options: full_options(),
+                    algorithm: HashAlgorithm::Blake3,
+                    offset: 123,
+                    len: Some(456),
+                    recursive: true,
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::SetPermissions {
+            let payload = Request::Hash {
                 path: PathBuf::from("path"),
-                permissions: Default::default(),
-                options: Default::default(),
+                algorithm: HashAlgorithm::Sha256,
+                offset: 0,
+                len: None,
+                recursive: false,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1997,10 +2909,12 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::SetPermissions {
+            let payload = Request::Hash {
                 path: PathBuf::from("path"),
-                permissions: full_permissions(),
-                options: full_options(),
+                algorithm: HashAlgorithm::Blake3,
+                offset: 123,
+                len: Some(456),
+                recursive: true,
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -2016,20 +2930,24 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::SetPermissions {
+            let buf = rmp_serde::encode::to_vec_named(&Request::Hash {
                 path: PathBuf::from("path"),
-                permissions: Default::default(),
-                options: Default::default(),
+                algorithm: HashAlgorithm::Sha256,
+                offset: 0,
+                len: None,
+                recursive: false,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::SetPermissions {
+                Request::Hash {
                     path: PathBuf::from("path"),
-                    permissions: Default::default(),
-                    options: Default::default(),
+                    algorithm: HashAlgorithm::Sha256,
+                    offset: 0,
+                    len: None,
+                    recursive: false,
                 }
             );
         }
@@ -2040,113 +2958,152 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::SetPermissions {
+            let buf = rmp_serde::encode::to_vec_named(&Request::Hash {
                 path: PathBuf::from("path"),
-                permissions: full_permissions(),
-                options: full_options(),
+                algorithm: HashAlgorithm::Blake3,
+                offset: 123,
+                len: Some(456),
+                recursive: true,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::SetPermissions {
+                Request::Hash {
                     path: PathBuf::from("path"),
-                    permissions: full_permissions(),
-                    options: full_options(),
+                    algorithm: HashAlgorithm::Blake3,
+                    offset: 123,
+                    len: Some(456),
+                    recursive: true,
                 }
             );
         }
     }
 
-    mod search {
+    mod file_block_checksums {
         use super::*;
-        use crate::common::{
-            FileType, SearchQueryCondition, SearchQueryOptions, SearchQueryTarget,
-        };
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::Search {
-                query: SearchQuery {
-                    target: SearchQueryTarget::Contents,
-                    condition: SearchQueryCondition::equals("hello world"),
-                    paths: vec![PathBuf::from("path")],
-                    options: Default::default(),
-                },
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::FileBlockChecksums {
+                path: PathBuf::from("path"),
+                algorithm: HashAlgorithm::Sha256,
+                block_size: 1024,
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "search",
-                    "query": {
-                        "target": "contents",
-                        "condition": {
-                            "type": "equals",
-                            "value": "hello world",
-                        },
-                        "paths": ["path"],
-                        "options": {},
-                    },
+                    "type": "file_block_checksums",
+                    "path": "path",
+                    "algorithm": "sha256",
+                    "block_size": 1024,
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::Search {
-                query: SearchQuery {
-                    target: SearchQueryTarget::Contents,
-                    condition: SearchQueryCondition::equals("hello world"),
-                    paths: vec![PathBuf::from("path")],
-                    options: SearchQueryOptions {
-                        allowed_file_types: [FileType::File].into_iter().collect(),
-                        include: Some(SearchQueryCondition::Equals {
-                            value: String::from("hello"),
-                        }),
-                        exclude: Some(SearchQueryCondition::Contains {
-                            value: String::from("world"),
-                        }),
-                        upward: true,
-                        follow_symbolic_links: true,
-                        limit: Some(u64::MAX),
-                        max_depth: Some(u64::MAX),
-                        pagination: Some(u64::MAX),
-                    },
-                },
-            };
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "file_block_checksums",
+                "path": "path",
+                "algorithm": "sha256",
+                "block_size": 1024,
+            });
 
-            let value = serde_json::to_value(payload).unwrap();
+            let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
-                value,
-                serde_json::json!({
-                    "type": "search",
-                    "query": {
-                        "target": "contents",
-                        "condition": {
-                            "type": "equals",
-                            "value": "hello world",
-                        },
-                        "paths": ["path"],
-                        "options": {
-                            "allowed_file_types": ["file"],
-                            "include": {
-                                "type": "equals",
-                                "value": "hello",
-                            },
-                            "exclude": {
-                                "type": "contains",
-                                "value": "world",
-                            },
-                            "upward": true,
-                            "follow_symbolic_links": true,
-                            "limit": u64::MAX,
-                            "max_depth": u64::MAX,
-                            "pagination": u64::MAX,
-                        },
-                    },
+                payload,
+                Request::FileBlockChecksums {
+                    path: PathBuf::from("path"),
+                    algorithm: HashAlgorithm::Sha256,
+                    block_size: 1024,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::FileBlockChecksums {
+                path: PathBuf::from("path"),
+                algorithm: HashAlgorithm::Sha256,
+                block_size: 1024,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::FileBlockChecksums {
+                path: PathBuf::from("path"),
+                algorithm: HashAlgorithm::Sha256,
+                block_size: 1024,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::FileBlockChecksums {
+                    path: PathBuf::from("path"),
+                    algorithm: HashAlgorithm::Sha256,
+                    block_size: 1024,
+                }
+            );
+        }
+    }
+
+    mod watch {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::Watch {
+                path: PathBuf::from("path"),
+                recursive: false,
+                only: Vec::new(),
+                except: Vec::new(),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "watch",
+                    "path": "path",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::Watch {
+                path: PathBuf::from("path"),
+                recursive: true,
+                only: vec![ChangeKind::Access],
+                except: vec![ChangeKind::Modify],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "watch",
+                    "path": "path",
+                    "recursive": true,
+                    "only": ["access"],
+                    "except": ["modify"],
                 })
             );
         }
@@ -2154,27 +3111,18 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "search",
-                "query": {
-                    "target": "contents",
-                    "condition": {
-                        "type": "equals",
-                        "value": "hello world",
-                    },
-                    "paths": ["path"],
-                },
+                "type": "watch",
+                "path": "path",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Search {
-                    query: SearchQuery {
-                        target: SearchQueryTarget::Contents,
-                        condition: SearchQueryCondition::equals("hello world"),
-                        paths: vec![PathBuf::from("path")],
-                        options: Default::default(),
-                    },
+                Request::Watch {
+                    path: PathBuf::from("path"),
+                    recursive: false,
+                    only: Vec::new(),
+                    except: Vec::new(),
                 }
             );
         }
@@ -2182,69 +3130,32 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "search",
-                "query": {
-                    "target": "contents",
-                    "condition": {
-                        "type": "equals",
-                        "value": "hello world",
-                    },
-                    "paths": ["path"],
-                    "options": {
-                        "allowed_file_types": ["file"],
-                        "include": {
-                            "type": "equals",
-                            "value": "hello",
-                        },
-                        "exclude": {
-                            "type": "contains",
-                            "value": "world",
-                        },
-                        "upward": true,
-                        "follow_symbolic_links": true,
-                        "limit": u64::MAX,
-                        "max_depth": u64::MAX,
-                        "pagination": u64::MAX,
-                    },
-                },
+                "type": "watch",
+                "path": "path",
+                "recursive": true,
+                "only": ["access"],
+                "except": ["modify"],
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::Search {
-                    query: SearchQuery {
-                        target: SearchQueryTarget::Contents,
-                        condition: SearchQueryCondition::equals("hello world"),
-                        paths: vec![PathBuf::from("path")],
-                        options: SearchQueryOptions {
-                            allowed_file_types: [FileType::File].into_iter().collect(),
-                            include: Some(SearchQueryCondition::Equals {
-                                value: String::from("hello"),
-                            }),
-                            exclude: Some(SearchQueryCondition::Contains {
-                                value: String::from("world"),
-                            }),
-                            upward: true,
-                            follow_symbolic_links: true,
-                            limit: Some(u64::MAX),
-                            max_depth: Some(u64::MAX),
-                            pagination: Some(u64::MAX),
-                        },
-                    },
+                Request::Watch {
+                    path: PathBuf::from("path"),
+                    recursive: true,
+                    only: vec![ChangeKind::Access],
+                    except: vec![ChangeKind::Modify],
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::Search {
-                query: SearchQuery {
-                    target: SearchQueryTarget::Contents,
-                    condition: SearchQueryCondition::equals("hello world"),
-                    paths: vec![PathBuf::from("path")],
-                    options: Default::default(),
-                },
+            let payload = Request::Watch {
+                path: PathBuf::from("path"),
+                recursive: false,
+                only: Vec::new(),
+                except: Vec::new(),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -2256,25 +3167,1741 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::Search {
-                query: SearchQuery {
-                    target: SearchQueryTarget::Contents,
-                    condition: SearchQueryCondition::equals("hello world"),
-                    paths: vec![PathBuf::from("path")],
-                    options: SearchQueryOptions {
-                        allowed_file_types: [FileType::File].into_iter().collect(),
-                        include: Some(SearchQueryCondition::Equals {
-                            value: String::from("hello"),
-                        }),
-                        exclude: Some(SearchQueryCondition::Contains {
-                            value: String::from("world"),
-                        }),
-                        upward: true,
-                        follow_symbolic_links: true,
-                        limit: Some(u64::MAX),
-                        max_depth: Some(u64::MAX),
-                        pagination: Some(u64::MAX),
+            let payload = Request::Watch {
+                path: PathBuf::from("path"),
+                recursive: true,
+                only: vec![ChangeKind::Access],
+                except: vec![ChangeKind::Modify],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Watch {
+                path: PathBuf::from("path"),
+                recursive: false,
+                only: Vec::new(),
+                except: Vec::new(),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Watch {
+                    path: PathBuf::from("path"),
+                    recursive: false,
+                    only: Vec::new(),
+                    except: Vec::new(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Watch {
+                path: PathBuf::from("path"),
+                recursive: true,
+                only: vec![ChangeKind::Access],
+                except: vec![ChangeKind::Modify],
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Watch {
+                    path: PathBuf::from("path"),
+                    recursive: true,
+                    only: vec![ChangeKind::Access],
+                    except: vec![ChangeKind::Modify],
+                }
+            );
+        }
+    }
+
+    mod unwatch {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::Unwatch {
+                path: PathBuf::from("path"),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "unwatch",
+                    "path": "path",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "unwatch",
+                "path": "path",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Unwatch {
+                    path: PathBuf::from("path")
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::Unwatch {
+                path: PathBuf::from("path"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Unwatch {
+                path: PathBuf::from("path"),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Unwatch {
+                    path: PathBuf::from("path"),
+                }
+            );
+        }
+    }
+
+    mod exists {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::Exists {
+                path: PathBuf::from("path"),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "exists",
+                    "path": "path",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "exists",
+                "path": "path",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Exists {
+                    path: PathBuf::from("path")
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::Exists {
+                path: PathBuf::from("path"),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Exists {
+                path: PathBuf::from("path"),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Exists {
+                    path: PathBuf::from("path"),
+                }
+            );
+        }
+    }
+
+    mod metadata {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::Metadata {
+                path: PathBuf::from("path"),
+                canonicalize: false,
+                resolve_file_type: false,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "metadata",
+                    "path": "path",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::Metadata {
+                path: PathBuf::from("path"),
+                canonicalize: true,
+                resolve_file_type: true,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "metadata",
+                    "path": "path",
+                    "canonicalize": true,
+                    "resolve_file_type": true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "metadata",
+                "path": "path",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Metadata {
+                    path: PathBuf::from("path"),
+                    canonicalize: false,
+                    resolve_file_type: false,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "metadata",
+                "path": "path",
+                "canonicalize": true,
+                "resolve_file_type": true,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Metadata {
+                    path: PathBuf::from("path"),
+                    canonicalize: true,
+                    resolve_file_type: true,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::Metadata {
+                path: PathBuf::from("path"),
+                canonicalize: false,
+                resolve_file_type: false,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::Metadata {
+                path: PathBuf::from("path"),
+                canonicalize: true,
+                resolve_file_type: true,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Metadata {
+                path: PathBuf::from("path"),
+                canonicalize: false,
+                resolve_file_type: false,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Metadata {
+                    path: PathBuf::from("path"),
+                    canonicalize: false,
+                    resolve_file_type: false,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Metadata {
+                path: PathBuf::from("path"),
+                canonicalize: true,
+                resolve_file_type: true,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Metadata {
+                    path: PathBuf::from("path"),
+                    canonicalize: true,
+                    resolve_file_type: true,
+                }
+            );
+        }
+    }
+
+    mod set_permissions {
+        use super::*;
+
+        const fn full_permissions() -> Permissions {
+            Permissions {
+                owner_read: Some(true),
+                owner_write: Some(true),
+                owner_exec: Some(true),
+                group_read: Some(true),
+                group_write: Some(true),
+                group_exec: Some(true),
+                other_read: Some(true),
+                other_write: Some(true),
+                other_exec: Some(true),
+            }
+        }
+
+        const fn full_options() -> SetPermissionsOptions {
+            SetPermissionsOptions {
+                exclude_symlinks: true,
+                follow_symlinks: true,
+                recursive: true,
+            }
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::SetPermissions {
+                path: PathBuf::from("path"),
+                permissions: Default::default(),
+                options: Default::default(),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "set_permissions",
+                    "path": "path",
+                    "permissions": {},
+                    "options": {},
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::SetPermissions {
+                path: PathBuf::from("path"),
+                permissions: full_permissions(),
+                options: full_options(),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "set_permissions",
+                    "path": "path",
+                    "permissions": {
+                        "owner_read": true,
+                        "owner_write": true,
+                        "owner_exec": true,
+                        "group_read": true,
+                        "group_write": true,
+                        "group_exec": true,
+                        "other_read": true,
+                        "other_write": true,
+                        "other_exec": true,
+                    },
+                    "options": {
+                        "exclude_symlinks": true,
+                        "follow_symlinks": true,
+                        "recursive": true,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "set_permissions",
+                "path": "path",
+                "permissions": {},
+                "options": {},
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::SetPermissions {
+                    path: PathBuf::from("path"),
+                    permissions: Default::default(),
+                    options: Default::default(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "set_permissions",
+                "path": "path",
+                "permissions": {
+                    "owner_read": true,
+                    "owner_write": true,
+                    "owner_exec": true,
+                    "group_read": true,
+                    "group_write": true,
+                    "group_exec": true,
+                    "other_read": true,
+                    "other_write": true,
+                    "other_exec": true,
+                },
+                "options": {
+                    "exclude_symlinks": true,
+                    "follow_symlinks": true,
+                    "recursive": true,
+                },
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::SetPermissions {
+                    path: PathBuf::from("path"),
+                    permissions: full_permissions(),
+                    options: full_options(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::SetPermissions {
+                path: PathBuf::from("path"),
+                permissions: Default::default(),
+                options: Default::default(),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::SetPermissions {
+                path: PathBuf::from("path"),
+                permissions: full_permissions(),
+                options: full_options(),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::SetPermissions {
+                path: PathBuf::from("path"),
+                permissions: Default::default(),
+                options: Default::default(),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::SetPermissions {
+                    path: PathBuf::from("path"),
+                    permissions: Default::default(),
+                    options: Default::default(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::SetPermissions {
+                path: PathBuf::from("path"),
+                permissions: full_permissions(),
+                options: full_options(),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::SetPermissions {
+                    path: PathBuf::from("path"),
+                    permissions: full_permissions(),
+                    options: full_options(),
+                }
+            );
+        }
+    }
+
+    mod search {
+        use super::*;
+        use crate::common::{
+            FileType, SearchQueryCondition, SearchQueryOptions, SearchQueryTarget,
+        };
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::Search {
+                query: SearchQuery {
+                    target: SearchQueryTarget::Contents,
+                    condition: SearchQueryCondition::equals("hello world"),
+                    paths: vec![PathBuf::from("path")],
+                    options: Default::default(),
+                },
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "search",
+                    "query": {
+                        "target": "contents",
+                        "condition": {
+                            "type": "equals",
+                            "value": "hello world",
+                        },
+                        "paths": ["path"],
+                        "options": {},
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::Search {
+                query: SearchQuery {
+                    target: SearchQueryTarget::Contents,
+                    condition: SearchQueryCondition::equals("hello world"),
+                    paths: vec![PathBuf::from("path")],
+                    options: SearchQueryOptions {
+                        allowed_file_types: [FileType::File].into_iter().collect(),
+                        include: Some(SearchQueryCondition::Equals {
+                            value: String::from("hello"),
+                        }),
+                        exclude: Some(SearchQueryCondition::Contains {
+                            value: String::from("world"),
+                        }),
+                        upward: true,
+                        follow_symbolic_links: true,
+                        limit: Some(u64::MAX),
+                        max_depth: Some(u64::MAX),
+                        pagination: Some(u64::MAX),
+                        min_size: Some(u64::MAX),
+                        max_size: Some(u64::MAX),
+                        modified_after: Some(u64::MAX),
+                        modified_before: Some(u64::MAX),
+                        hidden: true,
+                        no_ignore: true,
+                        ignore_files: vec![PathBuf::from("path")],
+                        before_context: Some(u64::MAX),
+                        after_context: Some(u64::MAX),
+                        multiline: true,
+                    },
+                },
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "search",
+                    "query": {
+                        "target": "contents",
+                        "condition": {
+                            "type": "equals",
+                            "value": "hello world",
+                        },
+                        "paths": ["path"],
+                        "options": {
+                            "allowed_file_types": ["file"],
+                            "include": {
+                                "type": "equals",
+                                "value": "hello",
+                            },
+                            "exclude": {
+                                "type": "contains",
+                                "value": "world",
+                            },
+                            "upward": true,
+                            "follow_symbolic_links": true,
+                            "limit": u64::MAX,
+                            "max_depth": u64::MAX,
+                            "pagination": u64::MAX,
+                            "min_size": u64::MAX,
+                            "max_size": u64::MAX,
+                            "modified_after": u64::MAX,
+                            "modified_before": u64::MAX,
+                            "hidden": true,
+                            "no_ignore": true,
+                            "ignore_files": ["path"],
+                            "before_context": u64::MAX,
+                            "after_context": u64::MAX,
+                            "multiline": true,
+                        },
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "search",
+                "query": {
+                    "target": "contents",
+                    "condition": {
+                        "type": "equals",
+                        "value": "hello world",
+                    },
+                    "paths": ["path"],
+                },
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Search {
+                    query: SearchQuery {
+                        target: SearchQueryTarget::Contents,
+                        condition: SearchQueryCondition::equals("hello world"),
+                        paths: vec![PathBuf::from("path")],
+                        options: Default::default(),
+                    },
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "search",
+                "query": {
+                    "target": "contents",
+                    "condition": {
+                        "type": "equals",
+                        "value": "hello world",
+                    },
+                    "paths": ["path"],
+                    "options": {
+                        "allowed_file_types": ["file"],
+                        "include": {
+                            "type": "equals",
+                            "value": "hello",
+                        },
+                        "exclude": {
+                            "type": "contains",
+                            "value": "world",
+                        },
+                        "upward": true,
+                        "follow_symbolic_links": true,
+                        "limit": u64::MAX,
+                        "max_depth": u64::MAX,
+                        "pagination": u64::MAX,
+                        "min_size": u64::MAX,
+                        "max_size": u64::MAX,
+                        "modified_after": u64::MAX,
+                        "modified_before": u64::MAX,
+                        "hidden": true,
+                        "no_ignore": true,
+                        "ignore_files": ["path"],
+                        "before_context": u64::MAX,
+                        "after_context": u64::MAX,
+                        "multiline": true,
+                    },
+                },
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Search {
+                    query: SearchQuery {
+                        target: SearchQueryTarget::Contents,
+                        condition: SearchQueryCondition::equals("hello world"),
+                        paths: vec![PathBuf::from("path")],
+                        options: SearchQueryOptions {
+                            allowed_file_types: [FileType::File].into_iter().collect(),
+                            include: Some(SearchQueryCondition::Equals {
+                                value: String::from("hello"),
+                            }),
+                            exclude: Some(SearchQueryCondition::Contains {
+                                value: String::from("world"),
+                            }),
+                            upward: true,
+                            follow_symbolic_links: true,
+                            limit: Some(u64::MAX),
+                            max_depth: Some(u64::MAX),
+                            pagination: Some(u64::MAX),
+                            min_size: Some(u64::MAX),
+                            max_size: Some(u64::MAX),
+                            modified_after: Some(u64::MAX),
+                            modified_before: Some(u64::MAX),
+                            hidden: true,
+                            no_ignore: true,
+                            ignore_files: vec![PathBuf::from("path")],
+                            before_context: Some(u64::MAX),
+                            after_context: Some(u64::MAX),
+                            multiline: true,
+                        },
+                    },
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::Search {
+                query: SearchQuery {
+                    target: SearchQueryTarget::Contents,
+                    condition: SearchQueryCondition::equals("hello world"),
+                    paths: vec![PathBuf::from("path")],
+                    options: Default::default(),
+                },
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::Search {
+                query: SearchQuery {
+                    target: SearchQueryTarget::Contents,
+                    condition: SearchQueryCondition::equals("hello world"),
+                    paths: vec![PathBuf::from("path")],
+                    options: SearchQueryOptions {
+                        allowed_file_types: [FileType::File].into_iter().collect(),
+                        include: Some(SearchQueryCondition::Equals {
+                            value: String::from("hello"),
+                        }),
+                        exclude: Some(SearchQueryCondition::Contains {
+                            value: String::from("world"),
+                        }),
+                        upward: true,
+                        follow_symbolic_links: true,
+                        limit: Some(u64::MAX),
+                        max_depth: Some(u64::MAX),
+                        pagination: Some(u64::MAX),
+                        min_size: Some(u64::MAX),
+                        max_size: Some(u64::MAX),
+                        modified_after: Some(u64::MAX),
+                        modified_before: Some(u64::MAX),
+                        hidden: true,
+                        no_ignore: true,
+                        ignore_files: vec![PathBuf::from("path")],
+                        before_context: Some(u64::MAX),
+                        after_context: Some(u64::MAX),
+                        multiline: true,
+                    },
+                },
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Search {
+                query: SearchQuery {
+                    target: SearchQueryTarget::Contents,
+                    condition: SearchQueryCondition::equals("hello world"),
+                    paths: vec![PathBuf::from("path")],
+                    options: Default::default(),
+                },
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Search {
+                    query: SearchQuery {
+                        target: SearchQueryTarget::Contents,
+                        condition: SearchQueryCondition::equals("hello world"),
+                        paths: vec![PathBuf::from("path")],
+                        options: Default::default(),
+                    },
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Search {
+                query: SearchQuery {
+                    target: SearchQueryTarget::Contents,
+                    condition: SearchQueryCondition::equals("hello world"),
+                    paths: vec![PathBuf::from("path")],
+                    options: SearchQueryOptions {
+                        allowed_file_types: [FileType::File].into_iter().collect(),
+                        include: Some(SearchQueryCondition::Equals {
+                            value: String::from("hello"),
+                        }),
+                        exclude: Some(SearchQueryCondition::Contains {
+                            value: String::from("world"),
+                        }),
+                        upward: true,
+                        follow_symbolic_links: true,
+                        limit: Some(u64::MAX),
+                        max_depth: Some(u64::MAX),
+                        pagination: Some(u64::MAX),
+                        min_size: Some(u64::MAX),
+                        max_size: Some(u64::MAX),
+                        modified_after: Some(u64::MAX),
+                        modified_before: Some(u64::MAX),
+                        hidden: true,
+                        no_ignore: true,
+                        ignore_files: vec![PathBuf::from("path")],
+                        before_context: Some(u64::MAX),
+                        after_context: Some(u64::MAX),
+                        multiline: true,
+                    },
+                },
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Search {
+                    query: SearchQuery {
+                        target: SearchQueryTarget::Contents,
+                        condition: SearchQueryCondition::equals("hello world"),
+                        paths: vec![PathBuf::from("path")],
+                        options: SearchQueryOptions {
+                            allowed_file_types: [FileType::File].into_iter().collect(),
+                            include: Some(SearchQueryCondition::Equals {
+                                value: String::from("hello"),
+                            }),
+                            exclude: Some(SearchQueryCondition::Contains {
+                                value: String::from("world"),
+                            }),
+                            upward: true,
+                            follow_symbolic_links: true,
+                            limit: Some(u64::MAX),
+                            max_depth: Some(u64::MAX),
+                            pagination: Some(u64::MAX),
+                            min_size: Some(u64::MAX),
+                            max_size: Some(u64::MAX),
+                            modified_after: Some(u64::MAX),
+                            modified_before: Some(u64::MAX),
+                            hidden: true,
+                            no_ignore: true,
+                            ignore_files: vec![PathBuf::from("path")],
+                            before_context: Some(u64::MAX),
+                            after_context: Some(u64::MAX),
+                            multiline: true,
+                        },
+                    },
+                }
+            );
+        }
+    }
+
+    mod cancel_search {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::CancelSearch { id: u32::MAX };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "cancel_search",
+                    "id": u32::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "cancel_search",
+                "id": u32::MAX,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Request::CancelSearch { id: u32::MAX });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::CancelSearch { id: u32::MAX };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf =
+                rmp_serde::encode::to_vec_named(&Request::CancelSearch { id: u32::MAX }).unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Request::CancelSearch { id: u32::MAX });
+        }
+    }
+
+    mod replace {
+        use super::*;
+        use crate::common::{SearchQueryCondition, SearchQueryTarget};
+
+        fn query() -> SearchQuery {
+            SearchQuery {
+                target: SearchQueryTarget::Contents,
+                condition: SearchQueryCondition::regex("(hello) world"),
+                paths: vec![PathBuf::from("path")],
+                options: Default::default(),
+            }
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::Replace {
+                query: query(),
+                replacement: String::from("$1 there"),
+                dry_run: false,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "replace",
+                    "query": {
+                        "target": "contents",
+                        "condition": {
+                            "type": "regex",
+                            "value": "(hello) world",
+                        },
+                        "paths": ["path"],
+                        "options": {},
+                    },
+                    "replacement": "$1 there",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::Replace {
+                query: query(),
+                replacement: String::from("$1 there"),
+                dry_run: true,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "replace",
+                    "query": {
+                        "target": "contents",
+                        "condition": {
+                            "type": "regex",
+                            "value": "(hello) world",
+                        },
+                        "paths": ["path"],
+                        "options": {},
+                    },
+                    "replacement": "$1 there",
+                    "dry_run": true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "replace",
+                "query": {
+                    "target": "contents",
+                    "condition": {
+                        "type": "regex",
+                        "value": "(hello) world",
+                    },
+                    "paths": ["path"],
+                },
+                "replacement": "$1 there",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Replace {
+                    query: query(),
+                    replacement: String::from("$1 there"),
+                    dry_run: false,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "replace",
+                "query": {
+                    "target": "contents",
+                    "condition": {
+                        "type": "regex",
+                        "value": "(hello) world",
+                    },
+                    "paths": ["path"],
+                },
+                "replacement": "$1 there",
+                "dry_run": true,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::Replace {
+                    query: query(),
+                    replacement: String::from("$1 there"),
+                    dry_run: true,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::Replace {
+                query: query(),
+                replacement: String::from("$1 there"),
+                dry_run: false,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::Replace {
+                query: query(),
+                replacement: String::from("$1 there"),
+                dry_run: true,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Replace {
+                query: query(),
+                replacement: String::from("$1 there"),
+                dry_run: false,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Replace {
+                    query: query(),
+                    replacement: String::from("$1 there"),
+                    dry_run: false,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Replace {
+                query: query(),
+                replacement: String::from("$1 there"),
+                dry_run: true,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::Replace {
+                    query: query(),
+                    replacement: String::from("$1 there"),
+                    dry_run: true,
+                }
+            );
+        }
+    }
+
+    mod proc_spawn {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Request::ProcSpawn {
+                cmd: Cmd::new("echo some text"),
+                environment: Environment::new(),
+                current_dir: None,
+                pty: None,
+                options: Default::default(),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_spawn",
+                    "cmd": "echo some text",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Request::ProcSpawn {
+                cmd: Cmd::new("echo some text"),
+                environment: [(String::from("hello"), String::from("world"))]
+                    .into_iter()
+                    .collect(),
+                current_dir: Some(PathBuf::from("current-dir")),
+                pty: Some(PtySize {
+                    rows: u16::MAX,
+                    cols: u16::MAX,
+                    pixel_width: u16::MAX,
+                    pixel_height: u16::MAX,
+                }),
+                options: ProcSpawnOptions {
+                    max_cpu_time: Some(u64::MAX),
+                    uid: Some(u32::MAX),
+                    umask: Some(0o022),
+                    ..Default::default()
+                },
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_spawn",
+                    "cmd": "echo some text",
+                    "environment": { "hello": "world" },
+                    "current_dir": "current-dir",
+                    "pty": {
+                        "rows": u16::MAX,
+                        "cols": u16::MAX,
+                        "pixel_width": u16::MAX,
+                        "pixel_height": u16::MAX,
+                    },
+                "options": { "max_cpu_time": u64::MAX, "uid": u32::MAX, "umask": 0o022 },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_spawn",
+                "cmd": "echo some text",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcSpawn {
+                    cmd: Cmd::new("echo some text"),
+                    environment: Environment::new(),
+                    current_dir: None,
+                    pty: None,
+                    options: Default::default(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_spawn",
+                "cmd": "echo some text",
+                "environment": { "hello": "world" },
+                "current_dir": "current-dir",
+                "pty": {
+                    "rows": u16::MAX,
+                    "cols": u16::MAX,
+                    "pixel_width": u16::MAX,
+                    "pixel_height": u16::MAX,
+                },
+            "options": { "max_cpu_time": u64::MAX, "uid": u32::MAX, "umask": 0o022 },
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcSpawn {
+                    cmd: Cmd::new("echo some text"),
+                    environment: [(String::from("hello"), String::from("world"))]
+                        .into_iter()
+                        .collect(),
+                    current_dir: Some(PathBuf::from("current-dir")),
+                    pty: Some(PtySize {
+                        rows: u16::MAX,
+                        cols: u16::MAX,
+                        pixel_width: u16::MAX,
+                        pixel_height: u16::MAX,
+                    }),
+                    options: ProcSpawnOptions {
+                        max_cpu_time: Some(u64::MAX),
+                        uid: Some(u32::MAX),
+                        umask: Some(0o022),
+                        ..Default::default()
+                    },
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Request::ProcSpawn {
+                cmd: Cmd::new("echo some text"),
+                environment: Environment::new(),
+                current_dir: None,
+                pty: None,
+                options: Default::default(),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Request::ProcSpawn {
+                cmd: Cmd::new("echo some text"),
+                environment: [(String::from("hello"), String::from("world"))]
+                    .into_iter()
+                    .collect(),
+                current_dir: Some(PathBuf::from("current-dir")),
+                pty: Some(PtySize {
+                    rows: u16::MAX,
+                    cols: u16::MAX,
+                    pixel_width: u16::MAX,
+                    pixel_height: u16::MAX,
+                }),
+                options: ProcSpawnOptions {
+                    max_cpu_time: Some(u64::MAX),
+                    uid: Some(u32::MAX),
+                    umask: Some(0o022),
+                    ..Default::default()
+                },
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcSpawn {
+                cmd: Cmd::new("echo some text"),
+                environment: Environment::new(),
+                current_dir: None,
+                pty: None,
+                options: Default::default(),
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcSpawn {
+                    cmd: Cmd::new("echo some text"),
+                    environment: Environment::new(),
+                    current_dir: None,
+                    pty: None,
+                    options: Default::default(),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcSpawn {
+                cmd: Cmd::new("echo some text"),
+                environment: [(String::from("hello"), String::from("world"))]
+                    .into_iter()
+                    .collect(),
+                current_dir: Some(PathBuf::from("current-dir")),
+                pty: Some(PtySize {
+                    rows: u16::MAX,
+                    cols: u16::MAX,
+                    pixel_width: u16::MAX,
+                    pixel_height: u16::MAX,
+                }),
+                options: ProcSpawnOptions {
+                    max_cpu_time: Some(u64::MAX),
+                    uid: Some(u32::MAX),
+                    umask: Some(0o022),
+                    ..Default::default()
+                },
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcSpawn {
+                    cmd: Cmd::new("echo some text"),
+                    environment: [(String::from("hello"), String::from("world"))]
+                        .into_iter()
+                        .collect(),
+                    current_dir: Some(PathBuf::from("current-dir")),
+                    pty: Some(PtySize {
+                        rows: u16::MAX,
+                        cols: u16::MAX,
+                        pixel_width: u16::MAX,
+                        pixel_height: u16::MAX,
+                    }),
+                    options: ProcSpawnOptions {
+                        max_cpu_time: Some(u64::MAX),
+                        uid: Some(u32::MAX),
+                        umask: Some(0o022),
+                        ..Default::default()
+                    },
+                }
+            );
+        }
+    }
+
+    mod proc_kill {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::ProcKill { id: u32::MAX };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_kill",
+                    "id": u32::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_kill",
+                "id": u32::MAX,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Request::ProcKill { id: u32::MAX });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::ProcKill { id: u32::MAX };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcKill { id: u32::MAX }).unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Request::ProcKill { id: u32::MAX });
+        }
+    }
+
+    mod proc_signal {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::ProcSignal {
+                id: u32::MAX,
+                signal: Signal::Term,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_signal",
+                    "id": u32::MAX,
+                    "signal": "term",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_signal",
+                "id": u32::MAX,
+                "signal": "term",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcSignal {
+                    id: u32::MAX,
+                    signal: Signal::Term,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::ProcSignal {
+                id: u32::MAX,
+                signal: Signal::Term,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcSignal {
+                id: u32::MAX,
+                signal: Signal::Term,
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcSignal {
+                    id: u32::MAX,
+                    signal: Signal::Term,
+                }
+            );
+        }
+    }
+
+    mod proc_stdin {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::ProcStdin {
+                id: u32::MAX,
+                data: vec![0, 1, 2, 3, u8::MAX],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_stdin",
+                    "id": u32::MAX,
+                    "data": [0, 1, 2, 3, u8::MAX],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_stdin",
+                "id": u32::MAX,
+                "data": [0, 1, 2, 3, u8::MAX],
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcStdin {
+                    id: u32::MAX,
+                    data: vec![0, 1, 2, 3, u8::MAX],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::ProcStdin {
+                id: u32::MAX,
+                data: vec![0, 1, 2, 3, u8::MAX],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcStdin {
+                id: u32::MAX,
+                data: vec![0, 1, 2, 3, u8::MAX],
+            })
+            .unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcStdin {
+                    id: u32::MAX,
+                    data: vec![0, 1, 2, 3, u8::MAX],
+                }
+            );
+        }
+    }
+
+    mod proc_resize_pty {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::ProcResizePty {
+                id: u32::MAX,
+                size: PtySize {
+                    rows: u16::MAX,
+                    cols: u16::MAX,
+                    pixel_width: u16::MAX,
+                    pixel_height: u16::MAX,
+                },
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_resize_pty",
+                    "id": u32::MAX,
+                    "size": {
+                        "rows": u16::MAX,
+                        "cols": u16::MAX,
+                        "pixel_width": u16::MAX,
+                        "pixel_height": u16::MAX,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_resize_pty",
+                "id": u32::MAX,
+                "size": {
+                    "rows": u16::MAX,
+                    "cols": u16::MAX,
+                    "pixel_width": u16::MAX,
+                    "pixel_height": u16::MAX,
+                },
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Request::ProcResizePty {
+                    id: u32::MAX,
+                    size: PtySize {
+                        rows: u16::MAX,
+                        cols: u16::MAX,
+                        pixel_width: u16::MAX,
+                        pixel_height: u16::MAX,
                     },
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::ProcResizePty {
+                id: u32::MAX,
+                size: PtySize {
+                    rows: u16::MAX,
+                    cols: u16::MAX,
+                    pixel_width: u16::MAX,
+                    pixel_height: u16::MAX,
                 },
             };
 
@@ -2286,60 +4913,18 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
-            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
-            // verify that we are not corrupting or causing issues when serializing on a
-            // client/server and then trying to deserialize on the other side. This has happened
-            // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Search {
-                query: SearchQuery {
-                    target: SearchQueryTarget::Contents,
-                    condition: SearchQueryCondition::equals("hello world"),
-                    paths: vec![PathBuf::from("path")],
-                    options: Default::default(),
-                },
-            })
-            .unwrap();
-
-            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(
-                payload,
-                Request::Search {
-                    query: SearchQuery {
-                        target: SearchQueryTarget::Contents,
-                        condition: SearchQueryCondition::equals("hello world"),
-                        paths: vec![PathBuf::from("path")],
-                        options: Default::default(),
-                    },
-                }
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::Search {
-                query: SearchQuery {
-                    target: SearchQueryTarget::Contents,
-                    condition: SearchQueryCondition::equals("hello world"),
-                    paths: vec![PathBuf::from("path")],
-                    options: SearchQueryOptions {
-                        allowed_file_types: [FileType::File].into_iter().collect(),
-                        include: Some(SearchQueryCondition::Equals {
-                            value: String::from("hello"),
-                        }),
-                        exclude: Some(SearchQueryCondition::Contains {
-                            value: String::from("world"),
-                        }),
-                        upward: true,
-                        follow_symbolic_links: true,
-                        limit: Some(u64::MAX),
-                        max_depth: Some(u64::MAX),
-                        pagination: Some(u64::MAX),
-                    },
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcResizePty {
+                id: u32::MAX,
+                size: PtySize {
+                    rows: u16::MAX,
+                    cols: u16::MAX,
+                    pixel_width: u16::MAX,
+                    pixel_height: u16::MAX,
                 },
             })
             .unwrap();
@@ -2347,44 +4932,31 @@ mod tests {
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::Search {
-                    query: SearchQuery {
-                        target: SearchQueryTarget::Contents,
-                        condition: SearchQueryCondition::equals("hello world"),
-                        paths: vec![PathBuf::from("path")],
-                        options: SearchQueryOptions {
-                            allowed_file_types: [FileType::File].into_iter().collect(),
-                            include: Some(SearchQueryCondition::Equals {
-                                value: String::from("hello"),
-                            }),
-                            exclude: Some(SearchQueryCondition::Contains {
-                                value: String::from("world"),
-                            }),
-                            upward: true,
-                            follow_symbolic_links: true,
-                            limit: Some(u64::MAX),
-                            max_depth: Some(u64::MAX),
-                            pagination: Some(u64::MAX),
-                        },
+                Request::ProcResizePty {
+                    id: u32::MAX,
+                    size: PtySize {
+                        rows: u16::MAX,
+                        cols: u16::MAX,
+                        pixel_width: u16::MAX,
+                        pixel_height: u16::MAX,
                     },
                 }
             );
         }
     }
 
-    mod cancel_search {
+    mod proc_list {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Request::CancelSearch { id: u32::MAX };
+            let payload = Request::ProcList {};
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "cancel_search",
-                    "id": u32::MAX,
+                    "type": "proc_list",
                 })
             );
         }
@@ -2392,17 +4964,16 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "cancel_search",
-                "id": u32::MAX,
+                "type": "proc_list",
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(payload, Request::CancelSearch { id: u32::MAX });
+            assert_eq!(payload, Request::ProcList {});
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::CancelSearch { id: u32::MAX };
+            let payload = Request::ProcList {};
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -2417,131 +4988,44 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf =
-                rmp_serde::encode::to_vec_named(&Request::CancelSearch { id: u32::MAX }).unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Request::ProcList {}).unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(payload, Request::CancelSearch { id: u32::MAX });
+            assert_eq!(payload, Request::ProcList {});
         }
     }
 
-    mod proc_spawn {
+    mod proc_attach {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Request::ProcSpawn {
-                cmd: Cmd::new("echo some text"),
-                environment: Environment::new(),
-                current_dir: None,
-                pty: None,
-            };
-
-            let value = serde_json::to_value(payload).unwrap();
-            assert_eq!(
-                value,
-                serde_json::json!({
-                    "type": "proc_spawn",
-                    "cmd": "echo some text",
-                })
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Request::ProcSpawn {
-                cmd: Cmd::new("echo some text"),
-                environment: [(String::from("hello"), String::from("world"))]
-                    .into_iter()
-                    .collect(),
-                current_dir: Some(PathBuf::from("current-dir")),
-                pty: Some(PtySize {
-                    rows: u16::MAX,
-                    cols: u16::MAX,
-                    pixel_width: u16::MAX,
-                    pixel_height: u16::MAX,
-                }),
-            };
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::ProcAttach { id: u32::MAX };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_spawn",
-                    "cmd": "echo some text",
-                    "environment": { "hello": "world" },
-                    "current_dir": "current-dir",
-                    "pty": {
-                        "rows": u16::MAX,
-                        "cols": u16::MAX,
-                        "pixel_width": u16::MAX,
-                        "pixel_height": u16::MAX,
-                    },
+                    "type": "proc_attach",
+                    "id": u32::MAX,
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_json() {
-            let value = serde_json::json!({
-                "type": "proc_spawn",
-                "cmd": "echo some text",
-            });
-
-            let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Request::ProcSpawn {
-                    cmd: Cmd::new("echo some text"),
-                    environment: Environment::new(),
-                    current_dir: None,
-                    pty: None,
-                }
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_deserialize_full_payload_from_json() {
+        fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_spawn",
-                "cmd": "echo some text",
-                "environment": { "hello": "world" },
-                "current_dir": "current-dir",
-                "pty": {
-                    "rows": u16::MAX,
-                    "cols": u16::MAX,
-                    "pixel_width": u16::MAX,
-                    "pixel_height": u16::MAX,
-                },
+                "type": "proc_attach",
+                "id": u32::MAX,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Request::ProcSpawn {
-                    cmd: Cmd::new("echo some text"),
-                    environment: [(String::from("hello"), String::from("world"))]
-                        .into_iter()
-                        .collect(),
-                    current_dir: Some(PathBuf::from("current-dir")),
-                    pty: Some(PtySize {
-                        rows: u16::MAX,
-                        cols: u16::MAX,
-                        pixel_width: u16::MAX,
-                        pixel_height: u16::MAX,
-                    }),
-                }
-            );
+            assert_eq!(payload, Request::ProcAttach { id: u32::MAX });
         }
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Request::ProcSpawn {
-                cmd: Cmd::new("echo some text"),
-                environment: Environment::new(),
-                current_dir: None,
-                pty: None,
-            };
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::ProcAttach { id: u32::MAX };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -2551,108 +5035,112 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Request::ProcSpawn {
-                cmd: Cmd::new("echo some text"),
-                environment: [(String::from("hello"), String::from("world"))]
-                    .into_iter()
-                    .collect(),
-                current_dir: Some(PathBuf::from("current-dir")),
-                pty: Some(PtySize {
-                    rows: u16::MAX,
-                    cols: u16::MAX,
-                    pixel_width: u16::MAX,
-                    pixel_height: u16::MAX,
-                }),
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf =
+                rmp_serde::encode::to_vec_named(&Request::ProcAttach { id: u32::MAX }).unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Request::ProcAttach { id: u32::MAX });
+        }
+    }
+
+    mod tcp_connect {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::TcpConnect {
+                host: String::from("localhost"),
+                port: 8080,
             };
 
-            // NOTE: We don't actually check the output here because it's an implementation detail
-            // and could change as we change how serialization is done. This is merely to verify
-            // that we can serialize since there are times when serde fails to serialize at
-            // runtime.
-            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "tcp_connect",
+                    "host": "localhost",
+                    "port": 8080,
+                })
+            );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
-            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
-            // verify that we are not corrupting or causing issues when serializing on a
-            // client/server and then trying to deserialize on the other side. This has happened
-            // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::ProcSpawn {
-                cmd: Cmd::new("echo some text"),
-                environment: Environment::new(),
-                current_dir: None,
-                pty: None,
-            })
-            .unwrap();
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "tcp_connect",
+                "host": "localhost",
+                "port": 8080,
+            });
 
-            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::ProcSpawn {
-                    cmd: Cmd::new("echo some text"),
-                    environment: Environment::new(),
-                    current_dir: None,
-                    pty: None,
+                Request::TcpConnect {
+                    host: String::from("localhost"),
+                    port: 8080,
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::TcpConnect {
+                host: String::from("localhost"),
+                port: 8080,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::ProcSpawn {
-                cmd: Cmd::new("echo some text"),
-                environment: [(String::from("hello"), String::from("world"))]
-                    .into_iter()
-                    .collect(),
-                current_dir: Some(PathBuf::from("current-dir")),
-                pty: Some(PtySize {
-                    rows: u16::MAX,
-                    cols: u16::MAX,
-                    pixel_width: u16::MAX,
-                    pixel_height: u16::MAX,
-                }),
+            let buf = rmp_serde::encode::to_vec_named(&Request::TcpConnect {
+                host: String::from("localhost"),
+                port: 8080,
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::ProcSpawn {
-                    cmd: Cmd::new("echo some text"),
-                    environment: [(String::from("hello"), String::from("world"))]
-                        .into_iter()
-                        .collect(),
-                    current_dir: Some(PathBuf::from("current-dir")),
-                    pty: Some(PtySize {
-                        rows: u16::MAX,
-                        cols: u16::MAX,
-                        pixel_width: u16::MAX,
-                        pixel_height: u16::MAX,
-                    }),
+                Request::TcpConnect {
+                    host: String::from("localhost"),
+                    port: 8080,
                 }
             );
         }
     }
 
-    mod proc_kill {
+    mod tcp_listen {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Request::ProcKill { id: u32::MAX };
+            let payload = Request::TcpListen {
+                host: String::from("127.0.0.1"),
+                port: 0,
+            };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_kill",
-                    "id": u32::MAX,
+                    "type": "tcp_listen",
+                    "host": "127.0.0.1",
+                    "port": 0,
                 })
             );
         }
@@ -2660,17 +5148,27 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_kill",
-                "id": u32::MAX,
+                "type": "tcp_listen",
+                "host": "127.0.0.1",
+                "port": 0,
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(payload, Request::ProcKill { id: u32::MAX });
+            assert_eq!(
+                payload,
+                Request::TcpListen {
+                    host: String::from("127.0.0.1"),
+                    port: 0,
+                }
+            );
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::ProcKill { id: u32::MAX };
+            let payload = Request::TcpListen {
+                host: String::from("127.0.0.1"),
+                port: 0,
+            };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -2685,30 +5183,40 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::ProcKill { id: u32::MAX }).unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Request::TcpListen {
+                host: String::from("127.0.0.1"),
+                port: 0,
+            })
+            .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(payload, Request::ProcKill { id: u32::MAX });
+            assert_eq!(
+                payload,
+                Request::TcpListen {
+                    host: String::from("127.0.0.1"),
+                    port: 0,
+                }
+            );
         }
     }
 
-    mod proc_stdin {
+    mod tcp_write {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Request::ProcStdin {
+            let payload = Request::TcpWrite {
                 id: u32::MAX,
-                data: vec![0, 1, 2, 3, u8::MAX],
+                data: vec![0, 1, 2, u8::MAX],
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_stdin",
+                    "type": "tcp_write",
                     "id": u32::MAX,
-                    "data": [0, 1, 2, 3, u8::MAX],
+                    "data": [0, 1, 2, u8::MAX],
                 })
             );
         }
@@ -2716,26 +5224,26 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_stdin",
+                "type": "tcp_write",
                 "id": u32::MAX,
-                "data": [0, 1, 2, 3, u8::MAX],
+                "data": [0, 1, 2, u8::MAX],
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Request::ProcStdin {
+                Request::TcpWrite {
                     id: u32::MAX,
-                    data: vec![0, 1, 2, 3, u8::MAX],
+                    data: vec![0, 1, 2, u8::MAX],
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::ProcStdin {
+            let payload = Request::TcpWrite {
                 id: u32::MAX,
-                data: vec![0, 1, 2, 3, u8::MAX],
+                data: vec![0, 1, 2, u8::MAX],
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -2751,50 +5259,36 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::ProcStdin {
+            let buf = rmp_serde::encode::to_vec_named(&Request::TcpWrite {
                 id: u32::MAX,
-                data: vec![0, 1, 2, 3, u8::MAX],
+                data: vec![0, 1, 2, u8::MAX],
             })
             .unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Request::ProcStdin {
+                Request::TcpWrite {
                     id: u32::MAX,
-                    data: vec![0, 1, 2, 3, u8::MAX],
+                    data: vec![0, 1, 2, u8::MAX],
                 }
             );
         }
     }
 
-    mod proc_resize_pty {
+    mod tcp_close {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Request::ProcResizePty {
-                id: u32::MAX,
-                size: PtySize {
-                    rows: u16::MAX,
-                    cols: u16::MAX,
-                    pixel_width: u16::MAX,
-                    pixel_height: u16::MAX,
-                },
-            };
+            let payload = Request::TcpClose { id: u32::MAX };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_resize_pty",
+                    "type": "tcp_close",
                     "id": u32::MAX,
-                    "size": {
-                        "rows": u16::MAX,
-                        "cols": u16::MAX,
-                        "pixel_width": u16::MAX,
-                        "pixel_height": u16::MAX,
-                    },
                 })
             );
         }
@@ -2802,42 +5296,17 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_resize_pty",
+                "type": "tcp_close",
                 "id": u32::MAX,
-                "size": {
-                    "rows": u16::MAX,
-                    "cols": u16::MAX,
-                    "pixel_width": u16::MAX,
-                    "pixel_height": u16::MAX,
-                },
             });
 
             let payload: Request = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Request::ProcResizePty {
-                    id: u32::MAX,
-                    size: PtySize {
-                        rows: u16::MAX,
-                        cols: u16::MAX,
-                        pixel_width: u16::MAX,
-                        pixel_height: u16::MAX,
-                    },
-                }
-            );
+            assert_eq!(payload, Request::TcpClose { id: u32::MAX });
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Request::ProcResizePty {
-                id: u32::MAX,
-                size: PtySize {
-                    rows: u16::MAX,
-                    cols: u16::MAX,
-                    pixel_width: u16::MAX,
-                    pixel_height: u16::MAX,
-                },
-            };
+            let payload = Request::TcpClose { id: u32::MAX };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -2852,31 +5321,64 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Request::ProcResizePty {
-                id: u32::MAX,
-                size: PtySize {
-                    rows: u16::MAX,
-                    cols: u16::MAX,
-                    pixel_width: u16::MAX,
-                    pixel_height: u16::MAX,
-                },
-            })
-            .unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Request::TcpClose { id: u32::MAX }).unwrap();
 
             let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Request::TcpClose { id: u32::MAX });
+        }
+    }
+
+    mod tcp_unlisten {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::TcpUnlisten { id: u32::MAX };
+
+            let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
-                payload,
-                Request::ProcResizePty {
-                    id: u32::MAX,
-                    size: PtySize {
-                        rows: u16::MAX,
-                        cols: u16::MAX,
-                        pixel_width: u16::MAX,
-                        pixel_height: u16::MAX,
-                    },
-                }
+                value,
+                serde_json::json!({
+                    "type": "tcp_unlisten",
+                    "id": u32::MAX,
+                })
             );
         }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "tcp_unlisten",
+                "id": u32::MAX,
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Request::TcpUnlisten { id: u32::MAX });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::TcpUnlisten { id: u32::MAX };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf =
+                rmp_serde::encode::to_vec_named(&Request::TcpUnlisten { id: u32::MAX }).unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Request::TcpUnlisten { id: u32::MAX });
+        }
     }
 
     mod system_info {
@@ -2929,6 +5431,56 @@ mod tests {
         }
     }
 
+    mod environment {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Request::Environment {};
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "environment",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "environment",
+            });
+
+            let payload: Request = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Request::Environment {});
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Request::Environment {};
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Request::Environment {}).unwrap();
+
+            let payload: Request = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Request::Environment {});
+        }
+    }
+
     mod version {
         use super::*;
 
@@ -1,12 +1,17 @@
 use std::io;
+use std::path::PathBuf;
 
 use derive_more::IsVariant;
 use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumDiscriminants, EnumIter, EnumMessage, EnumString};
 
 use crate::common::{
-    Change, DirEntry, Error, Metadata, ProcessId, SearchId, SearchQueryMatch, SystemInfo, Version,
+    Change, DirEntry, Error, FileHash, Metadata, ProcessId, ProcessInfo, ProcessLimit,
+    ProcessUsage, SearchId, SearchQueryMatch, SystemInfo, TcpListenerId, TcpStreamId, UploadId,
+    Version,
 };
+use crate::request::Environment;
+use crate::utils;
 
 /// Represents the payload of a successful response
 #[derive(
@@ -51,6 +56,28 @@ pub enum Response {
         data: String,
     },
 
+    /// Indicates that a streamed file read has finished and no more blobs will be sent
+    FileReadDone,
+
+    /// Response to opening an upload session
+    UploadOpened {
+        /// Arbitrary id associated with the upload session
+        id: UploadId,
+
+        /// Offset (in bytes) of data confirmed as written, which is where the upload
+        /// should continue when resuming
+        offset: u64,
+    },
+
+    /// Response to writing a chunk to an upload session
+    UploadWritten {
+        /// Arbitrary id associated with the upload session
+        id: UploadId,
+
+        /// Offset (in bytes) of data confirmed as written
+        offset: u64,
+    },
+
     /// Response to reading a directory
     DirEntries {
         /// Entries contained within the requested directory
@@ -60,6 +87,33 @@ pub enum Response {
         errors: Vec<Error>,
     },
 
+    /// Response to reading the target of a symbolic link
+    LinkTarget {
+        /// Path that the symbolic link points to, exactly as stored in the link
+        path: PathBuf,
+    },
+
+    /// Response to computing the digest of a file
+    Hash {
+        /// Digest of the file's contents as a lowercase hex string
+        digest: String,
+    },
+
+    /// Response to computing the digests of all files within a directory
+    Hashes {
+        /// Digests of the files, ordered by their paths
+        entries: Vec<FileHash>,
+    },
+
+    /// Response to computing the digests of blocks within a file
+    BlockChecksums {
+        /// Length (in bytes) of the file
+        len: u64,
+
+        /// Digest of each block as a lowercase hex string, ordered by offset
+        checksums: Vec<String>,
+    },
+
     /// Response to a filesystem change for some watched file, directory, or symlink
     Changed(Change),
 
@@ -90,6 +144,30 @@ pub enum Response {
         id: SearchId,
     },
 
+    /// Represents the matches replaced within a single file (or that would be replaced during a
+    /// dry run)
+    Replaced {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Number of matches replaced within the file
+        replacements: u64,
+
+        /// Unified diff of the changes to the file, only provided during a dry run
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        diff: Option<String>,
+    },
+
+    /// Represents a replace being completed, which is the response to the request and is
+    /// therefore sent before the `Replaced` response of each of the `files` changed
+    ReplaceDone {
+        /// Total number of files changed (or that would change during a dry run)
+        files: u64,
+
+        /// Total number of matches replaced across all files
+        replacements: u64,
+    },
+
     /// Response to starting a new process
     ProcSpawned {
         /// Arbitrary id associated with running process
@@ -104,6 +182,12 @@ pub enum Response {
         /// Data read from a process' stdout pipe
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
+
+        /// Position of this output amongst all stdout and stderr of the process, reflecting the
+        /// order in which the server read the two pipes rather than the order in which the
+        /// process wrote to them, which cannot be known across separate pipes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
 
     /// Actively-transmitted stderr as part of running process
@@ -114,6 +198,12 @@ pub enum Response {
         /// Data read from a process' stderr pipe
         #[serde(with = "serde_bytes")]
         data: Vec<u8>,
+
+        /// Position of this output amongst all stdout and stderr of the process, reflecting the
+        /// order in which the server read the two pipes rather than the order in which the
+        /// process wrote to them, which cannot be known across separate pipes
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
 
     /// Response to a process finishing
@@ -127,11 +217,87 @@ pub enum Response {
         /// Exit code associated with termination, will be missing if terminated by signal
         #[serde(default, skip_serializing_if = "Option::is_none")]
         code: Option<i32>,
+
+        /// Resource limit that was hit by the process, causing it to terminate
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<ProcessLimit>,
+
+        /// Whether or not the process was killed because it ran past its timeout
+        #[serde(default, skip_serializing_if = "utils::is_false")]
+        timed_out: bool,
+
+        /// Number of the signal that terminated the process, as defined by the operating system
+        /// of the server, will be missing if the process exited normally
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signal: Option<i32>,
+
+        /// Whether or not the process produced a core dump when it was terminated
+        #[serde(default, skip_serializing_if = "utils::is_false")]
+        core_dumped: bool,
+
+        /// Resources consumed by the process, will be missing if they could not be collected
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        usage: Option<ProcessUsage>,
+    },
+
+    /// Response to listing spawned processes
+    ProcEntries {
+        /// Processes being tracked by the server
+        entries: Vec<ProcessInfo>,
+    },
+
+    /// Response to opening a forwarded tcp stream
+    TcpConnected {
+        /// Arbitrary id associated with the forwarded tcp stream
+        id: TcpStreamId,
+    },
+
+    /// Response to listening for tcp connections
+    TcpListening {
+        /// Arbitrary id associated with the tcp listener
+        id: TcpListenerId,
+
+        /// Port that the listener is bound to on the remote machine
+        port: u16,
+    },
+
+    /// Actively-transmitted tcp connection accepted by a tcp listener
+    TcpAccepted {
+        /// Arbitrary id associated with the tcp listener
+        listener: TcpListenerId,
+
+        /// Arbitrary id associated with the forwarded tcp stream of the accepted connection
+        id: TcpStreamId,
+
+        /// Address of the peer that connected
+        peer_addr: String,
+    },
+
+    /// Actively-transmitted data received on a forwarded tcp stream
+    TcpData {
+        /// Arbitrary id associated with the forwarded tcp stream
+        id: TcpStreamId,
+
+        /// Data received on the tcp stream
+        #[serde(with = "serde_bytes")]
+        data: Vec<u8>,
+    },
+
+    /// Response to a forwarded tcp stream being closed
+    TcpClosed {
+        /// Arbitrary id associated with the forwarded tcp stream
+        id: TcpStreamId,
     },
 
     /// Response to retrieving information about the server and the system it is on
     SystemInfo(SystemInfo),
 
+    /// Response to retrieving the environment variables of the server
+    Environment {
+        /// Environment variables of the server, keyed by name
+        vars: Environment,
+    },
+
     /// Response to retrieving information about the server's version
     Version(Version),
 }
@@ -411,6 +577,208 @@ mod tests {
         }
     }
 
+    mod file_read_done {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::FileReadDone;
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "file_read_done",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "file_read_done",
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Response::FileReadDone);
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::FileReadDone;
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::FileReadDone).unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Response::FileReadDone);
+        }
+    }
+
+    mod upload_opened {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::UploadOpened {
+                id: 12345,
+                offset: 123,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "upload_opened",
+                    "id": 12345,
+                    "offset": 123,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "upload_opened",
+                "id": 12345,
+                "offset": 123,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::UploadOpened {
+                    id: 12345,
+                    offset: 123,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::UploadOpened {
+                id: 12345,
+                offset: 123,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::UploadOpened {
+                id: 12345,
+                offset: 123,
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::UploadOpened {
+                    id: 12345,
+                    offset: 123,
+                }
+            );
+        }
+    }
+
+    mod upload_written {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::UploadWritten {
+                id: 12345,
+                offset: 123,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "upload_written",
+                    "id": 12345,
+                    "offset": 123,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "upload_written",
+                "id": 12345,
+                "offset": 123,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::UploadWritten {
+                    id: 12345,
+                    offset: 123,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::UploadWritten {
+                id: 12345,
+                offset: 123,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::UploadWritten {
+                id: 12345,
+                offset: 123,
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::UploadWritten {
+                    id: 12345,
+                    offset: 123,
+                }
+            );
+        }
+    }
+
     mod dir_entries {
         use std::path::PathBuf;
 
@@ -611,147 +979,115 @@ mod tests {
         }
     }
 
-    mod changed {
-        use std::path::PathBuf;
-
+    mod link_target {
         use super::*;
-        use crate::common::{ChangeDetails, ChangeDetailsAttribute, ChangeKind};
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Response::Changed(Change {
-                timestamp: u64::MAX,
-                kind: ChangeKind::Access,
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::LinkTarget {
                 path: PathBuf::from("path"),
-                details: ChangeDetails::default(),
-            });
+            };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "changed",
-                    "timestamp": u64::MAX,
-                    "kind": "access",
+                    "type": "link_target",
                     "path": "path",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Response::Changed(Change {
-                timestamp: u64::MAX,
-                kind: ChangeKind::Access,
-                path: PathBuf::from("path"),
-                details: ChangeDetails {
-                    attribute: Some(ChangeDetailsAttribute::Permissions),
-                    renamed: Some(PathBuf::from("renamed")),
-                    timestamp: Some(u64::MAX),
-                    extra: Some(String::from("info")),
-                },
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "link_target",
+                "path": "path",
             });
 
-            let value = serde_json::to_value(payload).unwrap();
+            let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
-                value,
-                serde_json::json!({
-                    "type": "changed",
-                    "timestamp": u64::MAX,
-                    "kind": "access",
-                    "path": "path",
-                    "details": {
-                        "attribute": "permissions",
-                        "renamed": "renamed",
-                        "timestamp": u64::MAX,
-                        "extra": "info",
-                    },
-                })
+                payload,
+                Response::LinkTarget {
+                    path: PathBuf::from("path"),
+                }
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_json() {
-            let value = serde_json::json!({
-                "type": "changed",
-                "timestamp": u64::MAX,
-                "kind": "access",
-                "path": "path",
-            });
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::LinkTarget {
+                path: PathBuf::from("path"),
+            };
 
-            let payload: Response = serde_json::from_value(value).unwrap();
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::LinkTarget {
+                path: PathBuf::from("path"),
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::Changed(Change {
-                    timestamp: u64::MAX,
-                    kind: ChangeKind::Access,
+                Response::LinkTarget {
                     path: PathBuf::from("path"),
-                    details: ChangeDetails::default(),
-                })
+                }
             );
         }
+    }
+
+    mod hash {
+        use super::*;
 
         #[test]
-        fn should_be_able_to_deserialize_full_payload_from_json() {
-            let value = serde_json::json!({
-                "type": "changed",
-                "timestamp": u64::MAX,
-                "kind": "access",
-                "path": "path",
-                "details": {
-                    "attribute": "permissions",
-                    "renamed": "renamed",
-                    "timestamp": u64::MAX,
-                    "extra": "info",
-                },
-            });
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::Hash {
+                digest: String::from("abcdef"),
+            };
 
-            let payload: Response = serde_json::from_value(value).unwrap();
+            let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
-                payload,
-                Response::Changed(Change {
-                    timestamp: u64::MAX,
-                    kind: ChangeKind::Access,
-                    path: PathBuf::from("path"),
-                    details: ChangeDetails {
-                        attribute: Some(ChangeDetailsAttribute::Permissions),
-                        renamed: Some(PathBuf::from("renamed")),
-                        timestamp: Some(u64::MAX),
-                        extra: Some(String::from("info")),
-                    },
+                value,
+                serde_json::json!({
+                    "type": "hash",
+                    "digest": "abcdef",
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Response::Changed(Change {
-                timestamp: u64::MAX,
-                kind: ChangeKind::Access,
-                path: PathBuf::from("path"),
-                details: ChangeDetails::default(),
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "hash",
+                "digest": "abcdef",
             });
 
-            // NOTE: We don't actually check the output here because it's an implementation detail
-            // and could change as we change how serialization is done. This is merely to verify
-            // that we can serialize since there are times when serde fails to serialize at
-            // runtime.
-            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::Hash {
+                    digest: String::from("abcdef"),
+                }
+            );
         }
 
         #[test]
-        fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Response::Changed(Change {
-                timestamp: u64::MAX,
-                kind: ChangeKind::Access,
-                path: PathBuf::from("path"),
-                details: ChangeDetails {
-                    attribute: Some(ChangeDetailsAttribute::Permissions),
-                    renamed: Some(PathBuf::from("renamed")),
-                    timestamp: Some(u64::MAX),
-                    extra: Some(String::from("info")),
-                },
-            });
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::Hash {
+                digest: String::from("abcdef"),
+            };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -761,81 +1097,133 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::Changed(Change {
-                timestamp: u64::MAX,
-                kind: ChangeKind::Access,
-                path: PathBuf::from("path"),
-                details: ChangeDetails::default(),
-            }))
+            let buf = rmp_serde::encode::to_vec_named(&Response::Hash {
+                digest: String::from("abcdef"),
+            })
             .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::Changed(Change {
-                    timestamp: u64::MAX,
-                    kind: ChangeKind::Access,
+                Response::Hash {
+                    digest: String::from("abcdef"),
+                }
+            );
+        }
+    }
+
+    mod hashes {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::Hashes {
+                entries: vec![FileHash {
                     path: PathBuf::from("path"),
-                    details: ChangeDetails::default(),
+                    digest: String::from("abcdef"),
+                }],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "hashes",
+                    "entries": [{
+                        "path": "path",
+                        "digest": "abcdef",
+                    }],
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "hashes",
+                "entries": [{
+                    "path": "path",
+                    "digest": "abcdef",
+                }],
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::Hashes {
+                    entries: vec![FileHash {
+                        path: PathBuf::from("path"),
+                        digest: String::from("abcdef"),
+                    }],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::Hashes {
+                entries: vec![FileHash {
+                    path: PathBuf::from("path"),
+                    digest: String::from("abcdef"),
+                }],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::Changed(Change {
-                timestamp: u64::MAX,
-                kind: ChangeKind::Access,
-                path: PathBuf::from("path"),
-                details: ChangeDetails {
-                    attribute: Some(ChangeDetailsAttribute::Permissions),
-                    renamed: Some(PathBuf::from("renamed")),
-                    timestamp: Some(u64::MAX),
-                    extra: Some(String::from("info")),
-                },
-            }))
+            let buf = rmp_serde::encode::to_vec_named(&Response::Hashes {
+                entries: vec![FileHash {
+                    path: PathBuf::from("path"),
+                    digest: String::from("abcdef"),
+                }],
+            })
             .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::Changed(Change {
-                    timestamp: u64::MAX,
-                    kind: ChangeKind::Access,
-                    path: PathBuf::from("path"),
-                    details: ChangeDetails {
-                        attribute: Some(ChangeDetailsAttribute::Permissions),
-                        renamed: Some(PathBuf::from("renamed")),
-                        timestamp: Some(u64::MAX),
-                        extra: Some(String::from("info")),
-                    },
-                })
+                Response::Hashes {
+                    entries: vec![FileHash {
+                        path: PathBuf::from("path"),
+                        digest: String::from("abcdef"),
+                    }],
+                }
             );
         }
     }
 
-    mod exists {
+    mod block_checksums {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Response::Exists { value: true };
+            let payload = Response::BlockChecksums {
+                len: 123,
+                checksums: vec![String::from("abc"), String::from("def")],
+            };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "exists",
-                    "value": true,
+                    "type": "block_checksums",
+                    "len": 123,
+                    "checksums": ["abc", "def"],
                 })
             );
         }
@@ -843,17 +1231,27 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "exists",
-                "value": true,
+                "type": "block_checksums",
+                "len": 123,
+                "checksums": ["abc", "def"],
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
-            assert_eq!(payload, Response::Exists { value: true });
+            assert_eq!(
+                payload,
+                Response::BlockChecksums {
+                    len: 123,
+                    checksums: vec![String::from("abc"), String::from("def")],
+                }
+            );
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::Exists { value: true };
+            let payload = Response::BlockChecksums {
+                len: 123,
+                checksums: vec![String::from("abc"), String::from("def")],
+            };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -868,125 +1266,78 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::Exists { value: true }).unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Response::BlockChecksums {
+                len: 123,
+                checksums: vec![String::from("abc"), String::from("def")],
+            })
+            .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(payload, Response::Exists { value: true });
+            assert_eq!(
+                payload,
+                Response::BlockChecksums {
+                    len: 123,
+                    checksums: vec![String::from("abc"), String::from("def")],
+                }
+            );
         }
     }
 
-    mod metadata {
+    mod changed {
         use std::path::PathBuf;
 
         use super::*;
-        use crate::common::{FileType, UnixMetadata, WindowsMetadata};
+        use crate::common::{ChangeDetails, ChangeDetailsAttribute, ChangeKind};
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Response::Metadata(Metadata {
-                canonicalized_path: None,
-                file_type: FileType::File,
-                len: 0,
-                readonly: false,
-                accessed: None,
-                created: None,
-                modified: None,
-                unix: None,
-                windows: None,
+            let payload = Response::Changed(Change {
+                timestamp: u64::MAX,
+                kind: ChangeKind::Access,
+                path: PathBuf::from("path"),
+                details: ChangeDetails::default(),
             });
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "metadata",
-                    "file_type": "file",
-                    "len": 0,
-                    "readonly": false,
+                    "type": "changed",
+                    "timestamp": u64::MAX,
+                    "kind": "access",
+                    "path": "path",
                 })
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Response::Metadata(Metadata {
-                canonicalized_path: Some(PathBuf::from("path")),
-                file_type: FileType::File,
-                len: u64::MAX,
-                readonly: true,
-                accessed: Some(u64::MAX),
-                created: Some(u64::MAX),
-                modified: Some(u64::MAX),
-                unix: Some(UnixMetadata {
-                    owner_read: true,
-                    owner_write: false,
-                    owner_exec: false,
-                    group_read: true,
-                    group_write: false,
-                    group_exec: false,
-                    other_read: true,
-                    other_write: false,
-                    other_exec: false,
-                }),
-                windows: Some(WindowsMetadata {
-                    archive: true,
-                    compressed: false,
-                    encrypted: true,
-                    hidden: false,
-                    integrity_stream: true,
-                    normal: false,
-                    not_content_indexed: true,
-                    no_scrub_data: false,
-                    offline: true,
-                    recall_on_data_access: false,
-                    recall_on_open: true,
-                    reparse_point: false,
-                    sparse_file: true,
-                    system: false,
-                    temporary: true,
-                }),
+            let payload = Response::Changed(Change {
+                timestamp: u64::MAX,
+                kind: ChangeKind::Access,
+                path: PathBuf::from("path"),
+                details: ChangeDetails {
+                    attribute: Some(ChangeDetailsAttribute::Permissions),
+                    renamed: Some(PathBuf::from("renamed")),
+                    timestamp: Some(u64::MAX),
+                    extra: Some(String::from("info")),
+                },
             });
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "metadata",
-                    "canonicalized_path": "path",
-                    "file_type": "file",
-                    "len": u64::MAX,
-                    "readonly": true,
-                    "accessed": u64::MAX,
-                    "created": u64::MAX,
-                    "modified": u64::MAX,
-                    "unix": {
-                        "owner_read": true,
-                        "owner_write": false,
-                        "owner_exec": false,
-                        "group_read": true,
-                        "group_write": false,
-                        "group_exec": false,
-                        "other_read": true,
-                        "other_write": false,
-                        "other_exec": false,
+                    "type": "changed",
+                    "timestamp": u64::MAX,
+                    "kind": "access",
+                    "path": "path",
+                    "details": {
+                        "attribute": "permissions",
+                        "renamed": "renamed",
+                        "timestamp": u64::MAX,
+                        "extra": "info",
                     },
-                    "windows": {
-                        "archive": true,
-                        "compressed": false,
-                        "encrypted": true,
-                        "hidden": false,
-                        "integrity_stream": true,
-                        "normal": false,
-                        "not_content_indexed": true,
-                        "no_scrub_data": false,
-                        "offline": true,
-                        "recall_on_data_access": false,
-                        "recall_on_open": true,
-                        "reparse_point": false,
-                        "sparse_file": true,
-                        "system": false,
-                        "temporary": true,
-                    }
                 })
             );
         }
@@ -994,25 +1345,20 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "metadata",
-                "file_type": "file",
-                "len": 0,
-                "readonly": false,
+                "type": "changed",
+                "timestamp": u64::MAX,
+                "kind": "access",
+                "path": "path",
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Response::Metadata(Metadata {
-                    canonicalized_path: None,
-                    file_type: FileType::File,
-                    len: 0,
-                    readonly: false,
-                    accessed: None,
-                    created: None,
-                    modified: None,
-                    unix: None,
-                    windows: None,
+                Response::Changed(Change {
+                    timestamp: u64::MAX,
+                    kind: ChangeKind::Access,
+                    path: PathBuf::from("path"),
+                    details: ChangeDetails::default(),
                 })
             );
         }
@@ -1020,99 +1366,42 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "metadata",
-                "canonicalized_path": "path",
-                "file_type": "file",
-                "len": u64::MAX,
-                "readonly": true,
-                "accessed": u64::MAX,
-                "created": u64::MAX,
-                "modified": u64::MAX,
-                "unix": {
-                    "owner_read": true,
-                    "owner_write": false,
-                    "owner_exec": false,
-                    "group_read": true,
-                    "group_write": false,
-                    "group_exec": false,
-                    "other_read": true,
-                    "other_write": false,
-                    "other_exec": false,
+                "type": "changed",
+                "timestamp": u64::MAX,
+                "kind": "access",
+                "path": "path",
+                "details": {
+                    "attribute": "permissions",
+                    "renamed": "renamed",
+                    "timestamp": u64::MAX,
+                    "extra": "info",
                 },
-                "windows": {
-                    "archive": true,
-                    "compressed": false,
-                    "encrypted": true,
-                    "hidden": false,
-                    "integrity_stream": true,
-                    "normal": false,
-                    "not_content_indexed": true,
-                    "no_scrub_data": false,
-                    "offline": true,
-                    "recall_on_data_access": false,
-                    "recall_on_open": true,
-                    "reparse_point": false,
-                    "sparse_file": true,
-                    "system": false,
-                    "temporary": true,
-                }
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Response::Metadata(Metadata {
-                    canonicalized_path: Some(PathBuf::from("path")),
-                    file_type: FileType::File,
-                    len: u64::MAX,
-                    readonly: true,
-                    accessed: Some(u64::MAX),
-                    created: Some(u64::MAX),
-                    modified: Some(u64::MAX),
-                    unix: Some(UnixMetadata {
-                        owner_read: true,
-                        owner_write: false,
-                        owner_exec: false,
-                        group_read: true,
-                        group_write: false,
-                        group_exec: false,
-                        other_read: true,
-                        other_write: false,
-                        other_exec: false,
-                    }),
-                    windows: Some(WindowsMetadata {
-                        archive: true,
-                        compressed: false,
-                        encrypted: true,
-                        hidden: false,
-                        integrity_stream: true,
-                        normal: false,
-                        not_content_indexed: true,
-                        no_scrub_data: false,
-                        offline: true,
-                        recall_on_data_access: false,
-                        recall_on_open: true,
-                        reparse_point: false,
-                        sparse_file: true,
-                        system: false,
-                        temporary: true,
-                    }),
+                Response::Changed(Change {
+                    timestamp: u64::MAX,
+                    kind: ChangeKind::Access,
+                    path: PathBuf::from("path"),
+                    details: ChangeDetails {
+                        attribute: Some(ChangeDetailsAttribute::Permissions),
+                        renamed: Some(PathBuf::from("renamed")),
+                        timestamp: Some(u64::MAX),
+                        extra: Some(String::from("info")),
+                    },
                 })
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Response::Metadata(Metadata {
-                canonicalized_path: None,
-                file_type: FileType::File,
-                len: 0,
-                readonly: false,
-                accessed: None,
-                created: None,
-                modified: None,
-                unix: None,
-                windows: None,
+            let payload = Response::Changed(Change {
+                timestamp: u64::MAX,
+                kind: ChangeKind::Access,
+                path: PathBuf::from("path"),
+                details: ChangeDetails::default(),
             });
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1124,42 +1413,16 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Response::Metadata(Metadata {
-                canonicalized_path: Some(PathBuf::from("path")),
-                file_type: FileType::File,
-                len: u64::MAX,
-                readonly: true,
-                accessed: Some(u64::MAX),
-                created: Some(u64::MAX),
-                modified: Some(u64::MAX),
-                unix: Some(UnixMetadata {
-                    owner_read: true,
-                    owner_write: false,
-                    owner_exec: false,
-                    group_read: true,
-                    group_write: false,
-                    group_exec: false,
-                    other_read: true,
-                    other_write: false,
-                    other_exec: false,
-                }),
-                windows: Some(WindowsMetadata {
-                    archive: true,
-                    compressed: false,
-                    encrypted: true,
-                    hidden: false,
-                    integrity_stream: true,
-                    normal: false,
-                    not_content_indexed: true,
-                    no_scrub_data: false,
-                    offline: true,
-                    recall_on_data_access: false,
-                    recall_on_open: true,
-                    reparse_point: false,
-                    sparse_file: true,
-                    system: false,
-                    temporary: true,
-                }),
+            let payload = Response::Changed(Change {
+                timestamp: u64::MAX,
+                kind: ChangeKind::Access,
+                path: PathBuf::from("path"),
+                details: ChangeDetails {
+                    attribute: Some(ChangeDetailsAttribute::Permissions),
+                    renamed: Some(PathBuf::from("renamed")),
+                    timestamp: Some(u64::MAX),
+                    extra: Some(String::from("info")),
+                },
             });
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1175,32 +1438,22 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::Metadata(Metadata {
-                canonicalized_path: None,
-                file_type: FileType::File,
-                len: 0,
-                readonly: false,
-                accessed: None,
-                created: None,
-                modified: None,
-                unix: None,
-                windows: None,
+            let buf = rmp_serde::encode::to_vec_named(&Response::Changed(Change {
+                timestamp: u64::MAX,
+                kind: ChangeKind::Access,
+                path: PathBuf::from("path"),
+                details: ChangeDetails::default(),
             }))
             .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::Metadata(Metadata {
-                    canonicalized_path: None,
-                    file_type: FileType::File,
-                    len: 0,
-                    readonly: false,
-                    accessed: None,
-                    created: None,
-                    modified: None,
-                    unix: None,
-                    windows: None,
+                Response::Changed(Change {
+                    timestamp: u64::MAX,
+                    kind: ChangeKind::Access,
+                    path: PathBuf::from("path"),
+                    details: ChangeDetails::default(),
                 })
             );
         }
@@ -1211,7 +1464,124 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::Metadata(Metadata {
+            let buf = rmp_serde::encode::to_vec_named(&Response::Changed(Change {
+                timestamp: u64::MAX,
+                kind: ChangeKind::Access,
+                path: PathBuf::from("path"),
+                details: ChangeDetails {
+                    attribute: Some(ChangeDetailsAttribute::Permissions),
+                    renamed: Some(PathBuf::from("renamed")),
+                    timestamp: Some(u64::MAX),
+                    extra: Some(String::from("info")),
+                },
+            }))
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::Changed(Change {
+                    timestamp: u64::MAX,
+                    kind: ChangeKind::Access,
+                    path: PathBuf::from("path"),
+                    details: ChangeDetails {
+                        attribute: Some(ChangeDetailsAttribute::Permissions),
+                        renamed: Some(PathBuf::from("renamed")),
+                        timestamp: Some(u64::MAX),
+                        extra: Some(String::from("info")),
+                    },
+                })
+            );
+        }
+    }
+
+    mod exists {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::Exists { value: true };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "exists",
+                    "value": true,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "exists",
+                "value": true,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Response::Exists { value: true });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::Exists { value: true };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::Exists { value: true }).unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Response::Exists { value: true });
+        }
+    }
+
+    mod metadata {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::common::{FileType, UnixMetadata, WindowsMetadata};
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Response::Metadata(Metadata {
+                canonicalized_path: None,
+                file_type: FileType::File,
+                len: 0,
+                readonly: false,
+                accessed: None,
+                created: None,
+                modified: None,
+                unix: None,
+                windows: None,
+            });
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "metadata",
+                    "file_type": "file",
+                    "len": 0,
+                    "readonly": false,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Response::Metadata(Metadata {
                 canonicalized_path: Some(PathBuf::from("path")),
                 file_type: FileType::File,
                 len: u64::MAX,
@@ -1247,10 +1617,120 @@ mod tests {
                     system: false,
                     temporary: true,
                 }),
-            }))
-            .unwrap();
+            });
 
-            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "metadata",
+                    "canonicalized_path": "path",
+                    "file_type": "file",
+                    "len": u64::MAX,
+                    "readonly": true,
+                    "accessed": u64::MAX,
+                    "created": u64::MAX,
+                    "modified": u64::MAX,
+                    "unix": {
+                        "owner_read": true,
+                        "owner_write": false,
+                        "owner_exec": false,
+                        "group_read": true,
+                        "group_write": false,
+                        "group_exec": false,
+                        "other_read": true,
+                        "other_write": false,
+                        "other_exec": false,
+                    },
+                    "windows": {
+                        "archive": true,
+                        "compressed": false,
+                        "encrypted": true,
+                        "hidden": false,
+                        "integrity_stream": true,
+                        "normal": false,
+                        "not_content_indexed": true,
+                        "no_scrub_data": false,
+                        "offline": true,
+                        "recall_on_data_access": false,
+                        "recall_on_open": true,
+                        "reparse_point": false,
+                        "sparse_file": true,
+                        "system": false,
+                        "temporary": true,
+                    }
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "metadata",
+                "file_type": "file",
+                "len": 0,
+                "readonly": false,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::Metadata(Metadata {
+                    canonicalized_path: None,
+                    file_type: FileType::File,
+                    len: 0,
+                    readonly: false,
+                    accessed: None,
+                    created: None,
+                    modified: None,
+                    unix: None,
+                    windows: None,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "metadata",
+                "canonicalized_path": "path",
+                "file_type": "file",
+                "len": u64::MAX,
+                "readonly": true,
+                "accessed": u64::MAX,
+                "created": u64::MAX,
+                "modified": u64::MAX,
+                "unix": {
+                    "owner_read": true,
+                    "owner_write": false,
+                    "owner_exec": false,
+                    "group_read": true,
+                    "group_write": false,
+                    "group_exec": false,
+                    "other_read": true,
+                    "other_write": false,
+                    "other_exec": false,
+                },
+                "windows": {
+                    "archive": true,
+                    "compressed": false,
+                    "encrypted": true,
+                    "hidden": false,
+                    "integrity_stream": true,
+                    "normal": false,
+                    "not_content_indexed": true,
+                    "no_scrub_data": false,
+                    "offline": true,
+                    "recall_on_data_access": false,
+                    "recall_on_open": true,
+                    "reparse_point": false,
+                    "sparse_file": true,
+                    "system": false,
+                    "temporary": true,
+                }
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
                 Response::Metadata(Metadata {
@@ -1292,7 +1772,199 @@ mod tests {
                 })
             );
         }
-    }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Response::Metadata(Metadata {
+                canonicalized_path: None,
+                file_type: FileType::File,
+                len: 0,
+                readonly: false,
+                accessed: None,
+                created: None,
+                modified: None,
+                unix: None,
+                windows: None,
+            });
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Response::Metadata(Metadata {
+                canonicalized_path: Some(PathBuf::from("path")),
+                file_type: FileType::File,
+                len: u64::MAX,
+                readonly: true,
+                accessed: Some(u64::MAX),
+                created: Some(u64::MAX),
+                modified: Some(u64::MAX),
+                unix: Some(UnixMetadata {
+                    owner_read: true,
+                    owner_write: false,
+                    owner_exec: false,
+                    group_read: true,
+                    group_write: false,
+                    group_exec: false,
+                    other_read: true,
+                    other_write: false,
+                    other_exec: false,
+                }),
+                windows: Some(WindowsMetadata {
+                    archive: true,
+                    compressed: false,
+                    encrypted: true,
+                    hidden: false,
+                    integrity_stream: true,
+                    normal: false,
+                    not_content_indexed: true,
+                    no_scrub_data: false,
+                    offline: true,
+                    recall_on_data_access: false,
+                    recall_on_open: true,
+                    reparse_point: false,
+                    sparse_file: true,
+                    system: false,
+                    temporary: true,
+                }),
+            });
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::Metadata(Metadata {
+                canonicalized_path: None,
+                file_type: FileType::File,
+                len: 0,
+                readonly: false,
+                accessed: None,
+                created: None,
+                modified: None,
+                unix: None,
+                windows: None,
+            }))
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::Metadata(Metadata {
+                    canonicalized_path: None,
+                    file_type: FileType::File,
+                    len: 0,
+                    readonly: false,
+                    accessed: None,
+                    created: None,
+                    modified: None,
+                    unix: None,
+                    windows: None,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::Metadata(Metadata {
+                canonicalized_path: Some(PathBuf::from("path")),
+                file_type: FileType::File,
+                len: u64::MAX,
+                readonly: true,
+                accessed: Some(u64::MAX),
+                created: Some(u64::MAX),
+                modified: Some(u64::MAX),
+                unix: Some(UnixMetadata {
+                    owner_read: true,
+                    owner_write: false,
+                    owner_exec: false,
+                    group_read: true,
+                    group_write: false,
+                    group_exec: false,
+                    other_read: true,
+                    other_write: false,
+                    other_exec: false,
+                }),
+                windows: Some(WindowsMetadata {
+                    archive: true,
+                    compressed: false,
+                    encrypted: true,
+                    hidden: false,
+                    integrity_stream: true,
+                    normal: false,
+                    not_content_indexed: true,
+                    no_scrub_data: false,
+                    offline: true,
+                    recall_on_data_access: false,
+                    recall_on_open: true,
+                    reparse_point: false,
+                    sparse_file: true,
+                    system: false,
+                    temporary: true,
+                }),
+            }))
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::Metadata(Metadata {
+                    canonicalized_path: Some(PathBuf::from("path")),
+                    file_type: FileType::File,
+                    len: u64::MAX,
+                    readonly: true,
+                    accessed: Some(u64::MAX),
+                    created: Some(u64::MAX),
+                    modified: Some(u64::MAX),
+                    unix: Some(UnixMetadata {
+                        owner_read: true,
+                        owner_write: false,
+                        owner_exec: false,
+                        group_read: true,
+                        group_write: false,
+                        group_exec: false,
+                        other_read: true,
+                        other_write: false,
+                        other_exec: false,
+                    }),
+                    windows: Some(WindowsMetadata {
+                        archive: true,
+                        compressed: false,
+                        encrypted: true,
+                        hidden: false,
+                        integrity_stream: true,
+                        normal: false,
+                        not_content_indexed: true,
+                        no_scrub_data: false,
+                        offline: true,
+                        recall_on_data_access: false,
+                        recall_on_open: true,
+                        reparse_point: false,
+                        sparse_file: true,
+                        system: false,
+                        temporary: true,
+                    }),
+                })
+            );
+        }
+    }
 
     mod search_started {
         use super::*;
@@ -1312,21 +1984,982 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_json() {
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "search_started",
+                "id": SearchId::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Response::SearchStarted { id: SearchId::MAX });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::SearchStarted { id: SearchId::MAX };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf =
+                rmp_serde::encode::to_vec_named(&Response::SearchStarted { id: SearchId::MAX })
+                    .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Response::SearchStarted { id: SearchId::MAX });
+        }
+    }
+
+    mod search_results {
+        use std::path::PathBuf;
+
+        use super::*;
+        use crate::common::{SearchQueryContentsMatch, SearchQueryMatch, SearchQuerySubmatch};
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::SearchResults {
+                id: SearchId::MAX,
+                matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
+                    path: PathBuf::from("path"),
+                    lines: "some lines".into(),
+                    line_number: u64::MAX,
+                    absolute_offset: u64::MAX,
+                    submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
+                    context: Vec::new(),
+                })],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "search_results",
+                    "id": SearchId::MAX,
+                    "matches": [{
+                        "type": "contents",
+                        "path": "path",
+                        "lines": "some lines",
+                        "line_number": u64::MAX,
+                        "absolute_offset": u64::MAX,
+                        "submatches": [{
+                            "match": "text",
+                            "start": u64::MAX,
+                            "end": u64::MAX,
+                        }],
+                    }],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "search_results",
+                "id": SearchId::MAX,
+                "matches": [{
+                    "type": "contents",
+                    "path": "path",
+                    "lines": "some lines",
+                    "line_number": u64::MAX,
+                    "absolute_offset": u64::MAX,
+                    "submatches": [{
+                        "match": "text",
+                        "start": u64::MAX,
+                        "end": u64::MAX,
+                    }],
+                }],
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::SearchResults {
+                    id: SearchId::MAX,
+                    matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
+                        path: PathBuf::from("path"),
+                        lines: "some lines".into(),
+                        line_number: u64::MAX,
+                        absolute_offset: u64::MAX,
+                        submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
+                        context: Vec::new(),
+                    })],
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::SearchResults {
+                id: SearchId::MAX,
+                matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
+                    path: PathBuf::from("path"),
+                    lines: "some lines".into(),
+                    line_number: u64::MAX,
+                    absolute_offset: u64::MAX,
+                    submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
+                    context: Vec::new(),
+                })],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is results. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::SearchResults {
+                id: SearchId::MAX,
+                matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
+                    path: PathBuf::from("path"),
+                    lines: "some lines".into(),
+                    line_number: u64::MAX,
+                    absolute_offset: u64::MAX,
+                    submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
+                    context: Vec::new(),
+                })],
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::SearchResults {
+                    id: SearchId::MAX,
+                    matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
+                        path: PathBuf::from("path"),
+                        lines: "some lines".into(),
+                        line_number: u64::MAX,
+                        absolute_offset: u64::MAX,
+                        submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
+                        context: Vec::new(),
+                    })],
+                }
+            );
+        }
+    }
+
+    mod search_done {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::SearchDone { id: SearchId::MAX };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "search_done",
+                    "id": SearchId::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "search_done",
+                "id": SearchId::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Response::SearchDone { id: SearchId::MAX });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::SearchDone { id: SearchId::MAX };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::SearchDone { id: SearchId::MAX })
+                .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Response::SearchDone { id: SearchId::MAX });
+        }
+    }
+
+    mod replaced {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Response::Replaced {
+                path: PathBuf::from("path"),
+                replacements: u64::MAX,
+                diff: None,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "replaced",
+                    "path": "path",
+                    "replacements": u64::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Response::Replaced {
+                path: PathBuf::from("path"),
+                replacements: u64::MAX,
+                diff: Some(String::from("-old\n+new\n")),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "replaced",
+                    "path": "path",
+                    "replacements": u64::MAX,
+                    "diff": "-old\n+new\n",
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "replaced",
+                "path": "path",
+                "replacements": u64::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::Replaced {
+                    path: PathBuf::from("path"),
+                    replacements: u64::MAX,
+                    diff: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "replaced",
+                "path": "path",
+                "replacements": u64::MAX,
+                "diff": "-old\n+new\n",
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::Replaced {
+                    path: PathBuf::from("path"),
+                    replacements: u64::MAX,
+                    diff: Some(String::from("-old\n+new\n")),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Response::Replaced {
+                path: PathBuf::from("path"),
+                replacements: u64::MAX,
+                diff: None,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Response::Replaced {
+                path: PathBuf::from("path"),
+                replacements: u64::MAX,
+                diff: Some(String::from("-old\n+new\n")),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::Replaced {
+                path: PathBuf::from("path"),
+                replacements: u64::MAX,
+                diff: None,
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::Replaced {
+                    path: PathBuf::from("path"),
+                    replacements: u64::MAX,
+                    diff: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::Replaced {
+                path: PathBuf::from("path"),
+                replacements: u64::MAX,
+                diff: Some(String::from("-old\n+new\n")),
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::Replaced {
+                    path: PathBuf::from("path"),
+                    replacements: u64::MAX,
+                    diff: Some(String::from("-old\n+new\n")),
+                }
+            );
+        }
+    }
+
+    mod replace_done {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::ReplaceDone {
+                files: u64::MAX,
+                replacements: u64::MAX,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "replace_done",
+                    "files": u64::MAX,
+                    "replacements": u64::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "replace_done",
+                "files": u64::MAX,
+                "replacements": u64::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ReplaceDone {
+                    files: u64::MAX,
+                    replacements: u64::MAX,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::ReplaceDone {
+                files: u64::MAX,
+                replacements: u64::MAX,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ReplaceDone {
+                files: u64::MAX,
+                replacements: u64::MAX,
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ReplaceDone {
+                    files: u64::MAX,
+                    replacements: u64::MAX,
+                }
+            );
+        }
+    }
+
+    mod proc_spawned {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::ProcSpawned { id: ProcessId::MAX };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_spawned",
+                    "id": ProcessId::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_spawned",
+                "id": ProcessId::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(payload, Response::ProcSpawned { id: ProcessId::MAX });
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::ProcSpawned { id: ProcessId::MAX };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf =
+                rmp_serde::encode::to_vec_named(&Response::ProcSpawned { id: ProcessId::MAX })
+                    .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(payload, Response::ProcSpawned { id: ProcessId::MAX });
+        }
+    }
+
+    mod proc_stdout {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Response::ProcStdout {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: None,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_stdout",
+                    "id": ProcessId::MAX,
+                    "data": vec![0, 1, 2, u8::MAX],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Response::ProcStdout {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: Some(u64::MAX),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_stdout",
+                    "id": ProcessId::MAX,
+                    "data": vec![0, 1, 2, u8::MAX],
+                    "seq": u64::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_stdout",
+                "id": ProcessId::MAX,
+                "data": vec![0, 1, 2, u8::MAX],
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStdout {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_stdout",
+                "id": ProcessId::MAX,
+                "data": vec![0, 1, 2, u8::MAX],
+                "seq": u64::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStdout {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: Some(u64::MAX),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Response::ProcStdout {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: None,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Response::ProcStdout {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: Some(u64::MAX),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcStdout {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: None,
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStdout {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcStdout {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: Some(u64::MAX),
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStdout {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: Some(u64::MAX),
+                }
+            );
+        }
+    }
+
+    mod proc_stderr {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Response::ProcStderr {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: None,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_stderr",
+                    "id": ProcessId::MAX,
+                    "data": vec![0, 1, 2, u8::MAX],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Response::ProcStderr {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: Some(u64::MAX),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_stderr",
+                    "id": ProcessId::MAX,
+                    "data": vec![0, 1, 2, u8::MAX],
+                    "seq": u64::MAX,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_stderr",
+                "id": ProcessId::MAX,
+                "data": vec![0, 1, 2, u8::MAX],
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStderr {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_stderr",
+                "id": ProcessId::MAX,
+                "data": vec![0, 1, 2, u8::MAX],
+                "seq": u64::MAX,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStderr {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: Some(u64::MAX),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Response::ProcStderr {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: None,
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Response::ProcStderr {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: Some(u64::MAX),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcStderr {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: None,
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStderr {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcStderr {
+                id: ProcessId::MAX,
+                data: vec![0, 1, 2, u8::MAX],
+                seq: Some(u64::MAX),
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcStderr {
+                    id: ProcessId::MAX,
+                    data: vec![0, 1, 2, u8::MAX],
+                    seq: Some(u64::MAX),
+                }
+            );
+        }
+    }
+
+    mod proc_done {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Response::ProcDone {
+                id: ProcessId::MAX,
+                success: false,
+                code: None,
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_done",
+                    "id": ProcessId::MAX,
+                    "success": false,
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Response::ProcDone {
+                id: ProcessId::MAX,
+                success: true,
+                code: Some(i32::MAX),
+                limit: Some(ProcessLimit::CpuTime),
+                timed_out: true,
+                signal: Some(i32::MAX),
+                core_dumped: true,
+                usage: Some(ProcessUsage {
+                    user_time: 1,
+                    system_time: 2,
+                    max_rss: 3,
+                }),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_done",
+                    "id": ProcessId::MAX,
+                    "success": true,
+                    "code": i32::MAX,
+                    "limit": "cpu_time",
+                    "timed_out": true,
+                    "signal": i32::MAX,
+                    "core_dumped": true,
+                    "usage": {
+                        "user_time": 1,
+                        "system_time": 2,
+                        "max_rss": 3,
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_done",
+                "id": ProcessId::MAX,
+                "success": false,
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcDone {
+                    id: ProcessId::MAX,
+                    success: false,
+                    code: None,
+                    limit: None,
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
             let value = serde_json::json!({
-                "type": "search_started",
-                "id": SearchId::MAX,
+                "type": "proc_done",
+                "id": ProcessId::MAX,
+                "success": true,
+                "code": i32::MAX,
+                "limit": "cpu_time",
+                "timed_out": true,
+                "signal": i32::MAX,
+                "core_dumped": true,
+                "usage": {
+                    "user_time": 1,
+                    "system_time": 2,
+                    "max_rss": 3,
+                },
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
-            assert_eq!(payload, Response::SearchStarted { id: SearchId::MAX });
+            assert_eq!(
+                payload,
+                Response::ProcDone {
+                    id: ProcessId::MAX,
+                    success: true,
+                    code: Some(i32::MAX),
+                    limit: Some(ProcessLimit::CpuTime),
+                    timed_out: true,
+                    signal: Some(i32::MAX),
+                    core_dumped: true,
+                    usage: Some(ProcessUsage {
+                        user_time: 1,
+                        system_time: 2,
+                        max_rss: 3,
+                    }),
+                }
+            );
         }
 
         #[test]
-        fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::SearchStarted { id: SearchId::MAX };
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Response::ProcDone {
+                id: ProcessId::MAX,
+                success: false,
+                code: None,
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
+            };
 
-            // NOTE: We don't actually check the output here because it's an implementation detail
+            // NOTE: We don't actually check the errput here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
             // that we can serialize since there are times when serde fails to serialize at
             // runtime.
@@ -1334,164 +2967,299 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_msgpack() {
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Response::ProcDone {
+                id: ProcessId::MAX,
+                success: true,
+                code: Some(i32::MAX),
+                limit: Some(ProcessLimit::CpuTime),
+                timed_out: true,
+                signal: Some(i32::MAX),
+                core_dumped: true,
+                usage: Some(ProcessUsage {
+                    user_time: 1,
+                    system_time: 2,
+                    max_rss: 3,
+                }),
+            };
+
+            // NOTE: We don't actually check the errput here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf =
-                rmp_serde::encode::to_vec_named(&Response::SearchStarted { id: SearchId::MAX })
-                    .unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcDone {
+                id: ProcessId::MAX,
+                success: false,
+                code: None,
+                limit: None,
+                timed_out: false,
+                signal: None,
+                core_dumped: false,
+                usage: None,
+            })
+            .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(payload, Response::SearchStarted { id: SearchId::MAX });
+            assert_eq!(
+                payload,
+                Response::ProcDone {
+                    id: ProcessId::MAX,
+                    success: false,
+                    code: None,
+                    limit: None,
+                    timed_out: false,
+                    signal: None,
+                    core_dumped: false,
+                    usage: None,
+                }
+            );
         }
-    }
 
-    mod search_results {
-        use std::path::PathBuf;
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcDone {
+                id: ProcessId::MAX,
+                success: true,
+                code: Some(i32::MAX),
+                limit: Some(ProcessLimit::CpuTime),
+                timed_out: true,
+                signal: Some(i32::MAX),
+                core_dumped: true,
+                usage: Some(ProcessUsage {
+                    user_time: 1,
+                    system_time: 2,
+                    max_rss: 3,
+                }),
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcDone {
+                    id: ProcessId::MAX,
+                    success: true,
+                    code: Some(i32::MAX),
+                    limit: Some(ProcessLimit::CpuTime),
+                    timed_out: true,
+                    signal: Some(i32::MAX),
+                    core_dumped: true,
+                    usage: Some(ProcessUsage {
+                        user_time: 1,
+                        system_time: 2,
+                        max_rss: 3,
+                    }),
+                }
+            );
+        }
+    }
 
+    mod proc_entries {
         use super::*;
-        use crate::common::{SearchQueryContentsMatch, SearchQueryMatch, SearchQuerySubmatch};
+        use crate::common::PtySize;
 
         #[test]
-        fn should_be_able_to_serialize_to_json() {
-            let payload = Response::SearchResults {
-                id: SearchId::MAX,
-                matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
-                    path: PathBuf::from("path"),
-                    lines: "some lines".into(),
-                    line_number: u64::MAX,
-                    absolute_offset: u64::MAX,
-                    submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
-                })],
+        fn should_be_able_to_serialize_minimal_payload_to_json() {
+            let payload = Response::ProcEntries {
+                entries: Vec::new(),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "search_results",
-                    "id": SearchId::MAX,
-                    "matches": [{
-                        "type": "contents",
-                        "path": "path",
-                        "lines": "some lines",
-                        "line_number": u64::MAX,
-                        "absolute_offset": u64::MAX,
-                        "submatches": [{
-                            "match": "text",
-                            "start": u64::MAX,
-                            "end": u64::MAX,
-                        }],
+                    "type": "proc_entries",
+                    "entries": [],
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_full_payload_to_json() {
+            let payload = Response::ProcEntries {
+                entries: vec![ProcessInfo {
+                    id: ProcessId::MAX,
+                    cmd: String::from("some command"),
+                    pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                    started: u64::MAX,
+                }],
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "proc_entries",
+                    "entries": [{
+                        "id": ProcessId::MAX,
+                        "cmd": "some command",
+                        "pty": {
+                            "rows": 24,
+                            "cols": 80,
+                            "pixel_width": 0,
+                            "pixel_height": 0,
+                        },
+                        "started": u64::MAX,
                     }],
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_json() {
+        fn should_be_able_to_deserialize_minimal_payload_from_json() {
             let value = serde_json::json!({
-                "type": "search_results",
-                "id": SearchId::MAX,
-                "matches": [{
-                    "type": "contents",
-                    "path": "path",
-                    "lines": "some lines",
-                    "line_number": u64::MAX,
-                    "absolute_offset": u64::MAX,
-                    "submatches": [{
-                        "match": "text",
-                        "start": u64::MAX,
-                        "end": u64::MAX,
-                    }],
+                "type": "proc_entries",
+                "entries": [],
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcEntries {
+                    entries: Vec::new()
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_json() {
+            let value = serde_json::json!({
+                "type": "proc_entries",
+                "entries": [{
+                    "id": ProcessId::MAX,
+                    "cmd": "some command",
+                    "pty": {
+                        "rows": 24,
+                        "cols": 80,
+                        "pixel_width": 0,
+                        "pixel_height": 0,
+                    },
+                    "started": u64::MAX,
                 }],
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Response::SearchResults {
-                    id: SearchId::MAX,
-                    matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
-                        path: PathBuf::from("path"),
-                        lines: "some lines".into(),
-                        line_number: u64::MAX,
-                        absolute_offset: u64::MAX,
-                        submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
-                    })],
+                Response::ProcEntries {
+                    entries: vec![ProcessInfo {
+                        id: ProcessId::MAX,
+                        cmd: String::from("some command"),
+                        pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                        started: u64::MAX,
+                    }],
                 }
             );
         }
 
         #[test]
-        fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::SearchResults {
-                id: SearchId::MAX,
-                matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
-                    path: PathBuf::from("path"),
-                    lines: "some lines".into(),
-                    line_number: u64::MAX,
-                    absolute_offset: u64::MAX,
-                    submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
-                })],
+        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
+            let payload = Response::ProcEntries {
+                entries: Vec::new(),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
-            // and could change as we change how serialization is results. This is merely to verify
+            // and could change as we change how serialization is done. This is merely to verify
             // that we can serialize since there are times when serde fails to serialize at
             // runtime.
             let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
         }
 
         #[test]
-        fn should_be_able_to_deserialize_from_msgpack() {
+        fn should_be_able_to_serialize_full_payload_to_msgpack() {
+            let payload = Response::ProcEntries {
+                entries: vec![ProcessInfo {
+                    id: ProcessId::MAX,
+                    cmd: String::from("some command"),
+                    pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                    started: u64::MAX,
+                }],
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::SearchResults {
-                id: SearchId::MAX,
-                matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
-                    path: PathBuf::from("path"),
-                    lines: "some lines".into(),
-                    line_number: u64::MAX,
-                    absolute_offset: u64::MAX,
-                    submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
-                })],
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcEntries {
+                entries: Vec::new(),
             })
             .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::SearchResults {
-                    id: SearchId::MAX,
-                    matches: vec![SearchQueryMatch::Contents(SearchQueryContentsMatch {
-                        path: PathBuf::from("path"),
-                        lines: "some lines".into(),
-                        line_number: u64::MAX,
-                        absolute_offset: u64::MAX,
-                        submatches: vec![SearchQuerySubmatch::new("text", u64::MAX, u64::MAX)],
-                    })],
+                Response::ProcEntries {
+                    entries: Vec::new()
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::ProcEntries {
+                entries: vec![ProcessInfo {
+                    id: ProcessId::MAX,
+                    cmd: String::from("some command"),
+                    pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                    started: u64::MAX,
+                }],
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::ProcEntries {
+                    entries: vec![ProcessInfo {
+                        id: ProcessId::MAX,
+                        cmd: String::from("some command"),
+                        pty: Some(PtySize::from_rows_and_cols(24, 80)),
+                        started: u64::MAX,
+                    }],
                 }
             );
         }
     }
 
-    mod search_done {
+    mod tcp_connected {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Response::SearchDone { id: SearchId::MAX };
+            let payload = Response::TcpConnected { id: u32::MAX };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "search_done",
-                    "id": SearchId::MAX,
+                    "type": "tcp_connected",
+                    "id": u32::MAX,
                 })
             );
         }
@@ -1499,17 +3267,17 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "search_done",
-                "id": SearchId::MAX,
+                "type": "tcp_connected",
+                "id": u32::MAX,
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
-            assert_eq!(payload, Response::SearchDone { id: SearchId::MAX });
+            assert_eq!(payload, Response::TcpConnected { id: u32::MAX });
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::SearchDone { id: SearchId::MAX };
+            let payload = Response::TcpConnected { id: u32::MAX };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -1524,27 +3292,31 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::SearchDone { id: SearchId::MAX })
-                .unwrap();
+            let buf =
+                rmp_serde::encode::to_vec_named(&Response::TcpConnected { id: u32::MAX }).unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(payload, Response::SearchDone { id: SearchId::MAX });
+            assert_eq!(payload, Response::TcpConnected { id: u32::MAX });
         }
     }
 
-    mod proc_spawned {
+    mod tcp_listening {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Response::ProcSpawned { id: ProcessId::MAX };
+            let payload = Response::TcpListening {
+                id: u32::MAX,
+                port: 8080,
+            };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_spawned",
-                    "id": ProcessId::MAX,
+                    "type": "tcp_listening",
+                    "id": u32::MAX,
+                    "port": 8080,
                 })
             );
         }
@@ -1552,17 +3324,27 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_spawned",
-                "id": ProcessId::MAX,
+                "type": "tcp_listening",
+                "id": u32::MAX,
+                "port": 8080,
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
-            assert_eq!(payload, Response::ProcSpawned { id: ProcessId::MAX });
+            assert_eq!(
+                payload,
+                Response::TcpListening {
+                    id: u32::MAX,
+                    port: 8080,
+                }
+            );
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::ProcSpawned { id: ProcessId::MAX };
+            let payload = Response::TcpListening {
+                id: u32::MAX,
+                port: 8080,
+            };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
@@ -1577,32 +3359,42 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf =
-                rmp_serde::encode::to_vec_named(&Response::ProcSpawned { id: ProcessId::MAX })
-                    .unwrap();
+            let buf = rmp_serde::encode::to_vec_named(&Response::TcpListening {
+                id: u32::MAX,
+                port: 8080,
+            })
+            .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(payload, Response::ProcSpawned { id: ProcessId::MAX });
+            assert_eq!(
+                payload,
+                Response::TcpListening {
+                    id: u32::MAX,
+                    port: 8080,
+                }
+            );
         }
     }
 
-    mod proc_stdout {
+    mod tcp_accepted {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Response::ProcStdout {
-                id: ProcessId::MAX,
-                data: vec![0, 1, 2, u8::MAX],
+            let payload = Response::TcpAccepted {
+                listener: u32::MAX,
+                id: 123,
+                peer_addr: String::from("127.0.0.1:5000"),
             };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_stdout",
-                    "id": ProcessId::MAX,
-                    "data": vec![0, 1, 2, u8::MAX],
+                    "type": "tcp_accepted",
+                    "listener": u32::MAX,
+                    "id": 123,
+                    "peer_addr": "127.0.0.1:5000",
                 })
             );
         }
@@ -1610,26 +3402,29 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_stdout",
-                "id": ProcessId::MAX,
-                "data": vec![0, 1, 2, u8::MAX],
+                "type": "tcp_accepted",
+                "listener": u32::MAX,
+                "id": 123,
+                "peer_addr": "127.0.0.1:5000",
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Response::ProcStdout {
-                    id: ProcessId::MAX,
-                    data: vec![0, 1, 2, u8::MAX],
+                Response::TcpAccepted {
+                    listener: u32::MAX,
+                    id: 123,
+                    peer_addr: String::from("127.0.0.1:5000"),
                 }
             );
         }
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::ProcStdout {
-                id: ProcessId::MAX,
-                data: vec![0, 1, 2, u8::MAX],
+            let payload = Response::TcpAccepted {
+                listener: u32::MAX,
+                id: 123,
+                peer_addr: String::from("127.0.0.1:5000"),
             };
 
             // NOTE: We don't actually check the output here because it's an implementation detail
@@ -1645,30 +3440,32 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::ProcStdout {
-                id: ProcessId::MAX,
-                data: vec![0, 1, 2, u8::MAX],
+            let buf = rmp_serde::encode::to_vec_named(&Response::TcpAccepted {
+                listener: u32::MAX,
+                id: 123,
+                peer_addr: String::from("127.0.0.1:5000"),
             })
             .unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::ProcStdout {
-                    id: ProcessId::MAX,
-                    data: vec![0, 1, 2, u8::MAX],
+                Response::TcpAccepted {
+                    listener: u32::MAX,
+                    id: 123,
+                    peer_addr: String::from("127.0.0.1:5000"),
                 }
             );
         }
     }
 
-    mod proc_stderr {
+    mod tcp_data {
         use super::*;
 
         #[test]
         fn should_be_able_to_serialize_to_json() {
-            let payload = Response::ProcStderr {
-                id: ProcessId::MAX,
+            let payload = Response::TcpData {
+                id: u32::MAX,
                 data: vec![0, 1, 2, u8::MAX],
             };
 
@@ -1676,9 +3473,9 @@ mod tests {
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_stderr",
-                    "id": ProcessId::MAX,
-                    "data": vec![0, 1, 2, u8::MAX],
+                    "type": "tcp_data",
+                    "id": u32::MAX,
+                    "data": [0, 1, 2, u8::MAX],
                 })
             );
         }
@@ -1686,16 +3483,16 @@ mod tests {
         #[test]
         fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_stderr",
-                "id": ProcessId::MAX,
-                "data": vec![0, 1, 2, u8::MAX],
+                "type": "tcp_data",
+                "id": u32::MAX,
+                "data": [0, 1, 2, u8::MAX],
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
             assert_eq!(
                 payload,
-                Response::ProcStderr {
-                    id: ProcessId::MAX,
+                Response::TcpData {
+                    id: u32::MAX,
                     data: vec![0, 1, 2, u8::MAX],
                 }
             );
@@ -1703,12 +3500,12 @@ mod tests {
 
         #[test]
         fn should_be_able_to_serialize_to_msgpack() {
-            let payload = Response::ProcStderr {
-                id: ProcessId::MAX,
+            let payload = Response::TcpData {
+                id: u32::MAX,
                 data: vec![0, 1, 2, u8::MAX],
             };
 
-            // NOTE: We don't actually check the errput here because it's an implementation detail
+            // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
             // that we can serialize since there are times when serde fails to serialize at
             // runtime.
@@ -1721,8 +3518,8 @@ mod tests {
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::ProcStderr {
-                id: ProcessId::MAX,
+            let buf = rmp_serde::encode::to_vec_named(&Response::TcpData {
+                id: u32::MAX,
                 data: vec![0, 1, 2, u8::MAX],
             })
             .unwrap();
@@ -1730,119 +3527,47 @@ mod tests {
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
             assert_eq!(
                 payload,
-                Response::ProcStderr {
-                    id: ProcessId::MAX,
+                Response::TcpData {
+                    id: u32::MAX,
                     data: vec![0, 1, 2, u8::MAX],
                 }
             );
         }
     }
 
-    mod proc_done {
+    mod tcp_closed {
         use super::*;
 
         #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_json() {
-            let payload = Response::ProcDone {
-                id: ProcessId::MAX,
-                success: false,
-                code: None,
-            };
-
-            let value = serde_json::to_value(payload).unwrap();
-            assert_eq!(
-                value,
-                serde_json::json!({
-                    "type": "proc_done",
-                    "id": ProcessId::MAX,
-                    "success": false,
-                })
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_serialize_full_payload_to_json() {
-            let payload = Response::ProcDone {
-                id: ProcessId::MAX,
-                success: true,
-                code: Some(i32::MAX),
-            };
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::TcpClosed { id: u32::MAX };
 
             let value = serde_json::to_value(payload).unwrap();
             assert_eq!(
                 value,
                 serde_json::json!({
-                    "type": "proc_done",
-                    "id": ProcessId::MAX,
-                    "success": true,
-                    "code": i32::MAX,
+                    "type": "tcp_closed",
+                    "id": u32::MAX,
                 })
             );
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_json() {
-            let value = serde_json::json!({
-                "type": "proc_done",
-                "id": ProcessId::MAX,
-                "success": false,
-            });
-
-            let payload: Response = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Response::ProcDone {
-                    id: ProcessId::MAX,
-                    success: false,
-                    code: None,
-                }
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_deserialize_full_payload_from_json() {
+        fn should_be_able_to_deserialize_from_json() {
             let value = serde_json::json!({
-                "type": "proc_done",
-                "id": ProcessId::MAX,
-                "success": true,
-                "code": i32::MAX,
+                "type": "tcp_closed",
+                "id": u32::MAX,
             });
 
             let payload: Response = serde_json::from_value(value).unwrap();
-            assert_eq!(
-                payload,
-                Response::ProcDone {
-                    id: ProcessId::MAX,
-                    success: true,
-                    code: Some(i32::MAX),
-                }
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_serialize_minimal_payload_to_msgpack() {
-            let payload = Response::ProcDone {
-                id: ProcessId::MAX,
-                success: false,
-                code: None,
-            };
-
-            // NOTE: We don't actually check the errput here because it's an implementation detail
-            // and could change as we change how serialization is done. This is merely to verify
-            // that we can serialize since there are times when serde fails to serialize at
-            // runtime.
-            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+            assert_eq!(payload, Response::TcpClosed { id: u32::MAX });
         }
 
         #[test]
-        fn should_be_able_to_serialize_full_payload_to_msgpack() {
-            let payload = Response::ProcDone {
-                id: ProcessId::MAX,
-                success: true,
-                code: Some(i32::MAX),
-            };
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::TcpClosed { id: u32::MAX };
 
-            // NOTE: We don't actually check the errput here because it's an implementation detail
+            // NOTE: We don't actually check the output here because it's an implementation detail
             // and could change as we change how serialization is done. This is merely to verify
             // that we can serialize since there are times when serde fails to serialize at
             // runtime.
@@ -1850,51 +3575,16 @@ mod tests {
         }
 
         #[test]
-        fn should_be_able_to_deserialize_minimal_payload_from_msgpack() {
-            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
-            // verify that we are not corrupting or causing issues when serializing on a
-            // client/server and then trying to deserialize on the other side. This has happened
-            // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::ProcDone {
-                id: ProcessId::MAX,
-                success: false,
-                code: None,
-            })
-            .unwrap();
-
-            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(
-                payload,
-                Response::ProcDone {
-                    id: ProcessId::MAX,
-                    success: false,
-                    code: None,
-                }
-            );
-        }
-
-        #[test]
-        fn should_be_able_to_deserialize_full_payload_from_msgpack() {
+        fn should_be_able_to_deserialize_from_msgpack() {
             // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
             // verify that we are not corrupting or causing issues when serializing on a
             // client/server and then trying to deserialize on the other side. This has happened
             // enough times with minor changes that we need tests to verify.
-            let buf = rmp_serde::encode::to_vec_named(&Response::ProcDone {
-                id: ProcessId::MAX,
-                success: true,
-                code: Some(i32::MAX),
-            })
-            .unwrap();
+            let buf =
+                rmp_serde::encode::to_vec_named(&Response::TcpClosed { id: u32::MAX }).unwrap();
 
             let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
-            assert_eq!(
-                payload,
-                Response::ProcDone {
-                    id: ProcessId::MAX,
-                    success: true,
-                    code: Some(i32::MAX),
-                }
-            );
+            assert_eq!(payload, Response::TcpClosed { id: u32::MAX });
         }
     }
 
@@ -2011,6 +3701,79 @@ mod tests {
         }
     }
 
+    mod environment {
+        use super::*;
+
+        #[test]
+        fn should_be_able_to_serialize_to_json() {
+            let payload = Response::Environment {
+                vars: Environment::from([(String::from("key"), String::from("value"))]),
+            };
+
+            let value = serde_json::to_value(payload).unwrap();
+            assert_eq!(
+                value,
+                serde_json::json!({
+                    "type": "environment",
+                    "vars": {
+                        "key": "value",
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_json() {
+            let value = serde_json::json!({
+                "type": "environment",
+                "vars": {
+                    "key": "value",
+                },
+            });
+
+            let payload: Response = serde_json::from_value(value).unwrap();
+            assert_eq!(
+                payload,
+                Response::Environment {
+                    vars: Environment::from([(String::from("key"), String::from("value"))]),
+                }
+            );
+        }
+
+        #[test]
+        fn should_be_able_to_serialize_to_msgpack() {
+            let payload = Response::Environment {
+                vars: Environment::from([(String::from("key"), String::from("value"))]),
+            };
+
+            // NOTE: We don't actually check the output here because it's an implementation detail
+            // and could change as we change how serialization is done. This is merely to verify
+            // that we can serialize since there are times when serde fails to serialize at
+            // runtime.
+            let _ = rmp_serde::encode::to_vec_named(&payload).unwrap();
+        }
+
+        #[test]
+        fn should_be_able_to_deserialize_from_msgpack() {
+            // NOTE: It may seem odd that we are serializing just to deserialize, but this is to
+            // verify that we are not corrupting or causing issues when serializing on a
+            // client/server and then trying to deserialize on the other side. This has happened
+            // enough times with minor changes that we need tests to verify.
+            let buf = rmp_serde::encode::to_vec_named(&Response::Environment {
+                vars: Environment::from([(String::from("key"), String::from("value"))]),
+            })
+            .unwrap();
+
+            let payload: Response = rmp_serde::decode::from_slice(&buf).unwrap();
+            assert_eq!(
+                payload,
+                Response::Environment {
+                    vars: Environment::from([(String::from("key"), String::from("value"))]),
+                }
+            );
+        }
+    }
+
     mod version {
         use super::*;
         use crate::common::{Capabilities, Capability};
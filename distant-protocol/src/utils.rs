@@ -15,3 +15,9 @@ pub const fn is_one(value: &usize) -> bool {
 pub const fn one() -> usize {
     1
 }
+
+/// Used purely for skipping serialization of values that are 0 by default.
+#[inline]
+pub const fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
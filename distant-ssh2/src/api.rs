@@ -1,30 +1,48 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Weak};
 use std::time::Duration;
 
 use async_compat::CompatExt;
 use async_once_cell::OnceCell;
 use async_trait::async_trait;
-use distant_core::net::server::ConnectionCtx;
+use distant_core::net::common::ConnectionId;
+use distant_core::net::server::{ConnectionCtx, Reply};
 use distant_core::protocol::{
-    Capabilities, CapabilityKind, DirEntry, Environment, FileType, Metadata, Permissions,
-    ProcessId, PtySize, SetPermissionsOptions, SystemInfo, UnixMetadata, Version, PROTOCOL_VERSION,
+    Capabilities, CapabilityKind, ChangeKind, ChangeKindSet, DirEntry, Environment, FileType,
+    Metadata, Permissions, ProcSpawnOptions, ProcessId, PtySize, Response, SearchId, SearchQuery,
+    SetPermissionsOptions, Signal, SystemInfo, UnixMetadata, Version, PROTOCOL_VERSION,
 };
 use distant_core::{DistantApi, DistantCtx};
 use log::*;
 use tokio::sync::{mpsc, RwLock};
 use wezterm_ssh::{
-    FilePermissions, OpenFileType, OpenOptions, Session as WezSession, Utf8PathBuf, WriteMode,
+    Child, ChildKiller, ExecResult, FileDescriptor, FilePermissions, OpenFileType, OpenOptions,
+    Session as WezSession, SshChildProcess, Utf8PathBuf, WriteMode,
 };
 
 use crate::process::{spawn_pty, spawn_simple, SpawnResult};
+use crate::search::{spawn_search, SearchResult};
 use crate::utils::{self, to_other_error};
+use crate::watcher::PollWatcher;
 
 /// Time after copy completes to wait for stdout/stderr to close
 const COPY_COMPLETE_TIMEOUT: Duration = Duration::from_secs(1);
 
+/// Time after reading a file from an offset completes to wait for stdout/stderr to close
+const READ_COMPLETE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Time to wait between reads when streaming a file from an offset has no output ready
+const READ_PAUSE_DURATION: Duration = Duration::from_millis(10);
+
+/// Maximum size (in bytes) of each chunk sent back when streaming a file
+const MAX_FILE_CHUNK_SIZE: usize = 32768;
+
+/// Maximum size (in bytes) of a range read without a length, beyond which the file needs to be
+/// streamed instead
+const MAX_FILE_RANGE_SIZE: u64 = 16 * 1024 * 1024;
+
 #[derive(Default)]
 pub struct ConnectionState {
     /// List of process ids that will be killed when the connection terminates
@@ -33,6 +51,9 @@ pub struct ConnectionState {
     /// Internal reference to global process list for removals
     /// NOTE: Initialized during `on_accept` of [`DistantApi`]
     global_processes: Weak<RwLock<HashMap<ProcessId, Process>>>,
+
+    /// List of paths being watched that will stop being polled when the connection terminates
+    watchers: RwLock<Vec<PollWatcher>>,
 }
 
 struct Process {
@@ -48,6 +69,9 @@ pub struct SshDistantApi {
 
     /// Global tracking of running processes by id
     processes: Arc<RwLock<HashMap<ProcessId, Process>>>,
+
+    /// Global tracking of running searches by id
+    searches: Arc<RwLock<HashMap<SearchId, mpsc::Sender<()>>>>,
 }
 
 impl SshDistantApi {
@@ -55,6 +79,7 @@ impl SshDistantApi {
         Self {
             session,
             processes: Arc::new(RwLock::new(HashMap::new())),
+            searches: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -68,6 +93,35 @@ impl SshDistantApi {
             .get_or_try_init(utils::is_windows(&self.session))
             .await?)
     }
+
+    /// Builds a command that prints the contents of the file at `path` starting at `offset` and
+    /// limited to `len` bytes, as `tail` can seek to the offset whereas sftp files can only be
+    /// read from the start
+    async fn read_file_command(
+        &self,
+        path: &Path,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<String> {
+        if self.is_windows().await? {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Reading from an offset is not supported on windows",
+            ));
+        }
+
+        let path = path.to_string_lossy();
+        let mut cmd = format!(
+            "tail -c +{} -- {}",
+            offset.saturating_add(1),
+            shell_words::quote(&path)
+        );
+        if let Some(len) = len {
+            cmd.push_str(&format!(" | head -c {len}"));
+        }
+
+        Ok(format!("/bin/sh -c {}", shell_words::quote(&cmd)))
+    }
 }
 
 #[async_trait]
@@ -127,6 +181,156 @@ impl DistantApi for SshDistantApi {
         Ok(contents)
     }
 
+    async fn read_file_range(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<Vec<u8>> {
+        debug!(
+            "[Conn {}] Reading bytes from file {:?} {{offset: {}, len: {:?}}}",
+            ctx.connection_id, path, offset, len
+        );
+
+        if let Some(len) = len {
+            if len > MAX_FILE_RANGE_SIZE {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Cannot read {len} bytes from file {path:?} at once as it is more than \
+                         {MAX_FILE_RANGE_SIZE} bytes, so it must be streamed instead"
+                    ),
+                ));
+            }
+        }
+
+        use smol::io::AsyncReadExt;
+        let file = self
+            .session
+            .sftp()
+            .open(path.clone())
+            .compat()
+            .await
+            .map_err(to_other_error)?;
+
+        // NOTE: Without a length, we read one byte past the maximum to detect files that are too
+        //       large to send back as a single blob
+        let max = len.unwrap_or(MAX_FILE_RANGE_SIZE + 1);
+        let contents = if offset == 0 {
+            let mut contents = Vec::new();
+            file.take(max).read_to_end(&mut contents).compat().await?;
+            contents
+        } else {
+            let cmd = self.read_file_command(&path, offset, Some(max)).await?;
+            let output = utils::execute_output(&self.session, &cmd, READ_COMPLETE_TIMEOUT).await?;
+            if !output.success {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "Failed to read file {path:?}: {}",
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ),
+                ));
+            }
+            output.stdout
+        };
+
+        if len.is_none() && contents.len() as u64 > MAX_FILE_RANGE_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "File {path:?} has more than {MAX_FILE_RANGE_SIZE} bytes past offset \
+                     {offset}, so it must be read with a length or streamed"
+                ),
+            ));
+        }
+
+        Ok(contents)
+    }
+
+    async fn read_file_stream(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        offset: u64,
+        len: Option<u64>,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Streaming bytes from file {:?} {{offset: {}, len: {:?}}}",
+            ctx.connection_id, path, offset, len
+        );
+
+        use smol::io::AsyncReadExt;
+        let file = self
+            .session
+            .sftp()
+            .open(path.clone())
+            .compat()
+            .await
+            .map_err(to_other_error)?;
+
+        let connection_id = ctx.connection_id;
+        let reply = ctx.reply;
+
+        if offset > 0 {
+            let cmd = self.read_file_command(&path, offset, len).await?;
+            let ExecResult {
+                child,
+                stdout,
+                stdin: _stdin,
+                ..
+            } = self
+                .session
+                .exec(&cmd, None)
+                .compat()
+                .await
+                .map_err(to_other_error)?;
+
+            tokio::spawn(stream_exec_stdout(
+                connection_id,
+                path,
+                child,
+                stdout,
+                reply,
+            ));
+            return Ok(());
+        }
+
+        let mut file = file.take(len.unwrap_or(u64::MAX));
+        tokio::spawn(async move {
+            let mut buf = vec![0; MAX_FILE_CHUNK_SIZE];
+            loop {
+                let res = match file.read(&mut buf).compat().await {
+                    Ok(0) => {
+                        trace!("[Conn {connection_id}] Finished streaming file {path:?}");
+                        let _ = reply.send(Response::FileReadDone).await;
+                        break;
+                    }
+                    Ok(n) => {
+                        reply
+                            .send(Response::Blob {
+                                data: buf[..n].to_vec(),
+                            })
+                            .await
+                    }
+                    Err(x) => {
+                        error!("[Conn {connection_id}] Failed to stream file {path:?}: {x}");
+                        let _ = reply.send(Response::from(x)).await;
+                        break;
+                    }
+                };
+
+                // If the connection is gone, there is no point in reading any further
+                if res.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(())
+    }
+
     async fn write_file(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -594,6 +798,157 @@ impl DistantApi for SshDistantApi {
         Ok(())
     }
 
+    async fn symlink(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        src: PathBuf,
+        dst: PathBuf,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Creating symlink {:?} pointing to {:?}",
+            ctx.connection_id, dst, src
+        );
+
+        self.session
+            .sftp()
+            .symlink(src, dst)
+            .compat()
+            .await
+            .map_err(to_other_error)?;
+
+        Ok(())
+    }
+
+    async fn hard_link(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        src: PathBuf,
+        dst: PathBuf,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Creating hard link {:?} to {:?}",
+            ctx.connection_id, dst, src
+        );
+
+        // NOTE: SFTP does not provide a method to create hard links, so we instead execute
+        //       a program based on the platform like we do when copying
+        let is_windows = self.is_windows().await?;
+        let src = src.to_string_lossy();
+        let dst = dst.to_string_lossy();
+        let output = if is_windows {
+            // NOTE: Single-quoted strings in powershell are literal, with a quote escaped by
+            //       doubling it
+            let quote = |s: &str| format!("'{}'", s.replace('\'', "''"));
+            utils::powershell_output(
+                &self.session,
+                &format!(
+                    "New-Item -ItemType HardLink -Path {} -Target {}",
+                    quote(&dst),
+                    quote(&src)
+                ),
+                COPY_COMPLETE_TIMEOUT,
+            )
+            .await?
+        } else {
+            utils::execute_output(
+                &self.session,
+                &format!(
+                    "ln -- {} {}",
+                    shell_words::quote(&src),
+                    shell_words::quote(&dst)
+                ),
+                COPY_COMPLETE_TIMEOUT,
+            )
+            .await?
+        };
+
+        // NOTE: powershell.exe does not return an error upon failure, so we have to check if we
+        //       got some stderr as output and consider that a failure
+        let success = output.success && (!is_windows || output.stderr.is_empty());
+
+        if success {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "Hard link command failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            ))
+        }
+    }
+
+    async fn read_link(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+    ) -> io::Result<PathBuf> {
+        debug!("[Conn {}] Reading link {:?}", ctx.connection_id, path);
+
+        let target = self
+            .session
+            .sftp()
+            .read_link(path)
+            .compat()
+            .await
+            .map_err(to_other_error)?;
+
+        Ok(target.into_std_path_buf())
+    }
+
+    async fn watch(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        path: PathBuf,
+        recursive: bool,
+        only: Vec<ChangeKind>,
+        except: Vec<ChangeKind>,
+    ) -> io::Result<()> {
+        let only = only.into_iter().collect::<ChangeKindSet>();
+        let except = except.into_iter().collect::<ChangeKindSet>();
+        debug!(
+            "[Conn {}] Watching {:?} {{recursive: {}, only: {}, except: {}}}",
+            ctx.connection_id, path, recursive, only, except
+        );
+
+        let watcher = PollWatcher::spawn(
+            self.session.sftp(),
+            path,
+            recursive,
+            only,
+            except,
+            ctx.reply,
+        )
+        .await?;
+
+        ctx.local_data.watchers.write().await.push(watcher);
+
+        Ok(())
+    }
+
+    async fn unwatch(&self, ctx: DistantCtx<Self::LocalData>, path: PathBuf) -> io::Result<()> {
+        debug!("[Conn {}] Unwatching {:?}", ctx.connection_id, path);
+
+        // NOTE: The path may no longer exist, in which case we fall back to the raw path
+        let path = utils::canonicalize(&self.session.sftp(), path.as_path())
+            .await
+            .unwrap_or(path);
+
+        let mut watchers = ctx.local_data.watchers.write().await;
+        let old_len = watchers.len();
+        watchers.retain(|w| !w.is_watching(path.as_path()));
+
+        if watchers.len() == old_len {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("{path:?} is not being watched"),
+            ));
+        }
+
+        Ok(())
+    }
+
     async fn exists(&self, ctx: DistantCtx<Self::LocalData>, path: PathBuf) -> io::Result<bool> {
         debug!("[Conn {}] Checking if {:?} exists", ctx.connection_id, path);
 
@@ -803,6 +1158,59 @@ impl DistantApi for SshDistantApi {
         Ok(())
     }
 
+    async fn search(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        query: SearchQuery,
+    ) -> io::Result<SearchId> {
+        debug!(
+            "[Conn {}] Performing search via {query:?}",
+            ctx.connection_id,
+        );
+
+        if self.is_windows().await? {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Searching is not supported on Windows",
+            ));
+        }
+
+        let global_searches = Arc::downgrade(&self.searches);
+        let cleanup = |id: SearchId| async move {
+            if let Some(searches) = Weak::upgrade(&global_searches) {
+                searches.write().await.remove(&id);
+            }
+        };
+
+        // NOTE: We hold onto the lock while spawning so the search cannot be cleaned up before
+        //       we have started tracking it
+        let mut searches = self.searches.write().await;
+        let SearchResult { id, canceller } =
+            spawn_search(&self.session, query, ctx.reply, cleanup)?;
+        searches.insert(id, canceller);
+
+        Ok(id)
+    }
+
+    async fn cancel_search(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: SearchId,
+    ) -> io::Result<()> {
+        debug!("[Conn {}] Cancelling search {id}", ctx.connection_id);
+
+        if let Some(canceller) = self.searches.write().await.remove(&id) {
+            if canceller.send(()).await.is_ok() {
+                return Ok(());
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("[Query {id}] Cancellation failed because no search found"),
+        ))
+    }
+
     async fn proc_spawn(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -810,12 +1218,50 @@ impl DistantApi for SshDistantApi {
         environment: Environment,
         current_dir: Option<PathBuf>,
         pty: Option<PtySize>,
+        options: ProcSpawnOptions,
     ) -> io::Result<ProcessId> {
         debug!(
-            "[Conn {}] Spawning {} {{environment: {:?}, current_dir: {:?}, pty: {:?}}}",
-            ctx.connection_id, cmd, environment, current_dir, pty
+            "[Conn {}] Spawning {} {{environment: {:?}, current_dir: {:?}, pty: {:?}, options: {:?}}}",
+            ctx.connection_id, cmd, environment, current_dir, pty, options
         );
 
+        let login_shell = options.login_shell;
+        let options = ProcSpawnOptions {
+            login_shell: false,
+            ..options
+        };
+        if !options.is_default() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Process spawn options are not supported by ssh implementation",
+            ));
+        }
+
+        // Wrap the command so that it is interpreted by the user's login shell, quoting both as
+        // the command string given to exec is itself interpreted by a shell
+        let cmd = if login_shell {
+            if self.is_windows().await? {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "Login shell is not supported on Windows",
+                ));
+            }
+
+            let shell = utils::query_shell(&self.session, false).await?;
+            let shell = if shell.is_empty() {
+                String::from("/bin/sh")
+            } else {
+                shell
+            };
+            format!(
+                "{} -l -c {}",
+                shell_words::quote(&shell),
+                shell_words::quote(&cmd)
+            )
+        } else {
+            cmd
+        };
+
         let global_processes = Arc::downgrade(&self.processes);
         let local_processes = Arc::downgrade(&ctx.local_data.processes);
         let cleanup = |id: ProcessId| async move {
@@ -892,6 +1338,44 @@ impl DistantApi for SshDistantApi {
         ))
     }
 
+    async fn proc_signal(
+        &self,
+        ctx: DistantCtx<Self::LocalData>,
+        id: ProcessId,
+        signal: Signal,
+    ) -> io::Result<()> {
+        debug!(
+            "[Conn {}] Sending {} to process {}",
+            ctx.connection_id, signal, id
+        );
+
+        // NOTE: wezterm_ssh::SshChildProcess has no pid or way to send a signal over its channel,
+        //       so the only signal we can deliver is a kill
+        if signal != Signal::Kill {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "[Conn {}] Unable to send {} to process {} as only kill is supported",
+                    ctx.connection_id, signal, id
+                ),
+            ));
+        }
+
+        if let Some(process) = self.processes.read().await.get(&id) {
+            if process.kill_tx.send(()).await.is_ok() {
+                return Ok(());
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::BrokenPipe,
+            format!(
+                "[Conn {}] Unable to send {} to process {}",
+                ctx.connection_id, signal, id
+            ),
+        ))
+    }
+
     async fn proc_stdin(
         &self,
         ctx: DistantCtx<Self::LocalData>,
@@ -998,19 +1482,48 @@ impl DistantApi for SshDistantApi {
         })
     }
 
+    async fn environment(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Environment> {
+        debug!("[Conn {}] Reading environment variables", ctx.connection_id);
+        let is_windows = self.is_windows().await?;
+        utils::query_environment(&self.session, is_windows).await
+    }
+
     async fn version(&self, ctx: DistantCtx<Self::LocalData>) -> io::Result<Version> {
         debug!("[Conn {}] Querying capabilities", ctx.connection_id);
 
         let mut capabilities = Capabilities::all();
 
-        // Searching is not supported by ssh implementation
-        // TODO: Could we have external search using ripgrep's JSON lines API?
-        capabilities.take(CapabilityKind::Search);
-        capabilities.take(CapabilityKind::CancelSearch);
-
         // Broken via wezterm-ssh, so not supported right now
         capabilities.take(CapabilityKind::SetPermissions);
 
+        // Resumable uploads are not supported by ssh implementation
+        capabilities.take(CapabilityKind::UploadOpen);
+        capabilities.take(CapabilityKind::UploadWrite);
+        capabilities.take(CapabilityKind::UploadCommit);
+        capabilities.take(CapabilityKind::UploadAbort);
+
+        // Hashing and patching files are not supported by ssh implementation
+        capabilities.take(CapabilityKind::Hash);
+        capabilities.take(CapabilityKind::FileBlockChecksums);
+        capabilities.take(CapabilityKind::FilePatch);
+
+        // Signals other than kill cannot be delivered by ssh implementation
+        capabilities.take(CapabilityKind::ProcSignal);
+
+        // Listing and attaching to processes is not supported by ssh implementation
+        capabilities.take(CapabilityKind::ProcList);
+        capabilities.take(CapabilityKind::ProcAttach);
+
+        // Forwarding tcp streams is not supported by ssh implementation
+        capabilities.take(CapabilityKind::TcpConnect);
+        capabilities.take(CapabilityKind::TcpListen);
+        capabilities.take(CapabilityKind::TcpWrite);
+        capabilities.take(CapabilityKind::TcpClose);
+        capabilities.take(CapabilityKind::TcpUnlisten);
+
+        // Replacing matches within files is not supported by ssh implementation
+        capabilities.take(CapabilityKind::Replace);
+
         Ok(Version {
             server_version: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
             protocol_version: PROTOCOL_VERSION,
@@ -1018,3 +1531,82 @@ impl DistantApi for SshDistantApi {
         })
     }
 }
+
+/// Sends the output of a command printing the contents of the file at `path` back in chunks
+/// until the command exits, followed by the notice that the file has been read
+async fn stream_exec_stdout(
+    connection_id: ConnectionId,
+    path: PathBuf,
+    mut child: SshChildProcess,
+    mut stdout: FileDescriptor,
+    reply: Box<dyn Reply<Data = Response>>,
+) {
+    use std::io::Read;
+    if let Err(x) = stdout.set_non_blocking(true) {
+        let _ = reply.send(Response::from(to_other_error(x))).await;
+        return;
+    }
+
+    let mut buf = vec![0; MAX_FILE_CHUNK_SIZE];
+    let mut status = None;
+    let result = loop {
+        match stdout.read(&mut buf) {
+            Ok(n) if n > 0 => {
+                let res = reply
+                    .send(Response::Blob {
+                        data: buf[..n].to_vec(),
+                    })
+                    .await;
+
+                // If the connection is gone, there is no point in reading any further
+                if res.is_err() {
+                    let _ = child.kill();
+                    return;
+                }
+            }
+            Ok(_) => break Ok(()),
+
+            // NOTE: There is a bug where if the ssh backend is libssh, the non-blocking readers
+            //       will never report Ok(0) and are always Err(WouldBlock). So, once the command
+            //       has exited and nothing more is ready to be read, we consider it complete
+            Err(x) if x.kind() == io::ErrorKind::WouldBlock => {
+                if status.is_some() {
+                    break Ok(());
+                }
+
+                match child.try_wait() {
+                    Ok(x) => status = x,
+                    Err(x) => break Err(x),
+                }
+                tokio::time::sleep(READ_PAUSE_DURATION).await;
+            }
+            Err(x) => break Err(x),
+        }
+    };
+
+    let result = match (result, status) {
+        (Ok(()), Some(status)) => Ok(status),
+        (Ok(()), None) => child.async_wait().compat().await,
+        (Err(x), _) => Err(x),
+    };
+
+    match result {
+        Ok(status) if status.success() => {
+            trace!("[Conn {connection_id}] Finished streaming file {path:?}");
+            let _ = reply.send(Response::FileReadDone).await;
+        }
+        Ok(_) => {
+            error!("[Conn {connection_id}] Failed to stream file {path:?}");
+            let _ = reply
+                .send(Response::from(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("Failed to read file {path:?}"),
+                )))
+                .await;
+        }
+        Err(x) => {
+            error!("[Conn {connection_id}] Failed to stream file {path:?}: {x}");
+            let _ = reply.send(Response::from(x)).await;
+        }
+    }
+}
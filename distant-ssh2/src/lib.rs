@@ -32,7 +32,9 @@ use wezterm_ssh::{
 
 mod api;
 mod process;
+mod search;
 mod utils;
+mod watcher;
 
 use api::SshDistantApi;
 
@@ -16,8 +16,7 @@ use wezterm_ssh::{
 const MAX_PIPE_CHUNK_SIZE: usize = 8192;
 const THREAD_PAUSE_MILLIS: u64 = 1;
 
-/// Result of spawning a process, containing means to send stdin, means to kill the process,
-/// and the initialization function to use to start processing stdin, stdout, and stderr
+/// Result of spawning a process, containing means to send stdin, means to kill the process, and the initialization function to use to start processing stdin, stdout, and stderr
 pub struct SpawnResult {
     pub id: ProcessId,
     pub stdin: mpsc::Sender<Vec<u8>>,
@@ -215,6 +214,7 @@ fn spawn_blocking_stdout_task(
                     let payload = Response::ProcStdout {
                         id,
                         data: buf[..n].to_vec(),
+                        seq: None,
                     };
                     if reply.blocking_send(payload).is_err() {
                         error!("[Ssh | Proc {}] Stdout channel closed", id);
@@ -246,6 +246,7 @@ fn spawn_nonblocking_stdout_task(
                     let payload = Response::ProcStdout {
                         id,
                         data: buf[..n].to_vec(),
+                        seq: None,
                     };
                     if reply.send(payload).await.is_err() {
                         error!("[Ssh | Proc {}] Stdout channel closed", id);
@@ -280,6 +281,7 @@ fn spawn_nonblocking_stderr_task(
                     let payload = Response::ProcStderr {
                         id,
                         data: buf[..n].to_vec(),
+                        seq: None,
                     };
                     if reply.send(payload).await.is_err() {
                         error!("[Ssh | Proc {}] Stderr channel closed", id);
@@ -421,6 +423,11 @@ where
             id,
             success: !should_kill && success,
             code: if success { Some(0) } else { None },
+            limit: None,
+            timed_out: false,
+            signal: None,
+            core_dumped: false,
+            usage: None,
         };
 
         if reply.send(payload).await.is_err() {
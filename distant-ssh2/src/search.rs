@@ -0,0 +1,946 @@
+use std::future::Future;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use async_compat::CompatExt;
+use distant_core::net::server::Reply;
+use distant_core::protocol::{
+    FileType, Response, SearchId, SearchQuery, SearchQueryCondition, SearchQueryContentsMatch,
+    SearchQueryMatch, SearchQueryMatchData, SearchQueryOptions, SearchQueryPathMatch,
+    SearchQuerySubmatch, SearchQueryTarget,
+};
+use log::*;
+use regex::bytes::{Regex, RegexBuilder};
+use regex_syntax::ast::{self, Ast};
+use tokio::sync::mpsc;
+use wezterm_ssh::{ChildKiller, ExecResult, Session};
+
+use crate::utils;
+
+/// Maximum size (in bytes) of each read of the output of a search
+const MAX_PIPE_CHUNK_SIZE: usize = 8192;
+
+/// Maximum size (in bytes) of the errors of a search kept to report its failure
+const MAX_STDERR_SIZE: usize = 4096;
+
+/// Time to wait between reads when a search has no output ready
+const READ_PAUSE_DURATION: Duration = Duration::from_millis(10);
+
+/// Result of spawning a search, containing the id of the search and the means to cancel it
+pub struct SearchResult {
+    pub id: SearchId,
+    pub canceller: mpsc::Sender<()>,
+}
+
+/// Spawns a search on the remote machine using `find` to walk the paths of the query and `grep`
+/// to examine file contents, reporting matches through `reply` as they are found
+///
+/// The condition of the query is translated into a POSIX extended regular expression for `grep`
+/// that matches at least every line that the condition does, and each line is checked again
+/// locally to determine whether it truly matches alongside its submatches.
+///
+/// Hidden paths and sizes are filtered by `find`, but ignore files such as `.gitignore` are never
+/// read, and options that cannot be honored (modification times, additional ignore files,
+/// context lines, and multiline matching) are rejected as unsupported.
+///
+/// If `find` or `grep` fails, the failure is reported as an error once all matches that were
+/// found have been sent, followed by the notice that the search is done.
+pub fn spawn_search<F, R>(
+    session: &Session,
+    query: SearchQuery,
+    reply: Box<dyn Reply<Data = Response>>,
+    cleanup: F,
+) -> io::Result<SearchResult>
+where
+    F: FnOnce(SearchId) -> R + Send + 'static,
+    R: Future<Output = ()> + Send + 'static,
+{
+    let executor = SearchQueryExecutor::new(query)?;
+    let id = executor.id;
+    let (canceller, cancel_rx) = mpsc::channel(1);
+
+    let session = session.clone();
+    tokio::spawn(async move {
+        let mut reporter = SearchQueryReporter::new(id, &executor.query.options, reply);
+        if let Err(x) = executor.run(&session, cancel_rx, &mut reporter).await {
+            error!("[Query {id}] Search failed: {x}");
+            reporter.flush().await;
+            if let Err(x) = reporter.reply.send(Response::from(x)).await {
+                error!("[Query {id}] Failed to send error: {x}");
+            }
+        }
+
+        reporter.finish().await;
+        cleanup(id).await;
+    });
+
+    Ok(SearchResult { id, canceller })
+}
+
+/// Sends matches back in batches based on the pagination and limit of the query, followed by
+/// the notice that the search is done
+struct SearchQueryReporter {
+    id: SearchId,
+    limit: Option<u64>,
+    pagination: Option<u64>,
+    matches: Vec<SearchQueryMatch>,
+    total_matches_cnt: u64,
+    reply: Box<dyn Reply<Data = Response>>,
+}
+
+impl SearchQueryReporter {
+    fn new(
+        id: SearchId,
+        options: &SearchQueryOptions,
+        reply: Box<dyn Reply<Data = Response>>,
+    ) -> Self {
+        Self {
+            id,
+            limit: options.limit,
+            pagination: options.pagination,
+            matches: Vec::new(),
+            total_matches_cnt: 0,
+            reply,
+        }
+    }
+
+    /// Returns true if the limit of the query has been reached, meaning no more matches are
+    /// accepted
+    fn is_full(&self) -> bool {
+        matches!(self.limit, Some(len) if self.total_matches_cnt >= len)
+    }
+
+    /// Queues `m` to be sent, sending the queue once it reaches the pagination size
+    async fn report(&mut self, m: SearchQueryMatch) {
+        let id = self.id;
+        if self.is_full() {
+            return;
+        }
+
+        self.matches.push(m);
+        self.total_matches_cnt += 1;
+
+        if let Some(len) = self.pagination {
+            if self.matches.len() as u64 >= len {
+                trace!("[Query {id}] Reached {len} paginated matches");
+                self.flush().await;
+            }
+        }
+    }
+
+    /// Sends any queued matches
+    async fn flush(&mut self) {
+        let id = self.id;
+        if self.matches.is_empty() {
+            return;
+        }
+
+        let matches = std::mem::take(&mut self.matches);
+        if let Err(x) = self
+            .reply
+            .send(Response::SearchResults { id, matches })
+            .await
+        {
+            error!("[Query {id}] Failed to send matches: {x}");
+        }
+    }
+
+    /// Sends any remaining matches followed by the notice that the search is done
+    async fn finish(mut self) {
+        let id = self.id;
+        self.flush().await;
+
+        trace!("[Query {id}] Reporting as done");
+        if let Err(x) = self.reply.send(Response::SearchDone { id }).await {
+            error!("[Query {id}] Failed to send done status: {x}");
+        }
+    }
+}
+
+struct SearchQueryExecutor {
+    id: SearchId,
+    query: SearchQuery,
+    paths: Vec<PathBuf>,
+    pattern: Option<PosixPattern>,
+    matcher: Regex,
+    include: Option<Regex>,
+    exclude: Option<Regex>,
+}
+
+impl SearchQueryExecutor {
+    /// Creates a new executor, validating the query
+    pub fn new(query: SearchQuery) -> io::Result<Self> {
+        fn build_regex(regex: &str) -> io::Result<Regex> {
+            RegexBuilder::new(regex)
+                .multi_line(true)
+                .build()
+                .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))
+        }
+
+        if query.paths.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "missing paths"));
+        }
+
+        // NOTE: The search is performed by find and grep on the remote machine, which only
+        //       accept a single regex, so we cannot evaluate And/Not conditions
+        let is_structural = query.condition.is_structural()
+            || [&query.options.include, &query.options.exclude]
+                .into_iter()
+                .flatten()
+                .any(SearchQueryCondition::is_structural);
+        if is_structural {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "And and Not conditions are not supported",
+            ));
+        }
+
+        // NOTE: Options that find and grep cannot honor portably are rejected rather than
+        //       ignored, so that a query never returns different results than it asked for
+        let options = &query.options;
+        let unsupported: Vec<&str> = [
+            ("modified_after", options.modified_after.is_some()),
+            ("modified_before", options.modified_before.is_some()),
+            ("ignore_files", !options.ignore_files.is_empty()),
+            ("before_context", options.before_context.is_some()),
+            ("after_context", options.after_context.is_some()),
+            ("multiline", options.multiline),
+        ]
+        .into_iter()
+        .filter_map(|(name, is_set)| if is_set { Some(name) } else { None })
+        .collect();
+        if !unsupported.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!(
+                    "Search options are not supported: {}",
+                    unsupported.join(", ")
+                ),
+            ));
+        }
+
+        let regex = query.condition.to_regex_string();
+        let matcher = build_regex(&regex)?;
+        let pattern = match query.target {
+            SearchQueryTarget::Contents => Some(PosixPattern::from_regex(&regex)?),
+            SearchQueryTarget::Path => None,
+        };
+        let include = match query.options.include.as_ref() {
+            Some(condition) => Some(build_regex(&condition.to_regex_string())?),
+            None => None,
+        };
+        let exclude = match query.options.exclude.as_ref() {
+            Some(condition) => Some(build_regex(&condition.to_regex_string())?),
+            None => None,
+        };
+
+        // Build our list of paths so we can ensure we weed out duplicates
+        let mut paths = Vec::new();
+        for mut path in query.paths.iter().map(PathBuf::as_path) {
+            // For each explicit path, we will add it directly UNLESS we
+            // are searching upward and have a max depth > 0 to avoid
+            // searching this path twice
+            if !query.options.upward || query.options.max_depth == Some(0) {
+                paths.push(path.to_path_buf());
+            }
+
+            // For going in the upward direction, we will add ancestor paths as long
+            // as the max depth allows it
+            if query.options.upward {
+                let mut remaining = query.options.max_depth;
+                if query.options.max_depth.is_none() || query.options.max_depth > Some(0) {
+                    while let Some(parent) = path.parent() {
+                        // If we have a maximum depth and it has reached zero, we
+                        // don't want to include any more paths
+                        if remaining == Some(0) {
+                            break;
+                        }
+
+                        path = parent;
+                        paths.push(path.to_path_buf());
+
+                        if let Some(x) = remaining.as_mut() {
+                            *x -= 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        paths.sort_unstable();
+        paths.dedup();
+
+        Ok(Self {
+            id: rand::random(),
+            query,
+            paths,
+            pattern,
+            matcher,
+            include,
+            exclude,
+        })
+    }
+
+    /// Runs the search on the remote machine, reporting matches as the output of the search
+    /// arrives until it completes, is cancelled, or reaches the limit of the query
+    async fn run(
+        &self,
+        session: &Session,
+        mut cancel_rx: mpsc::Receiver<()>,
+        reporter: &mut SearchQueryReporter,
+    ) -> io::Result<()> {
+        let id = self.id;
+        let cmd = self.to_command();
+        trace!("[Query {id}] Executing {cmd:?}");
+
+        let ExecResult {
+            mut child,
+            mut stdout,
+            mut stderr,
+            stdin: _stdin,
+        } = session
+            .exec(&cmd, None)
+            .compat()
+            .await
+            .map_err(utils::to_other_error)?;
+        stdout
+            .set_non_blocking(true)
+            .map_err(utils::to_other_error)?;
+        stderr
+            .set_non_blocking(true)
+            .map_err(utils::to_other_error)?;
+
+        let mut parser = SearchQueryParser::new(self);
+        let mut buf = [0u8; MAX_PIPE_CHUNK_SIZE];
+        let mut errors = Vec::new();
+        let mut stdout_done = false;
+        let mut stderr_done = false;
+        let mut status = None;
+
+        // NOTE: Both pipes are drained together as the search stalls once either of them fills
+        //       up, and stderr is only kept up to a limit for the sake of reporting failures
+        while !stdout_done || !stderr_done {
+            let mut idle = true;
+
+            if !stdout_done {
+                match try_read(&mut stdout, &mut buf)? {
+                    Some(0) => stdout_done = true,
+                    Some(n) => {
+                        idle = false;
+                        for m in parser.parse(&buf[..n]) {
+                            reporter.report(m).await;
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            if !stderr_done {
+                match try_read(&mut stderr, &mut buf)? {
+                    Some(0) => stderr_done = true,
+                    Some(n) => {
+                        idle = false;
+                        let len = n.min(MAX_STDERR_SIZE.saturating_sub(errors.len()));
+                        errors.extend_from_slice(&buf[..len]);
+                    }
+                    None => {}
+                }
+            }
+
+            if reporter.is_full() {
+                trace!("[Query {id}] Reached limit of matches");
+                break;
+            }
+
+            if !idle {
+                continue;
+            }
+
+            // NOTE: There is a bug where if the ssh backend is libssh, the non-blocking readers
+            //       will never report Ok(0) and are always Err(WouldBlock). So, once the search
+            //       has exited and nothing more is ready to be read, we consider it complete
+            if status.is_some() {
+                break;
+            }
+
+            tokio::select! {
+                Some(()) = cancel_rx.recv() => {
+                    debug!("[Query {id}] Cancelled");
+                    break;
+                }
+                result = child.async_wait().compat() => {
+                    status = Some(result.map_err(utils::to_other_error)?);
+                }
+                _ = tokio::time::sleep(READ_PAUSE_DURATION) => {}
+            }
+        }
+
+        // If we stopped early, there is no reason to let the search continue
+        if !stdout_done && status.is_none() {
+            trace!("[Query {id}] Stopping search");
+            if let Err(x) = child.kill() {
+                trace!("[Query {id}] Failed to stop search: {x}");
+            }
+            return Ok(());
+        }
+
+        for m in parser.finish() {
+            reporter.report(m).await;
+        }
+
+        let status = match status {
+            Some(status) => status,
+            None => child
+                .async_wait()
+                .compat()
+                .await
+                .map_err(utils::to_other_error)?,
+        };
+
+        if !status.success() {
+            let errors = String::from_utf8_lossy(&errors);
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                match errors.trim() {
+                    "" => String::from("Search failed"),
+                    errors => format!("Search failed: {errors}"),
+                },
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Builds the shell command to run on the remote machine to perform the search
+    pub fn to_command(&self) -> String {
+        let options = &self.query.options;
+        let mut args: Vec<String> = vec![String::from("find")];
+
+        if options.follow_symbolic_links {
+            args.push(String::from("-L"));
+        }
+
+        for path in self.paths.iter() {
+            args.push(quote(&path.to_string_lossy()));
+        }
+
+        // When traversing upward, we only look at the immediate entries of each ancestor
+        let max_depth = if options.upward {
+            Some(1)
+        } else {
+            options.max_depth
+        };
+        if let Some(depth) = max_depth {
+            args.push(format!("-maxdepth {depth}"));
+        }
+
+        // Skip hidden entries beneath the paths being searched unless asked to include them
+        if !options.hidden {
+            let roots: Vec<String> = self
+                .paths
+                .iter()
+                .map(|path| format!("-path {}", quote(&escape_glob(&path.to_string_lossy()))))
+                .collect();
+            args.push(format!(
+                "-name '.*' ! \\( {} \\) -prune -o",
+                roots.join(" -o ")
+            ));
+        }
+
+        // Sizes only apply to files, so setting either limits the search to files
+        if options.min_size.is_some() || options.max_size.is_some() {
+            args.push(String::from("-type f"));
+        }
+        if let Some(size) = options.min_size {
+            args.push(format!("! -size -{size}c"));
+        }
+        if let Some(size) = options.max_size {
+            args.push(format!("! -size +{size}c"));
+        }
+
+        match self.query.target {
+            SearchQueryTarget::Path => {
+                if !options.allowed_file_types.is_empty() {
+                    let mut types = Vec::new();
+                    for ty in [FileType::Dir, FileType::File, FileType::Symlink] {
+                        if options.allowed_file_types.contains(&ty) {
+                            types.push(match ty {
+                                FileType::Dir => "-type d",
+                                FileType::File => "-type f",
+                                FileType::Symlink => "-type l",
+                            });
+                        }
+                    }
+                    args.push(format!("\\( {} \\)", types.join(" -o ")));
+                }
+
+                args.push(String::from("-print0"));
+            }
+
+            SearchQueryTarget::Contents => {
+                // Only files have contents, so restricting to anything else finds nothing
+                if !options.allowed_file_types.is_empty()
+                    && !options.allowed_file_types.contains(&FileType::File)
+                {
+                    args.push(String::from("-false"));
+                }
+
+                args.push(String::from("-type f"));
+
+                // NOTE: grep exits with 1 when nothing matched, which would otherwise make find
+                //       report a failure, so only an exit code above 1 counts as one
+                let pattern = self.pattern.as_ref().expect("Missing pattern for contents");
+                let mut grep = vec!["grep --null -H -n -b -I -E"];
+                if pattern.case_insensitive {
+                    grep.push("-i");
+                }
+                let script = format!(
+                    "{} -e {} -- \"$@\"; [ $? -le 1 ]",
+                    grep.join(" "),
+                    quote(&pattern.pattern)
+                );
+                args.push(format!("-exec sh -c {} sh {{}} +", quote(&script)));
+            }
+        }
+
+        format!("/bin/sh -c {}", quote(&args.join(" ")))
+    }
+
+    /// Parses a path from the output of `find`, returning a match if it meets the condition
+    fn parse_path_match(&self, line: &[u8]) -> Option<SearchQueryMatch> {
+        let path = PathBuf::from(String::from_utf8_lossy(line).into_owned());
+        if !self.is_path_allowed(&path) {
+            return None;
+        }
+
+        let submatches = self.find_submatches(path.to_string_lossy().as_bytes());
+        if submatches.is_empty() {
+            return None;
+        }
+
+        Some(SearchQueryMatch::Path(SearchQueryPathMatch {
+            path,
+            submatches,
+        }))
+    }
+
+    /// Parses a line from the output of `grep` in the form of `{path}\0{line}:{offset}:{text}`,
+    /// returning a match if it meets the condition
+    fn parse_contents_match(&self, line: &[u8]) -> Option<SearchQueryMatch> {
+        let (path, rest) = split_once(line, b'\0')?;
+        let (line_number, rest) = split_once(rest, b':')?;
+        let (absolute_offset, text) = split_once(rest, b':')?;
+
+        let path = PathBuf::from(String::from_utf8_lossy(path).into_owned());
+        if !self.is_path_allowed(&path) {
+            return None;
+        }
+
+        let line_number = std::str::from_utf8(line_number).ok()?.parse().ok()?;
+        let absolute_offset = std::str::from_utf8(absolute_offset).ok()?.parse().ok()?;
+
+        // NOTE: grep strips the line terminator, so we add it back to be consistent with
+        //       other implementations that include it as part of the matched lines
+        let mut text = text.to_vec();
+        text.push(b'\n');
+
+        let submatches = self.find_submatches(&text);
+        if submatches.is_empty() {
+            return None;
+        }
+
+        Some(SearchQueryMatch::Contents(SearchQueryContentsMatch {
+            path,
+            lines: to_match_data(&text),
+            line_number,
+            absolute_offset,
+            submatches,
+            context: Vec::new(),
+        }))
+    }
+
+    /// Returns true if the path passes the include and exclude filters of the query
+    fn is_path_allowed(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+        let included = self
+            .include
+            .as_ref()
+            .map(|r| r.is_match(path.as_bytes()))
+            .unwrap_or(true);
+        let excluded = self
+            .exclude
+            .as_ref()
+            .map(|r| r.is_match(path.as_bytes()))
+            .unwrap_or(false);
+
+        included && !excluded
+    }
+
+    fn find_submatches(&self, bytes: &[u8]) -> Vec<SearchQuerySubmatch> {
+        self.matcher
+            .find_iter(bytes)
+            .map(|m| SearchQuerySubmatch {
+                r#match: to_match_data(m.as_bytes()),
+                start: m.start() as u64,
+                end: m.end() as u64,
+            })
+            .collect()
+    }
+}
+
+/// Parses the output of the command produced by [`SearchQueryExecutor::to_command`] into
+/// matches as it arrives, holding onto any trailing output until the rest of it is received
+struct SearchQueryParser<'a> {
+    executor: &'a SearchQueryExecutor,
+    pending: Vec<u8>,
+}
+
+impl<'a> SearchQueryParser<'a> {
+    fn new(executor: &'a SearchQueryExecutor) -> Self {
+        Self {
+            executor,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Returns the byte that terminates each entry of the output, which is a null byte for the
+    /// paths printed by `find` and a newline for the lines printed by `grep`
+    fn terminator(&self) -> u8 {
+        match self.executor.query.target {
+            SearchQueryTarget::Path => b'\0',
+            SearchQueryTarget::Contents => b'\n',
+        }
+    }
+
+    /// Parses every complete entry found within the output received so far
+    fn parse(&mut self, bytes: &[u8]) -> Vec<SearchQueryMatch> {
+        self.pending.extend_from_slice(bytes);
+
+        let terminator = self.terminator();
+        let end = match self.pending.iter().rposition(|b| *b == terminator) {
+            Some(i) => i + 1,
+            None => return Vec::new(),
+        };
+
+        let entries: Vec<u8> = self.pending.drain(..end).collect();
+        entries
+            .split(|b| *b == terminator)
+            .filter(|entry| !entry.is_empty())
+            .filter_map(|entry| self.parse_entry(entry))
+            .collect()
+    }
+
+    /// Parses the last entry of the output, which is only needed if it was not terminated
+    fn finish(&mut self) -> Vec<SearchQueryMatch> {
+        let entry = std::mem::take(&mut self.pending);
+        if entry.is_empty() {
+            return Vec::new();
+        }
+
+        self.parse_entry(&entry).into_iter().collect()
+    }
+
+    fn parse_entry(&self, entry: &[u8]) -> Option<SearchQueryMatch> {
+        match self.executor.query.target {
+            SearchQueryTarget::Path => self.executor.parse_path_match(entry),
+            SearchQueryTarget::Contents => self.executor.parse_contents_match(entry),
+        }
+    }
+}
+
+/// Pattern for `grep -E` derived from a Rust regex, matching at least every line that the regex
+/// matches so that `grep` can find the candidate lines that are then checked with the regex
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PosixPattern {
+    pattern: String,
+    case_insensitive: bool,
+}
+
+impl PosixPattern {
+    /// Translates `regex` into a POSIX extended regular expression
+    ///
+    /// Anything without an equivalent is loosened rather than rejected, so word boundaries are
+    /// dropped, lazy repetitions become greedy, and classes such as `\pL` or `[\w&&\D]` match
+    /// any character. Perl classes like `\d` use the classes of the remote locale.
+    fn from_regex(regex: &str) -> io::Result<Self> {
+        let ast = ast::parse::Parser::new()
+            .parse(regex)
+            .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
+
+        let mut this = Self {
+            pattern: String::new(),
+            case_insensitive: false,
+        };
+        this.push_ast(&ast)?;
+        Ok(this)
+    }
+
+    fn push_ast(&mut self, ast: &Ast) -> io::Result<()> {
+        match ast {
+            Ast::Empty(_) => {}
+            Ast::Flags(x) => self.push_flags(&x.flags),
+            Ast::Literal(x) => self.push_literal(x.c)?,
+            Ast::Dot(_) => self.pattern.push('.'),
+            Ast::Assertion(x) => match x.kind {
+                ast::AssertionKind::StartLine | ast::AssertionKind::StartText => {
+                    self.pattern.push('^')
+                }
+                ast::AssertionKind::EndLine | ast::AssertionKind::EndText => self.pattern.push('$'),
+                ast::AssertionKind::WordBoundary | ast::AssertionKind::NotWordBoundary => {}
+            },
+            Ast::Class(ast::Class::Perl(x)) => {
+                self.pattern.push_str(perl_class(x).unwrap_or("."));
+            }
+            Ast::Class(ast::Class::Unicode(_)) => self.pattern.push('.'),
+            Ast::Class(ast::Class::Bracketed(x)) => match bracketed_class(x) {
+                Some(class) => self.pattern.push_str(&class),
+                None => self.pattern.push('.'),
+            },
+            Ast::Repetition(x) => {
+                let is_atom = matches!(
+                    x.ast.as_ref(),
+                    Ast::Literal(_) | Ast::Dot(_) | Ast::Class(_) | Ast::Group(_)
+                );
+                if is_atom {
+                    self.push_ast(&x.ast)?;
+                } else {
+                    self.pattern.push('(');
+                    self.push_ast(&x.ast)?;
+                    self.pattern.push(')');
+                }
+                self.push_repetition(&x.op.kind);
+            }
+            Ast::Group(x) => {
+                if let ast::GroupKind::NonCapturing(flags) = &x.kind {
+                    self.push_flags(flags);
+                }
+                self.pattern.push('(');
+                self.push_ast(&x.ast)?;
+                self.pattern.push(')');
+            }
+            Ast::Alternation(x) => {
+                for (i, ast) in x.asts.iter().enumerate() {
+                    if i > 0 {
+                        self.pattern.push('|');
+                    }
+                    self.push_ast(ast)?;
+                }
+            }
+            Ast::Concat(x) => {
+                for ast in x.asts.iter() {
+                    self.push_ast(ast)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the case insensitive flag to the entire pattern if found, as grep cannot apply
+    /// it to only part of a pattern
+    fn push_flags(&mut self, flags: &ast::Flags) {
+        if flags.flag_state(ast::Flag::CaseInsensitive) == Some(true) {
+            self.case_insensitive = true;
+        }
+    }
+
+    fn push_literal(&mut self, c: char) -> io::Result<()> {
+        match c {
+            // NOTE: grep treats a newline within a pattern as the start of another pattern
+            '\n' => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Line terminators cannot be matched without multiline searches",
+                ))
+            }
+            '\0' => self.pattern.push('.'),
+            '\\' | '.' | '[' | ']' | '(' | ')' | '*' | '+' | '?' | '{' | '}' | '|' | '^' | '$' => {
+                self.pattern.push('\\');
+                self.pattern.push(c);
+            }
+            c => self.pattern.push(c),
+        }
+
+        Ok(())
+    }
+
+    fn push_repetition(&mut self, kind: &ast::RepetitionKind) {
+        // NOTE: POSIX only guarantees support for counts up to 255, so we require fewer
+        //       repetitions or drop the upper bound for anything larger
+        const MAX: u32 = 255;
+
+        match kind {
+            ast::RepetitionKind::ZeroOrOne => self.pattern.push('?'),
+            ast::RepetitionKind::ZeroOrMore => self.pattern.push('*'),
+            ast::RepetitionKind::OneOrMore => self.pattern.push('+'),
+            ast::RepetitionKind::Range(range) => {
+                let s = match *range {
+                    ast::RepetitionRange::Exactly(n) if n <= MAX => format!("{{{n}}}"),
+                    ast::RepetitionRange::Bounded(n, m) if m <= MAX => format!("{{{n},{m}}}"),
+                    ast::RepetitionRange::Exactly(n)
+                    | ast::RepetitionRange::AtLeast(n)
+                    | ast::RepetitionRange::Bounded(n, _) => format!("{{{},}}", n.min(MAX)),
+                };
+                self.pattern.push_str(&s);
+            }
+        }
+    }
+}
+
+/// Translates a perl class into a bracket expression, or `None` if negated
+fn perl_class(class: &ast::ClassPerl) -> Option<&'static str> {
+    if class.negated {
+        return None;
+    }
+
+    Some(match class.kind {
+        ast::ClassPerlKind::Digit => "[[:digit:]]",
+        ast::ClassPerlKind::Space => "[[:space:]]",
+        ast::ClassPerlKind::Word => "[[:alnum:]_]",
+    })
+}
+
+/// Translates a bracketed class into a bracket expression, or `None` if it contains something
+/// that cannot be represented in one
+fn bracketed_class(class: &ast::ClassBracketed) -> Option<String> {
+    #[derive(Default)]
+    struct Items {
+        items: Vec<String>,
+        has_bracket: bool,
+        has_caret: bool,
+        has_hyphen: bool,
+        has_open_bracket: bool,
+    }
+
+    fn collect(item: &ast::ClassSetItem, out: &mut Items) -> Option<()> {
+        match item {
+            ast::ClassSetItem::Empty(_) => {}
+            ast::ClassSetItem::Literal(x) => match x.c {
+                '\n' | '\0' => return None,
+                ']' => out.has_bracket = true,
+                '^' => out.has_caret = true,
+                '-' => out.has_hyphen = true,
+                '[' => out.has_open_bracket = true,
+                c => out.items.push(c.to_string()),
+            },
+            ast::ClassSetItem::Range(x) => {
+                let special = |c: char| matches!(c, '\n' | '\0' | ']' | '^' | '-' | '[');
+                if special(x.start.c) || special(x.end.c) {
+                    return None;
+                }
+                out.items.push(format!("{}-{}", x.start.c, x.end.c));
+            }
+            ast::ClassSetItem::Ascii(x) if !x.negated => {
+                out.items.push(String::from(match x.kind {
+                    ast::ClassAsciiKind::Alnum => "[:alnum:]",
+                    ast::ClassAsciiKind::Alpha => "[:alpha:]",
+                    ast::ClassAsciiKind::Blank => "[:blank:]",
+                    ast::ClassAsciiKind::Cntrl => "[:cntrl:]",
+                    ast::ClassAsciiKind::Digit => "[:digit:]",
+                    ast::ClassAsciiKind::Graph => "[:graph:]",
+                    ast::ClassAsciiKind::Lower => "[:lower:]",
+                    ast::ClassAsciiKind::Print => "[:print:]",
+                    ast::ClassAsciiKind::Punct => "[:punct:]",
+                    ast::ClassAsciiKind::Space => "[:space:]",
+                    ast::ClassAsciiKind::Upper => "[:upper:]",
+                    ast::ClassAsciiKind::Word => "[:alnum:]_",
+                    ast::ClassAsciiKind::Xdigit => "[:xdigit:]",
+                    ast::ClassAsciiKind::Ascii => return None,
+                }))
+            }
+            ast::ClassSetItem::Perl(x) => {
+                let class = perl_class(x)?;
+                out.items.push(class[1..class.len() - 1].to_string());
+            }
+            ast::ClassSetItem::Bracketed(x) if !x.negated => match &x.kind {
+                ast::ClassSet::Item(item) => collect(item, out)?,
+                ast::ClassSet::BinaryOp(_) => return None,
+            },
+            ast::ClassSetItem::Union(x) => {
+                for item in x.items.iter() {
+                    collect(item, out)?;
+                }
+            }
+            ast::ClassSetItem::Ascii(_)
+            | ast::ClassSetItem::Unicode(_)
+            | ast::ClassSetItem::Bracketed(_) => return None,
+        }
+
+        Some(())
+    }
+
+    let mut items = Items::default();
+    match &class.kind {
+        ast::ClassSet::Item(item) => collect(item, &mut items)?,
+        ast::ClassSet::BinaryOp(_) => return None,
+    }
+
+    // NOTE: Within a bracket expression, `]` is only literal when first, `^` when not first,
+    //       `-` when last, and `[` when not followed by `:`, so we order them accordingly
+    let mut s = String::from("[");
+    if class.negated {
+        s.push('^');
+    }
+    if items.has_bracket {
+        s.push(']');
+    }
+    for item in items.items.iter() {
+        s.push_str(item);
+    }
+    if items.has_open_bracket {
+        s.push('[');
+    }
+    if items.has_caret {
+        if s.len() == 1 {
+            return None;
+        }
+        s.push('^');
+    }
+    if items.has_hyphen {
+        s.push('-');
+    }
+    if s.len() == 1 || (class.negated && s.len() == 2) {
+        return None;
+    }
+    s.push(']');
+    Some(s)
+}
+
+/// Reads whatever is available from a non-blocking `reader`, returning `None` if nothing is
+/// ready yet
+fn try_read(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<Option<usize>> {
+    match reader.read(buf) {
+        Ok(n) => Ok(Some(n)),
+        Err(x) if x.kind() == io::ErrorKind::WouldBlock => Ok(None),
+        Err(x) => Err(x),
+    }
+}
+
+fn to_match_data(bytes: &[u8]) -> SearchQueryMatchData {
+    match std::str::from_utf8(bytes) {
+        Ok(s) => SearchQueryMatchData::Text(s.to_string()),
+        Err(_) => SearchQueryMatchData::Bytes(bytes.to_vec()),
+    }
+}
+
+fn split_once(bytes: &[u8], delimiter: u8) -> Option<(&[u8], &[u8])> {
+    let i = bytes.iter().position(|b| *b == delimiter)?;
+    Some((&bytes[..i], &bytes[i + 1..]))
+}
+
+/// Escapes the characters of `s` that `find -path` would otherwise treat as a pattern
+fn escape_glob(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn quote(s: &str) -> String {
+    shell_words::quote(s).into_owned()
+}
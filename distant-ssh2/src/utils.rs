@@ -3,6 +3,7 @@ use std::time::Duration;
 use std::{fmt, io};
 
 use async_compat::CompatExt;
+use distant_core::protocol::Environment;
 use typed_path::windows::WindowsComponent;
 use typed_path::{Components, WindowsPath, WindowsPathBuf};
 use wezterm_ssh::{ExecResult, Session, Sftp};
@@ -203,6 +204,41 @@ pub async fn query_shell(session: &Session, is_windows: bool) -> io::Result<Stri
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
+/// Query remote system for the environment variables of current user
+pub async fn query_environment(session: &Session, is_windows: bool) -> io::Result<Environment> {
+    let output = if is_windows {
+        powershell_output(
+            session,
+            "Get-ChildItem env: | ForEach-Object { $_.Name + '=' + $_.Value }",
+            SSH_EXEC_TIMEOUT,
+        )
+        .await?
+    } else {
+        // NOTE: Only GNU and busybox support separating variables with null bytes, which keeps
+        //       values containing newlines intact, so we fall back to newlines elsewhere
+        execute_output(
+            session,
+            "/bin/sh -c 'env -0 2>/dev/null || env'",
+            SSH_EXEC_TIMEOUT,
+        )
+        .await?
+    };
+
+    // Every variable printed by `env -0` is terminated by a null byte, so finding one tells us
+    // which of the two forms we got back
+    let separator = if output.stdout.contains(&b'\0') {
+        '\0'
+    } else {
+        '\n'
+    };
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split(separator)
+        .filter_map(|line| line.trim_end_matches('\r').split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect())
+}
+
 /// Attempts to convert UTF8 str into a path compliant with Windows
 pub fn convert_to_windows_path_string(s: &str) -> Option<String> {
     let path = WindowsPath::new(s);
@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_compat::CompatExt;
+use distant_core::net::server::Reply;
+use distant_core::protocol::{
+    Change, ChangeDetails, ChangeDetailsAttribute, ChangeKind, ChangeKindSet, Response,
+};
+use log::*;
+use tokio::task::JoinHandle;
+use wezterm_ssh::{Metadata, Sftp};
+
+use crate::utils;
+
+/// Time to wait between each poll of the remote filesystem for changes
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Represents a path on the remote machine that is watched by periodically polling it over sftp
+/// and comparing the results against the previous poll
+pub struct PollWatcher {
+    /// The raw path provided to the watcher, which is not canonicalized
+    raw_path: PathBuf,
+
+    /// The canonicalized path at the time of creating the watcher
+    path: PathBuf,
+
+    task: JoinHandle<()>,
+}
+
+impl Drop for PollWatcher {
+    /// Aborts the task that polls for changes
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl PollWatcher {
+    /// Starts watching `path`, sending any changes that pass the `only` and `except` filters
+    /// through `reply`
+    pub async fn spawn(
+        sftp: Sftp,
+        path: impl Into<PathBuf>,
+        recursive: bool,
+        only: impl Into<ChangeKindSet>,
+        except: impl Into<ChangeKindSet>,
+        reply: Box<dyn Reply<Data = Response>>,
+    ) -> io::Result<Self> {
+        let raw_path = path.into();
+        let path = utils::canonicalize(&sftp, raw_path.as_path()).await?;
+        let only = only.into();
+        let except = except.into();
+
+        // Calculate the true list of kinds based on only and except filters
+        let allowed = if only.is_empty() {
+            ChangeKindSet::all() - except
+        } else {
+            only - except
+        };
+
+        // Take the initial snapshot before returning so that changes made right after the watch
+        // is established are detected
+        let snapshot = Snapshot::take(&sftp, path.as_path(), recursive).await;
+
+        let task = tokio::spawn({
+            let path = path.clone();
+            async move {
+                let mut snapshot = snapshot;
+                loop {
+                    tokio::time::sleep(WATCH_POLL_INTERVAL).await;
+
+                    let next = Snapshot::take(&sftp, path.as_path(), recursive).await;
+                    for change in snapshot.diff(&next) {
+                        if !allowed.contains(&change.kind) {
+                            continue;
+                        }
+
+                        // If the connection is gone, there is no point in watching any further
+                        if let Err(x) = reply.send(Response::Changed(change)).await {
+                            trace!("Stopping watch of {path:?}: {x}");
+                            return;
+                        }
+                    }
+
+                    snapshot = next;
+                }
+            }
+        });
+
+        Ok(Self {
+            raw_path,
+            path,
+            task,
+        })
+    }
+
+    /// Returns true if this watcher was created for the given path, which can be either the
+    /// original path or its canonicalized form
+    pub fn is_watching(&self, path: impl AsRef<Path>) -> bool {
+        let path = path.as_ref();
+        self.path == path || self.raw_path == path
+    }
+}
+
+/// Details about a single path captured during a poll
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    is_dir: bool,
+    size: Option<u64>,
+    modified: Option<u64>,
+    mode: Option<u32>,
+    uid: Option<u32>,
+    gid: Option<u32>,
+}
+
+impl From<Metadata> for Entry {
+    fn from(metadata: Metadata) -> Self {
+        Self {
+            is_dir: metadata.is_dir(),
+            size: metadata.size,
+            modified: metadata.modified,
+            mode: metadata.permissions.map(|p| p.to_unix_mode()),
+            uid: metadata.uid,
+            gid: metadata.gid,
+        }
+    }
+}
+
+/// State of a watched path (and its descendants) at a point in time
+#[derive(Debug, Default)]
+struct Snapshot(BTreeMap<PathBuf, Entry>);
+
+impl Snapshot {
+    /// Captures the state of `root`, its immediate entries if it is a directory, and all nested
+    /// entries if `recursive` is true
+    ///
+    /// Paths that cannot be read are left out of the snapshot, which means that a missing root
+    /// results in an empty snapshot.
+    async fn take(sftp: &Sftp, root: &Path, recursive: bool) -> Self {
+        let mut entries = BTreeMap::new();
+
+        let metadata = match sftp.symlink_metadata(root.to_path_buf()).compat().await {
+            Ok(metadata) => metadata,
+            Err(x) => {
+                trace!("Unable to read metadata for {root:?}: {x}");
+                return Self(entries);
+            }
+        };
+
+        let mut to_traverse = VecDeque::new();
+        if metadata.is_dir() {
+            to_traverse.push_back(root.to_path_buf());
+        }
+        entries.insert(root.to_path_buf(), Entry::from(metadata));
+
+        while let Some(dir) = to_traverse.pop_front() {
+            let dir_entries = match sftp.read_dir(dir.to_path_buf()).compat().await {
+                Ok(dir_entries) => dir_entries,
+                Err(x) => {
+                    trace!("Unable to read directory {dir:?}: {x}");
+                    continue;
+                }
+            };
+
+            for (path, metadata) in dir_entries {
+                let path = path.into_std_path_buf();
+
+                // NOTE: We do not follow symlinks to directories to avoid traversing loops
+                if recursive && metadata.is_dir() {
+                    to_traverse.push_back(path.to_path_buf());
+                }
+
+                entries.insert(path, Entry::from(metadata));
+            }
+        }
+
+        Self(entries)
+    }
+
+    /// Produces the list of changes needed to go from this snapshot to `next`
+    fn diff(&self, next: &Snapshot) -> Vec<Change> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time before unix epoch")
+            .as_secs();
+
+        let mut changes = Vec::new();
+        let mut push = |kind: ChangeKind, path: &Path, details: ChangeDetails| {
+            changes.push(Change {
+                timestamp,
+                kind,
+                path: path.to_path_buf(),
+                details,
+            })
+        };
+
+        for (path, old) in self.0.iter() {
+            let new = match next.0.get(path) {
+                Some(new) => new,
+                None => {
+                    push(ChangeKind::Delete, path, ChangeDetails::default());
+                    continue;
+                }
+            };
+
+            // If a file was replaced by a directory (or vice versa), treat it as a new path
+            if old.is_dir != new.is_dir {
+                push(ChangeKind::Delete, path, ChangeDetails::default());
+                push(
+                    ChangeKind::Create,
+                    path,
+                    ChangeDetails {
+                        timestamp: new.modified,
+                        ..Default::default()
+                    },
+                );
+                continue;
+            }
+
+            if old.size != new.size || old.modified != new.modified {
+                push(
+                    ChangeKind::Modify,
+                    path,
+                    ChangeDetails {
+                        timestamp: new.modified,
+                        ..Default::default()
+                    },
+                );
+            }
+
+            if old.mode != new.mode {
+                push(
+                    ChangeKind::Attribute,
+                    path,
+                    ChangeDetails {
+                        attribute: Some(ChangeDetailsAttribute::Permissions),
+                        ..Default::default()
+                    },
+                );
+            }
+
+            if old.uid != new.uid || old.gid != new.gid {
+                push(
+                    ChangeKind::Attribute,
+                    path,
+                    ChangeDetails {
+                        attribute: Some(ChangeDetailsAttribute::Ownership),
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        for (path, new) in next.0.iter() {
+            if !self.0.contains_key(path) {
+                push(
+                    ChangeKind::Create,
+                    path,
+                    ChangeDetails {
+                        timestamp: new.modified,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        changes
+    }
+}
@@ -4,8 +4,11 @@ use std::time::Duration;
 
 use assert_fs::prelude::*;
 use assert_fs::TempDir;
+use distant_core::net::common::Request;
 use distant_core::protocol::{
-    ChangeKindSet, Environment, FileType, Metadata, Permissions, SetPermissionsOptions,
+    self, CapabilityKind, ChangeKind, ChangeKindSet, Environment, ErrorKind, FileType, Metadata,
+    Msg, Permissions, SearchQuery, SearchQueryCondition, SearchQueryMatchData, SearchQueryOptions,
+    SearchQuerySubmatch, SetPermissionsOptions, Signal,
 };
 use distant_core::{DistantChannelExt, DistantClient};
 use once_cell::sync::Lazy;
@@ -17,6 +20,7 @@ use crate::sshd::*;
 
 const SETUP_DIR_TIMEOUT: Duration = Duration::from_secs(1);
 const SETUP_DIR_POLL: Duration = Duration::from_millis(50);
+const WATCH_TIMEOUT: Duration = Duration::from_secs(5);
 
 static TEMP_SCRIPT_DIR: Lazy<TempDir> = Lazy::new(|| TempDir::new().unwrap());
 static SCRIPT_RUNNER: Lazy<String> = Lazy::new(|| String::from("bash"));
@@ -128,6 +132,56 @@ async fn read_file_text_should_send_text_with_file_contents(#[future] client: Ct
     assert_eq!(text, "some file contents");
 }
 
+#[rstest]
+#[test(tokio::test)]
+async fn read_file_range_should_send_blob_with_bytes_within_range(
+    #[future] client: Ctx<DistantClient>,
+) {
+    let mut client = client.await;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("test-file");
+    file.write_str("some file contents").unwrap();
+
+    let bytes = client
+        .read_file_range(file.path().to_path_buf(), 5, Some(4))
+        .await
+        .unwrap();
+    assert_eq!(bytes, b"file");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn read_file_stream_should_fail_if_file_missing(#[future] client: Ctx<DistantClient>) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+    let path = temp.child("missing-file").path().to_path_buf();
+
+    let _ = client.read_file_stream(path, 0, None).await.unwrap_err();
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn read_file_stream_should_send_contents_starting_at_offset(
+    #[future] client: Ctx<DistantClient>,
+) {
+    use tokio::io::AsyncReadExt;
+    let mut client = client.await;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("test-file");
+    file.write_str("some file contents").unwrap();
+
+    let mut reader = client
+        .read_file_stream(file.path().to_path_buf(), 5, None)
+        .await
+        .unwrap();
+
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).await.unwrap();
+    assert_eq!(data, b"file contents");
+}
+
 #[rstest]
 #[test(tokio::test)]
 async fn write_file_should_send_error_if_fails_to_write_file(#[future] client: Ctx<DistantClient>) {
@@ -901,15 +955,89 @@ async fn rename_should_support_renaming_a_single_file(#[future] client: Ctx<Dist
 
 #[rstest]
 #[test(tokio::test)]
-async fn watch_should_fail_as_unsupported(#[future] client: Ctx<DistantClient>) {
-    // NOTE: Supporting multiple replies being sent back as part of creating, modifying, etc.
+async fn symlink_should_create_a_symlink_pointing_to_src(#[future] client: Ctx<DistantClient>) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src");
+    src.write_str("some text").unwrap();
+    let dst = temp.child("dst");
+
+    client
+        .symlink(src.path().to_path_buf(), dst.path().to_path_buf())
+        .await
+        .unwrap();
+
+    dst.assert(predicate::path::is_symlink());
+    dst.assert("some text");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn hard_link_should_create_a_link_sharing_contents_with_src(
+    #[future] client: Ctx<DistantClient>,
+) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src");
+    src.write_str("some text").unwrap();
+    let dst = temp.child("dst");
+
+    client
+        .hard_link(src.path().to_path_buf(), dst.path().to_path_buf())
+        .await
+        .unwrap();
+
+    // Verify that changes through one path are seen through the other
+    dst.write_str("new text").unwrap();
+    src.assert("new text");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn hard_link_should_not_interpret_paths_as_shell_syntax(
+    #[future] client: Ctx<DistantClient>,
+) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src $(echo x) `echo y` 'quoted' \\n");
+    src.write_str("some text").unwrap();
+    let dst = temp.child("dst $HOME");
+
+    client
+        .hard_link(src.path().to_path_buf(), dst.path().to_path_buf())
+        .await
+        .unwrap();
+
+    // If the shell had expanded any part of the paths, the link would fail or land elsewhere
+    dst.assert("some text");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn read_link_should_return_target_without_canonicalizing(
+    #[future] client: Ctx<DistantClient>,
+) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("file");
+    file.touch().unwrap();
+    let link = temp.child("link");
+    link.symlink_to_file("file").unwrap();
+
+    let target = client.read_link(link.path().to_path_buf()).await.unwrap();
+    assert_eq!(target, Path::new("file"));
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn watch_should_support_watching_a_single_file(#[future] client: Ctx<DistantClient>) {
     let mut client = client.await;
     let temp = assert_fs::TempDir::new().unwrap();
 
     let file = temp.child("file");
     file.touch().unwrap();
 
-    let err = client
+    let mut watcher = client
         .watch(
             file.path().to_path_buf(),
             /* recursive */ false,
@@ -917,9 +1045,65 @@ async fn watch_should_fail_as_unsupported(#[future] client: Ctx<DistantClient>)
             /* except */ ChangeKindSet::default(),
         )
         .await
-        .unwrap_err();
+        .unwrap();
+
+    // Update the file and verify we get a notification once the next poll happens
+    file.write_str("some text").unwrap();
 
-    assert_eq!(err.kind(), io::ErrorKind::Unsupported, "{:?}", err);
+    let change = tokio::time::timeout(WATCH_TIMEOUT, watcher.next())
+        .await
+        .expect("Timed out waiting for change")
+        .expect("Watcher closed before we got change");
+    assert_eq!(change.kind, ChangeKind::Modify);
+    assert_eq!(change.path, dunce::canonicalize(file.path()).unwrap());
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn watch_should_support_watching_a_directory_recursively(
+    #[future] client: Ctx<DistantClient>,
+) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let dir = temp.child("dir");
+    dir.create_dir_all().unwrap();
+
+    let mut watcher = client
+        .watch(
+            temp.path().to_path_buf(),
+            /* recursive */ true,
+            /* only */ ChangeKind::Create,
+            /* except */ ChangeKindSet::default(),
+        )
+        .await
+        .unwrap();
+
+    // Create a nested file and verify we get a notification
+    let nested_file = dir.child("nested-file");
+    nested_file.write_str("some text").unwrap();
+
+    let change = tokio::time::timeout(WATCH_TIMEOUT, watcher.next())
+        .await
+        .expect("Timed out waiting for change")
+        .expect("Watcher closed before we got change");
+    assert_eq!(change.kind, ChangeKind::Create);
+    assert_eq!(
+        change.path,
+        dunce::canonicalize(nested_file.path()).unwrap()
+    );
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn unwatch_should_fail_if_path_not_watched(#[future] client: Ctx<DistantClient>) {
+    let mut client = client.await;
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let file = temp.child("file");
+    file.touch().unwrap();
+
+    let _ = client.unwatch(file.path().to_path_buf()).await.unwrap_err();
 }
 
 #[rstest]
@@ -1681,6 +1865,73 @@ async fn set_permissions_should_support_following_symlinks_if_option_specified(
     );
 }
 
+#[rstest]
+#[test(tokio::test)]
+async fn search_should_find_paths_matching_condition(#[future] client: Ctx<DistantClient>) {
+    let mut client = client.await;
+    let root = assert_fs::TempDir::new().unwrap();
+    root.child("path1").touch().unwrap();
+    root.child("path2").touch().unwrap();
+    root.child("other").touch().unwrap();
+
+    let mut searcher = client
+        .search(SearchQuery::path(
+            SearchQueryCondition::regex("path"),
+            [root.path()],
+            SearchQueryOptions::default(),
+        ))
+        .await
+        .unwrap();
+
+    let mut paths = Vec::new();
+    while let Some(m) = searcher.next().await {
+        paths.push(m.into_path_match().unwrap().path);
+    }
+    paths.sort_unstable();
+
+    assert_eq!(
+        paths,
+        vec![
+            root.child("path1").to_path_buf(),
+            root.child("path2").to_path_buf()
+        ]
+    );
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn search_should_find_contents_matching_condition(#[future] client: Ctx<DistantClient>) {
+    let mut client = client.await;
+    let root = assert_fs::TempDir::new().unwrap();
+    root.child("file1")
+        .write_str("some\nlines of text\n")
+        .unwrap();
+    root.child("file2").write_str("no match").unwrap();
+
+    let mut searcher = client
+        .search(SearchQuery::contents(
+            SearchQueryCondition::contains("text"),
+            [root.path()],
+            SearchQueryOptions::default(),
+        ))
+        .await
+        .unwrap();
+
+    let m = searcher
+        .next()
+        .await
+        .unwrap()
+        .into_contents_match()
+        .unwrap();
+    assert_eq!(m.path, root.child("file1").to_path_buf());
+    assert_eq!(m.lines, SearchQueryMatchData::text("lines of text\n"));
+    assert_eq!(m.line_number, 2);
+    assert_eq!(m.absolute_offset, 5);
+    assert_eq!(m.submatches, vec![SearchQuerySubmatch::new("text", 9, 13)]);
+
+    assert_eq!(searcher.next().await, None);
+}
+
 #[rstest]
 #[test(tokio::test)]
 async fn proc_spawn_should_not_fail_even_if_process_not_found(
@@ -1867,6 +2118,48 @@ async fn proc_kill_should_fail_if_process_not_running(#[future] client: Ctx<Dist
     let _ = killer.kill().await.unwrap_err();
 }
 
+#[rstest]
+#[test(tokio::test)]
+async fn proc_signal_should_only_support_kill(#[future] client: Ctx<DistantClient>) {
+    let mut client = client.await;
+
+    // Signals other than kill cannot be delivered over ssh, so they should not be advertised
+    let version = client.version().await.unwrap();
+    assert!(!version.capabilities.contains(CapabilityKind::ProcSignal));
+
+    let proc = client
+        .spawn(
+            /* cmd */
+            format!("{} {} 1", *SCRIPT_RUNNER, SLEEP_SH.to_str().unwrap()),
+            /* environment */ Environment::new(),
+            /* current_dir */ None,
+            /* pty */ None,
+        )
+        .await
+        .unwrap();
+
+    for signal in [Signal::Term, Signal::Int] {
+        let res = client
+            .send(Request::new(Msg::Single(protocol::Request::ProcSignal {
+                id: proc.id(),
+                signal,
+            })))
+            .await
+            .unwrap();
+        match res.payload {
+            Msg::Single(protocol::Response::Error(x)) => {
+                assert_eq!(x.kind, ErrorKind::Unsupported, "{signal}: {x:?}")
+            }
+            x => panic!("Unexpected response for {signal}: {x:?}"),
+        }
+    }
+
+    // Killing through a signal is still supported and ends the process
+    proc.signal(Signal::Kill).await.unwrap();
+    let status = proc.wait().await.unwrap();
+    assert!(!status.success, "Process succeeded when killed")
+}
+
 #[rstest]
 #[test(tokio::test)]
 async fn proc_stdin_should_fail_if_process_not_running(#[future] client: Ctx<DistantClient>) {
@@ -6,16 +6,21 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use anyhow::Context;
-use distant_core::net::common::{ConnectionId, Host, Map, Request, Response};
+use distant_core::net::common::{ConnectionId, Host, Request, Response};
 use distant_core::net::manager::ManagerClient;
 use distant_core::protocol::SearchQueryContentsMatch;
+use distant_core::protocol::SearchQueryContextKind;
+use distant_core::protocol::SearchQueryContextLine;
 use distant_core::protocol::SearchQueryMatch;
 use distant_core::protocol::SearchQueryPathMatch;
 use distant_core::protocol::{
-    self, Capabilities, ChangeKind, ChangeKindSet, FileType, Permissions, SearchQuery,
-    SetPermissionsOptions, SystemInfo,
+    self, Capabilities, ChangeKind, ChangeKindSet, FileType, Permissions, ProcSpawnOptions,
+    SearchQuery, SearchQueryTarget, SetPermissionsOptions, SystemInfo,
+};
+use distant_core::{
+    DistantChannel, DistantChannelExt, RemoteCommand, Replacer, Searcher, SyncOptions,
+    TransferOptions, TransferProgress, Watcher,
 };
-use distant_core::{DistantChannel, DistantChannelExt, RemoteCommand, Searcher, Watcher};
 use log::*;
 use serde_json::json;
 use tabled::settings::object::Rows;
@@ -23,18 +28,26 @@ use tabled::settings::style::Style;
 use tabled::settings::{Alignment, Disable, Modify};
 use tabled::{Table, Tabled};
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 use crate::cli::common::{
     Cache, Client, JsonAuthHandler, MsgReceiver, MsgSender, PromptAuthHandler,
 };
 use crate::constants::MAX_PIPE_CHUNK_SIZE;
-use crate::options::{ClientFileSystemSubcommand, ClientSubcommand, Format, NetworkSettings};
+use crate::options::{
+    ClientFileSystemLinkSubcommand, ClientFileSystemSubcommand, ClientSubcommand, Format,
+    NetworkSettings,
+};
 use crate::{CliError, CliResult};
 
+mod forward;
 mod lsp;
+mod proxy;
 mod shell;
 
+use forward::Forward;
 use lsp::Lsp;
+use proxy::Proxy;
 use shell::Shell;
 
 use super::common::RemoteProcessLink;
@@ -60,8 +73,14 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
             destination,
             format,
             network,
-            options,
+            mut options,
         } => {
+            // Have the manager prefer the same encryption and compression when connecting to the
+            // server
+            for (key, value) in network.to_handshake_options().into_map() {
+                options.entry(key).or_insert(value);
+            }
+
             debug!("Connecting to manager");
             let mut client = connect_to_manager(format, network).await?;
 
@@ -96,6 +115,35 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                 ),
             }
         }
+        ClientSubcommand::Forward {
+            cache,
+            connection,
+            network,
+            local,
+            remote,
+        } => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            debug!("Forwarding (local = {:?}, remote = {:?})", local, remote);
+            Forward::new(channel.into_client().into_channel())
+                .run(local, remote)
+                .await?;
+        }
         ClientSubcommand::Launch {
             cache,
             mut destination,
@@ -106,6 +154,10 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
             network,
             mut options,
         } => {
+            // Have the manager prefer the same encryption and compression when connecting to the
+            // launched server
+            let handshake_options = network.to_handshake_options();
+
             debug!("Connecting to manager");
             let mut client = connect_to_manager(format, network).await?;
 
@@ -165,11 +217,15 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
             debug!("Connecting to server at {}", new_destination);
             let id = match format {
                 Format::Shell => client
-                    .connect(new_destination, Map::new(), PromptAuthHandler::new())
+                    .connect(new_destination, handshake_options, PromptAuthHandler::new())
                     .await
                     .context("Failed to connect to server")?,
                 Format::Json => client
-                    .connect(new_destination, Map::new(), JsonAuthHandler::default())
+                    .connect(
+                        new_destination,
+                        handshake_options,
+                        JsonAuthHandler::default(),
+                    )
                     .await
                     .context("Failed to connect to server")?,
             };
@@ -321,6 +377,34 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
 
             debug!("Shutting down repl");
         }
+        ClientSubcommand::Proxy {
+            cache,
+            connection,
+            network,
+            socks5,
+        } => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            debug!("Starting SOCKS5 proxy on {}", socks5);
+            Proxy::new(channel.into_client().into_channel())
+                .socks5(socks5)
+                .await?;
+        }
         ClientSubcommand::Shell {
             cache,
             cmd,
@@ -371,7 +455,9 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
             environment,
             lsp,
             pty,
+            login,
             network,
+            format,
         } => {
             debug!("Connecting to manager");
             let mut client = Client::new(network)
@@ -416,32 +502,67 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                     .await?;
             } else {
                 debug!(
-                    "Spawning regular process (environment = {:?}, cwd = {:?}): {}",
-                    environment, current_dir, cmd
+                    "Spawning regular process (environment = {:?}, cwd = {:?}, login = {}): {}",
+                    environment, current_dir, login, cmd
                 );
                 let mut proc = RemoteCommand::new()
                     .environment(environment.into_map())
                     .current_dir(current_dir)
                     .pty(None)
+                    .options(ProcSpawnOptions {
+                        login_shell: login,
+                        ..Default::default()
+                    })
                     .spawn(channel.into_client().into_channel(), &cmd)
                     .await
                     .with_context(|| format!("Failed to spawn {cmd}"))?;
 
-                // Now, map the remote process' stdin/stdout/stderr to our own process
-                let link = RemoteProcessLink::from_remote_pipes(
-                    proc.stdin.take(),
-                    proc.stdout.take().unwrap(),
-                    proc.stderr.take().unwrap(),
-                    MAX_PIPE_CHUNK_SIZE,
-                );
+                let (success, code) = match format {
+                    Format::Shell => {
+                        // Now, map the remote process' stdin/stdout/stderr to our own process
+                        let link = RemoteProcessLink::from_remote_pipes(
+                            proc.stdin.take(),
+                            proc.stdout.take().unwrap(),
+                            proc.stderr.take().unwrap(),
+                            MAX_PIPE_CHUNK_SIZE,
+                        );
 
-                let status = proc.wait().await.context("Failed to wait for process")?;
+                        let status = proc.wait().await.context("Failed to wait for process")?;
 
-                // Shut down our link
-                link.shutdown().await;
+                        // Shut down our link
+                        link.shutdown().await;
 
-                if !status.success {
-                    if let Some(code) = status.code {
+                        (status.success, status.code)
+                    }
+                    Format::Json => {
+                        let output = proc
+                            .output()
+                            .await
+                            .context("Failed to collect process output")?;
+
+                        println!(
+                            "{}",
+                            serde_json::to_string(&json!({
+                                "type": "proc_done",
+                                "success": output.success,
+                                "code": output.code,
+                                "signal": output.signal,
+                                "core_dumped": output.core_dumped,
+                                "limit": output.limit,
+                                "timed_out": output.timed_out,
+                                "usage": output.usage,
+                                "stdout": String::from_utf8_lossy(&output.stdout),
+                                "stderr": String::from_utf8_lossy(&output.stderr),
+                            }))
+                            .unwrap()
+                        );
+
+                        (output.success, output.code)
+                    }
+                };
+
+                if !success {
+                    if let Some(code) = code {
                         return Err(CliError::Exit(code as u8));
                     } else {
                         return Err(CliError::FAILURE);
@@ -653,6 +774,47 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                     format!("Failed to copy {src:?} to {dst:?} using connection {connection_id}")
                 })?;
         }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Download {
+            cache,
+            connection,
+            network,
+            recursive,
+            src,
+            dst,
+        }) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            let (options, progress) = transfer_options(recursive);
+            debug!("Downloading {src:?} to {dst:?} (recursive = {recursive})");
+            let result = channel
+                .into_client()
+                .into_channel()
+                .download(src.as_path(), dst.as_path(), options)
+                .await;
+
+            if let Some(progress) = progress {
+                let _ = progress.await;
+            }
+
+            result.with_context(|| {
+                format!("Failed to download {src:?} to {dst:?} using connection {connection_id}")
+            })?;
+        }
         ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Exists {
             cache,
             connection,
@@ -694,6 +856,148 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                 println!("false");
             }
         }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Hash {
+            cache,
+            connection,
+            network,
+            algorithm,
+            recursive,
+            offset,
+            len,
+            path,
+        }) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let mut channel: DistantChannel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?
+                .into_client()
+                .into_channel();
+
+            if recursive {
+                debug!("Hashing files within {path:?} using {algorithm}");
+                let entries = channel
+                    .hash_dir(path.as_path(), algorithm, offset, len)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to hash {path:?} using connection {connection_id}")
+                    })?;
+
+                // Mirror the output of tools like sha256sum so results can be compared easily
+                for entry in entries {
+                    println!("{}  {}", entry.digest, entry.path.to_string_lossy());
+                }
+            } else {
+                debug!("Hashing {path:?} using {algorithm}");
+                let digest = channel
+                    .hash(path.as_path(), algorithm, offset, len)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to hash {path:?} using connection {connection_id}")
+                    })?;
+
+                println!("{digest}");
+            }
+        }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Link(
+            ClientFileSystemLinkSubcommand::Create {
+                cache,
+                connection,
+                network,
+                hard,
+                src,
+                dst,
+            },
+        )) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let mut channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?
+                .into_client()
+                .into_channel();
+
+            if hard {
+                debug!("Creating hard link {dst:?} to {src:?}");
+                channel
+                    .hard_link(src.as_path(), dst.as_path())
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to create hard link {dst:?} to {src:?} using connection {connection_id}"
+                        )
+                    })?;
+            } else {
+                debug!("Creating symlink {dst:?} pointing to {src:?}");
+                channel
+                    .symlink(src.as_path(), dst.as_path())
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to create symlink {dst:?} pointing to {src:?} using connection {connection_id}"
+                        )
+                    })?;
+            }
+        }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Link(
+            ClientFileSystemLinkSubcommand::Read {
+                cache,
+                connection,
+                network,
+                path,
+            },
+        )) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            debug!("Reading link {path:?}");
+            let target = channel
+                .into_client()
+                .into_channel()
+                .read_link(path.as_path())
+                .await
+                .with_context(|| {
+                    format!("Failed to read link {path:?} using connection {connection_id}")
+                })?;
+
+            println!("{}", target.to_string_lossy());
+        }
         ClientSubcommand::FileSystem(ClientFileSystemSubcommand::MakeDir {
             cache,
             connection,
@@ -1028,6 +1332,75 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                     format!("Failed to rename {src:?} to {dst:?} using connection {connection_id}")
                 })?;
         }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Replace {
+            cache,
+            connection,
+            network,
+            dry_run,
+            condition,
+            replacement,
+            options,
+            paths,
+        }) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            let query = SearchQuery {
+                target: SearchQueryTarget::Contents,
+                condition: options.compose_condition(condition),
+                paths,
+                options: options.into(),
+            };
+
+            let mut replacer = Replacer::replace(
+                channel.into_client().into_channel(),
+                query,
+                replacement,
+                dry_run,
+            )
+            .await
+            .context("Failed to start replace")?;
+
+            // Report each file that the server changed, which is a diff of the changes during a
+            // dry run and the number of replacements otherwise
+            while let Some(file) = replacer.next().await.context("Failed to replace")? {
+                match file.diff {
+                    Some(diff) => print!("{diff}"),
+                    None => println!(
+                        "{}: {} replacement(s)",
+                        file.path.to_string_lossy(),
+                        file.replacements
+                    ),
+                }
+            }
+
+            let summary = replacer.summary().unwrap_or_default();
+            if dry_run {
+                println!(
+                    "Would replace {} match(es) in {} file(s)",
+                    summary.replacements, summary.files
+                );
+            } else {
+                println!(
+                    "Replaced {} match(es) in {} file(s)",
+                    summary.replacements, summary.files
+                );
+            }
+        }
         ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Search {
             cache,
             connection,
@@ -1056,7 +1429,7 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
 
             let query = SearchQuery {
                 target: target.into(),
-                condition,
+                condition: options.compose_condition(condition),
                 paths,
                 options: options.into(),
             };
@@ -1082,14 +1455,30 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                         path,
                         lines,
                         line_number,
+                        context,
                         ..
                     }) => {
                         let file_matches = files.entry(path).or_default();
 
+                        // Context lines use a dash instead of a colon to set them apart from
+                        // the lines that matched
+                        let (before, after): (Vec<_>, Vec<_>) = context
+                            .into_iter()
+                            .partition(|line| line.kind == SearchQueryContextKind::Before);
+                        let to_context_line = |line: SearchQueryContextLine| {
+                            format!(
+                                "{}-{}",
+                                line.line_number,
+                                line.line.to_string_lossy().trim_end()
+                            )
+                        };
+
+                        file_matches.extend(before.into_iter().map(to_context_line));
                         file_matches.push(format!(
                             "{line_number}:{}",
                             lines.to_string_lossy().trim_end()
                         ));
+                        file_matches.extend(after.into_iter().map(to_context_line));
                     }
                 }
 
@@ -1188,6 +1577,96 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
                     )
                 })?;
         }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Sync {
+            cache,
+            connection,
+            network,
+            delete,
+            dry_run,
+            include,
+            exclude,
+            src,
+            dst,
+        }) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            let options = SyncOptions {
+                delete,
+                dry_run,
+                include,
+                exclude,
+                ..Default::default()
+            };
+            debug!("Syncing {src:?} to {dst:?} (options = {options:?})");
+            let changes = channel
+                .into_client()
+                .into_channel()
+                .sync_dir(src.as_path(), dst.as_path(), options)
+                .await
+                .with_context(|| {
+                    format!("Failed to sync {src:?} to {dst:?} using connection {connection_id}")
+                })?;
+
+            for change in changes {
+                println!("{change}");
+            }
+        }
+        ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Upload {
+            cache,
+            connection,
+            network,
+            recursive,
+            src,
+            dst,
+        }) => {
+            debug!("Connecting to manager");
+            let mut client = Client::new(network)
+                .using_prompt_auth_handler()
+                .connect()
+                .await
+                .context("Failed to connect to manager")?;
+
+            let mut cache = read_cache(&cache).await;
+            let connection_id =
+                use_or_lookup_connection_id(&mut cache, connection, &mut client).await?;
+
+            debug!("Opening channel to connection {}", connection_id);
+            let channel = client
+                .open_raw_channel(connection_id)
+                .await
+                .with_context(|| format!("Failed to open channel to connection {connection_id}"))?;
+
+            let (options, progress) = transfer_options(recursive);
+            debug!("Uploading {src:?} to {dst:?} (recursive = {recursive})");
+            let result = channel
+                .into_client()
+                .into_channel()
+                .upload(src.as_path(), dst.as_path(), options)
+                .await;
+
+            if let Some(progress) = progress {
+                let _ = progress.await;
+            }
+
+            result.with_context(|| {
+                format!("Failed to upload {src:?} to {dst:?} using connection {connection_id}")
+            })?;
+        }
         ClientSubcommand::FileSystem(ClientFileSystemSubcommand::Watch {
             cache,
             connection,
@@ -1313,6 +1792,58 @@ async fn async_run(cmd: ClientSubcommand) -> CliResult {
     Ok(())
 }
 
+/// Returns true if stderr is a terminal
+#[cfg(unix)]
+fn is_stderr_terminal() -> bool {
+    use std::os::unix::io::AsRawFd;
+    terminal_size::terminal_size_using_fd(io::stderr().as_raw_fd()).is_some()
+}
+
+/// Returns true if stderr is a terminal
+#[cfg(windows)]
+fn is_stderr_terminal() -> bool {
+    use std::os::windows::io::AsRawHandle;
+    terminal_size::terminal_size_using_handle(io::stderr().as_raw_handle()).is_some()
+}
+
+/// Creates options for uploading or downloading, along with a task that prints the progress of
+/// each file to stderr when it is a terminal
+fn transfer_options(recursive: bool) -> (TransferOptions, Option<JoinHandle<()>>) {
+    if !is_stderr_terminal() {
+        let options = TransferOptions {
+            recursive,
+            progress: None,
+        };
+        return (options, None);
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<TransferProgress>();
+    let task = tokio::spawn(async move {
+        while let Some(progress) = rx.recv().await {
+            let percent = (progress.transferred * 100)
+                .checked_div(progress.len)
+                .unwrap_or(100);
+
+            eprint!(
+                "\r{} {percent:>3}% ({}/{} bytes)",
+                progress.path.to_string_lossy(),
+                progress.transferred,
+                progress.len
+            );
+
+            if progress.transferred >= progress.len {
+                eprintln!();
+            }
+        }
+    });
+
+    let options = TransferOptions {
+        recursive,
+        progress: Some(tx),
+    };
+    (options, Some(task))
+}
+
 async fn use_or_lookup_connection_id(
     cache: &mut Cache,
     connection: Option<ConnectionId>,
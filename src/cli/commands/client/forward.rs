@@ -0,0 +1,134 @@
+use std::io;
+
+use anyhow::Context;
+use distant_core::{DistantChannel, RemoteTcpListener, RemoteTcpStream};
+use log::*;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+
+use super::{CliError, CliResult};
+use crate::options::ForwardSpec;
+
+#[derive(Clone)]
+pub struct Forward(DistantChannel);
+
+impl Forward {
+    pub fn new(channel: DistantChannel) -> Self {
+        Self(channel)
+    }
+
+    /// Forwards connections made to the local machine for each of `local` and to the remote
+    /// machine for each of `remote`, running until every listener has stopped
+    pub async fn run(self, local: Vec<ForwardSpec>, remote: Vec<ForwardSpec>) -> CliResult {
+        if local.is_empty() && remote.is_empty() {
+            return Err(CliError::Error(anyhow::anyhow!(
+                "At least one --local or --remote forward is required"
+            )));
+        }
+
+        let mut tasks = JoinSet::new();
+
+        for spec in local {
+            let listener = TcpListener::bind((spec.bind_address.as_str(), spec.port))
+                .await
+                .with_context(|| {
+                    format!("Failed to listen on {}:{}", spec.bind_address, spec.port)
+                })?;
+            let port = listener
+                .local_addr()
+                .context("Failed to get local listening address")?
+                .port();
+
+            println!(
+                "Forwarding local {}:{} to remote {}:{}",
+                spec.bind_address, port, spec.host, spec.host_port
+            );
+            tasks.spawn(forward_local(self.0.clone(), listener, spec));
+        }
+
+        for spec in remote {
+            let listener =
+                RemoteTcpListener::bind(self.0.clone(), spec.bind_address.as_str(), spec.port)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to listen on remote {}:{}",
+                            spec.bind_address, spec.port
+                        )
+                    })?;
+
+            println!(
+                "Forwarding remote {}:{} to local {}:{}",
+                spec.bind_address,
+                listener.port(),
+                spec.host,
+                spec.host_port
+            );
+            tasks.spawn(forward_remote(listener, spec));
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            res.context("Forwarding task failed unexpectedly")?
+                .context("Forwarding stopped")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Accepts connections on the local `listener`, piping each to a new stream opened by the remote
+/// machine to the host and port of `spec`
+async fn forward_local(
+    channel: DistantChannel,
+    listener: TcpListener,
+    spec: ForwardSpec,
+) -> io::Result<()> {
+    loop {
+        let (stream, addr) = listener.accept().await?;
+        debug!("Accepted local connection from {addr}");
+
+        let channel = channel.clone();
+        let host = spec.host.clone();
+        let port = spec.host_port;
+        tokio::spawn(async move {
+            let remote = match RemoteTcpStream::connect(channel, host.as_str(), port).await {
+                Ok(remote) => remote,
+                Err(x) => {
+                    error!("Failed to connect to remote {host}:{port}: {x}");
+                    return;
+                }
+            };
+
+            if let Err(x) = remote.pipe(stream).await {
+                error!("Forwarding from {addr} to remote {host}:{port} failed: {x}");
+            }
+        });
+    }
+}
+
+/// Accepts streams on the remote `listener`, piping each to a new local connection to the host
+/// and port of `spec`
+async fn forward_remote(mut listener: RemoteTcpListener, spec: ForwardSpec) -> io::Result<()> {
+    while let Some((mut remote, addr)) = listener.accept().await {
+        debug!("Accepted remote connection from {addr}");
+
+        let host = spec.host.clone();
+        let port = spec.host_port;
+        tokio::spawn(async move {
+            let stream = match TcpStream::connect((host.as_str(), port)).await {
+                Ok(stream) => stream,
+                Err(x) => {
+                    error!("Failed to connect to local {host}:{port}: {x}");
+                    let _ = remote.close().await;
+                    return;
+                }
+            };
+
+            if let Err(x) = remote.pipe(stream).await {
+                error!("Forwarding from remote {addr} to {host}:{port} failed: {x}");
+            }
+        });
+    }
+
+    Ok(())
+}
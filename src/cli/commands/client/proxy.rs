@@ -0,0 +1,164 @@
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use anyhow::Context;
+use distant_core::{DistantChannel, RemoteTcpStream};
+use log::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::CliResult;
+
+/// Version of the SOCKS protocol that is supported
+const SOCKS_VERSION: u8 = 0x05;
+
+/// Authentication method where no authentication is required
+const METHOD_NO_AUTH: u8 = 0x00;
+
+/// Authentication method reply indicating that none of the offered methods are acceptable
+const METHOD_NOT_ACCEPTABLE: u8 = 0xFF;
+
+/// Command to establish a tcp stream to some destination
+const CMD_CONNECT: u8 = 0x01;
+
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+const REPLY_SUCCEEDED: u8 = 0x00;
+const REPLY_GENERAL_FAILURE: u8 = 0x01;
+const REPLY_CONNECTION_REFUSED: u8 = 0x05;
+const REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const REPLY_ADDRESS_TYPE_NOT_SUPPORTED: u8 = 0x08;
+
+#[derive(Clone)]
+pub struct Proxy(DistantChannel);
+
+impl Proxy {
+    pub fn new(channel: DistantChannel) -> Self {
+        Self(channel)
+    }
+
+    /// Listens for SOCKS5 clients at `addr`, dialing each requested destination from the remote
+    /// machine and forwarding data over the channel until the listener fails
+    pub async fn socks5(self, addr: SocketAddr) -> CliResult {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("Failed to listen on {addr}"))?;
+        let addr = listener
+            .local_addr()
+            .context("Failed to get local listening address")?;
+
+        println!("Listening for SOCKS5 connections on {addr}");
+
+        loop {
+            let (stream, peer_addr) = listener
+                .accept()
+                .await
+                .context("Failed to accept SOCKS5 connection")?;
+            debug!("Accepted SOCKS5 connection from {peer_addr}");
+
+            let channel = self.0.clone();
+            tokio::spawn(async move {
+                if let Err(x) = handle_socks5(channel, stream).await {
+                    error!("SOCKS5 connection from {peer_addr} failed: {x}");
+                }
+            });
+        }
+    }
+}
+
+/// Performs the SOCKS5 handshake with a client, opening a stream from the remote machine to the
+/// requested destination and piping data between the two
+async fn handle_socks5(channel: DistantChannel, mut stream: TcpStream) -> io::Result<()> {
+    // Greeting: VER | NMETHODS | METHODS
+    let version = stream.read_u8().await?;
+    if version != SOCKS_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported SOCKS version {version}"),
+        ));
+    }
+
+    let mut methods = vec![0; stream.read_u8().await? as usize];
+    stream.read_exact(&mut methods).await?;
+    if !methods.contains(&METHOD_NO_AUTH) {
+        stream
+            .write_all(&[SOCKS_VERSION, METHOD_NOT_ACCEPTABLE])
+            .await?;
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "Client does not support connecting without authentication",
+        ));
+    }
+    stream.write_all(&[SOCKS_VERSION, METHOD_NO_AUTH]).await?;
+
+    // Request: VER | CMD | RSV | ATYP | DST.ADDR | DST.PORT
+    let mut header = [0; 4];
+    stream.read_exact(&mut header).await?;
+    let [version, cmd, _, atyp] = header;
+    if version != SOCKS_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported SOCKS version {version}"),
+        ));
+    }
+
+    let host = match atyp {
+        ATYP_IPV4 => {
+            let mut octets = [0; 4];
+            stream.read_exact(&mut octets).await?;
+            Ipv4Addr::from(octets).to_string()
+        }
+        ATYP_DOMAIN => {
+            let mut name = vec![0; stream.read_u8().await? as usize];
+            stream.read_exact(&mut name).await?;
+            String::from_utf8(name).map_err(|x| io::Error::new(io::ErrorKind::InvalidData, x))?
+        }
+        ATYP_IPV6 => {
+            let mut octets = [0; 16];
+            stream.read_exact(&mut octets).await?;
+            Ipv6Addr::from(octets).to_string()
+        }
+        _ => {
+            write_reply(&mut stream, REPLY_ADDRESS_TYPE_NOT_SUPPORTED).await?;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported address type {atyp}"),
+            ));
+        }
+    };
+    let port = stream.read_u16().await?;
+
+    if cmd != CMD_CONNECT {
+        write_reply(&mut stream, REPLY_COMMAND_NOT_SUPPORTED).await?;
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Unsupported command {cmd}"),
+        ));
+    }
+
+    debug!("Connecting to {host}:{port} from remote machine");
+    let remote = match RemoteTcpStream::connect(channel, host.as_str(), port).await {
+        Ok(remote) => remote,
+        Err(x) => {
+            let reply = match x.kind() {
+                io::ErrorKind::ConnectionRefused => REPLY_CONNECTION_REFUSED,
+                _ => REPLY_GENERAL_FAILURE,
+            };
+            write_reply(&mut stream, reply).await?;
+            return Err(x);
+        }
+    };
+
+    write_reply(&mut stream, REPLY_SUCCEEDED).await?;
+    remote.pipe(stream).await
+}
+
+/// Writes a reply to a SOCKS5 request; as the stream is dialed remotely, the bound address is
+/// always reported as unspecified
+async fn write_reply(stream: &mut TcpStream, reply: u8) -> io::Result<()> {
+    stream
+        .write_all(&[SOCKS_VERSION, reply, 0x00, ATYP_IPV4, 0, 0, 0, 0, 0, 0])
+        .await
+}
@@ -208,6 +208,10 @@ async fn async_run(cmd: ManagerSubcommand) -> CliResult {
                             "distant".to_string(),
                             Box::new(handlers::DistantConnectHandler),
                         );
+                        handlers
+                            .insert("ws".to_string(), Box::new(handlers::DistantConnectHandler));
+                        handlers
+                            .insert("wss".to_string(), Box::new(handlers::DistantConnectHandler));
 
                         #[cfg(any(feature = "libssh", feature = "ssh2"))]
                         handlers.insert("ssh".to_string(), Box::new(handlers::SshConnectHandler));
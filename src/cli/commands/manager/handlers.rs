@@ -5,20 +5,27 @@ use std::process::Stdio;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use clap::ValueEnum;
 use distant_core::net::auth::msg::*;
 use distant_core::net::auth::{
-    AuthHandler, Authenticator, DynAuthHandler, ProxyAuthHandler, SingleAuthHandler,
-    StaticKeyAuthMethodHandler,
+    AuthHandler, AuthMethodHandler, Authenticator, DynAuthHandler, ProxyAuthHandler,
+    SingleAuthHandler, StaticKeyAuthMethodHandler,
+};
+use distant_core::net::client::{
+    Client, ClientConfig, Connector, ReconnectStrategy, TcpConnector, UntypedClient,
+    WebSocketConnector,
+};
+use distant_core::net::common::{
+    Destination, Handshake, HostKeyPolicy, HostKeyVerifier, KnownHosts, Map, SecretKey32,
 };
-use distant_core::net::client::{Client, ClientConfig, ReconnectStrategy, UntypedClient};
-use distant_core::net::common::{Destination, Map, SecretKey32};
 use distant_core::net::manager::{ConnectHandler, LaunchHandler};
 use log::*;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::Mutex;
 
-use crate::options::{BindAddress, ClientLaunchConfig};
+use crate::constants::user::KNOWN_HOSTS_FILE_PATH;
+use crate::options::{BindAddress, ClientLaunchConfig, Compression, Encryption};
 
 #[inline]
 fn missing(label: &str) -> io::Error {
@@ -219,23 +226,25 @@ impl LaunchHandler for SshLaunchHandler {
     }
 }
 
-/// Supports connecting to a remote distant TCP server as defined by `distant://...`
+/// Supports connecting to a remote distant server over TCP as defined by `distant://...` or over
+/// WebSocket as defined by `ws://...` and `wss://...`
 pub struct DistantConnectHandler;
 
 impl DistantConnectHandler {
-    async fn try_connect(
-        ips: Vec<IpAddr>,
-        port: u16,
+    async fn try_connect<C: Connector + Send>(
+        connectors: Vec<C>,
+        handshake: Handshake,
+        host_key_verifier: HostKeyVerifier,
         mut auth_handler: impl AuthHandler,
     ) -> io::Result<UntypedClient> {
-        // Try each IP address with the same port to see if one works
+        // Try each connector to see if one works
         let mut err = None;
-        for ip in ips {
-            let addr = SocketAddr::new(ip, port);
-            debug!("Attempting to connect to distant server @ {}", addr);
-
-            match Client::tcp(addr)
+        for connector in connectors {
+            match Client::build()
+                .connector(connector)
                 .auth_handler(DynAuthHandler::from(&mut auth_handler))
+                .handshake(handshake.clone())
+                .host_key_verifier(host_key_verifier.clone())
                 .config(ClientConfig {
                     reconnect_strategy: ReconnectStrategy::ExponentialBackoff {
                         base: Duration::from_secs(1),
@@ -258,6 +267,40 @@ impl DistantConnectHandler {
         // If all failed, return the last error we got
         Err(err.expect("Err set above"))
     }
+
+    /// Connects using the static `key` to authenticate if provided, otherwise deferring
+    /// authentication to the `authenticator`
+    async fn try_connect_with_key<C: Connector + Send>(
+        connectors: Vec<C>,
+        key: Option<SecretKey32>,
+        handshake: Handshake,
+        host_key_verifier: HostKeyVerifier,
+        authenticator: &mut dyn Authenticator,
+    ) -> io::Result<UntypedClient> {
+        match key {
+            Some(key) => {
+                Self::try_connect(
+                    connectors,
+                    handshake,
+                    host_key_verifier,
+                    StaticKeyAuthHandler {
+                        key: SingleAuthHandler::new(StaticKeyAuthMethodHandler::simple(key)),
+                        authenticator: ProxyAuthHandler::new(authenticator),
+                    },
+                )
+                .await
+            }
+            None => {
+                Self::try_connect(
+                    connectors,
+                    handshake,
+                    host_key_verifier,
+                    ProxyAuthHandler::new(authenticator),
+                )
+                .await
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -270,49 +313,154 @@ impl ConnectHandler for DistantConnectHandler {
     ) -> io::Result<UntypedClient> {
         debug!("Handling connect of {destination} with options '{options}'");
         let host = destination.host.to_string();
-        let port = destination.port.ok_or_else(|| missing("port"))?;
+        let websocket_url = destination.to_websocket_url();
+        let port = match destination.port {
+            Some(port) => port,
+            None if destination.scheme_eq("ws") => 80,
+            None if destination.scheme_eq("wss") => 443,
+            None => return Err(missing("port")),
+        };
 
-        debug!("Looking up host {host} @ port {port}");
-        let mut candidate_ips = tokio::net::lookup_host(format!("{host}:{port}"))
-            .await
-            .map_err(|x| {
-                io::Error::new(
-                    x.kind(),
-                    format!("{host} needs to be resolvable outside of ssh: {x}"),
-                )
-            })?
-            .map(|addr| addr.ip())
-            .collect::<Vec<IpAddr>>();
-        candidate_ips.sort_unstable();
-        candidate_ips.dedup();
-        if candidate_ips.is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::AddrNotAvailable,
-                format!("Unable to resolve {host}:{port}"),
-            ));
-        }
+        // Verify the identity of the server against the hosts we have trusted before, keyed by
+        // the host and port as given in the destination
+        let policy = match options.get("host_key_policy") {
+            Some(policy) => policy
+                .parse::<HostKeyPolicy>()
+                .map_err(|_| invalid("host_key_policy"))?,
+            None => HostKeyPolicy::default(),
+        };
+        let known_hosts = KnownHosts::load(KNOWN_HOSTS_FILE_PATH.as_path()).await?;
+        let host_key_verifier = HostKeyVerifier::new(format!("{host}:{port}"), known_hosts, policy);
+
+        // Prefer the requested encryption, falling back to whatever the server supports
+        let handshake = match options.get("encryption") {
+            Some(encryption) => Handshake::client().with_preferred_encryption_type(Some(
+                Encryption::from_str(encryption, true)
+                    .map_err(|_| invalid("encryption"))?
+                    .into_encryption_type(),
+            )),
+            None => Handshake::client(),
+        };
+
+        // Prefer the requested compression, not compressing if the server does not support it
+        let compression = options
+            .get("compression")
+            .map(|x| Compression::from_str(x, true).map_err(|_| invalid("compression")))
+            .transpose()?
+            .map(Compression::into_compression_type);
+        let adaptive_compression = options
+            .get("adaptive_compression")
+            .map(|x| {
+                x.parse::<bool>()
+                    .map_err(|_| invalid("adaptive_compression"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+        let handshake =
+            handshake.with_preferred_compression_type(compression, adaptive_compression);
 
         // For legacy reasons, we need to support a static key being provided
         // via part of the destination OR an option, and attempt to use it
         // during authentication if it is provided
-        if let Some(key) = destination
+        let key = destination
             .password
             .as_deref()
             .or_else(|| options.get("key").map(|s| s.as_str()))
-        {
-            let key = key.parse::<SecretKey32>().map_err(|_| invalid("key"))?;
-            Self::try_connect(
-                candidate_ips,
-                port,
-                SingleAuthHandler::new(StaticKeyAuthMethodHandler::simple(key)),
+            .map(|key| key.parse::<SecretKey32>().map_err(|_| invalid("key")))
+            .transpose()?;
+
+        // NOTE: WebSocket connections may go through a proxy, so we leave resolving the host to
+        //       whatever we connect to rather than looking it up ourselves
+        if let Some(url) = websocket_url {
+            debug!("Attempting to connect to distant server @ {url}");
+            Self::try_connect_with_key(
+                vec![WebSocketConnector::new(url)],
+                key,
+                handshake,
+                host_key_verifier,
+                authenticator,
             )
             .await
         } else {
-            Self::try_connect(candidate_ips, port, ProxyAuthHandler::new(authenticator)).await
+            debug!("Looking up host {host} @ port {port}");
+            let mut candidate_ips = tokio::net::lookup_host(format!("{host}:{port}"))
+                .await
+                .map_err(|x| {
+                    io::Error::new(
+                        x.kind(),
+                        format!("{host} needs to be resolvable outside of ssh: {x}"),
+                    )
+                })?
+                .map(|addr| addr.ip())
+                .collect::<Vec<IpAddr>>();
+            candidate_ips.sort_unstable();
+            candidate_ips.dedup();
+            if candidate_ips.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrNotAvailable,
+                    format!("Unable to resolve {host}:{port}"),
+                ));
+            }
+
+            // Try each IP address with the same port to see if one works
+            debug!("Attempting to connect to distant server @ {candidate_ips:?} with port {port}");
+            let connectors = candidate_ips
+                .into_iter()
+                .map(|ip| TcpConnector::new(SocketAddr::new(ip, port)))
+                .collect::<Vec<_>>();
+            Self::try_connect_with_key(connectors, key, handshake, host_key_verifier, authenticator)
+                .await
         }
     }
 }
 
+/// Authenticates using a static key, but defers verification of the host to an [`Authenticator`]
+/// so that a changed host key is never accepted without asking
+struct StaticKeyAuthHandler<'a> {
+    key: SingleAuthHandler,
+    authenticator: ProxyAuthHandler<'a>,
+}
+
+#[async_trait]
+impl<'a> AuthHandler for StaticKeyAuthHandler<'a> {
+    async fn on_initialization(
+        &mut self,
+        initialization: Initialization,
+    ) -> io::Result<InitializationResponse> {
+        self.key.on_initialization(initialization).await
+    }
+
+    async fn on_start_method(&mut self, start_method: StartMethod) -> io::Result<()> {
+        self.key.on_start_method(start_method).await
+    }
+
+    async fn on_finished(&mut self) -> io::Result<()> {
+        self.key.on_finished().await
+    }
+}
+
+#[async_trait]
+impl<'a> AuthMethodHandler for StaticKeyAuthHandler<'a> {
+    async fn on_challenge(&mut self, challenge: Challenge) -> io::Result<ChallengeResponse> {
+        self.key.on_challenge(challenge).await
+    }
+
+    async fn on_verification(
+        &mut self,
+        verification: Verification,
+    ) -> io::Result<VerificationResponse> {
+        self.authenticator.on_verification(verification).await
+    }
+
+    async fn on_info(&mut self, info: Info) -> io::Result<()> {
+        self.key.on_info(info).await
+    }
+
+    async fn on_error(&mut self, error: Error) -> io::Result<()> {
+        self.key.on_error(error).await
+    }
+}
+
 /// Supports connecting to a remote SSH server as defined by `ssh://...`
 #[cfg(any(feature = "libssh", feature = "ssh2"))]
 pub struct SshConnectHandler;
@@ -2,12 +2,15 @@ use std::io::{self, Read, Write};
 
 use anyhow::Context;
 use distant_core::net::auth::Verifier;
-use distant_core::net::common::{Host, SecretKey32};
+use distant_core::net::common::{Host, SecretKey32, ServerIdentity};
 use distant_core::net::server::{Server, ServerConfig as NetServerConfig, ServerRef};
 use distant_core::DistantSingleKeyCredentials;
-use distant_local::{Config as LocalConfig, WatchConfig as LocalWatchConfig};
+use distant_local::{
+    Config as LocalConfig, ProcessConfig as LocalProcessConfig, WatchConfig as LocalWatchConfig,
+};
 use log::*;
 
+use crate::constants::user::SERVER_IDENTITY_FILE_PATH;
 use crate::options::ServerSubcommand;
 use crate::{CliError, CliResult};
 
@@ -25,7 +28,7 @@ pub fn run(cmd: ServerSubcommand) -> CliResult {
 fn run_daemon(_cmd: ServerSubcommand) -> CliResult {
     use std::ffi::OsString;
 
-    use distant_core::net::common::{Listener, TransportExt, WindowsPipeListener};
+    use distant_core::net::common::{Destination, Listener, TransportExt, WindowsPipeListener};
 
     use crate::cli::Spawner;
     let rt = tokio::runtime::Runtime::new().context("Failed to start up runtime")?;
@@ -56,9 +59,12 @@ fn run_daemon(_cmd: ServerSubcommand) -> CliResult {
         if n == 0 {
             anyhow::bail!("No credentials received from spawned server");
         }
+        // NOTE: Servers listening over WebSocket send credentials with the ws scheme, so we parse
+        //       them as a general destination
         let credentials = s[..n]
             .trim()
-            .parse::<DistantSingleKeyCredentials>()
+            .parse::<Destination>()
+            .map_err(anyhow::Error::msg)
             .context("Failed to parse server credentials")?;
 
         println!("\r");
@@ -103,11 +109,14 @@ async fn async_run(cmd: ServerSubcommand, _is_forked: bool) -> CliResult {
             host,
             port,
             use_ipv6,
+            ws,
             shutdown,
             current_dir,
+            max_processes,
             watch,
             daemon: _,
             key_from_stdin,
+            identity_file,
             output_to_local_pipe,
         } => {
             let host = host.into_inner();
@@ -143,6 +152,9 @@ async fn async_run(cmd: ServerSubcommand, _is_forked: bool) -> CliResult {
                 }
             );
             let handler = distant_local::new_handler(LocalConfig {
+                process: LocalProcessConfig {
+                    max_per_connection: max_processes,
+                },
                 watch: LocalWatchConfig {
                     native: !watch.watch_polling,
                     poll_interval: watch.watch_poll_interval.map(Into::into),
@@ -152,20 +164,50 @@ async fn async_run(cmd: ServerSubcommand, _is_forked: bool) -> CliResult {
                 },
             })
             .context("Failed to create local distant api")?;
-            let server = Server::tcp()
+
+            let identity_file =
+                identity_file.unwrap_or_else(|| SERVER_IDENTITY_FILE_PATH.to_path_buf());
+            debug!("Loading server identity from {identity_file:?}");
+            let identity = ServerIdentity::load_or_generate(&identity_file)
+                .await
+                .with_context(|| {
+                    format!("Failed to load server identity from {identity_file:?}")
+                })?;
+            info!(
+                "Server host key fingerprint: {}",
+                identity.host_key().fingerprint()
+            );
+
+            let server = Server::new()
                 .config(NetServerConfig {
                     shutdown: shutdown.into_inner(),
                     ..Default::default()
                 })
                 .handler(handler)
                 .verifier(Verifier::static_key(key.clone()))
-                .start(addr, port)
-                .await
-                .with_context(|| format!("Failed to start server @ {addr} with {port}"))?;
+                .identity(identity);
+
+            let (port, server): (u16, Box<dyn ServerRef>) = if ws {
+                let server = server
+                    .into_websocket_builder()
+                    .start(addr, port)
+                    .await
+                    .with_context(|| {
+                        format!("Failed to start WebSocket server @ {addr} with {port}")
+                    })?;
+                (server.port(), Box::new(server))
+            } else {
+                let server = server
+                    .into_tcp_builder()
+                    .start(addr, port)
+                    .await
+                    .with_context(|| format!("Failed to start server @ {addr} with {port}"))?;
+                (server.port(), Box::new(server))
+            };
 
             let credentials = DistantSingleKeyCredentials {
                 host: Host::from(addr),
-                port: server.port(),
+                port,
                 key,
                 username: None,
             };
@@ -174,6 +216,17 @@ async fn async_run(cmd: ServerSubcommand, _is_forked: bool) -> CliResult {
                 credentials.host, credentials.port
             );
 
+            // Clients of a WebSocket server need to connect using the ws scheme instead
+            let credentials = if ws {
+                let mut destination = credentials
+                    .try_to_destination()
+                    .context("Failed to build server destination")?;
+                destination.scheme = Some("ws".to_string());
+                destination.to_string()
+            } else {
+                credentials.to_string()
+            };
+
             // Print information about port, key, etc.
             // NOTE: Following mosh approach of printing to make sure there's no garbage floating around
             #[cfg(not(windows))]
@@ -193,7 +246,7 @@ async fn async_run(cmd: ServerSubcommand, _is_forked: bool) -> CliResult {
                     .await
                     .with_context(|| format!("Failed to connect to local pipe named {name:?}"))?;
                 transport
-                    .write_all(credentials.to_string().as_bytes())
+                    .write_all(credentials.as_bytes())
                     .await
                     .context("Failed to send credentials through pipe")?;
             } else {
@@ -212,7 +265,10 @@ async fn async_run(cmd: ServerSubcommand, _is_forked: bool) -> CliResult {
             }
 
             // Let our server run to completion
-            server.wait().await.context("Failed to wait on server")?;
+            server
+                .polling_wait()
+                .await
+                .context("Failed to wait on server")?;
             info!("Server is shutting down");
         }
     }
@@ -61,6 +61,7 @@ impl<T: AuthHandler + Clone> Client<T> {
             for path in self.network.to_unix_socket_path_candidates() {
                 match NetClient::unix_socket(path)
                     .auth_handler(self.auth_handler.clone())
+                    .handshake(self.network.to_client_handshake())
                     .config(ClientConfig {
                         reconnect_strategy: ReconnectStrategy::ExponentialBackoff {
                             base: Duration::from_secs(1),
@@ -103,6 +104,7 @@ impl<T: AuthHandler + Clone> Client<T> {
             for name in self.network.to_windows_pipe_name_candidates() {
                 match NetClient::local_windows_pipe(name)
                     .auth_handler(self.auth_handler.clone())
+                    .handshake(self.network.to_client_handshake())
                     .config(ClientConfig {
                         reconnect_strategy: ReconnectStrategy::ExponentialBackoff {
                             base: Duration::from_secs(1),
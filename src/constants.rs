@@ -25,6 +25,14 @@ pub mod user {
     pub static CONFIG_FILE_PATH: Lazy<PathBuf> =
         Lazy::new(|| PROJECT_DIR.config_dir().join("config.toml"));
 
+    /// Path to host keys of distant servers trusted by the client
+    pub static KNOWN_HOSTS_FILE_PATH: Lazy<PathBuf> =
+        Lazy::new(|| PROJECT_DIR.config_dir().join("known_hosts"));
+
+    /// Path to the identity used by a distant server to prove itself to clients
+    pub static SERVER_IDENTITY_FILE_PATH: Lazy<PathBuf> =
+        Lazy::new(|| PROJECT_DIR.config_dir().join("server_identity.pem"));
+
     /// Path to cache file used for arbitrary CLI data
     pub static CACHE_FILE_PATH: Lazy<PathBuf> =
         Lazy::new(|| PROJECT_DIR.cache_dir().join("cache.toml"));
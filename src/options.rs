@@ -1,4 +1,5 @@
 use std::ffi::OsString;
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 
 use clap::builder::TypedValueParser as _;
@@ -7,7 +8,7 @@ use clap_complete::Shell as ClapCompleteShell;
 use derive_more::IsVariant;
 use distant_core::net::common::{ConnectionId, Destination, Map, PortRange};
 use distant_core::net::server::Shutdown;
-use distant_core::protocol::ChangeKind;
+use distant_core::protocol::{ChangeKind, HashAlgorithm};
 use service_manager::ServiceManagerKind;
 
 use crate::constants;
@@ -111,19 +112,31 @@ impl Options {
                     }
                     ClientSubcommand::FileSystem(
                         ClientFileSystemSubcommand::Copy { network, .. }
+                        | ClientFileSystemSubcommand::Download { network, .. }
                         | ClientFileSystemSubcommand::Exists { network, .. }
+                        | ClientFileSystemSubcommand::Hash { network, .. }
+                        | ClientFileSystemSubcommand::Link(
+                            ClientFileSystemLinkSubcommand::Create { network, .. }
+                            | ClientFileSystemLinkSubcommand::Read { network, .. },
+                        )
                         | ClientFileSystemSubcommand::MakeDir { network, .. }
                         | ClientFileSystemSubcommand::Metadata { network, .. }
                         | ClientFileSystemSubcommand::Read { network, .. }
                         | ClientFileSystemSubcommand::Remove { network, .. }
                         | ClientFileSystemSubcommand::Rename { network, .. }
+                        | ClientFileSystemSubcommand::Replace { network, .. }
                         | ClientFileSystemSubcommand::Search { network, .. }
                         | ClientFileSystemSubcommand::SetPermissions { network, .. }
+                        | ClientFileSystemSubcommand::Sync { network, .. }
+                        | ClientFileSystemSubcommand::Upload { network, .. }
                         | ClientFileSystemSubcommand::Watch { network, .. }
                         | ClientFileSystemSubcommand::Write { network, .. },
                     ) => {
                         network.merge(config.client.network);
                     }
+                    ClientSubcommand::Forward { network, .. } => {
+                        network.merge(config.client.network);
+                    }
                     ClientSubcommand::Launch {
                         distant_args,
                         distant_bin,
@@ -141,6 +154,9 @@ impl Options {
                                 .take()
                                 .or(config.client.launch.distant.bind_server);
                     }
+                    ClientSubcommand::Proxy { network, .. } => {
+                        network.merge(config.client.network);
+                    }
                     ClientSubcommand::Shell { network, .. } => {
                         network.merge(config.client.network);
                     }
@@ -191,9 +207,11 @@ impl Options {
                     ServerSubcommand::Listen {
                         current_dir,
                         host,
+                        max_processes,
                         port,
                         shutdown,
                         use_ipv6,
+                        ws,
                         watch,
                         ..
                     } => {
@@ -202,6 +220,8 @@ impl Options {
                         //
 
                         *current_dir = current_dir.take().or(config.server.listen.current_dir);
+                        *max_processes =
+                            max_processes.take().or(config.server.listen.max_processes);
                         if host.is_default() && config.server.listen.host.is_some() {
                             *host = Value::Explicit(config.server.listen.host.unwrap());
                         }
@@ -214,6 +234,9 @@ impl Options {
                         if !*use_ipv6 && config.server.listen.use_ipv6 {
                             *use_ipv6 = true;
                         }
+                        if !*ws && config.server.listen.ws {
+                            *ws = true;
+                        }
 
                         //
                         // WATCH-SPECIFIC SETTINGS
@@ -328,6 +351,35 @@ pub enum ClientSubcommand {
     #[clap(subcommand, name = "fs")]
     FileSystem(ClientFileSystemSubcommand),
 
+    /// Forwards tcp ports between the local and remote machines
+    Forward {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// Listens on the local machine, forwarding each connection to a host and port reachable
+        /// by the remote machine, in the form `[bind_address:]port:host:hostport`
+        #[clap(short = 'L', long = "local", value_name = "SPEC")]
+        local: Vec<ForwardSpec>,
+
+        /// Listens on the remote machine, forwarding each connection to a host and port
+        /// reachable by the local machine, in the form `[bind_address:]port:host:hostport`
+        #[clap(short = 'R', long = "remote", value_name = "SPEC")]
+        remote: Vec<ForwardSpec>,
+    },
+
     /// Launches the server-portion of the binary on a remote machine
     Launch {
         /// Location to store cached data
@@ -380,6 +432,29 @@ pub enum ClientSubcommand {
         destination: Box<Destination>,
     },
 
+    /// Runs a local proxy whose connections are dialed from the remote machine
+    Proxy {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// Address to listen on for SOCKS5 clients, e.g. `127.0.0.1:1080`
+        #[clap(long, value_name = "ADDR")]
+        socks5: SocketAddr,
+    },
+
     /// Specialized treatment of running a remote shell process
     Shell {
         /// Location to store cached data
@@ -429,6 +504,11 @@ pub enum ClientSubcommand {
         #[clap(flatten)]
         network: NetworkSettings,
 
+        /// If json, will wait for the process to complete and print its output alongside how it
+        /// exited (code, signal, and resource usage) as a single JSON object
+        #[clap(short, long, default_value_t, value_enum, conflicts_with_all = ["SCHEME", "pty"])]
+        format: Format,
+
         /// If specified, will assume the remote process is a LSP server
         /// and will translate paths that are local into `distant` and vice versa.
         ///
@@ -440,6 +520,12 @@ pub enum ClientSubcommand {
         #[clap(long)]
         pty: bool,
 
+        /// If specified, will run the command through the shell of the server (as reported by
+        /// `system-info`) as a login shell so that its profile (e.g. `PATH` from `.profile`) is
+        /// loaded
+        #[clap(long, conflicts_with_all = ["SCHEME", "pty"])]
+        login: bool,
+
         /// Alternative current directory for the remote process
         #[clap(long)]
         current_dir: Option<PathBuf>,
@@ -499,8 +585,10 @@ impl ClientSubcommand {
         match self {
             Self::Connect { cache, .. } => cache.as_path(),
             Self::FileSystem(fs) => fs.cache_path(),
+            Self::Forward { cache, .. } => cache.as_path(),
             Self::Launch { cache, .. } => cache.as_path(),
             Self::Api { cache, .. } => cache.as_path(),
+            Self::Proxy { cache, .. } => cache.as_path(),
             Self::Shell { cache, .. } => cache.as_path(),
             Self::Spawn { cache, .. } => cache.as_path(),
             Self::SystemInfo { cache, .. } => cache.as_path(),
@@ -512,8 +600,10 @@ impl ClientSubcommand {
         match self {
             Self::Connect { network, .. } => network,
             Self::FileSystem(fs) => fs.network_settings(),
+            Self::Forward { network, .. } => network,
             Self::Launch { network, .. } => network,
             Self::Api { network, .. } => network,
+            Self::Proxy { network, .. } => network,
             Self::Shell { network, .. } => network,
             Self::Spawn { network, .. } => network,
             Self::SystemInfo { network, .. } => network,
@@ -550,6 +640,38 @@ pub enum ClientFileSystemSubcommand {
         dst: PathBuf,
     },
 
+    /// Downloads a file or directory from the remote machine, preserving permissions and
+    /// modification times
+    Download {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// If true, will download directories along with everything within them
+        #[clap(short = 'r', long)]
+        recursive: bool,
+
+        /// The path to the file or directory on the remote machine
+        src: PathBuf,
+
+        /// The path on the local machine where the file or directory will be placed, or an
+        /// existing directory to place it within
+        #[clap(value_hint = ValueHint::AnyPath)]
+        dst: PathBuf,
+    },
+
     /// Checks whether the specified path exists on the remote machine
     Exists {
         /// Location to store cached data
@@ -572,6 +694,57 @@ pub enum ClientFileSystemSubcommand {
         path: PathBuf,
     },
 
+    /// Computes the digest of a file, or of every file within a directory, on the remote machine
+    Hash {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// Algorithm used to compute the digest
+        #[
+            clap(
+                long,
+                default_value_t = HashAlgorithm::Sha256,
+                value_parser = clap::builder::PossibleValuesParser::new(HashAlgorithm::variants())
+                    .map(|s| s.parse::<HashAlgorithm>().unwrap()),
+            )
+        ]
+        algorithm: HashAlgorithm,
+
+        /// If true, will compute the digest of every file within the directory and its
+        /// subdirectories
+        #[clap(short = 'r', long)]
+        recursive: bool,
+
+        /// Offset (in bytes) within each file where hashing will begin
+        #[clap(long, default_value_t = 0)]
+        offset: u64,
+
+        /// Maximum number of bytes to hash within each file, hashing until the end if not
+        /// provided
+        #[clap(long)]
+        len: Option<u64>,
+
+        /// The path to the file (or directory if recursive) on the remote machine
+        path: PathBuf,
+    },
+
+    /// Subcommands for creating and reading links on the remote machine
+    #[clap(subcommand)]
+    Link(ClientFileSystemLinkSubcommand),
+
     /// Creates a directory on the remote machine
     MakeDir {
         /// Location to store cached data
@@ -739,6 +912,45 @@ pub enum ClientFileSystemSubcommand {
         dst: PathBuf,
     },
 
+    /// Replaces matches within the contents of files on the remote machine
+    Replace {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// If specified, will print a diff of the changes for each file instead of applying them
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Condition to meet to be considered a match
+        #[clap(name = "pattern")]
+        condition: CliSearchQueryCondition,
+
+        /// Text to replace each match with, which can reference capture groups of the pattern
+        /// via `$1`, `${name}`, and so on
+        replacement: String,
+
+        /// Options to apply to the query
+        #[clap(flatten)]
+        options: CliSearchQueryOptions,
+
+        /// Paths in which to perform the replacement
+        #[clap(default_value = ".")]
+        paths: Vec<PathBuf>,
+    },
+
     /// Search files & directories on the remote machine
     Search {
         /// Location to store cached data
@@ -808,6 +1020,83 @@ pub enum ClientFileSystemSubcommand {
         path: PathBuf,
     },
 
+    /// Synchronizes a local directory to a directory on the remote machine, transferring only the
+    /// files and blocks that have changed
+    Sync {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// If true, will remove files and directories on the remote machine that do not exist
+        /// locally
+        #[clap(long)]
+        delete: bool,
+
+        /// If true, will report the changes that would be made without making them
+        #[clap(long)]
+        dry_run: bool,
+
+        /// Glob that files must match to be synchronized, which can be provided multiple times;
+        /// if not provided, all files are synchronized
+        #[clap(long)]
+        include: Vec<String>,
+
+        /// Glob of files and directories to skip, which can be provided multiple times
+        #[clap(long)]
+        exclude: Vec<String>,
+
+        /// The path to the directory on the local machine
+        #[clap(value_hint = ValueHint::DirPath)]
+        src: PathBuf,
+
+        /// The path to the directory on the remote machine
+        dst: PathBuf,
+    },
+
+    /// Uploads a file or directory to the remote machine, preserving permissions and
+    /// modification times
+    Upload {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// If true, will upload directories along with everything within them
+        #[clap(short = 'r', long)]
+        recursive: bool,
+
+        /// The path to the file or directory on the local machine
+        #[clap(value_hint = ValueHint::AnyPath)]
+        src: PathBuf,
+
+        /// The path on the remote machine where the file or directory will be placed, or an
+        /// existing directory to place it within
+        dst: PathBuf,
+    },
+
     /// Watch a path for changes on the remote machine
     Watch {
         /// Location to store cached data
@@ -889,14 +1178,20 @@ impl ClientFileSystemSubcommand {
     pub fn cache_path(&self) -> &Path {
         match self {
             Self::Copy { cache, .. } => cache.as_path(),
+            Self::Download { cache, .. } => cache.as_path(),
             Self::Exists { cache, .. } => cache.as_path(),
+            Self::Hash { cache, .. } => cache.as_path(),
+            Self::Link(link) => link.cache_path(),
             Self::MakeDir { cache, .. } => cache.as_path(),
             Self::Metadata { cache, .. } => cache.as_path(),
             Self::Read { cache, .. } => cache.as_path(),
             Self::Remove { cache, .. } => cache.as_path(),
             Self::Rename { cache, .. } => cache.as_path(),
+            Self::Replace { cache, .. } => cache.as_path(),
             Self::Search { cache, .. } => cache.as_path(),
             Self::SetPermissions { cache, .. } => cache.as_path(),
+            Self::Sync { cache, .. } => cache.as_path(),
+            Self::Upload { cache, .. } => cache.as_path(),
             Self::Watch { cache, .. } => cache.as_path(),
             Self::Write { cache, .. } => cache.as_path(),
         }
@@ -905,20 +1200,97 @@ impl ClientFileSystemSubcommand {
     pub fn network_settings(&self) -> &NetworkSettings {
         match self {
             Self::Copy { network, .. } => network,
+            Self::Download { network, .. } => network,
             Self::Exists { network, .. } => network,
+            Self::Hash { network, .. } => network,
+            Self::Link(link) => link.network_settings(),
             Self::MakeDir { network, .. } => network,
             Self::Metadata { network, .. } => network,
             Self::Read { network, .. } => network,
             Self::Remove { network, .. } => network,
             Self::Rename { network, .. } => network,
+            Self::Replace { network, .. } => network,
             Self::Search { network, .. } => network,
             Self::SetPermissions { network, .. } => network,
+            Self::Sync { network, .. } => network,
+            Self::Upload { network, .. } => network,
             Self::Watch { network, .. } => network,
             Self::Write { network, .. } => network,
         }
     }
 }
 
+/// Subcommands for `distant fs link`.
+#[derive(Debug, PartialEq, Eq, Subcommand, IsVariant)]
+pub enum ClientFileSystemLinkSubcommand {
+    /// Creates a link on the remote machine, which is a symbolic link unless specified otherwise
+    Create {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// Whether or not to create a hard link instead of a symbolic link
+        #[clap(long)]
+        hard: bool,
+
+        /// The path on the remote machine that the link will point to
+        src: PathBuf,
+
+        /// Location on the remote machine of the new link
+        dst: PathBuf,
+    },
+
+    /// Reads the target of a symbolic link on the remote machine without canonicalizing it
+    Read {
+        /// Location to store cached data
+        #[clap(
+            long,
+            value_hint = ValueHint::FilePath,
+            value_parser,
+            default_value = CACHE_FILE_PATH_STR.as_str()
+        )]
+        cache: PathBuf,
+
+        /// Specify a connection being managed
+        #[clap(long)]
+        connection: Option<ConnectionId>,
+
+        #[clap(flatten)]
+        network: NetworkSettings,
+
+        /// The path to the symbolic link on the remote machine
+        path: PathBuf,
+    },
+}
+
+impl ClientFileSystemLinkSubcommand {
+    pub fn cache_path(&self) -> &Path {
+        match self {
+            Self::Create { cache, .. } => cache.as_path(),
+            Self::Read { cache, .. } => cache.as_path(),
+        }
+    }
+
+    pub fn network_settings(&self) -> &NetworkSettings {
+        match self {
+            Self::Create { network, .. } => network,
+            Self::Read { network, .. } => network,
+        }
+    }
+}
+
 /// Subcommands for `distant generate`.
 #[derive(Debug, PartialEq, Eq, Subcommand, IsVariant)]
 pub enum GenerateSubcommand {
@@ -1119,6 +1491,11 @@ pub enum ServerSubcommand {
         #[clap(short = '6', long)]
         use_ipv6: bool,
 
+        /// If specified, will accept connections over WebSocket instead of raw TCP, which allows
+        /// clients to reach the server through HTTP proxies by connecting to `ws://host:port`
+        #[clap(long)]
+        ws: bool,
+
         /// Logic to apply to server when determining when to shutdown automatically
         ///
         /// 1. "never" means the server will never automatically shut down
@@ -1133,6 +1510,10 @@ pub enum ServerSubcommand {
         #[clap(long)]
         current_dir: Option<PathBuf>,
 
+        /// Maximum number of processes that a single connection can hold at once
+        #[clap(long)]
+        max_processes: Option<usize>,
+
         /// If specified, will fork the process to run as a standalone daemon
         #[clap(long)]
         daemon: bool,
@@ -1146,6 +1527,13 @@ pub enum ServerSubcommand {
         #[clap(long)]
         key_from_stdin: bool,
 
+        /// Path to the PEM file holding the identity the server uses to prove itself to clients,
+        /// which is generated if it does not exist
+        ///
+        /// Default is a file within the user's distant config directory
+        #[clap(long, value_name = "PATH")]
+        identity_file: Option<PathBuf>,
+
         /// If specified, will send output to the specified named pipe (internal usage)
         #[clap(long, help = None, long_help = None)]
         output_to_local_pipe: Option<std::ffi::OsString>,
@@ -1214,6 +1602,12 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn verify_cli() {
+        use clap::CommandFactory;
+        Options::command().debug_assert();
+    }
+
     #[test]
     fn distant_api_should_support_merging_with_config() {
         let mut options = Options {
@@ -1228,6 +1622,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 timeout: None,
             }),
@@ -1242,6 +1639,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 api: ClientApiConfig {
                     timeout: Some(Seconds::from(5u32)),
@@ -1265,6 +1665,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     timeout: Some(Seconds::from(5u32)),
                 }),
@@ -1286,6 +1689,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 timeout: Some(Seconds::from(99u32)),
             }),
@@ -1300,6 +1706,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 api: ClientApiConfig {
                     timeout: Some(Seconds::from(5u32)),
@@ -1323,6 +1732,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     timeout: Some(Seconds::from(99u32)),
                 }),
@@ -1344,6 +1756,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 format: Format::Json,
             }),
@@ -1358,6 +1773,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 connect: ClientConnectConfig {
                     options: map!("hello" -> "world"),
@@ -1381,6 +1799,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     format: Format::Json,
                 }),
@@ -1402,6 +1823,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 format: Format::Json,
             }),
@@ -1416,6 +1840,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 connect: ClientConnectConfig {
                     options: map!("hello" -> "world", "config" -> "value"),
@@ -1439,6 +1866,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     format: Format::Json,
                 }),
@@ -1460,6 +1890,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 format: Format::Json,
                 destination: Box::new("test://destination".parse().unwrap()),
@@ -1475,6 +1908,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: Some(Encryption::Aes256Gcm),
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 connect: ClientConnectConfig {
                     options: map!("hello" -> "world"),
@@ -1498,6 +1934,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: Some(Encryption::Aes256Gcm),
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     format: Format::Json,
                     destination: Box::new("test://destination".parse().unwrap()),
@@ -1520,6 +1959,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: Some(Encryption::XChaCha20Poly1305),
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 format: Format::Json,
                 destination: Box::new("test://destination".parse().unwrap()),
@@ -1535,6 +1977,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: Some(Encryption::Aes256Gcm),
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 connect: ClientConnectConfig {
                     options: map!("hello" -> "world", "config" -> "value"),
@@ -1558,6 +2003,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: Some(Encryption::XChaCha20Poly1305),
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     format: Format::Json,
                     destination: Box::new("test://destination".parse().unwrap()),
@@ -1567,22 +2015,157 @@ mod tests {
     }
 
     #[test]
-    fn distant_launch_should_support_merging_with_config() {
+    fn distant_forward_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
                 log_file: None,
                 log_level: None,
             },
-            command: DistantSubcommand::Client(ClientSubcommand::Launch {
+            command: DistantSubcommand::Client(ClientSubcommand::Forward {
                 cache: PathBuf::new(),
-                distant_bin: None,
-                distant_bind_server: None,
+                connection: None,
+                network: NetworkSettings {
+                    unix_socket: None,
+                    windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                local: vec!["8080:localhost:80".parse().unwrap()],
+                remote: Vec::new(),
+            }),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::Forward {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("config-unix-socket")),
+                        windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    local: vec!["8080:localhost:80".parse().unwrap()],
+                    remote: Vec::new(),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_forward_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::Forward {
+                cache: PathBuf::new(),
+                connection: None,
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                    windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                local: vec!["8080:localhost:80".parse().unwrap()],
+                remote: Vec::new(),
+            }),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::Forward {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    local: vec!["8080:localhost:80".parse().unwrap()],
+                    remote: Vec::new(),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_launch_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::Launch {
+                cache: PathBuf::new(),
+                distant_bin: None,
+                distant_bind_server: None,
                 distant_args: None,
                 options: map!(),
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 format: Format::Json,
                 destination: Box::new("test://destination".parse().unwrap()),
@@ -1598,6 +2181,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 launch: ClientLaunchConfig {
                     distant: ClientLaunchDistantConfig {
@@ -1633,6 +2219,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     format: Format::Json,
                     destination: Box::new("test://destination".parse().unwrap()),
@@ -1658,6 +2247,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 format: Format::Json,
                 destination: Box::new("test://destination".parse().unwrap()),
@@ -1673,6 +2265,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 launch: ClientLaunchConfig {
                     distant: ClientLaunchDistantConfig {
@@ -1708,6 +2303,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     format: Format::Json,
                     destination: Box::new("test://destination".parse().unwrap()),
@@ -1716,6 +2314,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn distant_proxy_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::Proxy {
+                cache: PathBuf::new(),
+                connection: None,
+                network: NetworkSettings {
+                    unix_socket: None,
+                    windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                socks5: "127.0.0.1:1080".parse().unwrap(),
+            }),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::Proxy {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("config-unix-socket")),
+                        windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    socks5: "127.0.0.1:1080".parse().unwrap(),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_proxy_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::Proxy {
+                cache: PathBuf::new(),
+                connection: None,
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                    windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                socks5: "127.0.0.1:1080".parse().unwrap(),
+            }),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::Proxy {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    socks5: "127.0.0.1:1080".parse().unwrap(),
+                }),
+            }
+        );
+    }
+
     #[test]
     fn distant_shell_should_support_merging_with_config() {
         let mut options = Options {
@@ -1730,6 +2456,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 current_dir: None,
                 environment: Default::default(),
@@ -1746,6 +2475,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -1766,6 +2498,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     current_dir: None,
                     environment: map!(),
@@ -1789,6 +2524,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 current_dir: None,
                 environment: map!(),
@@ -1805,6 +2543,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -1825,6 +2566,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     current_dir: None,
                     environment: map!(),
@@ -1848,11 +2592,16 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 current_dir: None,
                 environment: map!(),
+                format: Format::Shell,
                 lsp: Some(None),
                 pty: true,
+                login: false,
                 cmd: vec![String::from("cmd")],
             }),
         };
@@ -1866,6 +2615,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -1886,11 +2638,16 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     current_dir: None,
                     environment: map!(),
+                    format: Format::Shell,
                     lsp: Some(None),
                     pty: true,
+                    login: false,
                     cmd: vec![String::from("cmd")],
                 }),
             }
@@ -1911,11 +2668,16 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 current_dir: None,
                 environment: map!(),
+                format: Format::Shell,
                 lsp: Some(None),
                 pty: true,
+                login: false,
                 cmd: vec![String::from("cmd")],
             }),
         };
@@ -1929,6 +2691,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -1949,11 +2714,16 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     current_dir: None,
                     environment: map!(),
+                    format: Format::Shell,
                     lsp: Some(None),
                     pty: true,
+                    login: false,
                     cmd: vec![String::from("cmd")],
                 }),
             }
@@ -1974,6 +2744,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -1987,6 +2760,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2007,6 +2783,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -2027,6 +2806,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -2040,6 +2822,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2060,6 +2845,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -2081,6 +2869,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     src: PathBuf::from("src"),
                     dst: PathBuf::from("dst"),
@@ -2097,6 +2888,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2118,6 +2912,9 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         src: PathBuf::from("src"),
                         dst: PathBuf::from("dst"),
@@ -2142,6 +2939,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     src: PathBuf::from("src"),
                     dst: PathBuf::from("dst"),
@@ -2158,6 +2958,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2179,6 +2982,9 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         src: PathBuf::from("src"),
                         dst: PathBuf::from("dst"),
@@ -2189,7 +2995,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_exists_should_support_merging_with_config() {
+    fn distant_fs_download_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2197,14 +3003,19 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Exists {
+                ClientFileSystemSubcommand::Download {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    path: PathBuf::from("path"),
+                    recursive: true,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 },
             )),
         };
@@ -2218,6 +3029,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2233,14 +3047,19 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Exists {
+                    ClientFileSystemSubcommand::Download {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        path: PathBuf::from("path"),
+                        recursive: true,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
                     }
                 )),
             }
@@ -2248,7 +3067,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_exists_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_download_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2256,14 +3075,19 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Exists {
+                ClientFileSystemSubcommand::Download {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    path: PathBuf::from("path"),
+                    recursive: true,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 },
             )),
         };
@@ -2277,6 +3101,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2292,14 +3119,19 @@ mod tests {
                     log_level: Some(LogLevel::Info),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Exists {
+                    ClientFileSystemSubcommand::Download {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        path: PathBuf::from("path"),
+                        recursive: true,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
                     }
                 )),
             }
@@ -2307,7 +3139,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_makedir_should_support_merging_with_config() {
+    fn distant_fs_exists_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2315,15 +3147,17 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::MakeDir {
+                ClientFileSystemSubcommand::Exists {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     path: PathBuf::from("path"),
-                    all: true,
                 },
             )),
         };
@@ -2337,6 +3171,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2352,15 +3189,17 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::MakeDir {
+                    ClientFileSystemSubcommand::Exists {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         path: PathBuf::from("path"),
-                        all: true,
                     }
                 )),
             }
@@ -2368,7 +3207,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_makedir_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_exists_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2376,15 +3215,17 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::MakeDir {
+                ClientFileSystemSubcommand::Exists {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     path: PathBuf::from("path"),
-                    all: true,
                 },
             )),
         };
@@ -2398,6 +3239,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2413,15 +3257,17 @@ mod tests {
                     log_level: Some(LogLevel::Info),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::MakeDir {
+                    ClientFileSystemSubcommand::Exists {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         path: PathBuf::from("path"),
-                        all: true,
                     }
                 )),
             }
@@ -2429,7 +3275,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_metadata_should_support_merging_with_config() {
+    fn distant_fs_hash_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2437,15 +3283,20 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Metadata {
+                ClientFileSystemSubcommand::Hash {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    canonicalize: true,
-                    resolve_file_type: true,
+                    algorithm: HashAlgorithm::Blake3,
+                    recursive: true,
+                    offset: 123,
+                    len: Some(456),
                     path: PathBuf::from("path"),
                 },
             )),
@@ -2460,6 +3311,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2475,15 +3329,20 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Metadata {
+                    ClientFileSystemSubcommand::Hash {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        canonicalize: true,
-                        resolve_file_type: true,
+                        algorithm: HashAlgorithm::Blake3,
+                        recursive: true,
+                        offset: 123,
+                        len: Some(456),
                         path: PathBuf::from("path"),
                     }
                 )),
@@ -2492,7 +3351,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_metadata_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_hash_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2500,15 +3359,20 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Metadata {
+                ClientFileSystemSubcommand::Hash {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    canonicalize: true,
-                    resolve_file_type: true,
+                    algorithm: HashAlgorithm::Blake3,
+                    recursive: true,
+                    offset: 123,
+                    len: Some(456),
                     path: PathBuf::from("path"),
                 },
             )),
@@ -2523,6 +3387,575 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Hash {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        algorithm: HashAlgorithm::Blake3,
+                        recursive: true,
+                        offset: 123,
+                        len: Some(456),
+                        path: PathBuf::from("path"),
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_link_create_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Create {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    hard: false,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
+                }),
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Create {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        hard: false,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
+                    })
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_link_create_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Create {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    hard: false,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
+                }),
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Create {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        hard: false,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
+                    })
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_link_read_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Read {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                }),
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Read {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                    })
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_link_read_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Read {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                }),
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Link(ClientFileSystemLinkSubcommand::Read {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                    })
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_makedir_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::MakeDir {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                    all: true,
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::MakeDir {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                        all: true,
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_makedir_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::MakeDir {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                    all: true,
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::MakeDir {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                        all: true,
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_metadata_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Metadata {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    canonicalize: true,
+                    resolve_file_type: true,
+                    path: PathBuf::from("path"),
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Metadata {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        canonicalize: true,
+                        resolve_file_type: true,
+                        path: PathBuf::from("path"),
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_metadata_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Metadata {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    canonicalize: true,
+                    resolve_file_type: true,
+                    path: PathBuf::from("path"),
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2544,10 +3977,305 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        canonicalize: true,
+                        resolve_file_type: true,
+                        path: PathBuf::from("path"),
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_read_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Read {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                    depth: 1,
+                    absolute: true,
+                    canonicalize: true,
+                    include_root: true,
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Read {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                        depth: 1,
+                        absolute: true,
+                        canonicalize: true,
+                        include_root: true,
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_read_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Read {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                    depth: 1,
+                    absolute: true,
+                    canonicalize: true,
+                    include_root: true,
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Read {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                        depth: 1,
+                        absolute: true,
+                        canonicalize: true,
+                        include_root: true,
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_remove_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Remove {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                    force: true,
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Remove {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        path: PathBuf::from("path"),
+                        force: true,
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_remove_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Remove {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    path: PathBuf::from("path"),
+                    force: true,
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Remove {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        canonicalize: true,
-                        resolve_file_type: true,
                         path: PathBuf::from("path"),
+                        force: true,
                     }
                 )),
             }
@@ -2555,7 +4283,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_read_should_support_merging_with_config() {
+    fn distant_fs_rename_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2563,18 +4291,18 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Read {
+                ClientFileSystemSubcommand::Rename {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    path: PathBuf::from("path"),
-                    depth: 1,
-                    absolute: true,
-                    canonicalize: true,
-                    include_root: true,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 },
             )),
         };
@@ -2588,6 +4316,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2603,18 +4334,18 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Read {
+                    ClientFileSystemSubcommand::Rename {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        path: PathBuf::from("path"),
-                        depth: 1,
-                        absolute: true,
-                        canonicalize: true,
-                        include_root: true,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
                     }
                 )),
             }
@@ -2622,7 +4353,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_read_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_rename_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2630,18 +4361,18 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Read {
+                ClientFileSystemSubcommand::Rename {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    path: PathBuf::from("path"),
-                    depth: 1,
-                    absolute: true,
-                    canonicalize: true,
-                    include_root: true,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 },
             )),
         };
@@ -2655,6 +4386,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2670,18 +4404,18 @@ mod tests {
                     log_level: Some(LogLevel::Info),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Read {
+                    ClientFileSystemSubcommand::Rename {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        path: PathBuf::from("path"),
-                        depth: 1,
-                        absolute: true,
-                        canonicalize: true,
-                        include_root: true,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
                     }
                 )),
             }
@@ -2689,7 +4423,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_remove_should_support_merging_with_config() {
+    fn distant_fs_replace_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2697,15 +4431,21 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Remove {
+                ClientFileSystemSubcommand::Replace {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    path: PathBuf::from("path"),
-                    force: true,
+                    dry_run: false,
+                    condition: CliSearchQueryCondition::regex(".*"),
+                    replacement: String::from("text"),
+                    options: Default::default(),
+                    paths: vec![PathBuf::from(".")],
                 },
             )),
         };
@@ -2719,6 +4459,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2734,15 +4477,21 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Remove {
+                    ClientFileSystemSubcommand::Replace {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        path: PathBuf::from("path"),
-                        force: true,
+                        dry_run: false,
+                        condition: CliSearchQueryCondition::regex(".*"),
+                        replacement: String::from("text"),
+                        options: Default::default(),
+                        paths: vec![PathBuf::from(".")],
                     }
                 )),
             }
@@ -2750,7 +4499,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_remove_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_replace_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2758,15 +4507,21 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Remove {
+                ClientFileSystemSubcommand::Replace {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    path: PathBuf::from("path"),
-                    force: true,
+                    dry_run: false,
+                    condition: CliSearchQueryCondition::regex(".*"),
+                    replacement: String::from("text"),
+                    options: Default::default(),
+                    paths: vec![PathBuf::from(".")],
                 },
             )),
         };
@@ -2780,6 +4535,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2795,15 +4553,21 @@ mod tests {
                     log_level: Some(LogLevel::Info),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Remove {
+                    ClientFileSystemSubcommand::Replace {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        path: PathBuf::from("path"),
-                        force: true,
+                        dry_run: false,
+                        condition: CliSearchQueryCondition::regex(".*"),
+                        replacement: String::from("text"),
+                        options: Default::default(),
+                        paths: vec![PathBuf::from(".")],
                     }
                 )),
             }
@@ -2811,7 +4575,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_rename_should_support_merging_with_config() {
+    fn distant_fs_search_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2819,13 +4583,168 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Rename {
+                ClientFileSystemSubcommand::Search {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: None,
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    target: CliSearchQueryTarget::Contents,
+                    condition: CliSearchQueryCondition::regex(".*"),
+                    options: Default::default(),
+                    paths: vec![PathBuf::from(".")],
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Search {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("config-unix-socket")),
+                            windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        target: CliSearchQueryTarget::Contents,
+                        condition: CliSearchQueryCondition::regex(".*"),
+                        options: Default::default(),
+                        paths: vec![PathBuf::from(".")],
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_search_should_prioritize_explicit_cli_options_when_merging() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: Some(PathBuf::from("cli-log-file")),
+                log_level: Some(LogLevel::Info),
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Search {
+                    cache: PathBuf::new(),
+                    connection: None,
+                    network: NetworkSettings {
+                        unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                        windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
+                    },
+                    target: CliSearchQueryTarget::Contents,
+                    condition: CliSearchQueryCondition::regex(".*"),
+                    options: Default::default(),
+                    paths: vec![PathBuf::from(".")],
+                },
+            )),
+        };
+
+        options.merge(Config {
+            client: ClientConfig {
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("config-log-file")),
+                    log_level: Some(LogLevel::Trace),
+                },
+                network: NetworkSettings {
+                    unix_socket: Some(PathBuf::from("config-unix-socket")),
+                    windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(
+            options,
+            Options {
+                config_path: None,
+                logging: LoggingSettings {
+                    log_file: Some(PathBuf::from("cli-log-file")),
+                    log_level: Some(LogLevel::Info),
+                },
+                command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                    ClientFileSystemSubcommand::Search {
+                        cache: PathBuf::new(),
+                        connection: None,
+                        network: NetworkSettings {
+                            unix_socket: Some(PathBuf::from("cli-unix-socket")),
+                            windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
+                        },
+                        target: CliSearchQueryTarget::Contents,
+                        condition: CliSearchQueryCondition::regex(".*"),
+                        options: Default::default(),
+                        paths: vec![PathBuf::from(".")],
+                    }
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn distant_fs_sync_should_support_merging_with_config() {
+        let mut options = Options {
+            config_path: None,
+            logging: LoggingSettings {
+                log_file: None,
+                log_level: None,
+            },
+            command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
+                ClientFileSystemSubcommand::Sync {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
+                    delete: true,
+                    dry_run: true,
+                    include: vec![String::from("*.rs")],
+                    exclude: vec![String::from("target")],
                     src: PathBuf::from("src"),
                     dst: PathBuf::from("dst"),
                 },
@@ -2841,6 +4760,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2856,13 +4778,20 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Rename {
+                    ClientFileSystemSubcommand::Sync {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
+                        delete: true,
+                        dry_run: true,
+                        include: vec![String::from("*.rs")],
+                        exclude: vec![String::from("target")],
                         src: PathBuf::from("src"),
                         dst: PathBuf::from("dst"),
                     }
@@ -2872,7 +4801,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_rename_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_sync_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2880,13 +4809,20 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Rename {
+                ClientFileSystemSubcommand::Sync {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
+                    delete: true,
+                    dry_run: true,
+                    include: vec![String::from("*.rs")],
+                    exclude: vec![String::from("target")],
                     src: PathBuf::from("src"),
                     dst: PathBuf::from("dst"),
                 },
@@ -2902,6 +4838,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2917,13 +4856,20 @@ mod tests {
                     log_level: Some(LogLevel::Info),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Rename {
+                    ClientFileSystemSubcommand::Sync {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
+                        delete: true,
+                        dry_run: true,
+                        include: vec![String::from("*.rs")],
+                        exclude: vec![String::from("target")],
                         src: PathBuf::from("src"),
                         dst: PathBuf::from("dst"),
                     }
@@ -2933,7 +4879,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_search_should_support_merging_with_config() {
+    fn distant_fs_upload_should_support_merging_with_config() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -2941,17 +4887,19 @@ mod tests {
                 log_level: None,
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Search {
+                ClientFileSystemSubcommand::Upload {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    target: CliSearchQueryTarget::Contents,
-                    condition: CliSearchQueryCondition::regex(".*"),
-                    options: Default::default(),
-                    paths: vec![PathBuf::from(".")],
+                    recursive: true,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 },
             )),
         };
@@ -2965,6 +4913,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -2980,17 +4931,19 @@ mod tests {
                     log_level: Some(LogLevel::Trace),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Search {
+                    ClientFileSystemSubcommand::Upload {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        target: CliSearchQueryTarget::Contents,
-                        condition: CliSearchQueryCondition::regex(".*"),
-                        options: Default::default(),
-                        paths: vec![PathBuf::from(".")],
+                        recursive: true,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
                     }
                 )),
             }
@@ -2998,7 +4951,7 @@ mod tests {
     }
 
     #[test]
-    fn distant_fs_search_should_prioritize_explicit_cli_options_when_merging() {
+    fn distant_fs_upload_should_prioritize_explicit_cli_options_when_merging() {
         let mut options = Options {
             config_path: None,
             logging: LoggingSettings {
@@ -3006,17 +4959,19 @@ mod tests {
                 log_level: Some(LogLevel::Info),
             },
             command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                ClientFileSystemSubcommand::Search {
+                ClientFileSystemSubcommand::Upload {
                     cache: PathBuf::new(),
                     connection: None,
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
-                    target: CliSearchQueryTarget::Contents,
-                    condition: CliSearchQueryCondition::regex(".*"),
-                    options: Default::default(),
-                    paths: vec![PathBuf::from(".")],
+                    recursive: true,
+                    src: PathBuf::from("src"),
+                    dst: PathBuf::from("dst"),
                 },
             )),
         };
@@ -3030,6 +4985,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3045,17 +5003,19 @@ mod tests {
                     log_level: Some(LogLevel::Info),
                 },
                 command: DistantSubcommand::Client(ClientSubcommand::FileSystem(
-                    ClientFileSystemSubcommand::Search {
+                    ClientFileSystemSubcommand::Upload {
                         cache: PathBuf::new(),
                         connection: None,
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
-                        target: CliSearchQueryTarget::Contents,
-                        condition: CliSearchQueryCondition::regex(".*"),
-                        options: Default::default(),
-                        paths: vec![PathBuf::from(".")],
+                        recursive: true,
+                        src: PathBuf::from("src"),
+                        dst: PathBuf::from("dst"),
                     }
                 )),
             }
@@ -3077,6 +5037,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     recursive: true,
                     only: ChangeKind::all(),
@@ -3095,6 +5058,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3116,6 +5082,9 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         recursive: true,
                         only: ChangeKind::all(),
@@ -3142,6 +5111,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     recursive: true,
                     only: ChangeKind::all(),
@@ -3160,6 +5132,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3181,6 +5156,9 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         recursive: true,
                         only: ChangeKind::all(),
@@ -3207,6 +5185,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: None,
                         windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     append: false,
                     path: PathBuf::from("path"),
@@ -3224,6 +5205,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3245,6 +5229,9 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("config-unix-socket")),
                             windows_pipe: Some(String::from("config-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         append: false,
                         path: PathBuf::from("path"),
@@ -3270,6 +5257,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                     append: false,
                     path: PathBuf::from("path"),
@@ -3287,6 +5277,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3308,6 +5301,9 @@ mod tests {
                         network: NetworkSettings {
                             unix_socket: Some(PathBuf::from("cli-unix-socket")),
                             windows_pipe: Some(String::from("cli-windows-pipe")),
+                            encryption: None,
+                            compression: None,
+                            adaptive_compression: false,
                         },
                         append: false,
                         path: PathBuf::from("path"),
@@ -3411,6 +5407,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3424,6 +5423,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3443,6 +5445,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3462,6 +5467,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3475,6 +5483,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3494,6 +5505,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3514,6 +5528,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3527,6 +5544,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3547,6 +5567,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3567,6 +5590,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3580,6 +5606,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3600,6 +5629,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3620,6 +5652,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3633,6 +5668,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3653,6 +5691,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3673,6 +5714,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3686,6 +5730,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3706,6 +5753,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3726,6 +5776,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3739,6 +5792,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3759,6 +5815,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3779,6 +5838,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3792,6 +5854,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3812,6 +5877,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3833,6 +5901,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3847,6 +5918,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             },
             ..Default::default()
@@ -3867,6 +5941,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3888,6 +5965,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3902,6 +5982,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             },
             ..Default::default()
@@ -3922,6 +6005,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3943,6 +6029,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: None,
                     windows_pipe: None,
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -3956,6 +6045,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -3977,6 +6069,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("config-unix-socket")),
                         windows_pipe: Some(String::from("config-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -3998,6 +6093,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("cli-unix-socket")),
                     windows_pipe: Some(String::from("cli-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
             }),
         };
@@ -4011,6 +6109,9 @@ mod tests {
                 network: NetworkSettings {
                     unix_socket: Some(PathBuf::from("config-unix-socket")),
                     windows_pipe: Some(String::from("config-windows-pipe")),
+                    encryption: None,
+                    compression: None,
+                    adaptive_compression: false,
                 },
                 ..Default::default()
             },
@@ -4032,6 +6133,9 @@ mod tests {
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("cli-unix-socket")),
                         windows_pipe: Some(String::from("cli-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 }),
             }
@@ -4144,8 +6248,10 @@ mod tests {
                 host: Value::Default(BindAddress::Any),
                 port: Value::Default(PortRange::single(123)),
                 use_ipv6: false,
+                ws: false,
                 shutdown: Value::Default(Shutdown::After(Duration::from_secs(123))),
                 current_dir: None,
+                max_processes: None,
                 watch: ServerListenWatchOptions {
                     watch_polling: false,
                     watch_poll_interval: None,
@@ -4155,6 +6261,7 @@ mod tests {
                 },
                 daemon: false,
                 key_from_stdin: false,
+                identity_file: None,
                 output_to_local_pipe: None,
             }),
         };
@@ -4169,8 +6276,10 @@ mod tests {
                     host: Some(BindAddress::Ssh),
                     port: Some(PortRange::single(456)),
                     use_ipv6: true,
+                    ws: true,
                     shutdown: Some(Shutdown::Lonely(Duration::from_secs(456))),
                     current_dir: Some(PathBuf::from("config-dir")),
+                    max_processes: Some(456),
                 },
                 watch: ServerWatchConfig {
                     native: false,
@@ -4195,8 +6304,10 @@ mod tests {
                     host: Value::Explicit(BindAddress::Ssh),
                     port: Value::Explicit(PortRange::single(456)),
                     use_ipv6: true,
+                    ws: true,
                     shutdown: Value::Explicit(Shutdown::Lonely(Duration::from_secs(456))),
                     current_dir: Some(PathBuf::from("config-dir")),
+                    max_processes: Some(456),
                     watch: ServerListenWatchOptions {
                         watch_polling: true,
                         watch_poll_interval: Some(Seconds::from(100u32)),
@@ -4206,6 +6317,7 @@ mod tests {
                     },
                     daemon: false,
                     key_from_stdin: false,
+                    identity_file: None,
                     output_to_local_pipe: None,
                 }),
             }
@@ -4224,8 +6336,10 @@ mod tests {
                 host: Value::Explicit(BindAddress::Any),
                 port: Value::Explicit(PortRange::single(123)),
                 use_ipv6: true,
+                ws: true,
                 shutdown: Value::Explicit(Shutdown::After(Duration::from_secs(123))),
                 current_dir: Some(PathBuf::from("cli-dir")),
+                max_processes: Some(123),
                 watch: ServerListenWatchOptions {
                     watch_polling: true,
                     watch_poll_interval: Some(Seconds::from(10u32)),
@@ -4235,6 +6349,7 @@ mod tests {
                 },
                 daemon: false,
                 key_from_stdin: false,
+                identity_file: None,
                 output_to_local_pipe: None,
             }),
         };
@@ -4249,8 +6364,10 @@ mod tests {
                     host: Some(BindAddress::Ssh),
                     port: Some(PortRange::single(456)),
                     use_ipv6: false,
+                    ws: false,
                     shutdown: Some(Shutdown::Lonely(Duration::from_secs(456))),
                     current_dir: Some(PathBuf::from("config-dir")),
+                    max_processes: Some(456),
                 },
                 watch: ServerWatchConfig {
                     native: true,
@@ -4275,8 +6392,10 @@ mod tests {
                     host: Value::Explicit(BindAddress::Any),
                     port: Value::Explicit(PortRange::single(123)),
                     use_ipv6: true,
+                    ws: true,
                     shutdown: Value::Explicit(Shutdown::After(Duration::from_secs(123))),
                     current_dir: Some(PathBuf::from("cli-dir")),
+                    max_processes: Some(123),
                     watch: ServerListenWatchOptions {
                         watch_polling: true,
                         watch_poll_interval: Some(Seconds::from(10u32)),
@@ -4286,6 +6405,7 @@ mod tests {
                     },
                     daemon: false,
                     key_from_stdin: false,
+                    identity_file: None,
                     output_to_local_pipe: None,
                 }),
             }
@@ -1,5 +1,6 @@
 mod address;
 mod cmd;
+mod forward;
 mod logging;
 mod network;
 mod search;
@@ -8,6 +9,7 @@ mod value;
 
 pub use address::*;
 pub use cmd::*;
+pub use forward::*;
 pub use logging::*;
 pub use network::*;
 pub use search::*;
@@ -0,0 +1,192 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Represents a port forwarded from one machine to a host and port reachable by the other, in
+/// the form `[bind_address:]port:host:hostport`.
+///
+/// IPv6 addresses can be provided by surrounding them with brackets, e.g. `[::1]:8080:[::1]:80`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ForwardSpec {
+    /// Address to listen on, defaulting to `localhost`
+    pub bind_address: String,
+
+    /// Port to listen on, where 0 means any available port
+    pub port: u16,
+
+    /// Host to connect to for each forwarded stream
+    pub host: String,
+
+    /// Port to connect to for each forwarded stream
+    pub host_port: u16,
+}
+
+impl fmt::Display for ForwardSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn fmt_host(host: &str) -> String {
+            if host.contains(':') {
+                format!("[{host}]")
+            } else {
+                host.to_string()
+            }
+        }
+
+        write!(
+            f,
+            "{}:{}:{}:{}",
+            fmt_host(&self.bind_address),
+            self.port,
+            fmt_host(&self.host),
+            self.host_port
+        )
+    }
+}
+
+impl FromStr for ForwardSpec {
+    type Err = ParseForwardSpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Split on colons, treating anything within brackets as a single part
+        let mut parts = Vec::new();
+        let mut part = String::new();
+        let mut bracketed = false;
+        for c in s.trim().chars() {
+            match c {
+                '[' if !bracketed && part.is_empty() => bracketed = true,
+                ']' if bracketed => bracketed = false,
+                ':' if !bracketed => parts.push(std::mem::take(&mut part)),
+                c => part.push(c),
+            }
+        }
+        if bracketed {
+            return Err(ParseForwardSpecError::InvalidFormat);
+        }
+        parts.push(part);
+
+        let parse_port = |s: &str| {
+            s.parse::<u16>()
+                .map_err(|_| ParseForwardSpecError::InvalidPort)
+        };
+
+        let (bind_address, port, host, host_port) = match parts.as_slice() {
+            [port, host, host_port] => ("localhost", port, host, host_port),
+            [bind_address, port, host, host_port] => (bind_address.as_str(), port, host, host_port),
+            _ => return Err(ParseForwardSpecError::InvalidFormat),
+        };
+
+        if bind_address.is_empty() || host.is_empty() {
+            return Err(ParseForwardSpecError::InvalidFormat);
+        }
+
+        Ok(Self {
+            bind_address: bind_address.to_string(),
+            port: parse_port(port)?,
+            host: host.to_string(),
+            host_port: parse_port(host_port)?,
+        })
+    }
+}
+
+/// Represents errors that can occur when parsing a forward spec.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParseForwardSpecError {
+    InvalidFormat,
+    InvalidPort,
+}
+
+impl std::error::Error for ParseForwardSpecError {}
+
+impl fmt::Display for ParseForwardSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "expected [bind_address:]port:host:hostport"),
+            Self::InvalidPort => write!(f, "port must be a number between 0 and 65535"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_should_default_bind_address_to_localhost() {
+        assert_eq!(
+            "8080:example.com:80".parse::<ForwardSpec>().unwrap(),
+            ForwardSpec {
+                bind_address: String::from("localhost"),
+                port: 8080,
+                host: String::from("example.com"),
+                host_port: 80,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_should_support_bind_address() {
+        assert_eq!(
+            "0.0.0.0:8080:127.0.0.1:80".parse::<ForwardSpec>().unwrap(),
+            ForwardSpec {
+                bind_address: String::from("0.0.0.0"),
+                port: 8080,
+                host: String::from("127.0.0.1"),
+                host_port: 80,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_should_support_bracketed_ipv6_addresses() {
+        assert_eq!(
+            "[::1]:8080:[2001:db8::1]:80"
+                .parse::<ForwardSpec>()
+                .unwrap(),
+            ForwardSpec {
+                bind_address: String::from("::1"),
+                port: 8080,
+                host: String::from("2001:db8::1"),
+                host_port: 80,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_should_fail_if_format_is_invalid() {
+        assert_eq!(
+            "8080:80".parse::<ForwardSpec>().unwrap_err(),
+            ParseForwardSpecError::InvalidFormat
+        );
+        assert_eq!(
+            "a:8080:b:80:c".parse::<ForwardSpec>().unwrap_err(),
+            ParseForwardSpecError::InvalidFormat
+        );
+        assert_eq!(
+            "[::1:8080:example.com:80"
+                .parse::<ForwardSpec>()
+                .unwrap_err(),
+            ParseForwardSpecError::InvalidFormat
+        );
+        assert_eq!(
+            "8080::80".parse::<ForwardSpec>().unwrap_err(),
+            ParseForwardSpecError::InvalidFormat
+        );
+    }
+
+    #[test]
+    fn parse_should_fail_if_port_is_invalid() {
+        assert_eq!(
+            "abc:example.com:80".parse::<ForwardSpec>().unwrap_err(),
+            ParseForwardSpecError::InvalidPort
+        );
+        assert_eq!(
+            "8080:example.com:65536".parse::<ForwardSpec>().unwrap_err(),
+            ParseForwardSpecError::InvalidPort
+        );
+    }
+
+    #[test]
+    fn to_string_should_round_trip_through_parse() {
+        let spec = "[::1]:8080:example.com:80".parse::<ForwardSpec>().unwrap();
+        assert_eq!(spec.to_string(), "[::1]:8080:example.com:80");
+        assert_eq!(spec.to_string().parse::<ForwardSpec>().unwrap(), spec);
+    }
+}
@@ -1,4 +1,6 @@
 use clap::Args;
+use derive_more::Display;
+use distant_core::net::common::{CompressionType, EncryptionType, Handshake, Map};
 use serde::{Deserialize, Serialize};
 
 use crate::constants;
@@ -36,6 +38,69 @@ impl Default for AccessControl {
     }
 }
 
+/// Encryption algorithm to prefer when connecting
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[clap(rename_all = "lower")]
+#[serde(rename_all = "lowercase")]
+pub enum Encryption {
+    /// XChaCha20-Poly1305, which performs well on any hardware
+    #[display(fmt = "xchacha20poly1305")]
+    XChaCha20Poly1305,
+
+    /// AES-256-GCM, which is faster on hardware with AES instructions (e.g. AES-NI)
+    #[display(fmt = "aes256gcm")]
+    Aes256Gcm,
+}
+
+impl Encryption {
+    /// Converts into the encryption type negotiated during a handshake
+    pub fn into_encryption_type(self) -> EncryptionType {
+        match self {
+            Self::XChaCha20Poly1305 => EncryptionType::XChaCha20Poly1305,
+            Self::Aes256Gcm => EncryptionType::Aes256Gcm,
+        }
+    }
+}
+
+/// Compression algorithm to prefer when connecting
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[clap(rename_all = "lower")]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// DEFLATE, which is widely supported
+    #[display(fmt = "deflate")]
+    Deflate,
+
+    /// gzip, which wraps DEFLATE with a header and checksum
+    #[display(fmt = "gzip")]
+    Gzip,
+
+    /// LZ4, which favors speed over the size of the compressed data
+    #[display(fmt = "lz4")]
+    Lz4,
+
+    /// zlib, which wraps DEFLATE with a smaller header and checksum
+    #[display(fmt = "zlib")]
+    Zlib,
+
+    /// Zstandard, which compresses well while remaining fast
+    #[display(fmt = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Converts into the compression type negotiated during a handshake
+    pub fn into_compression_type(self) -> CompressionType {
+        match self {
+            Self::Deflate => CompressionType::Deflate,
+            Self::Gzip => CompressionType::Gzip,
+            Self::Lz4 => CompressionType::Lz4,
+            Self::Zlib => CompressionType::Zlib,
+            Self::Zstd => CompressionType::Zstd,
+        }
+    }
+}
+
 /// Represents common networking configuration
 #[derive(Args, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct NetworkSettings {
@@ -46,6 +111,22 @@ pub struct NetworkSettings {
     /// Override the name of the local named Windows pipe used by the manager (windows-only)
     #[clap(long)]
     pub windows_pipe: Option<String>,
+
+    /// Preferred encryption algorithm for connections, falling back to another algorithm if the
+    /// other side does not support it
+    #[clap(long, value_enum)]
+    pub encryption: Option<Encryption>,
+
+    /// Preferred compression algorithm for connections, not compressing at all if the other side
+    /// does not support it
+    #[clap(long, value_enum)]
+    pub compression: Option<Compression>,
+
+    /// Only compress data likely to benefit from it, skipping data that is small or appears to
+    /// be compressed already (requires --compression)
+    #[clap(long, requires = "compression")]
+    #[serde(default)]
+    pub adaptive_compression: bool,
 }
 
 impl NetworkSettings {
@@ -54,6 +135,40 @@ impl NetworkSettings {
     pub fn merge(&mut self, other: Self) {
         self.unix_socket = self.unix_socket.take().or(other.unix_socket);
         self.windows_pipe = self.windows_pipe.take().or(other.windows_pipe);
+        self.encryption = self.encryption.take().or(other.encryption);
+        self.compression = self.compression.take().or(other.compression);
+        self.adaptive_compression = self.adaptive_compression || other.adaptive_compression;
+    }
+
+    /// Returns the client-side handshake to perform, preferring the configured encryption and
+    /// compression algorithms if there are any
+    pub fn to_client_handshake(&self) -> Handshake {
+        let mut handshake = Handshake::client();
+        if let Some(encryption) = self.encryption {
+            handshake =
+                handshake.with_preferred_encryption_type(Some(encryption.into_encryption_type()));
+        }
+
+        handshake.with_preferred_compression_type(
+            self.compression.map(Compression::into_compression_type),
+            self.adaptive_compression,
+        )
+    }
+
+    /// Returns the options that have the manager prefer the same encryption and compression
+    /// algorithms when connecting to a server
+    pub fn to_handshake_options(&self) -> Map {
+        let mut options = Map::new();
+        if let Some(x) = self.encryption {
+            options.insert("encryption".to_string(), x.to_string());
+        }
+        if let Some(x) = self.compression {
+            options.insert("compression".to_string(), x.to_string());
+        }
+        if self.adaptive_compression {
+            options.insert("adaptive_compression".to_string(), "true".to_string());
+        }
+        options
     }
 
     /// Returns option containing reference to unix path if configured
@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 use clap::{Args, ValueEnum};
 pub use distant_core::protocol::SearchQueryCondition as CliSearchQueryCondition;
@@ -55,6 +56,81 @@ pub struct CliSearchQueryOptions {
     /// include the remaining results even if less than pagination request
     #[clap(long)]
     pub pagination: Option<u64>,
+
+    /// Minimum size (in bytes) of a file to be included in the search, excluding anything that is
+    /// not a file
+    #[clap(long)]
+    pub min_size: Option<u64>,
+
+    /// Maximum size (in bytes) of a file to be included in the search, excluding anything that is
+    /// not a file
+    #[clap(long)]
+    pub max_size: Option<u64>,
+
+    /// Only include paths last modified at or after this time (in seconds since the unix epoch)
+    #[clap(long)]
+    pub modified_after: Option<u64>,
+
+    /// Only include paths last modified at or before this time (in seconds since the unix epoch)
+    #[clap(long)]
+    pub modified_before: Option<u64>,
+
+    /// Search hidden files and directories, which are skipped by default
+    #[clap(long)]
+    pub hidden: bool,
+
+    /// Do not respect ignore files such as `.gitignore` and `.ignore`
+    #[clap(long)]
+    pub no_ignore: bool,
+
+    /// Additional ignore file (using gitignore syntax) on the remote machine whose rules are
+    /// applied to the search; can be specified multiple times
+    #[clap(long = "ignore-file", value_name = "PATH")]
+    pub ignore_files: Vec<PathBuf>,
+
+    /// Number of lines to show before each match on a file's contents
+    #[clap(long)]
+    pub before_context: Option<u64>,
+
+    /// Number of lines to show after each match on a file's contents
+    #[clap(long)]
+    pub after_context: Option<u64>,
+
+    /// Allow matches on a file's contents to span multiple lines
+    #[clap(long)]
+    pub multiline: bool,
+
+    /// Additional regex that must also be found within a path or file's contents for it to be
+    /// considered a match; can be specified multiple times
+    ///
+    /// Checked against the same target as the search, so a path cannot be required alongside
+    /// contents
+    #[clap(long = "and", value_name = "PATTERN")]
+    pub and: Vec<CliSearchQueryCondition>,
+
+    /// Regex that must not be found anywhere within a path or file's contents for it to be
+    /// considered a match; can be specified multiple times
+    ///
+    /// Checked against the same target as the search, so a path cannot be excluded based on
+    /// contents
+    #[clap(long = "not", value_name = "PATTERN")]
+    pub not: Vec<CliSearchQueryCondition>,
+}
+
+impl CliSearchQueryOptions {
+    /// Combines `condition` with the `--and` and `--not` conditions, returning `condition`
+    /// untouched if neither was provided
+    pub fn compose_condition(&self, condition: CliSearchQueryCondition) -> CliSearchQueryCondition {
+        if self.and.is_empty() && self.not.is_empty() {
+            return condition;
+        }
+
+        CliSearchQueryCondition::and(
+            std::iter::once(condition)
+                .chain(self.and.iter().cloned())
+                .chain(self.not.iter().cloned().map(CliSearchQueryCondition::not)),
+        )
+    }
 }
 
 impl From<CliSearchQueryOptions> for SearchQueryOptions {
@@ -68,6 +144,16 @@ impl From<CliSearchQueryOptions> for SearchQueryOptions {
             limit: x.limit,
             max_depth: x.max_depth,
             pagination: x.pagination,
+            min_size: x.min_size,
+            max_size: x.max_size,
+            modified_after: x.modified_after,
+            modified_before: x.modified_before,
+            hidden: x.hidden,
+            no_ignore: x.no_ignore,
+            ignore_files: x.ignore_files,
+            before_context: x.before_context,
+            after_context: x.after_context,
+            multiline: x.multiline,
         }
     }
 }
@@ -132,7 +132,10 @@ mod tests {
                     },
                     network: NetworkSettings {
                         unix_socket: None,
-                        windows_pipe: None
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false
                     },
                 },
                 generate: GenerateConfig {
@@ -149,7 +152,10 @@ mod tests {
                     },
                     network: NetworkSettings {
                         unix_socket: None,
-                        windows_pipe: None
+                        windows_pipe: None,
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false
                     },
                 },
                 server: ServerConfig {
@@ -157,8 +163,10 @@ mod tests {
                         host: Some(BindAddress::Any),
                         port: Some(0.into()),
                         use_ipv6: false,
+                        ws: false,
                         shutdown: Some(Shutdown::Never),
                         current_dir: None,
+                        max_processes: None,
                     },
                     logging: LoggingSettings {
                         log_level: Some(LogLevel::Info),
@@ -220,8 +228,10 @@ log_level = "error"
 host = "127.0.0.1"
 port = "8080:8089"
 use_ipv6 = true
+ws = true
 shutdown = "after=123"
 current_dir = "server-current-dir"
+max_processes = 16
 
 [server.watch]
 native = false
@@ -258,7 +268,10 @@ debounce_tick_rate = 0.678
                     },
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("client-unix-socket")),
-                        windows_pipe: Some(String::from("client-windows-pipe"))
+                        windows_pipe: Some(String::from("client-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false
                     },
                 },
                 generate: GenerateConfig {
@@ -276,6 +289,9 @@ debounce_tick_rate = 0.678
                     network: NetworkSettings {
                         unix_socket: Some(PathBuf::from("manager-unix-socket")),
                         windows_pipe: Some(String::from("manager-windows-pipe")),
+                        encryption: None,
+                        compression: None,
+                        adaptive_compression: false,
                     },
                 },
                 server: ServerConfig {
@@ -286,8 +302,10 @@ debounce_tick_rate = 0.678
                             end: Some(8089)
                         }),
                         use_ipv6: true,
+                        ws: true,
                         shutdown: Some(Shutdown::After(Duration::from_secs(123))),
                         current_dir: Some(PathBuf::from("server-current-dir")),
+                        max_processes: Some(16),
                     },
                     logging: LoggingSettings {
                         log_level: Some(LogLevel::Error),
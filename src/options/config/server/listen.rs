@@ -11,8 +11,10 @@ pub struct ServerListenConfig {
     pub host: Option<BindAddress>,
     pub port: Option<PortRange>,
     pub use_ipv6: bool,
+    pub ws: bool,
     pub shutdown: Option<Shutdown>,
     pub current_dir: Option<PathBuf>,
+    pub max_processes: Option<usize>,
 }
 
 impl From<Map> for ServerListenConfig {
@@ -26,12 +28,19 @@ impl From<Map> for ServerListenConfig {
                 .remove("use_ipv6")
                 .and_then(|x| x.parse::<bool>().ok())
                 .unwrap_or_default(),
+            ws: map
+                .remove("ws")
+                .and_then(|x| x.parse::<bool>().ok())
+                .unwrap_or_default(),
             shutdown: map
                 .remove("shutdown")
                 .and_then(|x| x.parse::<Shutdown>().ok()),
             current_dir: map
                 .remove("current_dir")
                 .and_then(|x| x.parse::<PathBuf>().ok()),
+            max_processes: map
+                .remove("max_processes")
+                .and_then(|x| x.parse::<usize>().ok()),
         }
     }
 }
@@ -49,6 +58,7 @@ impl From<ServerListenConfig> for Map {
         }
 
         this.insert("use_ipv6".to_string(), config.use_ipv6.to_string());
+        this.insert("ws".to_string(), config.ws.to_string());
 
         if let Some(x) = config.shutdown {
             this.insert("shutdown".to_string(), x.to_string());
@@ -58,6 +68,10 @@ impl From<ServerListenConfig> for Map {
             this.insert("current_dir".to_string(), x.to_string_lossy().to_string());
         }
 
+        if let Some(x) = config.max_processes {
+            this.insert("max_processes".to_string(), x.to_string());
+        }
+
         this
     }
 }
@@ -0,0 +1,23 @@
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_environment(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": { "type": "environment" },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "environment", "JSON: {res}");
+    assert!(res["payload"]["vars"].is_object(), "JSON: {res}");
+}
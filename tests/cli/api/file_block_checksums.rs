@@ -0,0 +1,72 @@
+use assert_fs::prelude::*;
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+
+/// SHA-256 digest of "abc"
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+/// SHA-256 digest of "d"
+const D_SHA256: &str = "18ac3e7343f016890c510e93f935261169d9e3f565436429830faf0934f4f8e4";
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("file");
+    file.write_str("abcabcd").unwrap();
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "file_block_checksums",
+            "path": file.to_path_buf(),
+            "algorithm": "sha256",
+            "block_size": 3,
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(
+        res["payload"],
+        json!({
+            "type": "block_checksums",
+            "len": 7,
+            "checksums": [ABC_SHA256, ABC_SHA256, D_SHA256],
+        }),
+        "JSON: {res}"
+    );
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output_for_error(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("missing-file");
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "file_block_checksums",
+            "path": file.to_path_buf(),
+            "algorithm": "sha256",
+            "block_size": 3,
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "error", "JSON: {res}");
+    assert_eq!(res["payload"]["kind"], "not_found", "JSON: {res}");
+}
@@ -1,18 +1,31 @@
 mod copy;
 mod dir_create;
 mod dir_read;
+mod environment;
 mod exists;
 mod file_append;
 mod file_append_text;
+mod file_block_checksums;
+mod file_patch;
 mod file_read;
+mod file_read_range;
+mod file_read_stream;
 mod file_read_text;
 mod file_write;
 mod file_write_text;
+mod hash;
+mod link;
 mod metadata;
+mod proc_attach;
+mod proc_list;
+mod proc_signal;
 mod proc_spawn;
 mod remove;
 mod rename;
+mod replace;
 mod search;
 mod system_info;
+mod tcp;
+mod upload;
 mod version;
 mod watch;
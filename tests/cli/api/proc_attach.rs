@@ -0,0 +1,119 @@
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+use crate::cli::scripts::*;
+
+fn make_cmd(args: Vec<&str>) -> String {
+    format!(
+        r#"{} {} {}"#,
+        *SCRIPT_RUNNER,
+        *SCRIPT_RUNNER_ARG,
+        args.join(" ")
+    )
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_to_receive_output_of_attached_process(
+    mut api_process: CtxCommand<ApiProcess>,
+) {
+    validate_authentication(&mut api_process).await;
+
+    let cmd = make_cmd(vec![ECHO_STDIN_TO_STDOUT.to_str().unwrap()]);
+
+    // Spawn a process that mirrors stdin to stdout
+    let origin_id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": origin_id,
+            "payload": {
+                "type": "proc_spawn",
+                "cmd": cmd,
+                "pty": null,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], origin_id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "proc_spawned", "JSON: {res}");
+    let proc_id = res["payload"]["id"].clone();
+
+    // Attach to the process, which moves its responses over to the attach request
+    let attach_id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": attach_id,
+            "payload": {
+                "type": "proc_attach",
+                "id": proc_id,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], attach_id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "ok", "JSON: {res}");
+
+    // Write to stdin of the process and verify stdout arrives for the attach request
+    let id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": id,
+            "payload": {
+                "type": "proc_stdin",
+                "id": proc_id,
+                "data": b"some output\n",
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "ok", "JSON: {res}");
+
+    let res = api_process.read_json_from_stdout().await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], attach_id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "proc_stdout", "JSON: {res}");
+
+    // Clean up the process
+    let _ = api_process
+        .write_and_read_json(json!({
+            "id": rand::random::<u64>().to_string(),
+            "payload": {
+                "type": "proc_kill",
+                "id": proc_id,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output_for_error(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": id,
+            "payload": {
+                "type": "proc_attach",
+                "id": 0xDEADBEEFu32,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "error", "JSON: {res}");
+}
@@ -0,0 +1,81 @@
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+use crate::cli::scripts::*;
+
+fn make_cmd(args: Vec<&str>) -> String {
+    format!(
+        r#"{} {} {}"#,
+        *SCRIPT_RUNNER,
+        *SCRIPT_RUNNER_ARG,
+        args.join(" ")
+    )
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_to_list_spawned_processes(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let cmd = make_cmd(vec![ECHO_STDIN_TO_STDOUT.to_str().unwrap()]);
+
+    // Spawn a process that will stay alive until we kill it
+    let origin_id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": origin_id,
+            "payload": {
+                "type": "proc_spawn",
+                "cmd": cmd,
+                "pty": null,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], origin_id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "proc_spawned", "JSON: {res}");
+    let proc_id = res["payload"]["id"].clone();
+
+    // List the processes and verify that ours is included
+    let id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": id,
+            "payload": {
+                "type": "proc_list",
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "proc_entries", "JSON: {res}");
+
+    let entry = res["payload"]["entries"]
+        .as_array()
+        .expect("Invalid entries value")
+        .iter()
+        .find(|entry| entry["id"] == proc_id)
+        .cloned()
+        .unwrap_or_else(|| panic!("Missing process {proc_id}: {res}"));
+    assert!(entry["pty"].is_null(), "JSON: {res}");
+    assert!(entry["started"].as_u64().is_some(), "JSON: {res}");
+
+    // Clean up the process
+    let _ = api_process
+        .write_and_read_json(json!({
+            "id": rand::random::<u64>().to_string(),
+            "payload": {
+                "type": "proc_kill",
+                "id": proc_id,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+}
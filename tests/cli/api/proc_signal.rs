@@ -0,0 +1,90 @@
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+use crate::cli::scripts::*;
+
+fn make_cmd(args: Vec<&str>) -> String {
+    format!(
+        r#"{} {} {}"#,
+        *SCRIPT_RUNNER,
+        *SCRIPT_RUNNER_ARG,
+        args.join(" ")
+    )
+}
+
+#[rstest]
+#[test(tokio::test)]
+#[cfg_attr(windows, ignore)]
+async fn should_support_json_to_signal_spawned_process(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let cmd = make_cmd(vec![ECHO_STDIN_TO_STDOUT.to_str().unwrap()]);
+
+    // Spawn a process that will stay alive until we signal it
+    let origin_id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": origin_id,
+            "payload": {
+                "type": "proc_spawn",
+                "cmd": cmd,
+                "pty": null,
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], origin_id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "proc_spawned", "JSON: {res}");
+    let proc_id = res["payload"]["id"].clone();
+
+    // Send a termination signal and verify the process ends because of it
+    let id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": id,
+            "payload": {
+                "type": "proc_signal",
+                "id": proc_id,
+                "signal": "term",
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "ok", "JSON: {res}");
+
+    let res = api_process.read_json_from_stdout().await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], origin_id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "proc_done", "JSON: {res}");
+    assert_eq!(res["payload"]["success"], false, "JSON: {res}");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output_for_error(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let id = rand::random::<u64>().to_string();
+    let res = api_process
+        .write_and_read_json(json!({
+            "id": id,
+            "payload": {
+                "type": "proc_signal",
+                "id": 0,
+                "signal": "int",
+            },
+        }))
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "error", "JSON: {res}");
+}
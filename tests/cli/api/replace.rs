@@ -0,0 +1,89 @@
+use assert_fs::prelude::*;
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_replacing_matches_within_files(
+    mut api_process: CtxCommand<ApiProcess>,
+) {
+    validate_authentication(&mut api_process).await;
+
+    let root = assert_fs::TempDir::new().unwrap();
+    root.child("file1.txt").write_str("some file text").unwrap();
+    root.child("file2.txt")
+        .write_str("lines\nof\nwords\ninformation")
+        .unwrap();
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "replace",
+            "query": {
+                "paths": [root.path().to_string_lossy()],
+                "target": "contents",
+                "condition": {"type": "regex", "value": "t(ext)"},
+            },
+            "replacement": "n$1",
+        },
+    });
+
+    // Get back the totals once finished, followed by the report for the only file with matches
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(
+        res["payload"],
+        json!({
+            "type": "replace_done",
+            "files": 1,
+            "replacements": 1,
+        }),
+        "JSON: {res}"
+    );
+
+    let res = api_process.read_json_from_stdout().await.unwrap().unwrap();
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(
+        res["payload"],
+        json!({
+            "type": "replaced",
+            "path": root.child("file1.txt").to_string_lossy(),
+            "replacements": 1,
+        }),
+        "JSON: {res}"
+    );
+
+    root.child("file1.txt").assert("some file next");
+    root.child("file2.txt")
+        .assert("lines\nof\nwords\ninformation");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output_for_error(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let root = assert_fs::TempDir::new().unwrap();
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "replace",
+            "query": {
+                "paths": [root.path().to_string_lossy()],
+                "target": "path",
+                "condition": {"type": "regex", "value": "file"},
+            },
+            "replacement": "text",
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "error", "JSON: {res}");
+}
@@ -0,0 +1,79 @@
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+
+use crate::cli::fixtures::*;
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "tcp_connect",
+            "host": "127.0.0.1",
+            "port": port,
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "tcp_connected", "JSON: {res}");
+    let stream_id = res["payload"]["id"].clone();
+
+    // Data sent by the peer should be forwarded as part of the connect request
+    let (mut stream, _) = listener.accept().await.unwrap();
+    stream.write_all(b"some data").await.unwrap();
+
+    let res = api_process.read_json_from_stdout().await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(
+        res["payload"],
+        json!({
+            "type": "tcp_data",
+            "id": stream_id,
+            "data": b"some data".to_vec(),
+        }),
+        "JSON: {res}"
+    );
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output_for_error(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    // Bind and immediately drop a listener to get a port that nothing is listening on
+    let port = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "tcp_connect",
+            "host": "127.0.0.1",
+            "port": port,
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "error", "JSON: {res}");
+    assert_eq!(res["payload"]["kind"], "connection_refused", "JSON: {res}");
+}
@@ -0,0 +1,96 @@
+use assert_fs::prelude::*;
+use rstest::*;
+use serde_json::json;
+use test_log::test;
+
+use crate::cli::fixtures::*;
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_upload_in_chunks(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("test-file");
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "upload_open",
+            "path": file.to_path_buf(),
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "upload_opened", "JSON: {res}");
+    assert_eq!(res["payload"]["offset"], 0, "JSON: {res}");
+    let upload_id = res["payload"]["id"].clone();
+
+    for (offset, data) in [(0, "some "), (5, "contents")] {
+        let id = rand::random::<u64>().to_string();
+        let req = json!({
+            "id": id,
+            "payload": {
+                "type": "upload_write",
+                "id": upload_id,
+                "offset": offset,
+                "data": data.as_bytes().to_vec(),
+            },
+        });
+
+        let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+        assert_eq!(res["origin_id"], id, "JSON: {res}");
+        assert_eq!(
+            res["payload"],
+            json!({
+                "type": "upload_written",
+                "id": upload_id,
+                "offset": offset + data.len(),
+            }),
+            "JSON: {res}"
+        );
+    }
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "upload_commit",
+            "id": upload_id,
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"], json!({ "type": "ok" }), "JSON: {res}");
+
+    file.assert("some contents");
+}
+
+#[rstest]
+#[test(tokio::test)]
+async fn should_support_json_output_for_error(mut api_process: CtxCommand<ApiProcess>) {
+    validate_authentication(&mut api_process).await;
+
+    let id = rand::random::<u64>().to_string();
+    let req = json!({
+        "id": id,
+        "payload": {
+            "type": "upload_write",
+            "id": 0,
+            "offset": 0,
+            "data": [],
+        },
+    });
+
+    let res = api_process.write_and_read_json(req).await.unwrap().unwrap();
+
+    assert_eq!(res["origin_id"], id, "JSON: {res}");
+    assert_eq!(res["payload"]["type"], "error", "JSON: {res}");
+    assert_eq!(res["payload"]["kind"], "not_found", "JSON: {res}");
+}
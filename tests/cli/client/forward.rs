@@ -0,0 +1,96 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::Child;
+use std::thread;
+
+use predicates::prelude::*;
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+/// Spawns a thread that echoes back anything sent to connections on the returned port
+fn spawn_echo_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            thread::spawn(move || {
+                let mut reader = stream.try_clone().unwrap();
+                let _ = std::io::copy(&mut reader, &mut stream);
+            });
+        }
+    });
+    port
+}
+
+/// Reads the port being listened on from the first line printed by `distant forward`
+fn read_forwarded_port(child: &mut Child) -> u16 {
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).expect("Failed to read line");
+
+    // Forwarding {local|remote} 127.0.0.1:{port} to ...
+    line.split_whitespace()
+        .nth(2)
+        .and_then(|addr| addr.rsplit(':').next())
+        .and_then(|port| port.parse().ok())
+        .unwrap_or_else(|| panic!("Unexpected output: {line}"))
+}
+
+fn assert_echoes(port: u16) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(b"hello world").unwrap();
+
+    let mut buf = [0; 11];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello world");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_forward_local_port_to_remote_host(ctx: DistantManagerCtx) {
+    let echo_port = spawn_echo_server();
+
+    // distant forward -L 127.0.0.1:0:127.0.0.1:{port}
+    let mut child = ctx
+        .new_std_cmd(["forward"])
+        .arg("-L")
+        .arg(format!("127.0.0.1:0:127.0.0.1:{echo_port}"))
+        .spawn()
+        .expect("Failed to spawn process");
+
+    let port = read_forwarded_port(&mut child);
+    assert_echoes(port);
+
+    child.kill().expect("Failed to kill forward process");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_forward_remote_port_to_local_host(ctx: DistantManagerCtx) {
+    let echo_port = spawn_echo_server();
+
+    // distant forward -R 127.0.0.1:0:127.0.0.1:{port}
+    let mut child = ctx
+        .new_std_cmd(["forward"])
+        .arg("-R")
+        .arg(format!("127.0.0.1:0:127.0.0.1:{echo_port}"))
+        .spawn()
+        .expect("Failed to spawn process");
+
+    let port = read_forwarded_port(&mut child);
+    assert_echoes(port);
+
+    child.kill().expect("Failed to kill forward process");
+}
+
+#[rstest]
+#[test_log::test]
+fn yield_an_error_when_no_forwards_provided(ctx: DistantManagerCtx) {
+    // distant forward
+    ctx.new_assert_cmd(["forward"])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::is_empty().not());
+}
@@ -0,0 +1,80 @@
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+/// SHA-256 digest of "abc"
+const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+/// SHA-256 digest of no content
+const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+#[rstest]
+#[test_log::test]
+fn should_print_out_digest_of_file(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("file");
+    file.write_str("abc").unwrap();
+
+    // distant fs hash {path}
+    ctx.new_assert_cmd(["fs", "hash"])
+        .args([file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(format!("{ABC_SHA256}\n"))
+        .stderr("");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_support_hashing_range_of_file(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("file");
+    file.write_str("some abc contents").unwrap();
+
+    // distant fs hash --offset 5 --len 3 {path}
+    ctx.new_assert_cmd(["fs", "hash"])
+        .args(["--offset", "5", "--len", "3", file.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(format!("{ABC_SHA256}\n"))
+        .stderr("");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_print_out_digest_of_each_file_if_recursive(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("file1").write_str("abc").unwrap();
+    temp.child("dir").create_dir_all().unwrap();
+    temp.child("dir").child("file2").touch().unwrap();
+
+    let expected = format!(
+        "{EMPTY_SHA256}  {}\n{ABC_SHA256}  file1\n",
+        std::path::Path::new("dir").join("file2").to_string_lossy()
+    );
+
+    // distant fs hash --recursive {path}
+    ctx.new_assert_cmd(["fs", "hash"])
+        .args(["--recursive", temp.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+}
+
+#[rstest]
+#[test_log::test]
+fn yield_an_error_when_fails(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("missing-file");
+
+    // distant fs hash {path}
+    ctx.new_assert_cmd(["fs", "hash"])
+        .args([file.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::is_empty().not());
+}
@@ -0,0 +1,94 @@
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+const FILE_CONTENTS: &str = r#"
+some text
+on multiple lines
+that is a file's contents
+"#;
+
+#[rstest]
+#[test_log::test]
+fn should_support_creating_symlink(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let src = temp.child("file");
+    src.write_str(FILE_CONTENTS).unwrap();
+
+    let dst = temp.child("link");
+
+    // distant fs link create {src} {dst}
+    ctx.new_assert_cmd(["fs", "link", "create"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    dst.assert(predicate::path::is_symlink());
+    dst.assert(FILE_CONTENTS);
+}
+
+#[rstest]
+#[test_log::test]
+fn should_support_creating_hard_link(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let src = temp.child("file");
+    src.write_str(FILE_CONTENTS).unwrap();
+
+    let dst = temp.child("link");
+
+    // distant fs link create --hard {src} {dst}
+    ctx.new_assert_cmd(["fs", "link", "create"])
+        .args(["--hard", src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    dst.assert(predicate::path::is_symlink().not());
+    dst.assert(FILE_CONTENTS);
+}
+
+#[rstest]
+#[test_log::test]
+fn should_support_reading_symlink(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let file = temp.child("file");
+    file.write_str(FILE_CONTENTS).unwrap();
+
+    let link = temp.child("link");
+    link.symlink_to_file("file").unwrap();
+
+    // distant fs link read {path}
+    ctx.new_assert_cmd(["fs", "link", "read"])
+        .args([link.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("file\n")
+        .stderr("");
+}
+
+#[rstest]
+#[test_log::test]
+fn yield_an_error_when_fails(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let src = temp.child("missing-file");
+    let dst = temp.child("link");
+
+    // distant fs link create --hard {src} {dst}
+    ctx.new_assert_cmd(["fs", "link", "create"])
+        .args(["--hard", src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::is_empty().not());
+
+    dst.assert(predicate::path::missing());
+}
@@ -0,0 +1,79 @@
+use assert_fs::prelude::*;
+use indoc::{formatdoc, indoc};
+use predicates::prelude::*;
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+#[rstest]
+#[test_log::test]
+fn should_replace_matches_and_report_counts(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("file");
+    file.write_str("let a = 1;\nlet b = 2;\n").unwrap();
+
+    let expected = format!(
+        "{}: 2 replacement(s)\nReplaced 2 match(es) in 1 file(s)\n",
+        file.to_string_lossy()
+    );
+
+    // distant fs replace {pattern} {replacement} {path}
+    ctx.new_assert_cmd(["fs", "replace"])
+        .args([
+            r"let (\w+) = (\d+)",
+            "const $1: u32 = $2",
+            temp.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+
+    file.assert(indoc! {"
+        const a: u32 = 1;
+        const b: u32 = 2;
+    "});
+}
+
+#[rstest]
+#[test_log::test]
+fn should_print_diff_without_changing_files_if_dry_run(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("file");
+    file.write_str("one\ntwo\nthree\n").unwrap();
+
+    let expected = formatdoc! {"
+        --- a/{path}
+        +++ b/{path}
+        @@ -1,3 +1,3 @@
+         one
+        -two
+        +2
+         three
+        Would replace 1 match(es) in 1 file(s)
+    ", path = file.to_string_lossy()};
+
+    // distant fs replace --dry-run {pattern} {replacement} {path}
+    ctx.new_assert_cmd(["fs", "replace"])
+        .args(["--dry-run", "two", "2", temp.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+
+    file.assert("one\ntwo\nthree\n");
+}
+
+#[rstest]
+#[test_log::test]
+fn yield_an_error_when_fails(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    // distant fs replace {pattern} {replacement} {path}
+    ctx.new_assert_cmd(["fs", "replace"])
+        .args(["(unclosed", "text", temp.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::is_empty().not());
+}
@@ -54,3 +54,67 @@ fn should_search_filesystem_using_query(ctx: DistantManagerCtx) {
         .stdout(stdout_predicate_fn)
         .stderr("");
 }
+
+#[rstest]
+#[test_log::test]
+fn should_support_filtering_by_size_and_searching_hidden_files(ctx: DistantManagerCtx) {
+    let root = assert_fs::TempDir::new().unwrap();
+    root.child(".hidden.txt").write_str("text").unwrap();
+    root.child("file.txt").write_str("more text").unwrap();
+
+    // distant action search --hidden --max-size 4
+    ctx.new_assert_cmd(["fs", "search"])
+        .arg("--hidden")
+        .arg("--max-size")
+        .arg("4")
+        .arg("text")
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"^.*?[\\/]\.hidden\.txt\n1:text\n").unwrap())
+        .stderr("");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_print_context_lines_around_matches_if_requested(ctx: DistantManagerCtx) {
+    let root = assert_fs::TempDir::new().unwrap();
+    root.child("file.txt")
+        .write_str("one\ntwo\nthree\nfour")
+        .unwrap();
+
+    // distant action search --before-context 1 --after-context 1
+    ctx.new_assert_cmd(["fs", "search"])
+        .arg("--before-context")
+        .arg("1")
+        .arg("--after-context")
+        .arg("1")
+        .arg("two")
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"^.*?[\\/]file\.txt\n1-one\n2:two\n3-three\n$").unwrap())
+        .stderr("");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_support_combining_conditions_with_and_and_not(ctx: DistantManagerCtx) {
+    let root = assert_fs::TempDir::new().unwrap();
+    root.child("file1.txt").write_str("foo\nbaz\n").unwrap();
+    root.child("file2.txt").write_str("foo\nbar\n").unwrap();
+    root.child("file3.txt").write_str("baz\n").unwrap();
+
+    // distant action search --and baz --not bar foo
+    ctx.new_assert_cmd(["fs", "search"])
+        .arg("--and")
+        .arg("baz")
+        .arg("--not")
+        .arg("bar")
+        .arg("foo")
+        .arg(root.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::is_match(r"^.*?[\\/]file1\.txt\n1:foo\n2:baz\n$").unwrap())
+        .stderr("");
+}
@@ -0,0 +1,128 @@
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+#[rstest]
+#[test_log::test]
+fn should_copy_missing_files_and_directories(ctx: DistantManagerCtx) {
+    let src = assert_fs::TempDir::new().unwrap();
+    src.child("file").write_str("abc").unwrap();
+    src.child("dir").child("nested").write_str("hello").unwrap();
+
+    let dst = assert_fs::TempDir::new().unwrap();
+    let dst = dst.child("dst");
+
+    let expected = format!(
+        "mkdir dir\ncreate {} (5 bytes)\ncreate file (3 bytes)\n",
+        std::path::Path::new("dir").join("nested").to_string_lossy()
+    );
+
+    // distant fs sync {src} {dst}
+    ctx.new_assert_cmd(["fs", "sync"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(expected)
+        .stderr("");
+
+    dst.child("file").assert("abc");
+    dst.child("dir").child("nested").assert("hello");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_only_report_files_that_changed(ctx: DistantManagerCtx) {
+    let src = assert_fs::TempDir::new().unwrap();
+    src.child("same").write_str("abc").unwrap();
+    src.child("changed").write_str("new contents").unwrap();
+
+    let dst = assert_fs::TempDir::new().unwrap();
+    dst.child("same").write_str("abc").unwrap();
+    dst.child("changed").write_str("old contents").unwrap();
+
+    // distant fs sync {src} {dst}
+    ctx.new_assert_cmd(["fs", "sync"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("update changed (12 bytes, 1 changed blocks)\n")
+        .stderr("");
+
+    dst.child("same").assert("abc");
+    dst.child("changed").assert("new contents");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_remove_extra_files_if_delete_specified(ctx: DistantManagerCtx) {
+    let src = assert_fs::TempDir::new().unwrap();
+    src.child("file").write_str("abc").unwrap();
+
+    let dst = assert_fs::TempDir::new().unwrap();
+    dst.child("file").write_str("abc").unwrap();
+    dst.child("extra").write_str("extra").unwrap();
+    dst.child("keep.log").write_str("log").unwrap();
+
+    // distant fs sync --delete --exclude *.log {src} {dst}
+    ctx.new_assert_cmd(["fs", "sync"])
+        .args([
+            "--delete",
+            "--exclude",
+            "*.log",
+            src.to_str().unwrap(),
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("remove extra\n")
+        .stderr("");
+
+    dst.child("extra").assert(predicate::path::missing());
+    dst.child("keep.log").assert("log");
+}
+
+#[rstest]
+#[test_log::test]
+fn should_not_change_anything_if_dry_run_specified(ctx: DistantManagerCtx) {
+    let src = assert_fs::TempDir::new().unwrap();
+    src.child("file").write_str("abc").unwrap();
+
+    let dst = assert_fs::TempDir::new().unwrap();
+    dst.child("extra").write_str("extra").unwrap();
+
+    // distant fs sync --delete --dry-run {src} {dst}
+    ctx.new_assert_cmd(["fs", "sync"])
+        .args([
+            "--delete",
+            "--dry-run",
+            src.to_str().unwrap(),
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout("create file (3 bytes)\nremove extra\n")
+        .stderr("");
+
+    dst.child("file").assert(predicate::path::missing());
+    dst.child("extra").assert("extra");
+}
+
+#[rstest]
+#[test_log::test]
+fn yield_an_error_when_fails(ctx: DistantManagerCtx) {
+    let src = assert_fs::TempDir::new().unwrap();
+    let dst = assert_fs::TempDir::new().unwrap();
+
+    // distant fs sync {src} {dst}
+    ctx.new_assert_cmd(["fs", "sync"])
+        .args([
+            src.child("missing-dir").to_str().unwrap(),
+            dst.to_str().unwrap(),
+        ])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::is_empty().not());
+}
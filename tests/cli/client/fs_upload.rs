@@ -0,0 +1,129 @@
+use assert_fs::prelude::*;
+use predicates::prelude::*;
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+const FILE_CONTENTS: &str = r#"
+some text
+on multiple lines
+that is a file's contents
+"#;
+
+#[rstest]
+#[test_log::test]
+fn should_support_uploading_a_file(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src");
+    src.write_str(FILE_CONTENTS).unwrap();
+
+    let dst = temp.child("dst");
+
+    // distant fs upload {src} {dst}
+    ctx.new_assert_cmd(["fs", "upload"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    dst.assert(FILE_CONTENTS);
+}
+
+#[rstest]
+#[test_log::test]
+fn should_place_file_within_existing_directory(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("file");
+    src.write_str(FILE_CONTENTS).unwrap();
+
+    let dst = temp.child("dir");
+    dst.create_dir_all().unwrap();
+
+    // distant fs upload {src} {dst}
+    ctx.new_assert_cmd(["fs", "upload"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    dst.child("file").assert(FILE_CONTENTS);
+}
+
+#[rstest]
+#[test_log::test]
+fn should_support_uploading_a_directory_if_recursive(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src");
+    src.child("file").write_str(FILE_CONTENTS).unwrap();
+    src.child("dir").child("nested").write_str("hello").unwrap();
+
+    let dst = temp.child("dst");
+
+    // distant fs upload --recursive {src} {dst}
+    ctx.new_assert_cmd(["fs", "upload"])
+        .args(["--recursive", src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    dst.child("file").assert(FILE_CONTENTS);
+    dst.child("dir").child("nested").assert("hello");
+}
+
+#[cfg(unix)]
+#[rstest]
+#[test_log::test]
+fn should_preserve_permissions_and_modification_time(ctx: DistantManagerCtx) {
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src");
+    src.write_str(FILE_CONTENTS).unwrap();
+    std::fs::set_permissions(src.path(), std::fs::Permissions::from_mode(0o751)).unwrap();
+
+    let modified = UNIX_EPOCH + Duration::from_secs(1234567890);
+    std::fs::File::options()
+        .write(true)
+        .open(src.path())
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+
+    let dst = temp.child("dst");
+
+    // distant fs upload {src} {dst}
+    ctx.new_assert_cmd(["fs", "upload"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout("")
+        .stderr("");
+
+    let metadata = std::fs::metadata(dst.path()).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o751);
+    assert_eq!(metadata.modified().unwrap(), modified);
+}
+
+#[rstest]
+#[test_log::test]
+fn yield_an_error_when_uploading_a_directory_without_recursive(ctx: DistantManagerCtx) {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = temp.child("src");
+    src.create_dir_all().unwrap();
+
+    let dst = temp.child("dst");
+
+    // distant fs upload {src} {dst}
+    ctx.new_assert_cmd(["fs", "upload"])
+        .args([src.to_str().unwrap(), dst.to_str().unwrap()])
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicates::str::is_empty().not());
+
+    dst.assert(predicate::path::missing());
+}
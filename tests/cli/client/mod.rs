@@ -1,14 +1,22 @@
+mod forward;
 mod fs_copy;
+mod fs_download;
 mod fs_exists;
+mod fs_hash;
+mod fs_link;
 mod fs_make_dir;
 mod fs_metadata;
 mod fs_read_directory;
 mod fs_read_file;
 mod fs_remove;
 mod fs_rename;
+mod fs_replace;
 mod fs_search;
+mod fs_sync;
+mod fs_upload;
 mod fs_watch;
 mod fs_write;
+mod proxy;
 mod spawn;
 mod system_info;
 mod version;
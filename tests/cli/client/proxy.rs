@@ -0,0 +1,72 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+use rstest::*;
+
+use crate::cli::fixtures::*;
+
+/// Spawns a thread that echoes back anything sent to connections on the returned port
+fn spawn_echo_server() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            thread::spawn(move || {
+                let mut reader = stream.try_clone().unwrap();
+                let _ = std::io::copy(&mut reader, &mut stream);
+            });
+        }
+    });
+    port
+}
+
+#[rstest]
+#[test_log::test]
+fn should_dial_socks5_connections_from_remote_machine(ctx: DistantManagerCtx) {
+    let echo_port = spawn_echo_server();
+
+    // distant proxy --socks5 127.0.0.1:0
+    let mut child = ctx
+        .new_std_cmd(["proxy"])
+        .arg("--socks5")
+        .arg("127.0.0.1:0")
+        .spawn()
+        .expect("Failed to spawn process");
+
+    // Listening for SOCKS5 connections on {addr}
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    stdout.read_line(&mut line).expect("Failed to read line");
+    let addr: SocketAddr = line
+        .split_whitespace()
+        .last()
+        .and_then(|addr| addr.parse().ok())
+        .unwrap_or_else(|| panic!("Unexpected output: {line}"));
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+
+    // Greet without authentication
+    stream.write_all(&[0x05, 0x01, 0x00]).unwrap();
+    let mut buf = [0; 2];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [0x05, 0x00]);
+
+    // Connect to the echo server by domain name
+    let mut req = vec![0x05, 0x01, 0x00, 0x03, 9];
+    req.extend_from_slice(b"localhost");
+    req.extend_from_slice(&echo_port.to_be_bytes());
+    stream.write_all(&req).unwrap();
+
+    let mut buf = [0; 10];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(buf[..2], [0x05, 0x00]);
+
+    stream.write_all(b"hello world").unwrap();
+    let mut buf = [0; 11];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello world");
+
+    child.kill().expect("Failed to kill proxy process");
+    child.wait().expect("Failed to wait on proxy process");
+}
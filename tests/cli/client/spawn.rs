@@ -145,3 +145,34 @@ fn yield_an_error_when_fails(ctx: DistantManagerCtx) {
         .stdout("")
         .stderr(regex_pred(".+"));
 }
+
+#[rstest]
+#[test_log::test]
+#[cfg_attr(windows, ignore)]
+fn should_run_command_through_login_shell_if_requested(ctx: DistantManagerCtx) {
+    // distant spawn --login -- {cmd}
+    ctx.cmd("spawn")
+        .arg("--login")
+        .arg("--")
+        .arg("printf %s hello | tr a-z A-Z")
+        .assert()
+        .success()
+        .stdout(regex_pred("HELLO$"));
+}
+
+#[rstest]
+#[test_log::test]
+#[cfg_attr(windows, ignore)]
+fn should_print_output_and_exit_details_as_json_if_requested(ctx: DistantManagerCtx) {
+    // distant spawn --format json -- {cmd}
+    ctx.cmd("spawn")
+        .arg("--format")
+        .arg("json")
+        .arg("--")
+        .arg("sh -c 'printf hello; exit 3'")
+        .assert()
+        .code(3)
+        .stdout(regex_pred(
+            r#"^\{"code":3,"core_dumped":false,.*"signal":null,"stderr":"","stdout":"hello",.*"type":"proc_done","usage":\{.+\}\}\n$"#,
+        ));
+}
@@ -18,12 +18,18 @@ fn should_output_capabilities(ctx: DistantManagerCtx) {
         Client: distant {package_version} (Protocol {major}.{minor}.{patch})
         Server: {package_name} {package_version} (Protocol {major}.{minor}.{patch})
         Capabilities supported (+) or not (-):
-        +cancel_search    +copy             +dir_create       +dir_read
-        +exists           +file_append      +file_append_text +file_read
-        +file_read_text   +file_write       +file_write_text  +metadata
-        +proc_kill        +proc_resize_pty  +proc_spawn       +proc_stdin
-        +remove           +rename           +search           +set_permissions
-        +system_info      +unwatch          +version          +watch
+        +cancel_search        +copy                 +dir_create           +dir_read
+        +environment          +exists               +file_append          +file_append_text
+        +file_block_checksums +file_patch           +file_read            +file_read_range
+        +file_read_stream     +file_read_text       +file_write           +file_write_text
+        +hard_link            +hash                 +metadata             +proc_attach
+        +proc_kill            +proc_list            +proc_resize_pty      +proc_signal
+        +proc_spawn           +proc_stdin           +read_link            +remove
+        +rename               +replace              +search               +set_permissions
+        +symlink              +system_info          +tcp_close            +tcp_connect
+        +tcp_listen           +tcp_unlisten         +tcp_write            +unwatch
+        +upload_abort         +upload_commit        +upload_open          +upload_write
+        +version              +watch
     "};
 
     ctx.cmd("version")